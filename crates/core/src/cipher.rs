@@ -0,0 +1,52 @@
+use crate::{CoreError, EntropySource};
+use alloc::vec::Vec;
+use chacha20poly1305::{
+  aead::{Aead, NewAead},
+  XChaCha20Poly1305,
+};
+
+pub type CipherKey = [u8; 32];
+pub type CipherNonce = [u8; 24];
+
+/// Generate a new 32 bytes long cipher key
+/// for ChaCha20Poly1305, using the passed RNG
+pub fn new_key<R: EntropySource + ?Sized>(rng: &mut R) -> CipherKey {
+  let mut key = [0; 32];
+  rng.fill_bytes(&mut key);
+  key
+}
+
+/// Encrypt data with XChaCha20Poly1305, using the passed key
+/// and a 24 bytes long nonce generated with the passed RNG.
+pub fn encrypt<R: EntropySource + ?Sized>(
+  rng: &mut R,
+  key: &CipherKey,
+  data: &[u8],
+) -> Result<(Vec<u8>, CipherNonce), CoreError> {
+  let mut nonce = [0; 24];
+  rng.fill_bytes(&mut nonce);
+
+  Ok((encrypt_with_nonce(key, &nonce, data)?, nonce))
+}
+
+/// Encrypt data with XChaCha20Poly1305, using the passed key and nonce.
+///
+/// The nonce must never be reused with the same key.
+pub fn encrypt_with_nonce(
+  key: &CipherKey,
+  nonce: &CipherNonce,
+  data: &[u8],
+) -> Result<Vec<u8>, CoreError> {
+  XChaCha20Poly1305::new_from_slice(key)
+    .or(Err(CoreError::InvalidCipherKey))?
+    .encrypt(nonce.into(), data)
+    .or(Err(CoreError::EncryptionFailed))
+}
+
+/// Decrypt data with XChaCha20Poly1305, using the passed key and nonce.
+pub fn decrypt(key: &CipherKey, nonce: &CipherNonce, data: &[u8]) -> Result<Vec<u8>, CoreError> {
+  XChaCha20Poly1305::new_from_slice(key)
+    .or(Err(CoreError::InvalidCipherKey))?
+    .decrypt(nonce.into(), data)
+    .or(Err(CoreError::DecryptionFailed))
+}
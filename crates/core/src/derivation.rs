@@ -0,0 +1,19 @@
+use bip32::{DerivationPath, XPrv};
+
+use crate::CoreError;
+
+/// Derive the private key at a BIP-32 derivation path
+/// (e.g. `m/44'/60'/0'/0/0`) from a seed
+pub fn derive_private_key(seed: &[u8], path: &str) -> Result<[u8; 32], CoreError> {
+  let path: DerivationPath = path.parse().or(Err(CoreError::InvalidDerivationPath))?;
+  let xprv = XPrv::derive_from_path(seed, &path).or(Err(CoreError::InvalidDerivationPath))?;
+
+  Ok(xprv.private_key().to_bytes().into())
+}
+
+/// Get the BIP-32 fingerprint of the master extended public key of a seed
+pub fn fingerprint(seed: &[u8]) -> Result<[u8; 4], CoreError> {
+  let xprv = XPrv::new(seed).or(Err(CoreError::InvalidPrivateKey))?;
+
+  Ok(xprv.public_key().fingerprint())
+}
@@ -0,0 +1,10 @@
+use rand_core::{CryptoRng, RngCore};
+
+/// A source of cryptographically secure randomness.
+///
+/// Every cryptographically secure RNG is an `EntropySource`, so production
+/// code can pass the operating system RNG while tests and auditors can
+/// inject a seeded, deterministic one.
+pub trait EntropySource: RngCore + CryptoRng {}
+
+impl<T: RngCore + CryptoRng + ?Sized> EntropySource for T {}
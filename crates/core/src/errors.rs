@@ -0,0 +1,26 @@
+use core::fmt::{Display, Formatter, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoreError {
+  InvalidCipherKey,
+  EncryptionFailed,
+  DecryptionFailed,
+  InvalidPrivateKey,
+  InvalidDerivationPath,
+  InvalidPublicKey,
+  InvalidSignature,
+}
+
+impl Display for CoreError {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    match self {
+      Self::InvalidCipherKey => write!(f, "Invalid cipher key"),
+      Self::EncryptionFailed => write!(f, "Encryption failed"),
+      Self::DecryptionFailed => write!(f, "Decryption failed"),
+      Self::InvalidPrivateKey => write!(f, "Invalid private key"),
+      Self::InvalidDerivationPath => write!(f, "Invalid derivation path"),
+      Self::InvalidPublicKey => write!(f, "Invalid public key"),
+      Self::InvalidSignature => write!(f, "Invalid signature"),
+    }
+  }
+}
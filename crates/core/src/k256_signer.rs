@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use k256::{
+  ecdsa::{
+    hazmat::SignPrimitive, signature::hazmat::PrehashVerifier, RecoveryId, Signature, SigningKey,
+    VerifyingKey,
+  },
+  sha2::Sha256,
+  FieldBytes,
+};
+
+use crate::{public_key_to_address, CoreError, NonceStrategy, SigningBackend};
+
+/// A secp256k1 signer over 32 bytes message digests, backed by the
+/// pure-Rust `k256` crate instead of the secp256k1 C library.
+///
+/// Produced signatures always have a low `s` value, and are identical
+/// to the ones of `Signer` for the same nonce strategy.
+pub struct K256Signer {
+  signing_key: SigningKey,
+}
+
+impl SigningBackend for K256Signer {
+  fn from_private_key(private_key: &[u8; 32]) -> Result<Self, CoreError> {
+    let signing_key = SigningKey::from_slice(private_key).or(Err(CoreError::InvalidPrivateKey))?;
+
+    Ok(Self { signing_key })
+  }
+
+  fn public_key_bytes(&self) -> [u8; 33] {
+    let mut public_key = [0u8; 33];
+    public_key.copy_from_slice(
+      self
+        .signing_key
+        .verifying_key()
+        .to_encoded_point(true)
+        .as_bytes(),
+    );
+
+    public_key
+  }
+
+  fn address_bytes(&self) -> [u8; 20] {
+    let mut public_key = [0u8; 65];
+    public_key.copy_from_slice(
+      self
+        .signing_key
+        .verifying_key()
+        .to_encoded_point(false)
+        .as_bytes(),
+    );
+
+    public_key_to_address(&public_key)
+  }
+
+  fn sign_compact(&self, digest: &[u8; 32], nonce: &NonceStrategy) -> ([u8; 64], u8) {
+    // Like libsecp256k1, the hardened entropy is passed to RFC 6979
+    // as additional data, so both backends derive the same nonce
+    let additional_data: &[u8] = match nonce {
+      NonceStrategy::Deterministic => &[],
+      NonceStrategy::Hardened(entropy) => entropy,
+    };
+    // Unwraps are safe because a valid private key and an RFC 6979
+    // nonce only fail to sign with negligible probability
+    let (signature, recovery_id) = self
+      .signing_key
+      .as_nonzero_scalar()
+      .try_sign_prehashed_rfc6979::<Sha256>(FieldBytes::from_slice(digest), additional_data)
+      .unwrap();
+
+    (signature.to_bytes().into(), recovery_id.unwrap().to_byte())
+  }
+
+  fn verify(public_key: &[u8], digest: &[u8; 32], signature: &[u8; 64]) -> Result<(), CoreError> {
+    let verifying_key =
+      VerifyingKey::from_sec1_bytes(public_key).or(Err(CoreError::InvalidPublicKey))?;
+    let signature = Signature::from_slice(signature).or(Err(CoreError::InvalidSignature))?;
+    // Like libsecp256k1, high `s` values are rejected, which
+    // `k256` only does when signing
+    if signature.normalize_s().is_some() {
+      return Err(CoreError::InvalidSignature);
+    }
+
+    verifying_key
+      .verify_prehash(digest, &signature)
+      .or(Err(CoreError::InvalidSignature))
+  }
+
+  fn recover(
+    digest: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+  ) -> Result<[u8; 65], CoreError> {
+    let recovery_id = RecoveryId::from_byte(recovery_id).ok_or(CoreError::InvalidSignature)?;
+    let signature = Signature::from_slice(signature).or(Err(CoreError::InvalidSignature))?;
+    let verifying_key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+      .or(Err(CoreError::InvalidSignature))?;
+
+    encode_public_key(&verifying_key, false)
+  }
+
+  fn compress_public_key(public_key: &[u8]) -> Result<[u8; 33], CoreError> {
+    let verifying_key =
+      VerifyingKey::from_sec1_bytes(public_key).or(Err(CoreError::InvalidPublicKey))?;
+
+    encode_public_key(&verifying_key, true)
+  }
+
+  fn uncompress_public_key(public_key: &[u8]) -> Result<[u8; 65], CoreError> {
+    let verifying_key =
+      VerifyingKey::from_sec1_bytes(public_key).or(Err(CoreError::InvalidPublicKey))?;
+
+    encode_public_key(&verifying_key, false)
+  }
+
+  fn normalize_s(signature: &[u8; 64]) -> Result<[u8; 64], CoreError> {
+    let signature = Signature::from_slice(signature).or(Err(CoreError::InvalidSignature))?;
+
+    Ok(
+      signature
+        .normalize_s()
+        .unwrap_or(signature)
+        .to_bytes()
+        .into(),
+    )
+  }
+
+  fn signature_from_der(der: &[u8]) -> Result<[u8; 64], CoreError> {
+    Ok(
+      Signature::from_der(der)
+        .or(Err(CoreError::InvalidSignature))?
+        .to_bytes()
+        .into(),
+    )
+  }
+
+  fn signature_to_der(signature: &[u8; 64]) -> Result<Vec<u8>, CoreError> {
+    Ok(
+      Signature::from_slice(signature)
+        .or(Err(CoreError::InvalidSignature))?
+        .to_der()
+        .as_bytes()
+        .to_vec(),
+    )
+  }
+}
+
+/// Get the SEC1 encoding of a public key, compressed in
+/// 33 bytes or uncompressed in 65 bytes depending on `N`
+fn encode_public_key<const N: usize>(
+  verifying_key: &VerifyingKey,
+  compress: bool,
+) -> Result<[u8; N], CoreError> {
+  verifying_key
+    .to_encoded_point(compress)
+    .as_bytes()
+    .try_into()
+    .or(Err(CoreError::InvalidPublicKey))
+}
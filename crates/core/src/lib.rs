@@ -0,0 +1,48 @@
+//! # walleth-core
+//!
+//! The signing core of walleth: ECDSA signing, ChaCha20Poly1305 encryption
+//! and BIP-32 derivation, with no dependency on the standard library.
+//!
+//! It builds with `no_std + alloc`, so that it can run on embedded secure
+//! elements and air-gapped devices. Randomness is never sourced from the
+//! operating system: every function that needs it takes an injected RNG.
+//!
+//! Signing is backed by the secp256k1 C library with the default
+//! `secp256k1` feature, or by the pure-Rust `k256` crate with the `k256`
+//! feature, which takes precedence. `Backend` is the one selected.
+#![no_std]
+#![forbid(unsafe_code)]
+
+extern crate alloc;
+
+#[cfg(feature = "cipher")]
+pub mod cipher;
+pub mod derivation;
+pub mod entropy;
+pub mod errors;
+#[cfg(feature = "k256")]
+pub mod k256_signer;
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1_signer;
+pub mod signer;
+
+#[cfg(feature = "cipher")]
+pub use cipher::{CipherKey, CipherNonce};
+pub use derivation::derive_private_key;
+pub use entropy::EntropySource;
+pub use errors::CoreError;
+#[cfg(feature = "k256")]
+pub use k256_signer::K256Signer;
+#[cfg(feature = "secp256k1")]
+pub use secp256k1_signer::Signer;
+pub use signer::{keccak256, public_key_to_address, NonceStrategy, SigningBackend};
+
+/// The signing backend used by walleth: the pure-Rust `K256Signer` when
+/// the `k256` feature is enabled, the secp256k1 `Signer` otherwise
+#[cfg(feature = "k256")]
+pub type Backend = K256Signer;
+
+/// The signing backend used by walleth: the pure-Rust `K256Signer` when
+/// the `k256` feature is enabled, the secp256k1 `Signer` otherwise
+#[cfg(all(feature = "secp256k1", not(feature = "k256")))]
+pub type Backend = Signer;
@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use secp256k1::{
+  ecdsa::{self, RecoverableSignature, RecoveryId},
+  Message, PublicKey, Secp256k1, SecretKey,
+};
+
+use crate::{public_key_to_address, CoreError, NonceStrategy, SigningBackend};
+
+/// A secp256k1 signer over 32 bytes message digests,
+/// backed by the secp256k1 C library.
+///
+/// Produced signatures always have a low `s` value.
+pub struct Signer {
+  secret_key: SecretKey,
+}
+
+impl Signer {
+  /// Create a new signer from private key bytes
+  pub fn new(private_key: &[u8; 32]) -> Result<Self, CoreError> {
+    let secret_key = SecretKey::from_slice(private_key).or(Err(CoreError::InvalidPrivateKey))?;
+
+    Ok(Self { secret_key })
+  }
+
+  /// Get the public key of the signer
+  pub fn public_key(&self) -> PublicKey {
+    self.secret_key.public_key(&Secp256k1::signing_only())
+  }
+
+  /// Get the Ethereum address of the signer
+  pub fn address(&self) -> [u8; 20] {
+    public_key_to_address(&self.public_key().serialize_uncompressed())
+  }
+
+  /// Sign a message digest, producing a signature without recovery id
+  pub fn sign(&self, digest: &[u8; 32], nonce: &NonceStrategy) -> ecdsa::Signature {
+    let secp = Secp256k1::signing_only();
+    // Unwrap is safe because the digest is 32 bytes long
+    let message = Message::from_slice(digest).unwrap();
+
+    match nonce {
+      NonceStrategy::Deterministic => secp.sign_ecdsa(&message, &self.secret_key),
+      NonceStrategy::Hardened(entropy) => {
+        secp.sign_ecdsa_with_noncedata(&message, &self.secret_key, entropy)
+      }
+    }
+  }
+
+  /// Sign a message digest, producing a recoverable signature
+  pub fn sign_recoverable(&self, digest: &[u8; 32], nonce: &NonceStrategy) -> RecoverableSignature {
+    let secp = Secp256k1::signing_only();
+    // Unwrap is safe because the digest is 32 bytes long
+    let message = Message::from_slice(digest).unwrap();
+
+    match nonce {
+      NonceStrategy::Deterministic => secp.sign_ecdsa_recoverable(&message, &self.secret_key),
+      NonceStrategy::Hardened(entropy) => {
+        secp.sign_ecdsa_recoverable_with_noncedata(&message, &self.secret_key, entropy)
+      }
+    }
+  }
+}
+
+impl SigningBackend for Signer {
+  fn from_private_key(private_key: &[u8; 32]) -> Result<Self, CoreError> {
+    Self::new(private_key)
+  }
+
+  fn public_key_bytes(&self) -> [u8; 33] {
+    self.public_key().serialize()
+  }
+
+  fn address_bytes(&self) -> [u8; 20] {
+    self.address()
+  }
+
+  fn sign_compact(&self, digest: &[u8; 32], nonce: &NonceStrategy) -> ([u8; 64], u8) {
+    let (recovery_id, signature) = self.sign_recoverable(digest, nonce).serialize_compact();
+
+    // Recovery ids are always in 0..4
+    (signature, recovery_id.to_i32() as u8)
+  }
+
+  fn verify(public_key: &[u8], digest: &[u8; 32], signature: &[u8; 64]) -> Result<(), CoreError> {
+    let public_key = PublicKey::from_slice(public_key).or(Err(CoreError::InvalidPublicKey))?;
+    let signature =
+      ecdsa::Signature::from_compact(signature).or(Err(CoreError::InvalidSignature))?;
+    // Unwrap is safe because the digest is 32 bytes long
+    let message = Message::from_slice(digest).unwrap();
+
+    Secp256k1::verification_only()
+      .verify_ecdsa(&message, &signature, &public_key)
+      .or(Err(CoreError::InvalidSignature))
+  }
+
+  fn recover(
+    digest: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+  ) -> Result<[u8; 65], CoreError> {
+    let recovery_id =
+      RecoveryId::from_i32(recovery_id as i32).or(Err(CoreError::InvalidSignature))?;
+    let signature = RecoverableSignature::from_compact(signature, recovery_id)
+      .or(Err(CoreError::InvalidSignature))?;
+    // Unwrap is safe because the digest is 32 bytes long
+    let message = Message::from_slice(digest).unwrap();
+
+    Ok(
+      Secp256k1::verification_only()
+        .recover_ecdsa(&message, &signature)
+        .or(Err(CoreError::InvalidSignature))?
+        .serialize_uncompressed(),
+    )
+  }
+
+  fn compress_public_key(public_key: &[u8]) -> Result<[u8; 33], CoreError> {
+    Ok(
+      PublicKey::from_slice(public_key)
+        .or(Err(CoreError::InvalidPublicKey))?
+        .serialize(),
+    )
+  }
+
+  fn uncompress_public_key(public_key: &[u8]) -> Result<[u8; 65], CoreError> {
+    Ok(
+      PublicKey::from_slice(public_key)
+        .or(Err(CoreError::InvalidPublicKey))?
+        .serialize_uncompressed(),
+    )
+  }
+
+  fn normalize_s(signature: &[u8; 64]) -> Result<[u8; 64], CoreError> {
+    let mut signature =
+      ecdsa::Signature::from_compact(signature).or(Err(CoreError::InvalidSignature))?;
+    signature.normalize_s();
+
+    Ok(signature.serialize_compact())
+  }
+
+  fn signature_from_der(der: &[u8]) -> Result<[u8; 64], CoreError> {
+    Ok(
+      ecdsa::Signature::from_der(der)
+        .or(Err(CoreError::InvalidSignature))?
+        .serialize_compact(),
+    )
+  }
+
+  fn signature_to_der(signature: &[u8; 64]) -> Result<Vec<u8>, CoreError> {
+    Ok(
+      ecdsa::Signature::from_compact(signature)
+        .or(Err(CoreError::InvalidSignature))?
+        .serialize_der()
+        .to_vec(),
+    )
+  }
+}
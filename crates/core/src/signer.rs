@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+
+use sha3::{Digest, Keccak256};
+
+use crate::CoreError;
+
+/// Strategy used to generate the ECDSA nonce when signing
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NonceStrategy {
+  /// The nonce is derived from the private key and the message digest
+  /// as specified by RFC 6979 (HMAC-SHA256), so signing the same message
+  /// with the same key produces byte-identical signatures on every platform.
+  #[default]
+  Deterministic,
+  /// The RFC 6979 nonce derivation is fed with 32 bytes of additional
+  /// data (RFC 6979, section 3.6), hardening the signer against fault
+  /// attacks at the cost of reproducible signatures.
+  Hardened([u8; 32]),
+}
+
+/// The operations of a secp256k1 signer, over plain bytes so that
+/// signers backed by different libraries are interchangeable.
+///
+/// All backends produce the same public keys, addresses and
+/// signatures for the same private key, digest and nonce strategy.
+/// The associated functions without a receiver need no key material
+pub trait SigningBackend: Sized {
+  /// Create a new signer from private key bytes
+  fn from_private_key(private_key: &[u8; 32]) -> Result<Self, CoreError>;
+
+  /// Get the compressed SEC1 encoding of the public key of the signer
+  fn public_key_bytes(&self) -> [u8; 33];
+
+  /// Get the Ethereum address of the signer
+  fn address_bytes(&self) -> [u8; 20];
+
+  /// Sign a message digest, producing a 64 bytes compact signature
+  /// with a low `s` value, and its recovery id
+  fn sign_compact(&self, digest: &[u8; 32], nonce: &NonceStrategy) -> ([u8; 64], u8);
+
+  /// Verify a compact signature of a message digest against a compressed
+  /// or uncompressed public key. Signatures with a high `s` value are rejected
+  fn verify(public_key: &[u8], digest: &[u8; 32], signature: &[u8; 64]) -> Result<(), CoreError>;
+
+  /// Recover the uncompressed public key that produced a compact
+  /// signature of a message digest, given its recovery id
+  fn recover(
+    digest: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+  ) -> Result<[u8; 65], CoreError>;
+
+  /// Get the compressed encoding of a compressed or uncompressed public key
+  fn compress_public_key(public_key: &[u8]) -> Result<[u8; 33], CoreError>;
+
+  /// Get the uncompressed encoding of a compressed or uncompressed public key
+  fn uncompress_public_key(public_key: &[u8]) -> Result<[u8; 65], CoreError>;
+
+  /// Get a compact signature with a low `s` value, failing
+  /// if its `r` or `s` values are out of range
+  fn normalize_s(signature: &[u8; 64]) -> Result<[u8; 64], CoreError>;
+
+  /// Parse a DER encoded signature into its compact form
+  fn signature_from_der(der: &[u8]) -> Result<[u8; 64], CoreError>;
+
+  /// Get the DER encoding of a compact signature
+  fn signature_to_der(signature: &[u8; 64]) -> Result<Vec<u8>, CoreError>;
+}
+
+/// Compute the keccak256 digest of `bytes`
+pub fn keccak256(bytes: &[u8]) -> [u8; 32] {
+  Keccak256::digest(bytes).into()
+}
+
+/// Get the Ethereum address of a public key, being the last 20 bytes
+/// of the keccak256 digest of its uncompressed encoding
+pub fn public_key_to_address(public_key: &[u8; 65]) -> [u8; 20] {
+  let digest = keccak256(&public_key[1..]);
+  let mut address = [0u8; 20];
+  address.copy_from_slice(&digest[12..]);
+
+  address
+}
@@ -0,0 +1,150 @@
+use rand_core::{CryptoRng, RngCore};
+use walleth_core::{
+  cipher, derivation::fingerprint, derive_private_key, keccak256, NonceStrategy, Signer,
+};
+
+const SEED: &str = "9dfc3c64c2f8bede1533b6a79f8570e5943e0b8fd1cf77107adf7b72cef42185d564a3aee24cab43f80e3c4538087d70fc824eabbad596a23c97b6ee8322ccc0";
+const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const ADDRESS: &str = "f39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+/// A fake RNG filling bytes with an incrementing counter
+struct CounterRng(u8);
+
+impl RngCore for CounterRng {
+  fn next_u32(&mut self) -> u32 {
+    rand_core::impls::next_u32_via_fill(self)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    rand_core::impls::next_u64_via_fill(self)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for byte in dest {
+      self.0 = self.0.wrapping_add(1);
+      *byte = self.0;
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl CryptoRng for CounterRng {}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+    .collect()
+}
+
+mod encrypt {
+  use super::*;
+
+  #[test]
+  fn it_encrypts_and_decrypts_with_an_injected_rng() {
+    let mut rng = CounterRng(0);
+    let key = cipher::new_key(&mut rng);
+
+    let (ciphertext, nonce) = cipher::encrypt(&mut rng, &key, b"secret").unwrap();
+
+    assert_eq!(key[0], 1);
+    assert_eq!(nonce[0], 33);
+    assert_eq!(
+      cipher::decrypt(&key, &nonce, &ciphertext).unwrap(),
+      b"secret"
+    );
+  }
+
+  #[test]
+  fn it_is_deterministic_for_the_same_rng_state() {
+    let key = [7u8; 32];
+
+    let (first, _) = cipher::encrypt(&mut CounterRng(0), &key, b"secret").unwrap();
+    let (second, _) = cipher::encrypt(&mut CounterRng(0), &key, b"secret").unwrap();
+
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn it_fails_to_decrypt_with_a_wrong_key() {
+    let (ciphertext, nonce) = cipher::encrypt(&mut CounterRng(0), &[1u8; 32], b"secret").unwrap();
+
+    assert!(cipher::decrypt(&[2u8; 32], &nonce, &ciphertext).is_err());
+  }
+}
+
+mod derive_private_key {
+  use super::*;
+
+  #[test]
+  fn it_derives_the_private_key_at_a_path() {
+    let private_key = derive_private_key(&from_hex(SEED), "m/44'/60'/0'/0/0").unwrap();
+
+    assert_eq!(private_key.to_vec(), from_hex(PRIVATE_KEY));
+  }
+
+  #[test]
+  fn it_fails_with_an_invalid_path() {
+    assert!(derive_private_key(&from_hex(SEED), "not a path").is_err());
+  }
+
+  #[test]
+  fn it_computes_the_master_fingerprint() {
+    assert_ne!(fingerprint(&from_hex(SEED)).unwrap(), [0u8; 4]);
+  }
+}
+
+#[cfg(feature = "secp256k1")]
+mod sign {
+  use super::*;
+
+  fn signer() -> Signer {
+    Signer::new(&from_hex(PRIVATE_KEY).try_into().unwrap()).unwrap()
+  }
+
+  #[test]
+  fn it_computes_the_address() {
+    assert_eq!(signer().address().to_vec(), from_hex(ADDRESS));
+  }
+
+  #[test]
+  fn it_signs_deterministically() {
+    let digest = keccak256(b"Hello world!");
+
+    assert_eq!(
+      signer().sign_recoverable(&digest, &NonceStrategy::Deterministic),
+      signer().sign_recoverable(&digest, &NonceStrategy::Deterministic)
+    );
+  }
+
+  #[test]
+  fn it_produces_the_same_signature_with_and_without_recovery() {
+    let digest = keccak256(b"Hello world!");
+
+    assert_eq!(
+      signer()
+        .sign_recoverable(&digest, &NonceStrategy::Deterministic)
+        .to_standard(),
+      signer().sign(&digest, &NonceStrategy::Deterministic)
+    );
+  }
+
+  #[test]
+  fn it_uses_the_hardened_nonce_entropy() {
+    let digest = keccak256(b"Hello world!");
+
+    assert_ne!(
+      signer().sign(&digest, &NonceStrategy::Hardened([1u8; 32])),
+      signer().sign(&digest, &NonceStrategy::Deterministic)
+    );
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_private_key() {
+    assert!(Signer::new(&[0u8; 32]).is_err());
+  }
+}
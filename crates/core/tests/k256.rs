@@ -0,0 +1,132 @@
+#![cfg(all(feature = "k256", feature = "secp256k1"))]
+
+use walleth_core::{keccak256, K256Signer, NonceStrategy, Signer, SigningBackend};
+
+const PRIVATE_KEY: [u8; 32] = [
+  0xac, 0x09, 0x74, 0xbe, 0xc3, 0x9a, 0x17, 0xe3, 0x6b, 0xa4, 0xa6, 0xb4, 0xd2, 0x38, 0xff, 0x94,
+  0x4b, 0xac, 0xb4, 0x78, 0xcb, 0xed, 0x5e, 0xfc, 0xae, 0x78, 0x4d, 0x7b, 0xf4, 0xf2, 0xff, 0x80,
+];
+const ADDRESS: [u8; 20] = [
+  0xf3, 0x9f, 0xd6, 0xe5, 0x1a, 0xad, 0x88, 0xf6, 0xf4, 0xce, 0x6a, 0xb8, 0x82, 0x72, 0x79, 0xcf,
+  0xff, 0xb9, 0x22, 0x66,
+];
+
+/// The order of the secp256k1 curve
+const CURVE_ORDER: [u8; 32] = [
+  0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+  0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+/// Turn a low-s compact signature into its high-s equivalent (s' = n - s)
+fn to_high_s(compact: [u8; 64]) -> [u8; 64] {
+  let mut high = compact;
+  let mut borrow = 0i16;
+  for i in (0..32).rev() {
+    let mut value = CURVE_ORDER[i] as i16 - compact[32 + i] as i16 - borrow;
+    borrow = 0;
+    if value < 0 {
+      value += 256;
+      borrow = 1;
+    }
+    high[32 + i] = value as u8;
+  }
+  high
+}
+
+/// Private keys to compare the backends with, derived from a counter
+fn private_keys() -> impl Iterator<Item = [u8; 32]> {
+  (0u32..32).map(|index| keccak256(&index.to_le_bytes()))
+}
+
+fn nonce_strategies() -> [NonceStrategy; 3] {
+  [
+    NonceStrategy::Deterministic,
+    NonceStrategy::Hardened([0u8; 32]),
+    NonceStrategy::Hardened([0x5a; 32]),
+  ]
+}
+
+mod k256_signer {
+  use super::*;
+
+  #[test]
+  fn it_computes_the_address() {
+    assert_eq!(
+      K256Signer::from_private_key(&PRIVATE_KEY)
+        .unwrap()
+        .address_bytes(),
+      ADDRESS
+    );
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_private_key() {
+    assert!(K256Signer::from_private_key(&[0u8; 32]).is_err());
+  }
+
+  #[test]
+  fn it_matches_the_secp256k1_public_keys() {
+    for private_key in private_keys() {
+      let secp256k1 = Signer::from_private_key(&private_key).unwrap();
+      let k256 = K256Signer::from_private_key(&private_key).unwrap();
+
+      assert_eq!(k256.public_key_bytes(), secp256k1.public_key_bytes());
+      assert_eq!(k256.address_bytes(), secp256k1.address_bytes());
+    }
+  }
+
+  #[test]
+  fn it_matches_the_secp256k1_signatures() {
+    for (index, private_key) in private_keys().enumerate() {
+      let secp256k1 = Signer::from_private_key(&private_key).unwrap();
+      let k256 = K256Signer::from_private_key(&private_key).unwrap();
+      let digest = keccak256(format!("message {}", index).as_bytes());
+
+      for nonce in nonce_strategies() {
+        assert_eq!(
+          k256.sign_compact(&digest, &nonce),
+          secp256k1.sign_compact(&digest, &nonce)
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn it_matches_the_secp256k1_verification_and_recovery() {
+    for (index, private_key) in private_keys().enumerate() {
+      let signer = Signer::from_private_key(&private_key).unwrap();
+      let public_key = signer.public_key_bytes();
+      let digest = keccak256(format!("message {}", index).as_bytes());
+      let (signature, recovery_id) = signer.sign_compact(&digest, &NonceStrategy::Deterministic);
+
+      assert!(K256Signer::verify(&public_key, &digest, &signature).is_ok());
+      assert_eq!(
+        K256Signer::recover(&digest, &signature, recovery_id).unwrap(),
+        Signer::recover(&digest, &signature, recovery_id).unwrap()
+      );
+      assert_eq!(
+        K256Signer::uncompress_public_key(&public_key).unwrap(),
+        Signer::uncompress_public_key(&public_key).unwrap()
+      );
+      assert_eq!(
+        K256Signer::signature_to_der(&signature).unwrap(),
+        Signer::signature_to_der(&signature).unwrap()
+      );
+    }
+  }
+
+  #[test]
+  fn it_rejects_high_s_signatures_like_secp256k1() {
+    let signer = Signer::from_private_key(&PRIVATE_KEY).unwrap();
+    let digest = keccak256(b"message");
+    let (signature, _) = signer.sign_compact(&digest, &NonceStrategy::Deterministic);
+    let signature = to_high_s(signature);
+
+    assert!(Signer::verify(&signer.public_key_bytes(), &digest, &signature).is_err());
+    assert!(K256Signer::verify(&signer.public_key_bytes(), &digest, &signature).is_err());
+    assert_eq!(
+      K256Signer::normalize_s(&signature).unwrap(),
+      Signer::normalize_s(&signature).unwrap()
+    );
+  }
+}
@@ -1,40 +1,57 @@
-use secp256k1::{PublicKey, Secp256k1, SecretKey};
-
-use super::AccountError;
+use super::{AccountError, AddressFormatter, DerivationPath};
 use utils::{
   crypto::sha3::keccak256,
-  hex::{add0x, assert_is_valid_hex_address, encode},
+  hex::{decode_to_array, encode_prefixed},
 };
+use walleth_core::{Backend, SigningBackend};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Account<T> {
+pub struct Account<T = DerivationPath> {
   pub address: String,
   pub public_key: Vec<u8>,
   pub path: T,
 }
 
 impl<T> Account<T> {
-  /// Create a new `Account` from an extended public key
-  pub fn from_public_key(public_key: &PublicKey, path: T) -> Result<Self, AccountError> {
-    let extended_address = encode(&keccak256(&public_key.serialize()));
-    let address = extended_address[extended_address.len() - 40..].to_string();
-
-    assert_is_valid_hex_address(&address)?;
-
+  /// Create a new `Account` from a compressed or uncompressed public key
+  pub fn from_public_key(public_key: &[u8], path: T) -> Result<Self, AccountError> {
     Ok(Account {
-      address: add0x(&address).to_owned(),
-      public_key: public_key.serialize().to_vec(),
+      address: public_key_to_address(public_key)?,
+      public_key: Backend::compress_public_key(public_key)
+        .or(Err(AccountError::InvalidKeyLength))?
+        .to_vec(),
       path,
     })
   }
 
   /// Create a new `Account` from a private key
   pub fn from_private_key(private_key: [u8; 32], path: T) -> Result<Self, AccountError> {
-    let secp = Secp256k1::new();
-    let public_key = SecretKey::from_slice(&private_key)
+    let public_key = Backend::from_private_key(&private_key)
       .or(Err(AccountError::InvalidPrivateKey))?
-      .public_key(&secp);
+      .public_key_bytes();
 
     Self::from_public_key(&public_key, path)
   }
+
+  /// Get the raw 20 bytes of the account address
+  pub fn address_bytes(&self) -> Result<[u8; 20], AccountError> {
+    decode_to_array::<20>(&self.address).or(Err(AccountError::InvalidHexAddress))
+  }
+
+  /// Render the account address with the given `formatter`,
+  /// e.g. the one configured for the chain being displayed
+  pub fn format_address(&self, formatter: &dyn AddressFormatter) -> Result<String, AccountError> {
+    Ok(formatter.format(&self.address_bytes()?))
+  }
+}
+
+/// Compute the 0x-prefixed address of a compressed or uncompressed public
+/// key, as the last 20 bytes of the keccak256 hash of its uncompressed
+/// form without the 0x04 prefix
+pub fn public_key_to_address(public_key: &[u8]) -> Result<String, AccountError> {
+  let public_key =
+    Backend::uncompress_public_key(public_key).or(Err(AccountError::InvalidKeyLength))?;
+  let hash = keccak256(&public_key[1..]);
+
+  Ok(encode_prefixed(&hash[12..]))
 }
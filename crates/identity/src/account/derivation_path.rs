@@ -0,0 +1,82 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::AccountError;
+
+/// BIP-44 purpose
+pub const PURPOSE: usize = 44;
+/// SLIP-44 coin type for Ethereum
+pub const COIN_TYPE: usize = 60;
+
+/// A BIP-44 derivation path for Ethereum accounts,
+/// in the form `m/44'/60'/{account}'/{change}/{index}`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DerivationPath {
+  /// The hardened account level
+  pub account: usize,
+  /// The change level, 0 for external addresses
+  pub change: usize,
+  /// The address index
+  pub index: usize,
+}
+
+impl DerivationPath {
+  /// Create a new `DerivationPath`
+  pub fn new(account: usize, change: usize, index: usize) -> Self {
+    Self {
+      account,
+      change,
+      index,
+    }
+  }
+}
+
+impl From<usize> for DerivationPath {
+  /// Create a `DerivationPath` for the address at `index`
+  /// of the first account, on the external chain
+  fn from(index: usize) -> Self {
+    Self::new(0, 0, index)
+  }
+}
+
+impl Display for DerivationPath {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "m/{}'/{}'/{}'/{}/{}",
+      PURPOSE, COIN_TYPE, self.account, self.change, self.index
+    )
+  }
+}
+
+impl FromStr for DerivationPath {
+  type Err = AccountError;
+
+  /// Parse a derivation path in the form `m/44'/60'/{account}'/{change}/{index}`
+  fn from_str(path: &str) -> Result<Self, AccountError> {
+    let segments = path.split('/').collect::<Vec<&str>>();
+    let purpose = format!("{}'", PURPOSE);
+    let coin_type = format!("{}'", COIN_TYPE);
+
+    match segments.as_slice() {
+      ["m", p, c, account, change, index] if *p == purpose && *c == coin_type => {
+        let account = account
+          .strip_suffix('\'')
+          .ok_or(AccountError::InvalidDerivationPath)?;
+
+        Ok(Self::new(
+          parse_segment(account)?,
+          parse_segment(change)?,
+          parse_segment(index)?,
+        ))
+      }
+      _ => Err(AccountError::InvalidDerivationPath),
+    }
+  }
+}
+
+/// Parse a non-hardened path segment
+fn parse_segment(segment: &str) -> Result<usize, AccountError> {
+  segment
+    .parse::<usize>()
+    .or(Err(AccountError::InvalidDerivationPath))
+}
@@ -5,6 +5,7 @@ pub enum AccountError {
   InvalidHexAddress,
   InvalidKeyLength,
   InvalidPrivateKey,
+  InvalidDerivationPath,
 }
 
 impl std::fmt::Display for AccountError {
@@ -13,6 +14,7 @@ impl std::fmt::Display for AccountError {
       Self::InvalidHexAddress => write!(f, "Invalid hex address"),
       Self::InvalidKeyLength => write!(f, "Invalid key length"),
       Self::InvalidPrivateKey => write!(f, "Invalid private key"),
+      Self::InvalidDerivationPath => write!(f, "Invalid derivation path"),
     }
   }
 }
@@ -0,0 +1,91 @@
+use utils::{
+  crypto::sha3::keccak256,
+  hex::{add0x, encode, encode_prefixed},
+};
+
+/// Renders the 20 bytes of an address in the textual
+/// format expected by a given chain or ecosystem
+pub trait AddressFormatter {
+  /// Format the raw bytes of an address
+  fn format(&self, address: &[u8; 20]) -> String;
+}
+
+/// Lowercase, 0x-prefixed hex addresses
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HexAddressFormatter;
+
+impl AddressFormatter for HexAddressFormatter {
+  fn format(&self, address: &[u8; 20]) -> String {
+    encode_prefixed(address)
+  }
+}
+
+/// Mixed-case checksummed addresses, as defined by EIP-55
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChecksumAddressFormatter;
+
+impl AddressFormatter for ChecksumAddressFormatter {
+  fn format(&self, address: &[u8; 20]) -> String {
+    let lowercase = encode(address);
+    let hash = encode(&keccak256(lowercase.as_bytes()));
+
+    let checksummed: String = lowercase
+      .chars()
+      .zip(hash.chars())
+      .map(|(char, nibble)| match nibble.to_digit(16) {
+        Some(value) if value >= 8 => char.to_ascii_uppercase(),
+        _ => char,
+      })
+      .collect();
+
+    add0x(&checksummed)
+  }
+}
+
+/// Inter-exchange Client Address Protocol (ICAP) addresses,
+/// an IBAN-compatible encoding with the `XE` country code
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IcapAddressFormatter;
+
+impl IcapAddressFormatter {
+  /// Encode a big-endian unsigned integer in base 36
+  fn to_base36(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut value = bytes.to_vec();
+    let mut digits = vec![];
+
+    while value.iter().any(|byte| *byte != 0) {
+      let mut remainder = 0u32;
+      for byte in value.iter_mut() {
+        let current = (remainder << 8) | *byte as u32;
+        *byte = (current / 36) as u8;
+        remainder = current % 36;
+      }
+      digits.push(ALPHABET[remainder as usize]);
+    }
+
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+  }
+
+  /// Compute the two-digit IBAN checksum of `country` followed by `bban`
+  fn checksum(country: &str, bban: &str) -> String {
+    let remainder = format!("{}{}00", bban, country)
+      .chars()
+      .filter_map(|char| char.to_digit(36))
+      .fold(0u32, |remainder, digit| match digit {
+        0..=9 => (remainder * 10 + digit) % 97,
+        _ => (remainder * 100 + digit) % 97,
+      });
+
+    format!("{:02}", 98 - remainder)
+  }
+}
+
+impl AddressFormatter for IcapAddressFormatter {
+  fn format(&self, address: &[u8; 20]) -> String {
+    let bban = format!("{:0>30}", Self::to_base36(address));
+
+    format!("XE{}{}", Self::checksum("XE", &bban), bban)
+  }
+}
@@ -1,5 +1,11 @@
 pub mod account;
+pub mod derivation_path;
 pub mod errors;
+pub mod formatter;
 
-pub use account::Account;
+pub use account::{public_key_to_address, Account};
+pub use derivation_path::DerivationPath;
 pub use errors::AccountError;
+pub use formatter::{
+  AddressFormatter, ChecksumAddressFormatter, HexAddressFormatter, IcapAddressFormatter,
+};
@@ -1,7 +1,16 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(not(any(feature = "secp256k1", feature = "k256")))]
+compile_error!("walleth-identity needs a signing backend: enable `secp256k1` or `k256`");
+
 pub mod account;
+pub mod registry;
 pub mod signer;
 pub mod traits;
 
-pub use account::{Account, AccountError};
-pub use signer::{Signer, SignerError};
+pub use account::{Account, AccountError, AddressFormatter, DerivationPath};
+pub use registry::{IdentityFactoryRegistry, RegistryError};
+pub use signer::{
+  verify_address, verify_any, verify_with_public_key, IntoSignable, Signable, Signer, SignerError,
+};
 pub use traits::*;
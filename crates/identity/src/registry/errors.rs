@@ -0,0 +1,26 @@
+use crate::IdentityError;
+
+#[derive(Debug)]
+pub enum RegistryError {
+  UnknownIdentityType(String),
+}
+
+impl std::fmt::Display for RegistryError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::UnknownIdentityType(identity_type) => {
+        write!(f, "Unknown identity type: {}", identity_type)
+      }
+    }
+  }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl IdentityError for RegistryError {}
+
+impl From<RegistryError> for Box<dyn IdentityError> {
+  fn from(error: RegistryError) -> Self {
+    Box::new(error)
+  }
+}
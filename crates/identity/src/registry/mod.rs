@@ -0,0 +1,5 @@
+pub mod registry;
+pub use registry::*;
+
+pub mod errors;
+pub use errors::*;
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use super::RegistryError;
+use crate::{IdentityError, Initializable};
+
+/// A function recreating an identity from its serialized bytes
+pub type IdentityDeserializer<T> = fn(&[u8]) -> Result<T, Box<dyn IdentityError>>;
+
+/// A runtime registry of deserializers, indexed by identity type.
+///
+/// It allows restoring identities whose concrete type is only known
+/// at runtime, like the content of a backup holding different kinds
+/// of identities.
+#[derive(Debug)]
+pub struct IdentityFactoryRegistry<T> {
+  /// Deserializers indexed by the identity type they restore
+  deserializers: HashMap<String, IdentityDeserializer<T>>,
+  /// The deserializer used for identity types not registered
+  fallback: Option<IdentityDeserializer<T>>,
+}
+
+impl<T> IdentityFactoryRegistry<T> {
+  /// Create a new empty registry
+  pub fn new() -> Self {
+    Self {
+      deserializers: HashMap::new(),
+      fallback: None,
+    }
+  }
+
+  /// Register a deserializer for an identity type
+  pub fn register(
+    &mut self,
+    identity_type: &str,
+    deserializer: IdentityDeserializer<T>,
+  ) -> &mut Self {
+    self
+      .deserializers
+      .insert(identity_type.to_string(), deserializer);
+    self
+  }
+
+  /// Set the deserializer used for identity types not registered
+  pub fn set_fallback(&mut self, deserializer: IdentityDeserializer<T>) -> &mut Self {
+    self.fallback = Some(deserializer);
+    self
+  }
+
+  /// Check if a deserializer is registered for an identity type
+  pub fn is_registered(&self, identity_type: &str) -> bool {
+    self.deserializers.contains_key(identity_type)
+  }
+
+  /// Recreate an identity of type `identity_type` from bytes
+  pub fn deserialize(
+    &self,
+    identity_type: &str,
+    bytes: &[u8],
+  ) -> Result<T, Box<dyn IdentityError>> {
+    match self
+      .deserializers
+      .get(identity_type)
+      .or(self.fallback.as_ref())
+    {
+      Some(deserializer) => deserializer(bytes),
+      None => Err(RegistryError::UnknownIdentityType(identity_type.to_string()).into()),
+    }
+  }
+}
+
+impl<T: Initializable> IdentityFactoryRegistry<T> {
+  /// Create a new registry that restores any identity type
+  /// by initializing a `T` and deserializing bytes into it
+  pub fn with_initializable() -> Self {
+    let mut registry = Self::new();
+    registry.set_fallback(|bytes| {
+      let mut identity = T::new();
+      identity.deserialize(bytes)?;
+      Ok(identity)
+    });
+
+    registry
+  }
+}
+
+// Deserializers are function pointers, so the registry
+// can be cloned whether identities can or not
+impl<T> Clone for IdentityFactoryRegistry<T> {
+  fn clone(&self) -> Self {
+    Self {
+      deserializers: self.deserializers.clone(),
+      fallback: self.fallback,
+    }
+  }
+}
+
+impl<T> Default for IdentityFactoryRegistry<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,76 @@
+use utils::B256;
+
+use super::{verify_address, IntoSignable, Signature, SignerError};
+
+/// The value returned by `isValidSignature(bytes32,bytes)` for a valid
+/// signature, which is also the selector of the function (ERC-1271)
+pub const ERC1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Performs read-only contract calls (`eth_call`) against a chain.
+///
+/// This is the only piece of network access needed to validate
+/// smart-contract signatures, and is meant to be implemented by providers.
+pub trait ContractCaller {
+  /// Call the contract at `contract` with `calldata`, returning the raw return data
+  fn call(&self, contract: &str, calldata: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+/// ABI-encode a call to `isValidSignature(bytes32 hash, bytes signature)`
+pub fn is_valid_signature_calldata(hash: &B256, signature: &[u8]) -> Vec<u8> {
+  let mut calldata = ERC1271_MAGIC_VALUE.to_vec();
+  calldata.extend(hash.as_bytes());
+  calldata.extend(abi_word(0x40));
+  calldata.extend(abi_word(signature.len() as u64));
+  calldata.extend(signature);
+  calldata.resize(calldata.len() + (32 - signature.len() % 32) % 32, 0u8);
+
+  calldata
+}
+
+/// Check whether `signature` over `hash` is valid for the smart
+/// contract account at `contract`, as defined by ERC-1271
+pub fn is_valid_signature(
+  caller: &dyn ContractCaller,
+  contract: &str,
+  hash: &B256,
+  signature: &[u8],
+) -> Result<bool, SignerError> {
+  let result = caller.call(contract, &is_valid_signature_calldata(hash, signature))?;
+
+  Ok(result.len() >= 4 && result[..4] == ERC1271_MAGIC_VALUE)
+}
+
+/// Verify that `signature` over `message` was produced by `address`,
+/// whether it is an externally owned account or a smart contract wallet.
+///
+/// The signature is first checked through public key recovery, and
+/// falls back to an ERC-1271 `isValidSignature` call on `address`
+/// with the digest of the message.
+pub fn verify_any<S>(
+  caller: &dyn ContractCaller,
+  address: &str,
+  message: &S,
+  signature: &[u8],
+) -> Result<(), SignerError>
+where
+  S: IntoSignable + ?Sized,
+{
+  if let Ok(recoverable) = Signature::from_compact(signature) {
+    if verify_address(address, message, &recoverable).is_ok() {
+      return Ok(());
+    }
+  }
+
+  match is_valid_signature(caller, address, &message.to_signable().digest(), signature)? {
+    true => Ok(()),
+    false => Err(SignerError::AddressMismatch),
+  }
+}
+
+/// Encode an integer as a big-endian 32 bytes ABI word
+fn abi_word(value: u64) -> [u8; 32] {
+  let mut word = [0u8; 32];
+  word[24..].copy_from_slice(&value.to_be_bytes());
+
+  word
+}
@@ -1,8 +1,14 @@
+use walleth_core::CoreError;
+
 #[derive(Debug)]
 pub enum SignerError {
   GenericError,
   InvalidPrivateKey,
   InvalidSignature,
+  MissingRecoveryId,
+  AddressMismatch,
+  InvalidPublicKey,
+  ContractCallFailed,
 }
 
 impl std::fmt::Display for SignerError {
@@ -10,6 +16,10 @@ impl std::fmt::Display for SignerError {
     match self {
       Self::InvalidPrivateKey => write!(f, "Invalid private key"),
       Self::InvalidSignature => write!(f, "Invalid signature"),
+      Self::MissingRecoveryId => write!(f, "Missing signature recovery id"),
+      Self::AddressMismatch => write!(f, "Signature does not match the address"),
+      Self::InvalidPublicKey => write!(f, "Invalid public key"),
+      Self::ContractCallFailed => write!(f, "Contract call failed"),
       Self::GenericError => write!(f, "Secp256k1 error"),
     }
   }
@@ -17,11 +27,12 @@ impl std::fmt::Display for SignerError {
 
 impl std::error::Error for SignerError {}
 
-impl From<secp256k1::Error> for SignerError {
-  fn from(error: secp256k1::Error) -> Self {
+impl From<CoreError> for SignerError {
+  fn from(error: CoreError) -> Self {
     match error {
-      secp256k1::Error::InvalidSecretKey => Self::InvalidPrivateKey,
-      secp256k1::Error::InvalidSignature => Self::InvalidSignature,
+      CoreError::InvalidPrivateKey => Self::InvalidPrivateKey,
+      CoreError::InvalidPublicKey => Self::InvalidPublicKey,
+      CoreError::InvalidSignature => Self::InvalidSignature,
       _ => Self::GenericError,
     }
   }
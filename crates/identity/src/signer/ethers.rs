@@ -0,0 +1,58 @@
+pub use ethers_core::types::{Address, Signature as EthersSignature};
+pub use ethers_signers::{LocalWallet, Signer as EthersSigner};
+
+use super::{Signature, Signer, SignerError};
+use crate::Account;
+
+impl TryFrom<&LocalWallet> for Signer {
+  type Error = SignerError;
+
+  /// Create a signer holding the key of an ethers-rs wallet
+  fn try_from(wallet: &LocalWallet) -> Result<Self, SignerError> {
+    Signer::new(wallet.signer().to_bytes().into())
+  }
+}
+
+impl TryFrom<&Signature> for EthersSignature {
+  type Error = SignerError;
+
+  /// Convert a signature to an ethers-rs one, with `v` being 27 or 28.
+  /// Fails if the signature has no recovery id
+  fn try_from(signature: &Signature) -> Result<Self, SignerError> {
+    EthersSignature::try_from(&signature.to_rsv()?[..]).or(Err(SignerError::InvalidSignature))
+  }
+}
+
+impl TryFrom<&EthersSignature> for Signature {
+  type Error = SignerError;
+
+  /// Convert an ethers-rs signature. Its `v` can be a recovery id,
+  /// 27 or 28, or an EIP-155 value including the chain id
+  fn try_from(signature: &EthersSignature) -> Result<Self, SignerError> {
+    let recovery_id = match signature.v {
+      0 | 1 | 27 | 28 => signature.v as u8,
+      v if v >= 35 => ((v - 35) % 2) as u8,
+      _ => return Err(SignerError::InvalidSignature),
+    };
+
+    let mut bytes = [0u8; 65];
+    signature.r.to_big_endian(&mut bytes[..32]);
+    signature.s.to_big_endian(&mut bytes[32..64]);
+    bytes[64] = recovery_id;
+
+    Signature::from_compact(&bytes)
+  }
+}
+
+impl TryFrom<&Account> for Address {
+  type Error = SignerError;
+
+  /// Get the ethers-rs address of an account
+  fn try_from(account: &Account) -> Result<Self, SignerError> {
+    Ok(Address::from(
+      account
+        .address_bytes()
+        .or(Err(SignerError::InvalidPublicKey))?,
+    ))
+  }
+}
@@ -6,3 +6,18 @@ pub use signable::*;
 
 pub mod errors;
 pub use errors::*;
+
+pub mod options;
+pub use options::*;
+
+pub mod signature;
+pub use signature::*;
+
+pub mod verify;
+pub use verify::*;
+
+#[cfg(feature = "ethers")]
+pub mod ethers;
+
+pub mod erc1271;
+pub use erc1271::*;
@@ -0,0 +1,24 @@
+pub use walleth_core::NonceStrategy;
+
+/// Options controlling how signatures are produced and verified
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SignatureOptions {
+  /// The nonce generation strategy
+  pub nonce: NonceStrategy,
+  /// Require the `s` value to be in the lower half of the curve order (EIP-2).
+  /// Signatures produced by a `Signer` are always low-s; when verifying,
+  /// high-s signatures are rejected if `true`, or normalized if `false`.
+  pub low_s: bool,
+  /// Produce a recoverable signature, carrying the recovery id
+  pub recoverable: bool,
+}
+
+impl Default for SignatureOptions {
+  fn default() -> Self {
+    Self {
+      nonce: NonceStrategy::Deterministic,
+      low_s: true,
+      recoverable: false,
+    }
+  }
+}
@@ -1,10 +1,9 @@
-use secp256k1::Message;
-
-use utils::crypto::sha3::keccak256;
+use utils::{crypto::sha3::keccak256, B256};
 
+/// A message digest to be signed
 #[derive(Debug, Clone)]
 pub struct Signable {
-  message: Message,
+  message: [u8; 32],
 }
 
 impl Signable {
@@ -17,6 +16,7 @@ impl Signable {
   }
 
   /// Parse a string into a byte array of a message digest
+  #[allow(clippy::should_implement_trait)]
   pub fn from_str(str: &str) -> Self {
     Signable {
       message: digest_str(str),
@@ -30,19 +30,74 @@ impl Signable {
     }
   }
 
+  /// Create a signable message from an already computed digest,
+  /// for messages hashed with something else than keccak256
+  pub fn from_digest(digest: B256) -> Self {
+    Signable { message: digest.0 }
+  }
+
   /// Get the message digest to be signed
-  pub fn to_signable_message(&self) -> Message {
+  pub fn to_signable_message(&self) -> [u8; 32] {
     self.message
   }
+
+  /// Get the bytes of the message digest
+  pub fn digest(&self) -> B256 {
+    B256(self.message)
+  }
+}
+
+/// Anything that can be signed, like an order or an attestation.
+///
+/// Byte slices, vectors, arrays and strings are digested with keccak256,
+/// while domain types can implement it to sign with their own hashing
+pub trait IntoSignable {
+  /// Get the message digest to be signed
+  fn to_signable(&self) -> Signable;
+}
+
+impl IntoSignable for Signable {
+  fn to_signable(&self) -> Signable {
+    self.clone()
+  }
+}
+
+impl IntoSignable for [u8] {
+  fn to_signable(&self) -> Signable {
+    Signable::from_bytes(self)
+  }
+}
+
+impl<const N: usize> IntoSignable for [u8; N] {
+  fn to_signable(&self) -> Signable {
+    Signable::from_bytes(self)
+  }
+}
+
+impl IntoSignable for Vec<u8> {
+  fn to_signable(&self) -> Signable {
+    Signable::from_bytes(self)
+  }
+}
+
+impl IntoSignable for str {
+  fn to_signable(&self) -> Signable {
+    Signable::from_str(self)
+  }
+}
+
+impl IntoSignable for String {
+  fn to_signable(&self) -> Signable {
+    Signable::from_str(self)
+  }
 }
 
 /// Digest a message string
-pub fn digest_str(message: &str) -> Message {
-  Message::from_slice(&keccak256(message.as_bytes())).unwrap()
+pub fn digest_str(message: &str) -> [u8; 32] {
+  keccak256(message.as_bytes())
 }
 
 /// Digest message bytes
-pub fn digest_bytes(message: &[u8]) -> Message {
-  // Unwrap is safe because the hash is always 32 bytes
-  Message::from_slice(&keccak256(message)).unwrap()
+pub fn digest_bytes(message: &[u8]) -> [u8; 32] {
+  keccak256(message)
 }
@@ -0,0 +1,116 @@
+use super::{Signable, SignerError};
+use utils::hex::{decode, encode_prefixed, remove0x};
+use walleth_core::{Backend, SigningBackend};
+
+/// An ECDSA signature over the secp256k1 curve, optionally carrying
+/// the recovery id needed to recover the signer public key.
+///
+/// It can be converted to and from DER, 64-bytes compact and
+/// 65-bytes RSV (`r || s || v`, with `v` being 27 or 28) encodings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Signature {
+  signature: [u8; 64],
+  recovery_id: Option<u8>,
+}
+
+impl Signature {
+  /// Create a new `Signature` from a 64-bytes compact signature
+  /// (`r || s`) and an optional recovery id (0 or 1)
+  pub fn new(signature: [u8; 64], recovery_id: Option<u8>) -> Self {
+    Self {
+      signature,
+      recovery_id,
+    }
+  }
+
+  /// Parse a DER encoded signature
+  pub fn from_der(bytes: &[u8]) -> Result<Self, SignerError> {
+    Ok(Self::new(Backend::signature_from_der(bytes)?, None))
+  }
+
+  /// Parse a compact signature. A 65th byte, if present, is read as
+  /// the recovery id (0, 1, 27 or 28)
+  pub fn from_compact(bytes: &[u8]) -> Result<Self, SignerError> {
+    let recovery_id = match bytes.len() {
+      64 => None,
+      65 => Some(parse_recovery_id(bytes[64])?),
+      _ => return Err(SignerError::InvalidSignature),
+    };
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&bytes[..64]);
+    // Fail early on `r` or `s` values out of range,
+    // keeping high `s` values as they are
+    Backend::normalize_s(&signature)?;
+
+    Ok(Self::new(signature, recovery_id))
+  }
+
+  /// Parse a 65-bytes RSV hex string, with or without the 0x prefix
+  pub fn from_rsv_hex(rsv: &str) -> Result<Self, SignerError> {
+    let bytes = decode(remove0x(rsv)).or(Err(SignerError::InvalidSignature))?;
+
+    match bytes.len() {
+      65 => Self::from_compact(&bytes),
+      _ => Err(SignerError::InvalidSignature),
+    }
+  }
+
+  /// Get the DER encoded signature
+  pub fn to_der(&self) -> Vec<u8> {
+    // Signatures are checked when created, so they always have a DER encoding
+    Backend::signature_to_der(&self.signature).unwrap_or_default()
+  }
+
+  /// Get the 64-bytes compact signature (`r || s`)
+  pub fn to_compact(&self) -> [u8; 64] {
+    self.signature
+  }
+
+  /// Get the 65-bytes RSV signature (`r || s || v`).
+  /// Fails if the signature has no recovery id
+  pub fn to_rsv(&self) -> Result<[u8; 65], SignerError> {
+    let v = self.v().ok_or(SignerError::MissingRecoveryId)?;
+    let mut rsv = [0u8; 65];
+    rsv[..64].copy_from_slice(&self.to_compact());
+    rsv[64] = v;
+
+    Ok(rsv)
+  }
+
+  /// Get the 0x-prefixed RSV hex string.
+  /// Fails if the signature has no recovery id
+  pub fn to_rsv_hex(&self) -> Result<String, SignerError> {
+    Ok(encode_prefixed(&self.to_rsv()?))
+  }
+
+  /// Get the recovery id (0 or 1), if any
+  pub fn recovery_id(&self) -> Option<u8> {
+    self.recovery_id
+  }
+
+  /// Get the Ethereum `v` value (27 or 28), if the recovery id is known
+  pub fn v(&self) -> Option<u8> {
+    self.recovery_id().map(|id| id + 27)
+  }
+
+  /// Recover the uncompressed public key that produced the signature
+  /// over a message digest. Fails if the signature has no recovery id
+  pub fn recover(&self, signable: &Signable) -> Result<[u8; 65], SignerError> {
+    let recovery_id = self.recovery_id.ok_or(SignerError::MissingRecoveryId)?;
+
+    Ok(Backend::recover(
+      &signable.to_signable_message(),
+      &self.signature,
+      recovery_id,
+    )?)
+  }
+}
+
+/// Parse a recovery id from either its raw (0, 1) or Ethereum (27, 28) form
+fn parse_recovery_id(v: u8) -> Result<u8, SignerError> {
+  match v {
+    0 | 1 => Ok(v),
+    27 | 28 => Ok(v - 27),
+    _ => Err(SignerError::InvalidSignature),
+  }
+}
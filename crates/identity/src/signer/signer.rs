@@ -1,32 +1,72 @@
-use secp256k1::{ecdsa::Signature, Secp256k1, SecretKey};
+use walleth_core::{Backend, SigningBackend};
 
-use super::{Signable, SignerError};
+use super::{IntoSignable, Signature, SignatureOptions, SignerError};
 
 /// A `Signer` is a safe wrapper around a Secp256k1 secret key. It can sign digested messages.
+///
+/// Nonces are generated deterministically (RFC 6979) unless a `NonceStrategy::Hardened`
+/// option is passed, and produced signatures always have a low `s` value.
+/// Signing goes through the `walleth_core::Backend` selected by the crate features
 pub struct Signer {
-  /// The secret key, derived from a private key
-  secret_key: SecretKey,
+  /// The `no_std` signer holding the secret key
+  inner: Backend,
 }
 
 impl Signer {
   /// Create a new signer from private key bytes
   pub fn new(private_key: [u8; 32]) -> Result<Self, SignerError> {
-    let secret_key = SecretKey::from_slice(&private_key)?;
+    let inner = Backend::from_private_key(&private_key).or(Err(SignerError::InvalidPrivateKey))?;
 
-    Ok(Self { secret_key })
+    Ok(Self { inner })
   }
 
   /// Sign a message digest
-  pub fn sign(&self, signable: &Signable) -> Signature {
-    Secp256k1::new().sign_ecdsa(&signable.to_signable_message(), &self.secret_key)
+  pub fn sign<S>(&self, signable: &S) -> Signature
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.sign_with_options(signable, &SignatureOptions::default())
+  }
+
+  /// Sign a message digest with custom options.
+  /// The signature carries its recovery id only if requested
+  pub fn sign_with_options<S>(&self, signable: &S, options: &SignatureOptions) -> Signature
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let message = signable.to_signable().to_signable_message();
+    let (signature, recovery_id) = self.inner.sign_compact(&message, &options.nonce);
+
+    Signature::new(signature, options.recoverable.then_some(recovery_id))
   }
 
   /// Verify signature
-  pub fn verify(&self, signable: &Signable, signature: &[u8]) -> Result<(), SignerError> {
-    let secp = Secp256k1::new();
-    let public_key = self.secret_key.public_key(&secp);
-    let signature = Signature::from_compact(signature)?;
+  pub fn verify<S>(&self, signable: &S, signature: &Signature) -> Result<(), SignerError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.verify_with_options(signable, signature, &SignatureOptions::default())
+  }
+
+  /// Verify a signature with custom options
+  pub fn verify_with_options<S>(
+    &self,
+    signable: &S,
+    signature: &Signature,
+    options: &SignatureOptions,
+  ) -> Result<(), SignerError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let signature = match options.low_s {
+      true => signature.to_compact(),
+      false => Backend::normalize_s(&signature.to_compact())?,
+    };
 
-    Ok(secp.verify_ecdsa(&signable.to_signable_message(), &signature, &public_key)?)
+    Ok(Backend::verify(
+      &self.inner.public_key_bytes(),
+      &signable.to_signable().to_signable_message(),
+      &signature,
+    )?)
   }
 }
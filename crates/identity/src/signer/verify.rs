@@ -0,0 +1,48 @@
+use super::{IntoSignable, Signature, SignerError};
+use crate::account::public_key_to_address;
+use utils::hex::remove0x;
+use walleth_core::{Backend, SigningBackend};
+
+/// Verify that `signature` over `message` was produced by the owner
+/// of `public_key`, without any key material.
+///
+/// The public key can be serialized either compressed (33 bytes) or
+/// uncompressed (65 bytes). As with `Signer::verify`, the message is
+/// digested internally and high-S signatures are rejected.
+pub fn verify_with_public_key<S>(
+  public_key: &[u8],
+  message: &S,
+  signature: &Signature,
+) -> Result<(), SignerError>
+where
+  S: IntoSignable + ?Sized,
+{
+  Ok(Backend::verify(
+    public_key,
+    &message.to_signable().to_signable_message(),
+    &signature.to_compact(),
+  )?)
+}
+
+/// Verify that `signature` over `message` was produced by the account
+/// at `address`, without any key material.
+///
+/// The public key is recovered from the signature, which must therefore
+/// carry its recovery id, and its address is compared with `address`.
+/// The message is digested internally, as done when signing.
+pub fn verify_address<S>(
+  address: &str,
+  message: &S,
+  signature: &Signature,
+) -> Result<(), SignerError>
+where
+  S: IntoSignable + ?Sized,
+{
+  let public_key = signature.recover(&message.to_signable())?;
+  let recovered = public_key_to_address(&public_key).or(Err(SignerError::InvalidSignature))?;
+
+  match remove0x(&recovered).eq_ignore_ascii_case(remove0x(address)) {
+    true => Ok(()),
+    false => Err(SignerError::AddressMismatch),
+  }
+}
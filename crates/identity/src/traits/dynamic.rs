@@ -0,0 +1,70 @@
+use std::fmt::Debug;
+
+use utils::PublicKeyBytes;
+
+use super::{GenericIdentity, IdentityError, MultiKeyPair};
+use crate::{
+  signer::{IntoSignable, Signature, SignatureOptions},
+  Account, DerivationPath,
+};
+
+type IdentityResult<T> = Result<T, Box<dyn IdentityError>>;
+
+/// An object-safe façade over `MultiKeyPair` for Ethereum keys.
+///
+/// Any multi keypair identity implements it, so that identities
+/// of different types can be stored together as `Box<dyn MultiKeyPairDyn>`.
+pub trait MultiKeyPairDyn: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> + Debug {}
+
+impl<T> MultiKeyPairDyn for T where T: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> + Debug
+{}
+
+impl GenericIdentity for Box<dyn MultiKeyPairDyn> {
+  fn identity_type(&self) -> String {
+    (**self).identity_type()
+  }
+
+  fn fingerprint(&self) -> [u8; 4] {
+    (**self).fingerprint()
+  }
+
+  fn serialize(&self) -> Vec<u8> {
+    (**self).serialize()
+  }
+
+  fn deserialize(&mut self, bytes: &[u8]) -> IdentityResult<()> {
+    (**self).deserialize(bytes)
+  }
+}
+
+impl MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> for Box<dyn MultiKeyPairDyn> {
+  fn private_key_at(&self, path: DerivationPath) -> IdentityResult<[u8; 32]> {
+    (**self).private_key_at(path)
+  }
+
+  fn public_key_at(&self, path: DerivationPath) -> IdentityResult<PublicKeyBytes> {
+    (**self).public_key_at(path)
+  }
+
+  fn private_keys_at(&self, paths: &[DerivationPath]) -> IdentityResult<Vec<[u8; 32]>> {
+    (**self).private_keys_at(paths)
+  }
+
+  fn sign(
+    &self,
+    from: &Account,
+    message: &dyn IntoSignable,
+    options: &SignatureOptions,
+  ) -> IdentityResult<Signature> {
+    (**self).sign(from, message, options)
+  }
+
+  fn verify(
+    &self,
+    from: &Account,
+    message: &dyn IntoSignable,
+    signature: &Signature,
+  ) -> IdentityResult<()> {
+    (**self).verify(from, message, signature)
+  }
+}
@@ -1,6 +1,9 @@
 use std::error::Error;
 
-use crate::Account;
+use crate::{
+  signer::{IntoSignable, Signature, SignatureOptions},
+  Account,
+};
 
 pub trait IdentityError: Error + 'static {}
 
@@ -10,6 +13,9 @@ pub trait GenericIdentity {
   /// Get the identity type
   fn identity_type(&self) -> String;
 
+  /// Get a stable, non-sensitive fingerprint of the identity
+  fn fingerprint(&self) -> [u8; 4];
+
   /// Serialize the identity into a byte array
   fn serialize(&self) -> Vec<u8>;
 
@@ -55,9 +61,34 @@ where
   /// Get the public key at a derivation path
   fn public_key_at(&self, path: P) -> IdentityResult<PB>;
 
-  /// Sign a message with an account of the identity
-  fn sign(&self, from: &Account<P>, message: &[u8]) -> IdentityResult<Vec<u8>>;
+  /// Get the private keys at multiple derivation paths.
+  /// Identities can override it to share the derivation
+  /// work between paths with a common parent.
+  fn private_keys_at(&self, paths: &[P]) -> IdentityResult<Vec<PK>>
+  where
+    P: Clone,
+  {
+    paths
+      .iter()
+      .map(|path| self.private_key_at(path.clone()))
+      .collect()
+  }
+
+  /// Sign a message with an account of the identity.
+  /// The message is digested through `IntoSignable`, so that byte
+  /// slices and domain types with custom hashing can both be signed
+  fn sign(
+    &self,
+    from: &Account<P>,
+    message: &dyn IntoSignable,
+    options: &SignatureOptions,
+  ) -> IdentityResult<Signature>;
 
   /// Verify a signature with an account of the identity
-  fn verify(&self, from: &Account<P>, message: &[u8], signature: &[u8]) -> IdentityResult<()>;
+  fn verify(
+    &self,
+    from: &Account<P>,
+    message: &dyn IntoSignable,
+    signature: &Signature,
+  ) -> IdentityResult<()>;
 }
@@ -1,3 +1,7 @@
 pub mod identity;
 
 pub use identity::*;
+
+pub mod dynamic;
+
+pub use dynamic::*;
@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use walleth_identity::DerivationPath;
+
+mod display {
+  use super::*;
+
+  #[test]
+  fn it_displays_the_full_bip44_path() {
+    let path = DerivationPath::new(1, 0, 3);
+
+    assert_eq!(path.to_string(), "m/44'/60'/1'/0/3");
+  }
+
+  #[test]
+  fn it_creates_paths_from_an_index() {
+    assert_eq!(DerivationPath::from(5), DerivationPath::new(0, 0, 5));
+  }
+}
+
+mod from_str {
+  use super::*;
+
+  #[test]
+  fn it_parses_a_displayed_path() {
+    let path = DerivationPath::new(2, 1, 7);
+
+    assert_eq!(DerivationPath::from_str(&path.to_string()).unwrap(), path);
+  }
+
+  #[test]
+  fn it_fails_with_non_ethereum_paths() {
+    assert!(DerivationPath::from_str("m/44'/0'/0'/0/0").is_err());
+  }
+
+  #[test]
+  fn it_fails_with_non_hardened_account() {
+    assert!(DerivationPath::from_str("m/44'/60'/0/0/0").is_err());
+  }
+
+  #[test]
+  fn it_fails_with_malformed_paths() {
+    assert!(DerivationPath::from_str("m/44'/60'/0'/0").is_err());
+    assert!(DerivationPath::from_str("m/44'/60'/0'/0/x").is_err());
+  }
+}
@@ -0,0 +1,149 @@
+use std::cell::RefCell;
+
+use utils::B256;
+
+use walleth_identity::{
+  signer::{
+    is_valid_signature, is_valid_signature_calldata, ContractCaller, Signable, SignatureOptions,
+    Signer, SignerError, ERC1271_MAGIC_VALUE,
+  },
+  verify_any, Account,
+};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+const WALLET: &str = "0x1111111111111111111111111111111111111111";
+
+/// A contract caller answering every call with a fixed value
+struct MockCaller {
+  result: Result<Vec<u8>, ()>,
+  calls: RefCell<Vec<(String, Vec<u8>)>>,
+}
+
+impl MockCaller {
+  fn returning(result: Vec<u8>) -> Self {
+    Self {
+      result: Ok(result),
+      calls: RefCell::new(vec![]),
+    }
+  }
+
+  fn failing() -> Self {
+    Self {
+      result: Err(()),
+      calls: RefCell::new(vec![]),
+    }
+  }
+}
+
+impl ContractCaller for MockCaller {
+  fn call(&self, contract: &str, calldata: &[u8]) -> Result<Vec<u8>, SignerError> {
+    self
+      .calls
+      .borrow_mut()
+      .push((contract.to_string(), calldata.to_vec()));
+
+    self.result.clone().or(Err(SignerError::ContractCallFailed))
+  }
+}
+
+fn magic_word() -> Vec<u8> {
+  let mut word = ERC1271_MAGIC_VALUE.to_vec();
+  word.resize(32, 0u8);
+
+  word
+}
+
+fn sign(message: &[u8]) -> [u8; 65] {
+  let options = SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  };
+
+  Signer::new(PRIVATE_KEY)
+    .unwrap()
+    .sign_with_options(&Signable::from_bytes(message), &options)
+    .to_rsv()
+    .unwrap()
+}
+
+mod is_valid_signature_calldata {
+  use super::*;
+
+  #[test]
+  fn it_abi_encodes_the_call() {
+    let calldata = is_valid_signature_calldata(&B256([7u8; 32]), &[9u8; 65]);
+
+    assert_eq!(calldata[..4], ERC1271_MAGIC_VALUE);
+    assert_eq!(calldata[4..36], [7u8; 32]);
+    assert_eq!(calldata[67], 0x40);
+    assert_eq!(calldata[99], 65);
+    assert_eq!(calldata[100..165], [9u8; 65]);
+    assert_eq!(calldata.len(), 4 + 32 * 3 + 96);
+  }
+}
+
+mod is_valid_signature {
+  use super::*;
+
+  #[test]
+  fn it_accepts_the_magic_value() {
+    let caller = MockCaller::returning(magic_word());
+
+    assert!(is_valid_signature(&caller, WALLET, &B256::default(), &[1u8; 65]).unwrap());
+    assert_eq!(caller.calls.borrow()[0].0, WALLET);
+  }
+
+  #[test]
+  fn it_rejects_other_values() {
+    let caller = MockCaller::returning(vec![0u8; 32]);
+
+    assert!(!is_valid_signature(&caller, WALLET, &B256::default(), &[1u8; 65]).unwrap());
+  }
+
+  #[test]
+  fn it_propagates_call_failures() {
+    let caller = MockCaller::failing();
+
+    assert!(matches!(
+      is_valid_signature(&caller, WALLET, &B256::default(), &[1u8; 65]),
+      Err(SignerError::ContractCallFailed)
+    ));
+  }
+}
+
+mod verify_any {
+  use super::*;
+
+  #[test]
+  fn it_verifies_eoa_signatures_without_calling_the_chain() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let caller = MockCaller::failing();
+
+    assert!(verify_any(
+      &caller,
+      &account.address,
+      b"Hello world!",
+      &sign(b"Hello world!")
+    )
+    .is_ok());
+    assert!(caller.calls.borrow().is_empty());
+  }
+
+  #[test]
+  fn it_falls_back_to_erc1271() {
+    let caller = MockCaller::returning(magic_word());
+
+    assert!(verify_any(&caller, WALLET, b"Hello world!", &sign(b"Hello world!")).is_ok());
+    assert_eq!(caller.calls.borrow().len(), 1);
+  }
+
+  #[test]
+  fn it_fails_when_the_contract_rejects_the_signature() {
+    let caller = MockCaller::returning(vec![0u8; 32]);
+
+    assert!(matches!(
+      verify_any(&caller, WALLET, b"Hello world!", &[3u8; 100]),
+      Err(SignerError::AddressMismatch)
+    ));
+  }
+}
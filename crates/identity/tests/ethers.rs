@@ -0,0 +1,97 @@
+#![cfg(feature = "ethers")]
+
+use utils::crypto::sha3::keccak256;
+use walleth_identity::{
+  signer::{
+    ethers::{Address, EthersSignature, EthersSigner, LocalWallet},
+    Signable, Signature, SignatureOptions, Signer, SignerError,
+  },
+  Account, DerivationPath,
+};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+
+const MESSAGE: &[u8] = b"Hello world!";
+
+fn wallet() -> LocalWallet {
+  LocalWallet::from_bytes(&PRIVATE_KEY).unwrap()
+}
+
+fn sign(options: &SignatureOptions) -> Signature {
+  Signer::try_from(&wallet())
+    .unwrap()
+    .sign_with_options(&Signable::new(MESSAGE), options)
+}
+
+mod signer {
+  use super::*;
+
+  #[test]
+  fn it_signs_with_the_key_of_the_wallet() {
+    let options = SignatureOptions {
+      recoverable: true,
+      ..Default::default()
+    };
+
+    let signature = EthersSignature::try_from(&sign(&options)).unwrap();
+
+    assert_eq!(
+      signature.recover(keccak256(MESSAGE)).unwrap(),
+      wallet().address()
+    );
+  }
+}
+
+mod signature {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_ethers_signatures() {
+    let options = SignatureOptions {
+      recoverable: true,
+      ..Default::default()
+    };
+    let signature = sign(&options);
+
+    let converted = Signature::try_from(&EthersSignature::try_from(&signature).unwrap()).unwrap();
+
+    assert_eq!(converted, signature);
+  }
+
+  #[test]
+  fn it_reads_eip155_v_values() {
+    let options = SignatureOptions {
+      recoverable: true,
+      ..Default::default()
+    };
+    let signature = sign(&options);
+    let mut ethers_signature = EthersSignature::try_from(&signature).unwrap();
+    // EIP-155 `v` on mainnet
+    ethers_signature.v = ethers_signature.v - 27 + 37;
+
+    let converted = Signature::try_from(&ethers_signature).unwrap();
+
+    assert_eq!(converted.recovery_id(), signature.recovery_id());
+  }
+
+  #[test]
+  fn it_fails_without_recovery_id() {
+    let signature = sign(&SignatureOptions::default());
+
+    assert!(matches!(
+      EthersSignature::try_from(&signature),
+      Err(SignerError::MissingRecoveryId)
+    ));
+  }
+}
+
+mod address {
+  use super::*;
+
+  #[test]
+  fn it_converts_account_addresses() {
+    let account = Account::from_private_key(PRIVATE_KEY, DerivationPath::from(0)).unwrap();
+
+    assert_eq!(Address::try_from(&account).unwrap(), wallet().address());
+  }
+}
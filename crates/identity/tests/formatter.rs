@@ -0,0 +1,78 @@
+use walleth_identity::{
+  account::{ChecksumAddressFormatter, HexAddressFormatter, IcapAddressFormatter},
+  Account, AddressFormatter, DerivationPath,
+};
+
+fn account(address: &str) -> Account {
+  Account {
+    address: address.to_string(),
+    public_key: vec![],
+    path: DerivationPath::from(0),
+  }
+}
+
+mod hex_address_formatter {
+  use super::*;
+
+  #[test]
+  fn it_formats_lowercase_prefixed_hex() {
+    let account = account("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED");
+
+    assert_eq!(
+      account.format_address(&HexAddressFormatter).unwrap(),
+      "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+    );
+  }
+}
+
+mod checksum_address_formatter {
+  use super::*;
+
+  #[test]
+  fn it_formats_eip55_addresses() {
+    let account = account("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+
+    assert_eq!(
+      account.format_address(&ChecksumAddressFormatter).unwrap(),
+      "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    );
+  }
+
+  #[test]
+  fn it_formats_all_caps_addresses() {
+    let address = [
+      0x52, 0x90, 0x84, 0x00, 0x09, 0x85, 0x27, 0x88, 0x6E, 0x0F, 0x70, 0x30, 0x06, 0x98, 0x57,
+      0xD2, 0xE4, 0x16, 0x9E, 0xE7,
+    ];
+
+    assert_eq!(
+      ChecksumAddressFormatter.format(&address),
+      "0x52908400098527886E0F7030069857D2E4169EE7"
+    );
+  }
+}
+
+mod icap_address_formatter {
+  use super::*;
+
+  #[test]
+  fn it_formats_icap_addresses() {
+    let account = account("0x8ba1f109551bd432803012645ac136ddd64dba72");
+
+    assert_eq!(
+      account.format_address(&IcapAddressFormatter).unwrap(),
+      "XE65GB6LDNXYOFTX0NSV3FUWKOWIXAMJK36"
+    );
+  }
+}
+
+mod format_address {
+  use super::*;
+
+  #[test]
+  fn it_fails_with_an_invalid_address() {
+    let account = account("0x1234");
+
+    assert!(account.format_address(&HexAddressFormatter).is_err());
+  }
+}
@@ -0,0 +1,55 @@
+use walleth_identity::{IdentityError, IdentityFactoryRegistry};
+
+fn first_byte(bytes: &[u8]) -> Result<u8, Box<dyn IdentityError>> {
+  Ok(bytes[0])
+}
+
+fn last_byte(bytes: &[u8]) -> Result<u8, Box<dyn IdentityError>> {
+  Ok(bytes[bytes.len() - 1])
+}
+
+mod register {
+  use super::*;
+
+  #[test]
+  fn it_registers_a_deserializer() {
+    let mut registry = IdentityFactoryRegistry::new();
+
+    registry.register("first", first_byte);
+
+    assert!(registry.is_registered("first"));
+    assert!(!registry.is_registered("last"));
+  }
+}
+
+mod deserialize {
+  use super::*;
+
+  #[test]
+  fn it_uses_the_deserializer_of_the_identity_type() {
+    let mut registry = IdentityFactoryRegistry::new();
+    registry
+      .register("first", first_byte)
+      .register("last", last_byte);
+
+    assert_eq!(registry.deserialize("first", &[1, 2, 3]).unwrap(), 1);
+    assert_eq!(registry.deserialize("last", &[1, 2, 3]).unwrap(), 3);
+  }
+
+  #[test]
+  fn it_uses_the_fallback_for_unregistered_types() {
+    let mut registry = IdentityFactoryRegistry::new();
+    registry
+      .register("first", first_byte)
+      .set_fallback(last_byte);
+
+    assert_eq!(registry.deserialize("unknown", &[1, 2, 3]).unwrap(), 3);
+  }
+
+  #[test]
+  fn it_fails_for_unregistered_types_without_fallback() {
+    let registry = IdentityFactoryRegistry::<u8>::new();
+
+    assert!(registry.deserialize("unknown", &[1, 2, 3]).is_err());
+  }
+}
@@ -1,3 +1,4 @@
+use utils::{hex::encode, B256};
 use walleth_identity::signer::Signable;
 
 const MESSAGE_DIGEST: &str = "ecd0e108a98e192af1d2c25055f4e3bed784b5c877204e73219a5203251feaab";
@@ -9,7 +10,7 @@ mod new {
   fn it_creates_a_new_signable() {
     let signable = Signable::new(b"Hello world!");
     assert_eq!(
-      signable.to_signable_message().to_string(),
+      encode(&signable.to_signable_message()),
       MESSAGE_DIGEST.to_string()
     );
   }
@@ -22,7 +23,7 @@ mod from_str {
   fn it_creates_a_new_signable() {
     let signable = Signable::from_str("Hello world!");
     assert_eq!(
-      signable.to_signable_message().to_string(),
+      encode(&signable.to_signable_message()),
       MESSAGE_DIGEST.to_string()
     );
   }
@@ -35,8 +36,54 @@ mod from_bytes {
   fn it_creates_a_new_signable() {
     let signable = Signable::from_bytes(b"Hello world!");
     assert_eq!(
-      signable.to_signable_message().to_string(),
+      encode(&signable.to_signable_message()),
       MESSAGE_DIGEST.to_string()
     );
   }
 }
+
+mod from_digest {
+  use super::*;
+
+  #[test]
+  fn it_keeps_the_digest() {
+    let signable = Signable::from_digest(B256([7u8; 32]));
+    assert_eq!(signable.digest(), B256([7u8; 32]));
+  }
+}
+
+mod into_signable {
+  use walleth_identity::IntoSignable;
+
+  use super::*;
+
+  struct Order {
+    id: u8,
+  }
+
+  impl IntoSignable for Order {
+    fn to_signable(&self) -> Signable {
+      Signable::from_digest(B256([self.id; 32]))
+    }
+  }
+
+  #[test]
+  fn it_digests_bytes_and_strings_with_keccak256() {
+    for signable in [
+      b"Hello world!".to_signable(),
+      b"Hello world!".to_vec().to_signable(),
+      "Hello world!".to_signable(),
+      "Hello world!".to_string().to_signable(),
+    ] {
+      assert_eq!(
+        encode(&signable.to_signable_message()),
+        MESSAGE_DIGEST.to_string()
+      );
+    }
+  }
+
+  #[test]
+  fn it_uses_the_custom_digest_of_a_type() {
+    assert_eq!(Order { id: 1 }.to_signable().digest(), B256([1u8; 32]));
+  }
+}
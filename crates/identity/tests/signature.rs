@@ -0,0 +1,93 @@
+use walleth_identity::signer::{Signable, Signature, SignatureOptions, Signer};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+
+fn recoverable_signature() -> Signature {
+  let signer = Signer::new(PRIVATE_KEY).unwrap();
+  let options = SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  };
+
+  signer.sign_with_options(&Signable::new(b"Hello world!"), &options)
+}
+
+mod der {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_der_encoding() {
+    let signature = recoverable_signature();
+
+    let decoded = Signature::from_der(&signature.to_der()).unwrap();
+
+    assert_eq!(decoded.to_compact(), signature.to_compact());
+    assert_eq!(decoded.recovery_id(), None);
+  }
+
+  #[test]
+  fn it_fails_with_invalid_der() {
+    assert!(Signature::from_der(&[0u8; 10]).is_err());
+  }
+}
+
+mod compact {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_compact_encoding() {
+    let signature = recoverable_signature();
+
+    let decoded = Signature::from_compact(&signature.to_compact()).unwrap();
+
+    assert_eq!(decoded.to_compact(), signature.to_compact());
+  }
+
+  #[test]
+  fn it_reads_the_recovery_id_from_the_65th_byte() {
+    let signature = recoverable_signature();
+
+    let decoded = Signature::from_compact(&signature.to_rsv().unwrap()).unwrap();
+
+    assert_eq!(decoded, signature);
+  }
+
+  #[test]
+  fn it_fails_with_wrong_length() {
+    assert!(Signature::from_compact(&[1u8; 63]).is_err());
+  }
+}
+
+mod rsv {
+  use super::*;
+
+  #[test]
+  fn it_uses_ethereum_v_values() {
+    let signature = recoverable_signature();
+
+    let rsv = signature.to_rsv().unwrap();
+
+    assert!(rsv[64] == 27 || rsv[64] == 28);
+    assert_eq!(Some(rsv[64]), signature.v());
+  }
+
+  #[test]
+  fn it_round_trips_rsv_hex() {
+    let signature = recoverable_signature();
+
+    let rsv_hex = signature.to_rsv_hex().unwrap();
+
+    assert!(rsv_hex.starts_with("0x"));
+    assert_eq!(rsv_hex.len(), 132);
+    assert_eq!(Signature::from_rsv_hex(&rsv_hex).unwrap(), signature);
+  }
+
+  #[test]
+  fn it_fails_without_recovery_id() {
+    let signature = Signer::new(PRIVATE_KEY)
+      .unwrap()
+      .sign(&Signable::new(b"Hello world!"));
+
+    assert!(signature.to_rsv().is_err());
+  }
+}
@@ -0,0 +1,110 @@
+use walleth_identity::signer::{NonceStrategy, Signable, Signature, SignatureOptions, Signer};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+
+/// The order of the secp256k1 curve
+const CURVE_ORDER: [u8; 32] = [
+  0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+  0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+/// Turn a low-s compact signature into its high-s equivalent (s' = n - s)
+fn to_high_s(compact: [u8; 64]) -> [u8; 64] {
+  let mut high = compact;
+  let mut borrow = 0i16;
+  for i in (0..32).rev() {
+    let mut value = CURVE_ORDER[i] as i16 - compact[32 + i] as i16 - borrow;
+    borrow = 0;
+    if value < 0 {
+      value += 256;
+      borrow = 1;
+    }
+    high[32 + i] = value as u8;
+  }
+  high
+}
+
+mod sign_with_options {
+  use super::*;
+
+  #[test]
+  fn it_produces_deterministic_signatures() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+
+    let first = signer.sign_with_options(&signable, &SignatureOptions::default());
+    let second = signer.sign_with_options(&signable, &SignatureOptions::default());
+
+    assert_eq!(first, second);
+    assert_eq!(first, signer.sign(&signable));
+  }
+
+  #[test]
+  fn it_mixes_extra_entropy_with_hardened_nonces() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+    let options = SignatureOptions {
+      nonce: NonceStrategy::Hardened([7u8; 32]),
+      ..Default::default()
+    };
+
+    let hardened = signer.sign_with_options(&signable, &options);
+
+    assert_ne!(hardened, signer.sign(&signable));
+    assert!(signer.verify(&signable, &hardened).is_ok());
+  }
+
+  #[test]
+  fn it_returns_the_recovery_id_when_requested() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+    let options = SignatureOptions {
+      recoverable: true,
+      ..Default::default()
+    };
+
+    let signature = signer.sign_with_options(&signable, &options);
+
+    assert!(signature.recovery_id().is_some());
+    assert_eq!(signature.to_compact(), signer.sign(&signable).to_compact());
+  }
+}
+
+mod verify_with_options {
+  use super::*;
+
+  #[test]
+  fn it_verifies_a_signature() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+    let signature = signer.sign(&signable);
+
+    assert!(signer.verify(&signable, &signature).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_high_s_signatures_by_default() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+    let high_s = to_high_s(signer.sign(&signable).to_compact());
+    let signature = Signature::from_compact(&high_s).unwrap();
+
+    assert!(signer.verify(&signable, &signature).is_err());
+  }
+
+  #[test]
+  fn it_normalizes_high_s_signatures_when_allowed() {
+    let signer = Signer::new(PRIVATE_KEY).unwrap();
+    let signable = Signable::new(b"Hello world!");
+    let high_s = to_high_s(signer.sign(&signable).to_compact());
+    let signature = Signature::from_compact(&high_s).unwrap();
+    let options = SignatureOptions {
+      low_s: false,
+      ..Default::default()
+    };
+
+    assert!(signer
+      .verify_with_options(&signable, &signature, &options)
+      .is_ok());
+  }
+}
@@ -0,0 +1,109 @@
+use walleth_identity::{
+  signer::{Signable, Signature, SignatureOptions, Signer, SignerError},
+  verify_address, verify_with_public_key, Account,
+};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+const OTHER_PRIVATE_KEY: [u8; 32] = [2u8; 32];
+
+fn sign(private_key: [u8; 32], message: &[u8], recoverable: bool) -> Signature {
+  let options = SignatureOptions {
+    recoverable,
+    ..Default::default()
+  };
+
+  Signer::new(private_key)
+    .unwrap()
+    .sign_with_options(&Signable::from_bytes(message), &options)
+}
+
+mod verify_address {
+  use super::*;
+
+  #[test]
+  fn it_verifies_a_signature_against_the_signer_address() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", true);
+
+    assert!(verify_address(&account.address, b"Hello world!", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_ignores_address_case_and_prefix() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", true);
+    let address = account.address[2..].to_uppercase();
+
+    assert!(verify_address(&address, b"Hello world!", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_another_address() {
+    let other = Account::from_private_key(OTHER_PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", true);
+
+    assert!(matches!(
+      verify_address(&other.address, b"Hello world!", &signature),
+      Err(SignerError::AddressMismatch)
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_another_message() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", true);
+
+    assert!(verify_address(&account.address, b"Goodbye world!", &signature).is_err());
+  }
+
+  #[test]
+  fn it_fails_without_recovery_id() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", false);
+
+    assert!(matches!(
+      verify_address(&account.address, b"Hello world!", &signature),
+      Err(SignerError::MissingRecoveryId)
+    ));
+  }
+}
+
+mod verify_with_public_key {
+  use super::*;
+
+  #[test]
+  fn it_verifies_a_signature_with_a_compressed_public_key() {
+    let account = Account::from_private_key(PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", false);
+
+    assert!(verify_with_public_key(&account.public_key, b"Hello world!", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_verifies_a_signature_with_an_uncompressed_public_key() {
+    let public_key = sign(PRIVATE_KEY, b"Hello world!", true)
+      .recover(&Signable::from_bytes(b"Hello world!"))
+      .unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", false);
+
+    assert!(verify_with_public_key(&public_key, b"Hello world!", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_another_public_key() {
+    let other = Account::from_private_key(OTHER_PRIVATE_KEY, 0).unwrap();
+    let signature = sign(PRIVATE_KEY, b"Hello world!", false);
+
+    assert!(verify_with_public_key(&other.public_key, b"Hello world!", &signature).is_err());
+  }
+
+  #[test]
+  fn it_fails_with_an_invalid_public_key() {
+    let signature = sign(PRIVATE_KEY, b"Hello world!", false);
+
+    assert!(matches!(
+      verify_with_public_key(&[1u8; 33], b"Hello world!", &signature),
+      Err(SignerError::InvalidPublicKey)
+    ));
+  }
+}
@@ -0,0 +1,110 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hdkey::{test_vectors::ABANDON_MNEMONIC, HDKey};
+use identity::{signer::SignatureOptions, DerivationPath, MultiKeyPair};
+use utils::Controller;
+use vault::KdfParams;
+use walleth_keychain::Keychain;
+
+/// Create a keychain with one HD wallet and `accounts` accounts
+fn keychain(accounts: usize, kdf: KdfParams) -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.set_kdf_params(kdf);
+  keychain
+    .add_multi_keypair(hdkey::hdkey_factory, Some(ABANDON_MNEMONIC.to_string()))
+    .unwrap();
+  keychain.derive_range(0, 0, accounts).unwrap();
+
+  keychain
+}
+
+fn derivation(c: &mut Criterion) {
+  let hdkey = HDKey::from_mnemonic_str(ABANDON_MNEMONIC).unwrap();
+  let mut group = c.benchmark_group("derivation");
+
+  group.bench_function("private_key_at", |b| {
+    b.iter(|| hdkey.private_key_at(black_box(DerivationPath::from(7))))
+  });
+  for count in [1usize, 10, 50] {
+    let paths: Vec<DerivationPath> = (0..count).map(DerivationPath::from).collect();
+    group.bench_with_input(
+      BenchmarkId::new("private_keys_at", count),
+      &paths,
+      |b, paths| b.iter(|| hdkey.private_keys_at(black_box(paths))),
+    );
+  }
+
+  group.finish();
+}
+
+fn lock_unlock(c: &mut Criterion) {
+  let mut group = c.benchmark_group("lock_unlock");
+  group.sample_size(10);
+
+  for rounds in [KdfParams::DEFAULT_ROUNDS, 10_000, 100_000] {
+    let mut keychain = keychain(5, KdfParams::new(rounds));
+    group.bench_with_input(BenchmarkId::new("rounds", rounds), &rounds, |b, _| {
+      b.iter(|| {
+        keychain.lock("password").unwrap();
+        keychain.unlock("password").unwrap();
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn signing(c: &mut Criterion) {
+  let mut keychain = keychain(1, KdfParams::default());
+  let address = keychain.get_state().accounts()[0].address.clone();
+  let options = SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  };
+  let mut group = c.benchmark_group("signing");
+
+  group.bench_function("use_signer", |b| {
+    b.iter(|| {
+      keychain
+        .use_signer(address.clone(), black_box(b"Hello world!"), &options)
+        .unwrap()
+    })
+  });
+
+  let pool = keychain
+    .start_signing_pool(std::slice::from_ref(&address))
+    .unwrap();
+  group.bench_function("signing_pool", |b| {
+    b.iter(|| {
+      pool
+        .sign(&address, black_box(b"Hello world!"), &options)
+        .unwrap()
+    })
+  });
+  keychain.stop_signing_pool();
+
+  group.finish();
+}
+
+fn backup_restore(c: &mut Criterion) {
+  let mut group = c.benchmark_group("backup_restore");
+  group.sample_size(10);
+
+  for accounts in [1usize, 20] {
+    let mut keychain = keychain(accounts, KdfParams::default());
+    group.bench_with_input(BenchmarkId::new("backup", accounts), &accounts, |b, _| {
+      b.iter(|| keychain.backup("password").unwrap())
+    });
+
+    let backup = keychain.backup("password").unwrap();
+    group.bench_with_input(
+      BenchmarkId::new("restore", accounts),
+      &backup,
+      |b, backup| b.iter(|| Keychain::<HDKey>::restore(backup.clone(), "password").unwrap()),
+    );
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, derivation, lock_unlock, signing, backup_restore);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+use std::fmt::Display;
+
+use bip39::{Language, Mnemonic};
+
+/// The numbers of words of the mnemonic phrases accepted by `HDKey`,
+/// encoding from 128 to 256 bits of entropy
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// The maximum number of suggestions given for an unknown word
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The maximum number of edits between an unknown word and a suggestion
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// A word of a mnemonic phrase missing from the BIP-39 English wordlist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownWord {
+  /// The position of the word in the phrase, starting from 0
+  pub index: usize,
+  /// The word, as typed
+  pub word: String,
+  /// The closest words of the wordlist, closest first
+  pub suggestions: Vec<&'static str>,
+}
+
+/// Why a mnemonic phrase was rejected, to guide users through
+/// fixing typos while recovering a wallet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MnemonicDiagnostic {
+  /// Some words are not in the wordlist
+  UnknownWords(Vec<UnknownWord>),
+  /// All words are in the wordlist, but their number is not one of `MNEMONIC_WORD_COUNTS`
+  InvalidWordCount(usize),
+  /// All words are in the wordlist, but the checksum does not match:
+  /// a word is misplaced or was replaced by another valid word
+  InvalidChecksum,
+}
+
+impl Display for MnemonicDiagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::UnknownWords(words) => write!(
+        f,
+        "Unknown words at positions {}",
+        words
+          .iter()
+          .map(|word| (word.index + 1).to_string())
+          .collect::<Vec<String>>()
+          .join(", ")
+      ),
+      Self::InvalidWordCount(count) => write!(f, "Invalid number of words: {}", count),
+      Self::InvalidChecksum => write!(f, "Invalid mnemonic checksum"),
+    }
+  }
+}
+
+/// Check a mnemonic phrase against the BIP-39 English wordlist and
+/// checksum, returning why it is invalid, or `None` if it is valid
+pub fn diagnose_mnemonic(phrase: &str) -> Option<MnemonicDiagnostic> {
+  let words = phrase
+    .split_whitespace()
+    .map(str::to_lowercase)
+    .collect::<Vec<String>>();
+
+  let unknown_words = words
+    .iter()
+    .enumerate()
+    .filter(|(_, word)| Language::English.find_word(word).is_none())
+    .map(|(index, word)| UnknownWord {
+      index,
+      word: word.clone(),
+      suggestions: suggest_words(word),
+    })
+    .collect::<Vec<UnknownWord>>();
+
+  if !unknown_words.is_empty() {
+    return Some(MnemonicDiagnostic::UnknownWords(unknown_words));
+  }
+
+  if !MNEMONIC_WORD_COUNTS.contains(&words.len()) {
+    return Some(MnemonicDiagnostic::InvalidWordCount(words.len()));
+  }
+
+  Mnemonic::parse_in_normalized(Language::English, &words.join(" "))
+    .err()
+    .map(|_| MnemonicDiagnostic::InvalidChecksum)
+}
+
+/// Get the words of the wordlist closest to `word`. Words sharing its
+/// first four letters come first, as they identify a BIP-39 word
+fn suggest_words(word: &str) -> Vec<&'static str> {
+  let prefix = word.chars().take(4).collect::<String>();
+
+  let mut candidates = Language::English
+    .word_list()
+    .iter()
+    .map(|candidate| {
+      let shares_prefix = prefix.chars().count() == 4 && candidate.starts_with(&prefix);
+      (!shares_prefix, edit_distance(word, candidate), *candidate)
+    })
+    .filter(|(other_prefix, distance, _)| !other_prefix || *distance <= MAX_SUGGESTION_DISTANCE)
+    .collect::<Vec<(bool, usize, &'static str)>>();
+  candidates.sort();
+
+  candidates
+    .into_iter()
+    .take(MAX_SUGGESTIONS)
+    .map(|(_, _, candidate)| candidate)
+    .collect()
+}
+
+/// Get the Levenshtein distance between two words
+fn edit_distance(first: &str, second: &str) -> usize {
+  let second = second.chars().collect::<Vec<char>>();
+  let mut previous = (0..=second.len()).collect::<Vec<usize>>();
+
+  for (i, first_char) in first.chars().enumerate() {
+    let mut current = vec![i + 1];
+    for (j, second_char) in second.iter().enumerate() {
+      let substitution = previous[j] + usize::from(first_char != *second_char);
+      current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+    }
+    previous = current;
+  }
+
+  previous[second.len()]
+}
@@ -2,11 +2,14 @@ use std::fmt::Display;
 
 use identity::{AccountError, IdentityError, SignerError};
 
+use crate::MnemonicDiagnostic;
+
 #[derive(Debug)]
 pub enum HDKeyError {
   GenericError,
   WrongDerivationPath,
   InvalidMnemonic,
+  MnemonicDiagnostic(MnemonicDiagnostic),
   InvalidSignature,
   InvalidPrivateKey,
 }
@@ -18,6 +21,7 @@ impl Display for HDKeyError {
       Self::InvalidSignature => write!(f, "Invalid signature"),
       Self::InvalidPrivateKey => write!(f, "Invalid private key"),
       Self::InvalidMnemonic => write!(f, "Invalid mnemonic"),
+      Self::MnemonicDiagnostic(diagnostic) => write!(f, "Invalid mnemonic: {}", diagnostic),
       Self::GenericError => write!(f, "Generic error"),
     }
   }
@@ -41,9 +45,9 @@ impl From<SignerError> for HDKeyError {
   }
 }
 
-impl Into<Box<dyn IdentityError>> for HDKeyError {
-  fn into(self) -> Box<dyn IdentityError> {
-    Box::new(self)
+impl From<HDKeyError> for Box<dyn IdentityError> {
+  fn from(error: HDKeyError) -> Self {
+    Box::new(error)
   }
 }
 
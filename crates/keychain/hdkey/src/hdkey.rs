@@ -1,56 +1,86 @@
-use bip32::XPrv;
-use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use std::collections::HashMap;
+
+use bip32::{ChildNumber, XPrv};
 
 use crate::{
-  utils::{generate_seed_bytes, get_derivation_path, parse_mnemonic},
-  HDKeyError,
+  diagnose_mnemonic,
+  utils::{
+    generate_seed_bytes, generate_seed_bytes_with_entropy, get_derivation_path,
+    get_parent_derivation_path, parse_mnemonic,
+  },
+  HDKeyError, HDKeyTree,
 };
 use identity::{
-  signer::{Signable, Signer},
-  Account, AccountDeriver, GenericIdentity, IdentityError, Initializable, MultiKeyPair,
+  signer::{IntoSignable, Signature, SignatureOptions, Signer},
+  Account, AccountDeriver, DerivationPath, GenericIdentity, IdentityError, Initializable,
+  MultiKeyPair,
+};
+use utils::{PublicKeyBytes, SecureBytes};
+use walleth_core::{
+  derivation::fingerprint, derive_private_key, Backend, EntropySource, SigningBackend,
 };
 
 #[derive(Clone, Debug)]
 pub struct HDKey {
-  seed: Vec<u8>,
+  seed: SecureBytes,
 }
 
 impl HDKey {
   /// Create a new `HDKey` from a mnemonic phrase
   pub fn from_mnemonic_str(mnemonic: &str) -> Result<Self, Box<dyn IdentityError>> {
-    let seed = parse_mnemonic(mnemonic.to_string())
-      .or(Err(HDKeyError::InvalidMnemonic.into()))?
-      .to_seed("");
+    Ok(Self::from_mnemonic(mnemonic)?)
+  }
 
-    Ok(HDKey {
-      seed: seed.as_bytes().to_vec(),
-    })
+  /// Create a new `HDKey` from a mnemonic phrase. When the phrase is
+  /// invalid, the error tells the unknown words and their closest
+  /// matches apart from a wrong word count or checksum
+  pub fn from_mnemonic(mnemonic: &str) -> Result<Self, HDKeyError> {
+    let seed = parse_mnemonic(mnemonic).map_err(|_| match diagnose_mnemonic(mnemonic) {
+      Some(diagnostic) => HDKeyError::MnemonicDiagnostic(diagnostic),
+      None => HDKeyError::InvalidMnemonic,
+    })?;
+
+    Ok(HDKey { seed })
   }
 
   /// Get the keypair at a derivation path
   pub fn keypair_at_path(
     &self,
-    account: usize,
-    change: usize,
-    index: usize,
-  ) -> Result<(SecretKey, PublicKey), String> {
-    let secp = Secp256k1::new();
-    let derived_pvk =
-      XPrv::derive_from_path(&self.seed, &get_derivation_path(account, change, index)?)
-        .or(Err("Invalid derivation path"))?;
+    path: &DerivationPath,
+  ) -> Result<(SecureBytes, PublicKeyBytes), String> {
+    let mut derived_pvk =
+      derive_private_key(&self.seed, &path.to_string()).or(Err("Invalid derivation path"))?;
 
-    let private_key = SecretKey::from_slice(&derived_pvk.private_key().to_bytes())
-      .or(Err("Invalid private key"))?;
+    let public_key =
+      Backend::from_private_key(&derived_pvk).map(|signer| signer.public_key_bytes());
+    let private_key = SecureBytes::new(&derived_pvk);
+    derived_pvk.fill(0);
 
-    let public_key = private_key.public_key(&secp);
+    Ok((
+      private_key,
+      PublicKeyBytes(public_key.or(Err("Invalid private key"))?),
+    ))
+  }
 
-    Ok((private_key, public_key))
+  /// Create a new `HDKey` from a random seed, drawing its
+  /// entropy from the passed source instead of the OS RNG
+  pub fn new_with_entropy<E: EntropySource>(entropy: &mut E) -> Self {
+    HDKey {
+      seed: SecureBytes::from(generate_seed_bytes_with_entropy(entropy)),
+    }
   }
 
   /// Get the seed as a slice of bytes
   pub fn to_bytes(&self) -> &[u8] {
     &self.seed
   }
+
+  /// Get an explorer of the full BIP-32 tree of the key, to derive
+  /// extended keys at arbitrary paths. The tree caches the intermediate
+  /// nodes it derives, for as long as it is kept
+  pub fn tree(&self) -> HDKeyTree<'_> {
+    HDKeyTree::new(self)
+  }
 }
 
 impl TryFrom<Vec<u8>> for HDKey {
@@ -58,14 +88,16 @@ impl TryFrom<Vec<u8>> for HDKey {
 
   /// Create a new `HDKey` from a seed as slice of bytes
   fn try_from(seed: Vec<u8>) -> Result<Self, HDKeyError> {
-    Ok(HDKey { seed: seed.into() })
+    Ok(HDKey {
+      seed: SecureBytes::from(seed),
+    })
   }
 }
 
-impl Into<Vec<u8>> for HDKey {
+impl From<HDKey> for Vec<u8> {
   /// Get the seed as a slice of bytes
-  fn into(self) -> Vec<u8> {
-    self.seed.clone()
+  fn from(hdkey: HDKey) -> Vec<u8> {
+    hdkey.seed.to_vec()
   }
 }
 
@@ -73,7 +105,7 @@ impl From<&[u8]> for HDKey {
   /// Create a new `HDKey` from a seed as slice of bytes
   fn from(seed: &[u8]) -> Self {
     HDKey {
-      seed: seed.to_vec(),
+      seed: SecureBytes::new(seed),
     }
   }
 }
@@ -83,12 +115,17 @@ impl GenericIdentity for HDKey {
     "HDKey".to_string()
   }
 
+  /// The BIP-32 fingerprint of the master extended public key
+  fn fingerprint(&self) -> [u8; 4] {
+    fingerprint(&self.seed).unwrap_or_default()
+  }
+
   fn serialize(&self) -> Vec<u8> {
-    self.seed.clone()
+    self.seed.to_vec()
   }
 
   fn deserialize(&mut self, bytes: &[u8]) -> Result<(), Box<dyn IdentityError>> {
-    self.seed = bytes.to_vec();
+    self.seed = SecureBytes::new(bytes);
     Ok(())
   }
 }
@@ -97,79 +134,109 @@ impl Initializable for HDKey {
   /// Create a new `HDKey` from a random seed
   fn new() -> Self {
     HDKey {
-      seed: generate_seed_bytes(),
+      seed: SecureBytes::from(generate_seed_bytes()),
     }
   }
 }
 
-impl AccountDeriver<usize> for HDKey {
+impl AccountDeriver<DerivationPath> for HDKey {
   /// Get an account of the hdkey
-  fn account_at(&self, index: usize) -> Result<Account<usize>, Box<dyn IdentityError>> {
-    let (_, public_key) = match self.keypair_at_path(0, 0, index) {
+  fn account_at(&self, path: DerivationPath) -> Result<Account, Box<dyn IdentityError>> {
+    let (_, public_key) = match self.keypair_at_path(&path) {
       Ok(keypair) => keypair,
       Err(_) => return Err(HDKeyError::WrongDerivationPath.into()),
     };
 
-    match Account::from_public_key(&public_key, index) {
+    match Account::from_public_key(&public_key.0, path) {
       Ok(account) => Ok(account),
       Err(_) => Err(HDKeyError::WrongDerivationPath.into()),
     }
   }
 }
 
-impl MultiKeyPair<[u8; 32], [u8; 33], usize> for HDKey {
+impl MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> for HDKey {
   /// Get the private key at a derivation path
-  fn private_key_at(&self, index: usize) -> Result<[u8; 32], Box<dyn IdentityError>> {
-    let derivation_path = match get_derivation_path(0, 0, index) {
-      Ok(derivation_path) => derivation_path,
-      Err(_) => return Err(HDKeyError::WrongDerivationPath.into()),
-    };
-
-    match XPrv::derive_from_path(&self.seed, &derivation_path) {
-      Ok(private_key) => Ok(private_key.to_bytes().into()),
+  fn private_key_at(&self, path: DerivationPath) -> Result<[u8; 32], Box<dyn IdentityError>> {
+    match derive_private_key(&self.seed, &path.to_string()) {
+      Ok(private_key) => Ok(private_key),
       Err(_) => Err(HDKeyError::WrongDerivationPath.into()),
     }
   }
 
   /// Get the public key at a derivation path
-  fn public_key_at(&self, index: usize) -> Result<[u8; 33], Box<dyn IdentityError>> {
-    let derivation_path = match get_derivation_path(0, 0, index) {
+  fn public_key_at(&self, path: DerivationPath) -> Result<PublicKeyBytes, Box<dyn IdentityError>> {
+    let derivation_path = match get_derivation_path(&path) {
       Ok(derivation_path) => derivation_path,
       Err(_) => return Err(HDKeyError::WrongDerivationPath.into()),
     };
 
     match XPrv::derive_from_path(&self.seed, &derivation_path) {
-      Ok(private_key) => Ok(private_key.public_key().to_bytes().into()),
+      Ok(private_key) => Ok(PublicKeyBytes(private_key.public_key().to_bytes())),
       Err(_) => Err(Box::new(HDKeyError::WrongDerivationPath)),
     }
   }
 
+  /// Get the private keys at multiple derivation paths, deriving
+  /// the parent extended key only once for paths sharing it
+  fn private_keys_at(
+    &self,
+    paths: &[DerivationPath],
+  ) -> Result<Vec<[u8; 32]>, Box<dyn IdentityError>> {
+    let mut parents: HashMap<(usize, usize), XPrv> = HashMap::new();
+
+    paths
+      .iter()
+      .map(|path| {
+        let parent = match parents.get(&(path.account, path.change)) {
+          Some(parent) => parent.clone(),
+          None => {
+            let derivation_path =
+              get_parent_derivation_path(path).or(Err(HDKeyError::WrongDerivationPath))?;
+            let parent = XPrv::derive_from_path(&self.seed, &derivation_path)
+              .or(Err(HDKeyError::WrongDerivationPath))?;
+            parents.insert((path.account, path.change), parent.clone());
+            parent
+          }
+        };
+
+        let index = u32::try_from(path.index).or(Err(HDKeyError::WrongDerivationPath))?;
+        let child_number =
+          ChildNumber::new(index, false).or(Err(HDKeyError::WrongDerivationPath))?;
+
+        match parent.derive_child(child_number) {
+          Ok(private_key) => Ok(private_key.to_bytes()),
+          Err(_) => Err(HDKeyError::WrongDerivationPath.into()),
+        }
+      })
+      .collect()
+  }
+
   /// Sign a message with the hdkey
-  fn sign(&self, from: &Account<usize>, message: &[u8]) -> Result<Vec<u8>, Box<dyn IdentityError>> {
+  fn sign(
+    &self,
+    from: &Account,
+    message: &dyn IntoSignable,
+    options: &SignatureOptions,
+  ) -> Result<Signature, Box<dyn IdentityError>> {
     let private_key = self.private_key_at(from.path)?;
-    let signer = Signer::new(private_key).or(Err(HDKeyError::InvalidPrivateKey.into()))?;
-    let signable = Signable::from_bytes(message);
-
-    let signature = signer.sign(&signable);
+    let signer = Signer::new(private_key).or(Err(HDKeyError::InvalidPrivateKey))?;
 
-    Ok(signature.serialize_der().to_vec())
+    Ok(signer.sign_with_options(&message.to_signable(), options))
   }
 
   /// Verify a signature with the hdkey
   fn verify(
     &self,
-    from: &Account<usize>,
-    message: &[u8],
-    signature: &[u8],
+    from: &Account,
+    message: &dyn IntoSignable,
+    signature: &Signature,
   ) -> Result<(), Box<dyn IdentityError>> {
     let private_key = self.private_key_at(from.path)?;
-    let signer = Signer::new(private_key).or(Err(HDKeyError::InvalidPrivateKey.into()))?;
+    let signer = Signer::new(private_key).or(Err(HDKeyError::InvalidPrivateKey))?;
 
-    Ok(
-      signer
-        .verify(&Signable::from_bytes(message), signature)
-        .or(Err(HDKeyError::InvalidSignature.into()))?,
-    )
+    signer
+      .verify(&message.to_signable(), signature)
+      .or(Err(HDKeyError::InvalidSignature.into()))
   }
 }
 
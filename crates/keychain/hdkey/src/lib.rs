@@ -4,8 +4,19 @@ pub use hdkey::HDKey;
 pub mod factory;
 pub use factory::hdkey_factory;
 
+pub mod tree;
+pub use tree::HDKeyTree;
+
+pub mod diagnostics;
+pub use diagnostics::*;
+
 pub mod errors;
 pub use errors::*;
 
 pub mod utils;
+pub use bip32::{self, Prefix, XPrv, XPub};
 pub use utils::*;
+pub use walleth_core::EntropySource;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
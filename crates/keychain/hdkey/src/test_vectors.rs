@@ -0,0 +1,109 @@
+//! Fixed-seed fixtures with the keys, addresses and signatures expected
+//! from BIP-39 / BIP-44 derivation, matching MetaMask and ethers.
+//!
+//! They are public knowledge and must never be used to hold funds.
+
+use identity::{DerivationPath, IdentityError};
+use utils::hex::decode;
+
+use crate::{HDKey, HDKeyError};
+
+/// The 12 words mnemonic used by Hardhat and Foundry development nodes
+pub const HARDHAT_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+/// The BIP-39 seed of `HARDHAT_MNEMONIC`, with an empty passphrase
+pub const HARDHAT_SEED: &str = "9dfc3c64c2f8bede1533b6a79f8570e5943e0b8fd1cf77107adf7b72cef42185d564a3aee24cab43f80e3c4538087d70fc824eabbad596a23c97b6ee8322ccc0";
+
+/// The 24 words mnemonic of an all-zero entropy
+pub const ABANDON_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+/// The BIP-39 seed of `ABANDON_MNEMONIC`, with an empty passphrase
+pub const ABANDON_SEED: &str = "408b285c123836004f4b8842c89324c1f01382450c0d439af345ba7fc49acf705489c6fc77dbd4e3dc1dd8cc6bc9f043db8ada1e243c4a0eafb290d399480840";
+
+/// The message signed in the fixtures signatures
+pub const SIGNED_MESSAGE: &[u8] = b"Hello world!";
+
+/// The expected derivation of an account from a mnemonic
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestVector {
+  /// The mnemonic phrase
+  pub mnemonic: &'static str,
+  /// The hex encoded BIP-39 seed of the mnemonic, with an empty passphrase
+  pub seed: &'static str,
+  /// The address index, under m/44'/60'/0'/0
+  pub index: usize,
+  /// The hex encoded private key
+  pub private_key: &'static str,
+  /// The hex encoded compressed public key
+  pub public_key: &'static str,
+  /// The EIP-55 checksummed address
+  pub address: &'static str,
+  /// The RSV signature of `SIGNED_MESSAGE`, with deterministic nonce
+  pub signature: &'static str,
+}
+
+impl TestVector {
+  /// Get the derivation path of the vector
+  pub fn path(&self) -> DerivationPath {
+    DerivationPath::from(self.index)
+  }
+
+  /// Create the `HDKey` of the vector from its seed.
+  ///
+  /// The seed is used instead of the mnemonic so that the vector
+  /// checks the derivation independently from the mnemonic parsing
+  pub fn hdkey(&self) -> Result<HDKey, Box<dyn IdentityError>> {
+    let seed = decode(self.seed).or(Err(HDKeyError::GenericError))?;
+
+    Ok(HDKey::from(seed.as_slice()))
+  }
+}
+
+/// Known derivations of the fixture mnemonics
+pub const TEST_VECTORS: &[TestVector] = &[
+  TestVector {
+    mnemonic: HARDHAT_MNEMONIC,
+    seed: HARDHAT_SEED,
+    index: 0,
+    private_key: "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    public_key: "038318535b54105d4a7aae60c08fc45f9687181b4fdfc625bd1a753fa7397fed75",
+    address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+    signature: "0xb6be54465f5bccdc6d77e23c021585aace52b8b8488065bb7c4901ac52f13e591295a459217f8ae777c9fca493001e607c0902faff1c1b163f74bbf02e6ffb4a1c",
+  },
+  TestVector {
+    mnemonic: HARDHAT_MNEMONIC,
+    seed: HARDHAT_SEED,
+    index: 1,
+    private_key: "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    public_key: "02ba5734d8f7091719471e7f7ed6b9df170dc70cc661ca05e688601ad984f068b0",
+    address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+    signature: "0xbb076f44c0294a93315078660732afe1b9a5e8ef6d0bd9f1f6deb23b11e80d9e3d7017d03ac7b37840a75f9c69b37bfee24221d4d8a2fd7373ccc268a632c8f31c",
+  },
+  TestVector {
+    mnemonic: HARDHAT_MNEMONIC,
+    seed: HARDHAT_SEED,
+    index: 2,
+    private_key: "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a",
+    public_key: "039d9031e97dd78ff8c15aa86939de9b1e791066a0224e331bc962a2099a7b1f04",
+    address: "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC",
+    signature: "0x9c8bf1583115faf813d528f640fe32b33426886b25723fb4fd65321ee69f38ef211267f72b73d76246619110f44368b383d5530b987db382549dc347e5bf7c651c",
+  },
+  TestVector {
+    mnemonic: ABANDON_MNEMONIC,
+    seed: ABANDON_SEED,
+    index: 0,
+    private_key: "1053fae1b3ac64f178bcc21026fd06a3f4544ec2f35338b001f02d1d8efa3d5f",
+    public_key: "02dc286c821c7490afbe20a79d13123b9f41f3d7ef21e4a9caacd22f5983b28eca",
+    address: "0xF278cF59F82eDcf871d630F28EcC8056f25C1cdb",
+    signature: "0x396b5862129777c8a10d45b3fa3c35a67e043b033d35a1263248ce3407ff3caa211410ef73a860174d04a219411ba659b4dca26ee23c1367e2b7a0310acc5a2e1b",
+  },
+  TestVector {
+    mnemonic: ABANDON_MNEMONIC,
+    seed: ABANDON_SEED,
+    index: 1,
+    private_key: "0855b75d03a8830e390b5483d81694c9c7121d971e092145cf8b9c6fa3a5b373",
+    public_key: "03efd4cb0d8102293499b4f79d73d8343faa44d0e3fc14537872b437c8ec527a0c",
+    address: "0xf785bD075874b8423D3583728a981399f31e95aA",
+    signature: "0x89702785248606417b3a6aa895e40021c8b55e7ef00b781d2b472ce05f66b20521670cd8974688fec910085694e395538e46c06786515b695cae21d990d52dcf1c",
+  },
+];
@@ -0,0 +1,69 @@
+use std::{collections::BTreeMap, fmt::Debug};
+
+use bip32::{ChildNumber, DerivationPath, XPrv, XPub};
+
+use crate::{HDKey, HDKeyError};
+
+/// An explorer of the full BIP-32 tree of an `HDKey`, deriving extended
+/// keys at arbitrary paths rather than only at the BIP-44 Ethereum leaves.
+///
+/// The intermediate nodes are cached, so that exploring siblings derives
+/// their shared ancestors only once: keep the same tree around to keep
+/// its cache, as `HDKey::tree` starts a new one. The cached keys are
+/// zeroed when the tree is dropped
+pub struct HDKeyTree<'a> {
+  hdkey: &'a HDKey,
+  nodes: BTreeMap<Vec<ChildNumber>, XPrv>,
+}
+
+impl<'a> HDKeyTree<'a> {
+  /// Create an explorer of the tree of `hdkey`, with no cached node
+  pub fn new(hdkey: &'a HDKey) -> Self {
+    Self {
+      hdkey,
+      nodes: BTreeMap::new(),
+    }
+  }
+
+  /// Get the extended private key at `path`, like `m/44'/0'/0'/1`
+  pub fn derive_xprv(&mut self, path: &DerivationPath) -> Result<XPrv, HDKeyError> {
+    let children = path.iter().collect::<Vec<ChildNumber>>();
+
+    let cached = (0..=children.len())
+      .rev()
+      .find(|depth| *depth == 0 || self.nodes.contains_key(&children[..*depth]))
+      .unwrap_or_default();
+    let mut node = match cached {
+      0 => XPrv::new(self.hdkey.to_bytes()).or(Err(HDKeyError::WrongDerivationPath))?,
+      depth => self.nodes[&children[..depth]].clone(),
+    };
+
+    for depth in cached..children.len() {
+      node = node
+        .derive_child(children[depth])
+        .or(Err(HDKeyError::WrongDerivationPath))?;
+      self.nodes.insert(children[..=depth].to_vec(), node.clone());
+    }
+
+    Ok(node)
+  }
+
+  /// Get the extended public key at `path`, like `m/44'/60'/0'`
+  pub fn derive_xpub(&mut self, path: &DerivationPath) -> Result<XPub, HDKeyError> {
+    Ok(self.derive_xprv(path)?.public_key())
+  }
+
+  /// Get the number of cached nodes
+  pub fn cached_nodes(&self) -> usize {
+    self.nodes.len()
+  }
+}
+
+impl Debug for HDKeyTree<'_> {
+  /// The cached keys are never printed
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HDKeyTree")
+      .field("cached_nodes", &self.nodes.len())
+      .finish()
+  }
+}
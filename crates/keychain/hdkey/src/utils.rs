@@ -1,10 +1,26 @@
 use bip32::{DerivationPath, Language, Mnemonic, Seed};
+use identity::DerivationPath as AccountDerivationPath;
 use rand_core::OsRng;
+use utils::SecureBytes;
+use walleth_core::EntropySource;
 
 /// Generate a new mnemonic phrase
 /// with 12 words and in English
 pub fn generate_english_mnemonic() -> Mnemonic {
-  Mnemonic::random(&mut OsRng, Language::English)
+  generate_english_mnemonic_with_entropy(&mut OsRng)
+}
+
+/// Generate a new mnemonic phrase in English,
+/// drawing its entropy from the passed source
+pub fn generate_english_mnemonic_with_entropy<E: EntropySource>(entropy: &mut E) -> Mnemonic {
+  Mnemonic::random(entropy, Language::English)
+}
+
+/// Get the english mnemonic phrase encoding 32 bytes of `entropy`
+pub fn mnemonic_from_entropy(entropy: [u8; 32]) -> String {
+  Mnemonic::from_entropy(entropy, Language::English)
+    .phrase()
+    .to_string()
 }
 
 /// Generate a new seed from a random english mnemonic phrase
@@ -17,26 +33,44 @@ pub fn generate_seed() -> Seed {
 /// with an empty password
 /// and return it as a vector of bytes
 pub fn generate_seed_bytes() -> Vec<u8> {
-  generate_english_mnemonic().to_seed("").as_bytes().to_vec()
+  generate_seed_bytes_with_entropy(&mut OsRng)
+}
+
+/// Generate a new seed from a mnemonic phrase drawn from the
+/// passed entropy source, with an empty password,
+/// and return it as a vector of bytes
+pub fn generate_seed_bytes_with_entropy<E: EntropySource>(entropy: &mut E) -> Vec<u8> {
+  generate_english_mnemonic_with_entropy(entropy)
+    .to_seed("")
+    .as_bytes()
+    .to_vec()
 }
 
-/// Parse a mnemonic phrase
-/// and return it as a `Mnemonic`
-pub fn parse_mnemonic(phrase: String) -> Result<Mnemonic, String> {
-  match Mnemonic::new(phrase, Default::default()) {
-    Ok(mnemonic) => Ok(mnemonic),
+/// Parse an English mnemonic phrase of 12, 15, 18, 21 or 24 words
+/// and return its seed, derived with an empty password
+pub fn parse_mnemonic(phrase: &str) -> Result<SecureBytes, String> {
+  match bip39::Mnemonic::parse_in(bip39::Language::English, phrase) {
+    Ok(mnemonic) => Ok(SecureBytes::new(&mnemonic.to_seed(""))),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+/// Get the BIP-32 `DerivationPath` of the parent of an account
+/// derivation path, shared by all the addresses of the same
+/// account and change levels
+pub fn get_parent_derivation_path(path: &AccountDerivationPath) -> Result<DerivationPath, String> {
+  let path = path.to_string();
+  let (parent, _) = path.rsplit_once('/').ok_or("Invalid derivation path")?;
+
+  match parent.parse() {
+    Ok(path) => Ok(path),
     Err(e) => Err(e.to_string()),
   }
 }
 
-/// Get a derivation path from an account, change and index
-/// and return it as a `DerivationPath`
-pub fn get_derivation_path(
-  account: usize,
-  change: usize,
-  index: usize,
-) -> Result<DerivationPath, String> {
-  match format!("m/44'/60'/{}'/{}/{}", account, change, index).parse() {
+/// Get a BIP-32 `DerivationPath` from an account derivation path
+pub fn get_derivation_path(path: &AccountDerivationPath) -> Result<DerivationPath, String> {
+  match path.to_string().parse() {
     Ok(path) => Ok(path),
     Err(e) => Err(e.to_string()),
   }
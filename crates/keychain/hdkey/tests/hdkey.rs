@@ -0,0 +1,204 @@
+use identity::{DerivationPath, MultiKeyPair};
+use rand_core::{CryptoRng, RngCore};
+use walleth_keychain_hdkey::{generate_english_mnemonic_with_entropy, HDKey};
+
+/// A deterministic entropy source filling bytes with an incrementing counter
+struct CounterRng(u8);
+
+impl RngCore for CounterRng {
+  fn next_u32(&mut self) -> u32 {
+    rand_core::impls::next_u32_via_fill(self)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    rand_core::impls::next_u64_via_fill(self)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for byte in dest {
+      self.0 = self.0.wrapping_add(1);
+      *byte = self.0;
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl CryptoRng for CounterRng {}
+
+mod new_with_entropy {
+  use super::*;
+
+  #[test]
+  fn it_creates_the_same_key_from_the_same_entropy() {
+    let first = HDKey::new_with_entropy(&mut CounterRng(0));
+    let second = HDKey::new_with_entropy(&mut CounterRng(0));
+
+    assert_eq!(first.to_bytes(), second.to_bytes());
+    assert_eq!(
+      first.private_key_at(DerivationPath::from(0)).unwrap(),
+      second.private_key_at(DerivationPath::from(0)).unwrap()
+    );
+  }
+
+  #[test]
+  fn it_creates_different_keys_from_different_entropy() {
+    let first = HDKey::new_with_entropy(&mut CounterRng(0));
+    let second = HDKey::new_with_entropy(&mut CounterRng(100));
+
+    assert_ne!(first.to_bytes(), second.to_bytes());
+  }
+}
+
+mod generate_english_mnemonic_with_entropy {
+  use super::*;
+
+  #[test]
+  fn it_generates_the_mnemonic_encoding_the_entropy() {
+    let mnemonic = generate_english_mnemonic_with_entropy(&mut CounterRng(0));
+
+    assert_eq!(
+      mnemonic.entropy(),
+      &core::array::from_fn::<u8, 32, _>(|i| i as u8 + 1)
+    );
+  }
+}
+
+mod tree {
+  use walleth_keychain_hdkey::{bip32, Prefix};
+
+  use super::*;
+
+  /// The seed of the first test vector of BIP-32
+  const SEED: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+  ];
+
+  fn path(path: &str) -> bip32::DerivationPath {
+    path.parse().unwrap()
+  }
+
+  #[test]
+  fn it_matches_the_bip_32_test_vector() {
+    let hdkey = HDKey::from(&SEED[..]);
+    let mut tree = hdkey.tree();
+
+    assert_eq!(
+      tree.derive_xpub(&path("m")).unwrap().to_string(Prefix::XPUB),
+      "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+    );
+    assert_eq!(
+      tree.derive_xpub(&path("m/0'")).unwrap().to_string(Prefix::XPUB),
+      "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw"
+    );
+    assert_eq!(
+      *tree.derive_xprv(&path("m/0'")).unwrap().to_string(Prefix::XPRV),
+      "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7"
+    );
+  }
+
+  #[test]
+  fn it_matches_the_account_derivation() {
+    let hdkey = HDKey::from(&SEED[..]);
+    let account_path = DerivationPath::new(0, 0, 3);
+
+    assert_eq!(
+      hdkey
+        .tree()
+        .derive_xprv(&path(&account_path.to_string()))
+        .unwrap()
+        .to_bytes(),
+      hdkey.private_key_at(account_path).unwrap()
+    );
+  }
+
+  #[test]
+  fn it_caches_shared_ancestors() {
+    let hdkey = HDKey::from(&[7u8; 32][..]);
+    let mut tree = hdkey.tree();
+
+    let first = tree.derive_xpub(&path("m/44'/60'/0'/0/0")).unwrap();
+    assert_eq!(tree.cached_nodes(), 5);
+
+    tree.derive_xpub(&path("m/44'/60'/0'/0/1")).unwrap();
+    assert_eq!(tree.cached_nodes(), 6);
+
+    assert_eq!(tree.derive_xpub(&path("m/44'/60'/0'/0/0")).unwrap(), first);
+    assert_eq!(tree.cached_nodes(), 6);
+    assert_eq!(
+      first,
+      hdkey.tree().derive_xpub(&path("m/44'/60'/0'/0/0")).unwrap()
+    );
+  }
+}
+
+mod from_mnemonic {
+  use walleth_keychain_hdkey::{diagnose_mnemonic, HDKeyError, MnemonicDiagnostic};
+
+  use super::*;
+
+  const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+    abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+    abandon abandon abandon abandon art";
+
+  #[test]
+  fn it_accepts_valid_phrases() {
+    assert!(HDKey::from_mnemonic(MNEMONIC).is_ok());
+    assert_eq!(diagnose_mnemonic(MNEMONIC), None);
+  }
+
+  #[test]
+  fn it_reports_unknown_words_with_suggestions() {
+    let phrase = MNEMONIC
+      .replacen("abandon", "abandn", 1)
+      .replace("art", "arr");
+
+    let Err(HDKeyError::MnemonicDiagnostic(MnemonicDiagnostic::UnknownWords(words))) =
+      HDKey::from_mnemonic(&phrase)
+    else {
+      panic!("Expected unknown words");
+    };
+
+    assert_eq!(words.len(), 2);
+    assert_eq!((words[0].index, words[0].word.as_str()), (0, "abandn"));
+    assert_eq!(words[0].suggestions[0], "abandon");
+    assert_eq!(words[1].index, 23);
+    assert!(words[1].suggestions.contains(&"art"));
+  }
+
+  #[test]
+  fn it_suggests_words_sharing_the_first_four_letters() {
+    let phrase = MNEMONIC.replace("art", "abouttt");
+
+    let Some(MnemonicDiagnostic::UnknownWords(words)) = diagnose_mnemonic(&phrase) else {
+      panic!("Expected unknown words");
+    };
+
+    assert_eq!(words[0].suggestions[0], "about");
+  }
+
+  #[test]
+  fn it_reports_checksum_failures() {
+    let phrase = MNEMONIC.replace("art", "abandon");
+
+    assert!(matches!(
+      HDKey::from_mnemonic(&phrase),
+      Err(HDKeyError::MnemonicDiagnostic(
+        MnemonicDiagnostic::InvalidChecksum
+      ))
+    ));
+  }
+
+  #[test]
+  fn it_reports_invalid_word_counts() {
+    let phrase = MNEMONIC.replacen("abandon ", "", 1);
+
+    assert_eq!(
+      diagnose_mnemonic(&phrase),
+      Some(MnemonicDiagnostic::InvalidWordCount(23))
+    );
+  }
+}
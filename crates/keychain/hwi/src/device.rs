@@ -0,0 +1,135 @@
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use crate::{BridgeTransport, HwiError};
+use identity::{
+  signer::{verify_address, Signature},
+  Account, AccountDeriver, DerivationPath, IdentityError,
+};
+use utils::{
+  hex::{decode, encode_prefixed, remove0x},
+  PublicKeyBytes,
+};
+use walleth_core::{Backend, SigningBackend};
+
+/// A hardware wallet reached through a bridge, for devices without
+/// native drivers in this crate. Private keys never leave the device:
+/// the bridge derives public keys and signs on request.
+///
+/// The bridge answers these methods:
+/// - `getpublickey`, with `{ "path" }`, returning `{ "public_key" }` as hex
+/// - `sign`, with `{ "path", "message" }` as hex, returning `{ "signature" }`
+///   as a 65 bytes RSV hex string over the keccak256 digest of the message
+#[derive(Debug)]
+pub struct HwiDevice<T>
+where
+  T: BridgeTransport,
+{
+  /// The channel to the bridge, behind a lock as deriving takes `&self`
+  transport: Mutex<T>,
+  /// The id of the last request sent
+  last_id: Mutex<u64>,
+}
+
+impl<T> HwiDevice<T>
+where
+  T: BridgeTransport,
+{
+  /// Create a new device speaking to a bridge through `transport`
+  pub fn new(transport: T) -> Self {
+    Self {
+      transport: Mutex::new(transport),
+      last_id: Mutex::new(0),
+    }
+  }
+
+  /// Send a request to the bridge, returning the result of its response
+  pub fn request(&self, method: &str, params: Value) -> Result<Value, HwiError> {
+    let id = {
+      let mut last_id = self
+        .last_id
+        .lock()
+        .or(Err(HwiError::Transport("Lock poisoned".to_string())))?;
+      *last_id += 1;
+      *last_id
+    };
+
+    let response = self
+      .transport
+      .lock()
+      .or(Err(HwiError::Transport("Lock poisoned".to_string())))?
+      .exchange(&json!({ "id": id, "method": method, "params": params }))?;
+
+    if response["id"] != id {
+      return Err(HwiError::InvalidResponse(
+        "Unexpected response id".to_string(),
+      ));
+    }
+
+    match (&response["result"], &response["error"]) {
+      (_, Value::Null) => Ok(response["result"].clone()),
+      (_, error) => Err(HwiError::Device(
+        error["message"]
+          .as_str()
+          .map(str::to_string)
+          .unwrap_or(error.to_string()),
+      )),
+    }
+  }
+
+  /// Get the compressed public key at a derivation path
+  pub fn public_key_at(&self, path: DerivationPath) -> Result<PublicKeyBytes, HwiError> {
+    let result = self.request("getpublickey", json!({ "path": path.to_string() }))?;
+
+    result["public_key"]
+      .as_str()
+      .and_then(|public_key| decode(remove0x(public_key)).ok())
+      .and_then(|bytes| Backend::compress_public_key(&bytes).ok())
+      .map(PublicKeyBytes)
+      .ok_or(HwiError::InvalidResponse("Invalid public key".to_string()))
+  }
+
+  /// Sign a message with an account of the device, checking
+  /// that the signature was produced by the account
+  pub fn sign(&self, from: &Account, message: &[u8]) -> Result<Signature, HwiError> {
+    let result = self.request(
+      "sign",
+      json!({ "path": from.path.to_string(), "message": encode_prefixed(message) }),
+    )?;
+
+    let signature = result["signature"]
+      .as_str()
+      .and_then(|signature| Signature::from_rsv_hex(signature).ok())
+      .ok_or(HwiError::InvalidResponse("Invalid signature".to_string()))?;
+    self.verify(from, message, &signature)?;
+
+    Ok(signature)
+  }
+
+  /// Verify a signature of an account of the device
+  pub fn verify(
+    &self,
+    from: &Account,
+    message: &[u8],
+    signature: &Signature,
+  ) -> Result<(), HwiError> {
+    verify_address(&from.address, message, signature).or(Err(HwiError::Device(
+      "Signature does not match the account".to_string(),
+    )))
+  }
+}
+
+impl<T> AccountDeriver<DerivationPath> for HwiDevice<T>
+where
+  T: BridgeTransport,
+{
+  /// Get an account of the device
+  fn account_at(&self, path: DerivationPath) -> Result<Account, Box<dyn IdentityError>> {
+    let public_key = self.public_key_at(path)?;
+
+    Account::from_public_key(&public_key.0, path).or(Err(
+      HwiError::InvalidResponse("Invalid public key".to_string()).into(),
+    ))
+  }
+}
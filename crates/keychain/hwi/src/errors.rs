@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use identity::IdentityError;
+
+#[derive(Debug)]
+pub enum HwiError {
+  Transport(String),
+  Device(String),
+  InvalidResponse(String),
+}
+
+impl Display for HwiError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Transport(reason) => write!(f, "Bridge transport error: {}", reason),
+      Self::Device(reason) => write!(f, "Hardware device error: {}", reason),
+      Self::InvalidResponse(reason) => write!(f, "Invalid bridge response: {}", reason),
+    }
+  }
+}
+
+impl std::error::Error for HwiError {}
+
+impl From<std::io::Error> for HwiError {
+  fn from(error: std::io::Error) -> Self {
+    Self::Transport(error.to_string())
+  }
+}
+
+impl From<HwiError> for Box<dyn IdentityError> {
+  fn from(error: HwiError) -> Self {
+    Box::new(error)
+  }
+}
+
+impl IdentityError for HwiError {}
@@ -0,0 +1,8 @@
+pub mod transport;
+pub use transport::*;
+
+pub mod device;
+pub use device::HwiDevice;
+
+pub mod errors;
+pub use errors::*;
@@ -0,0 +1,82 @@
+use std::{
+  io::{BufRead, BufReader, Write},
+  process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use serde_json::Value;
+
+use crate::HwiError;
+
+/// A channel to a hardware wallet bridge, exchanging JSON
+/// requests in the form `{ "id", "method", "params" }` for
+/// responses in the form `{ "id", "result" }` or `{ "id", "error" }`
+pub trait BridgeTransport {
+  /// Send a request to the bridge, and wait for its response
+  fn exchange(&mut self, request: &Value) -> Result<Value, HwiError>;
+}
+
+impl<F> BridgeTransport for F
+where
+  F: FnMut(&Value) -> Result<Value, HwiError>,
+{
+  fn exchange(&mut self, request: &Value) -> Result<Value, HwiError> {
+    self(request)
+  }
+}
+
+/// A bridge running as an external process, reading a JSON
+/// request per line from stdin and writing a JSON response
+/// per line to stdout
+#[derive(Debug)]
+pub struct ProcessTransport {
+  child: Child,
+  stdin: ChildStdin,
+  stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessTransport {
+  /// Spawn the bridge process
+  pub fn spawn(mut command: Command) -> Result<Self, HwiError> {
+    let mut child = command
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()?;
+
+    let stdin = child
+      .stdin
+      .take()
+      .ok_or(HwiError::Transport("Missing bridge stdin".to_string()))?;
+    let stdout = child
+      .stdout
+      .take()
+      .ok_or(HwiError::Transport("Missing bridge stdout".to_string()))?;
+
+    Ok(Self {
+      child,
+      stdin,
+      stdout: BufReader::new(stdout),
+    })
+  }
+}
+
+impl BridgeTransport for ProcessTransport {
+  fn exchange(&mut self, request: &Value) -> Result<Value, HwiError> {
+    writeln!(self.stdin, "{}", request)?;
+    self.stdin.flush()?;
+
+    let mut line = String::new();
+    if self.stdout.read_line(&mut line)? == 0 {
+      return Err(HwiError::Transport("Bridge closed".to_string()));
+    }
+
+    serde_json::from_str(&line).or(Err(HwiError::InvalidResponse(line.trim().to_string())))
+  }
+}
+
+impl Drop for ProcessTransport {
+  /// Stop the bridge process with the transport
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
@@ -0,0 +1,136 @@
+use std::{process::Command, str::FromStr};
+
+use hdkey::HDKey;
+use identity::{signer::SignatureOptions, AccountDeriver, DerivationPath, MultiKeyPair};
+use serde_json::{json, Value};
+use utils::hex::{decode, remove0x};
+use walleth_keychain_hwi::{HwiDevice, HwiError, ProcessTransport};
+
+const MNEMONIC: &str =
+	"grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+/// A bridge answering with a software key, as a device would
+fn bridge(hdkey: HDKey) -> impl FnMut(&Value) -> Result<Value, HwiError> {
+  move |request| {
+    let path = DerivationPath::from_str(request["params"]["path"].as_str().unwrap()).unwrap();
+    let result = match request["method"].as_str().unwrap() {
+      "getpublickey" => json!({
+        "public_key": hdkey.public_key_at(path).unwrap().to_string()
+      }),
+      "sign" => {
+        let message = decode(remove0x(request["params"]["message"].as_str().unwrap()))
+          .ok()
+          .unwrap();
+        let options = SignatureOptions {
+          recoverable: true,
+          ..Default::default()
+        };
+        let signature = hdkey
+          .sign(&hdkey.account_at(path).unwrap(), &message, &options)
+          .unwrap();
+        json!({ "signature": signature.to_rsv_hex().unwrap() })
+      }
+      _ => return Ok(json!({ "id": request["id"], "error": { "message": "Unknown method" } })),
+    };
+
+    Ok(json!({ "id": request["id"], "result": result }))
+  }
+}
+
+fn hdkey() -> HDKey {
+  HDKey::from_mnemonic_str(MNEMONIC).unwrap()
+}
+
+mod account_at {
+  use super::*;
+
+  #[test]
+  fn it_derives_accounts_through_the_bridge() {
+    let device = HwiDevice::new(bridge(hdkey()));
+
+    let account = device.account_at(DerivationPath::from(3)).unwrap();
+
+    assert_eq!(
+      account,
+      hdkey().account_at(DerivationPath::from(3)).unwrap()
+    );
+  }
+
+  #[test]
+  fn it_reports_device_errors() {
+    let device = HwiDevice::new(|request: &Value| {
+      Ok(json!({ "id": request["id"], "error": { "message": "Device locked" } }))
+    });
+
+    let error = device.public_key_at(DerivationPath::from(0)).unwrap_err();
+
+    assert!(matches!(error, HwiError::Device(message) if message == "Device locked"));
+  }
+
+  #[test]
+  fn it_rejects_responses_to_other_requests() {
+    let device = HwiDevice::new(|_: &Value| Ok(json!({ "id": 42, "result": {} })));
+
+    assert!(matches!(
+      device.public_key_at(DerivationPath::from(0)),
+      Err(HwiError::InvalidResponse(_))
+    ));
+  }
+}
+
+mod sign {
+  use super::*;
+
+  #[test]
+  fn it_signs_through_the_bridge() {
+    let device = HwiDevice::new(bridge(hdkey()));
+    let account = device.account_at(DerivationPath::from(0)).unwrap();
+
+    let signature = device.sign(&account, b"message").unwrap();
+
+    assert!(device.verify(&account, b"message", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_signatures_of_other_accounts() {
+    let device = HwiDevice::new(bridge(hdkey()));
+    let other = device.account_at(DerivationPath::from(1)).unwrap();
+    let mut account = device.account_at(DerivationPath::from(0)).unwrap();
+    account.address = other.address;
+
+    assert!(matches!(
+      device.sign(&account, b"message"),
+      Err(HwiError::Device(_))
+    ));
+  }
+}
+
+mod process_transport {
+  use super::*;
+
+  #[test]
+  fn it_exchanges_json_lines_with_the_bridge_process() {
+    let mut command = Command::new("sh");
+    command.args([
+      "-c",
+      "while read line; do echo '{\"id\":1,\"error\":{\"message\":\"Device locked\"}}'; done",
+    ]);
+    let device = HwiDevice::new(ProcessTransport::spawn(command).unwrap());
+
+    let error = device.public_key_at(DerivationPath::from(0)).unwrap_err();
+
+    assert!(matches!(error, HwiError::Device(message) if message == "Device locked"));
+  }
+
+  #[test]
+  fn it_fails_when_the_bridge_exits() {
+    let mut command = Command::new("sh");
+    command.args(["-c", "exit 0"]);
+    let device = HwiDevice::new(ProcessTransport::spawn(command).unwrap());
+
+    assert!(matches!(
+      device.public_key_at(DerivationPath::from(0)),
+      Err(HwiError::Transport(_))
+    ));
+  }
+}
@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use identity::{IdentityError, SignerError};
+
+#[derive(Debug)]
+pub enum SimpleKeyError {
+  GenericError,
+  WrongDerivationPath,
+  InvalidSignature,
+  InvalidPrivateKey,
+}
+
+impl Display for SimpleKeyError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::WrongDerivationPath => write!(f, "Wrong derivation path"),
+      Self::InvalidSignature => write!(f, "Invalid signature"),
+      Self::InvalidPrivateKey => write!(f, "Invalid private key"),
+      Self::GenericError => write!(f, "Generic error"),
+    }
+  }
+}
+
+impl std::error::Error for SimpleKeyError {}
+
+impl From<SignerError> for SimpleKeyError {
+  fn from(error: SignerError) -> Self {
+    match error {
+      SignerError::InvalidPrivateKey => Self::InvalidPrivateKey,
+      SignerError::InvalidSignature => Self::InvalidSignature,
+      _ => Self::GenericError,
+    }
+  }
+}
+
+impl From<SimpleKeyError> for Box<dyn IdentityError> {
+  fn from(error: SimpleKeyError) -> Self {
+    Box::new(error)
+  }
+}
+
+impl IdentityError for SimpleKeyError {}
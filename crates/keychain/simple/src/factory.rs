@@ -0,0 +1,11 @@
+use super::SimpleKey;
+use identity::{IdentityError, Initializable};
+
+pub fn simple_key_factory(
+  private_key: Option<[u8; 32]>,
+) -> Result<SimpleKey, Box<dyn IdentityError>> {
+  match private_key {
+    Some(private_key) => Ok(SimpleKey::from_private_key(private_key)?),
+    None => Ok(SimpleKey::new()),
+  }
+}
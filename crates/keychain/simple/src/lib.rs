@@ -0,0 +1,8 @@
+pub mod simple_key;
+pub use simple_key::SimpleKey;
+
+pub mod factory;
+pub use factory::simple_key_factory;
+
+pub mod errors;
+pub use errors::*;
@@ -0,0 +1,148 @@
+use rand_core::{OsRng, RngCore};
+
+use crate::SimpleKeyError;
+use identity::{
+  signer::{IntoSignable, Signature, SignatureOptions, Signer},
+  Account, DerivationPath, GenericIdentity, IdentityError, Initializable, MultiKeyPair,
+};
+use utils::{PublicKeyBytes, SecureBytes};
+use walleth_core::{Backend, SigningBackend};
+
+/// An identity holding a single imported private key.
+///
+/// Its only account is at the first derivation path,
+/// so that it can be stored in a `Vault` like any other identity
+#[derive(Clone, Debug)]
+pub struct SimpleKey {
+  private_key: SecureBytes,
+}
+
+impl SimpleKey {
+  /// Create a new `SimpleKey` from a private key
+  pub fn from_private_key(private_key: [u8; 32]) -> Result<Self, SimpleKeyError> {
+    Backend::from_private_key(&private_key).or(Err(SimpleKeyError::InvalidPrivateKey))?;
+
+    Ok(SimpleKey {
+      private_key: SecureBytes::new(&private_key),
+    })
+  }
+
+  /// Get the private key, checking that `path` is the only one of the identity
+  fn private_key_for(&self, path: DerivationPath) -> Result<[u8; 32], SimpleKeyError> {
+    if path != DerivationPath::default() {
+      return Err(SimpleKeyError::WrongDerivationPath);
+    }
+
+    self
+      .private_key
+      .as_ref()
+      .try_into()
+      .or(Err(SimpleKeyError::InvalidPrivateKey))
+  }
+}
+
+impl TryFrom<&[u8]> for SimpleKey {
+  type Error = SimpleKeyError;
+
+  /// Create a new `SimpleKey` from a private key as slice of bytes
+  fn try_from(private_key: &[u8]) -> Result<Self, SimpleKeyError> {
+    Self::from_private_key(
+      private_key
+        .try_into()
+        .or(Err(SimpleKeyError::InvalidPrivateKey))?,
+    )
+  }
+}
+
+impl GenericIdentity for SimpleKey {
+  fn identity_type(&self) -> String {
+    "SimpleKey".to_string()
+  }
+
+  /// The first four bytes of the address of the key
+  fn fingerprint(&self) -> [u8; 4] {
+    self
+      .private_key_for(DerivationPath::default())
+      .ok()
+      .and_then(|private_key| Backend::from_private_key(&private_key).ok())
+      .map(|signer| {
+        let mut fingerprint = [0u8; 4];
+        fingerprint.copy_from_slice(&signer.address_bytes()[..4]);
+        fingerprint
+      })
+      .unwrap_or_default()
+  }
+
+  fn serialize(&self) -> Vec<u8> {
+    self.private_key.to_vec()
+  }
+
+  fn deserialize(&mut self, bytes: &[u8]) -> Result<(), Box<dyn IdentityError>> {
+    *self = SimpleKey::try_from(bytes)?;
+    Ok(())
+  }
+}
+
+impl Initializable for SimpleKey {
+  /// Create a new `SimpleKey` from a random private key
+  fn new() -> Self {
+    loop {
+      let mut private_key = [0u8; 32];
+      OsRng.fill_bytes(&mut private_key);
+      // Out of range keys are astronomically unlikely, but still rejected
+      if let Ok(simple_key) = SimpleKey::from_private_key(private_key) {
+        return simple_key;
+      }
+    }
+  }
+}
+
+impl MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> for SimpleKey {
+  /// Get the private key, only at the first derivation path
+  fn private_key_at(&self, path: DerivationPath) -> Result<[u8; 32], Box<dyn IdentityError>> {
+    Ok(self.private_key_for(path)?)
+  }
+
+  /// Get the public key, only at the first derivation path
+  fn public_key_at(&self, path: DerivationPath) -> Result<PublicKeyBytes, Box<dyn IdentityError>> {
+    let private_key = self.private_key_for(path)?;
+    let signer =
+      Backend::from_private_key(&private_key).or(Err(SimpleKeyError::InvalidPrivateKey))?;
+
+    Ok(PublicKeyBytes(signer.public_key_bytes()))
+  }
+
+  /// Sign a message with the key
+  fn sign(
+    &self,
+    from: &Account,
+    message: &dyn IntoSignable,
+    options: &SignatureOptions,
+  ) -> Result<Signature, Box<dyn IdentityError>> {
+    let signer =
+      Signer::new(self.private_key_for(from.path)?).or(Err(SimpleKeyError::InvalidPrivateKey))?;
+
+    Ok(signer.sign_with_options(&message.to_signable(), options))
+  }
+
+  /// Verify a signature with the key
+  fn verify(
+    &self,
+    from: &Account,
+    message: &dyn IntoSignable,
+    signature: &Signature,
+  ) -> Result<(), Box<dyn IdentityError>> {
+    let signer =
+      Signer::new(self.private_key_for(from.path)?).or(Err(SimpleKeyError::InvalidPrivateKey))?;
+
+    signer
+      .verify(&message.to_signable(), signature)
+      .or(Err(SimpleKeyError::InvalidSignature.into()))
+  }
+}
+
+impl PartialEq for SimpleKey {
+  fn eq(&self, other: &Self) -> bool {
+    self.private_key == other.private_key
+  }
+}
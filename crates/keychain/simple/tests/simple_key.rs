@@ -0,0 +1,72 @@
+use identity::{
+  signer::SignatureOptions, Account, DerivationPath, GenericIdentity, Initializable, MultiKeyPair,
+};
+use walleth_keychain_simple::{simple_key_factory, SimpleKey, SimpleKeyError};
+
+const PRIVATE_KEY: [u8; 32] = [
+  0x4c, 0x08, 0x83, 0xa6, 0x91, 0x02, 0x93, 0x7d, 0x62, 0x31, 0x47, 0x1b, 0x5d, 0xbb, 0x62, 0x04,
+  0xfe, 0x51, 0x29, 0x61, 0x70, 0x82, 0x79, 0x2a, 0xe4, 0x68, 0xd0, 0x1a, 0x3f, 0x36, 0x23, 0x18,
+];
+
+const ADDRESS: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+
+mod from_private_key {
+  use super::*;
+
+  #[test]
+  fn it_holds_the_account_of_the_key() {
+    let simple_key = SimpleKey::from_private_key(PRIVATE_KEY).unwrap();
+
+    let private_key = simple_key.private_key_at(DerivationPath::from(0)).unwrap();
+    let account = Account::from_private_key(private_key, DerivationPath::from(0)).unwrap();
+
+    assert_eq!(account.address, ADDRESS);
+    assert_eq!(simple_key.fingerprint(), [0x2c, 0x75, 0x36, 0xe3]);
+  }
+
+  #[test]
+  fn it_fails_with_an_invalid_private_key() {
+    assert!(matches!(
+      SimpleKey::from_private_key([0u8; 32]),
+      Err(SimpleKeyError::InvalidPrivateKey)
+    ));
+  }
+
+  #[test]
+  fn it_has_no_other_derivation_path() {
+    let simple_key = SimpleKey::from_private_key(PRIVATE_KEY).unwrap();
+
+    assert!(simple_key.private_key_at(DerivationPath::from(1)).is_err());
+    assert!(simple_key.public_key_at(DerivationPath::from(1)).is_err());
+  }
+}
+
+mod serialize {
+  use super::*;
+
+  #[test]
+  fn it_deserializes_the_serialized_key() {
+    let simple_key = simple_key_factory(Some(PRIVATE_KEY)).unwrap();
+    let mut restored = SimpleKey::new();
+
+    restored.deserialize(&simple_key.serialize()).unwrap();
+
+    assert_eq!(restored, simple_key);
+  }
+}
+
+mod sign {
+  use super::*;
+
+  #[test]
+  fn it_signs_and_verifies_messages() {
+    let simple_key = SimpleKey::from_private_key(PRIVATE_KEY).unwrap();
+    let account = Account::from_private_key(PRIVATE_KEY, DerivationPath::from(0)).unwrap();
+
+    let signature = simple_key
+      .sign(&account, b"message", &SignatureOptions::default())
+      .unwrap();
+
+    assert!(simple_key.verify(&account, b"message", &signature).is_ok());
+  }
+}
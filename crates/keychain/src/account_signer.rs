@@ -0,0 +1,78 @@
+use hdkey::HDKey;
+use identity::{
+  signer::{IntoSignable, Signature, SignatureOptions},
+  Account, DerivationPath, MultiKeyPair,
+};
+use utils::PublicKeyBytes;
+
+use crate::{Keychain, KeychainError, SigningContext};
+
+/// A handle to an account of a `Keychain`, resolved once from
+/// its address and then used to sign without looking it up again
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AccountId {
+  /// The index of the vault holding the account
+  pub key_pair_index: usize,
+  /// The derivation path of the account in the vault
+  pub path: DerivationPath,
+}
+
+/// A signer bound to a single account of a `Keychain`, for repeated use.
+///
+/// It borrows the keychain mutably, so that each signature still goes
+/// through the signing policy, the ledger and the audit log.
+pub struct AccountSigner<'a, M = HDKey>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  pub(crate) keychain: &'a mut Keychain<M>,
+  pub(crate) id: AccountId,
+  pub(crate) account: Account,
+}
+
+impl<M> AccountSigner<'_, M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Get the account the signer is bound to
+  pub fn account(&self) -> &Account {
+    &self.account
+  }
+
+  /// Get the handle of the account the signer is bound to
+  pub fn id(&self) -> AccountId {
+    self.id
+  }
+
+  /// Sign a message with the bound account
+  pub fn sign<S>(
+    &mut self,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.sign_with_context(message, options, &SigningContext::default())
+  }
+
+  /// Sign a message with the bound account, after checking
+  /// the signing policy against what the message is about
+  pub fn sign_with_context<S>(
+    &mut self,
+    message: &S,
+    options: &SignatureOptions,
+    context: &SigningContext,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.keychain.sign_for_account(
+      self.id.key_pair_index,
+      &self.account,
+      &message.to_signable(),
+      options,
+      context,
+    )
+  }
+}
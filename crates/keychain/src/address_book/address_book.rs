@@ -0,0 +1,261 @@
+use identity::{
+  signer::{verify_address, Signature, SignatureOptions},
+  DerivationPath, MultiKeyPair,
+};
+use serde_json::{json, Value};
+use utils::{Controller, Observable, PublicKeyBytes};
+
+use super::{AddressBookError, Contact};
+use crate::{BackupSink, Keychain, KeychainError};
+
+/// The public state of an address book
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AddressBookState {
+  /// The contacts, sorted by chain id and address
+  pub contacts: Vec<Contact>,
+}
+
+impl AddressBookState {
+  /// Get the contact of `address` on the chain `chain_id`, if any
+  pub fn contact(&self, address: &str, chain_id: u64) -> Option<&Contact> {
+    self
+      .contacts
+      .iter()
+      .find(|contact| contact.chain_id == chain_id && contact.address.eq_ignore_ascii_case(address))
+  }
+
+  /// Get the contacts named `name`, on any chain
+  pub fn contacts_named(&self, name: &str) -> Vec<&Contact> {
+    self
+      .contacts
+      .iter()
+      .filter(|contact| contact.name == name)
+      .collect()
+  }
+
+  /// Get the canonical serialization of the contacts: a JSON array
+  /// in contacts order, with the keys of each contact sorted.
+  /// Equal address books always have the same bytes
+  pub fn to_bytes(&self) -> Vec<u8> {
+    Value::Array(self.contacts.iter().map(Contact::to_json).collect())
+      .to_string()
+      .into_bytes()
+  }
+}
+
+/// An address book signed by a keychain account, proving that
+/// its contacts were not altered since it was exported
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedAddressBook {
+  /// The signed contacts
+  pub state: AddressBookState,
+  /// The address of the signing account
+  pub signer: String,
+  /// The signature of the canonical serialization of the contacts
+  pub signature: Signature,
+}
+
+impl SignedAddressBook {
+  /// Check that the address book was signed by the account at `signer`
+  pub fn verify(&self, signer: &str) -> Result<(), AddressBookError> {
+    if !self.signer.eq_ignore_ascii_case(signer) {
+      return Err(AddressBookError::InvalidSignature);
+    }
+
+    verify_address(signer, &self.state.to_bytes(), &self.signature)
+      .or(Err(AddressBookError::InvalidSignature))
+  }
+
+  /// Get the JSON representation of the signed address book
+  pub fn to_json(&self) -> Value {
+    json!({
+      "contacts": self.state.contacts.iter().map(Contact::to_json).collect::<Vec<Value>>(),
+      "signer": self.signer,
+      "signature": self.signature.to_rsv_hex().unwrap_or_default(),
+    })
+  }
+
+  /// Get the bytes of the JSON representation
+  pub fn to_bytes(&self) -> Vec<u8> {
+    self.to_json().to_string().into_bytes()
+  }
+}
+
+impl TryFrom<&[u8]> for SignedAddressBook {
+  type Error = AddressBookError;
+
+  fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    let invalid = |reason: &str| AddressBookError::InvalidFormat(reason.to_string());
+    let value = serde_json::from_slice::<Value>(bytes).or(Err(invalid("Invalid JSON")))?;
+
+    let mut contacts = value
+      .get("contacts")
+      .and_then(Value::as_array)
+      .ok_or(invalid("Missing contacts"))?
+      .iter()
+      .map(Contact::try_from)
+      .collect::<Result<Vec<Contact>, AddressBookError>>()?;
+    contacts.sort();
+
+    Ok(Self {
+      state: AddressBookState { contacts },
+      signer: value
+        .get("signer")
+        .and_then(Value::as_str)
+        .ok_or(invalid("Missing signer"))?
+        .to_string(),
+      signature: value
+        .get("signature")
+        .and_then(Value::as_str)
+        .and_then(|signature| Signature::from_rsv_hex(signature).ok())
+        .ok_or(AddressBookError::InvalidSignature)?,
+    })
+  }
+}
+
+/// A controller of named addresses, observable like the keychain state.
+///
+/// Contacts are kept sorted, so that the same contacts always
+/// serialize to the same bytes. The address book is persisted signed
+/// by a keychain account, and the signature is checked when loading it
+#[derive(Debug)]
+pub struct AddressBook {
+  store: Observable<AddressBookState>,
+  sinks: Vec<Box<dyn BackupSink>>,
+}
+
+impl AddressBook {
+  /// Create a new empty address book
+  pub fn new() -> Self {
+    Self {
+      store: Observable::new(AddressBookState::default()),
+      sinks: vec![],
+    }
+  }
+
+  /// Load a signed address book, checking that it was signed by `signer`
+  pub fn load(bytes: &[u8], signer: &str) -> Result<Self, AddressBookError> {
+    let signed = SignedAddressBook::try_from(bytes)?;
+    signed.verify(signer)?;
+
+    Ok(Self {
+      store: Observable::new(signed.state),
+      sinks: vec![],
+    })
+  }
+
+  /// Add a sink to write the signed address book to on each save
+  pub fn add_sink<S>(&mut self, sink: S)
+  where
+    S: BackupSink + 'static,
+  {
+    self.sinks.push(Box::new(sink));
+  }
+
+  /// Add a contact, replacing the one with the same address and chain
+  pub fn set_contact(&mut self, contact: Contact) -> Result<(), KeychainError> {
+    self.store.update(move |state| {
+      state.contacts.retain(|existing| {
+        existing.address != contact.address || existing.chain_id != contact.chain_id
+      });
+      state.contacts.push(contact.clone());
+      state.contacts.sort();
+    })?;
+
+    Ok(())
+  }
+
+  /// Remove the contact of `address` on the chain `chain_id`
+  pub fn remove_contact(&mut self, address: &str, chain_id: u64) -> Result<Contact, KeychainError> {
+    let contact = self
+      .store
+      .get_state()
+      .contact(address, chain_id)
+      .cloned()
+      .ok_or(AddressBookError::ContactNotFound(address.to_string()))?;
+
+    let removed = contact.clone();
+    self
+      .store
+      .update(move |state| state.contacts.retain(|existing| *existing != removed))?;
+
+    Ok(contact)
+  }
+
+  /// Sign the address book with the keychain account at `signer`,
+  /// and write it to the sinks
+  pub fn save<M>(
+    &mut self,
+    keychain: &mut Keychain<M>,
+    signer: &str,
+  ) -> Result<SignedAddressBook, KeychainError>
+  where
+    M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+  {
+    let state = self.store.get_state().clone();
+    let signature = keychain.use_signer(
+      signer.to_string(),
+      &state.to_bytes(),
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+    )?;
+
+    let signed = SignedAddressBook {
+      state,
+      signer: signer.to_string(),
+      signature,
+    };
+    let bytes = signed.to_bytes();
+    self
+      .sinks
+      .iter_mut()
+      .try_for_each(|sink| sink.write(&bytes))?;
+
+    Ok(signed)
+  }
+}
+
+impl Default for AddressBook {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Controller<AddressBookState, KeychainError> for AddressBook {
+  /// Get the state of the address book
+  fn get_state(&self) -> &AddressBookState {
+    self.store.get_state()
+  }
+
+  /// Update the state of the address book
+  fn update<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  where
+    F: Fn(&mut AddressBookState),
+  {
+    Ok(self.store.update(updater)?)
+  }
+
+  /// Update the state of the address book, restoring
+  /// it if a subscriber cannot be notified
+  fn try_update<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  where
+    F: Fn(&mut AddressBookState),
+  {
+    Ok(self.store.try_update(updater)?)
+  }
+
+  /// Subscribe to state changes
+  fn subscribe<F>(&mut self, subscriber: F) -> usize
+  where
+    F: 'static + FnMut(&AddressBookState) + Send,
+  {
+    self.store.subscribe(subscriber)
+  }
+
+  /// Unsubscribe from state changes
+  fn unsubscribe(&mut self, id: usize) {
+    self.store.unsubscribe(id)
+  }
+}
@@ -0,0 +1,62 @@
+use serde_json::{json, Value};
+use utils::hex::{add0x, assert_is_valid_hex_address, remove0x};
+
+use super::AddressBookError;
+
+/// A named address on a chain
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Contact {
+  /// The id of the chain the address is used on
+  pub chain_id: u64,
+  /// The address of the contact, lowercase with the 0x prefix
+  pub address: String,
+  /// The name of the contact
+  pub name: String,
+}
+
+impl Contact {
+  /// Create a new contact, checking its address
+  pub fn new(name: &str, address: &str, chain_id: u64) -> Result<Self, AddressBookError> {
+    let address = address.to_lowercase();
+    assert_is_valid_hex_address(&address)
+      .or(Err(AddressBookError::InvalidAddress(address.clone())))?;
+
+    Ok(Self {
+      chain_id,
+      address: add0x(remove0x(&address)),
+      name: name.to_string(),
+    })
+  }
+
+  /// Get the JSON representation of the contact
+  pub fn to_json(&self) -> Value {
+    json!({
+      "name": self.name,
+      "address": self.address,
+      "chainId": self.chain_id,
+    })
+  }
+}
+
+impl TryFrom<&Value> for Contact {
+  type Error = AddressBookError;
+
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    let invalid = || AddressBookError::InvalidFormat("Invalid contact".to_string());
+
+    Self::new(
+      value
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(invalid)?,
+      value
+        .get("address")
+        .and_then(Value::as_str)
+        .ok_or_else(invalid)?,
+      value
+        .get("chainId")
+        .and_then(Value::as_u64)
+        .ok_or_else(invalid)?,
+    )
+  }
+}
@@ -0,0 +1,22 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AddressBookError {
+  InvalidAddress(String),
+  ContactNotFound(String),
+  InvalidFormat(String),
+  InvalidSignature,
+}
+
+impl Display for AddressBookError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidAddress(address) => write!(f, "Invalid contact address: {}", address),
+      Self::ContactNotFound(address) => write!(f, "Contact not found: {}", address),
+      Self::InvalidFormat(reason) => write!(f, "Invalid address book: {}", reason),
+      Self::InvalidSignature => write!(f, "Address book signature does not match its signer"),
+    }
+  }
+}
+
+impl Error for AddressBookError {}
@@ -0,0 +1,8 @@
+pub mod address_book;
+pub use address_book::*;
+
+pub mod contact;
+pub use contact::*;
+
+pub mod errors;
+pub use errors::*;
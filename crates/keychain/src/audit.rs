@@ -0,0 +1,303 @@
+use std::{
+  fmt::Display,
+  str::FromStr,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use identity::{Account, DerivationPath};
+use serde_json::{json, Value};
+use utils::{crypto::sha3::keccak256, B256};
+
+use crate::KeychainError;
+
+/// The kind of operation recorded in the audit log
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuditEvent {
+  Sign,
+  Unlock,
+  Lock,
+  Backup,
+  Decrypt,
+  Reveal,
+  SigningPool,
+}
+
+impl AuditEvent {
+  /// Get the byte representation of the event
+  pub fn as_byte(&self) -> u8 {
+    match self {
+      Self::Sign => 0u8,
+      Self::Unlock => 1u8,
+      Self::Lock => 2u8,
+      Self::Backup => 3u8,
+      Self::Decrypt => 4u8,
+      Self::Reveal => 5u8,
+      Self::SigningPool => 6u8,
+    }
+  }
+}
+
+impl Display for AuditEvent {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Sign => write!(f, "sign"),
+      Self::Unlock => write!(f, "unlock"),
+      Self::Lock => write!(f, "lock"),
+      Self::Backup => write!(f, "backup"),
+      Self::Decrypt => write!(f, "decrypt"),
+      Self::Reveal => write!(f, "reveal"),
+      Self::SigningPool => write!(f, "signing pool"),
+    }
+  }
+}
+
+impl FromStr for AuditEvent {
+  type Err = String;
+
+  /// Parse the name of an event, as displayed
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match name {
+      "sign" => Ok(Self::Sign),
+      "unlock" => Ok(Self::Unlock),
+      "lock" => Ok(Self::Lock),
+      "backup" => Ok(Self::Backup),
+      "decrypt" => Ok(Self::Decrypt),
+      "reveal" => Ok(Self::Reveal),
+      "signing pool" => Ok(Self::SigningPool),
+      _ => Err(format!("Unknown audit event: {}", name)),
+    }
+  }
+}
+
+/// A single record of the audit log.
+///
+/// Each entry commits to the hash of the previous one, so
+/// that altering or removing a past entry breaks the chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+  /// The position of the entry in the log
+  pub sequence: u64,
+  /// Seconds since the UNIX epoch
+  pub timestamp: u64,
+  /// The operation performed
+  pub event: AuditEvent,
+  /// The address of the account involved, if any
+  pub account: Option<String>,
+  /// The derivation path of the account involved, if any
+  pub path: Option<DerivationPath>,
+  /// The digest of the signed payload, if any
+  pub payload_digest: Option<B256>,
+  /// The hash of the previous entry, zeroed for the first one
+  pub previous_hash: B256,
+  /// The hash of this entry
+  pub hash: B256,
+}
+
+impl AuditEntry {
+  /// Compute the hash of the entry, chained to the previous one
+  pub fn compute_hash(&self) -> B256 {
+    let mut bytes = self.previous_hash.as_bytes().to_vec();
+    bytes.extend(self.sequence.to_le_bytes());
+    bytes.extend(self.timestamp.to_le_bytes());
+    bytes.push(self.event.as_byte());
+
+    match &self.account {
+      Some(account) => {
+        bytes.push(1u8);
+        bytes.extend((account.len() as u64).to_le_bytes());
+        bytes.extend(account.as_bytes());
+      }
+      None => bytes.push(0u8),
+    }
+
+    match &self.path {
+      Some(path) => {
+        let path = path.to_string();
+        bytes.push(1u8);
+        bytes.extend((path.len() as u64).to_le_bytes());
+        bytes.extend(path.as_bytes());
+      }
+      None => bytes.push(0u8),
+    }
+
+    match &self.payload_digest {
+      Some(digest) => {
+        bytes.push(1u8);
+        bytes.extend(digest.as_bytes());
+      }
+      None => bytes.push(0u8),
+    }
+
+    B256(keccak256(&bytes))
+  }
+
+  /// Get the JSON representation of the entry
+  pub fn to_json(&self) -> Value {
+    json!({
+      "sequence": self.sequence,
+      "timestamp": self.timestamp,
+      "event": self.event.to_string(),
+      "account": self.account,
+      "path": self.path.map(|path| path.to_string()),
+      "payload_digest": self.payload_digest,
+      "previous_hash": self.previous_hash,
+      "hash": self.hash,
+    })
+  }
+}
+
+impl TryFrom<&Value> for AuditEntry {
+  type Error = String;
+
+  /// Parse an entry from its JSON representation
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    let hash = |field: &str| {
+      value
+        .get(field)
+        .and_then(Value::as_str)
+        .and_then(|hash| hash.parse::<B256>().ok())
+        .ok_or(format!("Invalid audit entry {}", field))
+    };
+    let optional = |field: &str| match value.get(field) {
+      None | Some(Value::Null) => Ok(None),
+      Some(Value::String(string)) => Ok(Some(string.as_str())),
+      Some(_) => Err(format!("Invalid audit entry {}", field)),
+    };
+
+    Ok(Self {
+      sequence: value
+        .get("sequence")
+        .and_then(Value::as_u64)
+        .ok_or("Invalid audit entry sequence")?,
+      timestamp: value
+        .get("timestamp")
+        .and_then(Value::as_u64)
+        .ok_or("Invalid audit entry timestamp")?,
+      event: value
+        .get("event")
+        .and_then(Value::as_str)
+        .ok_or("Invalid audit entry event")?
+        .parse()?,
+      account: optional("account")?.map(str::to_string),
+      path: optional("path")?
+        .map(|path| path.parse::<DerivationPath>())
+        .transpose()
+        .or(Err("Invalid audit entry path"))?,
+      payload_digest: optional("payload_digest")?
+        .map(|digest| digest.parse::<B256>())
+        .transpose()
+        .or(Err("Invalid audit entry payload_digest"))?,
+      previous_hash: hash("previous_hash")?,
+      hash: hash("hash")?,
+    })
+  }
+}
+
+/// An append-only, tamper-evident log of the operations
+/// performed with a `Keychain`.
+///
+/// The log is written to full backups, and restored with them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditLog {
+  entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+  /// Create a new empty audit log
+  pub fn new() -> Self {
+    Self { entries: vec![] }
+  }
+
+  /// Append a new entry to the log
+  pub(crate) fn record(
+    &mut self,
+    event: AuditEvent,
+    account: Option<&Account>,
+    payload_digest: Option<B256>,
+  ) -> &AuditEntry {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
+
+    let mut entry = AuditEntry {
+      sequence: self.entries.len() as u64,
+      timestamp,
+      event,
+      account: account.map(|account| account.address.clone()),
+      path: account.map(|account| account.path),
+      payload_digest,
+      previous_hash: self.last_hash(),
+      hash: B256::default(),
+    };
+    entry.hash = entry.compute_hash();
+    self.entries.push(entry);
+
+    // Unwrap is safe because an entry has just been pushed
+    self.entries.last().unwrap()
+  }
+
+  /// Get all the entries of the log
+  pub fn entries(&self) -> &[AuditEntry] {
+    &self.entries
+  }
+
+  /// Get the hash of the last entry, or zeroes if the log is empty
+  pub fn last_hash(&self) -> B256 {
+    match self.entries.last() {
+      Some(entry) => entry.hash,
+      None => B256::default(),
+    }
+  }
+
+  /// Verify that the hash chain of the log has not been tampered with
+  pub fn verify(&self) -> Result<(), KeychainError> {
+    let mut previous_hash = B256::default();
+
+    for (sequence, entry) in self.entries.iter().enumerate() {
+      if entry.sequence != sequence as u64
+        || entry.previous_hash != previous_hash
+        || entry.hash != entry.compute_hash()
+      {
+        return Err(KeychainError::AuditLogTampered(sequence as u64));
+      }
+      previous_hash = entry.hash;
+    }
+
+    Ok(())
+  }
+
+  /// Export the log as a JSON array
+  pub fn to_json(&self) -> String {
+    Value::Array(self.entries.iter().map(AuditEntry::to_json).collect()).to_string()
+  }
+}
+
+impl TryFrom<&[u8]> for AuditLog {
+  type Error = KeychainError;
+
+  /// Parse a log exported with `AuditLog::to_json`, verifying its hash chain
+  fn try_from(bytes: &[u8]) -> Result<Self, KeychainError> {
+    let invalid = |reason: String| KeychainError::ByteDeserializationError(reason);
+    let entries = serde_json::from_slice::<Vec<Value>>(bytes)
+      .or(Err(invalid("Invalid audit log".to_string())))?
+      .iter()
+      .map(AuditEntry::try_from)
+      .collect::<Result<Vec<AuditEntry>, String>>()
+      .map_err(invalid)?;
+
+    Self::try_from(entries)
+  }
+}
+
+impl TryFrom<Vec<AuditEntry>> for AuditLog {
+  type Error = KeychainError;
+
+  /// Create an `AuditLog` from existing entries, verifying their hash chain
+  fn try_from(entries: Vec<AuditEntry>) -> Result<Self, KeychainError> {
+    let log = Self { entries };
+    log.verify()?;
+
+    Ok(log)
+  }
+}
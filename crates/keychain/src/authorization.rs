@@ -0,0 +1,121 @@
+use identity::{
+  account::public_key_to_address,
+  signer::{Signable, Signature, SignerError},
+  IntoSignable,
+};
+
+use crate::rlp::{rlp_bytes, rlp_list, rlp_uint};
+
+/// The prefix of the bytes signed for an EIP-7702 authorization
+pub const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// An EIP-7702 authorization, delegating the code of the signing
+/// account to the contract at `address`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Authorization {
+  /// The id of the chain the authorization is valid on, `0` for any chain
+  pub chain_id: u64,
+  /// The address of the contract to delegate to
+  pub address: [u8; 20],
+  /// The nonce of the signing account when the authorization is applied
+  pub nonce: u64,
+}
+
+impl Authorization {
+  /// Create an authorization to delegate to the contract at `address`
+  pub fn new(chain_id: u64, address: [u8; 20], nonce: u64) -> Self {
+    Self {
+      chain_id,
+      address,
+      nonce,
+    }
+  }
+
+  /// Get the bytes whose keccak256 digest is signed:
+  /// `0x05` and the RLP list of the chain id, address and nonce
+  pub fn signing_bytes(&self) -> Vec<u8> {
+    let mut bytes = vec![AUTHORIZATION_MAGIC];
+    bytes.extend(rlp_list(&self.rlp_fields()));
+
+    bytes
+  }
+
+  fn rlp_fields(&self) -> Vec<Vec<u8>> {
+    vec![
+      rlp_uint(&self.chain_id.to_be_bytes()),
+      rlp_bytes(&self.address),
+      rlp_uint(&self.nonce.to_be_bytes()),
+    ]
+  }
+}
+
+impl IntoSignable for Authorization {
+  fn to_signable(&self) -> Signable {
+    Signable::from_bytes(&self.signing_bytes())
+  }
+}
+
+/// An EIP-7702 authorization signed by the delegating account,
+/// ready to be embedded in the authorization list of a type-4 transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedAuthorization {
+  /// The signed authorization
+  pub authorization: Authorization,
+  /// The parity of the `y` coordinate of the signature point
+  pub y_parity: u8,
+  /// The `r` value of the signature
+  pub r: [u8; 32],
+  /// The `s` value of the signature
+  pub s: [u8; 32],
+}
+
+impl SignedAuthorization {
+  /// Create a signed authorization from a recoverable `signature`.
+  /// Fails if the signature has no recovery id
+  pub fn new(authorization: Authorization, signature: &Signature) -> Result<Self, SignerError> {
+    let y_parity = signature
+      .recovery_id()
+      .ok_or(SignerError::MissingRecoveryId)?;
+    let compact = signature.to_compact();
+    let mut r = [0u8; 32];
+    let mut s = [0u8; 32];
+    r.copy_from_slice(&compact[..32]);
+    s.copy_from_slice(&compact[32..]);
+
+    Ok(Self {
+      authorization,
+      y_parity,
+      r,
+      s,
+    })
+  }
+
+  /// Get the signature of the authorization
+  pub fn signature(&self) -> Result<Signature, SignerError> {
+    let mut bytes = self.r.to_vec();
+    bytes.extend(self.s);
+    bytes.push(self.y_parity);
+
+    Signature::from_compact(&bytes)
+  }
+
+  /// Recover the address of the account that signed the authorization
+  pub fn authority(&self) -> Result<String, SignerError> {
+    let public_key = self
+      .signature()?
+      .recover(&self.authorization.to_signable())?;
+
+    public_key_to_address(&public_key).or(Err(SignerError::InvalidSignature))
+  }
+
+  /// Get the RLP encoding of the authorization list item:
+  /// `[chain_id, address, nonce, y_parity, r, s]`
+  pub fn rlp_bytes(&self) -> Vec<u8> {
+    let mut fields = self.authorization.rlp_fields();
+    fields.push(rlp_uint(&[self.y_parity]));
+    fields.push(rlp_uint(&self.r));
+    fields.push(rlp_uint(&self.s));
+
+    rlp_list(&fields)
+  }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::{AccountUsage, BackupError, KeychainState, ProfileState, QuotaUsage};
+
+/// The bytes opening a serialized `BackupDelta`
+pub const BACKUP_DELTA_MAGIC: [u8; 4] = [0xff, b'W', b'L', b'D'];
+
+/// The changes of a keychain between two revisions, to be applied on
+/// top of a backup or of an older delta instead of uploading the whole
+/// keychain each time something changes
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupDelta {
+  /// The revision the delta was exported since
+  pub base_revision: u64,
+  /// The revision of the keychain when the delta was exported
+  pub revision: u64,
+  /// The vaults changed since the base revision, locked and
+  /// serialized, with their index in the keychain
+  pub vaults: Vec<(usize, Vec<u8>)>,
+  /// The profiles and the usage of the accounts,
+  /// if changed since the base revision
+  pub metadata: Option<Value>,
+}
+
+impl BackupDelta {
+  /// Check if nothing changed between the two revisions
+  pub fn is_empty(&self) -> bool {
+    self.vaults.is_empty() && self.metadata.is_none()
+  }
+
+  /// Serialize the delta: the magic bytes, the revisions as little endian
+  /// u64, the vaults each prefixed by their index and length as little
+  /// endian u32, and the JSON metadata, empty when unchanged
+  pub fn to_bytes(&self) -> Result<Vec<u8>, BackupError> {
+    let mut bytes = BACKUP_DELTA_MAGIC.to_vec();
+    bytes.extend(self.base_revision.to_le_bytes());
+    bytes.extend(self.revision.to_le_bytes());
+    bytes.extend(u32_le(self.vaults.len())?);
+    for (index, vault) in &self.vaults {
+      bytes.extend(u32_le(*index)?);
+      bytes.extend(u32_le(vault.len())?);
+      bytes.extend(vault);
+    }
+    bytes.extend(
+      self
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.to_string().into_bytes())
+        .unwrap_or_default(),
+    );
+
+    Ok(bytes)
+  }
+}
+
+impl TryFrom<&[u8]> for BackupDelta {
+  type Error = BackupError;
+
+  fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    if reader.take(4)? != BACKUP_DELTA_MAGIC {
+      return Err(BackupError::InvalidDelta(
+        "Missing delta header".to_string(),
+      ));
+    }
+
+    let base_revision = u64::from_le_bytes(reader.array()?);
+    let revision = u64::from_le_bytes(reader.array()?);
+    let vaults = (0..u32::from_le_bytes(reader.array()?))
+      .map(|_| {
+        let index = u32::from_le_bytes(reader.array()?) as usize;
+        let length = u32::from_le_bytes(reader.array()?) as usize;
+
+        Ok((index, reader.take(length)?.to_vec()))
+      })
+      .collect::<Result<Vec<(usize, Vec<u8>)>, BackupError>>()?;
+    let metadata = match reader.rest() {
+      [] => None,
+      metadata => Some(
+        serde_json::from_slice(metadata).or(Err(BackupError::InvalidDelta(
+          "Invalid metadata".to_string(),
+        )))?,
+      ),
+    };
+
+    Ok(Self {
+      base_revision,
+      revision,
+      vaults,
+      metadata,
+    })
+  }
+}
+
+impl KeychainState {
+  /// Get the part of the state carried by backup deltas: the profiles,
+  /// the labels, the usage of the accounts and the dapps they are bound to
+  pub(crate) fn delta_metadata(&self) -> Value {
+    json!({
+      "profiles": self
+        .profiles
+        .iter()
+        .map(|profile| json!({ "name": profile.name, "vaults": profile.vaults }))
+        .collect::<Vec<Value>>(),
+      "activeProfile": self.active_profile,
+      "labels": self.labels,
+      "quotaUsage": to_json_map(&self.quota_usage, QuotaUsage::to_json),
+      "usage": to_json_map(&self.usage, AccountUsage::to_json),
+      "domains": self.domains,
+    })
+  }
+
+  /// Replace the part of the state carried by backup deltas with
+  /// `metadata`, checking that its profiles hold `vault_count` vaults
+  pub(crate) fn apply_delta_metadata(
+    &mut self,
+    metadata: &Value,
+    vault_count: usize,
+  ) -> Result<(), BackupError> {
+    let invalid = |reason: &str| BackupError::InvalidDelta(reason.to_string());
+
+    let profiles = metadata
+      .get("profiles")
+      .and_then(Value::as_array)
+      .ok_or(invalid("Invalid profiles"))?
+      .iter()
+      .map(|profile| {
+        Some(ProfileState {
+          name: profile.get("name")?.as_str()?.to_string(),
+          vaults: profile
+            .get("vaults")?
+            .as_array()?
+            .iter()
+            .map(|index| Some(index.as_u64()? as usize))
+            .collect::<Option<Vec<usize>>>()?,
+        })
+      })
+      .collect::<Option<Vec<ProfileState>>>()
+      .ok_or(invalid("Invalid profiles"))?;
+    let mut indexes = profiles
+      .iter()
+      .flat_map(|profile| profile.vaults.iter().copied())
+      .collect::<Vec<usize>>();
+    indexes.sort_unstable();
+    if indexes != (0..vault_count).collect::<Vec<usize>>() {
+      return Err(invalid("Profiles do not match the vaults"));
+    }
+
+    let active_profile = metadata
+      .get("activeProfile")
+      .and_then(Value::as_str)
+      .filter(|name| profiles.iter().any(|profile| profile.name == *name))
+      .ok_or(invalid("Invalid active profile"))?
+      .to_string();
+    let labels = metadata
+      .get("labels")
+      .and_then(Value::as_object)
+      .ok_or(invalid("Invalid labels"))?
+      .iter()
+      .map(|(address, label)| Some((address.clone(), label.as_str()?.to_string())))
+      .collect::<Option<BTreeMap<String, String>>>()
+      .ok_or(invalid("Invalid labels"))?;
+    let quota_usage = from_json_map(metadata.get("quotaUsage"), |usage| {
+      QuotaUsage::try_from(usage)
+    })
+    .map_err(|reason| invalid(&reason))?;
+    let usage = from_json_map(metadata.get("usage"), |usage| AccountUsage::try_from(usage))
+      .map_err(|reason| invalid(&reason))?;
+    // Deltas exported before accounts could be bound to dapps have no domains
+    let domains = match metadata.get("domains") {
+      None => BTreeMap::new(),
+      domains => from_json_map(domains, |address| {
+        address
+          .as_str()
+          .map(str::to_string)
+          .ok_or("Invalid domains".to_string())
+      })
+      .map_err(|reason| invalid(&reason))?,
+    };
+
+    self.profiles = profiles;
+    self.active_profile = active_profile;
+    self.labels = labels;
+    self.quota_usage = quota_usage;
+    self.usage = usage;
+    self.domains = domains;
+
+    Ok(())
+  }
+}
+
+/// Get the JSON object of a map indexed by address
+fn to_json_map<T>(map: &BTreeMap<String, T>, to_json: fn(&T) -> Value) -> Value {
+  Value::Object(
+    map
+      .iter()
+      .map(|(address, value)| (address.clone(), to_json(value)))
+      .collect::<Map<String, Value>>(),
+  )
+}
+
+/// Parse a JSON object indexed by address
+fn from_json_map<T, F>(value: Option<&Value>, parse: F) -> Result<BTreeMap<String, T>, String>
+where
+  F: Fn(&Value) -> Result<T, String>,
+{
+  value
+    .and_then(Value::as_object)
+    .ok_or("Invalid delta metadata")?
+    .iter()
+    .map(|(address, value)| Ok((address.clone(), parse(value)?)))
+    .collect()
+}
+
+/// Get the little endian bytes of a length or index
+fn u32_le(value: usize) -> Result<[u8; 4], BackupError> {
+  u32::try_from(value)
+    .map(u32::to_le_bytes)
+    .or(Err(BackupError::InvalidDelta(
+      "Delta too large".to_string(),
+    )))
+}
+
+/// A cursor over the bytes of a delta
+struct Reader<'a> {
+  bytes: &'a [u8],
+  position: usize,
+}
+
+impl<'a> Reader<'a> {
+  /// Read the next `length` bytes
+  fn take(&mut self, length: usize) -> Result<&'a [u8], BackupError> {
+    let bytes = self
+      .bytes
+      .get(self.position..self.position.saturating_add(length))
+      .ok_or(BackupError::InvalidDelta(
+        "Unexpected end of delta".to_string(),
+      ))?;
+    self.position += length;
+
+    Ok(bytes)
+  }
+
+  /// Read the next `N` bytes as an array
+  fn array<const N: usize>(&mut self) -> Result<[u8; N], BackupError> {
+    // Unwrap is safe because exactly `N` bytes are taken
+    Ok(self.take(N)?.try_into().unwrap())
+  }
+
+  /// Read the remaining bytes
+  fn rest(&mut self) -> &'a [u8] {
+    let rest = &self.bytes[self.position..];
+    self.position = self.bytes.len();
+
+    rest
+  }
+}
@@ -0,0 +1,36 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackupError {
+  InvalidRecoveryCode,
+  InvalidSealedBackup(String),
+  DecryptionFailed,
+  Sealed,
+  InvalidDelta(String),
+  DeltaGap(u64, u64),
+  SealRoundsOutOfBounds(u32),
+}
+
+impl Display for BackupError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidRecoveryCode => write!(f, "Invalid recovery code"),
+      Self::InvalidSealedBackup(reason) => write!(f, "Invalid sealed backup: {}", reason),
+      Self::DecryptionFailed => write!(f, "Unable to decrypt backup"),
+      Self::Sealed => write!(f, "Backup is sealed with a backup secret"),
+      Self::InvalidDelta(reason) => write!(f, "Invalid backup delta: {}", reason),
+      Self::DeltaGap(base, revision) => write!(
+        f,
+        "Backup delta since revision {} cannot be applied at revision {}",
+        base, revision
+      ),
+      Self::SealRoundsOutOfBounds(rounds) => write!(
+        f,
+        "{} PBKDF2 rounds out of bounds for a sealed backup",
+        rounds
+      ),
+    }
+  }
+}
+
+impl Error for BackupError {}
@@ -0,0 +1,11 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod recovery_code;
+pub use recovery_code::*;
+
+pub mod sealed;
+pub use sealed::*;
+
+pub mod delta;
+pub use delta::*;
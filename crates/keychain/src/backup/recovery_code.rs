@@ -0,0 +1,69 @@
+use std::{fmt::Display, str::FromStr};
+
+use bip39::{Language, Mnemonic};
+use rand_core::{OsRng, RngCore};
+
+use crate::BackupError;
+
+/// The number of words of a recovery code
+pub const RECOVERY_CODE_WORDS: usize = 12;
+
+/// A generated code of 12 English BIP-39 words, sealing backups
+/// independently of the password unlocking the keychain.
+///
+/// It encodes 128 bits of entropy with a checksum, so that
+/// typos are detected when the code is typed back
+#[derive(Clone, PartialEq, Eq)]
+pub struct RecoveryCode {
+  phrase: String,
+}
+
+impl RecoveryCode {
+  /// Generate a new random recovery code
+  pub fn generate() -> Self {
+    let mut entropy = [0u8; 16];
+    OsRng.fill_bytes(&mut entropy);
+
+    Self {
+      // Unwrap is safe because 16 bytes are a valid entropy length
+      phrase: Mnemonic::from_entropy_in(Language::English, &entropy)
+        .unwrap()
+        .to_string(),
+    }
+  }
+
+  /// Get the words of the code, separated by single spaces
+  pub fn phrase(&self) -> &str {
+    &self.phrase
+  }
+}
+
+impl Display for RecoveryCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", self.phrase)
+  }
+}
+
+impl std::fmt::Debug for RecoveryCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "RecoveryCode(****)")
+  }
+}
+
+impl FromStr for RecoveryCode {
+  type Err = BackupError;
+
+  /// Parse a recovery code, ignoring case and extra whitespace
+  fn from_str(code: &str) -> Result<Self, Self::Err> {
+    let phrase = code
+      .split_whitespace()
+      .map(str::to_lowercase)
+      .collect::<Vec<String>>()
+      .join(" ");
+
+    match Mnemonic::parse_in_normalized(Language::English, &phrase) {
+      Ok(mnemonic) if mnemonic.word_count() == RECOVERY_CODE_WORDS => Ok(Self { phrase }),
+      _ => Err(BackupError::InvalidRecoveryCode),
+    }
+  }
+}
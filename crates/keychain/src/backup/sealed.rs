@@ -0,0 +1,160 @@
+use aes_gcm::{
+  aead::{Aead, KeyInit, Payload},
+  Aes256Gcm, Nonce,
+};
+use pbkdf2::pbkdf2_hmac;
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use utils::SecureBytes;
+
+use crate::{BackupError, RecoveryCode};
+
+/// The bytes opening a backup sealed with a `BackupSecret`
+pub const SEALED_BACKUP_MAGIC: [u8; 4] = [0xff, b'W', b'L', b'S'];
+
+/// The PBKDF2 rounds sealed backups are derived with by default, as
+/// recommended by OWASP for PBKDF2-HMAC-SHA256. Sealed backups are
+/// meant to be stored in the cloud, so they are derived with far more
+/// rounds than the vaults unlocked daily
+pub const DEFAULT_SEAL_ROUNDS: u32 = 600_000;
+
+/// The fewest PBKDF2 rounds a sealed backup can be derived with
+pub const MIN_SEAL_ROUNDS: u32 = 100_000;
+
+/// The most PBKDF2 rounds a sealed backup can be derived with, so
+/// that a crafted header cannot make restoring a backup hang
+pub const MAX_SEAL_ROUNDS: u32 = 10_000_000;
+
+/// The length of the header of a sealed backup: the magic bytes,
+/// the PBKDF2 rounds, the salt and the nonce
+const HEADER_LENGTH: usize = 4 + 4 + 16 + 12;
+
+/// A secret sealing backups, independent from the password unlocking
+/// the keychain, so that backups stored in the cloud are protected
+/// by something else than the daily unlock password
+#[derive(Clone, PartialEq, Eq)]
+pub enum BackupSecret {
+  /// A passphrase chosen by the user
+  Passphrase(String),
+  /// A generated recovery code
+  RecoveryCode(RecoveryCode),
+}
+
+impl BackupSecret {
+  /// Get the bytes the sealing key is derived from
+  fn as_bytes(&self) -> &[u8] {
+    match self {
+      Self::Passphrase(passphrase) => passphrase.as_bytes(),
+      Self::RecoveryCode(code) => code.phrase().as_bytes(),
+    }
+  }
+
+  /// Derive the sealing key from the secret
+  fn key(&self, salt: &[u8], rounds: u32) -> SecureBytes {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(self.as_bytes(), salt, rounds, &mut key);
+
+    SecureBytes::new(&key)
+  }
+}
+
+impl std::fmt::Debug for BackupSecret {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Passphrase(_) => write!(f, "Passphrase(****)"),
+      Self::RecoveryCode(code) => write!(f, "{:?}", code),
+    }
+  }
+}
+
+impl From<RecoveryCode> for BackupSecret {
+  fn from(code: RecoveryCode) -> Self {
+    Self::RecoveryCode(code)
+  }
+}
+
+/// Check if `backup` is sealed with a `BackupSecret`
+pub fn is_sealed(backup: &[u8]) -> bool {
+  backup.len() >= HEADER_LENGTH && backup[..4] == SEALED_BACKUP_MAGIC
+}
+
+/// Seal a backup with `secret`, with AES-256-GCM and a key derived with
+/// `rounds` iterations of PBKDF2-HMAC-SHA256. The header carrying the
+/// rounds, the salt and the nonce is authenticated with the backup.
+/// Fails if the rounds are out of `MIN_SEAL_ROUNDS..=MAX_SEAL_ROUNDS`
+pub fn seal_backup(
+  backup: &[u8],
+  secret: &BackupSecret,
+  rounds: u32,
+) -> Result<Vec<u8>, BackupError> {
+  check_seal_rounds(rounds)?;
+  let mut salt = [0u8; 16];
+  let mut nonce = [0u8; 12];
+  OsRng.fill_bytes(&mut salt);
+  OsRng.fill_bytes(&mut nonce);
+
+  let mut sealed = SEALED_BACKUP_MAGIC.to_vec();
+  sealed.extend(rounds.to_le_bytes());
+  sealed.extend(salt);
+  sealed.extend(nonce);
+
+  // Unwrap is safe because the key is always 32 bytes long,
+  // and encryption only fails on payloads larger than 64 GiB
+  let cipher = Aes256Gcm::new_from_slice(&secret.key(&salt, rounds)).unwrap();
+  let ciphertext = cipher
+    .encrypt(
+      Nonce::from_slice(&nonce),
+      Payload {
+        msg: backup,
+        aad: &sealed,
+      },
+    )
+    .unwrap();
+  sealed.extend(ciphertext);
+
+  Ok(sealed)
+}
+
+/// Fail if `rounds` are out of `MIN_SEAL_ROUNDS..=MAX_SEAL_ROUNDS`
+pub fn check_seal_rounds(rounds: u32) -> Result<(), BackupError> {
+  match (MIN_SEAL_ROUNDS..=MAX_SEAL_ROUNDS).contains(&rounds) {
+    true => Ok(()),
+    false => Err(BackupError::SealRoundsOutOfBounds(rounds)),
+  }
+}
+
+/// Open a backup sealed with `secret`, returning the backup.
+/// Fails before deriving the key if the rounds of the header
+/// are out of `MIN_SEAL_ROUNDS..=MAX_SEAL_ROUNDS`
+pub fn unseal_backup(sealed: &[u8], secret: &BackupSecret) -> Result<Vec<u8>, BackupError> {
+  if !is_sealed(sealed) {
+    return Err(BackupError::InvalidSealedBackup(
+      "Missing sealed backup header".to_string(),
+    ));
+  }
+
+  let (header, ciphertext) = sealed.split_at(HEADER_LENGTH);
+  // Unwrap is safe because the header length has been checked
+  let rounds = u32::from_le_bytes(header[4..8].try_into().unwrap());
+  if check_seal_rounds(rounds).is_err() {
+    return Err(BackupError::InvalidSealedBackup(format!(
+      "{} PBKDF2 rounds out of bounds",
+      rounds
+    )));
+  }
+  let salt = &header[8..24];
+  let nonce = &header[24..];
+
+  let cipher =
+    Aes256Gcm::new_from_slice(&secret.key(salt, rounds)).or(Err(BackupError::DecryptionFailed))?;
+
+  cipher
+    .decrypt(
+      Nonce::from_slice(nonce),
+      Payload {
+        msg: ciphertext,
+        aad: header,
+      },
+    )
+    .or(Err(BackupError::DecryptionFailed))
+}
@@ -0,0 +1,31 @@
+use utils::{crypto::sha3::keccak256, hex::encode_prefixed};
+
+use crate::rlp::{rlp_bytes, rlp_list, rlp_uint};
+
+/// The prefix of the bytes hashed to compute a CREATE2 address
+const CREATE2_PREFIX: u8 = 0xff;
+
+/// Compute the address of the contract deployed with CREATE by
+/// `deployer`, when its nonce is `nonce`
+pub fn create_address(deployer: &[u8; 20], nonce: u64) -> String {
+  let encoded = rlp_list(&[rlp_bytes(deployer), rlp_uint(&nonce.to_be_bytes())]);
+
+  encode_prefixed(&keccak256(&encoded)[12..])
+}
+
+/// Compute the address of the contract deployed with CREATE2 by
+/// `deployer`, with `salt` and the keccak256 hash of its init code
+pub fn create2_address(deployer: &[u8; 20], salt: &[u8; 32], init_code_hash: &[u8; 32]) -> String {
+  let mut bytes = vec![CREATE2_PREFIX];
+  bytes.extend(deployer);
+  bytes.extend(salt);
+  bytes.extend(init_code_hash);
+
+  encode_prefixed(&keccak256(&bytes)[12..])
+}
+
+/// Compute the address of the contract deployed with CREATE2 by
+/// `deployer`, with `salt` and `init_code`
+pub fn create2_address_from_code(deployer: &[u8; 20], salt: &[u8; 32], init_code: &[u8]) -> String {
+  create2_address(deployer, salt, &keccak256(init_code))
+}
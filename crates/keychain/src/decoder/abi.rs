@@ -0,0 +1,234 @@
+use std::fmt::Display;
+
+use utils::{
+  crypto::sha3::keccak256,
+  hex::{encode_prefixed, Hex},
+};
+
+use super::DecoderError;
+
+/// The ABI types the decoder understands
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbiType {
+  Address,
+  Uint,
+  Bool,
+  Bytes,
+  UintArray,
+}
+
+impl AbiType {
+  /// Parse a Solidity type name
+  pub fn parse(name: &str) -> Result<Self, DecoderError> {
+    match name {
+      "address" => Ok(Self::Address),
+      "bool" => Ok(Self::Bool),
+      "bytes" => Ok(Self::Bytes),
+      "uint256[]" => Ok(Self::UintArray),
+      uint if uint.starts_with("uint") && !uint.contains('[') => Ok(Self::Uint),
+      unsupported => Err(DecoderError::InvalidFunctionSignature(
+        unsupported.to_string(),
+      )),
+    }
+  }
+}
+
+/// A decoded ABI value
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiValue {
+  /// A 0x-prefixed, lowercase address
+  Address(String),
+  /// A big-endian 256-bit unsigned integer
+  Uint([u8; 32]),
+  Bool(bool),
+  Bytes(Vec<u8>),
+  UintArray(Vec<[u8; 32]>),
+}
+
+impl Display for AbiValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Address(address) => write!(f, "{}", address),
+      Self::Uint(value) => write!(f, "{}", uint_to_decimal(value)),
+      Self::Bool(value) => write!(f, "{}", value),
+      Self::Bytes(bytes) => write!(f, "{}", Hex(bytes)),
+      Self::UintArray(values) => write!(
+        f,
+        "[{}]",
+        values
+          .iter()
+          .map(uint_to_decimal)
+          .collect::<Vec<String>>()
+          .join(", ")
+      ),
+    }
+  }
+}
+
+/// A contract function the decoder can recognize from its selector
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbiFunction {
+  /// The name of the function
+  pub name: String,
+  /// The names and types of the function inputs
+  pub inputs: Vec<(String, AbiType)>,
+  /// The first 4 bytes of the keccak256 of the canonical signature
+  pub selector: [u8; 4],
+}
+
+impl AbiFunction {
+  /// Create a function from its canonical signature, like
+  /// `transfer(address,uint256)`, and the names of its inputs.
+  /// Unnamed inputs are called `arg{position}`.
+  pub fn new(signature: &str, names: &[&str]) -> Result<Self, DecoderError> {
+    let invalid = || DecoderError::InvalidFunctionSignature(signature.to_string());
+    let (name, types) = signature
+      .strip_suffix(')')
+      .and_then(|signature| signature.split_once('('))
+      .ok_or_else(invalid)?;
+
+    if name.is_empty() {
+      return Err(invalid());
+    }
+
+    let inputs = match types.is_empty() {
+      true => vec![],
+      false => types
+        .split(',')
+        .enumerate()
+        .map(|(position, kind)| {
+          let input_name = match names.get(position) {
+            Some(input_name) => input_name.to_string(),
+            None => format!("arg{}", position),
+          };
+          Ok((input_name, AbiType::parse(kind.trim())?))
+        })
+        .collect::<Result<Vec<(String, AbiType)>, DecoderError>>()?,
+    };
+
+    // Unwrap is safe because the hash is 32 bytes long
+    let selector = keccak256(signature.as_bytes())[..4].try_into().unwrap();
+
+    Ok(Self {
+      name: name.to_string(),
+      inputs,
+      selector,
+    })
+  }
+
+  /// Decode the arguments of the function from calldata,
+  /// selector included
+  pub fn decode(&self, calldata: &[u8]) -> Result<Vec<AbiValue>, DecoderError> {
+    if calldata.len() < 4 {
+      return Err(DecoderError::CalldataTooShort);
+    }
+
+    let arguments = &calldata[4..];
+    self
+      .inputs
+      .iter()
+      .enumerate()
+      .map(|(position, (_, kind))| decode_value(arguments, position * 32, *kind))
+      .collect()
+  }
+}
+
+/// Decode a value whose head is at `offset` in the encoded `arguments`
+fn decode_value(arguments: &[u8], offset: usize, kind: AbiType) -> Result<AbiValue, DecoderError> {
+  let head = word(arguments, offset)?;
+
+  match kind {
+    AbiType::Address => match head[..12].iter().all(|byte| *byte == 0) {
+      true => Ok(AbiValue::Address(encode_prefixed(&head[12..]))),
+      false => Err(DecoderError::InvalidEncoding(
+        "dirty address padding".to_string(),
+      )),
+    },
+    AbiType::Uint => Ok(AbiValue::Uint(head)),
+    AbiType::Bool => match word_to_usize(&head)? {
+      0 => Ok(AbiValue::Bool(false)),
+      1 => Ok(AbiValue::Bool(true)),
+      _ => Err(DecoderError::InvalidEncoding("invalid boolean".to_string())),
+    },
+    AbiType::Bytes => {
+      let tail = word_to_usize(&head)?;
+      let length = word_to_usize(&word(arguments, tail)?)?;
+
+      arguments
+        .get(tail + 32..tail + 32 + length)
+        .map(|bytes| AbiValue::Bytes(bytes.to_vec()))
+        .ok_or(DecoderError::CalldataTooShort)
+    }
+    AbiType::UintArray => {
+      let tail = word_to_usize(&head)?;
+      let length = word_to_usize(&word(arguments, tail)?)?;
+
+      (0..length)
+        .map(|position| word(arguments, tail + 32 + position * 32))
+        .collect::<Result<Vec<[u8; 32]>, DecoderError>>()
+        .map(AbiValue::UintArray)
+    }
+  }
+}
+
+/// Read the 32 bytes word at `offset`
+fn word(arguments: &[u8], offset: usize) -> Result<[u8; 32], DecoderError> {
+  arguments
+    .get(offset..offset + 32)
+    // Unwrap is safe because exactly 32 bytes are taken
+    .map(|word| word.try_into().unwrap())
+    .ok_or(DecoderError::CalldataTooShort)
+}
+
+/// Read a word as an offset or length, which must fit in a `usize`
+fn word_to_usize(word: &[u8; 32]) -> Result<usize, DecoderError> {
+  match word[..24].iter().all(|byte| *byte == 0) {
+    // Unwrap is safe because exactly 8 bytes are taken
+    true => Ok(u64::from_be_bytes(word[24..].try_into().unwrap()) as usize),
+    false => Err(DecoderError::InvalidEncoding(
+      "offset or length overflow".to_string(),
+    )),
+  }
+}
+
+/// Format a big-endian 256-bit unsigned integer in base 10
+pub fn uint_to_decimal(value: &[u8; 32]) -> String {
+  let mut value = *value;
+  let mut digits = vec![];
+
+  while value.iter().any(|byte| *byte != 0) {
+    // Long division of the whole number by 10
+    let mut remainder = 0u16;
+    value.iter_mut().for_each(|byte| {
+      let current = (remainder << 8) | u16::from(*byte);
+      *byte = (current / 10) as u8;
+      remainder = current % 10;
+    });
+    digits.push(char::from(b'0' + remainder as u8));
+  }
+
+  match digits.is_empty() {
+    true => "0".to_string(),
+    false => digits.iter().rev().collect(),
+  }
+}
+
+/// Format a big-endian 256-bit unsigned integer as a decimal amount
+/// with `decimals` fractional digits, trimming trailing zeros
+pub fn format_units(value: &[u8; 32], decimals: u8) -> String {
+  let digits = uint_to_decimal(value);
+  let decimals = usize::from(decimals);
+
+  if decimals == 0 {
+    return digits;
+  }
+
+  let padded = format!("{:0>width$}", digits, width = decimals + 1);
+  let (integer, fraction) = padded.split_at(padded.len() - decimals);
+  let fraction = fraction.trim_end_matches('0');
+
+  match fraction.is_empty() {
+    true => integer.to_string(),
+    false => format!("{}.{}", integer, fraction),
+  }
+}
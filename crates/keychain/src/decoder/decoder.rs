@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use super::{format_units, uint_to_decimal, AbiFunction, AbiValue, DecoderError};
+
+/// Symbol and decimals of a known ERC-20 token
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenInfo {
+  pub symbol: String,
+  pub decimals: u8,
+}
+
+/// The contract a call is addressed to, as seen by descriptions
+pub struct CallTarget<'a> {
+  /// The address of the contract, when known
+  pub contract: Option<&'a str>,
+  /// The token deployed at the contract, when registered
+  pub token: Option<&'a TokenInfo>,
+}
+
+impl CallTarget<'_> {
+  /// Get a human readable name of the contract
+  pub fn name(&self) -> String {
+    match (self.token, self.contract) {
+      (Some(token), _) => token.symbol.clone(),
+      (None, Some(contract)) => contract.to_string(),
+      (None, None) => "the contract".to_string(),
+    }
+  }
+
+  /// Format an amount of the token, using its decimals when known
+  pub fn amount(&self, value: &[u8; 32]) -> String {
+    match self.token {
+      Some(token) => format!("{} {}", format_units(value, token.decimals), token.symbol),
+      None => format!("{} tokens of {}", uint_to_decimal(value), self.name()),
+    }
+  }
+}
+
+/// A function producing the human readable description of a call
+pub type CallDescriber = fn(&CallTarget, &[AbiValue]) -> Option<String>;
+
+/// A call decoded from calldata
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+  /// The contract the call is addressed to, when known
+  pub contract: Option<String>,
+  /// The name of the function called
+  pub function: String,
+  /// The names and values of the arguments
+  pub arguments: Vec<(String, AbiValue)>,
+  /// A human readable description of the call,
+  /// like "transfer 100 USDC to 0xabc…"
+  pub description: String,
+}
+
+/// Decodes calldata into human readable descriptions, using
+/// a registry of known functions and tokens
+#[derive(Clone, Debug, Default)]
+pub struct Decoder {
+  /// Known functions, indexed by selector
+  functions: HashMap<[u8; 4], (AbiFunction, Option<CallDescriber>)>,
+  /// Known tokens, indexed by lowercase contract address
+  tokens: HashMap<String, TokenInfo>,
+}
+
+impl Decoder {
+  /// Create a new decoder without known functions
+  pub fn new() -> Self {
+    Self {
+      functions: HashMap::new(),
+      tokens: HashMap::new(),
+    }
+  }
+
+  /// Create a new decoder knowing the ERC-20, ERC-721
+  /// and ERC-1155 transfer and approval functions
+  pub fn with_builtins() -> Self {
+    let mut decoder = Self::new();
+    BUILTINS.iter().for_each(|(signature, names, describer)| {
+      // Unwrap is safe because built-in signatures are valid
+      let function = AbiFunction::new(signature, names).unwrap();
+      decoder
+        .functions
+        .insert(function.selector, (function, Some(*describer)));
+    });
+
+    decoder
+  }
+
+  /// Register a function from its canonical signature and the names
+  /// of its inputs. Its calls are described generically
+  pub fn register_function(
+    &mut self,
+    signature: &str,
+    names: &[&str],
+  ) -> Result<&mut Self, DecoderError> {
+    let function = AbiFunction::new(signature, names)?;
+    self.functions.insert(function.selector, (function, None));
+
+    Ok(self)
+  }
+
+  /// Register the symbol and decimals of the token at `contract`
+  pub fn register_token(&mut self, contract: &str, symbol: &str, decimals: u8) -> &mut Self {
+    self.tokens.insert(
+      contract.to_lowercase(),
+      TokenInfo {
+        symbol: symbol.to_string(),
+        decimals,
+      },
+    );
+    self
+  }
+
+  /// Check if the function called by `calldata` is known
+  pub fn can_decode(&self, calldata: &[u8]) -> bool {
+    self.decode(None, calldata).is_ok()
+  }
+
+  /// Decode `calldata` sent to `contract`
+  pub fn decode(
+    &self,
+    contract: Option<&str>,
+    calldata: &[u8],
+  ) -> Result<DecodedCall, DecoderError> {
+    // Unwrap is safe because the length is checked first
+    let selector: [u8; 4] = match calldata.len() >= 4 {
+      true => calldata[..4].try_into().unwrap(),
+      false => return Err(DecoderError::CalldataTooShort),
+    };
+    let (function, describer) = self
+      .functions
+      .get(&selector)
+      .ok_or(DecoderError::UnknownSelector(selector))?;
+
+    let values = function.decode(calldata)?;
+    let contract = contract.map(|contract| contract.to_lowercase());
+    let target = CallTarget {
+      contract: contract.as_deref(),
+      token: contract
+        .as_ref()
+        .and_then(|contract| self.tokens.get(contract)),
+    };
+    let description = describer
+      .and_then(|describer| describer(&target, &values))
+      .unwrap_or_else(|| describe_generic(&function.name, &values));
+
+    Ok(DecodedCall {
+      function: function.name.clone(),
+      arguments: function
+        .inputs
+        .iter()
+        .map(|(name, _)| name.clone())
+        .zip(values)
+        .collect(),
+      contract,
+      description,
+    })
+  }
+}
+
+/// Describe a call as `name(value, ...)`
+fn describe_generic(name: &str, values: &[AbiValue]) -> String {
+  format!(
+    "call {}({})",
+    name,
+    values
+      .iter()
+      .map(AbiValue::to_string)
+      .collect::<Vec<String>>()
+      .join(", ")
+  )
+}
+
+/// Built-in functions, with the names of their inputs and their describer
+const BUILTINS: [(&str, &[&str], CallDescriber); 8] = [
+  (
+    "transfer(address,uint256)",
+    &["to", "amount"],
+    |target, values| match values {
+      [AbiValue::Address(to), AbiValue::Uint(amount)] => {
+        Some(format!("transfer {} to {}", target.amount(amount), to))
+      }
+      _ => None,
+    },
+  ),
+  (
+    "approve(address,uint256)",
+    &["spender", "amount"],
+    |target, values| match values {
+      [AbiValue::Address(spender), AbiValue::Uint(amount)] if *amount == [0xff; 32] => Some(
+        format!("approve {} to spend unlimited {}", spender, target.name()),
+      ),
+      [AbiValue::Address(spender), AbiValue::Uint(amount)] => Some(format!(
+        "approve {} to spend {}",
+        spender,
+        target.amount(amount)
+      )),
+      _ => None,
+    },
+  ),
+  (
+    "transferFrom(address,address,uint256)",
+    &["from", "to", "amount"],
+    |target, values| match values {
+      [AbiValue::Address(from), AbiValue::Address(to), AbiValue::Uint(amount)] => Some(format!(
+        "transfer {} from {} to {}",
+        target.amount(amount),
+        from,
+        to
+      )),
+      _ => None,
+    },
+  ),
+  (
+    "safeTransferFrom(address,address,uint256)",
+    &["from", "to", "tokenId"],
+    describe_nft_transfer,
+  ),
+  (
+    "safeTransferFrom(address,address,uint256,bytes)",
+    &["from", "to", "tokenId", "data"],
+    describe_nft_transfer,
+  ),
+  (
+    "setApprovalForAll(address,bool)",
+    &["operator", "approved"],
+    |target, values| match values {
+      [AbiValue::Address(operator), AbiValue::Bool(true)] => Some(format!(
+        "approve {} to transfer all tokens of {}",
+        operator,
+        target.name()
+      )),
+      [AbiValue::Address(operator), AbiValue::Bool(false)] => Some(format!(
+        "revoke approval of {} for all tokens of {}",
+        operator,
+        target.name()
+      )),
+      _ => None,
+    },
+  ),
+  (
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    &["from", "to", "id", "amount", "data"],
+    |target, values| match values {
+      [AbiValue::Address(from), AbiValue::Address(to), AbiValue::Uint(id), AbiValue::Uint(amount), _] => {
+        Some(format!(
+          "transfer {} of token #{} of {} from {} to {}",
+          uint_to_decimal(amount),
+          uint_to_decimal(id),
+          target.name(),
+          from,
+          to
+        ))
+      }
+      _ => None,
+    },
+  ),
+  (
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+    &["from", "to", "ids", "amounts", "data"],
+    |target, values| match values {
+      [AbiValue::Address(from), AbiValue::Address(to), AbiValue::UintArray(ids), _, _] => {
+        Some(format!(
+          "transfer {} token types of {} from {} to {}",
+          ids.len(),
+          target.name(),
+          from,
+          to
+        ))
+      }
+      _ => None,
+    },
+  ),
+];
+
+/// Describe an ERC-721 transfer
+fn describe_nft_transfer(target: &CallTarget, values: &[AbiValue]) -> Option<String> {
+  match values {
+    [AbiValue::Address(from), AbiValue::Address(to), AbiValue::Uint(id), ..] => Some(format!(
+      "transfer token #{} of {} from {} to {}",
+      uint_to_decimal(id),
+      target.name(),
+      from,
+      to
+    )),
+    _ => None,
+  }
+}
@@ -0,0 +1,28 @@
+use std::{error::Error, fmt::Display};
+
+use utils::hex::encode;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecoderError {
+  CalldataTooShort,
+  UnknownSelector([u8; 4]),
+  InvalidEncoding(String),
+  InvalidFunctionSignature(String),
+}
+
+impl Display for DecoderError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::CalldataTooShort => write!(f, "Calldata too short"),
+      Self::UnknownSelector(selector) => {
+        write!(f, "Unknown function selector: 0x{}", encode(selector))
+      }
+      Self::InvalidEncoding(message) => write!(f, "Invalid calldata encoding: {}", message),
+      Self::InvalidFunctionSignature(signature) => {
+        write!(f, "Invalid function signature: {}", signature)
+      }
+    }
+  }
+}
+
+impl Error for DecoderError {}
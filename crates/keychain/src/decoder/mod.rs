@@ -0,0 +1,8 @@
+pub mod abi;
+pub use abi::*;
+
+pub mod decoder;
+pub use decoder::*;
+
+pub mod errors;
+pub use errors::*;
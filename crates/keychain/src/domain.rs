@@ -0,0 +1,31 @@
+use utils::crypto::sha3::keccak256;
+use vault::SEQUENTIAL_INDEX_LIMIT;
+
+use crate::KeychainState;
+
+/// Normalize a dapp origin, so that `https://App.xyz/` and
+/// `https://app.xyz` are bound to the same account
+pub fn normalize_origin(origin: &str) -> String {
+  origin.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// Get the derivation index of the account bound to `origin`: the
+/// keccak256 hash of the normalized origin, mapped to the indexes above
+/// `SEQUENTIAL_INDEX_LIMIT` and below the hardened ones, so that it never
+/// collides with the accounts added in sequence
+pub fn domain_account_index(origin: &str) -> usize {
+  let hash = keccak256(normalize_origin(origin).as_bytes());
+  let offset = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]) as usize;
+
+  SEQUENTIAL_INDEX_LIMIT + offset % SEQUENTIAL_INDEX_LIMIT
+}
+
+impl KeychainState {
+  /// Get the address of the account bound to `origin`, if any
+  pub fn domain_address(&self, origin: &str) -> Option<&str> {
+    self
+      .domains
+      .get(&normalize_origin(origin))
+      .map(String::as_str)
+  }
+}
@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crypto_box::{
+  aead::{Aead, AeadCore, OsRng},
+  Nonce, PublicKey, SalsaBox, SecretKey,
+};
+use serde_json::{json, Value};
+use utils::SecureBytes;
+
+use super::EncryptionError;
+
+/// The only encryption scheme of EIP-1024
+pub const ENCRYPTION_VERSION: &str = "x25519-xsalsa20-poly1305";
+
+/// Get the encryption public key of an account from its private key,
+/// as returned by `eth_getEncryptionPublicKey`: the X25519 public key
+/// of the secp256k1 private key bytes, encoded in base64
+pub fn encryption_public_key(private_key: &[u8; 32]) -> String {
+  STANDARD.encode(SecretKey::from(*private_key).public_key().as_bytes())
+}
+
+/// Data encrypted for an account as specified by EIP-1024, with a
+/// NaCl box between an ephemeral key and the account encryption key
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedData {
+  /// The nonce of the box
+  pub nonce: [u8; 24],
+  /// The public key of the ephemeral sender key
+  pub ephem_public_key: [u8; 32],
+  /// The encrypted data, followed by the authentication tag
+  pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedData {
+  /// Encrypt `data` for the owner of the base64 encryption `public_key`,
+  /// like a dapp does before calling `eth_decrypt`
+  pub fn encrypt(public_key: &str, data: &[u8]) -> Result<Self, EncryptionError> {
+    let public_key = STANDARD
+      .decode(public_key)
+      .ok()
+      .and_then(|bytes| PublicKey::from_slice(&bytes).ok())
+      .ok_or(EncryptionError::InvalidPublicKey)?;
+    let ephem_key = SecretKey::generate(&mut OsRng);
+    let nonce = SalsaBox::generate_nonce(&mut OsRng);
+
+    let ciphertext = SalsaBox::new(&public_key, &ephem_key)
+      .encrypt(&nonce, data)
+      .or(Err(EncryptionError::InvalidPublicKey))?;
+
+    Ok(Self {
+      nonce: nonce.into(),
+      ephem_public_key: ephem_key.public_key().to_bytes(),
+      ciphertext,
+    })
+  }
+
+  /// Decrypt the data with the private key of the account it was encrypted for
+  pub fn decrypt(&self, private_key: &[u8; 32]) -> Result<SecureBytes, EncryptionError> {
+    let secret_key = SecretKey::from(*private_key);
+    let data = SalsaBox::new(&PublicKey::from(self.ephem_public_key), &secret_key)
+      .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_ref())
+      .or(Err(EncryptionError::DecryptionFailed))?;
+
+    Ok(SecureBytes::from(data))
+  }
+
+  /// Get the JSON representation of the encrypted data
+  pub fn to_json(&self) -> Value {
+    json!({
+      "version": ENCRYPTION_VERSION,
+      "nonce": STANDARD.encode(self.nonce),
+      "ephemPublicKey": STANDARD.encode(self.ephem_public_key),
+      "ciphertext": STANDARD.encode(&self.ciphertext),
+    })
+  }
+
+  /// Get the hex encoded JSON representation, as passed to `eth_decrypt`
+  pub fn to_hex(&self) -> String {
+    format!(
+      "0x{}",
+      utils::hex::encode(self.to_json().to_string().as_bytes())
+    )
+  }
+}
+
+impl TryFrom<&Value> for EncryptedData {
+  type Error = EncryptionError;
+
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    let field = |name: &str| {
+      value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or(EncryptionError::InvalidEncryptedData(format!(
+          "Missing {}",
+          name
+        )))
+    };
+    let bytes = |name: &str| {
+      STANDARD
+        .decode(field(name)?)
+        .or(Err(EncryptionError::InvalidEncryptedData(format!(
+          "Invalid {}",
+          name
+        ))))
+    };
+
+    let version = field("version")?;
+    if version != ENCRYPTION_VERSION {
+      return Err(EncryptionError::UnsupportedVersion(version.to_string()));
+    }
+
+    Ok(Self {
+      nonce: bytes("nonce")?
+        .try_into()
+        .or(Err(EncryptionError::InvalidEncryptedData(
+          "Invalid nonce".to_string(),
+        )))?,
+      ephem_public_key: bytes("ephemPublicKey")?.try_into().or(Err(
+        EncryptionError::InvalidEncryptedData("Invalid ephemPublicKey".to_string()),
+      ))?,
+      ciphertext: bytes("ciphertext")?,
+    })
+  }
+}
+
+impl FromStr for EncryptedData {
+  type Err = EncryptionError;
+
+  /// Parse encrypted data from its JSON representation, or from
+  /// the hex encoding of it passed to `eth_decrypt`
+  fn from_str(data: &str) -> Result<Self, Self::Err> {
+    let data = data.trim();
+    let json = match data.starts_with('{') {
+      true => data.as_bytes().to_vec(),
+      false => utils::hex::decode(data.trim_start_matches("0x")).or(Err(
+        EncryptionError::InvalidEncryptedData("Invalid hex encoding".to_string()),
+      ))?,
+    };
+
+    let value = serde_json::from_slice::<Value>(&json).or(Err(
+      EncryptionError::InvalidEncryptedData("Invalid JSON".to_string()),
+    ))?;
+
+    Self::try_from(&value)
+  }
+}
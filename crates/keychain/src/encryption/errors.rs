@@ -0,0 +1,22 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EncryptionError {
+  UnsupportedVersion(String),
+  InvalidPublicKey,
+  InvalidEncryptedData(String),
+  DecryptionFailed,
+}
+
+impl Display for EncryptionError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::UnsupportedVersion(version) => write!(f, "Unsupported encryption version: {}", version),
+      Self::InvalidPublicKey => write!(f, "Invalid encryption public key"),
+      Self::InvalidEncryptedData(reason) => write!(f, "Invalid encrypted data: {}", reason),
+      Self::DecryptionFailed => write!(f, "Unable to decrypt data with this account"),
+    }
+  }
+}
+
+impl Error for EncryptionError {}
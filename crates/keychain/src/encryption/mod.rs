@@ -0,0 +1,5 @@
+pub mod encrypted_data;
+pub use encrypted_data::*;
+
+pub mod errors;
+pub use errors::*;
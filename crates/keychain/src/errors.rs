@@ -4,14 +4,42 @@ use identity::IdentityError;
 use utils::observable::ObservableError;
 use vault::VaultError;
 
+use crate::{
+  AddressBookError, BackupError, EncryptionError, MetamaskError, MigrationError, PolicyViolation,
+  SyncError, TypedDataError, UrError, WeakPassword,
+};
+
 #[derive(Debug)]
 pub enum KeychainError {
   VaultError(VaultError),
   KeyNotFoundForAddress(String),
+  Locked(String),
   EventEmitterError(ObservableError),
   KeyNotFoundForIndex(usize),
   ByteSerializationError,
   ByteDeserializationError(String),
+  AuditLogTampered(u64),
+  BackupSinkError(String),
+  PolicyViolation(PolicyViolation),
+  LockPoisoned,
+  SigningPoolClosed,
+  SigningPoolRestricted,
+  DraftRestricted,
+  UrError(UrError),
+  MigrationError(MigrationError),
+  WeakPassword(WeakPassword),
+  ProfileNotFound(String),
+  ProfileAlreadyExists(String),
+  MetamaskError(MetamaskError),
+  SyncError(SyncError),
+  EncryptionError(EncryptionError),
+  AddressBookError(AddressBookError),
+  TypedDataError(TypedDataError),
+  BackupError(BackupError),
+  VaultsUnchanged(Vec<(usize, VaultError)>),
+  InvalidSessionToken,
+  SessionExpired,
+  RelayError(String),
 }
 
 impl Display for KeychainError {
@@ -21,12 +49,49 @@ impl Display for KeychainError {
       KeychainError::KeyNotFoundForAddress(address) => {
         write!(f, "Key not found for address: {}", address)
       }
+      KeychainError::Locked(address) => {
+        write!(f, "Vault locked, unlock it to sign with {}", address)
+      }
       KeychainError::EventEmitterError(error) => write!(f, "Event emitter error: {}", error),
       KeychainError::KeyNotFoundForIndex(index) => write!(f, "Key not found for index {}", index),
       KeychainError::ByteSerializationError => write!(f, "Byte serialization error"),
       KeychainError::ByteDeserializationError(message) => {
         write!(f, "Byte deserialization error: {}", message)
       }
+      KeychainError::AuditLogTampered(sequence) => {
+        write!(f, "Audit log tampered at entry {}", sequence)
+      }
+      KeychainError::BackupSinkError(message) => write!(f, "Backup sink error: {}", message),
+      KeychainError::PolicyViolation(violation) => write!(f, "Policy violation: {}", violation),
+      KeychainError::LockPoisoned => write!(f, "Shared keychain lock poisoned"),
+      KeychainError::SigningPoolClosed => write!(f, "Signing pool closed"),
+      KeychainError::SigningPoolRestricted => write!(
+        f,
+        "Signing pool unavailable while a signing policy or a payload ledger is configured"
+      ),
+      KeychainError::DraftRestricted => {
+        write!(f, "Signing and private keys unavailable in a preview draft")
+      }
+      KeychainError::UrError(error) => write!(f, "UR error: {}", error),
+      KeychainError::MigrationError(error) => write!(f, "Migration error: {}", error),
+      KeychainError::WeakPassword(weakness) => write!(f, "{}", weakness),
+      KeychainError::ProfileNotFound(name) => write!(f, "Profile not found: {}", name),
+      KeychainError::ProfileAlreadyExists(name) => write!(f, "Profile already exists: {}", name),
+      KeychainError::MetamaskError(error) => write!(f, "MetaMask import error: {}", error),
+      KeychainError::SyncError(error) => write!(f, "Sync error: {}", error),
+      KeychainError::EncryptionError(error) => write!(f, "Encryption error: {}", error),
+      KeychainError::AddressBookError(error) => write!(f, "Address book error: {}", error),
+      KeychainError::TypedDataError(error) => write!(f, "Typed data error: {}", error),
+      KeychainError::BackupError(error) => write!(f, "Backup error: {}", error),
+      KeychainError::VaultsUnchanged(errors) => {
+        write!(f, "No vault changed, {} failed:", errors.len())?;
+        errors
+          .iter()
+          .try_for_each(|(index, error)| write!(f, " vault {}: {};", index, error))
+      }
+      KeychainError::InvalidSessionToken => write!(f, "Invalid session token"),
+      KeychainError::SessionExpired => write!(f, "Session expired, unlock with the password"),
+      KeychainError::RelayError(reason) => write!(f, "Relay error: {}", reason),
     }
   }
 }
@@ -37,6 +102,66 @@ impl From<VaultError> for KeychainError {
   }
 }
 
+impl From<UrError> for KeychainError {
+  fn from(error: UrError) -> Self {
+    Self::UrError(error)
+  }
+}
+
+impl From<MigrationError> for KeychainError {
+  fn from(error: MigrationError) -> Self {
+    Self::MigrationError(error)
+  }
+}
+
+impl From<MetamaskError> for KeychainError {
+  fn from(error: MetamaskError) -> Self {
+    Self::MetamaskError(error)
+  }
+}
+
+impl From<TypedDataError> for KeychainError {
+  fn from(error: TypedDataError) -> Self {
+    Self::TypedDataError(error)
+  }
+}
+
+impl From<AddressBookError> for KeychainError {
+  fn from(error: AddressBookError) -> Self {
+    Self::AddressBookError(error)
+  }
+}
+
+impl From<EncryptionError> for KeychainError {
+  fn from(error: EncryptionError) -> Self {
+    Self::EncryptionError(error)
+  }
+}
+
+impl From<SyncError> for KeychainError {
+  fn from(error: SyncError) -> Self {
+    Self::SyncError(error)
+  }
+}
+
+impl From<WeakPassword> for KeychainError {
+  fn from(weakness: WeakPassword) -> Self {
+    Self::WeakPassword(weakness)
+  }
+}
+
+impl From<PolicyViolation> for KeychainError {
+  fn from(violation: PolicyViolation) -> Self {
+    Self::PolicyViolation(violation)
+  }
+}
+
+impl From<BackupError> for KeychainError {
+  fn from(error: BackupError) -> Self {
+    Self::BackupError(error)
+  }
+}
+
 impl From<ObservableError> for KeychainError {
   fn from(error: ObservableError) -> Self {
     Self::EventEmitterError(error)
@@ -0,0 +1,106 @@
+use serde_json::{json, Value};
+
+use crate::KeychainState;
+
+/// The columns of a CSV export, in order
+const CSV_HEADER: &str = "vault,address,label,path,balance";
+
+/// The format of a public account list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+  /// A JSON array with an object per account
+  Json,
+  /// Comma-separated values, with a header row
+  Csv,
+}
+
+/// A single account of a public account list.
+/// It contains no secret material
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportedAccount {
+  /// The index of the vault holding the account
+  pub vault: usize,
+  /// The address of the account
+  pub address: String,
+  /// The label of the account, if any
+  pub label: Option<String>,
+  /// The derivation path of the account
+  pub path: String,
+  /// The last tracked balance of the account, in wei
+  pub balance: Option<u128>,
+}
+
+impl ExportedAccount {
+  /// Get the JSON representation of the account.
+  /// The balance is a decimal string, as it may not fit a JSON number
+  pub fn to_json(&self) -> Value {
+    json!({
+      "vault": self.vault,
+      "address": self.address,
+      "label": self.label,
+      "path": self.path,
+      "balance": self.balance.map(|balance| balance.to_string()),
+    })
+  }
+
+  /// Get the CSV row of the account
+  pub fn to_csv(&self) -> String {
+    [
+      self.vault.to_string(),
+      self.address.clone(),
+      csv_field(self.label.as_deref().unwrap_or_default()),
+      self.path.clone(),
+      self
+        .balance
+        .map(|balance| balance.to_string())
+        .unwrap_or_default(),
+    ]
+    .join(",")
+  }
+}
+
+impl KeychainState {
+  /// Get the public list of the accounts of all the vaults,
+  /// with their labels and tracked balances
+  pub fn exported_accounts(&self) -> Vec<ExportedAccount> {
+    self
+      .vaults
+      .iter()
+      .enumerate()
+      .flat_map(|(vault, state)| {
+        state.accounts.iter().map(move |account| ExportedAccount {
+          vault,
+          address: account.address.clone(),
+          label: self.labels.get(&account.address).cloned(),
+          path: account.path.to_string(),
+          balance: self
+            .snapshot(&account.address)
+            .and_then(|snapshot| snapshot.balance),
+        })
+      })
+      .collect()
+  }
+
+  /// Export the public list of the accounts in `format`
+  pub fn export_accounts(&self, format: ExportFormat) -> String {
+    let accounts = self.exported_accounts();
+
+    match format {
+      ExportFormat::Json => {
+        Value::Array(accounts.iter().map(ExportedAccount::to_json).collect()).to_string()
+      }
+      ExportFormat::Csv => std::iter::once(CSV_HEADER.to_string())
+        .chain(accounts.iter().map(ExportedAccount::to_csv))
+        .collect::<Vec<String>>()
+        .join("\n"),
+    }
+  }
+}
+
+/// Quote a CSV field containing separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+  match value.contains([',', '"', '\n', '\r']) {
+    true => format!("\"{}\"", value.replace('"', "\"\"")),
+    false => value.to_string(),
+  }
+}
@@ -0,0 +1,44 @@
+use vault::VaultError;
+
+/// The outcome of checking the integrity of a vault
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VaultIntegrity {
+  /// The vault decrypts, and its identity matches the stored metadata
+  Valid,
+  /// The vault does not decrypt, either because the password
+  /// is wrong or because the encrypted bytes are corrupted
+  Undecryptable,
+  /// The decrypted identity does not match the stored metadata
+  Mismatch(String),
+  /// The vault could not be checked
+  Error(String),
+}
+
+impl From<Result<(), VaultError>> for VaultIntegrity {
+  fn from(result: Result<(), VaultError>) -> Self {
+    match result {
+      Ok(()) => Self::Valid,
+      Err(VaultError::SafeDecrypt) => Self::Undecryptable,
+      Err(VaultError::IntegrityMismatch(message)) => Self::Mismatch(message),
+      Err(error) => Self::Error(error.to_string()),
+    }
+  }
+}
+
+/// The outcome of `Keychain::verify_integrity`, for each vault
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+  /// The fingerprint and the integrity of each vault,
+  /// in the same order as the vaults of the keychain
+  pub vaults: Vec<([u8; 4], VaultIntegrity)>,
+}
+
+impl IntegrityReport {
+  /// Check if all the vaults are valid
+  pub fn is_valid(&self) -> bool {
+    self
+      .vaults
+      .iter()
+      .all(|(_, integrity)| *integrity == VaultIntegrity::Valid)
+  }
+}
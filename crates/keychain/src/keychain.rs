@@ -1,22 +1,186 @@
-use super::KeychainError;
-use hdkey::HDKey;
-use identity::{Account, IdentityError, Initializable, MultiKeyPair};
-use utils::{Controller, Observable};
-use vault::{Vault, VaultError};
+use std::{
+  cmp::Ordering,
+  collections::BTreeMap,
+  str::FromStr,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Debug)]
+use super::{
+  check_seal_rounds, current_day, domain_account_index, encryption_public_key, is_sealed,
+  metamask::DEFAULT_HD_PATH,
+  metrics,
+  migrations::write_envelope,
+  normalize_origin, seal_backup,
+  session::{Session, SessionKeys},
+  unseal_backup, AccountId, AccountSigner, AccountUsage, AuditEvent, AuditLog, Authorization,
+  BackupDelta, BackupError, BackupSecret, BackupSink, DuplicateAction, EncryptedData,
+  EncryptionError, EthSignRequest, EthSignature, ExportFormat, ForwardRequest, Forwarder,
+  IntegrityReport, KeychainError, LabelConflict, MergeReport, MetamaskImport, MetamaskKeyring,
+  MetamaskVault, MigrationReport, Migrator, PasskeyCredential, PasswordPolicy, PayloadLedger,
+  Permit, Permit2, PolicyEvent, PolicyViolation, PreviewOutcome, ProfileState, QuotaUsage,
+  RecoveryCode, Relayer, RotationPair, RotationPlan, SessionToken, SignedAuthorization,
+  SignedForwardRequest, SigningContext, SigningPolicy, SigningPool, SigningPoolHandle, SiweMessage,
+  SyncChannel, TypedData, DEFAULT_PROFILE, DEFAULT_SEAL_ROUNDS, DEFAULT_SESSION_TTL,
+};
+use hdkey::{hdkey_factory, HDKey};
+use identity::{
+  signer::{IntoSignable, Signable, Signature, SignatureOptions},
+  Account, DerivationPath, IdentityError, IdentityFactoryRegistry, Initializable, MultiKeyPair,
+  MultiKeyPairDyn,
+};
+use simple::simple_key_factory;
+use tracing::instrument;
+use utils::{
+  hex::encode_prefixed, Controller, Observable, PublicKeyBytes, RevealedSecret, SecureBytes,
+};
+use vault::{KdfParams, StagedVault, Vault, VaultError};
+
+#[cfg(feature = "compression")]
+use super::migrations::write_compressed_envelope;
+
+/// The name of the key slot of the recovery key added by
+/// `Keychain::add_recovery_key`
+pub const RECOVERY_KEY_SLOT: &str = "recovery";
+
+/// The backup sections accepted from a paired device:
+/// vaults (0) and profile markers (1)
+const SYNC_SECTIONS: [u8; 2] = [0u8, 1u8];
+
+#[derive(Clone, Debug)]
 pub enum KeyPair<M = HDKey>
 where
-  M: MultiKeyPair<[u8; 32], [u8; 33], usize>,
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
 {
   MultiKeyPair(Vault<M>),
 }
 
-#[derive(Clone, Debug)]
+impl<M> KeyPair<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Get the public state of the keypair
+  fn to_state(&self) -> Result<VaultState, VaultError> {
+    match self {
+      KeyPair::MultiKeyPair(vault) => Ok(VaultState {
+        fingerprint: vault.fingerprint(),
+        accounts: vault.accounts()?,
+        locked: !vault.is_unlocked(),
+      }),
+    }
+  }
+}
+
+/// The public state of a single vault of the keychain
+#[derive(Clone, Debug, PartialEq)]
+pub struct VaultState {
+  /// The fingerprint of the identity inside the vault
+  pub fingerprint: [u8; 4],
+  /// The accounts derived from the vault.
+  /// This is a list of public accounts, still reported while the vault
+  /// is locked, although they cannot sign until it is unlocked
+  pub accounts: Vec<Account>,
+  /// Whether the vault is locked
+  pub locked: bool,
+}
+
+/// Whether a vault of the keychain is locked or unlocked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultStatus {
+  /// The identity is encrypted, and accounts cannot sign
+  Locked,
+  /// The identity is decrypted, and accounts can sign
+  Unlocked,
+}
+
+impl VaultState {
+  /// Get the fingerprint of the vault as a 0x-prefixed hex string,
+  /// to identify it in support requests and diagnostics
+  pub fn fingerprint_hex(&self) -> String {
+    encode_prefixed(&self.fingerprint)
+  }
+
+  /// Get whether the vault is locked or unlocked
+  pub fn status(&self) -> VaultStatus {
+    match self.locked {
+      true => VaultStatus::Locked,
+      false => VaultStatus::Unlocked,
+    }
+  }
+}
+
+/// On-chain information about an account, as last seen by an account tracker
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AccountSnapshot {
+  /// The balance of the account, in wei
+  pub balance: Option<u128>,
+  /// The number of transactions sent by the account
+  pub nonce: Option<u64>,
+  /// Seconds since the UNIX epoch of the last update
+  pub last_updated: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct KeychainState {
-  /// The accounts in the keychain
-  /// This is a list of public accounts
-  pub accounts: Vec<Account<usize>>,
+  /// The vaults in the keychain, in the same
+  /// order as the keypairs they represent
+  pub vaults: Vec<VaultState>,
+  /// On-chain snapshots of the accounts, indexed by address
+  pub snapshots: BTreeMap<String, AccountSnapshot>,
+  /// User-defined labels of the accounts, indexed by address
+  pub labels: BTreeMap<String, String>,
+  /// The profiles grouping the vaults, each vault belonging to one of them
+  pub profiles: Vec<ProfileState>,
+  /// The name of the profile currently selected
+  pub active_profile: String,
+  /// What the accounts with a spending limit spent on
+  /// their last day of activity, indexed by address
+  pub quota_usage: BTreeMap<String, QuotaUsage>,
+  /// How much the accounts have been used to sign, indexed by address
+  pub usage: BTreeMap<String, AccountUsage>,
+  /// The addresses of the accounts bound to dapps, indexed by normalized origin
+  pub domains: BTreeMap<String, String>,
+}
+
+impl KeychainState {
+  /// Get the on-chain snapshot of the account at `address`, if any
+  pub fn snapshot(&self, address: &str) -> Option<&AccountSnapshot> {
+    self.snapshots.get(address)
+  }
+
+  /// Get the number of vaults in the keychain
+  pub fn vault_count(&self) -> usize {
+    self.vaults.len()
+  }
+
+  /// Get what the account at `address` spent on `day`
+  pub fn quota_usage(&self, address: &str, day: u64) -> QuotaUsage {
+    self
+      .quota_usage
+      .get(address)
+      .copied()
+      .unwrap_or_default()
+      .on(day)
+  }
+
+  /// Check if the keychain is locked, having at
+  /// least one vault and all of them locked
+  pub fn is_locked(&self) -> bool {
+    !self.vaults.is_empty() && self.vaults.iter().all(|vault| vault.locked)
+  }
+
+  /// Get the status of the vault at `index`, if any
+  pub fn vault_status(&self, index: usize) -> Option<VaultStatus> {
+    self.vaults.get(index).map(VaultState::status)
+  }
+
+  /// Get the accounts of all the vaults in the keychain
+  pub fn accounts(&self) -> Vec<&Account> {
+    self
+      .vaults
+      .iter()
+      .flat_map(|vault| vault.accounts.iter())
+      .collect()
+  }
 }
 
 /// A `Keychain` is a collection of keyparis with different capabilities.
@@ -25,29 +189,252 @@ pub struct KeychainState {
 #[derive(Debug)]
 pub struct Keychain<M = HDKey>
 where
-  M: MultiKeyPair<[u8; 32], [u8; 33], usize>,
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
 {
   /// Key pairs handled by the keychain
   key_pairs: Vec<KeyPair<M>>,
   /// An observable wrapper around the keychain state
   store: Observable<KeychainState>,
+  /// A tamper-evident log of the operations performed with the keychain
+  audit_log: AuditLog,
+  /// Deserializers used to recreate identities when unlocking vaults
+  registry: IdentityFactoryRegistry<M>,
+  /// Destinations receiving the encrypted backups of the keychain
+  backup_sinks: Vec<Box<dyn BackupSink>>,
+  /// An optional secret sealing the backups written to the sinks
+  backup_secret: Option<BackupSecret>,
+  /// The PBKDF2 rounds sealed backups are derived with
+  seal_rounds: u32,
+  /// Whether backups are compressed with zstd
+  #[cfg(feature = "compression")]
+  compress_backups: bool,
+  /// The rules enforced before signing
+  policy: SigningPolicy,
+  /// An optional ledger of recently signed payloads
+  ledger: Option<PayloadLedger>,
+  /// An optional pool of threads signing for some accounts
+  signing_pool: Option<SigningPool>,
+  /// The key derivation parameters used when locking vaults
+  kdf: KdfParams,
+  /// The migrations applied to the backup the keychain was restored from
+  migration_report: Option<MigrationReport>,
+  /// The requirements for the passwords locking the keychain
+  password_policy: PasswordPolicy,
+  /// The revision of the keychain, increased by each change to its
+  /// vaults or to the metadata carried by backups
+  revision: u64,
+  /// The revision at which each vault last changed
+  vault_revisions: Vec<u64>,
+  /// The revision at which the profiles, labels or usage last changed
+  metadata_revision: u64,
+  /// The data keys sealed by the last session token issued, if any
+  session: Option<Session>,
+  /// How long session tokens can unlock the keychain
+  session_ttl: Duration,
+  /// Whether the keychain is the draft of a `preview`,
+  /// which cannot sign nor use private keys
+  draft: bool,
 }
 
+/// A `Keychain` holding identities of different types,
+/// like software, hardware or remote identities
+pub type DynKeychain = Keychain<Box<dyn MultiKeyPairDyn>>;
+
 impl<M> Keychain<M>
 where
-  M: MultiKeyPair<[u8; 32], [u8; 33], usize>,
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> + Initializable,
 {
   /// Create a new keychain
   pub fn new() -> Self {
+    Self::with_registry(IdentityFactoryRegistry::with_initializable())
+  }
+
+  /// Restore a `Keychain` from a backup
+  pub fn restore(backup: Vec<u8>, password: &str) -> Result<Self, KeychainError> {
+    Self::restore_with_registry(
+      backup,
+      password,
+      IdentityFactoryRegistry::with_initializable(),
+    )
+  }
+
+  /// Restore a `Keychain` from a backup sealed with `secret`,
+  /// unlocking its vaults with `password`
+  pub fn restore_sealed(
+    sealed: &[u8],
+    secret: &BackupSecret,
+    password: &str,
+  ) -> Result<Self, KeychainError> {
+    Self::restore(unseal_backup(sealed, secret)?, password)
+  }
+}
+
+impl<M> Keychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Create a new keychain recreating identities with the
+  /// deserializers of `registry` when unlocking
+  pub fn with_registry(registry: IdentityFactoryRegistry<M>) -> Self {
     Keychain {
       key_pairs: vec![],
-      store: Observable::new(KeychainState { accounts: vec![] }),
+      store: Observable::new(KeychainState {
+        vaults: vec![],
+        snapshots: BTreeMap::new(),
+        labels: BTreeMap::new(),
+        quota_usage: BTreeMap::new(),
+        usage: BTreeMap::new(),
+        domains: BTreeMap::new(),
+        profiles: vec![ProfileState::new(DEFAULT_PROFILE)],
+        active_profile: DEFAULT_PROFILE.to_string(),
+      }),
+      audit_log: AuditLog::new(),
+      registry,
+      backup_sinks: vec![],
+      backup_secret: None,
+      seal_rounds: DEFAULT_SEAL_ROUNDS,
+      #[cfg(feature = "compression")]
+      compress_backups: false,
+      policy: SigningPolicy::new(),
+      ledger: None,
+      signing_pool: None,
+      kdf: KdfParams::default(),
+      migration_report: None,
+      password_policy: PasswordPolicy::new(),
+      revision: 0,
+      vault_revisions: vec![],
+      metadata_revision: 0,
+      session: None,
+      session_ttl: DEFAULT_SESSION_TTL,
+      draft: false,
     }
   }
 
-  /// Add an existing keypair to the keychain
-  pub fn add_key_pair(&mut self, key_pair: KeyPair<M>) {
+  /// Get the revision of the keychain, increased by each change
+  /// to its vaults, profiles, labels or usage. Pass it to
+  /// `backup_delta` later to export only what changed since
+  pub fn revision(&self) -> u64 {
+    self.revision
+  }
+
+  /// Get the requirements for the passwords locking the keychain
+  pub fn password_policy(&self) -> &PasswordPolicy {
+    &self.password_policy
+  }
+
+  /// Set the requirements for the passwords locking the keychain,
+  /// enforced by `lock` and `change_password`
+  pub fn set_password_policy(&mut self, policy: PasswordPolicy) {
+    self.password_policy = policy;
+  }
+
+  /// Get the migrations applied to the backup the keychain was
+  /// restored from, or `None` if it was not restored from a backup
+  pub fn migration_report(&self) -> Option<&MigrationReport> {
+    self.migration_report.as_ref()
+  }
+
+  /// Get the key derivation parameters used when locking vaults
+  pub fn kdf_params(&self) -> KdfParams {
+    self.kdf
+  }
+
+  /// Set the key derivation parameters used when locking
+  /// the vaults of the keychain, and the ones created later
+  pub fn set_kdf_params(&mut self, kdf: KdfParams) {
+    self.kdf = kdf;
+    self
+      .key_pairs
+      .iter_mut()
+      .for_each(|key_pair| match key_pair {
+        KeyPair::MultiKeyPair(vault) => vault.set_kdf_params(kdf),
+      });
+  }
+
+  /// Get the signing policy of the keychain
+  pub fn policy(&self) -> &SigningPolicy {
+    &self.policy
+  }
+
+  /// Get the mutable signing policy of the keychain.
+  /// The signing pool, if running, is stopped
+  pub fn policy_mut(&mut self) -> &mut SigningPolicy {
+    self.stop_signing_pool();
+    &mut self.policy
+  }
+
+  /// Replace the signing policy of the keychain.
+  /// The signing pool, if running, is stopped
+  pub fn set_policy(&mut self, policy: SigningPolicy) {
+    self.stop_signing_pool();
+    self.policy = policy;
+  }
+
+  /// Remember recently signed payloads, to warn about
+  /// or refuse signing the same payload twice.
+  /// The signing pool, if running, is stopped
+  pub fn set_payload_ledger(&mut self, ledger: Option<PayloadLedger>) {
+    self.stop_signing_pool();
+    self.ledger = ledger;
+  }
+
+  /// Get the ledger of recently signed payloads, if enabled
+  pub fn payload_ledger_mut(&mut self) -> Option<&mut PayloadLedger> {
+    self.ledger.as_mut()
+  }
+
+  /// Add a sink receiving the encrypted backup of the keychain
+  /// each time it is locked or backed up
+  pub fn add_backup_sink<S>(&mut self, sink: S)
+  where
+    S: BackupSink + 'static,
+  {
+    self.backup_sinks.push(Box::new(sink));
+  }
+
+  /// Seal the backups written to the sinks with `secret`, so that they
+  /// can only be restored by someone knowing it. With `None`, sinks
+  /// receive backups protected by the keychain password only
+  pub fn set_backup_secret(&mut self, secret: Option<BackupSecret>) {
+    self.backup_secret = secret;
+  }
+
+  /// Get the PBKDF2 rounds sealed backups are derived with
+  pub fn seal_rounds(&self) -> u32 {
+    self.seal_rounds
+  }
+
+  /// Set the PBKDF2 rounds sealed backups are derived with, independently
+  /// from the rounds locking the vaults. Fails if the rounds are out of
+  /// `MIN_SEAL_ROUNDS..=MAX_SEAL_ROUNDS`
+  pub fn set_seal_rounds(&mut self, rounds: u32) -> Result<(), KeychainError> {
+    check_seal_rounds(rounds)?;
+    self.seal_rounds = rounds;
+
+    Ok(())
+  }
+
+  /// Compress the backups of the keychain with zstd. Compressed
+  /// backups are restored transparently, by keychains built
+  /// with the `compression` feature only
+  #[cfg(feature = "compression")]
+  pub fn set_backup_compression(&mut self, enabled: bool) {
+    self.compress_backups = enabled;
+  }
+
+  /// Add an existing keypair to the active profile of the keychain
+  pub fn add_key_pair(&mut self, key_pair: KeyPair<M>) -> Result<(), KeychainError> {
+    let vault_state = key_pair.to_state()?;
+    let index = self.key_pairs.len();
     self.key_pairs.push(key_pair);
+    self.touch_vault(index);
+    self.update_state(move |state| {
+      state.vaults.push(vault_state.clone());
+      let active_profile = state.active_profile.clone();
+      state.assign_vault(index, &active_profile);
+    })?;
+
+    Ok(())
   }
 
   /// Add a new `KeyPair` to the `Keychain` with multiple
@@ -56,8 +443,9 @@ where
   where
     F: FnOnce(A) -> Result<M, Box<dyn IdentityError>>,
   {
-    let keypair = KeyPair::MultiKeyPair(Vault::new(factory, args)?);
-    self.key_pairs.push(keypair);
+    let mut vault = Vault::new(factory, args)?;
+    vault.set_kdf_params(self.kdf);
+    self.add_key_pair(KeyPair::MultiKeyPair(vault))?;
 
     match self.key_pairs.last().unwrap() {
       KeyPair::MultiKeyPair(vault) => Ok(vault.get_identity()?),
@@ -69,148 +457,2172 @@ where
     self.key_pairs.get(at_index)
   }
 
+  /// Get the number of vaults in the keychain
+  pub fn vault_count(&self) -> usize {
+    self.key_pairs.len()
+  }
+
+  /// Check if the keychain is locked, having at
+  /// least one vault and all of them locked
+  pub fn is_locked(&self) -> bool {
+    !self.key_pairs.is_empty()
+      && self.key_pairs.iter().all(|key_pair| match key_pair {
+        KeyPair::MultiKeyPair(vault) => !vault.is_unlocked(),
+      })
+  }
+
+  /// Get the status of the vault at `index`
+  pub fn vault_status(&self, index: usize) -> Result<VaultStatus, KeychainError> {
+    match self.key_pairs.get(index) {
+      Some(KeyPair::MultiKeyPair(vault)) if vault.is_unlocked() => Ok(VaultStatus::Unlocked),
+      Some(KeyPair::MultiKeyPair(_)) => Ok(VaultStatus::Locked),
+      None => Err(KeychainError::KeyNotFoundForIndex(index)),
+    }
+  }
+
+  /// Get the name of the profile currently selected
+  pub fn active_profile(&self) -> &str {
+    &self.store.get_state().active_profile
+  }
+
+  /// Create a new empty profile named `name`
+  pub fn create_profile(&mut self, name: &str) -> Result<(), KeychainError> {
+    if self.store.get_state().profile(name).is_some() {
+      return Err(KeychainError::ProfileAlreadyExists(name.to_string()));
+    }
+
+    let profile = ProfileState::new(name);
+    self.update_state(move |state| {
+      state.profiles.push(profile.clone());
+    })?;
+
+    Ok(())
+  }
+
+  /// Select the profile named `name`, receiving the vaults added later
+  pub fn switch_profile(&mut self, name: &str) -> Result<(), KeychainError> {
+    self.profile_indexes(name)?;
+
+    let name = name.to_string();
+    self.update_state(move |state| {
+      state.active_profile = name.clone();
+    })?;
+
+    Ok(())
+  }
+
+  /// Move the vault at `index` to the profile named `name`
+  pub fn move_vault(&mut self, index: usize, name: &str) -> Result<(), KeychainError> {
+    if index >= self.key_pairs.len() {
+      return Err(KeychainError::KeyNotFoundForIndex(index));
+    }
+    self.profile_indexes(name)?;
+
+    let name = name.to_string();
+    self.update_state(move |state| {
+      state.assign_vault(index, &name);
+    })?;
+
+    Ok(())
+  }
+
+  /// Get the indexes of the vaults in the profile named `name`
+  fn profile_indexes(&self, name: &str) -> Result<Vec<usize>, KeychainError> {
+    self
+      .store
+      .get_state()
+      .profile(name)
+      .map(|profile| profile.vaults.clone())
+      .ok_or(KeychainError::ProfileNotFound(name.to_string()))
+  }
+
+  /// Update the state of the keychain, increasing the revision
+  /// if the metadata carried by backups changed
+  fn update_state<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  where
+    F: Fn(&mut KeychainState),
+  {
+    let metadata = self.store.get_state().delta_metadata();
+    self.store.update(updater)?;
+    self.track_metadata(&metadata);
+
+    Ok(())
+  }
+
+  /// Increase the revision if the metadata carried by
+  /// backups changed from `previous`
+  fn track_metadata(&mut self, previous: &serde_json::Value) {
+    if self.store.get_state().delta_metadata() != *previous {
+      self.revision += 1;
+      self.metadata_revision = self.revision;
+    }
+  }
+
+  /// Increase the revision of the keychain, marking
+  /// the vault at `index` as changed
+  fn touch_vault(&mut self, index: usize) {
+    self.revision += 1;
+    if self.vault_revisions.len() <= index {
+      self.vault_revisions.resize(index + 1, 0);
+    }
+    self.vault_revisions[index] = self.revision;
+  }
+
   /// Get a mutable identity from the keychain
   pub fn get_keypair_mut(&mut self, at_index: usize) -> Option<&mut KeyPair<M>> {
+    if at_index < self.key_pairs.len() {
+      self.touch_vault(at_index);
+    }
     self.key_pairs.get_mut(at_index)
   }
 
-  /// Lock the keychain
-  /// This will lock all the internal vaults, removing all
-  /// private keys from memory
-  pub fn lock(&mut self, password: &str) -> Result<(), KeychainError>
-  where
-    M: Initializable,
-  {
-    self.store.update(|state| {
-      state.accounts = vec![];
+  /// Get the audit log of the keychain
+  pub fn audit_log(&self) -> &AuditLog {
+    &self.audit_log
+  }
+
+  /// Derive a new account from the keypair at `key_pair_index`
+  /// and add it to the keychain state
+  #[instrument(level = "debug", skip(self), err)]
+  pub fn add_account(&mut self, key_pair_index: usize) -> Result<Account, KeychainError> {
+    let account = match self.key_pairs.get_mut(key_pair_index) {
+      Some(KeyPair::MultiKeyPair(vault)) => vault.add_key()?,
+      None => return Err(KeychainError::KeyNotFoundForIndex(key_pair_index)),
+    };
+    self.touch_vault(key_pair_index);
+
+    let new_account = account.clone();
+    self.update_state(move |state| {
+      state.vaults[key_pair_index]
+        .accounts
+        .push(new_account.clone());
     })?;
 
-    Ok(
-      self
-        .key_pairs
-        .iter_mut()
-        .try_for_each(|keypair| match keypair {
-          KeyPair::MultiKeyPair(vault) => vault.lock(password.as_bytes()),
-        })?,
-    )
+    Ok(account)
   }
 
-  /// Unlock the keychain
-  pub fn unlock(&mut self, password: &str) -> Result<(), KeychainError>
-  where
-    M: Initializable,
-  {
-    Ok(
-      self
-        .key_pairs
-        .iter_mut()
-        .try_for_each(|key_pair| match key_pair {
-          KeyPair::MultiKeyPair(vault) => vault.unlock(password.as_bytes()),
-        })?,
-    )
+  /// Derive the account at `index` from the keypair at `key_pair_index`
+  /// and add it to the keychain state, without deriving the previous ones
+  #[instrument(level = "debug", skip(self), err)]
+  pub fn add_account_at(
+    &mut self,
+    key_pair_index: usize,
+    index: usize,
+  ) -> Result<Account, KeychainError> {
+    let key_pair = self
+      .key_pairs
+      .get_mut(key_pair_index)
+      .ok_or(KeychainError::KeyNotFoundForIndex(key_pair_index))?;
+    let account = match key_pair {
+      KeyPair::MultiKeyPair(vault) => vault.add_key_at(index)?,
+    };
+
+    // The vault state is recreated to keep accounts sorted by index
+    let vault_state = key_pair.to_state()?;
+    self.touch_vault(key_pair_index);
+    self.update_state(move |state| {
+      state.vaults[key_pair_index] = vault_state.clone();
+    })?;
+
+    Ok(account)
   }
 
-  /// Backup the `Keychain` serializing all the keypairs to bytes and encrypting them
-  pub fn backup(&mut self, password: &str) -> Result<Vec<u8>, KeychainError>
-  where
-    M: Initializable,
-  {
-    let mut bytes_matrix = self
+  /// Get the account bound to the dapp at `origin`, deriving it from the
+  /// keypair at `key_pair_index` at an index given by the origin hash.
+  /// The same origin always gets the same account from a seed, and the
+  /// binding is recorded in the keychain state
+  pub fn domain_account(
+    &mut self,
+    key_pair_index: usize,
+    origin: &str,
+  ) -> Result<Account, KeychainError> {
+    let account = self.add_account_at(key_pair_index, domain_account_index(origin))?;
+
+    let origin = normalize_origin(origin);
+    let address = account.address.clone();
+    self.update_state(move |state| {
+      state.domains.insert(origin.clone(), address.clone());
+    })?;
+
+    Ok(account)
+  }
+
+  /// Derive `count` accounts at consecutive indexes starting from `start`
+  /// from the keypair at `key_pair_index`, and add them to the keychain state
+  #[instrument(level = "debug", skip(self), err)]
+  pub fn derive_range(
+    &mut self,
+    key_pair_index: usize,
+    start: usize,
+    count: usize,
+  ) -> Result<Vec<Account>, KeychainError> {
+    let key_pair = self
       .key_pairs
-      .iter_mut()
-      .map(|key_pair| match key_pair {
-        KeyPair::MultiKeyPair(vault) => {
-          if vault.is_unlocked() {
-            vault.lock(password.as_bytes())?;
-            let bytes = vault.to_bytes()?;
-            vault.unlock(password.as_bytes())?;
-            // 0u8 is a byte representation of a MultiKeyPair
-            return Ok((0u8, bytes));
-          }
+      .get_mut(key_pair_index)
+      .ok_or(KeychainError::KeyNotFoundForIndex(key_pair_index))?;
+    let accounts = match key_pair {
+      KeyPair::MultiKeyPair(vault) => vault.add_keys_range(start, count)?,
+    };
 
-          // 0u8 is a byte representation of a MultiKeyPair
-          Ok((0u8, vault.to_bytes()?))
-        }
-      })
-      .collect::<Result<Vec<(u8, Vec<u8>)>, VaultError>>()?;
+    let vault_state = key_pair.to_state()?;
+    self.touch_vault(key_pair_index);
+    self.update_state(move |state| {
+      state.vaults[key_pair_index] = vault_state.clone();
+    })?;
 
-    let mut condensed: Vec<u8> = vec![];
-    bytes_matrix
-      .iter_mut()
-      .try_for_each(|(vault_type, bytes)| {
-        let length = u8::try_from(bytes.len()).or(Err(KeychainError::ByteSerializationError))?;
-        // The length of the bytes is prepended to the type of vault
-        condensed.append(&mut [length].to_vec());
-        // The type of vault is prepended to the bytes
-        condensed.append(&mut [*vault_type].to_vec());
-        condensed.append(bytes);
-        Ok::<(), KeychainError>(())
-      })?;
+    Ok(accounts)
+  }
 
-    Ok(condensed)
+  /// Update the on-chain snapshot of the account at `address`,
+  /// notifying the subscribers of the keychain state.
+  /// Meant to be called by account trackers
+  pub fn set_account_snapshot(
+    &mut self,
+    address: &str,
+    balance: Option<u128>,
+    nonce: Option<u64>,
+  ) -> Result<(), KeychainError> {
+    if !self
+      .store
+      .get_state()
+      .accounts()
+      .iter()
+      .any(|account| account.address == address)
+    {
+      return Err(KeychainError::KeyNotFoundForAddress(address.to_string()));
+    }
+
+    let address = address.to_string();
+    let snapshot = AccountSnapshot {
+      balance,
+      nonce,
+      last_updated: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .ok(),
+    };
+    self.update_state(move |state| {
+      state.snapshots.insert(address.clone(), snapshot);
+    })?;
+
+    Ok(())
   }
 
-  /// Restore a `Keychain` from a backup
-  pub fn restore(backup: Vec<u8>, password: &str) -> Result<Self, KeychainError>
+  /// Get an ethers-rs wallet holding the private key of the account
+  /// matching `address`, to sign with an existing ethers-rs stack.
+  /// The vault holding the account must be unlocked
+  #[cfg(feature = "ethers")]
+  pub fn local_wallet(
+    &self,
+    address: &str,
+  ) -> Result<identity::signer::ethers::LocalWallet, KeychainError> {
+    let private_key = self.account_private_key(address)?;
+
+    identity::signer::ethers::LocalWallet::from_bytes(&private_key)
+      .or(Err(VaultError::KeyDerivation.into()))
+  }
+
+  /// Get the EIP-1024 encryption public key of the account matching
+  /// `address`, as returned by `eth_getEncryptionPublicKey`.
+  /// The vault holding the account must be unlocked
+  pub fn encryption_public_key(&self, address: &str) -> Result<String, KeychainError> {
+    let mut private_key = self.account_private_key(address)?;
+    let public_key = encryption_public_key(&private_key);
+    private_key.fill(0);
+
+    Ok(public_key)
+  }
+
+  /// Decrypt data encrypted for the account matching `address` with
+  /// its encryption public key, like `eth_decrypt`. The encrypted data
+  /// is either its JSON representation or the hex encoding of it
+  pub fn decrypt(&mut self, address: &str, encrypted_data: &str) -> Result<String, KeychainError> {
+    let encrypted_data = encrypted_data.parse::<EncryptedData>()?;
+    let mut private_key = self.account_private_key(address)?;
+    let data = encrypted_data.decrypt(&private_key);
+    private_key.fill(0);
+    let data = data?;
+    let (_, account) = self.find_account(address)?;
+    self
+      .audit_log
+      .record(AuditEvent::Decrypt, Some(&account), None);
+
+    String::from_utf8(data.to_vec()).or(Err(
+      EncryptionError::InvalidEncryptedData("Decrypted data is not UTF-8".to_string()).into(),
+    ))
+  }
+
+  /// Reveal the private key of the account matching `address` for `ttl`,
+  /// to show it to the user or copy it to the clipboard. The reveal is
+  /// recorded in the audit log. The vault holding the account must be unlocked
+  pub fn reveal_private_key(
+    &mut self,
+    address: &str,
+    ttl: Duration,
+  ) -> Result<RevealedSecret, KeychainError> {
+    let mut private_key = self.account_private_key(address)?;
+    let secret = SecureBytes::new(&private_key);
+    private_key.fill(0);
+    let (_, account) = self.find_account(address)?;
+    self
+      .audit_log
+      .record(AuditEvent::Reveal, Some(&account), None);
+
+    Ok(RevealedSecret::new(secret, ttl))
+  }
+
+  /// Get the private key of the account matching `address`.
+  /// The vault holding the account must be unlocked
+  fn account_private_key(&self, address: &str) -> Result<[u8; 32], KeychainError> {
+    self.ensure_not_draft()?;
+    let (key_pair_index, account) = self.find_account(address)?;
+    self.ensure_unlocked(key_pair_index, &account)?;
+
+    match &self.key_pairs[key_pair_index] {
+      KeyPair::MultiKeyPair(vault) => Ok(
+        vault
+          .get_identity()?
+          .private_key_at(account.path)
+          .or(Err(VaultError::KeyDerivation))?,
+      ),
+    }
+  }
+
+  /// Set the label of the account at `address`, or remove it with `None`
+  pub fn set_account_label(
+    &mut self,
+    address: &str,
+    label: Option<&str>,
+  ) -> Result<(), KeychainError> {
+    self.find_account(address)?;
+
+    let address = address.to_string();
+    let label = label.map(str::to_string);
+    self.update_state(move |state| match &label {
+      Some(label) => {
+        state.labels.insert(address.clone(), label.clone());
+      }
+      None => {
+        state.labels.remove(&address);
+      }
+    })?;
+
+    Ok(())
+  }
+
+  /// Export the addresses, labels, derivation paths and tracked balances
+  /// of all the accounts, for reporting tools. No secret material is
+  /// included, so accounts of locked vaults are exported too
+  pub fn export_accounts(&self, format: ExportFormat) -> String {
+    self.store.get_state().export_accounts(format)
+  }
+
+  /// Sign a message with the account matching `address`.
+  /// Byte slices and strings are digested with keccak256, while
+  /// other types provide their own digest through `IntoSignable`
+  pub fn use_signer<S>(
+    &mut self,
+    address: String,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
   where
-    M: Initializable,
+    S: IntoSignable + ?Sized,
   {
-    let mut keychain = Keychain::<M> {
-      key_pairs: vec![],
-      store: Observable::new(KeychainState { accounts: vec![] }),
-    };
-    // Loop through the bytes and deserialize the vaults
-    let mut bytes = backup.clone();
-    while !bytes.is_empty() {
-      // Each vault has a byte to represent the size
-      let length = usize::try_from(bytes[0]).or(Err(KeychainError::ByteDeserializationError(
-        "Error casting bytes vector length to usize".to_string(),
-      )))?;
-      // And one to represent its type
-      let key_pair_type = bytes[1];
+    self.use_signer_with_context(address, message, options, &SigningContext::default())
+  }
 
-      match key_pair_type {
-        0u8 => {
-          let key_pair_bytes = bytes[2..(length + 2)].to_vec();
-          let key_pair = KeyPair::MultiKeyPair(Vault::<M>::try_from(key_pair_bytes)?);
+  /// Sign a Sign-In with Ethereum message as a personal message (EIP-191)
+  /// with the account matching its address, producing a recoverable signature
+  pub fn sign_siwe(&mut self, message: &SiweMessage) -> Result<Signature, KeychainError> {
+    self.use_signer(
+      message.address.to_lowercase(),
+      message,
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+    )
+  }
 
-          keychain.add_key_pair(key_pair);
-        }
-        unsupported => {
-          return Err(KeychainError::ByteDeserializationError(format!(
-            "Unsupported key pair type: {}",
-            unsupported
-          )))
-        }
-      }
+  /// Sign EIP-712 typed data with the account matching `address`,
+  /// producing a recoverable signature like `eth_signTypedData_v4`
+  pub fn sign_typed_data(
+    &mut self,
+    address: &str,
+    typed_data: &TypedData,
+  ) -> Result<Signature, KeychainError> {
+    let context = SigningContext {
+      chain_id: typed_data.chain_id(),
+      ..Default::default()
+    };
 
-      bytes = bytes[(length + 2)..].to_vec();
-    }
+    self.use_signer_with_context(
+      address.to_lowercase(),
+      &typed_data.signing_bytes()?,
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+      &context,
+    )
+  }
 
-    keychain.unlock(password)?;
+  /// Sign an EIP-1559 transaction with the account matching its sender,
+  /// after checking the signing policy against it. Returns the signed
+  /// transaction with its raw encoding and hash, ready to be broadcast
+  #[cfg(feature = "rpc")]
+  pub fn sign_transaction(
+    &mut self,
+    transaction: &crate::RpcTransaction,
+  ) -> Result<crate::SignedRpcTransaction, KeychainError> {
+    let signature = self.use_signer_with_context(
+      transaction.from.clone(),
+      &transaction.signing_bytes(),
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+      &transaction.context(),
+    )?;
 
-    Ok(keychain)
+    Ok(
+      crate::SignedRpcTransaction::new(transaction.clone(), &signature)
+        .map_err(VaultError::from)?,
+    )
   }
-}
 
-impl Controller<KeychainState, KeychainError> for Keychain {
-  /// Get the state of the keychain
-  fn get_state(&self) -> &KeychainState {
-    self.store.get_state()
+  /// Sign an ERC-2771 forward request with the account matching its sender,
+  /// to be executed by `forwarder` on behalf of the account
+  pub fn sign_forward_request(
+    &mut self,
+    forwarder: &Forwarder,
+    request: ForwardRequest,
+  ) -> Result<SignedForwardRequest, KeychainError> {
+    let signature = self.sign_typed_data(&request.from, &request.to_typed_data(forwarder)?)?;
+
+    Ok(SignedForwardRequest {
+      forwarder: forwarder.clone(),
+      request,
+      signature,
+    })
   }
 
-  /// Update the state of the keychain
-  fn update<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  /// Sign an ERC-2771 forward request and submit it to `relayer`,
+  /// returning the identifier of the relayed transaction
+  pub fn relay_call<R>(
+    &mut self,
+    relayer: &mut R,
+    forwarder: &Forwarder,
+    request: ForwardRequest,
+  ) -> Result<String, KeychainError>
   where
-    F: Fn(&mut KeychainState),
+    R: Relayer + ?Sized,
   {
-    Ok(self.store.update(updater)?)
+    let signed = self.sign_forward_request(forwarder, request)?;
+
+    relayer.relay(&signed)
+  }
+
+  /// Sign an ERC-2612 permit with its owner account.
+  /// Fails if the deadline of the permit has passed
+  pub fn sign_permit(&mut self, permit: &Permit) -> Result<Signature, KeychainError> {
+    self.sign_typed_data(&permit.owner, &permit.to_typed_data()?)
+  }
+
+  /// Sign a Permit2 approval with the account matching `owner`.
+  /// Fails if the signature deadline of the approval has passed
+  pub fn sign_permit2(
+    &mut self,
+    owner: &str,
+    permit: &Permit2,
+  ) -> Result<Signature, KeychainError> {
+    self.sign_typed_data(owner, &permit.to_typed_data()?)
+  }
+
+  /// Sign an EIP-7702 authorization with the account matching `address`,
+  /// delegating its code to the contract of the authorization
+  pub fn sign_authorization(
+    &mut self,
+    address: &str,
+    authorization: &Authorization,
+  ) -> Result<SignedAuthorization, KeychainError> {
+    let context = SigningContext {
+      chain_id: Some(authorization.chain_id).filter(|chain_id| *chain_id != 0),
+      ..Default::default()
+    };
+    let signature = self.use_signer_with_context(
+      address.to_lowercase(),
+      authorization,
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+      &context,
+    )?;
+
+    Ok(SignedAuthorization::new(*authorization, &signature).map_err(VaultError::from)?)
+  }
+
+  /// Answer an EIP-4527 signing request scanned from a hot wallet, acting
+  /// as an offline signer. The signing account is looked up by the request
+  /// address, or by its derivation path when no address is given.
+  /// Transactions are decoded, so that the signing policy applies to them
+  pub fn sign_ur_request(
+    &mut self,
+    request: &EthSignRequest,
+  ) -> Result<EthSignature, KeychainError> {
+    let address = match request.address_hex() {
+      Some(address) => address,
+      None => self
+        .get_state()
+        .accounts()
+        .into_iter()
+        .find(|account| account.path == request.path)
+        .map(|account| account.address.clone())
+        .ok_or(KeychainError::KeyNotFoundForAddress(
+          request.path.to_string(),
+        ))?,
+    };
+
+    // Fail before signing if `v` cannot be encoded for any recovery id
+    request.v(3)?;
+    let signature = self.use_signer_with_context(
+      address,
+      &request.signable_bytes()?,
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+      &request.context()?,
+    )?;
+
+    // `v` is appended with its minimal big-endian encoding, as it
+    // exceeds a single byte for legacy transactions on large chain ids
+    let v = request
+      .v(signature.recovery_id().unwrap_or_default())?
+      .to_be_bytes();
+    let mut bytes = signature.to_compact().to_vec();
+    bytes.extend(&v[v.iter().position(|byte| *byte != 0).unwrap_or(7)..]);
+
+    Ok(EthSignature {
+      request_id: request.request_id,
+      signature: bytes,
+      origin: None,
+    })
+  }
+
+  /// Sign a message with the account matching `address`, after
+  /// checking the signing policy against what the message is about
+  pub fn use_signer_with_context<S>(
+    &mut self,
+    address: String,
+    message: &S,
+    options: &SignatureOptions,
+    context: &SigningContext,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let (key_pair_index, account) = self.find_account(&address)?;
+
+    self.sign_for_account(
+      key_pair_index,
+      &account,
+      &message.to_signable(),
+      options,
+      context,
+    )
+  }
+
+  /// Check a request to sign `message` with `account` against the
+  /// lock status of its vault, the signing policy and the ledger
+  fn authorize_account(
+    &mut self,
+    key_pair_index: usize,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(), KeychainError> {
+    self.ensure_not_draft()?;
+    self.ensure_unlocked(key_pair_index, account)?;
+    self
+      .check_policy(account, message, context)
+      .inspect_err(|_| metrics::policy_rejection())
+  }
+
+  /// Check a request to sign `message` with `account` against
+  /// the signing policy, the daily quota and the ledger
+  fn check_policy(
+    &mut self,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(), KeychainError> {
+    self.policy.check(account, context)?;
+    let usage = self
+      .store
+      .get_state()
+      .quota_usage(&account.address, current_day());
+    self.policy.check_quota(account, context, &usage)?;
+    self.check_duplicate(&account.address, message)
+  }
+
+  /// Fail with `KeychainError::Locked` if the vault holding `account` is locked.
+  /// Accounts of locked vaults are still known, but they cannot sign
+  fn ensure_unlocked(&self, key_pair_index: usize, account: &Account) -> Result<(), KeychainError> {
+    match self.vault_status(key_pair_index)? {
+      VaultStatus::Locked => Err(KeychainError::Locked(account.address.clone())),
+      VaultStatus::Unlocked => Ok(()),
+    }
+  }
+
+  /// Fail with `KeychainError::DraftRestricted` if the keychain is the
+  /// draft of a `preview`, whose records may be discarded with it
+  fn ensure_not_draft(&self) -> Result<(), KeychainError> {
+    match self.draft {
+      true => Err(KeychainError::DraftRestricted),
+      false => Ok(()),
+    }
+  }
+
+  /// Find the account matching `address`, with the
+  /// index of the keypair holding it
+  fn find_account(&self, address: &str) -> Result<(usize, Account), KeychainError> {
+    self
+      .store
+      .get_state()
+      .vaults
+      .iter()
+      .enumerate()
+      .find_map(|(index, vault)| {
+        vault
+          .accounts
+          .iter()
+          .find(|account| account.address == address)
+          .map(|account| (index, account.clone()))
+      })
+      .ok_or(KeychainError::KeyNotFoundForAddress(address.to_string()))
+  }
+
+  /// Authorize, sign and record a signature with an already resolved account
+  #[instrument(
+    level = "debug",
+    skip_all,
+    err(level = "warn"),
+    fields(address = %account.address)
+  )]
+  pub(crate) fn sign_for_account(
+    &mut self,
+    key_pair_index: usize,
+    account: &Account,
+    message: &Signable,
+    options: &SignatureOptions,
+    context: &SigningContext,
+  ) -> Result<Signature, KeychainError> {
+    self.authorize_account(key_pair_index, account, message, context)?;
+    let signature = self.sign_authorized(key_pair_index, account, message, options)?;
+    self.record_signature(account, message, context)?;
+
+    Ok(signature)
+  }
+
+  /// Resolve the account matching `address` into an `AccountId`
+  pub fn account_id(&self, address: &str) -> Result<AccountId, KeychainError> {
+    let (key_pair_index, account) = self.find_account(address)?;
+
+    Ok(AccountId {
+      key_pair_index,
+      path: account.path,
+    })
+  }
+
+  /// Get the account identified by `id`
+  pub fn account(&self, id: AccountId) -> Result<&Account, KeychainError> {
+    self
+      .store
+      .get_state()
+      .vaults
+      .get(id.key_pair_index)
+      .ok_or(KeychainError::KeyNotFoundForIndex(id.key_pair_index))?
+      .accounts
+      .iter()
+      .find(|account| account.path == id.path)
+      .ok_or(KeychainError::KeyNotFoundForAddress(id.path.to_string()))
+  }
+
+  /// Sign a message with `account`, which must belong to the keychain
+  pub fn use_signer_for<S>(
+    &mut self,
+    account: &Account,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let id = self.account_id(&account.address)?;
+
+    self.use_signer_by_id(id, message, options)
+  }
+
+  /// Sign a message with the account identified by `id`
+  pub fn use_signer_by_id<S>(
+    &mut self,
+    id: AccountId,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let account = self.account(id)?.clone();
+
+    self.sign_for_account(
+      id.key_pair_index,
+      &account,
+      &message.to_signable(),
+      options,
+      &SigningContext::default(),
+    )
+  }
+
+  /// Get a signer bound to the account identified by `id`, for repeated use
+  pub fn signer(&mut self, id: AccountId) -> Result<AccountSigner<'_, M>, KeychainError> {
+    let account = self.account(id)?.clone();
+
+    Ok(AccountSigner {
+      keychain: self,
+      id,
+      account,
+    })
+  }
+
+  /// Sign `message` with an account already authorized with
+  /// `authorize_account`
+  pub(crate) fn sign_authorized(
+    &self,
+    key_pair_index: usize,
+    account: &Account,
+    message: &Signable,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError> {
+    match self.key_pairs.get(key_pair_index) {
+      Some(KeyPair::MultiKeyPair(vault)) => Ok(vault.sign(account, message, options)?),
+      None => Err(KeychainError::KeyNotFoundForIndex(key_pair_index)),
+    }
+  }
+
+  /// Record a signature of `message` in the audit log and the ledger,
+  /// in the usage of the account, and count the signed transaction
+  /// in the quota of the account
+  pub(crate) fn record_signature(
+    &mut self,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(), KeychainError> {
+    if let Some(ledger) = &mut self.ledger {
+      ledger.record(message);
+    }
+    let quota_usage = self.quota_usage_after(account, context);
+
+    self.record_usage(account, message, context, quota_usage)
+  }
+
+  /// Authorize a request to sign `message` with the account matching
+  /// `address`, and reserve its entry in the ledger and its transaction
+  /// in the quota of the account, so that concurrent requests take them
+  /// into account before it is signed. The reservation is either
+  /// recorded with `record_reserved_signature` once signed, or released
+  /// with `release_signature` if signing fails
+  pub(crate) fn reserve_signature(
+    &mut self,
+    address: &str,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(usize, Account), KeychainError> {
+    let (key_pair_index, account) = self.find_account(address)?;
+    self.authorize_account(key_pair_index, &account, message, context)?;
+
+    if let Some(ledger) = &mut self.ledger {
+      ledger.record(message);
+    }
+    if let Some(quota_usage) = self.quota_usage_after(&account, context) {
+      let address = account.address.clone();
+      self.update_state(move |state| {
+        state.quota_usage.insert(address.clone(), quota_usage);
+      })?;
+    }
+
+    Ok((key_pair_index, account))
+  }
+
+  /// Record a signature reserved with `reserve_signature`
+  /// in the audit log and in the usage of the account
+  pub(crate) fn record_reserved_signature(
+    &mut self,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(), KeychainError> {
+    self.record_usage(account, message, context, None)
+  }
+
+  /// Release a reservation of `reserve_signature` that was not signed
+  pub(crate) fn release_signature(
+    &mut self,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+  ) -> Result<(), KeychainError> {
+    if let Some(ledger) = &mut self.ledger {
+      ledger.forget(message);
+    }
+    let quota_usage = match &context.transaction {
+      Some(transaction) if self.policy.spending_limit(&account.address).is_some() => self
+        .store
+        .get_state()
+        .quota_usage(&account.address, current_day())
+        .without(transaction),
+      _ => return Ok(()),
+    };
+
+    let address = account.address.clone();
+    self.update_state(move |state| {
+      state.quota_usage.insert(address.clone(), quota_usage);
+    })
+  }
+
+  /// Get the quota usage of `account` after signing the transaction of
+  /// `context`, or `None` if it is not a transaction or not limited
+  fn quota_usage_after(&self, account: &Account, context: &SigningContext) -> Option<QuotaUsage> {
+    match &context.transaction {
+      Some(transaction) if self.policy.spending_limit(&account.address).is_some() => Some(
+        self
+          .store
+          .get_state()
+          .quota_usage(&account.address, current_day())
+          .with(transaction),
+      ),
+      _ => None,
+    }
+  }
+
+  /// Record a signature of `message` in the audit log and in the usage
+  /// of the account, replacing its quota usage with `quota_usage` if set
+  fn record_usage(
+    &mut self,
+    account: &Account,
+    message: &Signable,
+    context: &SigningContext,
+    quota_usage: Option<QuotaUsage>,
+  ) -> Result<(), KeychainError> {
+    self
+      .audit_log
+      .record(AuditEvent::Sign, Some(account), Some(message.digest()));
+    metrics::signature("keychain");
+
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
+    let usage = self
+      .store
+      .get_state()
+      .account_usage(&account.address)
+      .with_signature(timestamp, context.chain_id);
+
+    let address = account.address.clone();
+    self.update_state(move |state| {
+      state.usage.insert(address.clone(), usage.clone());
+      if let Some(quota_usage) = quota_usage {
+        state.quota_usage.insert(address.clone(), quota_usage);
+      }
+    })?;
+
+    Ok(())
+  }
+
+  /// Start a pool of threads signing for the accounts matching
+  /// `addresses`, replacing the running one if any.
+  /// Private keys are derived once, so the keychain must be unlocked.
+  /// The pool is drained when the keychain is locked, or when a signing
+  /// policy or a payload ledger is set, since its signatures bypass them
+  pub fn start_signing_pool(
+    &mut self,
+    addresses: &[String],
+  ) -> Result<SigningPoolHandle, KeychainError> {
+    self.stop_signing_pool();
+    self.ensure_not_draft()?;
+    if !self.policy.is_permissive() || self.ledger.is_some() {
+      return Err(KeychainError::SigningPoolRestricted);
+    }
+
+    let accounts = addresses
+      .iter()
+      .map(|address| {
+        let (key_pair_index, account) = self.find_account(address)?;
+        self.ensure_unlocked(key_pair_index, &account)?;
+        let mut private_key = match &self.key_pairs[key_pair_index] {
+          KeyPair::MultiKeyPair(vault) => vault
+            .get_identity()?
+            .private_key_at(account.path)
+            .or(Err(VaultError::KeyDerivation))?,
+        };
+        let secret = SecureBytes::new(&private_key);
+        private_key.fill(0);
+
+        Ok((account, secret))
+      })
+      .collect::<Result<Vec<(Account, SecureBytes)>, KeychainError>>()?;
+
+    let pool = SigningPool::start(accounts)?;
+    pool.handle().addresses().into_iter().for_each(|address| {
+      let account = self.find_account(address).map(|(_, account)| account).ok();
+      self
+        .audit_log
+        .record(AuditEvent::SigningPool, account.as_ref(), None);
+    });
+    let handle = pool.handle();
+    self.signing_pool = Some(pool);
+
+    Ok(handle)
+  }
+
+  /// Stop the signing pool, if running, after signing
+  /// all the requests already sent to it
+  pub fn stop_signing_pool(&mut self) {
+    if let Some(pool) = self.signing_pool.take() {
+      pool.drain();
+    }
+  }
+
+  /// Check the payload ledger for a recent signature of `message`
+  fn check_duplicate(&mut self, address: &str, message: &Signable) -> Result<(), KeychainError> {
+    let (digest, action) = match &mut self.ledger {
+      Some(ledger) => match ledger.find_duplicate(message) {
+        Some(digest) => (digest, ledger.action()),
+        None => return Ok(()),
+      },
+      None => return Ok(()),
+    };
+
+    let refused = action == DuplicateAction::Refuse;
+    self.policy.emit(&PolicyEvent::DuplicatePayload {
+      account: address.to_string(),
+      digest,
+      refused,
+    });
+
+    match refused {
+      true => Err(PolicyViolation::DuplicatePayload(digest).into()),
+      false => Ok(()),
+    }
+  }
+
+  /// Lock the keychain
+  /// This will lock all the internal vaults, removing all
+  /// private keys from memory
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn lock(&mut self, password: &str) -> Result<(), KeychainError> {
+    self.password_policy.check(password)?;
+    self.stop_signing_pool();
+    let indexes = (0..self.key_pairs.len()).collect::<Vec<usize>>();
+    let staged = self.stage_vaults(&indexes, |vault| vault.stage_lock(password.as_bytes()))?;
+    self.apply_staged(staged);
+    self.update_state(|state| {
+      state
+        .vaults
+        .iter_mut()
+        .for_each(|vault| vault.locked = true);
+    })?;
+    self.audit_log.record(AuditEvent::Lock, None, None);
+    self.write_locked_backup()
+  }
+
+  /// Lock the vaults of the profile named `name`, leaving the other
+  /// profiles untouched. Backup sinks receive the backup of the keychain
+  /// only once all of its vaults are locked
+  #[instrument(level = "debug", skip(self, password), err)]
+  pub fn lock_profile(&mut self, name: &str, password: &str) -> Result<(), KeychainError> {
+    self.password_policy.check(password)?;
+    let indexes = self.profile_indexes(name)?;
+    self.stop_signing_pool();
+
+    let staged = self.stage_vaults(&indexes, |vault| vault.stage_lock(password.as_bytes()))?;
+    self.apply_staged(staged);
+    self.update_state(move |state| {
+      indexes
+        .iter()
+        .for_each(|index| state.vaults[*index].locked = true);
+    })?;
+    self.audit_log.record(AuditEvent::Lock, None, None);
+
+    match self.is_locked() {
+      true => self.write_locked_backup(),
+      false => Ok(()),
+    }
+  }
+
+  /// Unlock the locked vaults of the profile named `name`
+  #[instrument(level = "debug", skip(self, password), err)]
+  pub fn unlock_profile(&mut self, name: &str, password: &str) -> Result<(), KeychainError> {
+    let indexes = self.profile_indexes(name)?;
+    self.unlock_vaults(&indexes, password)
+  }
+
+  /// Unlock the locked vaults at `indexes`, refreshing their state
+  fn unlock_vaults(&mut self, indexes: &[usize], password: &str) -> Result<(), KeychainError> {
+    let staged = self.stage_vaults(indexes, |vault| match vault.is_unlocked() {
+      true => Ok(None),
+      false => vault
+        .stage_unlock(password.as_bytes(), &self.registry)
+        .map(Some),
+    });
+    metrics::unlock_attempt(staged.is_ok());
+    self.apply_staged(staged?);
+    self.refresh_vaults(indexes)?;
+    self.audit_log.record(AuditEvent::Unlock, None, None);
+
+    Ok(())
+  }
+
+  /// Stage the vaults at `indexes` with `stage`, without changing them.
+  /// Fails with the error of each vault that could not be staged, so
+  /// that the vaults are either all changed or none of them is
+  fn stage_vaults<F>(
+    &self,
+    indexes: &[usize],
+    stage: F,
+  ) -> Result<Vec<(usize, StagedVault<M>)>, KeychainError>
+  where
+    F: Fn(&Vault<M>) -> Result<Option<StagedVault<M>>, VaultError>,
+  {
+    let mut staged = vec![];
+    let mut errors = vec![];
+    for index in indexes {
+      match &self.key_pairs[*index] {
+        KeyPair::MultiKeyPair(vault) => match stage(vault) {
+          Ok(Some(vault)) => staged.push((*index, vault)),
+          Ok(None) => (),
+          Err(error) => errors.push((*index, error)),
+        },
+      }
+    }
+
+    match errors.is_empty() {
+      true => Ok(staged),
+      false => Err(KeychainError::VaultsUnchanged(errors)),
+    }
+  }
+
+  /// Apply the outcome of `stage_vaults` to the vaults
+  fn apply_staged(&mut self, staged: Vec<(usize, StagedVault<M>)>) {
+    staged
+      .into_iter()
+      .for_each(|(index, vault)| match &mut self.key_pairs[index] {
+        KeyPair::MultiKeyPair(key_pair) => key_pair.apply(vault),
+      });
+  }
+
+  /// Recreate the state of the vaults at `indexes`,
+  /// with the accounts derived before locking
+  fn refresh_vaults(&mut self, indexes: &[usize]) -> Result<(), KeychainError> {
+    let vaults = indexes
+      .iter()
+      .map(|index| Ok((*index, self.key_pairs[*index].to_state()?)))
+      .collect::<Result<Vec<(usize, VaultState)>, VaultError>>()?;
+    self.update_state(move |state| {
+      vaults
+        .iter()
+        .for_each(|(index, vault)| state.vaults[*index] = vault.clone());
+    })?;
+
+    Ok(())
+  }
+
+  /// Write the backup of the keychain to its sinks.
+  /// All vaults must be encrypted, so sinks can receive them as they are
+  fn write_locked_backup(&mut self) -> Result<(), KeychainError> {
+    if self.backup_sinks.is_empty() {
+      return Ok(());
+    }
+
+    let vaults = self
+      .key_pairs
+      .iter()
+      .enumerate()
+      .map(|(index, key_pair)| match key_pair {
+        KeyPair::MultiKeyPair(vault) => Ok((index, vault.to_bytes()?)),
+      })
+      .collect::<Result<Vec<(usize, Vec<u8>)>, VaultError>>()?;
+    let backup = self.condense(self.sections(vaults, true))?;
+
+    self.write_to_sinks(&backup)
+  }
+
+  /// Unlock the keychain
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn unlock(&mut self, password: &str) -> Result<(), KeychainError> {
+    self.unlock_with_credential(password.as_bytes())
+  }
+
+  /// Unlock the keychain with the output of the PRF extension of a passkey
+  /// added with `add_passkey`, evaluated with the salt of the passkey
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn unlock_with_passkey(&mut self, prf_output: &[u8]) -> Result<(), KeychainError> {
+    self.unlock_with_credential(prf_output)
+  }
+
+  /// Unlock all the vaults with `credential`, opening any of their key slots
+  fn unlock_with_credential(&mut self, credential: &[u8]) -> Result<(), KeychainError> {
+    let indexes = (0..self.key_pairs.len()).collect::<Vec<usize>>();
+    let staged = self.stage_vaults(&indexes, |vault| {
+      vault.stage_unlock(credential, &self.registry).map(Some)
+    });
+    metrics::unlock_attempt(staged.is_ok());
+    self.apply_staged(staged?);
+    // Accounts derived before locking are recreated in the state
+    self.refresh_vaults(&indexes)?;
+    self.audit_log.record(AuditEvent::Unlock, None, None);
+
+    Ok(())
+  }
+
+  /// Unlock the keychain like `unlock`, issuing a session token to unlock
+  /// it again later with `unlock_with_token`, like after an automatic lock,
+  /// without asking for the password. Tokens issued before are revoked.
+  /// Vaults encrypted with their password only are encrypted with a data
+  /// key from the next lock on, so that the token can open them
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn unlock_with_session(&mut self, password: &str) -> Result<SessionToken, KeychainError> {
+    self.unlock(password)?;
+    let data_keys = self
+      .key_pairs
+      .iter_mut()
+      .map(|key_pair| match key_pair {
+        KeyPair::MultiKeyPair(vault) => Ok((vault.fingerprint(), vault.data_key()?)),
+      })
+      .collect::<Result<SessionKeys, VaultError>>()?;
+    let (session, token) = Session::issue(password, &data_keys, self.kdf.rounds, self.session_ttl);
+    self.session = Some(session);
+
+    Ok(token)
+  }
+
+  /// Unlock the keychain with the token issued by the last
+  /// `unlock_with_session`, until it expires
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn unlock_with_token(&mut self, token: &SessionToken) -> Result<(), KeychainError> {
+    let data_keys = match &self.session {
+      Some(session) => session.open(token)?,
+      None => return Err(KeychainError::InvalidSessionToken),
+    };
+
+    let indexes = (0..self.key_pairs.len()).collect::<Vec<usize>>();
+    let staged = self.stage_vaults(&indexes, |vault| {
+      data_keys
+        .iter()
+        .filter(|(fingerprint, _)| *fingerprint == vault.fingerprint())
+        .find_map(|(_, data_key)| {
+          vault
+            .stage_unlock_with_data_key(data_key, &self.registry)
+            .ok()
+        })
+        .map(Some)
+        .ok_or(VaultError::SafeDecrypt)
+    });
+    metrics::unlock_attempt(staged.is_ok());
+    self.apply_staged(staged.or(Err(KeychainError::InvalidSessionToken))?);
+    self.refresh_vaults(&indexes)?;
+    self.audit_log.record(AuditEvent::Unlock, None, None);
+
+    Ok(())
+  }
+
+  /// Revoke the session token issued last, if any
+  pub fn end_session(&mut self) {
+    self.session = None;
+  }
+
+  /// Set how long the session tokens issued later can unlock the keychain
+  pub fn set_session_ttl(&mut self, ttl: Duration) {
+    self.session_ttl = ttl;
+  }
+
+  /// Change the password of the keychain, checking the new one against
+  /// the password policy. Locked vaults are unlocked with `old_password`
+  /// first, and the keychain is left locked only if it was locked before
+  pub fn change_password(
+    &mut self,
+    old_password: &str,
+    new_password: &str,
+  ) -> Result<(), KeychainError> {
+    self.password_policy.check(new_password)?;
+    let was_locked = self.is_locked();
+
+    let indexes = (0..self.key_pairs.len()).collect::<Vec<usize>>();
+    let staged = self.stage_vaults(&indexes, |vault| match vault.is_unlocked() {
+      true => Ok(None),
+      false => vault
+        .stage_unlock(old_password.as_bytes(), &self.registry)
+        .map(Some),
+    })?;
+    self.apply_staged(staged);
+    self.end_session();
+
+    self.lock(new_password)?;
+    (0..self.key_pairs.len()).for_each(|index| self.touch_vault(index));
+    if !was_locked {
+      self.unlock(new_password)?;
+    }
+
+    Ok(())
+  }
+
+  /// Add `credential` to every vault of the keychain, in a key slot named
+  /// `name`, so that the keychain can be unlocked with it as well as with
+  /// its password. Vaults added later do not get the slot. All the vaults
+  /// must be unlocked, and the slots are stored when they are locked next
+  pub fn add_unlock_credential(
+    &mut self,
+    name: &str,
+    credential: &str,
+  ) -> Result<(), KeychainError> {
+    self.ensure_key_slot_available(name)?;
+
+    for index in 0..self.key_pairs.len() {
+      match &mut self.key_pairs[index] {
+        KeyPair::MultiKeyPair(vault) => vault.add_key_slot(name, credential.as_bytes())?,
+      }
+      self.touch_vault(index);
+    }
+
+    Ok(())
+  }
+
+  /// Check that a key slot named `name` can be added to
+  /// every vault, all of them unlocked and without such slot
+  fn ensure_key_slot_available(&self, name: &str) -> Result<(), KeychainError> {
+    for key_pair in &self.key_pairs {
+      match key_pair {
+        KeyPair::MultiKeyPair(vault) if !vault.is_unlocked() => {
+          return Err(VaultError::ForbiddenWhileLocked.into())
+        }
+        KeyPair::MultiKeyPair(vault) if vault.key_slot_names().contains(&name) => {
+          return Err(VaultError::KeySlot(format!("{} already exists", name)).into())
+        }
+        KeyPair::MultiKeyPair(_) => (),
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Generate a recovery key unlocking every vault of the keychain, to be
+  /// printed and stored offline, in a key slot named `RECOVERY_KEY_SLOT`.
+  /// Unlock the keychain with its phrase like with the password
+  pub fn add_recovery_key(&mut self) -> Result<RecoveryCode, KeychainError> {
+    let code = RecoveryCode::generate();
+    self.add_unlock_credential(RECOVERY_KEY_SLOT, code.phrase())?;
+
+    Ok(code)
+  }
+
+  /// Add the passkey `passkey` to every vault of the keychain, so that the
+  /// keychain can be unlocked with `unlock_with_passkey`. `prf_output` is
+  /// the output of the PRF of the passkey evaluated with its salt. Like
+  /// for `add_unlock_credential`, all the vaults must be unlocked
+  pub fn add_passkey(
+    &mut self,
+    passkey: &PasskeyCredential,
+    prf_output: &[u8],
+  ) -> Result<(), KeychainError> {
+    self.ensure_key_slot_available(&passkey.name)?;
+
+    for index in 0..self.key_pairs.len() {
+      match &mut self.key_pairs[index] {
+        KeyPair::MultiKeyPair(vault) => vault.add_passkey_slot(
+          &passkey.name,
+          &passkey.credential_id,
+          passkey.salt,
+          prf_output,
+        )?,
+      }
+      self.touch_vault(index);
+    }
+
+    Ok(())
+  }
+
+  /// Get the passkeys added to the vaults of the keychain, to request
+  /// the evaluation of their PRF to the authenticator. Works while locked
+  pub fn passkeys(&self) -> Vec<PasskeyCredential> {
+    let mut passkeys: Vec<PasskeyCredential> = vec![];
+    for key_pair in &self.key_pairs {
+      let KeyPair::MultiKeyPair(vault) = key_pair;
+      for key_slot in vault.key_slots() {
+        if let Some(credential_id) = &key_slot.credential_id {
+          let passkey = PasskeyCredential {
+            name: key_slot.name.clone(),
+            credential_id: credential_id.clone(),
+            salt: key_slot.salt,
+          };
+          if !passkeys.contains(&passkey) {
+            passkeys.push(passkey);
+          }
+        }
+      }
+    }
+
+    passkeys
+  }
+
+  /// Remove the key slot named `name` from every vault of the keychain,
+  /// revoking its credential. Works while locked too.
+  /// Returns whether a slot was removed
+  pub fn remove_unlock_credential(&mut self, name: &str) -> Result<bool, KeychainError> {
+    let mut removed = false;
+    for index in 0..self.key_pairs.len() {
+      let removed_from_vault = match &mut self.key_pairs[index] {
+        KeyPair::MultiKeyPair(vault) => vault.remove_key_slot(name)?,
+      };
+      if removed_from_vault {
+        self.touch_vault(index);
+        removed = true;
+      }
+    }
+
+    Ok(removed)
+  }
+
+  /// Check that every vault decrypts with `password` and still derives
+  /// its stored accounts, without unlocking anything, to validate the
+  /// keychain before relying on its backups. Unlocked vaults are checked
+  /// by encrypting them like a backup would
+  #[instrument(level = "debug", skip_all)]
+  pub fn verify_integrity(&self, password: &str) -> IntegrityReport {
+    IntegrityReport {
+      vaults: self
+        .key_pairs
+        .iter()
+        .map(|key_pair| match key_pair {
+          KeyPair::MultiKeyPair(vault) => (
+            vault.fingerprint(),
+            vault
+              .verify_integrity(password.as_bytes(), &self.registry)
+              .into(),
+          ),
+        })
+        .collect(),
+    }
+  }
+
+  /// Backup the `Keychain` serializing all the keypairs to bytes and encrypting them
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn backup(&mut self, password: &str) -> Result<Vec<u8>, KeychainError> {
+    let indexes = (0..self.key_pairs.len()).collect::<Vec<usize>>();
+    let vaults = self.encrypt_vaults(&indexes, password)?;
+
+    let condensed = self.condense(self.sections(vaults, true))?;
+    self.audit_log.record(AuditEvent::Backup, None, None);
+    self.write_to_sinks(&condensed)?;
+
+    Ok(condensed)
+  }
+
+  /// Backup the `Keychain` like `backup`, sealing it with `secret`
+  /// so that it is protected by something else than the keychain
+  /// password, like a recovery code. Restore it with `restore_sealed`
+  pub fn backup_sealed(
+    &mut self,
+    password: &str,
+    secret: &BackupSecret,
+  ) -> Result<Vec<u8>, KeychainError> {
+    let backup = self.backup(password)?;
+
+    Ok(seal_backup(&backup, secret, self.seal_rounds)?)
+  }
+
+  /// Backup only what changed since `since_revision`: the vaults changed
+  /// since, encrypted with `password`, and the profiles, labels, usage and
+  /// dapp bindings if any of them changed. The delta is applied with `apply_delta`
+  /// on top of a keychain restored from an older backup or delta
+  #[instrument(level = "debug", skip(self, password), err)]
+  pub fn backup_delta(
+    &mut self,
+    password: &str,
+    since_revision: u64,
+  ) -> Result<BackupDelta, KeychainError> {
+    let indexes = (0..self.key_pairs.len())
+      .filter(|index| self.vault_revisions[*index] > since_revision)
+      .collect::<Vec<usize>>();
+    let vaults = self.encrypt_vaults(&indexes, password)?;
+    let metadata =
+      (self.metadata_revision > since_revision).then(|| self.store.get_state().delta_metadata());
+    self.audit_log.record(AuditEvent::Backup, None, None);
+
+    Ok(BackupDelta {
+      base_revision: since_revision,
+      revision: self.revision,
+      vaults,
+      metadata,
+    })
+  }
+
+  /// Backup the vaults of the profile named `name` only, as a section
+  /// that restores them into a profile with the same name
+  #[instrument(level = "debug", skip(self, password), err)]
+  pub fn backup_profile(&mut self, name: &str, password: &str) -> Result<Vec<u8>, KeychainError> {
+    let indexes = self.profile_indexes(name)?;
+    let vaults = self.encrypt_vaults(&indexes, password)?;
+
+    let mut sections = self.sections(vaults, false);
+    if indexes.is_empty() && name != DEFAULT_PROFILE {
+      // 1u8 is a byte representation of a profile marker
+      sections.push((1u8, name.as_bytes().to_vec()));
+    }
+    let condensed = self.condense(sections)?;
+    self.audit_log.record(AuditEvent::Backup, None, None);
+
+    Ok(condensed)
+  }
+
+  /// Send the vaults at `indexes` to a paired device through `channel`.
+  /// Vaults are encrypted with `password` before being sealed, so they
+  /// never leave the keychain in plaintext
+  pub fn sync_export(
+    &mut self,
+    channel: &mut SyncChannel,
+    indexes: &[usize],
+    password: &str,
+  ) -> Result<Vec<u8>, KeychainError> {
+    let mut indexes = indexes.to_vec();
+    indexes.sort_unstable();
+    indexes.dedup();
+    if let Some(index) = indexes.iter().find(|index| **index >= self.key_pairs.len()) {
+      return Err(KeychainError::KeyNotFoundForIndex(*index));
+    }
+
+    let vaults = self.encrypt_vaults(&indexes, password)?;
+    let condensed = self.condense(self.sections(vaults, false))?;
+    self.audit_log.record(AuditEvent::Backup, None, None);
+
+    Ok(channel.seal(&condensed)?)
+  }
+
+  /// Receive the vaults sent by a paired device through `channel`,
+  /// unlocking them with the `password` they were exported with.
+  /// Anything but vaults and profile markers is rejected, so that a
+  /// peer cannot reset quotas, usage, labels or domain bindings.
+  /// Returns the indexes of the new vaults
+  pub fn sync_import(
+    &mut self,
+    channel: &mut SyncChannel,
+    sealed: &[u8],
+    password: &str,
+  ) -> Result<Vec<usize>, KeychainError> {
+    let (bytes, _) = Migrator::default().migrate(&channel.open(sealed)?)?;
+    let indexes = self.add_condensed(bytes, Some(&SYNC_SECTIONS))?;
+    self.unlock_vaults(&indexes, password)?;
+
+    Ok(indexes)
+  }
+
+  /// Apply a delta exported by `backup_delta`, replacing the changed
+  /// vaults and unlocking them with the `password` they were exported
+  /// with. The delta must be based on a revision the keychain reached
+  #[instrument(level = "debug", skip_all, err, fields(revision = delta.revision))]
+  pub fn apply_delta(&mut self, delta: &BackupDelta, password: &str) -> Result<(), KeychainError> {
+    if delta.base_revision > self.revision {
+      return Err(BackupError::DeltaGap(delta.base_revision, self.revision).into());
+    }
+    let revision = self.revision.max(delta.revision);
+
+    // The vaults and the state are built first, and swapped in once the
+    // whole delta applied, so that a failing delta leaves them untouched
+    let mut key_pairs = vec![];
+    let mut state = self.store.get_state().clone();
+    for (index, bytes) in &delta.vaults {
+      let mut vault = Vault::<M>::try_from(bytes.clone())?;
+      vault.unlock_with_registry(password.as_bytes(), &self.registry)?;
+      let key_pair = KeyPair::MultiKeyPair(vault);
+      let vault_state = key_pair.to_state()?;
+
+      match index.cmp(&state.vaults.len()) {
+        Ordering::Less => state.vaults[*index] = vault_state,
+        Ordering::Equal => {
+          state.vaults.push(vault_state);
+          let active_profile = state.active_profile.clone();
+          state.assign_vault(*index, &active_profile);
+        }
+        Ordering::Greater => {
+          return Err(BackupError::InvalidDelta(format!("Missing vault before {}", index)).into())
+        }
+      }
+      key_pairs.push((*index, key_pair));
+    }
+    if let Some(metadata) = &delta.metadata {
+      state.apply_delta_metadata(metadata, state.vaults.len())?;
+    }
+
+    self.update_state(move |current| *current = state.clone())?;
+    for (index, key_pair) in key_pairs {
+      match index.cmp(&self.key_pairs.len()) {
+        Ordering::Less => self.key_pairs[index] = key_pair,
+        _ => self.key_pairs.push(key_pair),
+      }
+    }
+    self.vault_revisions.resize(self.key_pairs.len(), 0);
+
+    // The keychain is now at the revision of the delta, with the
+    // vaults and metadata it carried changed at that revision
+    self.revision = revision;
+    delta
+      .vaults
+      .iter()
+      .for_each(|(index, _)| self.vault_revisions[*index] = revision);
+    if delta.metadata.is_some() {
+      self.metadata_revision = revision;
+    }
+
+    Ok(())
+  }
+
+  /// Merge the vaults and metadata of another backup into the keychain,
+  /// unlocking them with `password`, like when consolidating wallets
+  /// from multiple devices. Vaults already in the keychain, with the same
+  /// fingerprint, are skipped. The other vaults join the profiles they
+  /// belong to in the backup. Accounts labeled differently in the
+  /// keychain and in the backup get a label resolved with `labels`
+  #[instrument(level = "debug", skip(self, other_backup, password), err)]
+  pub fn merge(
+    &mut self,
+    other_backup: Vec<u8>,
+    password: &str,
+    labels: LabelConflict,
+  ) -> Result<MergeReport, KeychainError> {
+    let other = Self::restore_with_registry(other_backup, password, self.registry.clone())?;
+    let other_state = other.store.get_state().clone();
+    let mut report = MergeReport::default();
+
+    for profile in &other_state.profiles {
+      if self.store.get_state().profile(&profile.name).is_none() {
+        self.create_profile(&profile.name)?;
+      }
+    }
+
+    for (index, key_pair) in other.key_pairs.into_iter().enumerate() {
+      let KeyPair::MultiKeyPair(mut vault) = key_pair;
+      let fingerprint = vault.fingerprint();
+      if self
+        .store
+        .get_state()
+        .vaults
+        .iter()
+        .any(|vault| vault.fingerprint == fingerprint)
+      {
+        report.duplicates.push(fingerprint);
+        continue;
+      }
+
+      vault.set_kdf_params(self.kdf);
+      self.add_key_pair(KeyPair::MultiKeyPair(vault))?;
+      let new_index = self.key_pairs.len() - 1;
+      if let Some(profile) = other_state.profile_of(index) {
+        self.move_vault(new_index, &profile.name)?;
+      }
+      report.vaults.push(new_index);
+    }
+
+    let mut state = self.store.get_state().clone();
+    report.label_conflicts = state.merge_metadata(&other_state, labels);
+    self.update_state(move |current| *current = state.clone())?;
+
+    Ok(report)
+  }
+
+  /// Apply changes to a draft of the keychain, committing them all at
+  /// once or discarding them depending on the outcome returned by `draft`,
+  /// like for a cancelable multi-step wizard. Committed changes emit a
+  /// single state event. Errors discard the draft. The draft has no backup
+  /// sinks or signing pool, and a copy of the signing policy of the keychain,
+  /// installed only when committed. Committing stops the signing pool.
+  /// It cannot sign nor use private keys, as its audit log, ledger and
+  /// quota usage would be lost with it when discarded
+  #[instrument(level = "debug", skip_all, err)]
+  pub fn preview<F>(&mut self, draft: F) -> Result<PreviewOutcome, KeychainError>
+  where
+    M: Clone,
+    F: FnOnce(&mut Keychain<M>) -> Result<PreviewOutcome, KeychainError>,
+  {
+    let mut keychain = Keychain {
+      key_pairs: self.key_pairs.clone(),
+      store: Observable::new(self.store.get_state().clone()),
+      audit_log: self.audit_log.clone(),
+      registry: self.registry.clone(),
+      backup_sinks: vec![],
+      backup_secret: None,
+      seal_rounds: self.seal_rounds,
+      #[cfg(feature = "compression")]
+      compress_backups: self.compress_backups,
+      policy: self.policy.clone(),
+      ledger: self.ledger.clone(),
+      signing_pool: None,
+      kdf: self.kdf,
+      migration_report: self.migration_report.clone(),
+      password_policy: self.password_policy.clone(),
+      revision: self.revision,
+      vault_revisions: self.vault_revisions.clone(),
+      metadata_revision: self.metadata_revision,
+      session: None,
+      session_ttl: self.session_ttl,
+      draft: true,
+    };
+    if draft(&mut keychain)? == PreviewOutcome::Discard {
+      return Ok(PreviewOutcome::Discard);
+    }
+
+    // The draft may have locked vaults, or set a policy or a ledger
+    // the signatures of the pool would bypass
+    self.stop_signing_pool();
+    let state = keychain.store.get_state().clone();
+    self.key_pairs = std::mem::take(&mut keychain.key_pairs);
+    self.audit_log = std::mem::take(&mut keychain.audit_log);
+    self.policy = std::mem::take(&mut keychain.policy);
+    self.ledger = keychain.ledger.take();
+    self.kdf = keychain.kdf;
+    self.seal_rounds = keychain.seal_rounds;
+    self.password_policy = std::mem::take(&mut keychain.password_policy);
+    self.revision = keychain.revision;
+    self.vault_revisions = std::mem::take(&mut keychain.vault_revisions);
+    self.metadata_revision = keychain.metadata_revision;
+    self.store.update(move |current| *current = state.clone())?;
+
+    Ok(PreviewOutcome::Commit)
+  }
+
+  /// Rotate the vault at `index`, whose seed may be compromised, creating
+  /// a new vault with `factory` in the same profile and deriving accounts
+  /// at the same derivation paths. Labels are copied to the new accounts.
+  /// The returned plan pairs each old address with its replacement and
+  /// its last tracked balance, so that funds can be swept before the old
+  /// vault is retired. The old vault may be locked
+  #[instrument(level = "debug", skip(self, factory, args), err)]
+  pub fn rotate_vault<F, A>(
+    &mut self,
+    index: usize,
+    factory: F,
+    args: A,
+  ) -> Result<RotationPlan, KeychainError>
+  where
+    F: FnOnce(A) -> Result<M, Box<dyn IdentityError>>,
+  {
+    let mut state = self.store.get_state().clone();
+    let old_vault = state
+      .vaults
+      .get(index)
+      .cloned()
+      .ok_or(KeychainError::KeyNotFoundForIndex(index))?;
+
+    // The new vault and the state are built first, and swapped in once
+    // all the accounts are derived, so that a failing rotation leaves
+    // the keychain untouched
+    let mut vault = Vault::new(factory, args)?;
+    vault.set_kdf_params(self.kdf);
+    let mut pairs = vec![];
+    for account in &old_vault.accounts {
+      let new_account = vault.add_key_at(account.path.index)?;
+      if let Some(label) = state.labels.get(&account.address).cloned() {
+        state.labels.insert(new_account.address.clone(), label);
+      }
+      // Dapps bound to the old account are bound to the new one
+      state
+        .domains
+        .values_mut()
+        .filter(|address| **address == account.address)
+        .for_each(|address| *address = new_account.address.clone());
+      pairs.push(RotationPair {
+        from: account.address.clone(),
+        to: new_account.address,
+        path: new_account.path,
+        balance: state
+          .snapshot(&account.address)
+          .and_then(|snapshot| snapshot.balance),
+      });
+    }
+
+    let key_pair = KeyPair::MultiKeyPair(vault);
+    let new_index = self.key_pairs.len();
+    let profile = match state.profile_of(index) {
+      Some(profile) => profile.name.clone(),
+      None => state.active_profile.clone(),
+    };
+    state.vaults.push(key_pair.to_state()?);
+    state.assign_vault(new_index, &profile);
+
+    self.update_state(move |current| *current = state.clone())?;
+    self.key_pairs.push(key_pair);
+    self.touch_vault(new_index);
+
+    Ok(RotationPlan {
+      from_vault: index,
+      to_vault: new_index,
+      pairs,
+    })
+  }
+
+  /// Serialize the vaults at `indexes` to bytes, encrypting
+  /// the unlocked ones with `password`
+  fn encrypt_vaults(
+    &mut self,
+    indexes: &[usize],
+    password: &str,
+  ) -> Result<Vec<(usize, Vec<u8>)>, VaultError> {
+    indexes
+      .iter()
+      .map(|index| match &mut self.key_pairs[*index] {
+        KeyPair::MultiKeyPair(vault) => {
+          if vault.is_unlocked() {
+            vault.lock(password.as_bytes())?;
+            let bytes = vault.to_bytes()?;
+            vault.unlock_with_registry(password.as_bytes(), &self.registry)?;
+            return Ok((*index, bytes));
+          }
+
+          Ok((*index, vault.to_bytes()?))
+        }
+      })
+      .collect()
+  }
+
+  /// Group the serialized vaults into sections, each starting with a
+  /// marker naming its profile unless it is the default one.
+  /// With `full`, markers of profiles without vaults, the quota usage,
+  /// the usage and labels of the accounts and the revision of the
+  /// keychain are appended, so that they are recreated on restore
+  fn sections(&self, vaults: Vec<(usize, Vec<u8>)>, full: bool) -> Vec<(u8, Vec<u8>)> {
+    let state = self.store.get_state();
+    let mut current_profile = DEFAULT_PROFILE;
+    let mut sections = vec![];
+
+    vaults.into_iter().for_each(|(index, bytes)| {
+      let profile = state
+        .profile_of(index)
+        .map(|profile| profile.name.as_str())
+        .unwrap_or(DEFAULT_PROFILE);
+      if profile != current_profile {
+        // 1u8 is a byte representation of a profile marker
+        sections.push((1u8, profile.as_bytes().to_vec()));
+        current_profile = profile;
+      }
+      // 0u8 is a byte representation of a MultiKeyPair
+      sections.push((0u8, bytes));
+    });
+
+    if full {
+      state
+        .profiles
+        .iter()
+        .filter(|profile| profile.vaults.is_empty() && profile.name != DEFAULT_PROFILE)
+        .for_each(|profile| sections.push((1u8, profile.name.as_bytes().to_vec())));
+    }
+
+    if full && !state.quota_usage.is_empty() {
+      let usage = state
+        .quota_usage
+        .iter()
+        .map(|(address, usage)| (address.clone(), usage.to_json()))
+        .collect::<serde_json::Map<String, serde_json::Value>>();
+      // 2u8 is a byte representation of the quota usage
+      sections.push((
+        2u8,
+        serde_json::Value::Object(usage).to_string().into_bytes(),
+      ));
+    }
+
+    if full && !state.usage.is_empty() {
+      let usage = state
+        .usage
+        .iter()
+        .map(|(address, usage)| (address.clone(), usage.to_json()))
+        .collect::<serde_json::Map<String, serde_json::Value>>();
+      // 3u8 is a byte representation of the usage of the accounts
+      sections.push((
+        3u8,
+        serde_json::Value::Object(usage).to_string().into_bytes(),
+      ));
+    }
+
+    if full && !state.labels.is_empty() {
+      // 5u8 is a byte representation of the labels of the accounts
+      sections.push((
+        5u8,
+        serde_json::json!(state.labels).to_string().into_bytes(),
+      ));
+    }
+
+    if full && !state.domains.is_empty() {
+      // 6u8 is a byte representation of the accounts bound to dapps
+      sections.push((
+        6u8,
+        serde_json::json!(state.domains).to_string().into_bytes(),
+      ));
+    }
+
+    if full && !self.audit_log.entries().is_empty() {
+      // 7u8 is a byte representation of the audit log
+      sections.push((7u8, self.audit_log.to_json().into_bytes()));
+    }
+
+    if full {
+      // 4u8 is a byte representation of the revision of the keychain
+      sections.push((4u8, self.revision.to_le_bytes().to_vec()));
+    }
+
+    sections
+  }
+
+  /// Concatenate the bytes of the vaults, each prepended
+  /// by its length and type
+  fn condense(&self, mut bytes_matrix: Vec<(u8, Vec<u8>)>) -> Result<Vec<u8>, KeychainError> {
+    let mut condensed: Vec<u8> = vec![];
+    bytes_matrix
+      .iter_mut()
+      .try_for_each(|(vault_type, bytes)| {
+        let length = u32::try_from(bytes.len()).or(Err(KeychainError::ByteSerializationError))?;
+        // The length of the bytes, as little endian u32, is prepended to the type of vault
+        condensed.append(&mut length.to_le_bytes().to_vec());
+        // The type of vault is prepended to the bytes
+        condensed.append(&mut [*vault_type].to_vec());
+        condensed.append(bytes);
+        Ok::<(), KeychainError>(())
+      })?;
+
+    #[cfg(feature = "compression")]
+    if self.compress_backups {
+      return write_compressed_envelope(&condensed).or(Err(KeychainError::ByteSerializationError));
+    }
+
+    Ok(write_envelope(&condensed))
+  }
+
+  /// Write a backup to all the sinks of the keychain
+  fn write_to_sinks(&mut self, backup: &[u8]) -> Result<(), KeychainError> {
+    if self.backup_sinks.is_empty() {
+      return Ok(());
+    }
+
+    let backup = match &self.backup_secret {
+      Some(secret) => seal_backup(backup, secret, self.seal_rounds)?,
+      None => backup.to_vec(),
+    };
+    self
+      .backup_sinks
+      .iter_mut()
+      .try_for_each(|sink| sink.write(&backup))
+  }
+
+  /// Restore a `Keychain` from a backup, recreating each identity
+  /// with the deserializer registered for its identity type
+  pub fn restore_with_registry(
+    backup: Vec<u8>,
+    password: &str,
+    registry: IdentityFactoryRegistry<M>,
+  ) -> Result<Self, KeychainError> {
+    Self::restore_with_migrator(backup, password, registry, &Migrator::default())
+  }
+
+  /// Restore a `Keychain` from a backup, upgrading it to the current
+  /// backup version with `migrator` first. The applied migration steps
+  /// are reported by `Keychain::migration_report`
+  #[instrument(level = "debug", skip_all, err, fields(bytes = backup.len()))]
+  pub fn restore_with_migrator(
+    backup: Vec<u8>,
+    password: &str,
+    registry: IdentityFactoryRegistry<M>,
+    migrator: &Migrator,
+  ) -> Result<Self, KeychainError> {
+    if is_sealed(&backup) {
+      return Err(BackupError::Sealed.into());
+    }
+
+    let mut keychain = Keychain::<M>::with_registry(registry);
+    let (bytes, report) = migrator.migrate(&backup)?;
+    keychain.migration_report = Some(report);
+
+    keychain.add_condensed(bytes, None)?;
+    keychain.unlock(password)?;
+
+    Ok(keychain)
+  }
+
+  /// Add the vaults of condensed backup bytes to the keychain, in the
+  /// profiles they are marked with. Vaults without a profile marker
+  /// join the active profile. When `accepted` is set, the bytes are
+  /// rejected before anything is applied if they carry a section of
+  /// another type. Returns the indexes of the new vaults
+  fn add_condensed(
+    &mut self,
+    bytes: Vec<u8>,
+    accepted: Option<&[u8]>,
+  ) -> Result<Vec<usize>, KeychainError> {
+    let active_profile = self.active_profile().to_string();
+    let mut indexes = vec![];
+    let mut revision = None;
+    let mut sections = vec![];
+    let mut rest = bytes.as_slice();
+
+    // Loop through the bytes and split the sections
+    while !rest.is_empty() {
+      let length_error =
+        || KeychainError::ByteDeserializationError("Unexpected backup length".to_string());
+      // Each vault has four bytes to represent the size
+      let length = rest
+        .get(..4)
+        .and_then(|length| length.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(length_error)? as usize;
+      // And one to represent its type
+      let key_pair_type = *rest.get(4).ok_or_else(length_error)?;
+      let section = rest
+        .get(5..)
+        .and_then(|section| section.get(..length))
+        .ok_or_else(length_error)?;
+
+      sections.push((key_pair_type, section));
+      rest = &rest[5 + length..];
+    }
+
+    if let Some(accepted) = accepted {
+      if let Some((unexpected, _)) = sections
+        .iter()
+        .find(|(key_pair_type, _)| !accepted.contains(key_pair_type))
+      {
+        return Err(KeychainError::ByteDeserializationError(format!(
+          "Unexpected section type: {}",
+          unexpected
+        )));
+      }
+    }
+
+    for (key_pair_type, section) in sections {
+      match key_pair_type {
+        0u8 => {
+          let key_pair = KeyPair::MultiKeyPair(Vault::<M>::try_from(section.to_vec())?);
+
+          indexes.push(self.key_pairs.len());
+          self.add_key_pair(key_pair)?;
+        }
+        1u8 => {
+          // The following vaults belong to the named profile
+          let name = String::from_utf8(section.to_vec()).or(Err(
+            KeychainError::ByteDeserializationError("Invalid profile name".to_string()),
+          ))?;
+          if self.get_state().profile(&name).is_none() {
+            self.create_profile(&name)?;
+          }
+          self.switch_profile(&name)?;
+        }
+        2u8 => {
+          let usage = serde_json::from_slice::<serde_json::Value>(section)
+            .ok()
+            .and_then(|usage| usage.as_object().cloned())
+            .ok_or(KeychainError::ByteDeserializationError(
+              "Invalid quota usage".to_string(),
+            ))?
+            .iter()
+            .map(|(address, usage)| Ok((address.clone(), QuotaUsage::try_from(usage)?)))
+            .collect::<Result<BTreeMap<String, QuotaUsage>, String>>()
+            .map_err(KeychainError::ByteDeserializationError)?;
+          self.update_state(move |state| {
+            state.quota_usage.extend(usage.clone());
+          })?;
+        }
+        3u8 => {
+          let usage = serde_json::from_slice::<serde_json::Value>(section)
+            .ok()
+            .and_then(|usage| usage.as_object().cloned())
+            .ok_or(KeychainError::ByteDeserializationError(
+              "Invalid account usage".to_string(),
+            ))?
+            .iter()
+            .map(|(address, usage)| Ok((address.clone(), AccountUsage::try_from(usage)?)))
+            .collect::<Result<BTreeMap<String, AccountUsage>, String>>()
+            .map_err(KeychainError::ByteDeserializationError)?;
+          self.update_state(move |state| {
+            state.usage.extend(usage.clone());
+          })?;
+        }
+        5u8 => {
+          let labels = serde_json::from_slice::<BTreeMap<String, String>>(section).or(Err(
+            KeychainError::ByteDeserializationError("Invalid account labels".to_string()),
+          ))?;
+          self.update_state(move |state| {
+            state.labels.extend(labels.clone());
+          })?;
+        }
+        6u8 => {
+          let domains = serde_json::from_slice::<BTreeMap<String, String>>(section).or(Err(
+            KeychainError::ByteDeserializationError("Invalid domain accounts".to_string()),
+          ))?;
+          self.update_state(move |state| {
+            state.domains.extend(domains.clone());
+          })?;
+        }
+        7u8 => {
+          let audit_log = AuditLog::try_from(section)?;
+          // The log of a keychain is never replaced by the one of a backup
+          if self.audit_log.entries().is_empty() {
+            self.audit_log = audit_log;
+          }
+        }
+        4u8 => {
+          revision = Some(u64::from_le_bytes(section.try_into().or(Err(
+            KeychainError::ByteDeserializationError("Invalid revision".to_string()),
+          ))?));
+        }
+        unsupported => {
+          return Err(KeychainError::ByteDeserializationError(format!(
+            "Unsupported key pair type: {}",
+            unsupported
+          )))
+        }
+      }
+    }
+
+    self.switch_profile(&active_profile)?;
+
+    // Deltas exported after the backup can be applied on top of it
+    if let Some(revision) = revision {
+      self.revision = revision;
+      self.metadata_revision = revision;
+      self.vault_revisions.fill(revision);
+    }
+
+    Ok(indexes)
+  }
+}
+
+impl DynKeychain {
+  /// Add a new `KeyPair` to the `Keychain` with an identity
+  /// of any type, stored as a `MultiKeyPairDyn` object
+  pub fn add_dyn_keypair<T, F, A>(
+    &mut self,
+    factory: F,
+    args: A,
+  ) -> Result<&dyn MultiKeyPairDyn, KeychainError>
+  where
+    T: MultiKeyPairDyn + 'static,
+    F: FnOnce(A) -> Result<T, Box<dyn IdentityError>>,
+  {
+    let identity = self.add_multi_keypair(
+      |args| Ok(Box::new(factory(args)?) as Box<dyn MultiKeyPairDyn>),
+      args,
+    )?;
+
+    Ok(identity.as_ref())
+  }
+
+  /// Import the key of an ethers-rs wallet as a `SimpleKey` vault,
+  /// returning its only account
+  #[cfg(feature = "ethers")]
+  pub fn import_local_wallet(
+    &mut self,
+    wallet: &identity::signer::ethers::LocalWallet,
+  ) -> Result<Account, KeychainError> {
+    let private_key: [u8; 32] = wallet.signer().to_bytes().into();
+    self.add_dyn_keypair(simple_key_factory, Some(private_key))?;
+
+    self.add_account(self.vault_count() - 1)
+  }
+
+  /// Import the keyrings of a MetaMask extension vault, or of the browser
+  /// storage blob holding it, decrypting it with the MetaMask password.
+  /// Mnemonics become `HDKey` vaults with the same number of accounts, and
+  /// each imported private key a `SimpleKey` vault with a single account.
+  /// Keyrings without secret material, like hardware wallets, are skipped.
+  /// Restoring the keychain requires deserializers for both identity types
+  pub fn import_metamask(
+    &mut self,
+    vault: &str,
+    password: &str,
+  ) -> Result<MetamaskImport, KeychainError> {
+    let keyrings = MetamaskVault::from_str(vault)?.decrypt(password)?;
+    let mut import = MetamaskImport::default();
+
+    for keyring in keyrings {
+      match keyring {
+        MetamaskKeyring::Hd {
+          mnemonic,
+          number_of_accounts,
+          hd_path,
+        } if hd_path == DEFAULT_HD_PATH => {
+          self.add_dyn_keypair(hdkey_factory, Some(mnemonic))?;
+          let index = self.vault_count() - 1;
+          self.derive_range(index, 0, number_of_accounts)?;
+          import.vaults.push(index);
+        }
+        MetamaskKeyring::Simple { private_keys } => {
+          for private_key in private_keys {
+            self.add_dyn_keypair(simple_key_factory, Some(private_key))?;
+            let index = self.vault_count() - 1;
+            self.add_account(index)?;
+            import.vaults.push(index);
+          }
+        }
+        keyring => import.skipped.push(keyring.keyring_type().to_string()),
+      }
+    }
+
+    Ok(import)
+  }
+}
+
+impl<M> Default for Keychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> + Initializable,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<M> Controller<KeychainState, KeychainError> for Keychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Get the state of the keychain
+  fn get_state(&self) -> &KeychainState {
+    self.store.get_state()
+  }
+
+  /// Update the state of the keychain
+  fn update<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  where
+    F: Fn(&mut KeychainState),
+  {
+    self.update_state(updater)
+  }
+
+  /// Update the state of the keychain, restoring
+  /// it if a subscriber cannot be notified
+  fn try_update<F>(&mut self, updater: F) -> Result<(), KeychainError>
+  where
+    F: Fn(&mut KeychainState),
+  {
+    let metadata = self.store.get_state().delta_metadata();
+    self.store.try_update(updater)?;
+    self.track_metadata(&metadata);
+
+    Ok(())
   }
 
   /// Subscribe to state changes
   fn subscribe<F>(&mut self, subscriber: F) -> usize
   where
-    F: 'static + FnMut(&KeychainState),
+    F: 'static + FnMut(&KeychainState) + Send,
   {
     self.store.subscribe(subscriber)
   }
@@ -0,0 +1,104 @@
+use std::{
+  collections::VecDeque,
+  time::{Duration, Instant},
+};
+
+use identity::IntoSignable;
+use utils::B256;
+
+/// What to do when a payload already signed within
+/// the window of the ledger is submitted again
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuplicateAction {
+  /// Sign the payload, emitting a policy event
+  Warn,
+  /// Refuse to sign the payload
+  Refuse,
+}
+
+/// A ledger of the digests of recently signed payloads, protecting
+/// against the same payload being signed twice by mistake
+#[derive(Clone, Debug)]
+pub struct PayloadLedger {
+  /// How long a signed payload is remembered
+  window: Duration,
+  /// What to do with duplicates
+  action: DuplicateAction,
+  /// Digests of the signed payloads, oldest first
+  entries: VecDeque<(B256, Instant)>,
+}
+
+impl PayloadLedger {
+  /// Create a new ledger remembering payloads for `window`
+  pub fn new(window: Duration, action: DuplicateAction) -> Self {
+    Self {
+      window,
+      action,
+      entries: VecDeque::new(),
+    }
+  }
+
+  /// Get what the ledger does with duplicates
+  pub fn action(&self) -> DuplicateAction {
+    self.action
+  }
+
+  /// Check if `payload` has been signed within the window.
+  /// Returns its digest when it has
+  pub fn find_duplicate<S>(&mut self, payload: &S) -> Option<B256>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.prune();
+    let digest = payload.to_signable().digest();
+
+    self
+      .entries
+      .iter()
+      .any(|(entry, _)| *entry == digest)
+      .then_some(digest)
+  }
+
+  /// Remember that `payload` has been signed
+  pub fn record<S>(&mut self, payload: &S)
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.prune();
+    self
+      .entries
+      .push_back((payload.to_signable().digest(), Instant::now()));
+  }
+
+  /// Forget the last record of `payload`, if any
+  pub fn forget<S>(&mut self, payload: &S)
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let digest = payload.to_signable().digest();
+    if let Some(index) = self.entries.iter().rposition(|(entry, _)| *entry == digest) {
+      self.entries.remove(index);
+    }
+  }
+
+  /// Get the number of payloads remembered
+  pub fn len(&mut self) -> usize {
+    self.prune();
+    self.entries.len()
+  }
+
+  /// Check if no payload is remembered
+  pub fn is_empty(&mut self) -> bool {
+    self.len() == 0
+  }
+
+  /// Forget the payloads signed before the window
+  fn prune(&mut self) {
+    while let Some((_, signed_at)) = self.entries.front() {
+      match signed_at.elapsed() > self.window {
+        true => self.entries.pop_front(),
+        false => break,
+      };
+    }
+  }
+}
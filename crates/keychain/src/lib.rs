@@ -1,5 +1,113 @@
+#![allow(clippy::module_inception)]
+
+pub mod account_signer;
+pub use account_signer::*;
+
+pub mod address_book;
+pub use address_book::*;
+
+pub mod audit;
+pub use audit::*;
+
+pub mod authorization;
+pub use authorization::*;
+
+pub mod backup;
+pub use backup::*;
+
+pub mod contract;
+pub use contract::*;
+
+pub mod decoder;
+pub use decoder::{DecodedCall, Decoder, DecoderError};
+
+pub mod domain;
+pub use domain::*;
+
+pub mod encryption;
+pub use encryption::*;
+
+pub mod export;
+pub use export::*;
+
+pub mod integrity;
+pub use integrity::*;
+
 pub mod keychain;
 pub use keychain::*;
 
+pub mod ledger;
+pub use ledger::*;
+
+pub mod merge;
+pub use merge::*;
+
+pub mod metamask;
+pub use metamask::{MetamaskError, MetamaskImport, MetamaskKeyring, MetamaskVault};
+
+pub mod metrics;
+
+pub mod migrations;
+pub use migrations::{MigrationError, MigrationReport, Migrator};
+
+pub mod passkey;
+pub use passkey::*;
+
+pub mod policy;
+pub use policy::*;
+
+pub mod pool;
+pub use pool::*;
+
+pub mod preview;
+pub use preview::*;
+
+pub mod profile;
+pub use profile::*;
+
+pub mod relay;
+pub use relay::*;
+
+mod rlp;
+
+pub mod rotation;
+pub use rotation::*;
+
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "rpc")]
+pub use rpc::{
+  RpcError, RpcHandler, RpcServer, RpcTransaction, SignedRpcTransaction, TransactionSender,
+};
+
+pub mod session;
+pub use session::{SessionToken, DEFAULT_SESSION_TTL, SESSION_TOKEN_LENGTH};
+
+pub mod shared;
+pub use shared::*;
+
+pub mod sink;
+pub use sink::*;
+
+pub mod siwe;
+pub use siwe::{SiweError, SiweMessage, SiweVerification};
+
+pub mod sync;
+pub use sync::{PairingCode, SyncChannel, SyncError, SyncHandshake};
+
+pub mod typed_data;
+pub use typed_data::{
+  Permit, Permit2, TokenDomain, TypedData, TypedDataError, TypedField, PERMIT2_ADDRESS,
+};
+
+pub mod ur;
+pub use ur::{EthDataType, EthSignRequest, EthSignature, Ur, UrDecoder, UrError};
+
+pub mod usage;
+pub use usage::*;
+
+pub mod view;
+pub use view::*;
+
 pub mod errors;
 pub use errors::*;
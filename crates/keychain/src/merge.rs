@@ -0,0 +1,90 @@
+use crate::KeychainState;
+
+/// How to resolve an account labeled differently in
+/// the keychain and in the backup merged into it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelConflict {
+  /// Keep the label of the keychain
+  #[default]
+  KeepExisting,
+  /// Replace it with the label of the backup
+  Replace,
+  /// Join both labels, the one of the keychain first
+  Combine,
+}
+
+impl LabelConflict {
+  /// Get the label of an account labeled `existing` in the
+  /// keychain and `imported` in the merged backup
+  pub fn resolve(&self, existing: &str, imported: &str) -> String {
+    match self {
+      Self::KeepExisting => existing.to_string(),
+      Self::Replace => imported.to_string(),
+      Self::Combine => format!("{} / {}", existing, imported),
+    }
+  }
+}
+
+/// The outcome of merging a backup into a keychain
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeReport {
+  /// The indexes of the vaults created in the keychain
+  pub vaults: Vec<usize>,
+  /// The fingerprints of the vaults of the backup already
+  /// in the keychain, which were not imported again
+  pub duplicates: Vec<[u8; 4]>,
+  /// The addresses labeled differently in the keychain and
+  /// in the backup, resolved with a `LabelConflict` strategy
+  pub label_conflicts: Vec<String>,
+}
+
+impl KeychainState {
+  /// Merge the labels, quota usage, usage and dapp bindings of the accounts
+  /// of `other`, for the accounts held by the keychain. Returns the addresses whose
+  /// labels conflicted, resolved with `strategy`
+  pub(crate) fn merge_metadata(
+    &mut self,
+    other: &KeychainState,
+    strategy: LabelConflict,
+  ) -> Vec<String> {
+    let addresses = self
+      .accounts()
+      .into_iter()
+      .map(|account| account.address.clone())
+      .collect::<Vec<String>>();
+    let mut conflicts = vec![];
+
+    for address in &addresses {
+      match (self.labels.get(address), other.labels.get(address)) {
+        (Some(existing), Some(imported)) if existing != imported => {
+          let label = strategy.resolve(existing, imported);
+          self.labels.insert(address.clone(), label);
+          conflicts.push(address.clone());
+        }
+        (None, Some(imported)) => {
+          self.labels.insert(address.clone(), imported.clone());
+        }
+        _ => (),
+      }
+
+      if let Some(usage) = other.quota_usage.get(address) {
+        self.quota_usage.entry(address.clone()).or_insert(*usage);
+      }
+      if let Some(usage) = other.usage.get(address) {
+        let merged = self.account_usage(address).merge(usage);
+        self.usage.insert(address.clone(), merged);
+      }
+    }
+
+    for (origin, address) in &other.domains {
+      if addresses.contains(address) {
+        self
+          .domains
+          .entry(origin.clone())
+          .or_insert(address.clone());
+      }
+    }
+
+    conflicts
+  }
+}
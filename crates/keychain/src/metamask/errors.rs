@@ -0,0 +1,24 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetamaskError {
+  InvalidVault(String),
+  UnsupportedAlgorithm(String),
+  DecryptionFailed,
+  InvalidKeyring(String),
+}
+
+impl Display for MetamaskError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidVault(reason) => write!(f, "Invalid MetaMask vault: {}", reason),
+      Self::UnsupportedAlgorithm(algorithm) => {
+        write!(f, "Unsupported key derivation algorithm: {}", algorithm)
+      }
+      Self::DecryptionFailed => write!(f, "Wrong password or corrupted MetaMask vault"),
+      Self::InvalidKeyring(reason) => write!(f, "Invalid MetaMask keyring: {}", reason),
+    }
+  }
+}
+
+impl Error for MetamaskError {}
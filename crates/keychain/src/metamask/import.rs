@@ -0,0 +1,8 @@
+/// The outcome of importing a MetaMask vault into a keychain
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetamaskImport {
+  /// The indexes of the vaults created in the keychain
+  pub vaults: Vec<usize>,
+  /// The types of the keyrings not imported, like hardware wallets
+  pub skipped: Vec<String>,
+}
@@ -0,0 +1,150 @@
+use std::fmt::Debug;
+
+use serde_json::Value;
+use utils::hex::decode_to_array;
+
+use super::MetamaskError;
+
+/// The type of the MetaMask keyring holding a mnemonic
+pub const HD_KEYRING: &str = "HD Key Tree";
+
+/// The type of the MetaMask keyring holding imported private keys
+pub const SIMPLE_KEYRING: &str = "Simple Key Pair";
+
+/// The derivation path of the accounts of MetaMask HD keyrings,
+/// without the address index
+pub const DEFAULT_HD_PATH: &str = "m/44'/60'/0'/0";
+
+/// A keyring decrypted from a MetaMask vault
+#[derive(Clone, PartialEq)]
+pub enum MetamaskKeyring {
+  /// A keyring deriving accounts from a mnemonic
+  Hd {
+    /// The mnemonic phrase of the keyring
+    mnemonic: String,
+    /// The number of accounts derived from the keyring
+    number_of_accounts: usize,
+    /// The derivation path of the accounts, without the address index
+    hd_path: String,
+  },
+  /// A keyring holding private keys imported one by one
+  Simple {
+    /// The imported private keys
+    private_keys: Vec<[u8; 32]>,
+  },
+  /// A keyring with no secret material to import, like hardware wallets
+  Unsupported {
+    /// The type of the keyring
+    keyring_type: String,
+  },
+}
+
+impl MetamaskKeyring {
+  /// Get the type of the keyring, as named by MetaMask
+  pub fn keyring_type(&self) -> &str {
+    match self {
+      Self::Hd { .. } => HD_KEYRING,
+      Self::Simple { .. } => SIMPLE_KEYRING,
+      Self::Unsupported { keyring_type } => keyring_type,
+    }
+  }
+
+  /// Parse the data of a HD keyring. Recent MetaMask versions
+  /// store the mnemonic as an array of UTF-8 bytes
+  fn hd_from_json(data: &Value) -> Result<Self, MetamaskError> {
+    let mnemonic = match &data["mnemonic"] {
+      Value::String(mnemonic) => mnemonic.clone(),
+      Value::Array(bytes) => String::from_utf8(
+        bytes
+          .iter()
+          .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+          .collect::<Option<Vec<u8>>>()
+          .ok_or(MetamaskError::InvalidKeyring(
+            "Invalid mnemonic bytes".to_string(),
+          ))?,
+      )
+      .or(Err(MetamaskError::InvalidKeyring(
+        "Invalid mnemonic encoding".to_string(),
+      )))?,
+      _ => {
+        return Err(MetamaskError::InvalidKeyring(
+          "Missing mnemonic".to_string(),
+        ))
+      }
+    };
+
+    Ok(Self::Hd {
+      mnemonic,
+      number_of_accounts: data["numberOfAccounts"].as_u64().unwrap_or(1) as usize,
+      hd_path: data["hdPath"]
+        .as_str()
+        .unwrap_or(DEFAULT_HD_PATH)
+        .to_string(),
+    })
+  }
+
+  /// Parse the data of a simple keyring, a list of hex private keys
+  fn simple_from_json(data: &Value) -> Result<Self, MetamaskError> {
+    let private_keys = data
+      .as_array()
+      .ok_or(MetamaskError::InvalidKeyring(
+        "Missing private keys".to_string(),
+      ))?
+      .iter()
+      .map(|private_key| {
+        let private_key = private_key.as_str().ok_or(MetamaskError::InvalidKeyring(
+          "Invalid private key".to_string(),
+        ))?;
+        decode_to_array::<32>(private_key).or(Err(MetamaskError::InvalidKeyring(
+          "Invalid private key".to_string(),
+        )))
+      })
+      .collect::<Result<Vec<[u8; 32]>, MetamaskError>>()?;
+
+    Ok(Self::Simple { private_keys })
+  }
+}
+
+impl TryFrom<&Value> for MetamaskKeyring {
+  type Error = MetamaskError;
+
+  /// Parse a serialized MetaMask keyring, in the form `{ "type", "data" }`
+  fn try_from(keyring: &Value) -> Result<Self, MetamaskError> {
+    let keyring_type = keyring["type"]
+      .as_str()
+      .ok_or(MetamaskError::InvalidKeyring("Missing type".to_string()))?;
+
+    match keyring_type {
+      HD_KEYRING => Self::hd_from_json(&keyring["data"]),
+      SIMPLE_KEYRING => Self::simple_from_json(&keyring["data"]),
+      keyring_type => Ok(Self::Unsupported {
+        keyring_type: keyring_type.to_string(),
+      }),
+    }
+  }
+}
+
+impl Debug for MetamaskKeyring {
+  /// Format the keyring without its secret material
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Hd {
+        number_of_accounts,
+        hd_path,
+        ..
+      } => f
+        .debug_struct("Hd")
+        .field("number_of_accounts", number_of_accounts)
+        .field("hd_path", hd_path)
+        .finish_non_exhaustive(),
+      Self::Simple { private_keys } => f
+        .debug_struct("Simple")
+        .field("private_keys", &private_keys.len())
+        .finish(),
+      Self::Unsupported { keyring_type } => f
+        .debug_struct("Unsupported")
+        .field("keyring_type", keyring_type)
+        .finish(),
+    }
+  }
+}
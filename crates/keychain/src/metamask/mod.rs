@@ -0,0 +1,11 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod import;
+pub use import::*;
+
+pub mod keyring;
+pub use keyring::*;
+
+pub mod vault;
+pub use vault::*;
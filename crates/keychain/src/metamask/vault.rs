@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use aes_gcm::{
+  aead::{consts::U16, Aead, KeyInit},
+  aes::Aes256,
+  AesGcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pbkdf2::pbkdf2_hmac;
+use serde_json::Value;
+use sha2::Sha256;
+use utils::SecureBytes;
+
+use super::{MetamaskError, MetamaskKeyring};
+
+/// The PBKDF2 iterations of vaults created before
+/// MetaMask started storing key derivation metadata
+pub const LEGACY_ITERATIONS: u32 = 10_000;
+
+/// AES-256-GCM with the 16 bytes IVs used by MetaMask
+type MetamaskCipher = AesGcm<Aes256, U16>;
+
+/// An encrypted MetaMask extension vault, as created by
+/// `@metamask/browser-passworder`: keyrings serialized as JSON,
+/// encrypted with AES-256-GCM and a PBKDF2-SHA256 password key
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetamaskVault {
+  /// The encrypted keyrings, followed by the authentication tag
+  data: Vec<u8>,
+  /// The initialization vector of the cipher
+  iv: Vec<u8>,
+  /// The salt of the password key
+  salt: Vec<u8>,
+  /// The PBKDF2 iterations of the password key
+  iterations: u32,
+}
+
+impl MetamaskVault {
+  /// Get the PBKDF2 iterations of the password key
+  pub fn iterations(&self) -> u32 {
+    self.iterations
+  }
+
+  /// Decrypt the keyrings of the vault with the MetaMask password
+  pub fn decrypt(&self, password: &str) -> Result<Vec<MetamaskKeyring>, MetamaskError> {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(password.as_bytes(), &self.salt, self.iterations, &mut key);
+    let key = SecureBytes::new(&key);
+
+    let cipher = MetamaskCipher::new_from_slice(&key).or(Err(MetamaskError::DecryptionFailed))?;
+    let keyrings = SecureBytes::from(
+      cipher
+        .decrypt(Nonce::<U16>::from_slice(&self.iv), self.data.as_ref())
+        .or(Err(MetamaskError::DecryptionFailed))?,
+    );
+
+    serde_json::from_slice::<Value>(&keyrings)
+      .or(Err(MetamaskError::InvalidVault(
+        "Invalid keyrings".to_string(),
+      )))?
+      .as_array()
+      .ok_or(MetamaskError::InvalidVault("Invalid keyrings".to_string()))?
+      .iter()
+      .map(MetamaskKeyring::try_from)
+      .collect()
+  }
+
+  /// Find the encrypted vault in a JSON value, either the vault itself or
+  /// the browser storage of the extension, holding it as a string
+  /// in `KeyringController.vault`
+  fn find(value: &Value) -> Option<Value> {
+    match value {
+      Value::Object(object) if object.contains_key("iv") && object.contains_key("salt") => {
+        Some(value.clone())
+      }
+      Value::Object(object) => object.get("vault").and_then(Value::as_str).map_or_else(
+        || object.values().find_map(Self::find),
+        |vault| serde_json::from_str(vault).ok(),
+      ),
+      _ => None,
+    }
+  }
+}
+
+impl FromStr for MetamaskVault {
+  type Err = MetamaskError;
+
+  /// Parse a MetaMask vault, or the browser storage blob holding it
+  fn from_str(json: &str) -> Result<Self, MetamaskError> {
+    let value: Value = serde_json::from_str(json)
+      .or(Err(MetamaskError::InvalidVault("Invalid JSON".to_string())))?;
+    let vault =
+      Self::find(&value).ok_or(MetamaskError::InvalidVault("No vault found".to_string()))?;
+
+    let field = |name: &str| {
+      vault[name]
+        .as_str()
+        .and_then(|field| STANDARD.decode(field).ok())
+        .ok_or(MetamaskError::InvalidVault(format!("Invalid {}", name)))
+    };
+
+    let iterations = match &vault["keyMetadata"] {
+      Value::Null => LEGACY_ITERATIONS,
+      metadata => {
+        let algorithm = metadata["algorithm"].as_str().unwrap_or_default();
+        if algorithm != "PBKDF2" {
+          return Err(MetamaskError::UnsupportedAlgorithm(algorithm.to_string()));
+        }
+        metadata["params"]["iterations"]
+          .as_u64()
+          .and_then(|iterations| u32::try_from(iterations).ok())
+          .ok_or(MetamaskError::InvalidVault(
+            "Invalid iterations".to_string(),
+          ))?
+      }
+    };
+
+    let iv = field("iv")?;
+    if iv.len() != 16 {
+      return Err(MetamaskError::InvalidVault("Invalid iv length".to_string()));
+    }
+
+    Ok(Self {
+      data: field("data")?,
+      iv,
+      salt: field("salt")?,
+      iterations,
+    })
+  }
+}
@@ -0,0 +1,66 @@
+//! Operational metrics of a keychain, recorded through the `metrics`
+//! facade when the `metrics` feature is enabled, so that services embedding
+//! walleth can export them with any recorder, like a Prometheus exporter.
+//!
+//! Without the feature, recording a metric does nothing.
+
+/// Counter of the signatures produced, labeled by `source`
+/// (`keychain` or `pool`)
+pub const SIGNATURES: &str = "walleth_signatures_total";
+
+/// Counter of the unlock attempts, labeled by `outcome`
+/// (`success` or `failure`)
+pub const UNLOCK_ATTEMPTS: &str = "walleth_unlock_attempts_total";
+
+/// Counter of the signature requests rejected by the signing policy
+pub const POLICY_REJECTIONS: &str = "walleth_policy_rejections_total";
+
+/// Histogram of the time taken to answer JSON-RPC calls, in seconds,
+/// labeled by `method`
+pub const RPC_LATENCY: &str = "walleth_rpc_latency_seconds";
+
+/// Gauge of the signature requests waiting in the signing pool
+pub const SIGNING_POOL_QUEUE_DEPTH: &str = "walleth_signing_pool_queue_depth";
+
+/// Count a signature produced by `source`
+pub(crate) fn signature(source: &'static str) {
+  #[cfg(feature = "metrics")]
+  metrics::counter!(SIGNATURES, "source" => source).increment(1);
+  #[cfg(not(feature = "metrics"))]
+  let _ = source;
+}
+
+/// Count an unlock attempt, successful or not
+pub(crate) fn unlock_attempt(success: bool) {
+  #[cfg(feature = "metrics")]
+  metrics::counter!(
+    UNLOCK_ATTEMPTS,
+    "outcome" => if success { "success" } else { "failure" }
+  )
+  .increment(1);
+  #[cfg(not(feature = "metrics"))]
+  let _ = success;
+}
+
+/// Count a signature request rejected by the signing policy
+pub(crate) fn policy_rejection() {
+  #[cfg(feature = "metrics")]
+  metrics::counter!(POLICY_REJECTIONS).increment(1);
+}
+
+/// Record the time taken to answer a JSON-RPC call to `method`
+#[cfg(feature = "rpc")]
+pub(crate) fn rpc_latency(method: &str, elapsed: std::time::Duration) {
+  #[cfg(feature = "metrics")]
+  metrics::histogram!(RPC_LATENCY, "method" => method.to_string()).record(elapsed.as_secs_f64());
+  #[cfg(not(feature = "metrics"))]
+  let _ = (method, elapsed);
+}
+
+/// Record the number of signature requests waiting in the signing pool
+pub(crate) fn signing_pool_queue_depth(depth: usize) {
+  #[cfg(feature = "metrics")]
+  metrics::gauge!(SIGNING_POOL_QUEUE_DEPTH).set(depth as f64);
+  #[cfg(not(feature = "metrics"))]
+  let _ = depth;
+}
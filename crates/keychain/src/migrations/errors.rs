@@ -0,0 +1,36 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MigrationError {
+  UnsupportedVersion(u16),
+  MissingMigration(u16),
+  StepFailed(u16, String),
+  UnsupportedFlags(u8),
+  InvalidPayload(String),
+}
+
+impl Display for MigrationError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::UnsupportedVersion(version) => write!(
+        f,
+        "Backup version {} is newer than the supported one, refusing to downgrade",
+        version
+      ),
+      Self::MissingMigration(version) => {
+        write!(f, "No migration registered from version {}", version)
+      }
+      Self::StepFailed(version, reason) => {
+        write!(f, "Migration from version {} failed: {}", version, reason)
+      }
+      Self::UnsupportedFlags(flags) => write!(
+        f,
+        "Backup envelope flags {:#04x} are not supported, the backup may be compressed",
+        flags
+      ),
+      Self::InvalidPayload(reason) => write!(f, "Invalid backup payload: {}", reason),
+    }
+  }
+}
+
+impl Error for MigrationError {}
@@ -0,0 +1,248 @@
+use super::MigrationError;
+
+/// The version of the backups produced by this crate
+pub const BACKUP_VERSION: u16 = 3;
+
+/// The version of unversioned backups written before section and
+/// safe metadata lengths were widened from u8 to little endian u32,
+/// when vaults larger than 255 bytes could not be backed up
+pub const LEGACY_VERSION: u16 = 0;
+
+/// The bytes opening a versioned backup. Backups created before
+/// versioning start with the little endian u32 length of their first
+/// vault instead, which never takes this value in practice
+pub const BACKUP_MAGIC: [u8; 4] = [0xff, b'W', b'L', b'T'];
+
+/// The envelope flag marking a payload compressed with zstd,
+/// whose frame carries a checksum of the uncompressed content
+pub const COMPRESSED_FLAG: u8 = 1;
+
+/// A single step upgrading a backup payload from a version to the next one
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+  /// The version the step upgrades from, to `from + 1`
+  pub from: u16,
+  /// A human readable description of the step
+  pub description: &'static str,
+  /// The function upgrading the payload
+  pub migrate: fn(Vec<u8>) -> Result<Vec<u8>, String>,
+}
+
+/// What was migrated while loading a backup
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationReport {
+  /// The version of the loaded backup
+  pub from_version: u16,
+  /// The version the backup was upgraded to
+  pub to_version: u16,
+  /// The descriptions of the applied steps, in order
+  pub applied: Vec<&'static str>,
+}
+
+impl MigrationReport {
+  /// Check if any migration step was applied
+  pub fn is_migrated(&self) -> bool {
+    !self.applied.is_empty()
+  }
+}
+
+/// Upgrades backups of older versions to `BACKUP_VERSION`, one step at a time.
+///
+/// Backups of newer versions are rejected rather than read as if they
+/// were of the current one, so data is never silently downgraded.
+#[derive(Clone, Debug)]
+pub struct Migrator {
+  migrations: Vec<Migration>,
+}
+
+impl Migrator {
+  /// Create a `Migrator` with no migration steps
+  pub fn empty() -> Self {
+    Self { migrations: vec![] }
+  }
+
+  /// Register a migration step, replacing any other step
+  /// from the same version
+  pub fn with(mut self, migration: Migration) -> Self {
+    self.migrations.retain(|step| step.from != migration.from);
+    self.migrations.push(migration);
+    self
+  }
+
+  /// Upgrade a backup to `BACKUP_VERSION`, returning its unversioned
+  /// and uncompressed payload and a report of the applied steps
+  pub fn migrate(&self, backup: &[u8]) -> Result<(Vec<u8>, MigrationReport), MigrationError> {
+    let (from_version, mut payload) = read_envelope(backup);
+    if from_version > BACKUP_VERSION {
+      return Err(MigrationError::UnsupportedVersion(from_version));
+    }
+
+    let mut report = MigrationReport {
+      from_version,
+      to_version: BACKUP_VERSION,
+      applied: vec![],
+    };
+
+    for version in from_version..BACKUP_VERSION {
+      let step = self
+        .migrations
+        .iter()
+        .find(|step| step.from == version)
+        .ok_or(MigrationError::MissingMigration(version))?;
+
+      payload =
+        (step.migrate)(payload).map_err(|reason| MigrationError::StepFailed(version, reason))?;
+      report.applied.push(step.description);
+    }
+
+    Ok((read_flags(payload)?, report))
+  }
+}
+
+impl Default for Migrator {
+  /// Create a `Migrator` with the built-in migration steps
+  fn default() -> Self {
+    Self::empty()
+      .with(Migration {
+        from: LEGACY_VERSION,
+        description: "Widen section and safe metadata lengths to u32",
+        migrate: widen_lengths,
+      })
+      .with(Migration {
+        from: 1,
+        description: "Wrap unversioned backup in a versioned envelope",
+        // The payload layout did not change, only the envelope was added
+        migrate: Ok,
+      })
+      .with(Migration {
+        from: 2,
+        description: "Add flags to the envelope",
+        migrate: |payload| Ok([vec![0u8], payload].concat()),
+      })
+  }
+}
+
+/// Rewrite a legacy payload, made of vaults prefixed by their u8 length
+/// and type and holding safes prefixed by their u8 metadata length,
+/// with little endian u32 lengths
+fn widen_lengths(payload: Vec<u8>) -> Result<Vec<u8>, String> {
+  let mut widened = vec![];
+  let mut rest = payload.as_slice();
+
+  while let [length, section_type, tail @ ..] = rest {
+    let length = usize::from(*length);
+    let vault = tail.get(..length).ok_or("Unexpected vault length")?;
+    let (metadata_length, safe) = vault.split_first().ok_or("Unexpected safe length")?;
+
+    widened.extend((length as u32 + 3).to_le_bytes());
+    widened.push(*section_type);
+    widened.extend(u32::from(*metadata_length).to_le_bytes());
+    widened.extend(safe);
+    rest = &tail[length..];
+  }
+
+  match rest.is_empty() {
+    true => Ok(widened),
+    false => Err("Unexpected vault length".to_string()),
+  }
+}
+
+/// Check if a payload is made of sections prefixed
+/// by their u32 length and type, up to its last byte
+fn has_u32_sections(payload: &[u8]) -> bool {
+  let mut rest = payload;
+  while !rest.is_empty() {
+    let length = match rest.get(..4).and_then(|length| length.try_into().ok()) {
+      Some(length) => u32::from_le_bytes(length) as usize,
+      None => return false,
+    };
+    rest = match rest.get(5..).and_then(|tail| tail.get(length..)) {
+      Some(rest) => rest,
+      None => return false,
+    };
+  }
+
+  true
+}
+
+/// Check if a payload is made of vaults prefixed
+/// by their u8 length and type, up to its last byte
+fn has_u8_sections(payload: &[u8]) -> bool {
+  let mut rest = payload;
+  while let [length, 0u8, tail @ ..] = rest {
+    rest = match tail.get(usize::from(*length)..) {
+      Some(rest) => rest,
+      None => return false,
+    };
+  }
+
+  rest.is_empty()
+}
+
+/// Wrap a backup payload in an envelope carrying `BACKUP_VERSION`
+pub fn write_envelope(payload: &[u8]) -> Vec<u8> {
+  let mut bytes = envelope_header(0);
+  bytes.extend(payload);
+
+  bytes
+}
+
+/// Wrap a backup payload in an envelope carrying `BACKUP_VERSION`,
+/// compressing it with zstd
+#[cfg(feature = "compression")]
+pub fn write_compressed_envelope(payload: &[u8]) -> Result<Vec<u8>, String> {
+  use std::io::Write;
+
+  let compress = || {
+    let mut encoder = zstd::Encoder::new(
+      envelope_header(COMPRESSED_FLAG),
+      zstd::DEFAULT_COMPRESSION_LEVEL,
+    )?;
+    encoder.include_checksum(true)?;
+    encoder.write_all(payload)?;
+    encoder.finish()
+  };
+
+  compress().map_err(|error: std::io::Error| error.to_string())
+}
+
+/// The magic, version and flags opening a backup
+fn envelope_header(flags: u8) -> Vec<u8> {
+  let mut bytes = BACKUP_MAGIC.to_vec();
+  bytes.extend(BACKUP_VERSION.to_le_bytes());
+  bytes.push(flags);
+
+  bytes
+}
+
+/// Read the flags opening a payload of the current version,
+/// returning the payload they describe, decompressed if needed
+fn read_flags(payload: Vec<u8>) -> Result<Vec<u8>, MigrationError> {
+  match payload.split_first() {
+    Some((&0u8, payload)) => Ok(payload.to_vec()),
+    #[cfg(feature = "compression")]
+    Some((&COMPRESSED_FLAG, payload)) => {
+      zstd::decode_all(payload).map_err(|error| MigrationError::InvalidPayload(error.to_string()))
+    }
+    Some((flags, _)) => Err(MigrationError::UnsupportedFlags(*flags)),
+    None => Err(MigrationError::InvalidPayload(
+      "missing envelope flags".to_string(),
+    )),
+  }
+}
+
+/// Read the version and payload of a backup.
+/// Backups without an envelope are of version 1, or of
+/// `LEGACY_VERSION` when their sections have u8 lengths
+pub fn read_envelope(backup: &[u8]) -> (u16, Vec<u8>) {
+  match backup.len() >= 6 && backup[..4] == BACKUP_MAGIC {
+    true => (
+      u16::from_le_bytes([backup[4], backup[5]]),
+      backup[6..].to_vec(),
+    ),
+    false => match !has_u32_sections(backup) && has_u8_sections(backup) {
+      true => (LEGACY_VERSION, backup.to_vec()),
+      false => (1, backup.to_vec()),
+    },
+  }
+}
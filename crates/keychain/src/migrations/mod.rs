@@ -0,0 +1,5 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod migrator;
+pub use migrator::*;
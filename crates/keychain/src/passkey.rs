@@ -0,0 +1,32 @@
+use rand_core::{OsRng, RngCore};
+
+/// A passkey unlocking the keychain with the PRF extension of WebAuthn
+/// (`hmac-secret` for CTAP2 authenticators), with no typed password.
+///
+/// Evaluate the PRF of the passkey `credential_id` with `salt`, then pass
+/// its output to `Keychain::add_passkey` to enroll the passkey, or to
+/// `Keychain::unlock_with_passkey` to unlock the keychain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasskeyCredential {
+  /// The name of the key slots of the passkey
+  pub name: String,
+  /// The WebAuthn credential id of the passkey
+  pub credential_id: Vec<u8>,
+  /// The input the PRF of the passkey is evaluated with
+  pub salt: [u8; 16],
+}
+
+impl PasskeyCredential {
+  /// Prepare the enrollment of the passkey `credential_id`
+  /// under `name`, with a random PRF input
+  pub fn new(name: &str, credential_id: &[u8]) -> Self {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    Self {
+      name: name.to_string(),
+      credential_id: credential_id.to_vec(),
+      salt,
+    }
+  }
+}
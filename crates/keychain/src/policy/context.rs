@@ -0,0 +1,49 @@
+/// A contract call or transfer being signed, as inspected by policies
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransactionIntent {
+  /// The recipient of the transaction, `None` for contract deployments
+  pub to: Option<String>,
+  /// The value transferred, in wei
+  pub value: u128,
+  /// The calldata of the transaction
+  pub data: Vec<u8>,
+}
+
+/// What a signature request is about, as inspected by policies
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SigningContext {
+  /// The transaction being signed, if the payload is a transaction
+  pub transaction: Option<TransactionIntent>,
+  /// Explicitly allow signing calldata that cannot be decoded,
+  /// when the policy forbids blind signing
+  pub allow_blind_signing: bool,
+  /// The id of the chain the payload is signed for, if known
+  pub chain_id: Option<u64>,
+}
+
+impl SigningContext {
+  /// Create a context for signing a transaction
+  pub fn transaction(to: Option<&str>, value: u128, data: &[u8]) -> Self {
+    Self {
+      transaction: Some(TransactionIntent {
+        to: to.map(str::to_string),
+        value,
+        data: data.to_vec(),
+      }),
+      allow_blind_signing: false,
+      chain_id: None,
+    }
+  }
+
+  /// Set the id of the chain the payload is signed for
+  pub fn on_chain(mut self, chain_id: u64) -> Self {
+    self.chain_id = Some(chain_id);
+    self
+  }
+
+  /// Explicitly allow signing calldata that cannot be decoded
+  pub fn allowing_blind_signing(mut self) -> Self {
+    self.allow_blind_signing = true;
+    self
+  }
+}
@@ -0,0 +1,11 @@
+pub mod context;
+pub use context::*;
+
+pub mod password;
+pub use password::*;
+
+pub mod policy;
+pub use policy::*;
+
+pub mod quota;
+pub use quota::*;
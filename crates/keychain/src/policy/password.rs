@@ -0,0 +1,168 @@
+use std::fmt::{Display, Formatter};
+
+/// The number of bits contributed by a character repeating
+/// the previous one, or continuing a sequence like `abc` or `321`
+const PREDICTABLE_CHARACTER_BITS: f64 = 1.0;
+
+/// A password rejected by a `PasswordPolicy`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeakPassword {
+  /// The estimated entropy of the password, in bits
+  pub entropy_bits: f64,
+  /// Suggestions to make the password stronger
+  pub suggestions: Vec<String>,
+}
+
+impl Display for WeakPassword {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Weak password ({:.0} bits): {}",
+      self.entropy_bits,
+      self.suggestions.join(", ")
+    )
+  }
+}
+
+/// The requirements a password must meet to lock a `Keychain`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PasswordPolicy {
+  /// The minimum number of characters
+  pub min_length: usize,
+  /// The minimum estimated entropy, in bits
+  pub min_entropy_bits: f64,
+  /// Words that cannot appear in the password, case-insensitively
+  pub denylist: Vec<String>,
+}
+
+impl PasswordPolicy {
+  /// Create a new permissive policy, accepting any password
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a policy requiring at least 12 characters and 60 bits
+  /// of entropy, and rejecting some of the most common passwords
+  pub fn recommended() -> Self {
+    Self::new()
+      .with_min_length(12)
+      .with_min_entropy_bits(60.0)
+      .with_denylist(&[
+        "password", "123456", "qwerty", "letmein", "welcome", "admin", "iloveyou", "monkey",
+        "dragon", "abc123", "walleth",
+      ])
+  }
+
+  /// Require at least `min_length` characters
+  pub fn with_min_length(mut self, min_length: usize) -> Self {
+    self.min_length = min_length;
+    self
+  }
+
+  /// Require at least `min_entropy_bits` bits of estimated entropy
+  pub fn with_min_entropy_bits(mut self, min_entropy_bits: f64) -> Self {
+    self.min_entropy_bits = min_entropy_bits;
+    self
+  }
+
+  /// Reject passwords containing any of `words`
+  pub fn with_denylist(mut self, words: &[&str]) -> Self {
+    self
+      .denylist
+      .extend(words.iter().map(|word| word.to_lowercase()));
+    self
+  }
+
+  /// Check `password` against the policy
+  pub fn check(&self, password: &str) -> Result<(), WeakPassword> {
+    let entropy_bits = estimate_entropy(password);
+    let mut suggestions = vec![];
+
+    if password.chars().count() < self.min_length {
+      suggestions.push(format!("use at least {} characters", self.min_length));
+    }
+
+    let lowercase = password.to_lowercase();
+    if let Some(word) = self
+      .denylist
+      .iter()
+      .find(|word| !word.is_empty() && lowercase.contains(word.as_str()))
+    {
+      suggestions.push(format!(
+        "avoid common passwords and words like \"{}\"",
+        word
+      ));
+    }
+
+    if entropy_bits < self.min_entropy_bits {
+      if has_predictable_characters(password) {
+        suggestions.push("avoid repeated characters and sequences like \"abc\"".to_string());
+      }
+      suggestions.push("add more words, or mix uppercase letters, digits and symbols".to_string());
+    }
+
+    match suggestions.is_empty() {
+      true => Ok(()),
+      false => Err(WeakPassword {
+        entropy_bits,
+        suggestions,
+      }),
+    }
+  }
+}
+
+/// Estimate the entropy of a password, in bits.
+///
+/// Each character contributes the bits needed to pick it from the
+/// character classes the password uses (lowercase, uppercase, digits,
+/// symbols), except for characters repeating the previous one or
+/// continuing a sequence, which are easy to guess.
+pub fn estimate_entropy(password: &str) -> f64 {
+  let characters = password.chars().collect::<Vec<char>>();
+  let pool_bits = (pool_size(&characters) as f64).log2();
+
+  characters
+    .iter()
+    .enumerate()
+    .map(|(index, character)| match index {
+      0 => pool_bits,
+      _ if is_predictable(characters[index - 1], *character) => PREDICTABLE_CHARACTER_BITS,
+      _ => pool_bits,
+    })
+    .sum()
+}
+
+/// A class of characters: a predicate matching its members, and its size
+type CharacterClass = (fn(&char) -> bool, usize);
+
+/// Get the number of characters in the classes used by a password
+fn pool_size(characters: &[char]) -> usize {
+  let classes: [CharacterClass; 5] = [
+    (char::is_ascii_lowercase, 26),
+    (char::is_ascii_uppercase, 26),
+    (char::is_ascii_digit, 10),
+    (char::is_ascii_punctuation, 33),
+    (|character| !character.is_ascii(), 100),
+  ];
+
+  classes
+    .iter()
+    .filter(|(belongs, _)| characters.iter().any(belongs))
+    .map(|(_, size)| size)
+    .sum::<usize>()
+    .max(1)
+}
+
+/// Check whether a character repeats the previous one or continues a sequence
+fn is_predictable(previous: char, character: char) -> bool {
+  (previous as i64 - character as i64).abs() <= 1
+}
+
+/// Check whether a password has repeated characters or sequences
+fn has_predictable_characters(password: &str) -> bool {
+  let characters = password.chars().collect::<Vec<char>>();
+
+  characters
+    .windows(2)
+    .any(|pair| is_predictable(pair[0], pair[1]))
+}
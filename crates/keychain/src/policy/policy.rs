@@ -0,0 +1,237 @@
+use std::{
+  collections::BTreeMap,
+  fmt::{Debug, Display, Formatter},
+  sync::{Arc, Mutex},
+};
+
+use identity::Account;
+use utils::B256;
+
+use super::{QuotaUsage, SigningContext, SpendingLimit};
+use crate::{Decoder, DecoderError};
+
+/// The reason a signature request breaks a policy
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyViolation {
+  /// The transaction calldata cannot be decoded
+  BlindSigning(DecoderError),
+  /// The payload, identified by its digest, has been signed recently
+  DuplicatePayload(B256),
+  /// The transaction would transfer more than the daily value limit,
+  /// given what the account already spent that day
+  DailyValueExceeded { limit: u128, spent: u128 },
+  /// The account already signed its daily number of transactions
+  DailyTransactionsExceeded { limit: u32 },
+}
+
+impl Display for PolicyViolation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::BlindSigning(error) => write!(f, "Blind signing is not allowed: {}", error),
+      Self::DuplicatePayload(digest) => {
+        write!(f, "Payload {} has been signed recently", digest)
+      }
+      Self::DailyValueExceeded { limit, spent } => write!(
+        f,
+        "Daily value limit of {} wei exceeded, {} wei already spent",
+        limit, spent
+      ),
+      Self::DailyTransactionsExceeded { limit } => {
+        write!(f, "Daily limit of {} transactions reached", limit)
+      }
+    }
+  }
+}
+
+/// An event emitted when a policy is enforced
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyEvent {
+  /// A signature request was rejected
+  Rejected {
+    account: String,
+    violation: PolicyViolation,
+  },
+  /// A signature request breaking a policy was allowed
+  /// by an explicit override of the caller
+  Overridden {
+    account: String,
+    violation: PolicyViolation,
+  },
+  /// A payload already signed recently was submitted again
+  DuplicatePayload {
+    account: String,
+    digest: B256,
+    refused: bool,
+  },
+}
+
+/// A listener of the policy events
+pub type PolicyListener = Box<dyn FnMut(&PolicyEvent) + Send + Sync>;
+
+/// The rules a `Keychain` enforces before signing.
+/// Clones share the listeners of the policy they are cloned from
+#[derive(Clone)]
+pub struct SigningPolicy {
+  /// Reject transactions whose calldata cannot be decoded
+  pub no_blind_signing: bool,
+  /// The decoder used to recognize calldata
+  pub decoder: Decoder,
+  /// The daily spending limits, indexed by lowercase account address
+  pub spending_limits: BTreeMap<String, SpendingLimit>,
+  /// Listeners of the policy events
+  listeners: Vec<Arc<Mutex<PolicyListener>>>,
+}
+
+impl SigningPolicy {
+  /// Create a new permissive policy, decoding calldata
+  /// with the built-in functions
+  pub fn new() -> Self {
+    Self {
+      no_blind_signing: false,
+      decoder: Decoder::with_builtins(),
+      spending_limits: BTreeMap::new(),
+      listeners: vec![],
+    }
+  }
+
+  /// Reject transactions whose calldata cannot be decoded,
+  /// unless the caller explicitly allows it
+  pub fn with_no_blind_signing(mut self) -> Self {
+    self.no_blind_signing = true;
+    self
+  }
+
+  /// Limit the transactions signed each day by the account at `address`
+  pub fn with_spending_limit(mut self, address: &str, limit: SpendingLimit) -> Self {
+    self.spending_limits.insert(address.to_lowercase(), limit);
+    self
+  }
+
+  /// Get the spending limit of the account at `address`, in any case
+  pub fn spending_limit(&self, address: &str) -> Option<&SpendingLimit> {
+    self.spending_limits.get(&address.to_lowercase())
+  }
+
+  /// Check if the policy allows any signature, without
+  /// blind signing protection nor spending limits
+  pub fn is_permissive(&self) -> bool {
+    !self.no_blind_signing && self.spending_limits.is_empty()
+  }
+
+  /// Listen to the events emitted when the policy is enforced
+  pub fn subscribe<F>(&mut self, listener: F)
+  where
+    F: 'static + FnMut(&PolicyEvent) + Send + Sync,
+  {
+    self
+      .listeners
+      .push(Arc::new(Mutex::new(Box::new(listener))));
+  }
+
+  /// Check a signature request of `account` against the policy
+  pub fn check(
+    &mut self,
+    account: &Account,
+    context: &SigningContext,
+  ) -> Result<(), PolicyViolation> {
+    let violation = match self.find_violation(context) {
+      Some(violation) => violation,
+      None => return Ok(()),
+    };
+
+    let allowed = match violation {
+      PolicyViolation::BlindSigning(_) => context.allow_blind_signing,
+      _ => false,
+    };
+    let event = match allowed {
+      true => PolicyEvent::Overridden {
+        account: account.address.clone(),
+        violation: violation.clone(),
+      },
+      false => PolicyEvent::Rejected {
+        account: account.address.clone(),
+        violation: violation.clone(),
+      },
+    };
+    self.emit(&event);
+
+    match allowed {
+      true => Ok(()),
+      false => Err(violation),
+    }
+  }
+
+  /// Check a transaction of `account` against its spending limit,
+  /// given its `usage` on the current day. Spending limits cannot be
+  /// overridden by the caller
+  pub fn check_quota(
+    &mut self,
+    account: &Account,
+    context: &SigningContext,
+    usage: &QuotaUsage,
+  ) -> Result<(), PolicyViolation> {
+    let (transaction, limit) = match (&context.transaction, self.spending_limit(&account.address)) {
+      (Some(transaction), Some(limit)) => (transaction, limit),
+      _ => return Ok(()),
+    };
+
+    let after = usage.with(transaction);
+    let violation = match (limit.daily_value, limit.daily_transactions) {
+      (_, Some(limit)) if after.transactions > limit => {
+        PolicyViolation::DailyTransactionsExceeded { limit }
+      }
+      (Some(limit), _) if after.value > limit => PolicyViolation::DailyValueExceeded {
+        limit,
+        spent: usage.value,
+      },
+      _ => return Ok(()),
+    };
+    self.emit(&PolicyEvent::Rejected {
+      account: account.address.clone(),
+      violation: violation.clone(),
+    });
+
+    Err(violation)
+  }
+
+  /// Find the first rule of the policy broken by a request
+  fn find_violation(&self, context: &SigningContext) -> Option<PolicyViolation> {
+    let transaction = context.transaction.as_ref()?;
+
+    if self.no_blind_signing && !transaction.data.is_empty() {
+      if let Err(error) = self
+        .decoder
+        .decode(transaction.to.as_deref(), &transaction.data)
+      {
+        return Some(PolicyViolation::BlindSigning(error));
+      }
+    }
+
+    None
+  }
+
+  /// Notify all the listeners of an event
+  pub(crate) fn emit(&mut self, event: &PolicyEvent) {
+    self.listeners.iter().for_each(|listener| {
+      if let Ok(mut listener) = listener.lock() {
+        listener(event)
+      }
+    });
+  }
+}
+
+impl Default for SigningPolicy {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Debug for SigningPolicy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SigningPolicy")
+      .field("no_blind_signing", &self.no_blind_signing)
+      .field("decoder", &self.decoder)
+      .field("spending_limits", &self.spending_limits)
+      .finish()
+  }
+}
@@ -0,0 +1,120 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::TransactionIntent;
+
+/// The length of a quota period, in seconds
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Get the number of days elapsed since the UNIX epoch, in UTC
+pub fn current_day() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+    .unwrap_or_default()
+}
+
+/// The daily limits of the transactions signed by an account.
+/// Limits reset at midnight UTC
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpendingLimit {
+  /// The maximum value transferred in a day, in wei
+  pub daily_value: Option<u128>,
+  /// The maximum number of transactions signed in a day
+  pub daily_transactions: Option<u32>,
+}
+
+impl SpendingLimit {
+  /// Create a new limit, allowing any transaction
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Limit the value transferred in a day, in wei
+  pub fn with_daily_value(mut self, value: u128) -> Self {
+    self.daily_value = Some(value);
+    self
+  }
+
+  /// Limit the number of transactions signed in a day
+  pub fn with_daily_transactions(mut self, transactions: u32) -> Self {
+    self.daily_transactions = Some(transactions);
+    self
+  }
+}
+
+/// What an account spent on a day
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QuotaUsage {
+  /// The day of the usage, in days since the UNIX epoch
+  pub day: u64,
+  /// The value transferred, in wei
+  pub value: u128,
+  /// The number of transactions signed
+  pub transactions: u32,
+}
+
+impl QuotaUsage {
+  /// Get the usage on `day`, empty if this usage is of another day
+  pub fn on(&self, day: u64) -> Self {
+    match self.day == day {
+      true => *self,
+      false => Self {
+        day,
+        ..Default::default()
+      },
+    }
+  }
+
+  /// Get the usage after signing `transaction`
+  pub fn with(&self, transaction: &TransactionIntent) -> Self {
+    Self {
+      day: self.day,
+      value: self.value.saturating_add(transaction.value),
+      transactions: self.transactions.saturating_add(1),
+    }
+  }
+
+  /// Get the usage before signing `transaction`
+  pub fn without(&self, transaction: &TransactionIntent) -> Self {
+    Self {
+      day: self.day,
+      value: self.value.saturating_sub(transaction.value),
+      transactions: self.transactions.saturating_sub(1),
+    }
+  }
+
+  /// Get the JSON representation of the usage.
+  /// The value is a decimal string, as it may not fit a JSON number
+  pub fn to_json(&self) -> Value {
+    json!({
+      "day": self.day,
+      "value": self.value.to_string(),
+      "transactions": self.transactions,
+    })
+  }
+}
+
+impl TryFrom<&Value> for QuotaUsage {
+  type Error = String;
+
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    Ok(Self {
+      day: value
+        .get("day")
+        .and_then(Value::as_u64)
+        .ok_or("Invalid quota day")?,
+      value: value
+        .get("value")
+        .and_then(Value::as_str)
+        .and_then(|value| value.parse().ok())
+        .ok_or("Invalid quota value")?,
+      transactions: value
+        .get("transactions")
+        .and_then(Value::as_u64)
+        .and_then(|transactions| u32::try_from(transactions).ok())
+        .ok_or("Invalid quota transactions")?,
+    })
+  }
+}
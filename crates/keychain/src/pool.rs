@@ -0,0 +1,241 @@
+use std::{
+  collections::HashMap,
+  fmt::{Debug, Formatter},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{channel, Receiver, Sender},
+    Arc,
+  },
+  thread::{self, JoinHandle},
+};
+
+use identity::{
+  signer::{IntoSignable, Signable, Signature, SignatureOptions, Signer},
+  Account,
+};
+use utils::SecureBytes;
+
+use crate::{metrics, KeychainError};
+
+/// The reply to a signature request sent to a `SigningPool`
+pub type PendingSignature = Receiver<Signature>;
+
+/// A request handled by a signing worker
+enum Request {
+  Sign {
+    message: Signable,
+    options: SignatureOptions,
+    reply: Sender<Signature>,
+  },
+  Drain,
+}
+
+/// A signing worker, owning the private key of one account
+struct Worker {
+  sender: Sender<Request>,
+  thread: JoinHandle<()>,
+}
+
+impl Worker {
+  /// Spawn a worker signing with `private_key`, counting
+  /// the requests it serves out of `queued`
+  fn spawn(private_key: SecureBytes, queued: Arc<AtomicUsize>) -> Self {
+    let (sender, receiver) = channel::<Request>();
+    let thread = thread::spawn(move || {
+      // Requests are served in order, so all the requests
+      // sent before draining are signed before exiting
+      for request in receiver {
+        match request {
+          Request::Sign {
+            message,
+            options,
+            reply,
+          } => {
+            // The key is checked when the pool starts
+            let signature =
+              signer(&private_key).map(|signer| signer.sign_with_options(&message, &options));
+            metrics::signing_pool_queue_depth(queued.fetch_sub(1, Ordering::SeqCst) - 1);
+            if let Some(signature) = signature {
+              metrics::signature("pool");
+              // The requester may have stopped waiting for the signature
+              let _ = reply.send(signature);
+            }
+          }
+          Request::Drain => break,
+        }
+      }
+    });
+
+    Self { sender, thread }
+  }
+}
+
+/// Build a signer from a private key, only for the time of a signature
+fn signer(private_key: &SecureBytes) -> Option<Signer> {
+  let mut bytes = [0u8; 32];
+  bytes.copy_from_slice(private_key.get(..32)?);
+  let signer = Signer::new(bytes).ok();
+  bytes.fill(0);
+
+  signer
+}
+
+/// A pool of threads signing in parallel for a set of accounts,
+/// for services producing large amounts of signatures.
+///
+/// Each account gets a dedicated thread holding its private key,
+/// derived once when the pool is started. Requests are sent to the
+/// threads through channels, with a `SigningPoolHandle`.
+///
+/// Signatures produced by the pool do not go through the keychain, so
+/// the pool cannot start while a signing policy, a spending limit or
+/// a payload ledger is configured. Its start is recorded in the audit
+/// log, not its signatures. The threads and their keys are dropped
+/// when the pool is stopped or dropped, even if handles are still alive.
+pub struct SigningPool {
+  workers: Vec<Worker>,
+  handle: SigningPoolHandle,
+}
+
+impl SigningPool {
+  /// Start a pool signing for `accounts`, with their private keys
+  pub(crate) fn start(accounts: Vec<(Account, SecureBytes)>) -> Result<Self, KeychainError> {
+    let mut workers = vec![];
+    let mut senders = HashMap::new();
+    let queued = Arc::new(AtomicUsize::new(0));
+
+    for (account, private_key) in accounts {
+      signer(&private_key).ok_or(KeychainError::KeyNotFoundForAddress(
+        account.address.clone(),
+      ))?;
+      let worker = Worker::spawn(private_key, Arc::clone(&queued));
+      senders.insert(account.address, worker.sender.clone());
+      workers.push(worker);
+    }
+
+    Ok(Self {
+      workers,
+      handle: SigningPoolHandle {
+        senders: Arc::new(senders),
+        queued,
+      },
+    })
+  }
+
+  /// Get a handle to send signature requests to the pool
+  pub fn handle(&self) -> SigningPoolHandle {
+    self.handle.clone()
+  }
+
+  /// Stop the pool, after signing all the requests already sent.
+  /// The private keys are dropped together with the threads
+  pub fn drain(mut self) {
+    self.shutdown();
+  }
+
+  /// Stop the threads of the pool, after signing all the requests
+  /// already sent. Requests sent afterwards fail with
+  /// `KeychainError::SigningPoolClosed`
+  fn shutdown(&mut self) {
+    let workers = std::mem::take(&mut self.workers);
+    workers.iter().for_each(|worker| {
+      // The worker may have already exited
+      let _ = worker.sender.send(Request::Drain);
+    });
+    workers.into_iter().for_each(|worker| {
+      let _ = worker.thread.join();
+    });
+  }
+}
+
+impl Drop for SigningPool {
+  fn drop(&mut self) {
+    self.shutdown();
+  }
+}
+
+impl Debug for SigningPool {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SigningPool")
+      .field("workers", &self.workers.len())
+      .finish()
+  }
+}
+
+/// A cloneable handle to send signature requests to a `SigningPool`
+/// from any thread
+#[derive(Clone)]
+pub struct SigningPoolHandle {
+  senders: Arc<HashMap<String, Sender<Request>>>,
+  /// The number of requests sent and not signed yet
+  queued: Arc<AtomicUsize>,
+}
+
+impl SigningPoolHandle {
+  /// Get the addresses of the accounts the pool signs for
+  pub fn addresses(&self) -> Vec<&str> {
+    self.senders.keys().map(String::as_str).collect()
+  }
+
+  /// Get the number of signature requests waiting to be signed
+  pub fn queue_depth(&self) -> usize {
+    self.queued.load(Ordering::SeqCst)
+  }
+
+  /// Request a signature of `message` with the account matching `address`,
+  /// without waiting for it
+  pub fn submit<S>(
+    &self,
+    address: &str,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<PendingSignature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let sender = self
+      .senders
+      .get(address)
+      .ok_or(KeychainError::KeyNotFoundForAddress(address.to_string()))?;
+    let (reply, pending) = channel();
+
+    metrics::signing_pool_queue_depth(self.queued.fetch_add(1, Ordering::SeqCst) + 1);
+    sender
+      .send(Request::Sign {
+        message: message.to_signable(),
+        options: *options,
+        reply,
+      })
+      .map_err(|_| {
+        metrics::signing_pool_queue_depth(self.queued.fetch_sub(1, Ordering::SeqCst) - 1);
+        KeychainError::SigningPoolClosed
+      })?;
+
+    Ok(pending)
+  }
+
+  /// Sign `message` with the account matching `address`,
+  /// waiting for the signature
+  pub fn sign<S>(
+    &self,
+    address: &str,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self
+      .submit(address, message, options)?
+      .recv()
+      .or(Err(KeychainError::SigningPoolClosed))
+  }
+}
+
+impl Debug for SigningPoolHandle {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SigningPoolHandle")
+      .field("addresses", &self.addresses())
+      .finish()
+  }
+}
@@ -0,0 +1,8 @@
+/// What to do with the draft of a `Keychain::preview`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreviewOutcome {
+  /// Apply the changes of the draft to the keychain
+  Commit,
+  /// Drop the draft, leaving the keychain untouched
+  Discard,
+}
@@ -0,0 +1,90 @@
+use crate::{KeychainState, VaultState, VaultStatus};
+
+/// The profile holding the vaults of a keychain not assigned to any other
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The public state of a named group of vaults of the keychain,
+/// like "personal", "work" or "testnet"
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileState {
+  /// The name of the profile, unique in the keychain
+  pub name: String,
+  /// The indexes of the vaults in the profile, sorted
+  pub vaults: Vec<usize>,
+}
+
+impl ProfileState {
+  /// Create a new empty profile
+  pub fn new(name: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      vaults: vec![],
+    }
+  }
+
+  /// Check if the profile contains the vault at `index`
+  pub fn contains(&self, index: usize) -> bool {
+    self.vaults.contains(&index)
+  }
+}
+
+impl KeychainState {
+  /// Get the profile named `name`, if any
+  pub fn profile(&self, name: &str) -> Option<&ProfileState> {
+    self.profiles.iter().find(|profile| profile.name == name)
+  }
+
+  /// Get the profile the vault at `index` belongs to, if any
+  pub fn profile_of(&self, index: usize) -> Option<&ProfileState> {
+    self.profiles.iter().find(|profile| profile.contains(index))
+  }
+
+  /// Get the vaults of the profile named `name`, or
+  /// an empty list if the profile does not exist
+  pub fn profile_vaults(&self, name: &str) -> Vec<&VaultState> {
+    self
+      .profile(name)
+      .map(|profile| {
+        profile
+          .vaults
+          .iter()
+          .filter_map(|index| self.vaults.get(*index))
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// Get the status of the profile named `name`, locked when it has
+  /// at least one vault and all of them are locked
+  pub fn profile_status(&self, name: &str) -> Option<VaultStatus> {
+    self.profile(name)?;
+    let vaults = self.profile_vaults(name);
+    match !vaults.is_empty() && vaults.iter().all(|vault| vault.locked) {
+      true => Some(VaultStatus::Locked),
+      false => Some(VaultStatus::Unlocked),
+    }
+  }
+
+  /// Get the profile currently selected
+  pub fn active_profile(&self) -> Option<&ProfileState> {
+    self.profile(&self.active_profile)
+  }
+
+  /// Move the vault at `index` to the profile named `name`,
+  /// removing it from the profile it belonged to
+  pub(crate) fn assign_vault(&mut self, index: usize, name: &str) {
+    self
+      .profiles
+      .iter_mut()
+      .for_each(|profile| profile.vaults.retain(|vault| *vault != index));
+
+    if let Some(profile) = self
+      .profiles
+      .iter_mut()
+      .find(|profile| profile.name == name)
+    {
+      profile.vaults.push(index);
+      profile.vaults.sort_unstable();
+    }
+  }
+}
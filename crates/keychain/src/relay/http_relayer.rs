@@ -0,0 +1,46 @@
+use super::{Relayer, SignedForwardRequest};
+use crate::KeychainError;
+
+/// A `Relayer` posting each signed request to an HTTP endpoint as
+/// JSON, and reading the relayed transaction identifier from the body
+#[derive(Clone, Debug)]
+pub struct HttpRelayer {
+  /// The endpoint receiving the requests
+  url: String,
+  /// Additional headers sent with each request, like an API key
+  headers: Vec<(String, String)>,
+}
+
+impl HttpRelayer {
+  /// Create a new relayer posting requests to `url`
+  pub fn new(url: &str) -> Self {
+    Self {
+      url: url.to_string(),
+      headers: vec![],
+    }
+  }
+
+  /// Add a header sent with each request
+  pub fn with_header(mut self, name: &str, value: &str) -> Self {
+    self.headers.push((name.to_string(), value.to_string()));
+    self
+  }
+}
+
+impl Relayer for HttpRelayer {
+  fn relay(&mut self, request: &SignedForwardRequest) -> Result<String, KeychainError> {
+    let http_request = self.headers.iter().fold(
+      ureq::post(&self.url).set("Content-Type", "application/json"),
+      |http_request, (name, value)| http_request.set(name, value),
+    );
+
+    let response = http_request
+      .send_string(&request.to_json().to_string())
+      .map_err(|error| KeychainError::RelayError(error.to_string()))?;
+
+    match response.into_string() {
+      Ok(body) => Ok(body.trim().to_string()),
+      Err(error) => Err(KeychainError::RelayError(error.to_string())),
+    }
+  }
+}
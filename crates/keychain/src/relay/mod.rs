@@ -0,0 +1,10 @@
+pub mod request;
+pub use request::*;
+
+pub mod relayer;
+pub use relayer::*;
+
+#[cfg(feature = "http-relayer")]
+pub mod http_relayer;
+#[cfg(feature = "http-relayer")]
+pub use http_relayer::*;
@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+
+use super::SignedForwardRequest;
+use crate::KeychainError;
+
+/// A service submitting signed forward requests on chain,
+/// paying for their gas on behalf of the signers
+pub trait Relayer {
+  /// Submit a signed forward request, returning the
+  /// identifier of the relayed transaction given by the relayer
+  fn relay(&mut self, request: &SignedForwardRequest) -> Result<String, KeychainError>;
+}
+
+impl<F> Relayer for F
+where
+  F: FnMut(&SignedForwardRequest) -> Result<String, KeychainError>,
+{
+  fn relay(&mut self, request: &SignedForwardRequest) -> Result<String, KeychainError> {
+    self(request)
+  }
+}
+
+impl Debug for dyn Relayer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Relayer")
+  }
+}
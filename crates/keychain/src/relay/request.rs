@@ -0,0 +1,115 @@
+use identity::signer::Signature;
+use serde_json::{json, Value};
+use utils::hex::encode_prefixed;
+
+use crate::{TypedData, TypedDataError};
+
+/// An ERC-2771 trusted forwarder, as deployed from OpenZeppelin's
+/// `ERC2771Forwarder`, identified by its EIP-712 domain
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forwarder {
+  /// The name of the forwarder domain, given at its deployment
+  pub name: String,
+  /// The version of the forwarder domain, usually `1`
+  pub version: String,
+  /// The id of the chain the forwarder is deployed on
+  pub chain_id: u64,
+  /// The address of the forwarder contract
+  pub address: String,
+}
+
+/// A call relayed through a forwarder on behalf of `from`,
+/// so that the relayer pays for the gas
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ForwardRequest {
+  /// The address of the account signing the request
+  pub from: String,
+  /// The address of the called contract, trusting the forwarder
+  pub to: String,
+  /// The value sent with the call, in wei
+  pub value: u128,
+  /// The gas limit of the call
+  pub gas: u64,
+  /// The forwarder nonce of `from`, as returned by `nonces(from)`
+  pub nonce: u64,
+  /// Seconds since the UNIX epoch after which the request is invalid
+  pub deadline: u64,
+  /// The calldata of the call
+  pub data: Vec<u8>,
+}
+
+impl ForwardRequest {
+  /// Get the request as EIP-712 typed data of `forwarder`.
+  /// The nonce is part of the signed payload, although the forwarder
+  /// reads it from its own storage when executing the request
+  pub fn to_typed_data(&self, forwarder: &Forwarder) -> Result<TypedData, TypedDataError> {
+    TypedData::try_from(&json!({
+      "types": {
+        "EIP712Domain": [
+          { "name": "name", "type": "string" },
+          { "name": "version", "type": "string" },
+          { "name": "chainId", "type": "uint256" },
+          { "name": "verifyingContract", "type": "address" },
+        ],
+        "ForwardRequest": [
+          { "name": "from", "type": "address" },
+          { "name": "to", "type": "address" },
+          { "name": "value", "type": "uint256" },
+          { "name": "gas", "type": "uint256" },
+          { "name": "nonce", "type": "uint256" },
+          { "name": "deadline", "type": "uint48" },
+          { "name": "data", "type": "bytes" },
+        ],
+      },
+      "primaryType": "ForwardRequest",
+      "domain": {
+        "name": forwarder.name,
+        "version": forwarder.version,
+        "chainId": forwarder.chain_id,
+        "verifyingContract": forwarder.address,
+      },
+      "message": self.message(),
+    }))
+  }
+
+  /// Get the JSON object of the request, with the
+  /// integers as decimal strings and the data as hex
+  fn message(&self) -> Value {
+    json!({
+      "from": self.from,
+      "to": self.to,
+      "value": self.value.to_string(),
+      "gas": self.gas.to_string(),
+      "nonce": self.nonce.to_string(),
+      "deadline": self.deadline.to_string(),
+      "data": encode_prefixed(&self.data),
+    })
+  }
+}
+
+/// A forward request signed by its sender, ready to be submitted to a relayer
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedForwardRequest {
+  /// The forwarder executing the request
+  pub forwarder: Forwarder,
+  /// The signed request
+  pub request: ForwardRequest,
+  /// The recoverable signature of the sender
+  pub signature: Signature,
+}
+
+impl SignedForwardRequest {
+  /// Get the JSON body submitted to a relayer: the forwarder address
+  /// and chain, and the request with its 65-bytes RSV signature,
+  /// as passed to `ERC2771Forwarder.execute`
+  pub fn to_json(&self) -> Value {
+    let mut request = self.request.message();
+    request["signature"] = Value::from(self.signature.to_rsv_hex().unwrap_or_default());
+
+    json!({
+      "chainId": self.forwarder.chain_id,
+      "forwarder": self.forwarder.address,
+      "request": request,
+    })
+  }
+}
@@ -0,0 +1,127 @@
+//! A minimal RLP encoder, for the few structures signed or hashed
+//! by the keychain
+
+/// Encode a big-endian unsigned integer, without its leading zeros
+pub(crate) fn rlp_uint(value: &[u8]) -> Vec<u8> {
+  let start = value
+    .iter()
+    .position(|byte| *byte != 0)
+    .unwrap_or(value.len());
+
+  rlp_bytes(&value[start..])
+}
+
+/// Encode a byte string
+pub(crate) fn rlp_bytes(value: &[u8]) -> Vec<u8> {
+  match value {
+    [byte] if *byte < 0x80 => vec![*byte],
+    _ => {
+      let mut bytes = rlp_header(0x80, value.len());
+      bytes.extend(value);
+      bytes
+    }
+  }
+}
+
+/// Encode a list of already encoded items
+pub(crate) fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+  let payload = items.concat();
+  let mut bytes = rlp_header(0xc0, payload.len());
+  bytes.extend(payload);
+
+  bytes
+}
+
+/// Encode the header of an item of `length` bytes, with `offset`
+/// being `0x80` for byte strings and `0xc0` for lists
+fn rlp_header(offset: u8, length: usize) -> Vec<u8> {
+  match length {
+    0..=55 => vec![offset + length as u8],
+    _ => {
+      let length = length.to_be_bytes();
+      let length = &length[length.iter().position(|byte| *byte != 0).unwrap_or(0)..];
+      let mut bytes = vec![offset + 55 + length.len() as u8];
+      bytes.extend(length);
+      bytes
+    }
+  }
+}
+
+/// A decoded RLP item, borrowing the bytes it was decoded from
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum RlpItem<'a> {
+  /// A byte string
+  Bytes(&'a [u8]),
+  /// A list of items
+  List(Vec<RlpItem<'a>>),
+}
+
+impl<'a> RlpItem<'a> {
+  /// Get the bytes of a byte string item
+  pub(crate) fn as_bytes(&self) -> Option<&'a [u8]> {
+    match self {
+      Self::Bytes(bytes) => Some(bytes),
+      Self::List(_) => None,
+    }
+  }
+
+  /// Get the items of a list item
+  pub(crate) fn as_list(&self) -> Option<&[RlpItem<'a>]> {
+    match self {
+      Self::Bytes(_) => None,
+      Self::List(items) => Some(items),
+    }
+  }
+}
+
+/// Decode a single item spanning all of `bytes`, or
+/// `None` if the bytes are not a valid RLP encoding
+pub(crate) fn rlp_decode(bytes: &[u8]) -> Option<RlpItem<'_>> {
+  match rlp_decode_item(bytes)? {
+    (item, []) => Some(item),
+    _ => None,
+  }
+}
+
+/// Decode the item at the start of `bytes`, with the bytes following it
+fn rlp_decode_item(bytes: &[u8]) -> Option<(RlpItem<'_>, &[u8])> {
+  let (prefix, rest) = bytes.split_first()?;
+
+  match prefix {
+    0x00..=0x7f => Some((RlpItem::Bytes(&bytes[..1]), rest)),
+    0x80..=0xbf => {
+      let (payload, rest) = rlp_decode_payload(prefix - 0x80, rest)?;
+      Some((RlpItem::Bytes(payload), rest))
+    }
+    0xc0..=0xff => {
+      let (mut payload, rest) = rlp_decode_payload(prefix - 0xc0, rest)?;
+      let mut items = vec![];
+      while !payload.is_empty() {
+        let (item, remaining) = rlp_decode_item(payload)?;
+        items.push(item);
+        payload = remaining;
+      }
+      Some((RlpItem::List(items), rest))
+    }
+  }
+}
+
+/// Split the payload announced by a header `marker`, being the prefix
+/// minus its `0x80` or `0xc0` offset, from the bytes following it
+fn rlp_decode_payload(marker: u8, bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+  let (length, bytes) = match marker {
+    0..=55 => (marker as usize, bytes),
+    _ => {
+      let (length, bytes) = bytes.split_at_checked((marker - 55) as usize)?;
+      if length.len() > std::mem::size_of::<usize>() {
+        return None;
+      }
+      let length = length
+        .iter()
+        .fold(0usize, |length, byte| length << 8 | *byte as usize);
+      (length, bytes)
+    }
+  };
+
+  bytes.split_at_checked(length)
+}
@@ -0,0 +1,40 @@
+use identity::DerivationPath;
+
+/// An account of a retired vault and the account
+/// replacing it in the vault created to rotate it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RotationPair {
+  /// The address of the account of the retired vault
+  pub from: String,
+  /// The address of the account replacing it
+  pub to: String,
+  /// The derivation path shared by both accounts
+  pub path: DerivationPath,
+  /// The last balance of the retired account seen by an
+  /// account tracker, in wei, to be swept to the new one
+  pub balance: Option<u128>,
+}
+
+/// The plan to move funds out of a vault whose seed may be compromised,
+/// produced by `Keychain::rotate_vault`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RotationPlan {
+  /// The index of the vault being retired
+  pub from_vault: usize,
+  /// The index of the vault created to replace it
+  pub to_vault: usize,
+  /// The accounts of the retired vault, paired with their replacements
+  pub pairs: Vec<RotationPair>,
+}
+
+impl RotationPlan {
+  /// Get the pairs whose retired account still holds funds,
+  /// as last seen by an account tracker
+  pub fn pending_sweeps(&self) -> Vec<&RotationPair> {
+    self
+      .pairs
+      .iter()
+      .filter(|pair| pair.balance.is_some_and(|balance| balance > 0))
+      .collect()
+  }
+}
@@ -0,0 +1,90 @@
+use std::{error::Error, fmt::Display};
+
+use serde_json::{json, Value};
+
+use crate::KeychainError;
+
+/// A JSON-RPC error, with the codes of JSON-RPC 2.0 and EIP-1193
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcError {
+  pub code: i64,
+  pub message: String,
+}
+
+impl RpcError {
+  /// The request body is not valid JSON
+  pub fn parse_error() -> Self {
+    Self::new(-32700, "Parse error")
+  }
+
+  /// The request is not a valid JSON-RPC request
+  pub fn invalid_request() -> Self {
+    Self::new(-32600, "Invalid request")
+  }
+
+  /// The method does not exist
+  pub fn method_not_found(method: &str) -> Self {
+    Self::new(-32601, &format!("Method not found: {}", method))
+  }
+
+  /// The parameters of the method are invalid
+  pub fn invalid_params(reason: &str) -> Self {
+    Self::new(-32602, &format!("Invalid params: {}", reason))
+  }
+
+  /// The request failed for an internal reason
+  pub fn internal(reason: &str) -> Self {
+    Self::new(-32603, reason)
+  }
+
+  /// The request was rejected, by the signing policy
+  pub fn rejected(reason: &str) -> Self {
+    Self::new(4001, reason)
+  }
+
+  /// The account is unknown, or its vault is locked
+  pub fn unauthorized(reason: &str) -> Self {
+    Self::new(4100, reason)
+  }
+
+  /// The method exists but is not supported by this server
+  pub fn unsupported_method(method: &str) -> Self {
+    Self::new(4200, &format!("Unsupported method: {}", method))
+  }
+
+  /// Get the JSON representation of the error
+  pub fn to_json(&self) -> Value {
+    json!({
+      "code": self.code,
+      "message": self.message,
+    })
+  }
+
+  fn new(code: i64, message: &str) -> Self {
+    Self {
+      code,
+      message: message.to_string(),
+    }
+  }
+}
+
+impl Display for RpcError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "JSON-RPC error {}: {}", self.code, self.message)
+  }
+}
+
+impl Error for RpcError {}
+
+impl From<KeychainError> for RpcError {
+  fn from(error: KeychainError) -> Self {
+    match error {
+      KeychainError::KeyNotFoundForAddress(_) | KeychainError::Locked(_) => {
+        Self::unauthorized(&error.to_string())
+      }
+      KeychainError::PolicyViolation(_) => Self::rejected(&error.to_string()),
+      KeychainError::TypedDataError(_) => Self::invalid_params(&error.to_string()),
+      _ => Self::internal(&error.to_string()),
+    }
+  }
+}
@@ -0,0 +1,287 @@
+use std::time::Instant;
+
+use hdkey::HDKey;
+use identity::{
+  signer::{Signature, SignatureOptions},
+  DerivationPath, MultiKeyPair,
+};
+use serde_json::{json, Value};
+use tracing::instrument;
+use utils::{hex::decode, Controller, PublicKeyBytes, TxHash};
+
+use super::{RpcError, RpcTransaction, SignedRpcTransaction};
+use crate::{metrics, KeychainError, SharedKeychain, SigningContext, TypedData};
+
+/// Broadcasts the transactions of `eth_sendTransaction`, once signed
+/// by the keychain, through a provider returning the transaction hash
+pub trait TransactionSender: Send + Sync {
+  /// Broadcast a raw signed transaction, like `eth_sendRawTransaction`
+  fn send_raw_transaction(&self, raw_transaction: &[u8]) -> Result<TxHash, RpcError>;
+}
+
+impl<F> TransactionSender for F
+where
+  F: Fn(&[u8]) -> Result<TxHash, RpcError> + Send + Sync,
+{
+  fn send_raw_transaction(&self, raw_transaction: &[u8]) -> Result<TxHash, RpcError> {
+    self(raw_transaction)
+  }
+}
+
+/// Answers JSON-RPC requests with a keychain, as the wallet of a dapp
+/// or of any tooling expecting an Ethereum node signer.
+///
+/// Accounts of locked vaults are not exposed, and every signature,
+/// transactions included, is produced by the keychain through its
+/// signing policy. Only the broadcast of transactions is delegated
+pub struct RpcHandler<M = HDKey>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  keychain: SharedKeychain<M>,
+  sender: Option<Box<dyn TransactionSender>>,
+}
+
+impl<M> RpcHandler<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Create a new handler signing with `keychain`
+  pub fn new(keychain: SharedKeychain<M>) -> Self {
+    Self {
+      keychain,
+      sender: None,
+    }
+  }
+
+  /// Broadcast the transactions of `eth_sendTransaction` with `sender`.
+  /// Without a sender, the method is answered as unsupported
+  pub fn with_transaction_sender<S>(mut self, sender: S) -> Self
+  where
+    S: TransactionSender + 'static,
+  {
+    self.sender = Some(Box::new(sender));
+    self
+  }
+
+  /// Answer a request body, either a single request or a batch
+  pub fn handle_str(&self, body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+      Ok(request) => self.handle(&request),
+      Err(_) => response(Value::Null, Err(RpcError::parse_error())),
+    }
+    .to_string()
+  }
+
+  /// Answer a request, either a single request or a batch
+  pub fn handle(&self, request: &Value) -> Value {
+    match request {
+      Value::Array(requests) if !requests.is_empty() => Value::Array(
+        requests
+          .iter()
+          .map(|request| self.handle(request))
+          .collect(),
+      ),
+      Value::Object(_) => {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let result = match (
+          request.get("method").and_then(Value::as_str),
+          request.get("params").cloned().unwrap_or(json!([])),
+        ) {
+          (Some(method), Value::Array(params)) => self.call(method, &params),
+          _ => Err(RpcError::invalid_request()),
+        };
+
+        response(id, result)
+      }
+      _ => response(Value::Null, Err(RpcError::invalid_request())),
+    }
+  }
+
+  /// Call a method with its positional parameters
+  #[instrument(level = "debug", skip(self, params), err(level = "warn"))]
+  fn call(&self, method: &str, params: &[Value]) -> Result<Value, RpcError> {
+    let started = Instant::now();
+    let result = self.dispatch(method, params);
+    metrics::rpc_latency(method, started.elapsed());
+
+    result
+  }
+
+  /// Answer a call to `method`
+  fn dispatch(&self, method: &str, params: &[Value]) -> Result<Value, RpcError> {
+    match method {
+      "eth_accounts" | "eth_requestAccounts" => Ok(json!(self.accounts()?)),
+      "eth_sign" => {
+        let message = parse_data(param(params, 1)?)?;
+        self.sign(
+          param(params, 0)?,
+          &personal_message(&message),
+          &SigningContext::default(),
+        )
+      }
+      "personal_sign" => {
+        let message = parse_data(param(params, 0)?)?;
+        self.sign(
+          param(params, 1)?,
+          &personal_message(&message),
+          &SigningContext::default(),
+        )
+      }
+      "eth_signTypedData_v4" => {
+        let typed_data = match params.get(1) {
+          Some(Value::String(typed_data)) => typed_data
+            .parse::<TypedData>()
+            .map_err(KeychainError::from)?,
+          Some(typed_data) => TypedData::try_from(typed_data).map_err(KeychainError::from)?,
+          None => return Err(RpcError::invalid_params("Missing typed data")),
+        };
+        let signing_bytes = typed_data.signing_bytes().map_err(KeychainError::from)?;
+        let context = SigningContext {
+          chain_id: typed_data.chain_id(),
+          ..Default::default()
+        };
+        self.sign(param(params, 0)?, &signing_bytes, &context)
+      }
+      "eth_signTransaction" => Ok(self.sign_transaction(params)?.to_json()),
+      "eth_sendTransaction" => {
+        let sender = self
+          .sender
+          .as_ref()
+          .ok_or(RpcError::unsupported_method(method))?;
+        let signed = self.sign_transaction(params)?;
+
+        Ok(json!(sender.send_raw_transaction(&signed.raw)?))
+      }
+      _ => Err(RpcError::method_not_found(method)),
+    }
+  }
+
+  /// Sign the transaction object of `eth_signTransaction`
+  /// or `eth_sendTransaction`
+  fn sign_transaction(&self, params: &[Value]) -> Result<SignedRpcTransaction, RpcError> {
+    let transaction = params
+      .first()
+      .filter(|transaction| transaction.is_object())
+      .ok_or(RpcError::invalid_params("Missing transaction"))?;
+    let from = transaction
+      .get("from")
+      .and_then(Value::as_str)
+      .ok_or(RpcError::invalid_params("Missing from"))?;
+    // Unknown accounts are refused before the transaction is parsed
+    self.ensure_exposed(from)?;
+
+    let transaction = RpcTransaction::try_from(transaction)?;
+    let signature = self.sign_signature(
+      &transaction.from,
+      &transaction.signing_bytes(),
+      &transaction.context(),
+    )?;
+
+    SignedRpcTransaction::new(transaction, &signature)
+      .map_err(|error| RpcError::internal(&error.to_string()))
+  }
+
+  /// Get the addresses of the accounts of the unlocked vaults
+  fn accounts(&self) -> Result<Vec<String>, RpcError> {
+    Ok(
+      self
+        .keychain
+        .read()?
+        .get_state()
+        .vaults
+        .iter()
+        .filter(|vault| !vault.locked)
+        .flat_map(|vault| vault.accounts.iter().map(|account| account.address.clone()))
+        .collect(),
+    )
+  }
+
+  /// Fail if the account at `address` is not exposed
+  fn ensure_exposed(&self, address: &str) -> Result<(), RpcError> {
+    let address = address.to_lowercase();
+    match self.accounts()?.contains(&address) {
+      true => Ok(()),
+      false => Err(RpcError::unauthorized(&format!(
+        "Account not available: {}",
+        address
+      ))),
+    }
+  }
+
+  /// Sign the keccak256 digest of `signing_bytes` with the account at
+  /// `address`, checking the signing policy against `context`
+  fn sign(
+    &self,
+    address: &Value,
+    signing_bytes: &[u8],
+    context: &SigningContext,
+  ) -> Result<Value, RpcError> {
+    let address = address
+      .as_str()
+      .ok_or(RpcError::invalid_params("Invalid address"))?;
+
+    Ok(json!(self
+      .sign_signature(address, signing_bytes, context)?
+      .to_rsv_hex()
+      .map_err(|error| RpcError::internal(&error.to_string()))?))
+  }
+
+  /// Get the recoverable signature of the keccak256 digest of
+  /// `signing_bytes` by the account at `address`, checking the
+  /// signing policy against `context`
+  fn sign_signature(
+    &self,
+    address: &str,
+    signing_bytes: &[u8],
+    context: &SigningContext,
+  ) -> Result<Signature, RpcError> {
+    self.ensure_exposed(address)?;
+
+    Ok(self.keychain.sign_with_context(
+      &address.to_lowercase(),
+      signing_bytes,
+      &SignatureOptions {
+        recoverable: true,
+        ..Default::default()
+      },
+      context,
+    )?)
+  }
+}
+
+/// Build the response to a request with `id`
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+  match result {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() }),
+  }
+}
+
+/// Get the positional parameter at `index`
+fn param(params: &[Value], index: usize) -> Result<&Value, RpcError> {
+  params.get(index).ok_or(RpcError::invalid_params(&format!(
+    "Missing parameter {}",
+    index
+  )))
+}
+
+/// Parse message data, either hex with the 0x prefix or plain text
+fn parse_data(data: &Value) -> Result<Vec<u8>, RpcError> {
+  let data = data
+    .as_str()
+    .ok_or(RpcError::invalid_params("Invalid data"))?;
+
+  match data.strip_prefix("0x") {
+    Some(hex) => decode(hex).or(Err(RpcError::invalid_params("Invalid hex data"))),
+    None => Ok(data.as_bytes().to_vec()),
+  }
+}
+
+/// Prefix a message as a personal message (EIP-191)
+fn personal_message(message: &[u8]) -> Vec<u8> {
+  let mut bytes = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+  bytes.extend(message);
+
+  bytes
+}
@@ -0,0 +1,11 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod handler;
+pub use handler::*;
+
+pub mod server;
+pub use server::*;
+
+pub mod transaction;
+pub use transaction::*;
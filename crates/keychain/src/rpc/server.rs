@@ -0,0 +1,195 @@
+use std::{
+  io::{self, Read, Write},
+  net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+  time::{Duration, Instant},
+};
+
+use identity::{DerivationPath, MultiKeyPair};
+use tracing::warn;
+use utils::PublicKeyBytes;
+
+use super::RpcHandler;
+
+/// The maximum size of a request, headers included
+const MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+/// The maximum number of headers of a request
+const MAX_HEADERS: usize = 64;
+
+/// The time to wait for a client to send or receive data
+/// before dropping the connection
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The default time a client has to send a whole request, so that
+/// a client sending it slowly cannot hold the server
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A local HTTP JSON-RPC server answering with an `RpcHandler`.
+///
+/// Requests sent by browsers, carrying an `Origin` header, are refused
+/// unless their origin is explicitly allowed, so that web pages cannot
+/// sign through the server. It should be bound to a loopback address
+pub struct RpcServer<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  listener: TcpListener,
+  handler: RpcHandler<M>,
+  allowed_origins: Vec<String>,
+  request_timeout: Duration,
+}
+
+impl<M> RpcServer<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Bind a new server to `address`
+  pub fn bind<A>(address: A, handler: RpcHandler<M>) -> io::Result<Self>
+  where
+    A: ToSocketAddrs,
+  {
+    Ok(Self {
+      listener: TcpListener::bind(address)?,
+      handler,
+      allowed_origins: vec![],
+      request_timeout: REQUEST_TIMEOUT,
+    })
+  }
+
+  /// Set the time a client has to send a whole request
+  pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+    self.request_timeout = timeout;
+    self
+  }
+
+  /// Accept requests sent by web pages of `origin`
+  pub fn allow_origin(mut self, origin: &str) -> Self {
+    self.allowed_origins.push(origin.to_string());
+    self
+  }
+
+  /// Get the address the server is bound to
+  pub fn local_addr(&self) -> io::Result<SocketAddr> {
+    self.listener.local_addr()
+  }
+
+  /// Answer incoming connections, one at a time.
+  ///
+  /// A connection failing, stalling for longer than `IO_TIMEOUT` or not
+  /// sending its request within the request timeout is logged and
+  /// dropped, and the server moves on to the next one
+  pub fn serve(&self) -> io::Result<()> {
+    for stream in self.listener.incoming() {
+      if let Err(error) = stream.and_then(|stream| self.handle_connection(stream)) {
+        warn!(%error, "dropping RPC connection");
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Answer a single HTTP request on `stream`
+  pub fn handle_connection(&self, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let deadline = Instant::now() + self.request_timeout;
+    let (status, body) = match self.read_request(&mut stream, deadline)? {
+      Ok(body) => ("200 OK", self.handler.handle_str(&body)),
+      Err(status) => (status, String::new()),
+    };
+
+    write!(
+      stream,
+      "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+      status,
+      body.len(),
+      body
+    )?;
+    stream.flush()
+  }
+
+  /// Read the body of a JSON-RPC request before `deadline`, or the
+  /// status of the response when the request cannot be answered
+  fn read_request(
+    &self,
+    stream: &mut TcpStream,
+    deadline: Instant,
+  ) -> io::Result<Result<String, &'static str>> {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 4096];
+
+    loop {
+      let read = read_before(stream, &mut chunk, deadline)?;
+      if read == 0 {
+        return Ok(Err("400 Bad Request"));
+      }
+      buffer.extend(&chunk[..read]);
+      if buffer.len() > MAX_REQUEST_SIZE {
+        return Ok(Err("413 Payload Too Large"));
+      }
+
+      let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+      let mut request = httparse::Request::new(&mut headers);
+      let header_length = match request.parse(&buffer) {
+        Ok(httparse::Status::Complete(length)) => length,
+        Ok(httparse::Status::Partial) => continue,
+        Err(_) => return Ok(Err("400 Bad Request")),
+      };
+
+      if request.method != Some("POST") {
+        return Ok(Err("405 Method Not Allowed"));
+      }
+      let header = |name: &str| {
+        request
+          .headers
+          .iter()
+          .find(|header| header.name.eq_ignore_ascii_case(name))
+          .map(|header| String::from_utf8_lossy(header.value).to_string())
+      };
+      if let Some(origin) = header("Origin") {
+        if !self.allowed_origins.contains(&origin) {
+          return Ok(Err("403 Forbidden"));
+        }
+      }
+      let content_length = match header("Content-Length").map(|length| length.parse::<usize>()) {
+        Some(Ok(length))
+          if length
+            .checked_add(header_length)
+            .is_some_and(|total| total <= MAX_REQUEST_SIZE) =>
+        {
+          length
+        }
+        Some(Ok(_)) => return Ok(Err("413 Payload Too Large")),
+        _ => return Ok(Err("411 Length Required")),
+      };
+
+      while buffer.len() < header_length + content_length {
+        let read = read_before(stream, &mut chunk, deadline)?;
+        if read == 0 {
+          return Ok(Err("400 Bad Request"));
+        }
+        buffer.extend(&chunk[..read]);
+      }
+
+      return Ok(
+        String::from_utf8(buffer[header_length..header_length + content_length].to_vec())
+          .or(Err("400 Bad Request")),
+      );
+    }
+  }
+}
+
+/// Read from `stream` into `chunk`, failing if no data
+/// is received within `IO_TIMEOUT` or before `deadline`
+fn read_before(stream: &mut TcpStream, chunk: &mut [u8], deadline: Instant) -> io::Result<usize> {
+  let remaining = deadline.saturating_duration_since(Instant::now());
+  if remaining.is_zero() {
+    return Err(io::Error::new(
+      io::ErrorKind::TimedOut,
+      "Request not received in time",
+    ));
+  }
+  stream.set_read_timeout(Some(remaining.min(IO_TIMEOUT)))?;
+
+  stream.read(chunk)
+}
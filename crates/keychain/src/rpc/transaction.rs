@@ -0,0 +1,307 @@
+use identity::signer::{Signature, SignerError};
+use serde_json::{json, Value};
+use utils::{
+  crypto::sha3::keccak256,
+  hex::{decode, decode_to_array, encode, encode_prefixed, remove0x},
+  TxHash,
+};
+
+use super::RpcError;
+use crate::{
+  rlp::{rlp_bytes, rlp_decode, rlp_list, rlp_uint, RlpItem},
+  SigningContext,
+};
+
+/// The EIP-2718 type of EIP-1559 transactions
+pub const EIP1559_TRANSACTION_TYPE: u8 = 0x02;
+
+/// An EIP-1559 transaction, as passed to `eth_signTransaction` and `eth_sendTransaction`.
+///
+/// The keychain has no provider to fill the transaction with, so the
+/// chain id, nonce, gas limit and fees must all be set by the caller
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcTransaction {
+  /// The address of the signing account
+  pub from: String,
+  /// The recipient of the transaction, `None` for contract deployments
+  pub to: Option<[u8; 20]>,
+  /// The value transferred, in wei
+  pub value: u128,
+  /// The calldata of the transaction
+  pub data: Vec<u8>,
+  /// The id of the chain the transaction is signed for
+  pub chain_id: u64,
+  /// The nonce of the signing account
+  pub nonce: u64,
+  /// The gas limit
+  pub gas: u64,
+  /// The maximum fee per gas, in wei
+  pub max_fee_per_gas: u128,
+  /// The maximum priority fee per gas, in wei
+  pub max_priority_fee_per_gas: u128,
+}
+
+impl RpcTransaction {
+  /// Decode an unsigned EIP-1559 transaction from its raw hex encoding,
+  /// `0x02` and the RLP list of its fields, as produced by other tooling,
+  /// to be signed by the account at `from`. Transactions with a non-empty
+  /// access list are not supported
+  pub fn from_raw_hex(raw: &str, from: &str) -> Result<Self, RpcError> {
+    let bytes = decode(remove0x(raw)).or(Err(RpcError::invalid_params("Invalid hex")))?;
+    let payload = match bytes.split_first() {
+      Some((&EIP1559_TRANSACTION_TYPE, payload)) => payload,
+      _ => return Err(RpcError::invalid_params("Unsupported transaction type")),
+    };
+    let decoded = rlp_decode(payload).ok_or(RpcError::invalid_params("Invalid RLP encoding"))?;
+    let fields = match decoded.as_list() {
+      Some(fields) if fields.len() == 9 => fields,
+      _ => return Err(RpcError::invalid_params("Invalid transaction fields")),
+    };
+    if fields[8]
+      .as_list()
+      .is_none_or(|access_list| !access_list.is_empty())
+    {
+      return Err(RpcError::invalid_params("Access lists are not supported"));
+    }
+
+    Ok(Self {
+      from: from.to_lowercase(),
+      to: match fields[5].as_bytes() {
+        Some([]) => None,
+        Some(to) => Some(
+          to.try_into()
+            .or(Err(RpcError::invalid_params("Invalid to")))?,
+        ),
+        None => return Err(RpcError::invalid_params("Invalid to")),
+      },
+      value: rlp_quantity(&fields[6], "value")?,
+      data: fields[7]
+        .as_bytes()
+        .ok_or(RpcError::invalid_params("Invalid data"))?
+        .to_vec(),
+      chain_id: rlp_quantity(&fields[0], "chainId")?,
+      nonce: rlp_quantity(&fields[1], "nonce")?,
+      gas: rlp_quantity(&fields[4], "gas")?,
+      max_fee_per_gas: rlp_quantity(&fields[3], "maxFeePerGas")?,
+      max_priority_fee_per_gas: rlp_quantity(&fields[2], "maxPriorityFeePerGas")?,
+    })
+  }
+
+  /// Get the JSON representation of the transaction fields, in the
+  /// format of the transaction objects of the JSON-RPC API
+  pub fn to_json(&self) -> Value {
+    json!({
+      "type": format!("{:#x}", EIP1559_TRANSACTION_TYPE),
+      "chainId": format!("{:#x}", self.chain_id),
+      "nonce": format!("{:#x}", self.nonce),
+      "from": self.from,
+      "to": self.to.map(|to| encode_prefixed(&to)),
+      "value": format!("{:#x}", self.value),
+      "input": encode_prefixed(&self.data),
+      "gas": format!("{:#x}", self.gas),
+      "maxFeePerGas": format!("{:#x}", self.max_fee_per_gas),
+      "maxPriorityFeePerGas": format!("{:#x}", self.max_priority_fee_per_gas),
+      "accessList": [],
+    })
+  }
+
+  /// Get the context the signing policy inspects the transaction with
+  pub fn context(&self) -> SigningContext {
+    let to = self.to.map(|to| encode_prefixed(&to));
+
+    SigningContext::transaction(to.as_deref(), self.value, &self.data).on_chain(self.chain_id)
+  }
+
+  /// Get the bytes whose keccak256 digest is signed:
+  /// `0x02` and the RLP list of the transaction fields
+  pub fn signing_bytes(&self) -> Vec<u8> {
+    let mut bytes = vec![EIP1559_TRANSACTION_TYPE];
+    bytes.extend(rlp_list(&self.rlp_fields()));
+
+    bytes
+  }
+
+  /// Get the raw transaction signed with a recoverable `signature`, ready
+  /// to be broadcast with `eth_sendRawTransaction`. Fails if the signature
+  /// has no recovery id
+  pub fn raw_bytes(&self, signature: &Signature) -> Result<Vec<u8>, SignerError> {
+    let y_parity = signature
+      .recovery_id()
+      .ok_or(SignerError::MissingRecoveryId)?;
+    let compact = signature.to_compact();
+    let mut fields = self.rlp_fields();
+    fields.push(rlp_uint(&[y_parity]));
+    fields.push(rlp_uint(&compact[..32]));
+    fields.push(rlp_uint(&compact[32..]));
+
+    let mut bytes = vec![EIP1559_TRANSACTION_TYPE];
+    bytes.extend(rlp_list(&fields));
+
+    Ok(bytes)
+  }
+
+  fn rlp_fields(&self) -> Vec<Vec<u8>> {
+    vec![
+      rlp_uint(&self.chain_id.to_be_bytes()),
+      rlp_uint(&self.nonce.to_be_bytes()),
+      rlp_uint(&self.max_priority_fee_per_gas.to_be_bytes()),
+      rlp_uint(&self.max_fee_per_gas.to_be_bytes()),
+      rlp_uint(&self.gas.to_be_bytes()),
+      rlp_bytes(self.to.as_ref().map_or(&[][..], |to| &to[..])),
+      rlp_uint(&self.value.to_be_bytes()),
+      rlp_bytes(&self.data),
+      // The access list is always empty
+      rlp_list(&[]),
+    ]
+  }
+}
+
+/// A transaction signed by the keychain, with its raw encoding and
+/// hash, as answered to `eth_signTransaction`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedRpcTransaction {
+  /// The transaction that was signed
+  pub transaction: RpcTransaction,
+  /// The raw signed transaction, ready for `eth_sendRawTransaction`
+  pub raw: Vec<u8>,
+  /// The hash of the transaction, being the keccak256 digest of `raw`
+  pub hash: TxHash,
+  /// The parity of the y coordinate of the signature point
+  pub y_parity: u8,
+  /// The `r` value of the signature
+  pub r: [u8; 32],
+  /// The `s` value of the signature
+  pub s: [u8; 32],
+}
+
+impl SignedRpcTransaction {
+  /// Combine `transaction` with its recoverable `signature`.
+  /// Fails if the signature has no recovery id
+  pub fn new(transaction: RpcTransaction, signature: &Signature) -> Result<Self, SignerError> {
+    let y_parity = signature
+      .recovery_id()
+      .ok_or(SignerError::MissingRecoveryId)?;
+    let raw = transaction.raw_bytes(signature)?;
+    let compact = signature.to_compact();
+    let mut r = [0u8; 32];
+    let mut s = [0u8; 32];
+    r.copy_from_slice(&compact[..32]);
+    s.copy_from_slice(&compact[32..]);
+
+    Ok(Self {
+      hash: TxHash(keccak256(&raw)),
+      transaction,
+      raw,
+      y_parity,
+      r,
+      s,
+    })
+  }
+
+  /// Get the JSON representation of the signed transaction, in the
+  /// format of the `eth_signTransaction` response: the raw transaction,
+  /// and the transaction object with its hash and signature values
+  pub fn to_json(&self) -> Value {
+    let mut transaction = self.transaction.to_json();
+    transaction["hash"] = json!(self.hash.to_string());
+    transaction["v"] = json!(format!("{:#x}", self.y_parity));
+    transaction["yParity"] = json!(format!("{:#x}", self.y_parity));
+    transaction["r"] = json!(quantity_hex(&self.r));
+    transaction["s"] = json!(quantity_hex(&self.s));
+
+    json!({
+      "raw": encode_prefixed(&self.raw),
+      "tx": transaction,
+    })
+  }
+}
+
+impl TryFrom<&Value> for RpcTransaction {
+  type Error = RpcError;
+
+  /// Parse the transaction object of an `eth_signTransaction` or `eth_sendTransaction` request
+  fn try_from(transaction: &Value) -> Result<Self, RpcError> {
+    let from = transaction
+      .get("from")
+      .and_then(Value::as_str)
+      .ok_or(RpcError::invalid_params("Missing from"))?;
+    let to = match transaction.get("to") {
+      None | Some(Value::Null) => None,
+      Some(to) => Some(
+        to.as_str()
+          .and_then(|to| decode_to_array(remove0x(to)).ok())
+          .ok_or(RpcError::invalid_params("Invalid to"))?,
+      ),
+    };
+    let data = match transaction.get("data").or(transaction.get("input")) {
+      None | Some(Value::Null) => vec![],
+      Some(data) => data
+        .as_str()
+        .and_then(|data| decode(remove0x(data)).ok())
+        .ok_or(RpcError::invalid_params("Invalid data"))?,
+    };
+
+    Ok(Self {
+      from: from.to_lowercase(),
+      to,
+      value: optional_quantity(transaction, "value")?.unwrap_or_default(),
+      data,
+      chain_id: quantity(transaction, "chainId")?,
+      nonce: quantity(transaction, "nonce")?,
+      gas: quantity(transaction, "gas")?,
+      max_fee_per_gas: quantity(transaction, "maxFeePerGas")?,
+      max_priority_fee_per_gas: quantity(transaction, "maxPriorityFeePerGas")?,
+    })
+  }
+}
+
+/// Parse the hex quantity at `field`, failing if it is missing
+fn quantity<T>(transaction: &Value, field: &str) -> Result<T, RpcError>
+where
+  T: TryFrom<u128>,
+{
+  optional_quantity(transaction, field)?
+    .ok_or(RpcError::invalid_params(&format!("Missing {}", field)))
+}
+
+/// Parse the hex quantity at `field`, if any
+fn optional_quantity<T>(transaction: &Value, field: &str) -> Result<Option<T>, RpcError>
+where
+  T: TryFrom<u128>,
+{
+  match transaction.get(field) {
+    None | Some(Value::Null) => Ok(None),
+    Some(value) => value
+      .as_str()
+      .and_then(|value| value.strip_prefix("0x"))
+      .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+      .and_then(|value| T::try_from(value).ok())
+      .map(Some)
+      .ok_or(RpcError::invalid_params(&format!("Invalid {}", field))),
+  }
+}
+
+/// Parse the RLP encoded quantity of `field`
+fn rlp_quantity<T>(item: &RlpItem, field: &str) -> Result<T, RpcError>
+where
+  T: TryFrom<u128>,
+{
+  item
+    .as_bytes()
+    .filter(|bytes| bytes.len() <= 16)
+    .map(|bytes| {
+      bytes
+        .iter()
+        .fold(0u128, |value, byte| value << 8 | *byte as u128)
+    })
+    .and_then(|value| T::try_from(value).ok())
+    .ok_or(RpcError::invalid_params(&format!("Invalid {}", field)))
+}
+
+/// Get the hex quantity of big-endian `bytes`, without leading zeros
+fn quantity_hex(bytes: &[u8]) -> String {
+  match encode(bytes).trim_start_matches('0') {
+    "" => "0x0".to_string(),
+    hex => format!("0x{}", hex),
+  }
+}
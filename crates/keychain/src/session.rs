@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use aes_gcm::{
+  aead::{Aead, KeyInit},
+  Aes256Gcm, Nonce,
+};
+use pbkdf2::{
+  hmac::{Hmac, Mac},
+  pbkdf2_hmac,
+};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use utils::SecureBytes;
+
+use crate::KeychainError;
+
+/// How long a session token can unlock the keychain, unless
+/// changed with `Keychain::set_session_ttl`
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// The length of a session token, in bytes
+pub const SESSION_TOKEN_LENGTH: usize = 32;
+
+/// A short-lived token issued by `Keychain::unlock_with_session`, to unlock
+/// the keychain again after it was locked without asking for the password.
+///
+/// The token is an HMAC of a key derived from the password, and decrypts
+/// the data keys of the vaults sealed in the keychain. The password is
+/// not kept in any form, and neither the token nor the keychain alone
+/// unlocks the vaults. Keep it in storage protected by the operating
+/// system, and never next to backups
+#[derive(Clone, PartialEq)]
+pub struct SessionToken(SecureBytes);
+
+impl SessionToken {
+  /// Recreate a token from the bytes of `SessionToken::as_bytes`
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeychainError> {
+    if bytes.len() != SESSION_TOKEN_LENGTH {
+      return Err(KeychainError::InvalidSessionToken);
+    }
+
+    Ok(Self(SecureBytes::new(bytes)))
+  }
+
+  /// Get the bytes of the token, to store it
+  pub fn as_bytes(&self) -> &[u8] {
+    self.0.as_slice()
+  }
+}
+
+impl std::fmt::Debug for SessionToken {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "SessionToken(****)")
+  }
+}
+
+/// The length of the data key of a vault, in bytes
+const DATA_KEY_LENGTH: usize = 32;
+
+/// The data keys of the vaults, indexed by vault fingerprint
+pub(crate) type SessionKeys = Vec<([u8; 4], SecureBytes)>;
+
+/// The data keys of the vaults of the keychain sealed with a session token
+#[derive(Debug)]
+pub(crate) struct Session {
+  sealed_keys: Vec<u8>,
+  nonce: [u8; 12],
+  expires_at: Instant,
+}
+
+impl Session {
+  /// Seal `data_keys` for `ttl`, returning the session and the token
+  /// opening it. The token is keyed with `rounds` iterations of
+  /// PBKDF2-HMAC-SHA256 of the password
+  pub(crate) fn issue(
+    password: &str,
+    data_keys: &SessionKeys,
+    rounds: u32,
+    ttl: Duration,
+  ) -> (Self, SessionToken) {
+    let mut salt = [0u8; 16];
+    let mut id = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut id);
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, rounds, &mut key);
+    // Unwrap is safe because HMAC accepts keys of any length
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).unwrap();
+    key.fill(0);
+    mac.update(&id);
+    let token = SessionToken(SecureBytes::from(mac.finalize().into_bytes().to_vec()));
+
+    let mut keys = vec![];
+    data_keys.iter().for_each(|(fingerprint, data_key)| {
+      keys.extend(fingerprint);
+      keys.extend(data_key.as_slice());
+    });
+    // Unwrap is safe because the token is always 32 bytes long
+    let cipher = Aes256Gcm::new_from_slice(token.as_bytes()).unwrap();
+    let sealed_keys = cipher
+      .encrypt(Nonce::from_slice(&nonce), keys.as_slice())
+      .unwrap();
+    keys.fill(0);
+
+    let session = Self {
+      sealed_keys,
+      nonce,
+      expires_at: Instant::now() + ttl,
+    };
+
+    (session, token)
+  }
+
+  /// Check if the time to live of the session has passed
+  pub(crate) fn is_expired(&self) -> bool {
+    Instant::now() >= self.expires_at
+  }
+
+  /// Open the sealed data keys with `token`
+  pub(crate) fn open(&self, token: &SessionToken) -> Result<SessionKeys, KeychainError> {
+    if self.is_expired() {
+      return Err(KeychainError::SessionExpired);
+    }
+
+    let cipher =
+      Aes256Gcm::new_from_slice(token.as_bytes()).or(Err(KeychainError::InvalidSessionToken))?;
+    let keys = cipher
+      .decrypt(Nonce::from_slice(&self.nonce), self.sealed_keys.as_slice())
+      .map(SecureBytes::from)
+      .or(Err(KeychainError::InvalidSessionToken))?;
+
+    Ok(
+      keys
+        .chunks(4 + DATA_KEY_LENGTH)
+        .filter(|chunk| chunk.len() == 4 + DATA_KEY_LENGTH)
+        .map(|chunk| {
+          let mut fingerprint = [0u8; 4];
+          fingerprint.copy_from_slice(&chunk[..4]);
+          (fingerprint, SecureBytes::new(&chunk[4..]))
+        })
+        .collect(),
+    )
+  }
+}
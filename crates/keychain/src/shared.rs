@@ -0,0 +1,188 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use hdkey::HDKey;
+use identity::{
+  signer::{IntoSignable, Signature, SignatureOptions},
+  Account, DerivationPath, MultiKeyPair,
+};
+use utils::{Controller, PublicKeyBytes};
+
+use crate::{Keychain, KeychainError, SigningContext};
+
+/// A `Keychain` that can be cloned and shared across threads.
+///
+/// Signing checks the signing policy and reserves the quota and the
+/// ledger entry of the signature under the write lock, so that concurrent
+/// signatures cannot exceed the daily quota of an account, nor sign the
+/// same payload twice when a payload ledger refuses duplicates. The
+/// signature itself is produced under the read lock, so that accounts
+/// sign in parallel.
+pub struct SharedKeychain<M = HDKey>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  inner: Arc<RwLock<Keychain<M>>>,
+}
+
+impl<M> SharedKeychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Share an existing keychain
+  pub fn new(keychain: Keychain<M>) -> Self {
+    Self {
+      inner: Arc::new(RwLock::new(keychain)),
+    }
+  }
+
+  /// Acquire shared access to the keychain
+  pub fn read(&self) -> Result<RwLockReadGuard<'_, Keychain<M>>, KeychainError> {
+    self.inner.read().or(Err(KeychainError::LockPoisoned))
+  }
+
+  /// Acquire exclusive access to the keychain
+  pub fn write(&self) -> Result<RwLockWriteGuard<'_, Keychain<M>>, KeychainError> {
+    self.inner.write().or(Err(KeychainError::LockPoisoned))
+  }
+
+  /// Get all the accounts of the keychain
+  pub fn read_accounts(&self) -> Result<Vec<Account>, KeychainError> {
+    Ok(
+      self
+        .read()?
+        .get_state()
+        .accounts()
+        .into_iter()
+        .cloned()
+        .collect(),
+    )
+  }
+
+  /// Run `f` with a signer bound to the account matching `address`
+  pub fn with_signer<F, R>(&self, address: &str, f: F) -> Result<R, KeychainError>
+  where
+    F: FnOnce(&SharedSigner<'_, M>) -> R,
+  {
+    if !self
+      .read_accounts()?
+      .iter()
+      .any(|account| account.address == address)
+    {
+      return Err(KeychainError::KeyNotFoundForAddress(address.to_string()));
+    }
+
+    Ok(f(&SharedSigner {
+      keychain: self,
+      address: address.to_string(),
+    }))
+  }
+
+  /// Sign a message with the account matching `address`
+  pub fn sign<S>(
+    &self,
+    address: &str,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.sign_with_context(address, message, options, &SigningContext::default())
+  }
+
+  /// Sign a message with the account matching `address`, after
+  /// checking the signing policy against what the message is about
+  pub fn sign_with_context<S>(
+    &self,
+    address: &str,
+    message: &S,
+    options: &SignatureOptions,
+    context: &SigningContext,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    let message = message.to_signable();
+    let (key_pair_index, account) = self
+      .write()?
+      .reserve_signature(address, &message, context)?;
+    let signature = self
+      .read()?
+      .sign_authorized(key_pair_index, &account, &message, options);
+
+    let mut keychain = self.write()?;
+    match signature {
+      Ok(signature) => {
+        keychain.record_reserved_signature(&account, &message, context)?;
+        Ok(signature)
+      }
+      Err(error) => {
+        keychain.release_signature(&account, &message, context)?;
+        Err(error)
+      }
+    }
+  }
+}
+
+impl<M> Clone for SharedKeychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  fn clone(&self) -> Self {
+    Self {
+      inner: Arc::clone(&self.inner),
+    }
+  }
+}
+
+impl<M> From<Keychain<M>> for SharedKeychain<M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  fn from(keychain: Keychain<M>) -> Self {
+    Self::new(keychain)
+  }
+}
+
+/// A signer bound to one account of a `SharedKeychain`
+pub struct SharedSigner<'a, M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  keychain: &'a SharedKeychain<M>,
+  address: String,
+}
+
+impl<'a, M> SharedSigner<'a, M>
+where
+  M: MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>,
+{
+  /// Get the address of the account
+  pub fn address(&self) -> &str {
+    &self.address
+  }
+
+  /// Sign a message with the account
+  pub fn sign<S>(&self, message: &S, options: &SignatureOptions) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self.keychain.sign(&self.address, message, options)
+  }
+
+  /// Sign a message with the account, after checking the
+  /// signing policy against what the message is about
+  pub fn sign_with_context<S>(
+    &self,
+    message: &S,
+    options: &SignatureOptions,
+    context: &SigningContext,
+  ) -> Result<Signature, KeychainError>
+  where
+    S: IntoSignable + ?Sized,
+  {
+    self
+      .keychain
+      .sign_with_context(&self.address, message, options, context)
+  }
+}
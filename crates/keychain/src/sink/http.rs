@@ -0,0 +1,42 @@
+use super::BackupSink;
+use crate::KeychainError;
+
+/// A `BackupSink` sending each backup to an HTTP endpoint,
+/// as the body of a `POST` request
+#[derive(Clone, Debug)]
+pub struct HttpBackupSink {
+  /// The endpoint receiving the backups
+  url: String,
+  /// Additional headers sent with each request, like authorization
+  headers: Vec<(String, String)>,
+}
+
+impl HttpBackupSink {
+  /// Create a new sink posting backups to `url`
+  pub fn new(url: &str) -> Self {
+    Self {
+      url: url.to_string(),
+      headers: vec![],
+    }
+  }
+
+  /// Add a header sent with each request
+  pub fn with_header(mut self, name: &str, value: &str) -> Self {
+    self.headers.push((name.to_string(), value.to_string()));
+    self
+  }
+}
+
+impl BackupSink for HttpBackupSink {
+  fn write(&mut self, backup: &[u8]) -> Result<(), KeychainError> {
+    let request = self.headers.iter().fold(
+      ureq::post(&self.url).set("Content-Type", "application/octet-stream"),
+      |request, (name, value)| request.set(name, value),
+    );
+
+    match request.send_bytes(backup) {
+      Ok(_) => Ok(()),
+      Err(error) => Err(KeychainError::BackupSinkError(error.to_string())),
+    }
+  }
+}
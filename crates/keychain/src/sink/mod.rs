@@ -0,0 +1,7 @@
+pub mod sink;
+pub use sink::*;
+
+#[cfg(feature = "http-sink")]
+pub mod http;
+#[cfg(feature = "http-sink")]
+pub use http::*;
@@ -0,0 +1,29 @@
+use std::fmt::Debug;
+
+use crate::KeychainError;
+
+/// A destination for the encrypted backups of a `Keychain`,
+/// like a cloud storage or a secret escrow service.
+///
+/// Sinks only ever receive encrypted bytes: they are written
+/// when the keychain is locked, and on each explicit backup.
+/// Sinks must be thread safe, as the keychain can be shared across threads.
+pub trait BackupSink: Send + Sync {
+  /// Write the encrypted backup of the keychain
+  fn write(&mut self, backup: &[u8]) -> Result<(), KeychainError>;
+}
+
+impl<F> BackupSink for F
+where
+  F: FnMut(&[u8]) -> Result<(), KeychainError> + Send + Sync,
+{
+  fn write(&mut self, backup: &[u8]) -> Result<(), KeychainError> {
+    self(backup)
+  }
+}
+
+impl Debug for dyn BackupSink {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "BackupSink")
+  }
+}
@@ -0,0 +1,28 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SiweError {
+  InvalidMessage(String),
+  InvalidTimestamp(String),
+  InvalidSignature,
+  DomainMismatch,
+  NonceMismatch,
+  Expired,
+  NotYetValid,
+}
+
+impl Display for SiweError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidMessage(reason) => write!(f, "Invalid SIWE message: {}", reason),
+      Self::InvalidTimestamp(value) => write!(f, "Invalid timestamp: {}", value),
+      Self::InvalidSignature => write!(f, "Invalid SIWE signature"),
+      Self::DomainMismatch => write!(f, "SIWE domain does not match"),
+      Self::NonceMismatch => write!(f, "SIWE nonce does not match"),
+      Self::Expired => write!(f, "SIWE message is expired"),
+      Self::NotYetValid => write!(f, "SIWE message is not yet valid"),
+    }
+  }
+}
+
+impl Error for SiweError {}
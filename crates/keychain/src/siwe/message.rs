@@ -0,0 +1,436 @@
+use std::{
+  fmt::Display,
+  str::FromStr,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use identity::{
+  account::ChecksumAddressFormatter,
+  signer::{Signable, Signature},
+  verify_address, AddressFormatter, IntoSignable,
+};
+use utils::hex::decode_to_array;
+
+use super::SiweError;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// A Sign-In with Ethereum message, as defined by EIP-4361.
+///
+/// Timestamps are kept as the RFC 3339 strings found in the message,
+/// so that parsed messages are re-encoded byte for byte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SiweMessage {
+  /// The RFC 3986 authority requesting the signing
+  pub domain: String,
+  /// The EIP-55 checksummed address of the signer
+  pub address: String,
+  /// A human-readable assertion the user signs
+  pub statement: Option<String>,
+  /// The RFC 3986 URI referring to the subject of the signing
+  pub uri: String,
+  /// The version of the message, always `1`
+  pub version: String,
+  /// The EIP-155 chain id the session is bound to
+  pub chain_id: u64,
+  /// A random token preventing replay attacks
+  pub nonce: String,
+  /// When the message was generated
+  pub issued_at: String,
+  /// When the signed message expires
+  pub expiration_time: Option<String>,
+  /// When the signed message becomes valid
+  pub not_before: Option<String>,
+  /// A system-specific identifier for the request
+  pub request_id: Option<String>,
+  /// URIs the user wishes to have resolved as part of authentication
+  pub resources: Vec<String>,
+}
+
+/// The expectations checked when verifying a `SiweMessage`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SiweVerification {
+  /// The domain the message must be bound to
+  pub domain: Option<String>,
+  /// The nonce issued to the client
+  pub nonce: Option<String>,
+  /// The time to check validity against, defaults to now
+  pub time: Option<SystemTime>,
+}
+
+impl SiweMessage {
+  /// Create a new message issued now. A valid hex `address`
+  /// is formatted with its EIP-55 checksum
+  pub fn new(domain: &str, address: &str, uri: &str, chain_id: u64, nonce: &str) -> Self {
+    Self {
+      domain: domain.to_string(),
+      address: checksum_address(address),
+      statement: None,
+      uri: uri.to_string(),
+      version: "1".to_string(),
+      chain_id,
+      nonce: nonce.to_string(),
+      issued_at: format_timestamp(unix_time(SystemTime::now())),
+      expiration_time: None,
+      not_before: None,
+      request_id: None,
+      resources: vec![],
+    }
+  }
+
+  /// Set the statement of the message
+  pub fn with_statement(mut self, statement: &str) -> Self {
+    self.statement = Some(statement.to_string());
+    self
+  }
+
+  /// Set when the message was generated
+  pub fn with_issued_at(mut self, issued_at: SystemTime) -> Self {
+    self.issued_at = format_timestamp(unix_time(issued_at));
+    self
+  }
+
+  /// Set when the signed message expires
+  pub fn with_expiration_time(mut self, expiration_time: SystemTime) -> Self {
+    self.expiration_time = Some(format_timestamp(unix_time(expiration_time)));
+    self
+  }
+
+  /// Set when the signed message becomes valid
+  pub fn with_not_before(mut self, not_before: SystemTime) -> Self {
+    self.not_before = Some(format_timestamp(unix_time(not_before)));
+    self
+  }
+
+  /// Set the request id of the message
+  pub fn with_request_id(mut self, request_id: &str) -> Self {
+    self.request_id = Some(request_id.to_string());
+    self
+  }
+
+  /// Add a resource to the message
+  pub fn with_resource(mut self, resource: &str) -> Self {
+    self.resources.push(resource.to_string());
+    self
+  }
+
+  /// Get the bytes to be signed, prefixed as a personal message (EIP-191)
+  pub fn to_eip191_bytes(&self) -> Vec<u8> {
+    let message = self.to_string();
+    let mut bytes = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    bytes.extend(message.as_bytes());
+
+    bytes
+  }
+
+  /// Verify that `signature` was produced by the message address,
+  /// and that the message matches the expected domain, nonce and
+  /// validity period
+  pub fn verify(
+    &self,
+    signature: &Signature,
+    verification: &SiweVerification,
+  ) -> Result<(), SiweError> {
+    if let Some(domain) = &verification.domain {
+      if *domain != self.domain {
+        return Err(SiweError::DomainMismatch);
+      }
+    }
+
+    if let Some(nonce) = &verification.nonce {
+      if *nonce != self.nonce {
+        return Err(SiweError::NonceMismatch);
+      }
+    }
+
+    let now = unix_time(verification.time.unwrap_or_else(SystemTime::now));
+
+    if let Some(expiration_time) = &self.expiration_time {
+      if now >= parse_timestamp(expiration_time)? {
+        return Err(SiweError::Expired);
+      }
+    }
+
+    if let Some(not_before) = &self.not_before {
+      if now < parse_timestamp(not_before)? {
+        return Err(SiweError::NotYetValid);
+      }
+    }
+
+    verify_address(&self.address, self, signature).or(Err(SiweError::InvalidSignature))
+  }
+}
+
+impl IntoSignable for SiweMessage {
+  /// Digest the message as a personal message (EIP-191)
+  fn to_signable(&self) -> Signable {
+    Signable::from_bytes(&self.to_eip191_bytes())
+  }
+}
+
+impl Display for SiweMessage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "{}{}", self.domain, HEADER_SUFFIX)?;
+    writeln!(f, "{}", self.address)?;
+    writeln!(f)?;
+    if let Some(statement) = &self.statement {
+      writeln!(f, "{}", statement)?;
+    }
+    writeln!(f)?;
+    writeln!(f, "URI: {}", self.uri)?;
+    writeln!(f, "Version: {}", self.version)?;
+    writeln!(f, "Chain ID: {}", self.chain_id)?;
+    writeln!(f, "Nonce: {}", self.nonce)?;
+    write!(f, "Issued At: {}", self.issued_at)?;
+    if let Some(expiration_time) = &self.expiration_time {
+      write!(f, "\nExpiration Time: {}", expiration_time)?;
+    }
+    if let Some(not_before) = &self.not_before {
+      write!(f, "\nNot Before: {}", not_before)?;
+    }
+    if let Some(request_id) = &self.request_id {
+      write!(f, "\nRequest ID: {}", request_id)?;
+    }
+    if !self.resources.is_empty() {
+      write!(f, "\nResources:")?;
+      for resource in &self.resources {
+        write!(f, "\n- {}", resource)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl FromStr for SiweMessage {
+  type Err = SiweError;
+
+  /// Parse a message formatted as defined by EIP-4361
+  fn from_str(message: &str) -> Result<Self, SiweError> {
+    let mut lines = message.split('\n').peekable();
+    let mut next = || {
+      lines.next().ok_or(SiweError::InvalidMessage(
+        "unexpected end of message".to_string(),
+      ))
+    };
+
+    let domain = next()?
+      .strip_suffix(HEADER_SUFFIX)
+      .ok_or(SiweError::InvalidMessage("missing header".to_string()))?
+      .to_string();
+    let address = next()?.to_string();
+    if decode_to_array::<20>(&address).is_err() {
+      return Err(SiweError::InvalidMessage("invalid address".to_string()));
+    }
+    expect_empty(next()?)?;
+    let statement = match next()? {
+      "" => None,
+      statement => {
+        expect_empty(next()?)?;
+        Some(statement.to_string())
+      }
+    };
+
+    let uri = field(next()?, "URI")?;
+    let version = field(next()?, "Version")?;
+    if version != "1" {
+      return Err(SiweError::InvalidMessage("unsupported version".to_string()));
+    }
+    let chain_id = field(next()?, "Chain ID")?
+      .parse()
+      .or(Err(SiweError::InvalidMessage(
+        "invalid chain id".to_string(),
+      )))?;
+    let nonce = field(next()?, "Nonce")?;
+    let issued_at = field(next()?, "Issued At")?;
+    parse_timestamp(&issued_at)?;
+
+    let mut message = Self {
+      domain,
+      address,
+      statement,
+      uri,
+      version,
+      chain_id,
+      nonce,
+      issued_at,
+      expiration_time: None,
+      not_before: None,
+      request_id: None,
+      resources: vec![],
+    };
+
+    let mut line = lines.next();
+    if let Some(value) = line.and_then(|line| line.strip_prefix("Expiration Time: ")) {
+      parse_timestamp(value)?;
+      message.expiration_time = Some(value.to_string());
+      line = lines.next();
+    }
+    if let Some(value) = line.and_then(|line| line.strip_prefix("Not Before: ")) {
+      parse_timestamp(value)?;
+      message.not_before = Some(value.to_string());
+      line = lines.next();
+    }
+    if let Some(value) = line.and_then(|line| line.strip_prefix("Request ID: ")) {
+      message.request_id = Some(value.to_string());
+      line = lines.next();
+    }
+    if line == Some("Resources:") {
+      line = lines.next();
+      while let Some(resource) = line.and_then(|line| line.strip_prefix("- ")) {
+        message.resources.push(resource.to_string());
+        line = lines.next();
+      }
+    }
+
+    match line {
+      None => Ok(message),
+      Some(line) => Err(SiweError::InvalidMessage(format!(
+        "unexpected line: {}",
+        line
+      ))),
+    }
+  }
+}
+
+/// Read the value of a `Name: value` line
+fn field(line: &str, name: &str) -> Result<String, SiweError> {
+  line
+    .strip_prefix(name)
+    .and_then(|line| line.strip_prefix(": "))
+    .map(|value| value.to_string())
+    .ok_or(SiweError::InvalidMessage(format!("missing {}", name)))
+}
+
+fn expect_empty(line: &str) -> Result<(), SiweError> {
+  match line.is_empty() {
+    true => Ok(()),
+    false => Err(SiweError::InvalidMessage(format!(
+      "unexpected line: {}",
+      line
+    ))),
+  }
+}
+
+/// Format `address` with its EIP-55 checksum, or leave it untouched if invalid
+fn checksum_address(address: &str) -> String {
+  match decode_to_array::<20>(address) {
+    Ok(bytes) => ChecksumAddressFormatter.format(&bytes),
+    _ => address.to_string(),
+  }
+}
+
+/// Seconds since the UNIX epoch, negative for earlier times
+fn unix_time(time: SystemTime) -> i64 {
+  match time.duration_since(UNIX_EPOCH) {
+    Ok(duration) => duration.as_secs() as i64,
+    Err(error) => -(error.duration().as_secs() as i64),
+  }
+}
+
+/// Days since the UNIX epoch of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = if year >= 0 { year } else { year - 399 } / 400;
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+  era * 146097 + day_of_era - 719468
+}
+
+/// Format seconds since the UNIX epoch as an RFC 3339 UTC timestamp
+fn format_timestamp(time: i64) -> String {
+  let days = time.div_euclid(86400);
+  let seconds = time.rem_euclid(86400);
+
+  // Inverse of `days_from_civil`
+  let days = days + 719468;
+  let era = if days >= 0 { days } else { days - 146096 } / 146097;
+  let day_of_era = days - era * 146097;
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+    year,
+    month,
+    day,
+    seconds / 3600,
+    seconds % 3600 / 60,
+    seconds % 60
+  )
+}
+
+/// Parse an RFC 3339 timestamp into seconds since the UNIX epoch.
+/// Fractions of seconds are truncated
+fn parse_timestamp(value: &str) -> Result<i64, SiweError> {
+  let invalid = || SiweError::InvalidTimestamp(value.to_string());
+  let number = |range: std::ops::Range<usize>| -> Result<i64, SiweError> {
+    value
+      .get(range)
+      .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))
+      .and_then(|digits| digits.parse().ok())
+      .ok_or_else(invalid)
+  };
+
+  let bytes = value.as_bytes();
+  if bytes.len() < 20
+    || bytes[4] != b'-'
+    || bytes[7] != b'-'
+    || !matches!(bytes[10], b'T' | b't')
+    || bytes[13] != b':'
+    || bytes[16] != b':'
+  {
+    return Err(invalid());
+  }
+
+  let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+  let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+  if !(1..=12).contains(&month)
+    || !(1..=31).contains(&day)
+    || hour > 23
+    || minute > 59
+    || second > 60
+  {
+    return Err(invalid());
+  }
+
+  let mut rest = &value[19..];
+  if let Some(fraction) = rest.strip_prefix('.') {
+    let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+      return Err(invalid());
+    }
+    rest = &fraction[digits..];
+  }
+
+  let offset = match rest {
+    "Z" | "z" => 0,
+    _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+      let sign = match rest.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return Err(invalid()),
+      };
+      let end = value.len();
+      let (hours, minutes) = (number(end - 5..end - 3)?, number(end - 2..end)?);
+      if hours > 23 || minutes > 59 {
+        return Err(invalid());
+      }
+      sign * (hours * 3600 + minutes * 60)
+    }
+    _ => return Err(invalid()),
+  };
+
+  Ok(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
@@ -0,0 +1,132 @@
+use snow::{params::NoiseParams, Builder, HandshakeState, TransportState};
+
+use crate::{PairingCode, SyncError};
+
+/// The Noise protocol of the sync channel: ephemeral X25519 keys on
+/// both sides, authenticated by the pre-shared key of the pairing code
+pub const NOISE_PATTERN: &str = "Noise_NNpsk0_25519_ChaChaPoly_SHA256";
+
+/// The maximum size of a Noise message
+const MAX_MESSAGE_LENGTH: usize = 65535;
+
+/// The size of the authentication tag of each Noise message
+const TAG_LENGTH: usize = 16;
+
+/// The initiator side of a sync handshake, waiting for the response
+pub struct SyncHandshake {
+  state: HandshakeState,
+}
+
+impl SyncHandshake {
+  /// Start a handshake with the device showing `code`.
+  /// Returns the handshake and the first message to send to it
+  pub fn initiate(code: &PairingCode) -> Result<(Self, Vec<u8>), SyncError> {
+    let mut state = handshake_state(code, true)?;
+    let mut message = vec![0u8; MAX_MESSAGE_LENGTH];
+    let length = state.write_message(&[], &mut message)?;
+    message.truncate(length);
+
+    Ok((Self { state }, message))
+  }
+
+  /// Answer the first message of a handshake started with `code`.
+  /// Returns the established channel and the response to send back
+  pub fn respond(code: &PairingCode, message: &[u8]) -> Result<(SyncChannel, Vec<u8>), SyncError> {
+    let mut state = handshake_state(code, false)?;
+    let mut payload = vec![0u8; MAX_MESSAGE_LENGTH];
+    state.read_message(message, &mut payload)?;
+
+    let mut response = vec![0u8; MAX_MESSAGE_LENGTH];
+    let length = state.write_message(&[], &mut response)?;
+    response.truncate(length);
+
+    Ok((SyncChannel::try_from(state)?, response))
+  }
+
+  /// Complete the handshake with the response of the other device
+  pub fn finish(mut self, response: &[u8]) -> Result<SyncChannel, SyncError> {
+    let mut payload = vec![0u8; MAX_MESSAGE_LENGTH];
+    self.state.read_message(response, &mut payload)?;
+
+    SyncChannel::try_from(self.state)
+  }
+}
+
+/// An authenticated encrypted channel between two paired devices.
+///
+/// Vaults sent through it are still encrypted with their password:
+/// the channel hides them from anyone without the pairing code,
+/// and prevents their tampering while in transit
+pub struct SyncChannel {
+  transport: TransportState,
+}
+
+impl SyncChannel {
+  /// Encrypt `payload` for the other device.
+  /// Payloads larger than a Noise message are split in frames,
+  /// each prefixed with its length as a little-endian u16
+  pub fn seal(&mut self, payload: &[u8]) -> Result<Vec<u8>, SyncError> {
+    let mut sealed = vec![];
+    let mut frame = vec![0u8; MAX_MESSAGE_LENGTH];
+
+    for chunk in payload.chunks(MAX_MESSAGE_LENGTH - TAG_LENGTH) {
+      let length = self
+        .transport
+        .write_message(chunk, &mut frame)
+        .or(Err(SyncError::Decryption))?;
+      sealed.extend((length as u16).to_le_bytes());
+      sealed.extend(&frame[..length]);
+    }
+
+    Ok(sealed)
+  }
+
+  /// Decrypt a payload sealed by the other device
+  pub fn open(&mut self, sealed: &[u8]) -> Result<Vec<u8>, SyncError> {
+    let mut payload = vec![];
+    let mut chunk = vec![0u8; MAX_MESSAGE_LENGTH];
+    let mut rest = sealed;
+
+    while !rest.is_empty() {
+      if rest.len() < 2 {
+        return Err(SyncError::UnexpectedMessage("Truncated frame".to_string()));
+      }
+      let length = u16::from_le_bytes([rest[0], rest[1]]) as usize;
+      let frame = rest
+        .get(2..length + 2)
+        .ok_or(SyncError::UnexpectedMessage("Truncated frame".to_string()))?;
+
+      let read = self
+        .transport
+        .read_message(frame, &mut chunk)
+        .or(Err(SyncError::Decryption))?;
+      payload.extend(&chunk[..read]);
+      rest = &rest[length + 2..];
+    }
+
+    Ok(payload)
+  }
+}
+
+impl TryFrom<HandshakeState> for SyncChannel {
+  type Error = SyncError;
+
+  fn try_from(state: HandshakeState) -> Result<Self, Self::Error> {
+    Ok(Self {
+      transport: state.into_transport_mode()?,
+    })
+  }
+}
+
+/// Create the handshake state of one side of the channel,
+/// keyed with the pairing code
+fn handshake_state(code: &PairingCode, initiator: bool) -> Result<HandshakeState, SyncError> {
+  let params: NoiseParams = NOISE_PATTERN.parse()?;
+  let psk = code.psk();
+  let builder = Builder::new(params).psk(0, &psk);
+
+  Ok(match initiator {
+    true => builder.build_initiator()?,
+    false => builder.build_responder()?,
+  })
+}
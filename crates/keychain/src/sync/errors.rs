@@ -0,0 +1,28 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncError {
+  InvalidPairingCode(String),
+  Handshake(String),
+  Decryption,
+  UnexpectedMessage(String),
+}
+
+impl Display for SyncError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidPairingCode(code) => write!(f, "Invalid pairing code: {}", code),
+      Self::Handshake(reason) => write!(f, "Sync handshake failed: {}", reason),
+      Self::Decryption => write!(f, "Unable to decrypt sync message"),
+      Self::UnexpectedMessage(reason) => write!(f, "Unexpected sync message: {}", reason),
+    }
+  }
+}
+
+impl Error for SyncError {}
+
+impl From<snow::Error> for SyncError {
+  fn from(error: snow::Error) -> Self {
+    Self::Handshake(error.to_string())
+  }
+}
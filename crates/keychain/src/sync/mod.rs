@@ -0,0 +1,8 @@
+pub mod channel;
+pub use channel::*;
+
+pub mod errors;
+pub use errors::*;
+
+pub mod pairing;
+pub use pairing::*;
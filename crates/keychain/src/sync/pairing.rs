@@ -0,0 +1,83 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::SyncError;
+
+/// The number of digits of a pairing code
+pub const PAIRING_CODE_DIGITS: usize = 16;
+
+/// The domain separating pairing keys from any other hash of the code
+const PAIRING_DOMAIN: &[u8] = b"walleth-sync-pairing-v1";
+
+/// A short code shown on one device and typed on the other
+/// to pair them, like `4821-0937-5512-6604`.
+///
+/// Both devices derive the pre-shared key of the sync channel from
+/// it, so only a device knowing the code can complete the handshake
+#[derive(Clone, PartialEq, Eq)]
+pub struct PairingCode {
+  digits: String,
+}
+
+impl PairingCode {
+  /// Generate a new random pairing code
+  pub fn generate() -> Self {
+    let mut digits = String::with_capacity(PAIRING_CODE_DIGITS);
+    while digits.len() < PAIRING_CODE_DIGITS {
+      // Reject the bytes above the last multiple of ten to avoid bias
+      let byte = (OsRng.next_u32() & 0xff) as u8;
+      if byte < 250 {
+        digits.push(char::from(b'0' + byte % 10));
+      }
+    }
+
+    Self { digits }
+  }
+
+  /// Get the pre-shared key of the sync channel
+  pub fn psk(&self) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(PAIRING_DOMAIN);
+    hasher.update(self.digits.as_bytes());
+    hasher.finalize().into()
+  }
+}
+
+impl Display for PairingCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let groups = self
+      .digits
+      .as_bytes()
+      .chunks(4)
+      .map(|group| String::from_utf8_lossy(group).to_string())
+      .collect::<Vec<String>>();
+
+    write!(f, "{}", groups.join("-"))
+  }
+}
+
+impl std::fmt::Debug for PairingCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "PairingCode(****)")
+  }
+}
+
+impl FromStr for PairingCode {
+  type Err = SyncError;
+
+  /// Parse a pairing code, ignoring separators and whitespace
+  fn from_str(code: &str) -> Result<Self, Self::Err> {
+    let digits = code
+      .chars()
+      .filter(|char| !matches!(char, '-' | ' '))
+      .collect::<String>();
+
+    if digits.len() != PAIRING_CODE_DIGITS || !digits.chars().all(|char| char.is_ascii_digit()) {
+      return Err(SyncError::InvalidPairingCode(code.to_string()));
+    }
+
+    Ok(Self { digits })
+  }
+}
@@ -0,0 +1,22 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedDataError {
+  InvalidFormat(String),
+  UnknownType(String),
+  InvalidValue(String),
+  Expired(u64),
+}
+
+impl Display for TypedDataError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidFormat(reason) => write!(f, "Invalid typed data: {}", reason),
+      Self::UnknownType(name) => write!(f, "Unknown type: {}", name),
+      Self::InvalidValue(field) => write!(f, "Invalid value for {}", field),
+      Self::Expired(deadline) => write!(f, "Deadline passed at {}", deadline),
+    }
+  }
+}
+
+impl Error for TypedDataError {}
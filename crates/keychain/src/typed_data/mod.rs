@@ -0,0 +1,8 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod permit;
+pub use permit::*;
+
+pub mod typed_data;
+pub use typed_data::*;
@@ -0,0 +1,226 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+use utils::B256;
+
+use super::{TypedData, TypedDataError};
+
+/// The address of the canonical Permit2 contract, the same on every chain
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+/// The EIP-712 domain of an ERC-20 token supporting ERC-2612 permits
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenDomain {
+  /// The name of the token, as returned by `name()`
+  pub name: String,
+  /// The version of the domain, usually `1`
+  pub version: String,
+  /// The id of the chain the token is deployed on
+  pub chain_id: u64,
+  /// The address of the token contract
+  pub address: String,
+}
+
+/// An ERC-2612 permit, approving `spender` to transfer `value`
+/// tokens of `owner` until `deadline`, without a transaction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Permit {
+  /// The domain of the token
+  pub token: TokenDomain,
+  /// The address of the token owner, signing the permit
+  pub owner: String,
+  /// The address of the approved spender
+  pub spender: String,
+  /// The approved amount, in the smallest unit of the token,
+  /// as a big-endian uint256. `Permit::UNLIMITED` approves any amount
+  pub value: B256,
+  /// The permit nonce of the owner, as returned by `nonces(owner)`
+  pub nonce: u64,
+  /// Seconds since the UNIX epoch after which the permit is invalid
+  pub deadline: u64,
+}
+
+impl Permit {
+  /// The maximum uint256, the standard unlimited approval
+  pub const UNLIMITED: B256 = B256([0xff; 32]);
+
+  /// Create a permit valid for `ttl` from now
+  pub fn new(
+    token: TokenDomain,
+    owner: &str,
+    spender: &str,
+    value: B256,
+    nonce: u64,
+    ttl: Duration,
+  ) -> Self {
+    Self {
+      token,
+      owner: owner.to_string(),
+      spender: spender.to_string(),
+      value,
+      nonce,
+      deadline: deadline_in(ttl),
+    }
+  }
+
+  /// Check if the deadline of the permit has passed
+  pub fn is_expired(&self) -> bool {
+    now() > self.deadline
+  }
+
+  /// Get the permit as EIP-712 typed data, to sign with the owner account.
+  /// Fails if its deadline has passed
+  pub fn to_typed_data(&self) -> Result<TypedData, TypedDataError> {
+    if self.is_expired() {
+      return Err(TypedDataError::Expired(self.deadline));
+    }
+
+    TypedData::try_from(&json!({
+      "types": {
+        "EIP712Domain": [
+          { "name": "name", "type": "string" },
+          { "name": "version", "type": "string" },
+          { "name": "chainId", "type": "uint256" },
+          { "name": "verifyingContract", "type": "address" },
+        ],
+        "Permit": [
+          { "name": "owner", "type": "address" },
+          { "name": "spender", "type": "address" },
+          { "name": "value", "type": "uint256" },
+          { "name": "nonce", "type": "uint256" },
+          { "name": "deadline", "type": "uint256" },
+        ],
+      },
+      "primaryType": "Permit",
+      "domain": {
+        "name": self.token.name,
+        "version": self.token.version,
+        "chainId": self.token.chain_id,
+        "verifyingContract": self.token.address,
+      },
+      "message": {
+        "owner": self.owner,
+        "spender": self.spender,
+        "value": self.value,
+        "nonce": self.nonce,
+        "deadline": self.deadline,
+      },
+    }))
+  }
+}
+
+/// A Uniswap Permit2 `PermitSingle`, approving `spender` to transfer
+/// `amount` tokens through the Permit2 contract until `expiration`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Permit2 {
+  /// The id of the chain the approval is valid on
+  pub chain_id: u64,
+  /// The address of the approved token
+  pub token: String,
+  /// The approved amount, as a big-endian uint160 in a 32 bytes word.
+  /// `Permit2::UNLIMITED` approves any amount
+  pub amount: B256,
+  /// Seconds since the UNIX epoch after which the approval expires
+  pub expiration: u64,
+  /// The Permit2 nonce of the owner for the token and spender,
+  /// as returned by `allowance(owner, token, spender)`
+  pub nonce: u64,
+  /// The address of the approved spender
+  pub spender: String,
+  /// Seconds since the UNIX epoch after which the signature is invalid
+  pub sig_deadline: u64,
+}
+
+impl Permit2 {
+  /// The maximum uint160, the unlimited approval of Permit2
+  pub const UNLIMITED: B256 = B256([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+  ]);
+
+  /// Create an approval valid for `ttl` from now, whose
+  /// signature must be submitted within `sig_ttl`
+  pub fn new(
+    chain_id: u64,
+    token: &str,
+    spender: &str,
+    amount: B256,
+    nonce: u64,
+    ttl: Duration,
+    sig_ttl: Duration,
+  ) -> Self {
+    Self {
+      chain_id,
+      token: token.to_string(),
+      amount,
+      expiration: deadline_in(ttl),
+      nonce,
+      spender: spender.to_string(),
+      sig_deadline: deadline_in(sig_ttl),
+    }
+  }
+
+  /// Check if the signature deadline has passed
+  pub fn is_expired(&self) -> bool {
+    now() > self.sig_deadline
+  }
+
+  /// Get the approval as EIP-712 typed data, to sign with the owner account.
+  /// Fails if its signature deadline has passed, or if its amount
+  /// does not fit in a uint160
+  pub fn to_typed_data(&self) -> Result<TypedData, TypedDataError> {
+    if self.is_expired() {
+      return Err(TypedDataError::Expired(self.sig_deadline));
+    }
+
+    TypedData::try_from(&json!({
+      "types": {
+        "EIP712Domain": [
+          { "name": "name", "type": "string" },
+          { "name": "chainId", "type": "uint256" },
+          { "name": "verifyingContract", "type": "address" },
+        ],
+        "PermitSingle": [
+          { "name": "details", "type": "PermitDetails" },
+          { "name": "spender", "type": "address" },
+          { "name": "sigDeadline", "type": "uint256" },
+        ],
+        "PermitDetails": [
+          { "name": "token", "type": "address" },
+          { "name": "amount", "type": "uint160" },
+          { "name": "expiration", "type": "uint48" },
+          { "name": "nonce", "type": "uint48" },
+        ],
+      },
+      "primaryType": "PermitSingle",
+      "domain": {
+        "name": "Permit2",
+        "chainId": self.chain_id,
+        "verifyingContract": PERMIT2_ADDRESS,
+      },
+      "message": {
+        "details": {
+          "token": self.token,
+          "amount": self.amount,
+          "expiration": self.expiration,
+          "nonce": self.nonce,
+        },
+        "spender": self.spender,
+        "sigDeadline": self.sig_deadline,
+      },
+    }))
+  }
+}
+
+/// Get the seconds since the UNIX epoch
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_secs()
+}
+
+/// Get the seconds since the UNIX epoch, `ttl` from now
+fn deadline_in(ttl: Duration) -> u64 {
+  now().saturating_add(ttl.as_secs())
+}
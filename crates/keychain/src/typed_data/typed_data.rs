@@ -0,0 +1,370 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  str::FromStr,
+};
+
+use serde_json::Value;
+use utils::{crypto::sha3::keccak256, hex::decode, B256};
+
+use super::TypedDataError;
+
+/// The name of the type of the domain of typed data
+pub const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// A member of a struct type: its name and type
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedField {
+  pub name: String,
+  pub r#type: String,
+}
+
+/// Structured data to be signed as specified by EIP-712, in the
+/// JSON format of `eth_signTypedData_v4`: struct types, the name
+/// of the signed type, the domain and the message
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedData {
+  /// The struct types, indexed by name
+  pub types: BTreeMap<String, Vec<TypedField>>,
+  /// The name of the type of the message
+  pub primary_type: String,
+  /// The domain, of type `EIP712Domain`
+  pub domain: Value,
+  /// The message, of type `primary_type`
+  pub message: Value,
+}
+
+impl TypedData {
+  /// Get the hash of the domain
+  pub fn domain_separator(&self) -> Result<B256, TypedDataError> {
+    Ok(B256(self.hash_struct(DOMAIN_TYPE, &self.domain)?))
+  }
+
+  /// Get the hash of the message
+  pub fn message_hash(&self) -> Result<B256, TypedDataError> {
+    Ok(B256(self.hash_struct(&self.primary_type, &self.message)?))
+  }
+
+  /// Get the bytes whose keccak256 digest is signed:
+  /// `0x19 0x01`, the domain separator and the message hash
+  pub fn signing_bytes(&self) -> Result<Vec<u8>, TypedDataError> {
+    let mut bytes = vec![0x19, 0x01];
+    bytes.extend(self.domain_separator()?.as_bytes());
+    if self.primary_type != DOMAIN_TYPE {
+      bytes.extend(self.message_hash()?.as_bytes());
+    }
+
+    Ok(bytes)
+  }
+
+  /// Get the digest signed for the typed data
+  pub fn digest(&self) -> Result<B256, TypedDataError> {
+    Ok(B256(keccak256(&self.signing_bytes()?)))
+  }
+
+  /// Get the chain id of the domain, if any, given
+  /// as a number or as a decimal or hexadecimal string
+  pub fn chain_id(&self) -> Option<u64> {
+    match self.domain.get("chainId")? {
+      Value::Number(chain_id) => chain_id.as_u64(),
+      Value::String(chain_id) => match chain_id.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => chain_id.parse().ok(),
+      },
+      _ => None,
+    }
+  }
+
+  /// Get the encoding of a struct type, followed
+  /// by the types it references, sorted by name
+  pub fn encode_type(&self, name: &str) -> Result<String, TypedDataError> {
+    let mut dependencies = BTreeSet::new();
+    self.find_dependencies(name, &mut dependencies)?;
+    dependencies.remove(name);
+
+    std::iter::once(name)
+      .chain(dependencies.iter().map(String::as_str))
+      .map(|name| {
+        let fields = self.fields(name)?;
+        Ok(format!(
+          "{}({})",
+          name,
+          fields
+            .iter()
+            .map(|field| format!("{} {}", field.r#type, field.name))
+            .collect::<Vec<String>>()
+            .join(",")
+        ))
+      })
+      .collect()
+  }
+
+  /// Get the hash of a struct of type `name`
+  pub fn hash_struct(&self, name: &str, value: &Value) -> Result<[u8; 32], TypedDataError> {
+    let mut encoded = keccak256(self.encode_type(name)?.as_bytes()).to_vec();
+    for field in self.fields(name)? {
+      let member = value.get(&field.name).unwrap_or(&Value::Null);
+      encoded.extend(self.encode_value(&field.r#type, member, &field.name)?);
+    }
+
+    Ok(keccak256(&encoded))
+  }
+
+  /// Get the fields of the struct type `name`
+  fn fields(&self, name: &str) -> Result<&Vec<TypedField>, TypedDataError> {
+    self
+      .types
+      .get(name)
+      .ok_or(TypedDataError::UnknownType(name.to_string()))
+  }
+
+  /// Collect the struct types referenced by `name`, including itself
+  fn find_dependencies(
+    &self,
+    name: &str,
+    found: &mut BTreeSet<String>,
+  ) -> Result<(), TypedDataError> {
+    if found.contains(name) {
+      return Ok(());
+    }
+    found.insert(name.to_string());
+
+    for field in self.fields(name)? {
+      let base = base_type(&field.r#type);
+      if self.types.contains_key(base) {
+        self.find_dependencies(base, found)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Encode a member value to its 32 bytes word
+  fn encode_value(
+    &self,
+    r#type: &str,
+    value: &Value,
+    field: &str,
+  ) -> Result<[u8; 32], TypedDataError> {
+    let invalid = || TypedDataError::InvalidValue(field.to_string());
+
+    if let Some(item_type) = array_item_type(r#type) {
+      let mut encoded = vec![];
+      for item in value.as_array().ok_or_else(invalid)? {
+        encoded.extend(self.encode_value(item_type, item, field)?);
+      }
+      return Ok(keccak256(&encoded));
+    }
+
+    if self.types.contains_key(r#type) {
+      return self.hash_struct(r#type, value);
+    }
+
+    match r#type {
+      "string" => Ok(keccak256(value.as_str().ok_or_else(invalid)?.as_bytes())),
+      "bytes" => Ok(keccak256(&parse_bytes(value).ok_or_else(invalid)?)),
+      "bool" => {
+        let mut word = [0u8; 32];
+        word[31] = match value {
+          Value::Bool(value) => *value as u8,
+          Value::String(value) if value == "true" || value == "false" => (value == "true") as u8,
+          _ => return Err(invalid()),
+        };
+        Ok(word)
+      }
+      "address" => {
+        let bytes = parse_bytes(value).filter(|bytes| bytes.len() == 20);
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(&bytes.ok_or_else(invalid)?);
+        Ok(word)
+      }
+      _ if r#type.starts_with("bytes") => {
+        let size = r#type[5..].parse::<usize>().map_err(|_| unknown(r#type))?;
+        let bytes = parse_bytes(value).filter(|bytes| bytes.len() == size && size <= 32);
+        let mut word = [0u8; 32];
+        word[..size].copy_from_slice(&bytes.ok_or_else(invalid)?);
+        Ok(word)
+      }
+      _ if r#type.starts_with("uint") => {
+        let bits = integer_bits(r#type, "uint")?;
+        // Unsigned values must fit in the bits of their type
+        parse_integer(value, false)
+          .filter(|word| word[..32 - bits / 8].iter().all(|byte| *byte == 0))
+          .ok_or_else(invalid)
+      }
+      _ if r#type.starts_with("int") => {
+        let bits = integer_bits(r#type, "int")?;
+        // Signed values must be the sign extension of the bits of their type
+        parse_integer(value, true)
+          .filter(|word| {
+            let extension = match word[32 - bits / 8] & 0x80 {
+              0 => 0x00,
+              _ => 0xff,
+            };
+            word[..32 - bits / 8].iter().all(|byte| *byte == extension)
+          })
+          .ok_or_else(invalid)
+      }
+      _ => Err(unknown(r#type)),
+    }
+  }
+}
+
+impl TryFrom<&Value> for TypedData {
+  type Error = TypedDataError;
+
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    let invalid = |reason: &str| TypedDataError::InvalidFormat(reason.to_string());
+
+    let types = value
+      .get("types")
+      .and_then(Value::as_object)
+      .ok_or(invalid("Missing types"))?
+      .iter()
+      .map(|(name, fields)| {
+        let fields = fields
+          .as_array()
+          .ok_or(invalid("Invalid type fields"))?
+          .iter()
+          .map(|field| {
+            Ok(TypedField {
+              name: field
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or(invalid("Invalid field name"))?
+                .to_string(),
+              r#type: field
+                .get("type")
+                .and_then(Value::as_str)
+                .ok_or(invalid("Invalid field type"))?
+                .to_string(),
+            })
+          })
+          .collect::<Result<Vec<TypedField>, TypedDataError>>()?;
+        Ok((name.clone(), fields))
+      })
+      .collect::<Result<BTreeMap<String, Vec<TypedField>>, TypedDataError>>()?;
+
+    if !types.contains_key(DOMAIN_TYPE) {
+      return Err(invalid("Missing EIP712Domain type"));
+    }
+
+    Ok(Self {
+      types,
+      primary_type: value
+        .get("primaryType")
+        .and_then(Value::as_str)
+        .ok_or(invalid("Missing primaryType"))?
+        .to_string(),
+      domain: value.get("domain").cloned().unwrap_or(Value::Null),
+      message: value.get("message").cloned().unwrap_or(Value::Null),
+    })
+  }
+}
+
+impl FromStr for TypedData {
+  type Err = TypedDataError;
+
+  fn from_str(typed_data: &str) -> Result<Self, Self::Err> {
+    let value = serde_json::from_str::<Value>(typed_data).or(Err(
+      TypedDataError::InvalidFormat("Invalid JSON".to_string()),
+    ))?;
+
+    Self::try_from(&value)
+  }
+}
+
+fn unknown(r#type: &str) -> TypedDataError {
+  TypedDataError::UnknownType(r#type.to_string())
+}
+
+/// Get the bit width of an integer type, like 64 for `uint64` or 256
+/// for `int`. Fails unless it is a multiple of 8, from 8 to 256
+fn integer_bits(r#type: &str, prefix: &str) -> Result<usize, TypedDataError> {
+  let bits = match &r#type[prefix.len()..] {
+    "" => 256,
+    bits => bits.parse::<usize>().map_err(|_| unknown(r#type))?,
+  };
+  if bits == 0 || bits > 256 || bits % 8 != 0 {
+    return Err(unknown(r#type));
+  }
+
+  Ok(bits)
+}
+
+/// Get the type of the items of an array type, like `Person` for `Person[]`
+fn array_item_type(r#type: &str) -> Option<&str> {
+  r#type
+    .strip_suffix(']')
+    .and_then(|r#type| r#type.rfind('[').map(|index| &r#type[..index]))
+}
+
+/// Get the type without array dimensions, like `Person` for `Person[][2]`
+fn base_type(r#type: &str) -> &str {
+  r#type.split('[').next().unwrap_or(r#type)
+}
+
+/// Parse a hex string value, with the 0x prefix
+fn parse_bytes(value: &Value) -> Option<Vec<u8>> {
+  decode(value.as_str()?.strip_prefix("0x")?).ok()
+}
+
+/// Parse an integer value, either a JSON number, a decimal string
+/// or a hex string, into its 32 bytes two's complement word
+fn parse_integer(value: &Value, signed: bool) -> Option<[u8; 32]> {
+  let (negative, digits) = match value {
+    Value::Number(number) => match number.as_i64() {
+      Some(number) => (number < 0, number.unsigned_abs().to_string()),
+      None => (false, number.as_u64()?.to_string()),
+    },
+    Value::String(string) => match string.strip_prefix('-') {
+      Some(digits) => (true, digits.to_string()),
+      None => (false, string.clone()),
+    },
+    _ => return None,
+  };
+  if negative && !signed {
+    return None;
+  }
+
+  let mut word = [0u8; 32];
+  match digits.strip_prefix("0x") {
+    Some(hex) if hex.len() <= 64 => {
+      word.copy_from_slice(&decode(&format!("{:0>64}", hex)).ok()?);
+    }
+    Some(_) => return None,
+    None => {
+      for digit in digits.chars() {
+        let mut carry = digit.to_digit(10)?;
+        for byte in word.iter_mut().rev() {
+          let product = *byte as u32 * 10 + carry;
+          *byte = product as u8;
+          carry = product >> 8;
+        }
+        if carry != 0 {
+          return None;
+        }
+      }
+    }
+  }
+
+  // Signed values must fit in 255 bits, besides the sign bit
+  if signed && !negative && word[0] & 0x80 != 0 {
+    return None;
+  }
+
+  if negative {
+    // Two's complement: invert and add one
+    let mut carry = 1u16;
+    for byte in word.iter_mut().rev() {
+      let sum = (!*byte) as u16 + carry;
+      *byte = sum as u8;
+      carry = sum >> 8;
+    }
+  }
+
+  if negative && word[0] & 0x80 == 0 && word.iter().any(|byte| *byte != 0) {
+    return None;
+  }
+
+  Some(word)
+}
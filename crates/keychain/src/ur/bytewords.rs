@@ -0,0 +1,92 @@
+use super::UrError;
+
+/// The 256 bytewords, one for each byte value. The first and last
+/// letters of each word are unique, and form its minimal encoding
+const WORDS: [&str; 256] = [
+  "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+  "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+  "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+  "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+  "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+  "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+  "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+  "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+  "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+  "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+  "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+  "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+  "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+  "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+  "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+  "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+  "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+  "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+  "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+  "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+  "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+  "zest", "zinc", "zone", "zoom",
+];
+
+/// Encode bytes as minimal bytewords (two letters per byte),
+/// followed by their CRC-32 checksum
+pub fn encode(bytes: &[u8]) -> String {
+  let mut data = bytes.to_vec();
+  data.extend(crc32(bytes).to_be_bytes());
+
+  data
+    .iter()
+    .flat_map(|byte| {
+      let word = WORDS[*byte as usize].as_bytes();
+      [word[0] as char, word[3] as char]
+    })
+    .collect()
+}
+
+/// Decode minimal bytewords, verifying and stripping their CRC-32 checksum.
+/// Decoding is case-insensitive, as QR codes carry uppercase text
+pub fn decode(encoded: &str) -> Result<Vec<u8>, UrError> {
+  let encoded = encoded.to_lowercase();
+  if !encoded.is_ascii() || !encoded.len().is_multiple_of(2) {
+    return Err(UrError::InvalidBytewords);
+  }
+
+  let mut data = encoded
+    .as_bytes()
+    .chunks(2)
+    .map(|pair| {
+      WORDS
+        .iter()
+        .position(|word| word.as_bytes()[0] == pair[0] && word.as_bytes()[3] == pair[1])
+        .map(|byte| byte as u8)
+        .ok_or(UrError::InvalidBytewords)
+    })
+    .collect::<Result<Vec<u8>, UrError>>()?;
+
+  if data.len() < 4 {
+    return Err(UrError::InvalidBytewords);
+  }
+
+  let checksum = data.split_off(data.len() - 4);
+  if checksum != crc32(&data).to_be_bytes() {
+    return Err(UrError::ChecksumMismatch);
+  }
+
+  Ok(data)
+}
+
+/// Compute the CRC-32 (ISO-HDLC) checksum of bytes
+pub fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = 0xffffffffu32;
+
+  for byte in bytes {
+    crc ^= *byte as u32;
+    for _ in 0..8 {
+      crc = match crc & 1 {
+        1 => (crc >> 1) ^ 0xedb88320,
+        _ => crc >> 1,
+      };
+    }
+  }
+
+  !crc
+}
@@ -0,0 +1,231 @@
+use super::UrError;
+
+/// The maximum nesting depth accepted when decoding, as
+/// payloads are read from untrusted QR codes
+const MAX_DEPTH: usize = 16;
+
+/// A minimal CBOR (RFC 8949) value, covering the subset
+/// of the data model used by Uniform Resources
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cbor {
+  Unsigned(u64),
+  Bytes(Vec<u8>),
+  Text(String),
+  Array(Vec<Cbor>),
+  Map(Vec<(Cbor, Cbor)>),
+  Tag(u64, Box<Cbor>),
+  Bool(bool),
+}
+
+impl Cbor {
+  /// Encode the value in its canonical form
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = vec![];
+    self.encode(&mut bytes);
+
+    bytes
+  }
+
+  /// Decode a single CBOR value, rejecting trailing bytes
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, UrError> {
+    let mut position = 0;
+    let value = Self::decode(bytes, &mut position, 0)?;
+
+    match position == bytes.len() {
+      true => Ok(value),
+      false => Err(UrError::InvalidCbor("trailing bytes".to_string())),
+    }
+  }
+
+  /// Get the value as an unsigned integer
+  pub fn as_unsigned(&self) -> Result<u64, UrError> {
+    match self {
+      Self::Unsigned(value) => Ok(*value),
+      _ => Err(UrError::InvalidCbor(
+        "expected an unsigned integer".to_string(),
+      )),
+    }
+  }
+
+  /// Get the value as a byte string
+  pub fn as_bytes(&self) -> Result<&[u8], UrError> {
+    match self {
+      Self::Bytes(bytes) => Ok(bytes),
+      _ => Err(UrError::InvalidCbor("expected a byte string".to_string())),
+    }
+  }
+
+  /// Get the value as a text string
+  pub fn as_text(&self) -> Result<&str, UrError> {
+    match self {
+      Self::Text(text) => Ok(text),
+      _ => Err(UrError::InvalidCbor("expected a text string".to_string())),
+    }
+  }
+
+  /// Get the value as an array
+  pub fn as_array(&self) -> Result<&[Cbor], UrError> {
+    match self {
+      Self::Array(items) => Ok(items),
+      _ => Err(UrError::InvalidCbor("expected an array".to_string())),
+    }
+  }
+
+  /// Get the value as a boolean
+  pub fn as_bool(&self) -> Result<bool, UrError> {
+    match self {
+      Self::Bool(value) => Ok(*value),
+      _ => Err(UrError::InvalidCbor("expected a boolean".to_string())),
+    }
+  }
+
+  /// Get the value wrapped by the tag `tag`
+  pub fn untag(&self, tag: u64) -> Result<&Cbor, UrError> {
+    match self {
+      Self::Tag(found, value) if *found == tag => Ok(value),
+      _ => Err(UrError::InvalidCbor(format!("expected tag {}", tag))),
+    }
+  }
+
+  /// Get the value at the unsigned integer `key` of a map, if any
+  pub fn get(&self, key: u64) -> Result<Option<&Cbor>, UrError> {
+    match self {
+      Self::Map(entries) => Ok(
+        entries
+          .iter()
+          .find(|(found, _)| *found == Self::Unsigned(key))
+          .map(|(_, value)| value),
+      ),
+      _ => Err(UrError::InvalidCbor("expected a map".to_string())),
+    }
+  }
+
+  fn encode(&self, bytes: &mut Vec<u8>) {
+    match self {
+      Self::Unsigned(value) => encode_head(bytes, 0, *value),
+      Self::Bytes(value) => {
+        encode_head(bytes, 2, value.len() as u64);
+        bytes.extend(value);
+      }
+      Self::Text(value) => {
+        encode_head(bytes, 3, value.len() as u64);
+        bytes.extend(value.as_bytes());
+      }
+      Self::Array(items) => {
+        encode_head(bytes, 4, items.len() as u64);
+        items.iter().for_each(|item| item.encode(bytes));
+      }
+      Self::Map(entries) => {
+        encode_head(bytes, 5, entries.len() as u64);
+        entries.iter().for_each(|(key, value)| {
+          key.encode(bytes);
+          value.encode(bytes);
+        });
+      }
+      Self::Tag(tag, value) => {
+        encode_head(bytes, 6, *tag);
+        value.encode(bytes);
+      }
+      Self::Bool(value) => bytes.push(if *value { 0xf5 } else { 0xf4 }),
+    }
+  }
+
+  fn decode(bytes: &[u8], position: &mut usize, depth: usize) -> Result<Self, UrError> {
+    if depth > MAX_DEPTH {
+      return Err(UrError::InvalidCbor("nesting too deep".to_string()));
+    }
+
+    let initial = read(bytes, position, 1)?[0];
+    let major = initial >> 5;
+
+    if major == 7 {
+      return match initial {
+        0xf4 => Ok(Self::Bool(false)),
+        0xf5 => Ok(Self::Bool(true)),
+        _ => Err(UrError::InvalidCbor("unsupported simple value".to_string())),
+      };
+    }
+
+    let argument = decode_argument(bytes, position, initial & 0x1f)?;
+
+    match major {
+      0 => Ok(Self::Unsigned(argument)),
+      2 => Ok(Self::Bytes(read(bytes, position, argument)?.to_vec())),
+      3 => String::from_utf8(read(bytes, position, argument)?.to_vec())
+        .map(Self::Text)
+        .or(Err(UrError::InvalidCbor("invalid utf-8 text".to_string()))),
+      4 => (0..argument)
+        .map(|_| Self::decode(bytes, position, depth + 1))
+        .collect::<Result<Vec<Cbor>, UrError>>()
+        .map(Self::Array),
+      5 => (0..argument)
+        .map(|_| {
+          Ok((
+            Self::decode(bytes, position, depth + 1)?,
+            Self::decode(bytes, position, depth + 1)?,
+          ))
+        })
+        .collect::<Result<Vec<(Cbor, Cbor)>, UrError>>()
+        .map(Self::Map),
+      6 => Ok(Self::Tag(
+        argument,
+        Box::new(Self::decode(bytes, position, depth + 1)?),
+      )),
+      _ => Err(UrError::InvalidCbor("unsupported major type".to_string())),
+    }
+  }
+}
+
+/// Encode the head of a data item, with the shortest argument encoding
+fn encode_head(bytes: &mut Vec<u8>, major: u8, argument: u64) {
+  let major = major << 5;
+
+  match argument {
+    0..=23 => bytes.push(major | argument as u8),
+    24..=0xff => bytes.extend([major | 24, argument as u8]),
+    0x100..=0xffff => {
+      bytes.push(major | 25);
+      bytes.extend((argument as u16).to_be_bytes());
+    }
+    0x10000..=0xffffffff => {
+      bytes.push(major | 26);
+      bytes.extend((argument as u32).to_be_bytes());
+    }
+    _ => {
+      bytes.push(major | 27);
+      bytes.extend(argument.to_be_bytes());
+    }
+  }
+}
+
+/// Decode the argument of a data item from its additional information
+fn decode_argument(bytes: &[u8], position: &mut usize, info: u8) -> Result<u64, UrError> {
+  let length = match info {
+    0..=23 => return Ok(info as u64),
+    24 => 1,
+    25 => 2,
+    26 => 4,
+    27 => 8,
+    _ => return Err(UrError::InvalidCbor("unsupported argument".to_string())),
+  };
+
+  Ok(
+    read(bytes, position, length)?
+      .iter()
+      .fold(0u64, |value, byte| (value << 8) | *byte as u64),
+  )
+}
+
+/// Read `length` bytes, advancing the position
+fn read<'a>(bytes: &'a [u8], position: &mut usize, length: u64) -> Result<&'a [u8], UrError> {
+  let end = usize::try_from(length)
+    .ok()
+    .and_then(|length| position.checked_add(length))
+    .filter(|end| *end <= bytes.len())
+    .ok_or(UrError::InvalidCbor("unexpected end of input".to_string()))?;
+
+  let slice = &bytes[*position..end];
+  *position = end;
+
+  Ok(slice)
+}
@@ -0,0 +1,34 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UrError {
+  InvalidUr(String),
+  InvalidBytewords,
+  ChecksumMismatch,
+  InvalidCbor(String),
+  UnexpectedType(String),
+  InconsistentPart,
+  UnsupportedDataType(u64),
+  InvalidTransaction(String),
+  InvalidTypedData(String),
+  ChainIdOutOfRange(u64),
+}
+
+impl Display for UrError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::InvalidUr(reason) => write!(f, "Invalid UR: {}", reason),
+      Self::InvalidBytewords => write!(f, "Invalid bytewords"),
+      Self::ChecksumMismatch => write!(f, "UR checksum does not match"),
+      Self::InvalidCbor(reason) => write!(f, "Invalid CBOR: {}", reason),
+      Self::UnexpectedType(ur_type) => write!(f, "Unexpected UR type: {}", ur_type),
+      Self::InconsistentPart => write!(f, "UR part does not belong to the same message"),
+      Self::UnsupportedDataType(data_type) => write!(f, "Unsupported data type: {}", data_type),
+      Self::InvalidTransaction(reason) => write!(f, "Invalid transaction: {}", reason),
+      Self::InvalidTypedData(reason) => write!(f, "Invalid typed data: {}", reason),
+      Self::ChainIdOutOfRange(chain_id) => write!(f, "Chain id {} out of range", chain_id),
+    }
+  }
+}
+
+impl Error for UrError {}
@@ -0,0 +1,413 @@
+use identity::{
+  account::derivation_path::{COIN_TYPE, PURPOSE},
+  DerivationPath,
+};
+use utils::hex::encode_prefixed;
+
+use super::{Cbor, Ur, UrError};
+use crate::{
+  rlp::{rlp_decode, RlpItem},
+  SigningContext, TransactionIntent, TypedData, TypedDataError,
+};
+
+/// The UR type of an EIP-4527 signing request
+pub const ETH_SIGN_REQUEST: &str = "eth-sign-request";
+/// The UR type of an EIP-4527 signature
+pub const ETH_SIGNATURE: &str = "eth-signature";
+
+/// CBOR tag of a UUID
+const UUID_TAG: u64 = 37;
+/// CBOR tag of a `crypto-keypath`
+const KEYPATH_TAG: u64 = 304;
+
+/// The kind of data carried by an `EthSignRequest` (EIP-4527)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EthDataType {
+  /// An RLP encoded legacy transaction
+  Transaction,
+  /// EIP-712 typed data
+  TypedData,
+  /// A personal message, to be signed with the EIP-191 prefix
+  PersonalMessage,
+  /// An EIP-2718 typed transaction
+  TypedTransaction,
+}
+
+impl EthDataType {
+  /// Get the numeric identifier of the data type
+  pub fn as_u64(&self) -> u64 {
+    match self {
+      Self::Transaction => 1,
+      Self::TypedData => 2,
+      Self::PersonalMessage => 3,
+      Self::TypedTransaction => 4,
+    }
+  }
+}
+
+impl TryFrom<u64> for EthDataType {
+  type Error = UrError;
+
+  fn try_from(value: u64) -> Result<Self, UrError> {
+    match value {
+      1 => Ok(Self::Transaction),
+      2 => Ok(Self::TypedData),
+      3 => Ok(Self::PersonalMessage),
+      4 => Ok(Self::TypedTransaction),
+      _ => Err(UrError::UnsupportedDataType(value)),
+    }
+  }
+}
+
+/// A request to sign data with an offline keychain, as exported
+/// by a hot wallet through an `eth-sign-request` UR (EIP-4527)
+#[derive(Clone, Debug, PartialEq)]
+pub struct EthSignRequest {
+  /// The UUID of the request, echoed in the signature
+  pub request_id: Option<[u8; 16]>,
+  /// The data to be signed
+  pub sign_data: Vec<u8>,
+  /// The kind of data to be signed
+  pub data_type: EthDataType,
+  /// The chain id, used for legacy transactions
+  pub chain_id: Option<u64>,
+  /// The derivation path of the signing account
+  pub path: DerivationPath,
+  /// The fingerprint of the master key the path derives from
+  pub source_fingerprint: Option<u32>,
+  /// The address of the signing account
+  pub address: Option<[u8; 20]>,
+  /// The name of the requesting wallet
+  pub origin: Option<String>,
+}
+
+impl EthSignRequest {
+  /// Create a new request to sign `sign_data` with the account at `path`
+  pub fn new(sign_data: Vec<u8>, data_type: EthDataType, path: DerivationPath) -> Self {
+    Self {
+      request_id: None,
+      sign_data,
+      data_type,
+      chain_id: None,
+      path,
+      source_fingerprint: None,
+      address: None,
+      origin: None,
+    }
+  }
+
+  /// Get the 0x-prefixed address of the signing account, if set
+  pub fn address_hex(&self) -> Option<String> {
+    self.address.map(|address| encode_prefixed(&address))
+  }
+
+  /// Get the bytes whose keccak256 digest is signed: the
+  /// EIP-191 prefixed message for personal messages, the
+  /// EIP-712 signing bytes for typed data, or the sign
+  /// data itself for transactions
+  pub fn signable_bytes(&self) -> Result<Vec<u8>, UrError> {
+    match self.data_type {
+      EthDataType::PersonalMessage => {
+        let mut bytes =
+          format!("\x19Ethereum Signed Message:\n{}", self.sign_data.len()).into_bytes();
+        bytes.extend(&self.sign_data);
+        Ok(bytes)
+      }
+      EthDataType::Transaction | EthDataType::TypedTransaction => Ok(self.sign_data.clone()),
+      EthDataType::TypedData => self
+        .typed_data()?
+        .signing_bytes()
+        .map_err(|error| UrError::InvalidTypedData(error.to_string())),
+    }
+  }
+
+  /// Parse the JSON typed data carried by the request
+  fn typed_data(&self) -> Result<TypedData, UrError> {
+    std::str::from_utf8(&self.sign_data)
+      .or(Err(UrError::InvalidTypedData("not UTF-8".to_string())))?
+      .parse()
+      .map_err(|error: TypedDataError| UrError::InvalidTypedData(error.to_string()))
+  }
+
+  /// Get the context the signing policy inspects the request with.
+  /// Transactions are decoded so that their recipient, value and
+  /// calldata are checked, and fail if they cannot be decoded
+  pub fn context(&self) -> Result<SigningContext, UrError> {
+    let (transaction, chain_id) = match self.data_type {
+      EthDataType::Transaction | EthDataType::TypedTransaction => {
+        let (transaction, chain_id) = self.decode_transaction()?;
+        (Some(transaction), chain_id.or(self.chain_id))
+      }
+      EthDataType::TypedData => (None, self.typed_data()?.chain_id().or(self.chain_id)),
+      EthDataType::PersonalMessage => (None, self.chain_id),
+    };
+
+    Ok(SigningContext {
+      transaction,
+      allow_blind_signing: false,
+      chain_id,
+    })
+  }
+
+  /// Decode the recipient, value and calldata of the transaction
+  /// carried by the request, with its chain id if it has one
+  fn decode_transaction(&self) -> Result<(TransactionIntent, Option<u64>), UrError> {
+    let invalid = |reason: &str| UrError::InvalidTransaction(reason.to_string());
+
+    // The payload of each kind of transaction, and the position
+    // of the recipient in its fields, followed by the value and calldata
+    let (payload, legacy, to) = match (self.data_type, self.sign_data.split_first()) {
+      (EthDataType::Transaction, Some((0xc0..=0xff, _))) => (&self.sign_data[..], true, 3),
+      // EIP-2930 transactions
+      (EthDataType::TypedTransaction, Some((0x01, payload))) => (payload, false, 4),
+      // EIP-1559, EIP-4844 and EIP-7702 transactions
+      (EthDataType::TypedTransaction, Some((0x02..=0x04, payload))) => (payload, false, 5),
+      _ => return Err(invalid("unsupported transaction type")),
+    };
+    let decoded = rlp_decode(payload).ok_or(invalid("invalid RLP encoding"))?;
+    let fields = decoded.as_list().ok_or(invalid("not an RLP list"))?;
+    let field = |index: usize| {
+      fields
+        .get(index)
+        .and_then(RlpItem::as_bytes)
+        .ok_or(invalid("missing field"))
+    };
+    let uint = |bytes: &[u8]| {
+      (bytes.len() <= 16).then(|| {
+        bytes
+          .iter()
+          .fold(0u128, |value, byte| value << 8 | *byte as u128)
+      })
+    };
+
+    // Legacy transactions carry a chain id only when signed with EIP-155
+    let chain_id = match (legacy, fields.len()) {
+      (true, 9) => Some(6),
+      (true, _) => None,
+      (false, _) => Some(0),
+    }
+    .map(|index| {
+      uint(field(index)?)
+        .and_then(|chain_id| u64::try_from(chain_id).ok())
+        .ok_or(invalid("invalid chain id"))
+    })
+    .transpose()?;
+    let transaction = TransactionIntent {
+      to: match field(to)? {
+        [] => None,
+        to if to.len() == 20 => Some(encode_prefixed(to)),
+        _ => return Err(invalid("invalid recipient")),
+      },
+      value: uint(field(to + 1)?).ok_or(invalid("invalid value"))?,
+      data: field(to + 2)?.to_vec(),
+    };
+
+    Ok((transaction, chain_id))
+  }
+
+  /// Get the `v` value of a signature with `recovery_id`, for the data type.
+  /// Fails if the EIP-155 `v` of a legacy transaction overflows
+  pub fn v(&self, recovery_id: u8) -> Result<u64, UrError> {
+    match (self.data_type, self.chain_id) {
+      (EthDataType::TypedTransaction, _) => Ok(recovery_id as u64),
+      (EthDataType::Transaction, Some(chain_id)) => chain_id
+        .checked_mul(2)
+        .and_then(|v| v.checked_add(35 + recovery_id as u64))
+        .ok_or(UrError::ChainIdOutOfRange(chain_id)),
+      _ => Ok(27 + recovery_id as u64),
+    }
+  }
+
+  /// Encode the request as an `eth-sign-request` UR
+  pub fn to_ur(&self) -> Ur {
+    let mut entries = vec![];
+    if let Some(request_id) = self.request_id {
+      entries.push((Cbor::Unsigned(1), uuid(request_id)));
+    }
+    entries.push((Cbor::Unsigned(2), Cbor::Bytes(self.sign_data.clone())));
+    entries.push((Cbor::Unsigned(3), Cbor::Unsigned(self.data_type.as_u64())));
+    if let Some(chain_id) = self.chain_id {
+      entries.push((Cbor::Unsigned(4), Cbor::Unsigned(chain_id)));
+    }
+    entries.push((
+      Cbor::Unsigned(5),
+      keypath(&self.path, self.source_fingerprint),
+    ));
+    if let Some(address) = self.address {
+      entries.push((Cbor::Unsigned(6), Cbor::Bytes(address.to_vec())));
+    }
+    if let Some(origin) = &self.origin {
+      entries.push((Cbor::Unsigned(7), Cbor::Text(origin.clone())));
+    }
+
+    Ur {
+      ur_type: ETH_SIGN_REQUEST.to_string(),
+      cbor: Cbor::Map(entries).to_bytes(),
+    }
+  }
+}
+
+impl TryFrom<&Ur> for EthSignRequest {
+  type Error = UrError;
+
+  /// Decode a request from an `eth-sign-request` UR
+  fn try_from(ur: &Ur) -> Result<Self, UrError> {
+    if ur.ur_type != ETH_SIGN_REQUEST {
+      return Err(UrError::UnexpectedType(ur.ur_type.clone()));
+    }
+
+    let map = Cbor::from_bytes(&ur.cbor)?;
+    let (path, source_fingerprint) = parse_keypath(required(map.get(5)?)?)?;
+
+    Ok(Self {
+      request_id: map.get(1)?.map(parse_uuid).transpose()?,
+      sign_data: required(map.get(2)?)?.as_bytes()?.to_vec(),
+      data_type: match map.get(3)? {
+        Some(data_type) => EthDataType::try_from(data_type.as_unsigned()?)?,
+        None => EthDataType::Transaction,
+      },
+      chain_id: map.get(4)?.map(Cbor::as_unsigned).transpose()?,
+      path,
+      source_fingerprint,
+      address: map
+        .get(6)?
+        .map(|address| {
+          address
+            .as_bytes()?
+            .try_into()
+            .or(Err(UrError::InvalidCbor("invalid address".to_string())))
+        })
+        .transpose()?,
+      origin: map
+        .get(7)?
+        .map(|origin| origin.as_text().map(str::to_string))
+        .transpose()?,
+    })
+  }
+}
+
+/// A signature produced by an offline keychain, to be imported by
+/// a hot wallet through an `eth-signature` UR (EIP-4527)
+#[derive(Clone, Debug, PartialEq)]
+pub struct EthSignature {
+  /// The UUID of the request being answered
+  pub request_id: Option<[u8; 16]>,
+  /// The signature, as `r || s || v` with `v` big-endian encoded
+  pub signature: Vec<u8>,
+  /// The name of the signing wallet
+  pub origin: Option<String>,
+}
+
+impl EthSignature {
+  /// Encode the signature as an `eth-signature` UR
+  pub fn to_ur(&self) -> Ur {
+    let mut entries = vec![];
+    if let Some(request_id) = self.request_id {
+      entries.push((Cbor::Unsigned(1), uuid(request_id)));
+    }
+    entries.push((Cbor::Unsigned(2), Cbor::Bytes(self.signature.clone())));
+    if let Some(origin) = &self.origin {
+      entries.push((Cbor::Unsigned(3), Cbor::Text(origin.clone())));
+    }
+
+    Ur {
+      ur_type: ETH_SIGNATURE.to_string(),
+      cbor: Cbor::Map(entries).to_bytes(),
+    }
+  }
+}
+
+impl TryFrom<&Ur> for EthSignature {
+  type Error = UrError;
+
+  /// Decode a signature from an `eth-signature` UR
+  fn try_from(ur: &Ur) -> Result<Self, UrError> {
+    if ur.ur_type != ETH_SIGNATURE {
+      return Err(UrError::UnexpectedType(ur.ur_type.clone()));
+    }
+
+    let map = Cbor::from_bytes(&ur.cbor)?;
+
+    Ok(Self {
+      request_id: map.get(1)?.map(parse_uuid).transpose()?,
+      signature: required(map.get(2)?)?.as_bytes()?.to_vec(),
+      origin: map
+        .get(3)?
+        .map(|origin| origin.as_text().map(str::to_string))
+        .transpose()?,
+    })
+  }
+}
+
+fn required(value: Option<&Cbor>) -> Result<&Cbor, UrError> {
+  value.ok_or(UrError::InvalidCbor("missing required field".to_string()))
+}
+
+fn uuid(id: [u8; 16]) -> Cbor {
+  Cbor::Tag(UUID_TAG, Box::new(Cbor::Bytes(id.to_vec())))
+}
+
+fn parse_uuid(value: &Cbor) -> Result<[u8; 16], UrError> {
+  value
+    .untag(UUID_TAG)?
+    .as_bytes()?
+    .try_into()
+    .or(Err(UrError::InvalidCbor("invalid uuid".to_string())))
+}
+
+/// Encode a derivation path as a tagged `crypto-keypath`
+fn keypath(path: &DerivationPath, source_fingerprint: Option<u32>) -> Cbor {
+  let components = [
+    (PURPOSE, true),
+    (COIN_TYPE, true),
+    (path.account, true),
+    (path.change, false),
+    (path.index, false),
+  ]
+  .iter()
+  .flat_map(|(index, hardened)| [Cbor::Unsigned(*index as u64), Cbor::Bool(*hardened)])
+  .collect();
+
+  let mut entries = vec![(Cbor::Unsigned(1), Cbor::Array(components))];
+  if let Some(fingerprint) = source_fingerprint {
+    entries.push((Cbor::Unsigned(2), Cbor::Unsigned(fingerprint as u64)));
+  }
+
+  Cbor::Tag(KEYPATH_TAG, Box::new(Cbor::Map(entries)))
+}
+
+/// Decode a tagged `crypto-keypath` into a derivation path
+/// and the optional source fingerprint
+fn parse_keypath(value: &Cbor) -> Result<(DerivationPath, Option<u32>), UrError> {
+  let keypath = value.untag(KEYPATH_TAG)?;
+  let components = required(keypath.get(1)?)?.as_array()?;
+
+  let path = components
+    .chunks(2)
+    .map(|component| match component {
+      [index, hardened] => Ok(format!(
+        "{}{}",
+        index.as_unsigned()?,
+        if hardened.as_bool()? { "'" } else { "" }
+      )),
+      _ => Err(UrError::InvalidCbor("invalid keypath".to_string())),
+    })
+    .collect::<Result<Vec<String>, UrError>>()?
+    .join("/");
+
+  let path = format!("m/{}", path)
+    .parse()
+    .or(Err(UrError::InvalidCbor(format!(
+      "unsupported path {}",
+      path
+    ))))?;
+  let source_fingerprint = keypath
+    .get(2)?
+    .map(|fingerprint| {
+      u32::try_from(fingerprint.as_unsigned()?)
+        .or(Err(UrError::InvalidCbor("invalid fingerprint".to_string())))
+    })
+    .transpose()?;
+
+  Ok((path, source_fingerprint))
+}
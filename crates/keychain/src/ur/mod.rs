@@ -0,0 +1,13 @@
+pub mod errors;
+pub use errors::*;
+
+pub mod bytewords;
+
+pub mod cbor;
+pub use cbor::Cbor;
+
+pub mod ur;
+pub use ur::*;
+
+pub mod eth;
+pub use eth::*;
@@ -0,0 +1,264 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+  bytewords::{self, crc32},
+  Cbor, UrError,
+};
+
+/// The minimum length of a fragment of a multi-part UR
+const MIN_FRAGMENT_LENGTH: usize = 10;
+
+/// The maximum length of a message reassembled from a multi-part UR
+const MAX_MESSAGE_LENGTH: usize = 1 << 20;
+
+/// A Uniform Resource (BCR-2020-005): a typed CBOR payload encoded
+/// as text, suitable for transport through QR codes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ur {
+  /// The type of the payload, e.g. `eth-sign-request`
+  pub ur_type: String,
+  /// The CBOR encoded payload
+  pub cbor: Vec<u8>,
+}
+
+impl Ur {
+  /// Create a new `Ur` with a CBOR encoded payload
+  pub fn new(ur_type: &str, cbor: Vec<u8>) -> Result<Self, UrError> {
+    if ur_type.is_empty()
+      || !ur_type
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+      return Err(UrError::InvalidUr(format!("invalid type {}", ur_type)));
+    }
+
+    Ok(Self {
+      ur_type: ur_type.to_string(),
+      cbor,
+    })
+  }
+
+  /// Split the UR into the parts of an animated QR code, each one
+  /// carrying a fragment of at most `max_fragment_length` bytes.
+  ///
+  /// A payload fitting a single fragment is returned as a single-part UR.
+  /// Otherwise, the parts are the simple fountain-code parts (one fragment
+  /// each), meant to be displayed in a loop until the scanner completes.
+  pub fn to_parts(&self, max_fragment_length: usize) -> Vec<String> {
+    if self.cbor.len() <= max_fragment_length {
+      return vec![self.to_string()];
+    }
+
+    let fragment_length = nominal_fragment_length(self.cbor.len(), max_fragment_length);
+    let fragments = self.cbor.chunks(fragment_length).collect::<Vec<&[u8]>>();
+    let checksum = crc32(&self.cbor);
+
+    fragments
+      .iter()
+      .enumerate()
+      .map(|(index, fragment)| {
+        let mut fragment = fragment.to_vec();
+        fragment.resize(fragment_length, 0u8);
+
+        let part = Cbor::Array(vec![
+          Cbor::Unsigned(index as u64 + 1),
+          Cbor::Unsigned(fragments.len() as u64),
+          Cbor::Unsigned(self.cbor.len() as u64),
+          Cbor::Unsigned(checksum as u64),
+          Cbor::Bytes(fragment),
+        ]);
+
+        format!(
+          "ur:{}/{}-{}/{}",
+          self.ur_type,
+          index + 1,
+          fragments.len(),
+          bytewords::encode(&part.to_bytes())
+        )
+      })
+      .collect()
+  }
+}
+
+impl Display for Ur {
+  /// Encode the UR as a single-part `ur:<type>/<bytewords>` string
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "ur:{}/{}", self.ur_type, bytewords::encode(&self.cbor))
+  }
+}
+
+impl FromStr for Ur {
+  type Err = UrError;
+
+  /// Parse a single-part UR
+  fn from_str(ur: &str) -> Result<Self, UrError> {
+    match split_ur(ur)? {
+      (ur_type, None, payload) => Self::new(&ur_type, bytewords::decode(payload)?),
+      _ => Err(UrError::InvalidUr("expected a single-part UR".to_string())),
+    }
+  }
+}
+
+/// Reassembles a UR from the parts scanned from an animated QR code,
+/// received in any order and possibly repeated
+#[derive(Clone, Debug, Default)]
+pub struct UrDecoder {
+  ur_type: Option<String>,
+  message_length: usize,
+  checksum: u32,
+  fragments: Vec<Option<Vec<u8>>>,
+  result: Option<Ur>,
+}
+
+impl UrDecoder {
+  /// Create a new empty `UrDecoder`
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Receive a single-part UR or a part of a multi-part UR.
+  ///
+  /// Only simple parts (carrying a single fragment) are used:
+  /// mixed fountain-code parts are accepted and ignored.
+  pub fn receive(&mut self, part: &str) -> Result<(), UrError> {
+    if self.result.is_some() {
+      return Ok(());
+    }
+
+    let (ur_type, sequence, payload) = split_ur(part)?;
+    let Some((seq_num, seq_len)) = sequence else {
+      self.result = Some(Ur::new(&ur_type, bytewords::decode(payload)?)?);
+      return Ok(());
+    };
+
+    let part = Cbor::from_bytes(&bytewords::decode(payload)?)?;
+    let [number, length, message_length, checksum, fragment] = part.as_array()? else {
+      return Err(UrError::InvalidUr("malformed part".to_string()));
+    };
+    let number = number.as_unsigned()? as usize;
+    let length = length.as_unsigned()? as usize;
+    let message_length = message_length.as_unsigned()? as usize;
+    let checksum = u32::try_from(checksum.as_unsigned()?).or(Err(UrError::InconsistentPart))?;
+    let fragment = fragment.as_bytes()?;
+
+    if number != seq_num || number == 0 || length != seq_len || length == 0 {
+      return Err(UrError::InconsistentPart);
+    }
+    // Each fragment is at least `MIN_FRAGMENT_LENGTH` bytes long, which
+    // bounds the fragments to allocate for the declared message length
+    if message_length == 0
+      || message_length > MAX_MESSAGE_LENGTH
+      || length > message_length.div_ceil(MIN_FRAGMENT_LENGTH)
+    {
+      return Err(UrError::InconsistentPart);
+    }
+
+    match &self.ur_type {
+      None => {
+        self.ur_type = Some(ur_type);
+        self.message_length = message_length;
+        self.checksum = checksum;
+        self.fragments = vec![None; length];
+      }
+      Some(expected) => {
+        if *expected != ur_type
+          || self.message_length != message_length
+          || self.checksum != checksum
+          || self.fragments.len() != length
+        {
+          return Err(UrError::InconsistentPart);
+        }
+      }
+    }
+
+    // Mixed fountain-code parts are numbered past `length`
+    if number > length {
+      return Ok(());
+    }
+    self.fragments[number - 1] = Some(fragment.to_vec());
+
+    self.try_complete()
+  }
+
+  /// Check whether the UR has been fully received
+  pub fn is_complete(&self) -> bool {
+    self.result.is_some()
+  }
+
+  /// Get the fraction of the fragments received so far, between 0 and 1
+  pub fn progress(&self) -> f64 {
+    match (&self.result, self.fragments.len()) {
+      (Some(_), _) => 1.0,
+      (None, 0) => 0.0,
+      (None, total) => self.fragments.iter().flatten().count() as f64 / total as f64,
+    }
+  }
+
+  /// Get the reassembled UR, if complete
+  pub fn result(&self) -> Option<&Ur> {
+    self.result.as_ref()
+  }
+
+  fn try_complete(&mut self) -> Result<(), UrError> {
+    if self.fragments.iter().any(Option::is_none) {
+      return Ok(());
+    }
+
+    let mut message = self
+      .fragments
+      .iter()
+      .flatten()
+      .flatten()
+      .copied()
+      .collect::<Vec<u8>>();
+    if message.len() < self.message_length {
+      return Err(UrError::InconsistentPart);
+    }
+    message.truncate(self.message_length);
+
+    if crc32(&message) != self.checksum {
+      return Err(UrError::ChecksumMismatch);
+    }
+
+    // Unwrap is safe because the type is set with the first part
+    self.result = Some(Ur::new(self.ur_type.as_ref().unwrap(), message)?);
+
+    Ok(())
+  }
+}
+
+/// The `seq-len` sequence of a part of a multi-part UR
+type Sequence = Option<(usize, usize)>;
+
+/// Split a UR string into its type, optional sequence and payload
+fn split_ur(ur: &str) -> Result<(String, Sequence, &str), UrError> {
+  let body = match ur.get(..3) {
+    Some(scheme) if scheme.eq_ignore_ascii_case("ur:") => &ur[3..],
+    _ => return Err(UrError::InvalidUr("missing ur: scheme".to_string())),
+  };
+
+  match body.split('/').collect::<Vec<&str>>().as_slice() {
+    [ur_type, payload] => Ok((ur_type.to_lowercase(), None, payload)),
+    [ur_type, sequence, payload] => {
+      let (number, length) = sequence
+        .split_once('-')
+        .and_then(|(number, length)| Some((number.parse().ok()?, length.parse().ok()?)))
+        .ok_or(UrError::InvalidUr(format!("invalid sequence {}", sequence)))?;
+
+      Ok((ur_type.to_lowercase(), Some((number, length)), payload))
+    }
+    _ => Err(UrError::InvalidUr("invalid path".to_string())),
+  }
+}
+
+/// Find the fragment length splitting a message in the fewest fragments
+/// not longer than `max_fragment_length`, as specified by BCR-2020-005
+fn nominal_fragment_length(message_length: usize, max_fragment_length: usize) -> usize {
+  let max_fragment_length = max_fragment_length.max(MIN_FRAGMENT_LENGTH);
+  let max_fragment_count = (message_length / MIN_FRAGMENT_LENGTH).max(1);
+
+  (1..=max_fragment_count)
+    .map(|count| message_length.div_ceil(count))
+    .find(|length| *length <= max_fragment_length)
+    .unwrap_or(MIN_FRAGMENT_LENGTH)
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeSet;
+
+use identity::Account;
+use serde_json::{json, Value};
+
+use crate::KeychainState;
+
+/// How much an account of the keychain has been used to sign
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountUsage {
+  /// The number of signatures produced by the account
+  pub signatures: u64,
+  /// Seconds since the UNIX epoch of the last signature, if any
+  pub last_used: Option<u64>,
+  /// The ids of the chains the account signed for
+  pub chains: BTreeSet<u64>,
+}
+
+impl AccountUsage {
+  /// Get the usage after a signature at `timestamp`, on `chain_id` if known
+  pub fn with_signature(mut self, timestamp: u64, chain_id: Option<u64>) -> Self {
+    self.signatures += 1;
+    self.last_used = Some(self.last_used.unwrap_or_default().max(timestamp));
+    self.chains.extend(chain_id);
+    self
+  }
+
+  /// Get the usage of an account used both here and as `other`,
+  /// like the same account used on two devices
+  pub fn merge(mut self, other: &AccountUsage) -> Self {
+    self.signatures += other.signatures;
+    self.last_used = self.last_used.max(other.last_used);
+    self.chains.extend(&other.chains);
+    self
+  }
+
+  /// Check if the account has not signed since `since`,
+  /// in seconds since the UNIX epoch
+  pub fn is_dormant(&self, since: u64) -> bool {
+    self.last_used.is_none_or(|last_used| last_used < since)
+  }
+
+  /// Get the JSON representation of the usage
+  pub fn to_json(&self) -> Value {
+    json!({
+      "signatures": self.signatures,
+      "lastUsed": self.last_used,
+      "chains": self.chains,
+    })
+  }
+}
+
+impl TryFrom<&Value> for AccountUsage {
+  type Error = String;
+
+  fn try_from(value: &Value) -> Result<Self, Self::Error> {
+    Ok(Self {
+      signatures: value
+        .get("signatures")
+        .and_then(Value::as_u64)
+        .ok_or("Invalid usage signatures")?,
+      last_used: match value.get("lastUsed") {
+        None | Some(Value::Null) => None,
+        Some(last_used) => Some(last_used.as_u64().ok_or("Invalid usage timestamp")?),
+      },
+      chains: value
+        .get("chains")
+        .and_then(Value::as_array)
+        .ok_or("Invalid usage chains")?
+        .iter()
+        .map(|chain_id| chain_id.as_u64().ok_or("Invalid usage chain id"))
+        .collect::<Result<BTreeSet<u64>, &str>>()?,
+    })
+  }
+}
+
+impl KeychainState {
+  /// Get the usage of the account at `address`
+  pub fn account_usage(&self, address: &str) -> AccountUsage {
+    self.usage.get(address).cloned().unwrap_or_default()
+  }
+
+  /// Get the accounts of all the vaults, the most recently used first.
+  /// Accounts that never signed come last, in their vault order
+  pub fn accounts_by_activity(&self) -> Vec<&Account> {
+    let mut accounts = self.accounts();
+    accounts.sort_by_key(|account| {
+      std::cmp::Reverse(
+        self
+          .usage
+          .get(&account.address)
+          .and_then(|usage| usage.last_used),
+      )
+    });
+
+    accounts
+  }
+
+  /// Get the accounts that have not signed since `since`, in seconds
+  /// since the UNIX epoch, as candidates for rotation
+  pub fn dormant_accounts(&self, since: u64) -> Vec<&Account> {
+    self
+      .accounts()
+      .into_iter()
+      .filter(|account| self.account_usage(&account.address).is_dormant(since))
+      .collect()
+  }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::KeychainState;
+
+/// A single account of a keychain view
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountView {
+  /// The index of the vault holding the account
+  pub vault: usize,
+  /// The address of the account
+  pub address: String,
+  /// The label of the account, if any
+  pub label: Option<String>,
+  /// The last tracked balance of the account, in wei
+  pub balance: Option<u128>,
+  /// Whether the vault holding the account is locked
+  pub locked: bool,
+}
+
+impl Serialize for AccountView {
+  /// The balance is a decimal string, as it may not fit a JSON number
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut account = serializer.serialize_struct("AccountView", 5)?;
+    account.serialize_field("vault", &self.vault)?;
+    account.serialize_field("address", &self.address)?;
+    account.serialize_field("label", &self.label)?;
+    account.serialize_field("balance", &self.balance.map(|balance| balance.to_string()))?;
+    account.serialize_field("locked", &self.locked)?;
+    account.end()
+  }
+}
+
+/// A read-only snapshot of the keychain, to hand to GUI layers
+/// without sharing the mutable keychain.
+///
+/// It holds no secret material and cannot sign. Cloning it is cheap,
+/// as its contents are shared between the clones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeychainView {
+  accounts: Arc<[AccountView]>,
+  vaults: Arc<[bool]>,
+  active_profile: Arc<str>,
+}
+
+impl KeychainView {
+  /// Get the accounts of all the vaults
+  pub fn accounts(&self) -> &[AccountView] {
+    &self.accounts
+  }
+
+  /// Get the account at `address`, if any
+  pub fn account(&self, address: &str) -> Option<&AccountView> {
+    self
+      .accounts
+      .iter()
+      .find(|account| account.address == address)
+  }
+
+  /// Get the number of vaults in the keychain
+  pub fn vault_count(&self) -> usize {
+    self.vaults.len()
+  }
+
+  /// Check if the vault at `index` is locked, if any
+  pub fn is_vault_locked(&self, index: usize) -> Option<bool> {
+    self.vaults.get(index).copied()
+  }
+
+  /// Check if all the vaults of the keychain are locked
+  pub fn is_locked(&self) -> bool {
+    !self.vaults.is_empty() && self.vaults.iter().all(|locked| *locked)
+  }
+
+  /// Get the name of the profile currently selected
+  pub fn active_profile(&self) -> &str {
+    &self.active_profile
+  }
+
+  /// Check if two views are the same, comparing their shared contents
+  /// by pointer first, as expected by data binding frameworks like druid
+  pub fn same(&self, other: &Self) -> bool {
+    (Arc::ptr_eq(&self.accounts, &other.accounts)
+      && Arc::ptr_eq(&self.vaults, &other.vaults)
+      && Arc::ptr_eq(&self.active_profile, &other.active_profile))
+      || self == other
+  }
+}
+
+impl Serialize for KeychainView {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut view = serializer.serialize_struct("KeychainView", 3)?;
+    view.serialize_field("accounts", &*self.accounts)?;
+    view.serialize_field("vaults", &*self.vaults)?;
+    view.serialize_field("active_profile", &*self.active_profile)?;
+    view.end()
+  }
+}
+
+impl From<&KeychainState> for KeychainView {
+  fn from(state: &KeychainState) -> Self {
+    Self {
+      accounts: state
+        .vaults
+        .iter()
+        .enumerate()
+        .flat_map(|(vault, vault_state)| {
+          vault_state.accounts.iter().map(move |account| AccountView {
+            vault,
+            address: account.address.clone(),
+            label: state.labels.get(&account.address).cloned(),
+            balance: state
+              .snapshot(&account.address)
+              .and_then(|snapshot| snapshot.balance),
+            locked: vault_state.locked,
+          })
+        })
+        .collect(),
+      vaults: state.vaults.iter().map(|vault| vault.locked).collect(),
+      active_profile: state.active_profile.as_str().into(),
+    }
+  }
+}
+
+impl KeychainState {
+  /// Get a read-only, cheaply clonable snapshot of the state
+  pub fn view(&self) -> KeychainView {
+    KeychainView::from(self)
+  }
+}
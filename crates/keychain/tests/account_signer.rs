@@ -0,0 +1,151 @@
+use hdkey::hdkey_factory;
+use identity::{signer::SignatureOptions, verify_address, Account, DerivationPath};
+use walleth_keychain::{
+  AccountId, AuditEvent, Keychain, KeychainError, SigningContext, SigningPolicy,
+};
+
+fn keychain_with_accounts() -> (Keychain, Account, Account) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let first = keychain.add_account(0).unwrap();
+  let second = keychain.add_account(1).unwrap();
+
+  (keychain, first, second)
+}
+
+fn recoverable() -> SignatureOptions {
+  SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  }
+}
+
+mod account_id {
+  use super::*;
+
+  #[test]
+  fn it_resolves_the_vault_and_path_of_an_address() {
+    let (keychain, _, second) = keychain_with_accounts();
+
+    let id = keychain.account_id(&second.address).unwrap();
+
+    assert_eq!(
+      id,
+      AccountId {
+        key_pair_index: 1,
+        path: second.path
+      }
+    );
+    assert_eq!(keychain.account(id).unwrap(), &second);
+  }
+
+  #[test]
+  fn it_fails_for_an_unknown_address() {
+    let (keychain, _, _) = keychain_with_accounts();
+
+    assert!(keychain.account_id("0x0").is_err());
+  }
+
+  #[test]
+  fn it_fails_for_an_unknown_id() {
+    let (keychain, _, _) = keychain_with_accounts();
+
+    assert!(matches!(
+      keychain.account(AccountId {
+        key_pair_index: 5,
+        path: DerivationPath::from(0)
+      }),
+      Err(KeychainError::KeyNotFoundForIndex(5))
+    ));
+  }
+}
+
+mod use_signer_for {
+  use super::*;
+
+  #[test]
+  fn it_signs_with_an_account_reference() {
+    let (mut keychain, _, second) = keychain_with_accounts();
+
+    let signature = keychain
+      .use_signer_for(&second, b"Hello", &recoverable())
+      .unwrap();
+
+    verify_address(&second.address, b"Hello", &signature).unwrap();
+  }
+}
+
+mod use_signer_by_id {
+  use super::*;
+
+  #[test]
+  fn it_signs_with_an_account_id() {
+    let (mut keychain, first, _) = keychain_with_accounts();
+    let id = keychain.account_id(&first.address).unwrap();
+
+    let signature = keychain
+      .use_signer_by_id(id, b"Hello", &recoverable())
+      .unwrap();
+
+    verify_address(&first.address, b"Hello", &signature).unwrap();
+  }
+
+  #[test]
+  fn it_records_the_signature_in_the_audit_log() {
+    let (mut keychain, first, _) = keychain_with_accounts();
+    let id = keychain.account_id(&first.address).unwrap();
+
+    keychain
+      .use_signer_by_id(id, b"Hello", &recoverable())
+      .unwrap();
+
+    let entry = keychain.audit_log().entries().last().unwrap();
+    assert_eq!(entry.event, AuditEvent::Sign);
+    assert_eq!(entry.account, Some(first.address));
+  }
+}
+
+mod signer {
+  use super::*;
+
+  #[test]
+  fn it_signs_repeatedly_with_the_bound_account() {
+    let (mut keychain, _, second) = keychain_with_accounts();
+    let id = keychain.account_id(&second.address).unwrap();
+
+    let mut signer = keychain.signer(id).unwrap();
+    let first_signature = signer.sign(b"first", &recoverable()).unwrap();
+    let second_signature = signer.sign(b"second", &recoverable()).unwrap();
+
+    assert_eq!(signer.account(), &second);
+    verify_address(&second.address, b"first", &first_signature).unwrap();
+    verify_address(&second.address, b"second", &second_signature).unwrap();
+  }
+
+  #[test]
+  fn it_enforces_the_signing_policy() {
+    let (mut keychain, first, _) = keychain_with_accounts();
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+    let id = keychain.account_id(&first.address).unwrap();
+    let context = SigningContext::transaction(None, 0, &[0xde, 0xad, 0xbe, 0xef]);
+
+    let mut signer = keychain.signer(id).unwrap();
+
+    assert!(matches!(
+      signer.sign_with_context(b"tx", &recoverable(), &context),
+      Err(KeychainError::PolicyViolation(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_while_locked() {
+    let (mut keychain, first, _) = keychain_with_accounts();
+    let id = keychain.account_id(&first.address).unwrap();
+    keychain.lock("password").unwrap();
+
+    let mut signer = keychain.signer(id).unwrap();
+
+    assert!(signer.sign(b"Hello", &recoverable()).is_err());
+  }
+}
@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex};
+
+use utils::Controller;
+use walleth_keychain::{
+  AddressBook, AddressBookError, AddressBookState, Contact, KeychainError, SignedAddressBook,
+};
+
+mod common;
+use common::keychain_with_account;
+
+const ALICE: &str = "0x2C7536E3605D9C16A7A3D7B1898E529396A65C23";
+const BOB: &str = "0x8ba1f109551bd432803012645ac136ddd64dba72";
+
+fn address_book() -> AddressBook {
+  let mut book = AddressBook::new();
+  book
+    .set_contact(Contact::new("bob", BOB, 1).unwrap())
+    .unwrap();
+  book
+    .set_contact(Contact::new("alice", ALICE, 1).unwrap())
+    .unwrap();
+
+  book
+}
+
+mod contact {
+  use super::*;
+
+  #[test]
+  fn it_normalizes_the_address() {
+    let contact = Contact::new("alice", &ALICE[2..], 1).unwrap();
+
+    assert_eq!(contact.address, ALICE.to_lowercase());
+  }
+
+  #[test]
+  fn it_rejects_invalid_addresses() {
+    assert_eq!(
+      Contact::new("alice", "0x1234", 1),
+      Err(AddressBookError::InvalidAddress("0x1234".to_string()))
+    );
+  }
+}
+
+mod set_contact {
+  use super::*;
+
+  #[test]
+  fn it_keeps_contacts_sorted() {
+    let book = address_book();
+
+    let names = book
+      .get_state()
+      .contacts
+      .iter()
+      .map(|contact| contact.name.as_str())
+      .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["alice", "bob"]);
+  }
+
+  #[test]
+  fn it_replaces_the_contact_with_the_same_address_and_chain() {
+    let mut book = address_book();
+
+    book
+      .set_contact(Contact::new("alice (cold)", ALICE, 1).unwrap())
+      .unwrap();
+    book
+      .set_contact(Contact::new("alice", ALICE, 10).unwrap())
+      .unwrap();
+
+    assert_eq!(book.get_state().contacts.len(), 3);
+    assert_eq!(
+      book.get_state().contact(ALICE, 1).unwrap().name,
+      "alice (cold)"
+    );
+  }
+
+  #[test]
+  fn it_notifies_subscribers() {
+    let mut book = AddressBook::new();
+    let states = Arc::new(Mutex::new(vec![]));
+    let received = states.clone();
+    book.subscribe(move |state: &AddressBookState| received.lock().unwrap().push(state.clone()));
+
+    book
+      .set_contact(Contact::new("alice", ALICE, 1).unwrap())
+      .unwrap();
+
+    assert_eq!(states.lock().unwrap().len(), 1);
+  }
+}
+
+mod remove_contact {
+  use super::*;
+
+  #[test]
+  fn it_removes_the_contact() {
+    let mut book = address_book();
+
+    let contact = book.remove_contact(BOB, 1).unwrap();
+
+    assert_eq!(contact.name, "bob");
+    assert!(book.get_state().contact(BOB, 1).is_none());
+  }
+
+  #[test]
+  fn it_fails_with_an_unknown_contact() {
+    let mut book = address_book();
+
+    assert!(matches!(
+      book.remove_contact(BOB, 10),
+      Err(KeychainError::AddressBookError(
+        AddressBookError::ContactNotFound(_)
+      ))
+    ));
+  }
+}
+
+mod save {
+  use super::*;
+
+  #[test]
+  fn it_writes_the_signed_address_book_to_the_sinks() {
+    let (mut keychain, signer) = keychain_with_account();
+    let mut book = address_book();
+    let written = Arc::new(Mutex::new(vec![]));
+    let sink = written.clone();
+    book.add_sink(move |bytes: &[u8]| {
+      *sink.lock().unwrap() = bytes.to_vec();
+      Ok(())
+    });
+
+    let signed = book.save(&mut keychain, &signer).unwrap();
+
+    assert_eq!(*written.lock().unwrap(), signed.to_bytes());
+  }
+
+  #[test]
+  fn it_loads_the_saved_address_book() {
+    let (mut keychain, signer) = keychain_with_account();
+    let mut book = address_book();
+
+    let bytes = book.save(&mut keychain, &signer).unwrap().to_bytes();
+    let loaded = AddressBook::load(&bytes, &signer).unwrap();
+
+    assert_eq!(loaded.get_state(), book.get_state());
+  }
+
+  #[test]
+  fn it_rejects_an_altered_address_book() {
+    let (mut keychain, signer) = keychain_with_account();
+    let mut book = address_book();
+
+    let mut signed = book.save(&mut keychain, &signer).unwrap();
+    signed.state.contacts[0].address = BOB.to_string();
+
+    assert!(matches!(
+      AddressBook::load(&signed.to_bytes(), &signer),
+      Err(AddressBookError::InvalidSignature)
+    ));
+  }
+
+  #[test]
+  fn it_rejects_an_address_book_signed_by_another_account() {
+    let (mut keychain, signer) = keychain_with_account();
+    let other = keychain.add_account(0).unwrap();
+    let mut book = address_book();
+
+    let signed = book.save(&mut keychain, &other.address).unwrap();
+
+    assert_eq!(
+      signed.verify(&signer),
+      Err(AddressBookError::InvalidSignature)
+    );
+    assert!(SignedAddressBook::try_from(signed.to_bytes().as_slice())
+      .unwrap()
+      .verify(&other.address)
+      .is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_a_locked_keychain() {
+    let (mut keychain, signer) = keychain_with_account();
+    let mut book = address_book();
+    keychain.lock("password").unwrap();
+
+    assert!(matches!(
+      book.save(&mut keychain, &signer),
+      Err(KeychainError::Locked(_))
+    ));
+  }
+}
@@ -0,0 +1,163 @@
+use identity::{signer::SignatureOptions, DerivationPath};
+use utils::B256;
+use walleth_keychain::{AuditEvent, AuditLog, Keychain, KeychainError};
+
+mod common;
+use common::keychain_with_account;
+
+mod record {
+  use super::*;
+
+  #[test]
+  fn it_records_sign_events_with_account_and_digest() {
+    let (mut keychain, address) = keychain_with_account();
+
+    keychain
+      .use_signer(
+        address.clone(),
+        b"Hello world!",
+        &SignatureOptions::default(),
+      )
+      .unwrap();
+
+    let entry = &keychain.audit_log().entries()[0];
+    assert_eq!(entry.event, AuditEvent::Sign);
+    assert_eq!(entry.account, Some(address));
+    assert_eq!(entry.path, Some(DerivationPath::from(0)));
+    assert!(entry.payload_digest.is_some());
+  }
+
+  #[test]
+  fn it_records_lock_unlock_and_backup_events() {
+    let (mut keychain, _) = keychain_with_account();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+    keychain.backup("password").unwrap();
+
+    let events = keychain
+      .audit_log()
+      .entries()
+      .iter()
+      .map(|entry| entry.event)
+      .collect::<Vec<AuditEvent>>();
+    assert_eq!(
+      events,
+      vec![AuditEvent::Lock, AuditEvent::Unlock, AuditEvent::Backup]
+    );
+  }
+
+  #[test]
+  fn it_chains_entries_by_hash() {
+    let (mut keychain, _) = keychain_with_account();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    let entries = keychain.audit_log().entries();
+    assert_eq!(entries[0].previous_hash, B256::default());
+    assert_eq!(entries[1].previous_hash, entries[0].hash);
+  }
+}
+
+mod verify {
+  use super::*;
+
+  #[test]
+  fn it_verifies_an_untouched_log() {
+    let (mut keychain, _) = keychain_with_account();
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    assert!(keychain.audit_log().verify().is_ok());
+  }
+
+  #[test]
+  fn it_detects_tampered_entries() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain
+      .use_signer(address, b"Hello world!", &SignatureOptions::default())
+      .unwrap();
+    keychain.lock("password").unwrap();
+
+    let mut entries = keychain.audit_log().entries().to_vec();
+    entries[0].payload_digest = Some(B256::default());
+
+    assert!(matches!(
+      AuditLog::try_from(entries),
+      Err(KeychainError::AuditLogTampered(0))
+    ));
+  }
+
+  #[test]
+  fn it_detects_removed_entries() {
+    let (mut keychain, _) = keychain_with_account();
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    let mut entries = keychain.audit_log().entries().to_vec();
+    entries.remove(0);
+
+    assert!(AuditLog::try_from(entries).is_err());
+  }
+}
+
+mod to_json {
+  use super::*;
+
+  #[test]
+  fn it_exports_entries_as_json() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain
+      .use_signer(
+        address.clone(),
+        b"Hello world!",
+        &SignatureOptions::default(),
+      )
+      .unwrap();
+
+    let json = keychain.audit_log().to_json();
+
+    assert!(json.starts_with('['));
+    assert!(json.contains("\"event\":\"sign\""));
+    assert!(json.contains(&format!("\"account\":\"{}\"", address)));
+    assert!(json.contains("\"path\":\"m/44'/60'/0'/0/0\""));
+  }
+}
+
+mod backup {
+  use super::*;
+
+  #[test]
+  fn it_restores_the_log_with_the_backup() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain
+      .use_signer(address, b"payload", &SignatureOptions::default())
+      .unwrap();
+    let entries = keychain.audit_log().entries().to_vec();
+
+    let restored: Keychain =
+      Keychain::restore(keychain.backup("password").unwrap(), "password").unwrap();
+
+    let log = restored.audit_log();
+    assert_eq!(&log.entries()[..entries.len()], entries.as_slice());
+    assert_eq!(log.entries().last().unwrap().event, AuditEvent::Unlock);
+    assert!(log.verify().is_ok());
+  }
+
+  #[test]
+  fn it_parses_an_exported_log() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain
+      .use_signer(address, b"payload", &SignatureOptions::default())
+      .unwrap();
+    keychain.lock("password").unwrap();
+
+    let json = keychain.audit_log().to_json();
+
+    assert_eq!(
+      &AuditLog::try_from(json.as_bytes()).unwrap(),
+      keychain.audit_log()
+    );
+  }
+}
@@ -0,0 +1,77 @@
+use utils::hex::encode;
+use walleth_keychain::{Authorization, AUTHORIZATION_MAGIC};
+
+mod common;
+use common::keychain_with_account;
+
+const DELEGATE: [u8; 20] = [0x11; 20];
+
+mod signing_bytes {
+  use super::*;
+
+  #[test]
+  fn it_prefixes_the_rlp_tuple() {
+    let authorization = Authorization::new(1, DELEGATE, 0);
+
+    assert_eq!(
+      encode(&authorization.signing_bytes()),
+      format!("{:02x}d70194{}80", AUTHORIZATION_MAGIC, encode(&DELEGATE))
+    );
+  }
+
+  #[test]
+  fn it_encodes_large_integers_as_big_endian() {
+    let authorization = Authorization::new(0x0100, DELEGATE, 0x80);
+
+    assert_eq!(
+      encode(&authorization.signing_bytes()),
+      format!("05da82010094{}8180", encode(&DELEGATE))
+    );
+  }
+}
+
+mod sign_authorization {
+  use super::*;
+
+  #[test]
+  fn it_is_recoverable_to_the_signer() {
+    let (mut keychain, address) = keychain_with_account();
+    let authorization = Authorization::new(1, DELEGATE, 7);
+
+    let signed = keychain
+      .sign_authorization(&address, &authorization)
+      .unwrap();
+
+    assert_eq!(signed.authorization, authorization);
+    assert!(signed.y_parity <= 1);
+    assert_eq!(signed.authority().unwrap(), address);
+  }
+
+  #[test]
+  fn it_encodes_the_authorization_list_item() {
+    let (mut keychain, address) = keychain_with_account();
+    let authorization = Authorization::new(0, DELEGATE, 0);
+
+    let signed = keychain
+      .sign_authorization(&address, &authorization)
+      .unwrap();
+    let rlp = signed.rlp_bytes();
+
+    // A long list: `0xf8`, then the payload length in one byte
+    assert_eq!(rlp[0], 0xf8);
+    assert_eq!(rlp[1] as usize, rlp.len() - 2);
+    assert_eq!(&rlp[2..4], &[0x80, 0x94]);
+  }
+
+  #[test]
+  fn it_fails_for_unknown_accounts() {
+    let (mut keychain, _) = keychain_with_account();
+
+    assert!(keychain
+      .sign_authorization(
+        "0x0000000000000000000000000000000000000000",
+        &Authorization::new(1, DELEGATE, 0)
+      )
+      .is_err());
+  }
+}
@@ -0,0 +1,190 @@
+use std::sync::{Arc, Mutex};
+
+use utils::Controller;
+use walleth_keychain::{
+  is_sealed, seal_backup, unseal_backup, BackupError, BackupSecret, Keychain, KeychainError,
+  RecoveryCode, DEFAULT_SEAL_ROUNDS, MAX_SEAL_ROUNDS, MIN_SEAL_ROUNDS,
+};
+
+mod common;
+use common::keychain_with_account;
+
+fn passphrase() -> BackupSecret {
+  BackupSecret::Passphrase("cloud passphrase".to_string())
+}
+
+mod recovery_code {
+  use super::*;
+
+  #[test]
+  fn it_generates_twelve_words() {
+    let code = RecoveryCode::generate();
+
+    assert_eq!(code.phrase().split(' ').count(), 12);
+    assert_eq!(code.to_string().parse::<RecoveryCode>().unwrap(), code);
+  }
+
+  #[test]
+  fn it_ignores_case_and_whitespace() {
+    let code = RecoveryCode::generate();
+    let typed = format!("  {}  ", code.phrase().to_uppercase().replace(' ', "   "));
+
+    assert_eq!(typed.parse::<RecoveryCode>().unwrap(), code);
+  }
+
+  #[test]
+  fn it_rejects_invalid_codes() {
+    assert_eq!(
+      "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"
+        .parse::<RecoveryCode>(),
+      Err(BackupError::InvalidRecoveryCode)
+    );
+    assert_eq!(
+      "not a recovery code".parse::<RecoveryCode>(),
+      Err(BackupError::InvalidRecoveryCode)
+    );
+  }
+
+  #[test]
+  fn it_does_not_leak_in_debug() {
+    let code = RecoveryCode::generate();
+
+    assert!(!format!("{:?}", code).contains(code.phrase()));
+  }
+}
+
+mod seal_backup {
+  use super::*;
+
+  #[test]
+  fn it_round_trips() {
+    let sealed = seal_backup(b"backup", &passphrase(), MIN_SEAL_ROUNDS).unwrap();
+
+    assert!(is_sealed(&sealed));
+    assert_eq!(unseal_backup(&sealed, &passphrase()).unwrap(), b"backup");
+  }
+
+  #[test]
+  fn it_fails_with_another_secret() {
+    let sealed = seal_backup(b"backup", &passphrase(), MIN_SEAL_ROUNDS).unwrap();
+
+    assert_eq!(
+      unseal_backup(&sealed, &RecoveryCode::generate().into()),
+      Err(BackupError::DecryptionFailed)
+    );
+  }
+
+  #[test]
+  fn it_authenticates_the_header() {
+    let mut sealed = seal_backup(b"backup", &passphrase(), MIN_SEAL_ROUNDS).unwrap();
+    // Tamper with the rounds
+    sealed[4] ^= 1;
+
+    assert!(unseal_backup(&sealed, &passphrase()).is_err());
+  }
+
+  #[test]
+  fn it_rejects_rounds_out_of_bounds() {
+    let mut sealed = seal_backup(b"backup", &passphrase(), MIN_SEAL_ROUNDS).unwrap();
+
+    for rounds in [0, MAX_SEAL_ROUNDS + 1, u32::MAX] {
+      sealed[4..8].copy_from_slice(&rounds.to_le_bytes());
+      assert!(matches!(
+        unseal_backup(&sealed, &passphrase()),
+        Err(BackupError::InvalidSealedBackup(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn it_rejects_sealing_rounds_out_of_bounds() {
+    for rounds in [0, MIN_SEAL_ROUNDS - 1, MAX_SEAL_ROUNDS + 1] {
+      assert_eq!(
+        seal_backup(b"backup", &passphrase(), rounds),
+        Err(BackupError::SealRoundsOutOfBounds(rounds))
+      );
+    }
+  }
+}
+
+mod backup_sealed {
+  use super::*;
+
+  #[test]
+  fn it_restores_with_the_secret_and_the_password() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain.set_seal_rounds(MIN_SEAL_ROUNDS).unwrap();
+    let code = RecoveryCode::generate();
+    let secret = BackupSecret::from(code.clone());
+
+    let sealed = keychain.backup_sealed("password", &secret).unwrap();
+    let restored: Keychain = Keychain::restore_sealed(
+      &sealed,
+      &BackupSecret::from(code.phrase().parse::<RecoveryCode>().unwrap()),
+      "password",
+    )
+    .unwrap();
+
+    assert_eq!(restored.get_state().accounts()[0].address, address);
+  }
+
+  #[test]
+  fn it_seals_with_the_seal_rounds() {
+    let (mut keychain, _) = keychain_with_account();
+    assert_eq!(keychain.seal_rounds(), DEFAULT_SEAL_ROUNDS);
+
+    keychain.set_seal_rounds(MIN_SEAL_ROUNDS).unwrap();
+    let sealed = keychain.backup_sealed("password", &passphrase()).unwrap();
+
+    assert_eq!(sealed[4..8], MIN_SEAL_ROUNDS.to_le_bytes());
+  }
+
+  #[test]
+  fn it_rejects_seal_rounds_out_of_bounds() {
+    let (mut keychain, _) = keychain_with_account();
+
+    assert!(matches!(
+      keychain.set_seal_rounds(MIN_SEAL_ROUNDS - 1),
+      Err(KeychainError::BackupError(
+        BackupError::SealRoundsOutOfBounds(_)
+      ))
+    ));
+    assert_eq!(keychain.seal_rounds(), DEFAULT_SEAL_ROUNDS);
+  }
+
+  #[test]
+  fn it_cannot_be_restored_as_a_plain_backup() {
+    let (mut keychain, _) = keychain_with_account();
+    keychain.set_seal_rounds(MIN_SEAL_ROUNDS).unwrap();
+    let sealed = keychain.backup_sealed("password", &passphrase()).unwrap();
+
+    assert!(matches!(
+      Keychain::<hdkey::HDKey>::restore(sealed, "password"),
+      Err(KeychainError::BackupError(BackupError::Sealed))
+    ));
+  }
+}
+
+mod set_backup_secret {
+  use super::*;
+
+  #[test]
+  fn it_seals_the_backups_written_to_sinks() {
+    let (mut keychain, address) = keychain_with_account();
+    let written = Arc::new(Mutex::new(vec![]));
+    let sink = Arc::clone(&written);
+    keychain.add_backup_sink(move |backup: &[u8]| {
+      *sink.lock().unwrap() = backup.to_vec();
+      Ok(())
+    });
+    keychain.set_backup_secret(Some(passphrase()));
+    keychain.set_seal_rounds(MIN_SEAL_ROUNDS).unwrap();
+
+    keychain.lock("password").unwrap();
+
+    let backup = written.lock().unwrap().clone();
+    assert!(is_sealed(&backup));
+    let restored: Keychain = Keychain::restore_sealed(&backup, &passphrase(), "password").unwrap();
+    assert_eq!(restored.get_state().accounts()[0].address, address);
+  }
+}
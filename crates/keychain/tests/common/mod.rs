@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+
+use hdkey::hdkey_factory;
+use walleth_keychain::Keychain;
+
+/// Create a keychain with one vault and one account,
+/// returning the address of the account
+pub fn keychain_with_account() -> (Keychain, String) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let account = keychain.add_account(0).unwrap();
+
+  (keychain, account.address)
+}
+
+/// Create a keychain with one vault and `count` accounts,
+/// returning the addresses of the accounts
+pub fn keychain_with_accounts(count: usize) -> (Keychain, Vec<String>) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let addresses = keychain
+    .derive_range(0, 0, count)
+    .unwrap()
+    .into_iter()
+    .map(|account| account.address)
+    .collect();
+
+  (keychain, addresses)
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "compression")]
+
+use hdkey::{hdkey_factory, HDKey};
+use walleth_keychain::{migrations::COMPRESSED_FLAG, Keychain, KeychainError, MigrationError};
+
+fn keychain() -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.derive_range(0, 0, 20).unwrap();
+
+  keychain
+}
+
+mod set_backup_compression {
+  use super::*;
+
+  #[test]
+  fn it_compresses_backups() {
+    let mut keychain = keychain();
+    let plain = keychain.backup("password").unwrap();
+
+    keychain.set_backup_compression(true);
+    let compressed = keychain.backup("password").unwrap();
+
+    assert_eq!(compressed[6], COMPRESSED_FLAG);
+    assert!(compressed.len() < plain.len());
+  }
+
+  #[test]
+  fn it_restores_compressed_backups_transparently() {
+    let mut keychain = keychain();
+    keychain.set_backup_compression(true);
+    let backup = keychain.backup("password").unwrap();
+
+    let restored = Keychain::<HDKey>::restore(backup, "password").unwrap();
+
+    assert_eq!(restored, keychain);
+  }
+
+  #[test]
+  fn it_detects_corrupted_backups() {
+    let mut keychain = keychain();
+    keychain.set_backup_compression(true);
+    let mut backup = keychain.backup("password").unwrap();
+    let last = backup.len() - 1;
+    backup[last] ^= 1;
+
+    assert!(matches!(
+      Keychain::<HDKey>::restore(backup, "password"),
+      Err(KeychainError::MigrationError(
+        MigrationError::InvalidPayload(_)
+      ))
+    ));
+  }
+}
@@ -0,0 +1,74 @@
+use utils::{crypto::sha3::keccak256, hex::decode_to_array};
+use walleth_keychain::{create2_address, create2_address_from_code, create_address};
+
+fn address(hex: &str) -> [u8; 20] {
+  decode_to_array(hex).unwrap()
+}
+
+mod create_address {
+  use super::*;
+
+  #[test]
+  fn it_matches_known_deployments() {
+    let deployer = address("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+
+    assert_eq!(
+      create_address(&deployer, 0),
+      "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"
+    );
+    assert_eq!(
+      create_address(&deployer, 1),
+      "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"
+    );
+    assert_eq!(
+      create_address(&deployer, 3),
+      "0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c"
+    );
+  }
+}
+
+mod create2_address {
+  use super::*;
+
+  #[test]
+  fn it_matches_the_eip_1014_examples() {
+    let zero_salt = [0u8; 32];
+    let mut salt = [0u8; 32];
+    salt[28..].copy_from_slice(&[0xca, 0xfe, 0xba, 0xbe]);
+
+    assert_eq!(
+      create2_address_from_code(&[0u8; 20], &zero_salt, &[0x00]),
+      "0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"
+    );
+    assert_eq!(
+      create2_address_from_code(
+        &address("deadbeef00000000000000000000000000000000"),
+        &zero_salt,
+        &[0x00]
+      ),
+      "0xb928f69bb1d91cd65274e3c79d8986362984fda3"
+    );
+    assert_eq!(
+      create2_address_from_code(
+        &address("00000000000000000000000000000000deadbeef"),
+        &salt,
+        &[0xde, 0xad, 0xbe, 0xef]
+      ),
+      "0x60f3f640a8508fc6a86d45df051962668e1e8ac7"
+    );
+    assert_eq!(
+      create2_address_from_code(&[0u8; 20], &zero_salt, &[]),
+      "0xe33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0"
+    );
+  }
+
+  #[test]
+  fn it_accepts_the_init_code_hash() {
+    let deployer = address("deadbeef00000000000000000000000000000000");
+
+    assert_eq!(
+      create2_address(&deployer, &[1u8; 32], &keccak256(&[0x60, 0x00])),
+      create2_address_from_code(&deployer, &[1u8; 32], &[0x60, 0x00])
+    );
+  }
+}
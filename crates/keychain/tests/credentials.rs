@@ -0,0 +1,194 @@
+use hdkey::hdkey_factory;
+use utils::Controller;
+use vault::{KdfParams, PASSWORD_KEY_SLOT};
+use walleth_keychain::{KeyPair, Keychain, KeychainError, PasskeyCredential, RECOVERY_KEY_SLOT};
+
+const PASSWORD: &str = "password";
+
+/// The output of the PRF extension of a passkey, as returned by an authenticator
+const PRF_OUTPUT: [u8; 32] = [9u8; 32];
+
+fn keychain_with_vaults(count: usize) -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.set_kdf_params(KdfParams::new(10));
+  for index in 0..count {
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_account(index).unwrap();
+  }
+
+  keychain
+}
+
+mod add_recovery_key {
+  use super::*;
+
+  #[test]
+  fn it_unlocks_with_the_password_or_the_recovery_key() {
+    let mut keychain = keychain_with_vaults(2);
+    let code = keychain.add_recovery_key().unwrap();
+    let accounts = keychain.get_state().accounts().len();
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.unlock(code.phrase()).unwrap();
+    assert_eq!(keychain.get_state().accounts().len(), accounts);
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.unlock(PASSWORD).unwrap();
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_keeps_the_recovery_key_when_changing_the_password() {
+    let mut keychain = keychain_with_vaults(1);
+    let code = keychain.add_recovery_key().unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.change_password(PASSWORD, "new password").unwrap();
+
+    assert!(keychain.unlock(PASSWORD).is_err());
+    keychain.unlock(code.phrase()).unwrap();
+    keychain.lock("new password").unwrap();
+    keychain.unlock("new password").unwrap();
+  }
+
+  #[test]
+  fn it_restores_backups_with_the_recovery_key() {
+    let mut keychain = keychain_with_vaults(1);
+    let code = keychain.add_recovery_key().unwrap();
+    let backup = keychain.backup(PASSWORD).unwrap();
+
+    let restored: Keychain = Keychain::restore(backup, code.phrase()).unwrap();
+
+    assert_eq!(
+      restored.get_state().accounts(),
+      keychain.get_state().accounts()
+    );
+  }
+
+  #[test]
+  fn it_adds_a_slot_to_every_vault() {
+    let mut keychain = keychain_with_vaults(2);
+
+    keychain.add_recovery_key().unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    for index in 0..2 {
+      let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(index).unwrap();
+      assert_eq!(
+        vault.key_slot_names(),
+        vec![PASSWORD_KEY_SLOT, RECOVERY_KEY_SLOT]
+      );
+    }
+  }
+
+  #[test]
+  fn it_fails_while_locked() {
+    let mut keychain = keychain_with_vaults(1);
+    keychain.lock(PASSWORD).unwrap();
+
+    assert!(matches!(
+      keychain.add_recovery_key(),
+      Err(KeychainError::VaultError(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_a_duplicate_slot() {
+    let mut keychain = keychain_with_vaults(1);
+    keychain.add_recovery_key().unwrap();
+
+    assert!(keychain.add_recovery_key().is_err());
+  }
+}
+
+mod remove_unlock_credential {
+  use super::*;
+
+  #[test]
+  fn it_revokes_a_credential_while_locked() {
+    let mut keychain = keychain_with_vaults(1);
+    keychain
+      .add_unlock_credential("laptop", "laptop secret")
+      .unwrap();
+    let code = keychain.add_recovery_key().unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    assert!(keychain.remove_unlock_credential("laptop").unwrap());
+
+    assert!(keychain.unlock("laptop secret").is_err());
+    keychain.unlock(code.phrase()).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+    keychain.unlock(PASSWORD).unwrap();
+  }
+
+  #[test]
+  fn it_reports_missing_credentials() {
+    let mut keychain = keychain_with_vaults(1);
+
+    assert!(!keychain.remove_unlock_credential("laptop").unwrap());
+  }
+
+  #[test]
+  fn it_keeps_the_password_slot() {
+    let mut keychain = keychain_with_vaults(1);
+
+    assert!(keychain
+      .remove_unlock_credential(PASSWORD_KEY_SLOT)
+      .is_err());
+  }
+}
+
+mod add_passkey {
+  use super::*;
+
+  #[test]
+  fn it_unlocks_with_the_prf_output_of_the_passkey() {
+    let mut keychain = keychain_with_vaults(2);
+    let passkey = PasskeyCredential::new("yubikey", b"credential id");
+    keychain.add_passkey(&passkey, &PRF_OUTPUT).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    assert!(keychain.unlock_with_passkey(&[1u8; 32]).is_err());
+    keychain.unlock_with_passkey(&PRF_OUTPUT).unwrap();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_lists_the_passkeys_while_locked() {
+    let mut keychain = keychain_with_vaults(2);
+    let passkey = PasskeyCredential::new("yubikey", b"credential id");
+    keychain.add_passkey(&passkey, &PRF_OUTPUT).unwrap();
+    keychain.add_recovery_key().unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    assert_eq!(keychain.passkeys(), vec![passkey]);
+  }
+
+  #[test]
+  fn it_restores_passkeys_from_backups() {
+    let mut keychain = keychain_with_vaults(1);
+    let passkey = PasskeyCredential::new("yubikey", b"credential id");
+    keychain.add_passkey(&passkey, &PRF_OUTPUT).unwrap();
+    let backup = keychain.backup(PASSWORD).unwrap();
+
+    let mut restored: Keychain = Keychain::restore(backup, PASSWORD).unwrap();
+    restored.lock(PASSWORD).unwrap();
+
+    assert_eq!(restored.passkeys(), vec![passkey]);
+    restored.unlock_with_passkey(&PRF_OUTPUT).unwrap();
+  }
+
+  #[test]
+  fn it_revokes_passkeys() {
+    let mut keychain = keychain_with_vaults(1);
+    let passkey = PasskeyCredential::new("yubikey", b"credential id");
+    keychain.add_passkey(&passkey, &PRF_OUTPUT).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.remove_unlock_credential("yubikey").unwrap();
+
+    assert!(keychain.passkeys().is_empty());
+    assert!(keychain.unlock_with_passkey(&PRF_OUTPUT).is_err());
+  }
+}
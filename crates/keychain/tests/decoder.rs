@@ -0,0 +1,193 @@
+use walleth_keychain::{
+  decoder::{format_units, uint_to_decimal, AbiFunction, AbiValue},
+  Decoder, DecoderError,
+};
+
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const RECIPIENT: &str = "0x00000000000000000000000000000000000abc12";
+
+fn word(value: u64) -> Vec<u8> {
+  let mut word = vec![0u8; 24];
+  word.extend(value.to_be_bytes());
+  word
+}
+
+fn address_word(address: &str) -> Vec<u8> {
+  let mut word = vec![0u8; 12];
+  word.extend(utils::hex::decode(&address[2..]).ok().unwrap());
+  word
+}
+
+fn calldata(signature: &str, words: Vec<Vec<u8>>) -> Vec<u8> {
+  let mut calldata = AbiFunction::new(signature, &[]).unwrap().selector.to_vec();
+  words.into_iter().for_each(|word| calldata.extend(word));
+  calldata
+}
+
+mod abi_function {
+  use super::*;
+
+  #[test]
+  fn it_computes_the_selector() {
+    let function = AbiFunction::new("transfer(address,uint256)", &["to", "amount"]).unwrap();
+
+    assert_eq!(function.selector, [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(function.inputs[0].0, "to");
+  }
+
+  #[test]
+  fn it_rejects_invalid_signatures() {
+    assert!(AbiFunction::new("transfer", &[]).is_err());
+    assert!(AbiFunction::new("transfer(string)", &[]).is_err());
+  }
+}
+
+mod decode {
+  use super::*;
+
+  #[test]
+  fn it_describes_an_erc20_transfer_of_a_known_token() {
+    let mut decoder = Decoder::with_builtins();
+    decoder.register_token(USDC, "USDC", 6);
+    let data = calldata(
+      "transfer(address,uint256)",
+      vec![address_word(RECIPIENT), word(100_000_000)],
+    );
+
+    let call = decoder.decode(Some(USDC), &data).unwrap();
+
+    assert_eq!(call.function, "transfer");
+    assert_eq!(
+      call.description,
+      format!("transfer 100 USDC to {}", RECIPIENT)
+    );
+    assert_eq!(
+      call.arguments[0],
+      ("to".to_string(), AbiValue::Address(RECIPIENT.to_string()))
+    );
+  }
+
+  #[test]
+  fn it_describes_an_erc20_transfer_of_an_unknown_token() {
+    let decoder = Decoder::with_builtins();
+    let data = calldata(
+      "transfer(address,uint256)",
+      vec![address_word(RECIPIENT), word(5)],
+    );
+
+    let call = decoder.decode(Some(USDC), &data).unwrap();
+
+    assert_eq!(
+      call.description,
+      format!(
+        "transfer 5 tokens of {} to {}",
+        USDC.to_lowercase(),
+        RECIPIENT
+      )
+    );
+  }
+
+  #[test]
+  fn it_describes_unlimited_approvals() {
+    let mut decoder = Decoder::with_builtins();
+    decoder.register_token(USDC, "USDC", 6);
+    let data = calldata(
+      "approve(address,uint256)",
+      vec![address_word(RECIPIENT), vec![0xff; 32]],
+    );
+
+    let call = decoder.decode(Some(USDC), &data).unwrap();
+
+    assert_eq!(
+      call.description,
+      format!("approve {} to spend unlimited USDC", RECIPIENT)
+    );
+  }
+
+  #[test]
+  fn it_describes_an_erc1155_batch_transfer() {
+    let decoder = Decoder::with_builtins();
+    let data = calldata(
+      "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+      vec![
+        address_word(RECIPIENT),
+        address_word(RECIPIENT),
+        // Offsets of the dynamic arguments
+        word(160),
+        word(256),
+        word(352),
+        // ids
+        word(2),
+        word(1),
+        word(2),
+        // amounts
+        word(2),
+        word(10),
+        word(20),
+        // data
+        word(0),
+      ],
+    );
+
+    let call = decoder.decode(None, &data).unwrap();
+
+    assert_eq!(
+      call.description,
+      format!(
+        "transfer 2 token types of the contract from {} to {}",
+        RECIPIENT, RECIPIENT
+      )
+    );
+    assert_eq!(call.arguments[4].1, AbiValue::Bytes(vec![]));
+  }
+
+  #[test]
+  fn it_describes_registered_functions_generically() {
+    let mut decoder = Decoder::new();
+    decoder
+      .register_function("deposit(uint256,bool)", &["amount", "stake"])
+      .unwrap();
+    let data = calldata("deposit(uint256,bool)", vec![word(42), word(1)]);
+
+    let call = decoder.decode(None, &data).unwrap();
+
+    assert_eq!(call.description, "call deposit(42, true)");
+  }
+
+  #[test]
+  fn it_fails_with_unknown_selectors() {
+    let decoder = Decoder::with_builtins();
+
+    assert_eq!(
+      decoder.decode(None, &[1, 2, 3, 4]),
+      Err(DecoderError::UnknownSelector([1, 2, 3, 4]))
+    );
+    assert!(!decoder.can_decode(&[1, 2, 3, 4]));
+  }
+
+  #[test]
+  fn it_fails_with_truncated_calldata() {
+    let decoder = Decoder::with_builtins();
+    let data = calldata("transfer(address,uint256)", vec![address_word(RECIPIENT)]);
+
+    assert_eq!(
+      decoder.decode(None, &data),
+      Err(DecoderError::CalldataTooShort)
+    );
+  }
+}
+
+mod format_units {
+  use super::*;
+
+  #[test]
+  fn it_formats_amounts_with_decimals() {
+    let mut value = [0u8; 32];
+    value[24..].copy_from_slice(&1_500_000u64.to_be_bytes());
+
+    assert_eq!(uint_to_decimal(&value), "1500000");
+    assert_eq!(format_units(&value, 6), "1.5");
+    assert_eq!(format_units(&value, 8), "0.015");
+    assert_eq!(format_units(&[0u8; 32], 18), "0");
+  }
+}
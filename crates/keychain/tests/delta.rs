@@ -0,0 +1,187 @@
+use hdkey::hdkey_factory;
+use utils::Controller;
+use walleth_keychain::{BackupDelta, BackupError, Keychain, KeychainError};
+
+mod common;
+use common::keychain_with_account;
+
+fn restored(keychain: &mut Keychain) -> Keychain {
+  Keychain::restore(keychain.backup("password").unwrap(), "password").unwrap()
+}
+
+mod revision {
+  use super::*;
+
+  #[test]
+  fn it_increases_with_each_change() {
+    let (mut keychain, address) = keychain_with_account();
+    let revision = keychain.revision();
+
+    keychain.set_account_label(&address, Some("main")).unwrap();
+
+    assert!(keychain.revision() > revision);
+  }
+
+  #[test]
+  fn it_does_not_change_when_locking() {
+    let (mut keychain, _) = keychain_with_account();
+    let revision = keychain.revision();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    assert_eq!(keychain.revision(), revision);
+  }
+
+  #[test]
+  fn it_is_restored_from_backups() {
+    let (mut keychain, _) = keychain_with_account();
+
+    assert_eq!(restored(&mut keychain).revision(), keychain.revision());
+  }
+}
+
+mod backup_delta {
+  use super::*;
+
+  #[test]
+  fn it_is_empty_without_changes() {
+    let (mut keychain, _) = keychain_with_account();
+    let revision = keychain.revision();
+
+    let delta = keychain.backup_delta("password", revision).unwrap();
+
+    assert!(delta.is_empty());
+    assert_eq!(delta.revision, revision);
+  }
+
+  #[test]
+  fn it_only_includes_the_metadata_after_a_label_change() {
+    let (mut keychain, address) = keychain_with_account();
+    let revision = keychain.revision();
+
+    keychain.set_account_label(&address, Some("main")).unwrap();
+    let delta = keychain.backup_delta("password", revision).unwrap();
+
+    assert!(delta.vaults.is_empty());
+    assert_eq!(delta.metadata.unwrap()["labels"][&address], "main");
+  }
+
+  #[test]
+  fn it_only_includes_the_changed_vaults() {
+    let (mut keychain, _) = keychain_with_account();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let revision = keychain.revision();
+
+    keychain.add_account(1).unwrap();
+    let delta = keychain.backup_delta("password", revision).unwrap();
+
+    assert_eq!(
+      delta
+        .vaults
+        .iter()
+        .map(|(index, _)| *index)
+        .collect::<Vec<usize>>(),
+      vec![1]
+    );
+    assert!(delta.metadata.is_none());
+  }
+
+  #[test]
+  fn it_round_trips_through_bytes() {
+    let (mut keychain, address) = keychain_with_account();
+    keychain.set_account_label(&address, Some("main")).unwrap();
+
+    let delta = keychain.backup_delta("password", 0).unwrap();
+
+    assert_eq!(
+      BackupDelta::try_from(delta.to_bytes().unwrap().as_slice()).unwrap(),
+      delta
+    );
+  }
+
+  #[test]
+  fn it_rejects_truncated_bytes() {
+    let (mut keychain, _) = keychain_with_account();
+    let bytes = keychain
+      .backup_delta("password", 0)
+      .unwrap()
+      .to_bytes()
+      .unwrap();
+
+    assert!(matches!(
+      BackupDelta::try_from(&bytes[..bytes.len() / 2]),
+      Err(BackupError::InvalidDelta(_))
+    ));
+  }
+}
+
+mod apply_delta {
+  use super::*;
+
+  #[test]
+  fn it_brings_a_restored_keychain_up_to_date() {
+    let (mut keychain, address) = keychain_with_account();
+    let mut copy = restored(&mut keychain);
+    let revision = keychain.revision();
+
+    keychain.set_account_label(&address, Some("main")).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(1).unwrap();
+    let delta = keychain.backup_delta("password", revision).unwrap();
+    copy.apply_delta(&delta, "password").unwrap();
+
+    assert_eq!(copy.revision(), keychain.revision());
+    assert_eq!(copy.get_state().labels, keychain.get_state().labels);
+    assert_eq!(copy.get_state().profiles, keychain.get_state().profiles);
+    assert_eq!(copy.get_state().accounts()[1].address, account.address);
+  }
+
+  #[test]
+  fn it_replaces_changed_vaults() {
+    let (mut keychain, _) = keychain_with_account();
+    let mut copy = restored(&mut keychain);
+    let revision = keychain.revision();
+
+    keychain.add_account(0).unwrap();
+    let delta = keychain.backup_delta("password", revision).unwrap();
+    copy.apply_delta(&delta, "password").unwrap();
+
+    assert_eq!(copy.vault_count(), 1);
+    assert_eq!(copy.get_state().accounts(), keychain.get_state().accounts());
+  }
+
+  #[test]
+  fn it_leaves_the_keychain_untouched_on_failure() {
+    let (mut keychain, address) = keychain_with_account();
+    let mut copy = restored(&mut keychain);
+    let revision = keychain.revision();
+
+    keychain.set_account_label(&address, Some("main")).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_account(1).unwrap();
+    let mut delta = keychain.backup_delta("password", revision).unwrap();
+    delta.vaults.push((2, b"not a vault".to_vec()));
+
+    assert!(copy.apply_delta(&delta, "password").is_err());
+    assert_eq!(copy.vault_count(), 1);
+    assert_eq!(copy.revision(), revision);
+    assert!(copy.get_state().labels.is_empty());
+  }
+
+  #[test]
+  fn it_refuses_deltas_with_a_gap() {
+    let (mut keychain, address) = keychain_with_account();
+    let mut copy = restored(&mut keychain);
+
+    keychain.set_account_label(&address, Some("main")).unwrap();
+    let revision = keychain.revision();
+    keychain.set_account_label(&address, None).unwrap();
+    let delta = keychain.backup_delta("password", revision).unwrap();
+
+    assert!(matches!(
+      copy.apply_delta(&delta, "password"),
+      Err(KeychainError::BackupError(BackupError::DeltaGap(_, _)))
+    ));
+  }
+}
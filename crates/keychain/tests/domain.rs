@@ -0,0 +1,123 @@
+use hdkey::hdkey_factory;
+use utils::Controller;
+use vault::SEQUENTIAL_INDEX_LIMIT;
+use walleth_keychain::{domain_account_index, Keychain};
+
+const PASSWORD: &str = "password";
+const MNEMONIC: &str =
+  "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+fn keychain() -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain
+    .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+    .unwrap();
+  keychain
+}
+
+mod domain_account_index {
+  use super::*;
+
+  #[test]
+  fn it_normalizes_the_origin() {
+    assert_eq!(
+      domain_account_index("https://App.example/"),
+      domain_account_index("https://app.example")
+    );
+    assert_ne!(
+      domain_account_index("https://app.example"),
+      domain_account_index("https://other.example")
+    );
+  }
+
+  #[test]
+  fn it_stays_out_of_the_sequential_indexes() {
+    let index = domain_account_index("https://app.example");
+
+    assert!(index >= SEQUENTIAL_INDEX_LIMIT);
+    assert!(index < 1 << 31);
+  }
+}
+
+mod domain_account {
+  use super::*;
+
+  #[test]
+  fn it_is_deterministic_across_keychains() {
+    let mut first = keychain();
+    let mut second = keychain();
+
+    let account = first.domain_account(0, "https://app.example").unwrap();
+
+    assert_eq!(
+      second
+        .domain_account(0, "https://APP.example/")
+        .unwrap()
+        .address,
+      account.address
+    );
+    assert_ne!(
+      first
+        .domain_account(0, "https://other.example")
+        .unwrap()
+        .address,
+      account.address
+    );
+  }
+
+  #[test]
+  fn it_records_the_binding() {
+    let mut keychain = keychain();
+
+    let account = keychain.domain_account(0, "https://app.example").unwrap();
+
+    assert_eq!(
+      keychain.get_state().domain_address("https://app.example/"),
+      Some(account.address.as_str())
+    );
+  }
+
+  #[test]
+  fn it_does_not_shift_sequential_accounts() {
+    let mut keychain = keychain();
+    let first = keychain.add_account(0).unwrap();
+
+    keychain.domain_account(0, "https://app.example").unwrap();
+    let second = keychain.add_account(0).unwrap();
+
+    assert_eq!(second.path.index, first.path.index + 1);
+  }
+
+  #[test]
+  fn it_persists_in_backups() {
+    let mut keychain = keychain();
+    let account = keychain.domain_account(0, "https://app.example").unwrap();
+
+    let backup = keychain.backup(PASSWORD).unwrap();
+    let restored: Keychain = Keychain::restore(backup, PASSWORD).unwrap();
+
+    assert_eq!(
+      restored.get_state().domain_address("https://app.example"),
+      Some(account.address.as_str())
+    );
+    assert!(restored
+      .get_state()
+      .accounts()
+      .iter()
+      .any(|restored| restored.address == account.address));
+  }
+
+  #[test]
+  fn it_is_carried_by_deltas() {
+    let mut keychain = keychain();
+    let revision = keychain.revision();
+
+    let account = keychain.domain_account(0, "https://app.example").unwrap();
+    let delta = keychain.backup_delta(PASSWORD, revision).unwrap();
+
+    assert_eq!(
+      delta.metadata.unwrap()["domains"]["https://app.example"],
+      account.address
+    );
+  }
+}
@@ -0,0 +1,139 @@
+use walleth_keychain::{
+  encryption_public_key, AuditEvent, EncryptedData, EncryptionError, KeychainError,
+};
+
+mod common;
+use common::keychain_with_account;
+
+const PASSWORD: &str = "password";
+
+/// The private key of the EIP-1024 example
+const PRIVATE_KEY: [u8; 32] = [
+  0x7e, 0x53, 0x74, 0xec, 0x2e, 0xf0, 0xd9, 0x17, 0x61, 0xa6, 0xe7, 0x2f, 0xdf, 0x8f, 0x6a, 0xc6,
+  0x65, 0x51, 0x9b, 0xfd, 0xf6, 0xda, 0x0a, 0x23, 0x29, 0xcf, 0x0d, 0x80, 0x45, 0x14, 0xb8, 0x16,
+];
+
+/// The encryption public key of the EIP-1024 example
+const PUBLIC_KEY: &str = "C5YMNdqE4kLgxQhJO1MfuQcHP5hjVSXzamzd/TxlR0U=";
+
+/// The encrypted data of the EIP-1024 example
+const ENCRYPTED_DATA: &str = r#"{
+  "version": "x25519-xsalsa20-poly1305",
+  "nonce": "1dvWO7uOnBnO7iNDJ9kO9pTasLuKNlej",
+  "ephemPublicKey": "FBH1/pAEHOOW14Lu3FWkgV3qOEcuL78Zy+qW1RwzMXQ=",
+  "ciphertext": "f8kBcl/NCyf3sybfbwAKk/np2Bzt9lRVkZejr6uh5FgnNlH/ic62DZzy"
+}"#;
+
+mod encryption_public_key {
+  use super::*;
+
+  #[test]
+  fn it_matches_the_eip_1024_example() {
+    assert_eq!(encryption_public_key(&PRIVATE_KEY), PUBLIC_KEY);
+  }
+}
+
+mod encrypted_data {
+  use super::*;
+
+  #[test]
+  fn it_decrypts_the_eip_1024_example() {
+    let data = ENCRYPTED_DATA.parse::<EncryptedData>().unwrap();
+
+    assert_eq!(
+      data.decrypt(&PRIVATE_KEY).unwrap().as_slice(),
+      b"My name is Satoshi Buterin"
+    );
+  }
+
+  #[test]
+  fn it_round_trips_through_hex() {
+    let data = EncryptedData::encrypt(PUBLIC_KEY, b"hello").unwrap();
+
+    assert_eq!(data.to_hex().parse::<EncryptedData>().unwrap(), data);
+  }
+
+  #[test]
+  fn it_fails_with_another_private_key() {
+    let data = ENCRYPTED_DATA.parse::<EncryptedData>().unwrap();
+
+    assert_eq!(
+      data.decrypt(&[1u8; 32]),
+      Err(EncryptionError::DecryptionFailed)
+    );
+  }
+
+  #[test]
+  fn it_rejects_unsupported_versions() {
+    let data = ENCRYPTED_DATA.replace("x25519-xsalsa20-poly1305", "x25519-chacha20");
+
+    assert_eq!(
+      data.parse::<EncryptedData>(),
+      Err(EncryptionError::UnsupportedVersion(
+        "x25519-chacha20".to_string()
+      ))
+    );
+  }
+
+  #[test]
+  fn it_rejects_invalid_public_keys() {
+    assert_eq!(
+      EncryptedData::encrypt("not a key", b"hello"),
+      Err(EncryptionError::InvalidPublicKey)
+    );
+  }
+}
+
+mod decrypt {
+  use super::*;
+
+  #[test]
+  fn it_decrypts_data_encrypted_for_the_account() {
+    let (mut keychain, address) = keychain_with_account();
+    let public_key = keychain.encryption_public_key(&address).unwrap();
+    let data = EncryptedData::encrypt(&public_key, b"gm").unwrap();
+
+    assert_eq!(keychain.decrypt(&address, &data.to_hex()).unwrap(), "gm");
+  }
+
+  #[test]
+  fn it_records_the_decryption() {
+    let (mut keychain, address) = keychain_with_account();
+    let public_key = keychain.encryption_public_key(&address).unwrap();
+    let data = EncryptedData::encrypt(&public_key, b"gm").unwrap();
+
+    keychain
+      .decrypt(&address, &data.to_json().to_string())
+      .unwrap();
+
+    let entry = keychain.audit_log().entries().last().unwrap();
+    assert_eq!(entry.event, AuditEvent::Decrypt);
+    assert_eq!(entry.account, Some(address));
+  }
+
+  #[test]
+  fn it_fails_with_a_locked_vault() {
+    let (mut keychain, address) = keychain_with_account();
+    let public_key = keychain.encryption_public_key(&address).unwrap();
+    let data = EncryptedData::encrypt(&public_key, b"gm").unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    assert!(matches!(
+      keychain.decrypt(&address, &data.to_hex()),
+      Err(KeychainError::Locked(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_data_for_another_account() {
+    let (mut keychain, address) = keychain_with_account();
+    let data = EncryptedData::encrypt(PUBLIC_KEY, b"gm").unwrap();
+
+    assert!(matches!(
+      keychain.decrypt(&address, &data.to_hex()),
+      Err(KeychainError::EncryptionError(
+        EncryptionError::DecryptionFailed
+      ))
+    ));
+  }
+}
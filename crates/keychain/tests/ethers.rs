@@ -0,0 +1,63 @@
+#![cfg(feature = "ethers")]
+
+use hdkey::{hdkey_factory, HDKey};
+use identity::{
+  signer::ethers::{Address, EthersSigner, LocalWallet},
+  IdentityFactoryRegistry, MultiKeyPairDyn,
+};
+use simple::SimpleKey;
+use walleth_keychain::{DynKeychain, Keychain, KeychainError};
+
+const PRIVATE_KEY: [u8; 32] = [1u8; 32];
+
+mod local_wallet {
+  use super::*;
+
+  #[test]
+  fn it_exports_the_account_as_a_wallet() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    let wallet = keychain.local_wallet(&account.address).unwrap();
+
+    assert_eq!(wallet.address(), Address::try_from(&account).unwrap());
+  }
+
+  #[test]
+  fn it_fails_while_locked() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+
+    assert!(matches!(
+      keychain.local_wallet(&account.address),
+      Err(KeychainError::Locked(_))
+    ));
+  }
+}
+
+mod import_local_wallet {
+  use super::*;
+
+  #[test]
+  fn it_imports_the_wallet_as_a_vault() {
+    let mut registry = IdentityFactoryRegistry::<Box<dyn MultiKeyPairDyn>>::new();
+    registry
+      .register("HDKey", |bytes| Ok(Box::new(HDKey::from(bytes))))
+      .register("SimpleKey", |bytes| {
+        Ok(Box::new(SimpleKey::try_from(bytes)?))
+      });
+    let mut keychain = DynKeychain::with_registry(registry);
+    let wallet = LocalWallet::from_bytes(&PRIVATE_KEY).unwrap();
+
+    let account = keychain.import_local_wallet(&wallet).unwrap();
+
+    assert_eq!(Address::try_from(&account).unwrap(), wallet.address());
+    assert_eq!(
+      keychain.local_wallet(&account.address).unwrap().address(),
+      wallet.address()
+    );
+  }
+}
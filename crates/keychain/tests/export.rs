@@ -0,0 +1,115 @@
+use serde_json::Value;
+use utils::Controller;
+use walleth_keychain::{ExportFormat, Keychain, KeychainError};
+
+mod common;
+use common::keychain_with_accounts;
+
+const PASSWORD: &str = "password";
+
+mod set_account_label {
+  use super::*;
+
+  #[test]
+  fn it_sets_and_removes_labels() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+
+    keychain
+      .set_account_label(&addresses[0], Some("savings"))
+      .unwrap();
+    keychain
+      .set_account_label(&addresses[1], Some("fees"))
+      .unwrap();
+    keychain.set_account_label(&addresses[1], None).unwrap();
+
+    let accounts = keychain.get_state().exported_accounts();
+    assert_eq!(accounts[0].label, Some("savings".to_string()));
+    assert_eq!(accounts[1].label, None);
+  }
+
+  #[test]
+  fn it_persists_labels_in_backups() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .set_account_label(&addresses[0], Some("savings"))
+      .unwrap();
+
+    let backup = keychain.backup(PASSWORD).unwrap();
+    let restored: Keychain = Keychain::restore(backup, PASSWORD).unwrap();
+
+    assert_eq!(restored.get_state().labels, keychain.get_state().labels);
+  }
+
+  #[test]
+  fn it_fails_with_unknown_address() {
+    let (mut keychain, _) = keychain_with_accounts(2);
+
+    let result = keychain.set_account_label("0x00", Some("savings"));
+
+    assert!(matches!(
+      result,
+      Err(KeychainError::KeyNotFoundForAddress(_))
+    ));
+  }
+}
+
+mod export_accounts {
+  use super::*;
+
+  #[test]
+  fn it_exports_accounts_as_json() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .set_account_label(&addresses[0], Some("savings"))
+      .unwrap();
+    keychain
+      .set_account_snapshot(&addresses[0], Some(u128::MAX), Some(1))
+      .unwrap();
+
+    let json: Value = serde_json::from_str(&keychain.export_accounts(ExportFormat::Json)).unwrap();
+
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert_eq!(json[0]["address"], addresses[0].as_str());
+    assert_eq!(json[0]["label"], "savings");
+    assert_eq!(json[0]["path"], "m/44'/60'/0'/0/0");
+    assert_eq!(json[0]["balance"], u128::MAX.to_string());
+    assert_eq!(json[1]["label"], Value::Null);
+    assert_eq!(json[1]["balance"], Value::Null);
+  }
+
+  #[test]
+  fn it_exports_accounts_as_csv() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .set_account_label(&addresses[1], Some("trading, \"hot\""))
+      .unwrap();
+    keychain
+      .set_account_snapshot(&addresses[1], Some(42), None)
+      .unwrap();
+
+    let csv = keychain.export_accounts(ExportFormat::Csv);
+
+    assert_eq!(
+      csv.lines().collect::<Vec<&str>>(),
+      vec![
+        "vault,address,label,path,balance".to_string(),
+        format!("0,{},,m/44'/60'/0'/0/0,", addresses[0]),
+        format!(
+          "0,{},\"trading, \"\"hot\"\"\",m/44'/60'/0'/0/1,42",
+          addresses[1]
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn it_exports_accounts_while_locked() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+
+    keychain.lock(PASSWORD).unwrap();
+
+    let json: Value = serde_json::from_str(&keychain.export_accounts(ExportFormat::Json)).unwrap();
+    assert_eq!(json[1]["address"], addresses[1].as_str());
+    assert!(keychain.get_state().is_locked());
+  }
+}
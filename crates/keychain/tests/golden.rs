@@ -0,0 +1,182 @@
+use hdkey::{
+  test_vectors::{TestVector, ABANDON_MNEMONIC, SIGNED_MESSAGE, TEST_VECTORS},
+  HDKey,
+};
+use identity::{
+  account::ChecksumAddressFormatter,
+  signer::{Signable, Signature, SignatureOptions, Signer},
+  verify_address, Account, MultiKeyPair,
+};
+use utils::hex::{decode, encode, remove0x};
+use walleth_core::{NonceStrategy, SigningBackend};
+use walleth_keychain::Keychain;
+
+fn derive(vector: &TestVector) -> ([u8; 32], Account) {
+  let private_key = vector
+    .hdkey()
+    .unwrap()
+    .private_key_at(vector.path())
+    .unwrap();
+
+  (
+    private_key,
+    Account::from_private_key(private_key, vector.path()).unwrap(),
+  )
+}
+
+mod derivation {
+  use super::*;
+
+  #[test]
+  fn it_derives_the_expected_private_keys() {
+    for vector in TEST_VECTORS {
+      let (private_key, _) = derive(vector);
+
+      assert_eq!(encode(&private_key), vector.private_key, "{:?}", vector);
+    }
+  }
+
+  #[test]
+  fn it_derives_the_expected_public_keys() {
+    for vector in TEST_VECTORS {
+      let (_, account) = derive(vector);
+
+      assert_eq!(
+        encode(&account.public_key),
+        vector.public_key,
+        "{:?}",
+        vector
+      );
+    }
+  }
+
+  #[test]
+  fn it_derives_the_expected_addresses() {
+    for vector in TEST_VECTORS {
+      let (_, account) = derive(vector);
+
+      assert_eq!(
+        account.address,
+        vector.address.to_lowercase(),
+        "{:?}",
+        vector
+      );
+      assert_eq!(
+        account.format_address(&ChecksumAddressFormatter).unwrap(),
+        vector.address
+      );
+    }
+  }
+
+  #[test]
+  fn it_derives_the_same_seed_from_the_mnemonic() {
+    for vector in TEST_VECTORS {
+      assert_eq!(
+        HDKey::from_mnemonic_str(vector.mnemonic).unwrap(),
+        vector.hdkey().unwrap(),
+        "{:?}",
+        vector
+      );
+    }
+  }
+
+  #[test]
+  fn it_derives_the_expected_accounts_in_a_keychain() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey::hdkey_factory, Some(ABANDON_MNEMONIC.to_string()))
+      .unwrap();
+
+    let expected = TEST_VECTORS
+      .iter()
+      .filter(|vector| vector.mnemonic == ABANDON_MNEMONIC);
+    for vector in expected {
+      let account = keychain.add_account_at(0, vector.index).unwrap();
+
+      assert_eq!(account.address, vector.address.to_lowercase());
+    }
+  }
+}
+
+mod signing {
+  use super::*;
+
+  #[test]
+  fn it_produces_the_expected_signatures() {
+    let options = SignatureOptions {
+      recoverable: true,
+      ..Default::default()
+    };
+
+    for vector in TEST_VECTORS {
+      let (private_key, _) = derive(vector);
+      let signature = Signer::new(private_key)
+        .unwrap()
+        .sign_with_options(&Signable::from_bytes(SIGNED_MESSAGE), &options);
+
+      assert_eq!(
+        signature.to_rsv_hex().unwrap(),
+        vector.signature,
+        "{:?}",
+        vector
+      );
+    }
+  }
+
+  #[test]
+  fn it_recovers_the_expected_addresses() {
+    for vector in TEST_VECTORS {
+      let signature = Signature::from_rsv_hex(vector.signature).unwrap();
+
+      assert!(verify_address(vector.address, SIGNED_MESSAGE, &signature).is_ok());
+    }
+  }
+}
+
+mod backends {
+  use super::*;
+
+  /// Check the vectors against a signing backend, independently
+  /// from the backend selected by the crate features
+  fn check<B: SigningBackend>() {
+    for vector in TEST_VECTORS {
+      let (private_key, _) = derive(vector);
+      let signer = B::from_private_key(&private_key).unwrap();
+      let (signature, recovery_id) = signer.sign_compact(
+        &Signable::from_bytes(SIGNED_MESSAGE).to_signable_message(),
+        &NonceStrategy::Deterministic,
+      );
+
+      assert_eq!(
+        encode(&signer.public_key_bytes()),
+        vector.public_key,
+        "{:?}",
+        vector
+      );
+      assert_eq!(
+        encode(&signer.address_bytes()),
+        remove0x(&vector.address.to_lowercase()),
+        "{:?}",
+        vector
+      );
+      assert_eq!(
+        [&signature[..], &[recovery_id + 27]].concat(),
+        decode(remove0x(vector.signature)).unwrap(),
+        "{:?}",
+        vector
+      );
+    }
+  }
+
+  #[cfg(feature = "secp256k1")]
+  #[test]
+  fn it_matches_the_vectors_with_secp256k1() {
+    check::<walleth_core::Signer>();
+  }
+
+  #[cfg(feature = "k256")]
+  #[test]
+  fn it_matches_the_vectors_with_k256() {
+    check::<walleth_core::K256Signer>();
+  }
+}
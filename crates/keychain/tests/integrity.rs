@@ -0,0 +1,80 @@
+use hdkey::{hdkey_factory, HDKey};
+use vault::{KdfParams, Vault};
+use walleth_keychain::{KeyPair, Keychain, VaultIntegrity};
+
+const PASSWORD: &str = "password";
+
+fn locked_keychain() -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.set_kdf_params(KdfParams::new(10));
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.add_account(0).unwrap();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.lock(PASSWORD).unwrap();
+
+  keychain
+}
+
+mod verify_integrity {
+  use super::*;
+
+  #[test]
+  fn it_validates_locked_vaults_without_unlocking_them() {
+    let keychain = locked_keychain();
+
+    let report = keychain.verify_integrity(PASSWORD);
+
+    assert!(report.is_valid());
+    assert_eq!(report.vaults.len(), 2);
+    assert!(keychain.is_locked());
+  }
+
+  #[test]
+  fn it_validates_unlocked_vaults() {
+    let mut keychain = locked_keychain();
+    keychain.unlock(PASSWORD).unwrap();
+
+    assert!(keychain.verify_integrity(PASSWORD).is_valid());
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_reports_vaults_not_decrypting() {
+    let keychain = locked_keychain();
+
+    let report = keychain.verify_integrity("wrong password");
+
+    assert!(!report.is_valid());
+    assert!(report
+      .vaults
+      .iter()
+      .all(|(_, integrity)| *integrity == VaultIntegrity::Undecryptable));
+  }
+
+  #[test]
+  fn it_reports_tampered_accounts() {
+    let keychain = locked_keychain();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(0).unwrap();
+    let address = vault.accounts().unwrap()[0].address.clone();
+    let mut bytes = vault.to_bytes().unwrap();
+    let offset = bytes
+      .windows(address.len())
+      .position(|window| window == address.as_bytes())
+      .unwrap();
+    bytes[offset + 2] = if bytes[offset + 2] == b'a' {
+      b'b'
+    } else {
+      b'a'
+    };
+    let mut tampered: Keychain = Keychain::new();
+    tampered
+      .add_key_pair(KeyPair::MultiKeyPair(
+        Vault::<HDKey>::try_from(bytes).unwrap(),
+      ))
+      .unwrap();
+
+    let report = tampered.verify_integrity(PASSWORD);
+
+    assert!(matches!(report.vaults[0].1, VaultIntegrity::Mismatch(_)));
+  }
+}
@@ -0,0 +1,92 @@
+use hdkey::{hdkey_factory, mnemonic_from_entropy, HDKey};
+use proptest::prelude::*;
+use utils::Controller;
+use walleth_keychain::{KeyPair, Keychain};
+
+/// Key derivation and encryption are slow by design,
+/// so fewer cases than the default are generated
+fn config() -> ProptestConfig {
+  ProptestConfig::with_cases(12)
+}
+
+fn password() -> impl Strategy<Value = String> {
+  "[ -~]{1,32}"
+}
+
+/// Create a keychain with one vault per mnemonic and `accounts` accounts each
+fn keychain(entropies: &[[u8; 32]], accounts: usize) -> Keychain {
+  let mut keychain = Keychain::new();
+  for (index, entropy) in entropies.iter().enumerate() {
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(mnemonic_from_entropy(*entropy)))
+      .unwrap();
+    keychain.derive_range(index, 0, accounts).unwrap();
+  }
+
+  keychain
+}
+
+fn identities(keychain: &Keychain) -> Vec<(HDKey, [u8; 4])> {
+  let mut identities = vec![];
+  let mut index = 0;
+  while let Some(KeyPair::MultiKeyPair(vault)) = keychain.get_keypair(index) {
+    identities.push((vault.get_identity().unwrap().clone(), vault.fingerprint()));
+    index += 1;
+  }
+
+  identities
+}
+
+proptest! {
+  #![proptest_config(config())]
+
+  #[test]
+  fn lock_then_unlock_restores_the_same_identities(
+    entropies in prop::collection::vec(any::<[u8; 32]>(), 1..3),
+    accounts in 0usize..5,
+    password in password(),
+  ) {
+    let mut keychain = keychain(&entropies, accounts);
+    let expected = identities(&keychain);
+    let state = keychain.get_state().clone();
+
+    keychain.lock(&password).unwrap();
+    prop_assert_eq!(keychain.get_state().accounts(), state.accounts());
+    keychain.unlock(&password).unwrap();
+
+    prop_assert_eq!(identities(&keychain), expected);
+    prop_assert_eq!(keychain.get_state(), &state);
+  }
+
+  #[test]
+  fn backup_then_restore_recreates_the_keychain(
+    entropies in prop::collection::vec(any::<[u8; 32]>(), 1..3),
+    accounts in 0usize..5,
+    password in password(),
+  ) {
+    let mut keychain = keychain(&entropies, accounts);
+    let backup = keychain.backup(&password).unwrap();
+
+    let restored: Keychain = Keychain::restore(backup, &password).unwrap();
+
+    prop_assert_eq!(identities(&restored), identities(&keychain));
+    prop_assert_eq!(restored.get_state().accounts(), keychain.get_state().accounts());
+  }
+
+  #[test]
+  fn a_wrong_password_is_rejected(
+    entropy in any::<[u8; 32]>(),
+    password in password(),
+    wrong_password in password(),
+  ) {
+    prop_assume!(password != wrong_password);
+    let mut keychain = keychain(&[entropy], 1);
+    let backup = keychain.backup(&password).unwrap();
+
+    prop_assert!(Keychain::<HDKey>::restore(backup, &wrong_password).is_err());
+
+    keychain.lock(&password).unwrap();
+    prop_assert!(keychain.unlock(&wrong_password).is_err());
+    prop_assert!(keychain.unlock(&password).is_ok());
+  }
+}
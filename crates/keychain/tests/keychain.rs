@@ -1,4 +1,5 @@
-use utils::Controller;
+use identity::signer::SignatureOptions;
+use utils::{Controller, PublicKeyBytes, B256};
 use walleth_keychain::Keychain;
 
 const MNEMONIC: &str =
@@ -9,8 +10,8 @@ mod new {
 
   #[test]
   fn it_creates_a_new_keychain() {
-    let keychain = Keychain::new();
-    assert_eq!(keychain.get_state().accounts.len(), 0);
+    let keychain: Keychain = Keychain::new();
+    assert_eq!(keychain.get_state().vaults.len(), 0);
   }
 }
 
@@ -64,17 +65,1038 @@ mod recover {
 
     assert_eq!(recovered, keychain);
   }
+
+  #[test]
+  fn it_recovers_a_keychain_with_many_accounts() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.derive_range(0, 0, 20).unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let recovered: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    assert_eq!(recovered.get_state(), keychain.get_state());
+  }
+}
+
+mod restore_with_registry {
+  use hdkey::{hdkey_factory, HDKey};
+  use identity::IdentityFactoryRegistry;
+
+  use super::*;
+
+  #[test]
+  fn it_restores_identities_with_the_registered_deserializer() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let backup = keychain.backup("password").unwrap();
+    let mut registry = IdentityFactoryRegistry::new();
+    registry.register("HDKey", |bytes| Ok(HDKey::from(bytes)));
+
+    let recovered = Keychain::restore_with_registry(backup, "password", registry).unwrap();
+
+    assert_eq!(recovered, keychain);
+  }
+
+  #[test]
+  fn it_fails_when_the_identity_type_is_not_registered() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let recovered =
+      Keychain::<HDKey>::restore_with_registry(backup, "password", IdentityFactoryRegistry::new());
+
+    assert!(recovered.is_err());
+  }
+
+  #[test]
+  fn it_fails_on_truncated_backups() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    for length in [10, backup.len() / 2, backup.len() - 1] {
+      let mut registry = IdentityFactoryRegistry::new();
+      registry.register("HDKey", |bytes| Ok(HDKey::from(bytes)));
+
+      let recovered =
+        Keychain::restore_with_registry(backup[..length].to_vec(), "password", registry);
+
+      assert!(recovered.is_err());
+    }
+  }
+}
+
+mod add_backup_sink {
+  use std::sync::{Arc, Mutex};
+
+  use hdkey::hdkey_factory;
+
+  use super::*;
+
+  fn recording_sink(
+    backups: &Arc<Mutex<Vec<Vec<u8>>>>,
+  ) -> impl FnMut(&[u8]) -> Result<(), walleth_keychain::KeychainError> {
+    let backups = backups.clone();
+    move |backup: &[u8]| {
+      backups.lock().unwrap().push(backup.to_vec());
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn it_writes_the_backup_to_sinks() {
+    let backups = Arc::new(Mutex::new(vec![]));
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_backup_sink(recording_sink(&backups));
+
+    let backup = keychain.backup("password").unwrap();
+
+    assert_eq!(*backups.lock().unwrap(), vec![backup]);
+  }
+
+  #[test]
+  fn it_writes_a_restorable_backup_to_sinks_on_lock() {
+    let backups = Arc::new(Mutex::new(vec![]));
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_account(0).unwrap();
+    keychain.add_backup_sink(recording_sink(&backups));
+
+    keychain.lock("password").unwrap();
+
+    assert_eq!(backups.lock().unwrap().len(), 1);
+    let recovered: Keychain =
+      Keychain::restore(backups.lock().unwrap()[0].clone(), "password").unwrap();
+    assert_eq!(
+      recovered.get_state().accounts(),
+      keychain.get_state().accounts()
+    );
+  }
+
+  #[test]
+  fn it_reports_sink_errors() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_backup_sink(|_: &[u8]| {
+      Err(walleth_keychain::KeychainError::BackupSinkError(
+        "unreachable".to_string(),
+      ))
+    });
+
+    assert!(keychain.backup("password").is_err());
+  }
+}
+
+mod add_account {
+  use hdkey::hdkey_factory;
+
+  use super::*;
+
+  #[test]
+  fn it_adds_accounts_to_the_state() {
+    let mut keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+
+    let first = keychain.add_account(0).unwrap();
+    let second = keychain.add_account(0).unwrap();
+
+    assert_eq!(first.path.to_string(), "m/44'/60'/0'/0/0");
+    assert_eq!(second.path.to_string(), "m/44'/60'/0'/0/1");
+    assert_eq!(keychain.get_state().vaults[0].accounts, vec![first, second]);
+  }
+
+  #[test]
+  fn it_fails_with_unknown_keypair_index() {
+    let mut keychain: Keychain = Keychain::new();
+
+    assert!(keychain.add_account(0).is_err());
+  }
+
+  #[test]
+  fn it_recreates_accounts_after_unlock() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    assert_eq!(keychain.get_state().accounts(), vec![&account]);
+  }
+
+  #[test]
+  fn it_reports_accounts_while_locked() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let first = keychain.add_account(0).unwrap();
+    let second = keychain.add_account_at(0, 4).unwrap();
+
+    keychain.lock("password").unwrap();
+
+    assert_eq!(keychain.get_state().accounts(), vec![&first, &second]);
+  }
+
+  #[test]
+  fn it_reports_accounts_of_a_locked_backup() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let recovered: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    assert_eq!(recovered.get_state().accounts(), vec![&account]);
+  }
+}
+
+mod add_account_at {
+  use hdkey::hdkey_factory;
+
+  use super::*;
+
+  #[test]
+  fn it_adds_an_account_at_an_arbitrary_index() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let account = keychain.add_account_at(0, 5).unwrap();
+
+    assert_eq!(account.path.to_string(), "m/44'/60'/0'/0/5");
+    assert_eq!(keychain.get_state().accounts(), vec![&account]);
+  }
+
+  #[test]
+  fn it_keeps_accounts_sorted_by_index() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let fifth = keychain.add_account_at(0, 5).unwrap();
+    let second = keychain.add_account_at(0, 2).unwrap();
+    let sixth = keychain.add_account(0).unwrap();
+
+    assert_eq!(sixth.path.index, 6);
+    assert_eq!(
+      keychain.get_state().vaults[0].accounts,
+      vec![second, fifth, sixth]
+    );
+  }
+
+  #[test]
+  fn it_does_not_duplicate_accounts() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    keychain.add_account_at(0, 3).unwrap();
+    keychain.add_account_at(0, 3).unwrap();
+
+    assert_eq!(keychain.get_state().accounts().len(), 1);
+  }
+
+  #[test]
+  fn it_persists_sparse_indexes_in_the_backup() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_account_at(0, 1).unwrap();
+    keychain.add_account_at(0, 7).unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let recovered: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    assert_eq!(recovered.get_state(), keychain.get_state());
+  }
+}
+
+mod derive_range {
+  use hdkey::hdkey_factory;
+  use vault::{VaultError, HARDENED_INDEX_OFFSET};
+  use walleth_keychain::KeychainError;
+
+  use super::*;
+
+  #[test]
+  fn it_derives_consecutive_accounts() {
+    let mut keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+
+    let accounts = keychain.derive_range(0, 3, 4).unwrap();
+
+    assert_eq!(
+      accounts
+        .iter()
+        .map(|account| account.path.index)
+        .collect::<Vec<usize>>(),
+      vec![3, 4, 5, 6]
+    );
+    assert_eq!(keychain.get_state().vaults[0].accounts, accounts);
+  }
+
+  #[test]
+  fn it_derives_the_same_accounts_as_single_derivations() {
+    let mut keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+    let mut other = Keychain::new();
+    other
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+
+    let accounts = keychain.derive_range(0, 0, 3).unwrap();
+
+    assert_eq!(
+      accounts,
+      vec![
+        other.add_account(0).unwrap(),
+        other.add_account(0).unwrap(),
+        other.add_account(0).unwrap(),
+      ]
+    );
+  }
+
+  #[test]
+  fn it_rejects_ranges_past_the_non_hardened_indexes() {
+    let mut keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+
+    for (start, count) in [
+      (HARDENED_INDEX_OFFSET - 1, 2),
+      (HARDENED_INDEX_OFFSET, 1),
+      (usize::MAX, 2),
+    ] {
+      assert!(matches!(
+        keychain.derive_range(0, start, count),
+        Err(KeychainError::VaultError(VaultError::IndexRangeOutOfBounds(s, c)))
+          if (s, c) == (start, count)
+      ));
+    }
+    assert!(keychain.get_state().vaults[0].accounts.is_empty());
+  }
+
+  #[test]
+  fn it_derives_up_to_the_last_non_hardened_index() {
+    let mut keychain = Keychain::new();
+    keychain
+      .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+
+    let accounts = keychain
+      .derive_range(0, HARDENED_INDEX_OFFSET - 1, 1)
+      .unwrap();
+
+    assert_eq!(accounts[0].path.index, HARDENED_INDEX_OFFSET - 1);
+  }
+}
+
+mod use_signer {
+  use hdkey::hdkey_factory;
+  use identity::{signer::Signable, verify_address, IntoSignable};
+
+  use super::*;
+
+  #[test]
+  fn it_signs_with_an_account() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    let signature = keychain.use_signer(
+      account.address,
+      b"Hello world!",
+      &SignatureOptions::default(),
+    );
+
+    assert!(signature.is_ok());
+  }
+
+  #[test]
+  fn it_signs_a_type_with_a_custom_digest() {
+    struct Order(u8);
+
+    impl IntoSignable for Order {
+      fn to_signable(&self) -> Signable {
+        Signable::from_digest(B256([self.0; 32]))
+      }
+    }
+
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    let signature = keychain
+      .use_signer(
+        account.address.clone(),
+        &Order(1),
+        &SignatureOptions {
+          recoverable: true,
+          ..Default::default()
+        },
+      )
+      .unwrap();
+
+    assert!(verify_address(&account.address, &Order(1), &signature).is_ok());
+    assert!(verify_address(&account.address, &Order(2), &signature).is_err());
+  }
+
+  #[test]
+  fn it_fails_with_unknown_address() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let signature = keychain.use_signer(
+      "0x0".to_string(),
+      b"Hello world!",
+      &SignatureOptions::default(),
+    );
+
+    assert!(signature.is_err());
+  }
+}
+
+mod use_signer_while_locked {
+  use hdkey::hdkey_factory;
+  use walleth_keychain::KeychainError;
+
+  use super::*;
+
+  #[test]
+  fn it_does_not_sign_with_a_locked_account() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+
+    let signature = keychain.use_signer(account.address, b"message", &SignatureOptions::default());
+
+    assert!(signature.is_err());
+  }
+
+  #[test]
+  fn it_fails_with_the_locked_address() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+
+    let signature = keychain.use_signer(
+      account.address.clone(),
+      b"message",
+      &SignatureOptions::default(),
+    );
+
+    assert!(matches!(signature, Err(KeychainError::Locked(address)) if address == account.address));
+  }
+
+  #[test]
+  fn it_still_fails_with_key_not_found_for_unknown_addresses() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock("password").unwrap();
+
+    let signature =
+      keychain.use_signer("0x0".to_string(), b"message", &SignatureOptions::default());
+
+    assert!(matches!(
+      signature,
+      Err(KeychainError::KeyNotFoundForAddress(_))
+    ));
+  }
+
+  #[test]
+  fn it_does_not_start_a_signing_pool_while_locked() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+
+    let pool = keychain.start_signing_pool(&[account.address]);
+
+    assert!(matches!(pool, Err(KeychainError::Locked(_))));
+  }
 }
 
 mod get_state {
+  use hdkey::hdkey_factory;
+  use walleth_keychain::KeyPair;
+
   use super::*;
 
   #[test]
   fn it_gets_the_keychain_state() {
-    let keychain = Keychain::new();
+    let keychain: Keychain = Keychain::new();
+
+    let state = keychain.get_state();
+
+    assert_eq!(state.accounts().len(), 0);
+  }
+
+  #[test]
+  fn it_groups_accounts_by_vault() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let first = keychain.add_account(0).unwrap();
+    let second = keychain.add_account(1).unwrap();
+
+    let state = keychain.get_state();
+
+    assert_eq!(state.vaults.len(), 2);
+    assert_eq!(state.vaults[0].accounts, vec![first]);
+    assert_eq!(state.vaults[1].accounts, vec![second]);
+    assert_ne!(state.vaults[0].fingerprint, state.vaults[1].fingerprint);
+  }
+
+  #[test]
+  fn it_reports_lock_status_per_vault() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let fingerprint = keychain.get_state().vaults[0].fingerprint;
+    assert!(!keychain.get_state().vaults[0].locked);
+
+    keychain.lock("password").unwrap();
+
+    assert!(keychain.get_state().vaults[0].locked);
+    assert_eq!(keychain.get_state().vaults[0].fingerprint, fingerprint);
+  }
+
+  #[test]
+  fn it_identifies_vaults_by_fingerprint() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock("password").unwrap();
+    let fingerprint = keychain.get_state().vaults[0].fingerprint_hex();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(0).unwrap();
+
+    assert_eq!(fingerprint.len(), 10);
+    assert_eq!(vault.to_string(), format!("Vault {}", fingerprint));
+    assert_eq!(
+      format!("{:?}", vault),
+      format!(
+        "Vault {{ fingerprint: {}, identity_type: \"HDKey\", locked: true, accounts: 0 }}",
+        fingerprint
+      )
+    );
+  }
+}
+
+mod vault_status {
+  use hdkey::hdkey_factory;
+  use walleth_keychain::{KeychainError, VaultStatus};
+
+  use super::*;
+
+  #[test]
+  fn it_counts_the_vaults() {
+    let mut keychain = Keychain::new();
+    assert_eq!(keychain.vault_count(), 0);
+
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    assert_eq!(keychain.vault_count(), 2);
+    assert_eq!(keychain.get_state().vault_count(), 2);
+  }
+
+  #[test]
+  fn it_reports_the_status_of_each_vault() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    assert_eq!(keychain.vault_status(0).unwrap(), VaultStatus::Unlocked);
+    assert!(!keychain.is_locked());
+
+    keychain.lock("password").unwrap();
+
+    assert_eq!(keychain.vault_status(0).unwrap(), VaultStatus::Locked);
+    assert_eq!(
+      keychain.get_state().vault_status(0),
+      Some(VaultStatus::Locked)
+    );
+    assert!(keychain.is_locked());
+    assert!(keychain.get_state().is_locked());
+  }
+
+  #[test]
+  fn it_is_not_locked_with_a_vault_unlocked() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock("password").unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    assert!(!keychain.is_locked());
+    assert!(!keychain.get_state().is_locked());
+  }
+
+  #[test]
+  fn it_is_not_locked_without_vaults() {
+    let keychain: Keychain = Keychain::new();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_fails_for_a_missing_vault() {
+    let keychain: Keychain = Keychain::new();
+
+    assert!(matches!(
+      keychain.vault_status(0),
+      Err(KeychainError::KeyNotFoundForIndex(0))
+    ));
+    assert_eq!(keychain.get_state().vault_status(0), None);
+  }
+}
+
+mod unlock {
+  use hdkey::hdkey_factory;
+  use vault::VaultError;
+  use walleth_keychain::{KeychainError, VaultStatus};
+
+  use super::*;
+
+  fn keychain_with_passwords(passwords: &[&str]) -> Keychain {
+    let mut keychain = Keychain::new();
+    for password in passwords {
+      keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+      keychain.lock(password).unwrap();
+    }
+
+    keychain
+  }
+
+  #[test]
+  fn it_unlocks_all_the_vaults() {
+    let mut keychain = keychain_with_passwords(&["password", "password"]);
+
+    keychain.unlock("password").unwrap();
+
+    assert!(keychain
+      .get_state()
+      .vaults
+      .iter()
+      .all(|vault| !vault.locked));
+  }
+
+  #[test]
+  fn it_leaves_all_the_vaults_locked_on_a_failure() {
+    let mut keychain =
+      keychain_with_passwords(&["password", "password", "other", "password", "password"]);
+
+    let result = keychain.unlock("password");
+
+    match result {
+      Err(KeychainError::VaultsUnchanged(errors)) => {
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], (2, VaultError::SafeDecrypt)));
+      }
+      _ => panic!("Expected the vaults to be left unchanged"),
+    }
+    for index in 0..5 {
+      assert_eq!(keychain.vault_status(index).unwrap(), VaultStatus::Locked);
+    }
+    assert!(keychain.get_state().is_locked());
+  }
+
+  #[test]
+  fn it_reports_every_failing_vault() {
+    let mut keychain = keychain_with_passwords(&["password", "other", "other"]);
+
+    let result = keychain.unlock("password");
+
+    match result {
+      Err(KeychainError::VaultsUnchanged(errors)) => assert_eq!(
+        errors.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        vec![1, 2]
+      ),
+      _ => panic!("Expected the vaults to be left unchanged"),
+    }
+    assert_eq!(keychain.vault_status(0).unwrap(), VaultStatus::Locked);
+  }
+}
+
+mod vault_secrets {
+  use hdkey::hdkey_factory;
+  use walleth_keychain::KeyPair;
+
+  use super::*;
+
+  fn keychain_with_secret() -> Keychain {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair_mut(0).unwrap();
+    vault.put_secret("label", b"savings").unwrap();
+
+    keychain
+  }
+
+  #[test]
+  fn it_stores_secrets_in_the_vault() {
+    let keychain = keychain_with_secret();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(0).unwrap();
+
+    assert_eq!(vault.get_secret("label").unwrap(), Some(&b"savings"[..]));
+    assert_eq!(vault.get_secret("other").unwrap(), None);
+    assert_eq!(vault.secret_keys().unwrap(), vec!["label"]);
+  }
+
+  #[test]
+  fn it_keeps_secrets_across_lock_and_unlock() {
+    let mut keychain = keychain_with_secret();
+
+    keychain.lock("password").unwrap();
+    {
+      let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(0).unwrap();
+      assert!(vault.get_secret("label").is_err());
+    }
+    keychain.unlock("password").unwrap();
+
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(0).unwrap();
+    assert_eq!(vault.get_secret("label").unwrap(), Some(&b"savings"[..]));
+  }
+
+  #[test]
+  fn it_includes_secrets_in_backups() {
+    let mut keychain = keychain_with_secret();
+    let backup = keychain.backup("password").unwrap();
+
+    let restored: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    let KeyPair::MultiKeyPair(vault) = restored.get_keypair(0).unwrap();
+    assert_eq!(vault.get_secret("label").unwrap(), Some(&b"savings"[..]));
+  }
+
+  #[test]
+  fn it_removes_secrets() {
+    let mut keychain = keychain_with_secret();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair_mut(0).unwrap();
+
+    assert!(vault.remove_secret("label").unwrap());
+    assert!(!vault.remove_secret("label").unwrap());
+    assert_eq!(vault.get_secret("label").unwrap(), None);
+  }
+
+  #[test]
+  fn it_rejects_oversized_secrets() {
+    let mut keychain = keychain_with_secret();
+    let KeyPair::MultiKeyPair(vault) = keychain.get_keypair_mut(0).unwrap();
+
+    assert!(vault.put_secret("", b"value").is_err());
+    assert!(vault.put_secret("blob", &vec![0u8; 70_000]).is_err());
+  }
+}
+
+mod set_kdf_params {
+  use hdkey::hdkey_factory;
+  use vault::KdfParams;
+  use walleth_keychain::KeyPair;
+
+  use super::*;
+
+  #[test]
+  fn it_applies_to_existing_and_new_vaults() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    keychain.set_kdf_params(KdfParams::new(10));
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    for index in 0..2 {
+      let KeyPair::MultiKeyPair(vault) = keychain.get_keypair(index).unwrap();
+      assert_eq!(vault.kdf_params(), KdfParams::new(10));
+    }
+  }
+
+  #[test]
+  fn it_locks_and_unlocks_with_custom_rounds() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain.set_kdf_params(KdfParams::new(10));
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_account(0).unwrap();
+
+    keychain.lock("password").unwrap();
+
+    assert!(keychain.unlock("wrong password").is_err());
+    assert!(keychain.unlock("password").is_ok());
+  }
+
+  #[test]
+  fn it_restores_the_rounds_from_backups() {
+    let mut keychain: Keychain = Keychain::new();
+    keychain.set_kdf_params(KdfParams::new(2000));
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let restored: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    let KeyPair::MultiKeyPair(vault) = restored.get_keypair(0).unwrap();
+    assert_eq!(vault.kdf_params(), KdfParams::new(2000));
+  }
+}
+
+mod set_account_snapshot {
+  use hdkey::hdkey_factory;
+
+  use super::*;
+
+  #[test]
+  fn it_stores_the_snapshot_in_the_state() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    keychain
+      .set_account_snapshot(&account.address, Some(1_000), Some(3))
+      .unwrap();
 
     let state = keychain.get_state();
+    let snapshot = state.snapshot(&account.address).unwrap();
+    assert_eq!(snapshot.balance, Some(1_000));
+    assert_eq!(snapshot.nonce, Some(3));
+    assert!(snapshot.last_updated.is_some());
+  }
+
+  #[test]
+  fn it_keeps_snapshots_across_lock() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain
+      .set_account_snapshot(&account.address, Some(1), None)
+      .unwrap();
+
+    keychain.lock("password").unwrap();
+
+    assert!(keychain.get_state().snapshot(&account.address).is_some());
+  }
+
+  #[test]
+  fn it_fails_for_unknown_accounts() {
+    let mut keychain: Keychain = Keychain::new();
+
+    assert!(keychain
+      .set_account_snapshot(
+        "0x0000000000000000000000000000000000000000",
+        Some(1),
+        Some(1)
+      )
+      .is_err());
+  }
+}
+
+mod reveal_private_key {
+  use std::time::Duration;
+
+  use hdkey::hdkey_factory;
+  use identity::{Account, DerivationPath};
+  use utils::ExposeAcknowledgement;
+  use walleth_keychain::{AuditEvent, KeychainError};
+
+  use super::*;
+
+  #[test]
+  fn it_reveals_the_private_key_of_the_account() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    let mut secret = keychain
+      .reveal_private_key(&account.address, Duration::from_secs(60))
+      .unwrap();
+    let private_key = secret
+      .expose(ExposeAcknowledgement::i_will_not_log_or_retain_this_secret())
+      .unwrap();
+
+    assert_eq!(
+      Account::from_private_key(private_key.try_into().unwrap(), DerivationPath::from(0))
+        .unwrap()
+        .address,
+      account.address
+    );
+  }
+
+  #[test]
+  fn it_records_the_reveal() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+
+    keychain
+      .reveal_private_key(&account.address, Duration::from_secs(60))
+      .unwrap();
+
+    let entry = keychain.audit_log().entries().last().unwrap();
+    assert_eq!(entry.event, AuditEvent::Reveal);
+    assert_eq!(entry.account, Some(account.address));
+  }
+
+  #[test]
+  fn it_fails_with_a_locked_vault() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    keychain.lock("password").unwrap();
+
+    assert!(matches!(
+      keychain.reveal_private_key(&account.address, Duration::from_secs(60)),
+      Err(KeychainError::Locked(_))
+    ));
+  }
+}
+
+mod dyn_keychain {
+  use hdkey::{hdkey_factory, HDKey, HDKeyError};
+  use identity::{
+    signer::{IntoSignable, Signature},
+    Account, DerivationPath, GenericIdentity, IdentityError, IdentityFactoryRegistry, MultiKeyPair,
+    MultiKeyPairDyn, Signer,
+  };
+  use walleth_keychain::DynKeychain;
+
+  use super::*;
+
+  /// An identity holding a single private key for every derivation path
+  #[derive(Debug)]
+  struct SingleKey([u8; 32]);
+
+  impl GenericIdentity for SingleKey {
+    fn identity_type(&self) -> String {
+      "SingleKey".to_string()
+    }
+
+    fn fingerprint(&self) -> [u8; 4] {
+      self.0[..4].try_into().unwrap()
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+      self.0.to_vec()
+    }
+
+    fn deserialize(&mut self, bytes: &[u8]) -> Result<(), Box<dyn IdentityError>> {
+      self.0 = bytes.try_into().or(Err(HDKeyError::InvalidPrivateKey))?;
+      Ok(())
+    }
+  }
+
+  impl MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> for SingleKey {
+    fn private_key_at(&self, _: DerivationPath) -> Result<[u8; 32], Box<dyn IdentityError>> {
+      Ok(self.0)
+    }
+
+    fn public_key_at(&self, _: DerivationPath) -> Result<PublicKeyBytes, Box<dyn IdentityError>> {
+      Err(HDKeyError::WrongDerivationPath.into())
+    }
+
+    fn sign(
+      &self,
+      _: &Account,
+      message: &dyn IntoSignable,
+      options: &SignatureOptions,
+    ) -> Result<Signature, Box<dyn IdentityError>> {
+      let signer = Signer::new(self.0).or(Err(HDKeyError::InvalidPrivateKey))?;
+
+      Ok(signer.sign_with_options(&message.to_signable(), options))
+    }
+
+    fn verify(
+      &self,
+      _: &Account,
+      message: &dyn IntoSignable,
+      signature: &Signature,
+    ) -> Result<(), Box<dyn IdentityError>> {
+      let signer = Signer::new(self.0).or(Err(HDKeyError::InvalidPrivateKey))?;
+
+      signer
+        .verify(&message.to_signable(), signature)
+        .or(Err(HDKeyError::InvalidSignature.into()))
+    }
+  }
+
+  fn single_key_factory(key: [u8; 32]) -> Result<SingleKey, Box<dyn IdentityError>> {
+    Ok(SingleKey(key))
+  }
+
+  fn registry() -> IdentityFactoryRegistry<Box<dyn MultiKeyPairDyn>> {
+    let mut registry = IdentityFactoryRegistry::<Box<dyn MultiKeyPairDyn>>::new();
+    registry
+      .register("HDKey", |bytes| Ok(Box::new(HDKey::from(bytes))))
+      .register("SingleKey", |bytes| {
+        let mut identity = SingleKey([0u8; 32]);
+        identity.deserialize(bytes)?;
+        Ok(Box::new(identity))
+      });
+
+    registry
+  }
+
+  #[test]
+  fn it_holds_identities_of_different_types() {
+    let mut keychain = DynKeychain::with_registry(registry());
+
+    let hdkey = keychain
+      .add_dyn_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap()
+      .identity_type();
+    let single_key = keychain
+      .add_dyn_keypair(single_key_factory, [1u8; 32])
+      .unwrap()
+      .identity_type();
+
+    assert_eq!(hdkey, "HDKey");
+    assert_eq!(single_key, "SingleKey");
+  }
+
+  #[test]
+  fn it_signs_with_identities_of_different_types_after_unlock() {
+    let mut keychain = DynKeychain::with_registry(registry());
+    keychain
+      .add_dyn_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+    keychain
+      .add_dyn_keypair(single_key_factory, [1u8; 32])
+      .unwrap();
+    let hdkey_account = keychain.add_account(0).unwrap();
+    let single_key_account = keychain.add_account(1).unwrap();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    assert!(keychain
+      .use_signer(
+        hdkey_account.address,
+        b"message",
+        &SignatureOptions::default()
+      )
+      .is_ok());
+    assert!(keychain
+      .use_signer(
+        single_key_account.address,
+        b"message",
+        &SignatureOptions::default()
+      )
+      .is_ok());
+  }
+
+  #[test]
+  fn it_restores_identities_of_different_types() {
+    let mut keychain = DynKeychain::with_registry(registry());
+    keychain
+      .add_dyn_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+      .unwrap();
+    keychain
+      .add_dyn_keypair(single_key_factory, [1u8; 32])
+      .unwrap();
+    let backup = keychain.backup("password").unwrap();
+
+    let recovered = DynKeychain::restore_with_registry(backup, "password", registry()).unwrap();
 
-    assert_eq!(state.accounts.len(), 0);
+    assert_eq!(recovered.get_state(), keychain.get_state());
   }
 }
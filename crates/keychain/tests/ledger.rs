@@ -0,0 +1,85 @@
+use std::{
+  sync::{Arc, Mutex},
+  thread::sleep,
+  time::Duration,
+};
+
+use hdkey::hdkey_factory;
+use identity::signer::SignatureOptions;
+use walleth_keychain::{
+  DuplicateAction, Keychain, KeychainError, PayloadLedger, PolicyEvent, PolicyViolation,
+};
+
+fn keychain_with_ledger(ledger: Option<PayloadLedger>) -> (Keychain, String) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let account = keychain.add_account(0).unwrap();
+  keychain.set_payload_ledger(ledger);
+
+  (keychain, account.address)
+}
+
+fn sign(keychain: &mut Keychain, address: &str, message: &[u8]) -> Result<(), KeychainError> {
+  keychain
+    .use_signer(address.to_string(), message, &SignatureOptions::default())
+    .map(|_| ())
+}
+
+mod payload_ledger {
+  use super::*;
+
+  #[test]
+  fn it_refuses_duplicate_payloads() {
+    let ledger = PayloadLedger::new(Duration::from_secs(60), DuplicateAction::Refuse);
+    let (mut keychain, address) = keychain_with_ledger(Some(ledger));
+
+    sign(&mut keychain, &address, b"payload").unwrap();
+
+    assert!(matches!(
+      sign(&mut keychain, &address, b"payload"),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DuplicatePayload(_)
+      ))
+    ));
+    assert!(sign(&mut keychain, &address, b"other payload").is_ok());
+  }
+
+  #[test]
+  fn it_warns_about_duplicate_payloads() {
+    let ledger = PayloadLedger::new(Duration::from_secs(60), DuplicateAction::Warn);
+    let (mut keychain, address) = keychain_with_ledger(Some(ledger));
+    let events = Arc::new(Mutex::new(vec![]));
+    let recorded = events.clone();
+    keychain
+      .policy_mut()
+      .subscribe(move |event| recorded.lock().unwrap().push(event.clone()));
+
+    sign(&mut keychain, &address, b"payload").unwrap();
+    sign(&mut keychain, &address, b"payload").unwrap();
+
+    assert!(matches!(
+      &events.lock().unwrap()[..],
+      [PolicyEvent::DuplicatePayload { refused: false, .. }]
+    ));
+  }
+
+  #[test]
+  fn it_forgets_payloads_after_the_window() {
+    let ledger = PayloadLedger::new(Duration::from_millis(1), DuplicateAction::Refuse);
+    let (mut keychain, address) = keychain_with_ledger(Some(ledger));
+
+    sign(&mut keychain, &address, b"payload").unwrap();
+    sleep(Duration::from_millis(10));
+
+    assert!(sign(&mut keychain, &address, b"payload").is_ok());
+  }
+
+  #[test]
+  fn it_allows_duplicates_without_ledger() {
+    let (mut keychain, address) = keychain_with_ledger(None);
+
+    sign(&mut keychain, &address, b"payload").unwrap();
+
+    assert!(sign(&mut keychain, &address, b"payload").is_ok());
+  }
+}
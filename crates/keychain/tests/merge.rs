@@ -0,0 +1,134 @@
+use hdkey::hdkey_factory;
+use identity::signer::SignatureOptions;
+use utils::Controller;
+use walleth_keychain::{Keychain, KeychainError, LabelConflict};
+
+const MNEMONIC: &str =
+  "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+const PASSWORD: &str = "password";
+
+fn keychain_with_mnemonic() -> (Keychain, String) {
+  let mut keychain = Keychain::new();
+  keychain
+    .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+    .unwrap();
+  let account = keychain.add_account(0).unwrap();
+
+  (keychain, account.address)
+}
+
+mod merge {
+  use super::*;
+
+  #[test]
+  fn it_imports_new_vaults_into_their_profiles() {
+    let (mut keychain, _) = keychain_with_mnemonic();
+    let mut other: Keychain = Keychain::new();
+    other.create_profile("work").unwrap();
+    other.switch_profile("work").unwrap();
+    other.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = other.add_account(0).unwrap();
+
+    let report = keychain
+      .merge(
+        other.backup(PASSWORD).unwrap(),
+        PASSWORD,
+        LabelConflict::default(),
+      )
+      .unwrap();
+
+    assert_eq!(report.vaults, vec![1]);
+    assert!(report.duplicates.is_empty());
+    assert_eq!(
+      keychain.get_state().profile("work").unwrap().vaults,
+      vec![1]
+    );
+    assert_eq!(keychain.get_state().accounts()[1].address, account.address);
+    assert!(keychain
+      .use_signer(account.address, b"payload", &SignatureOptions::default())
+      .is_ok());
+  }
+
+  #[test]
+  fn it_skips_vaults_with_the_same_fingerprint() {
+    let (mut keychain, _) = keychain_with_mnemonic();
+    let (mut other, _) = keychain_with_mnemonic();
+
+    let report = keychain
+      .merge(
+        other.backup(PASSWORD).unwrap(),
+        PASSWORD,
+        LabelConflict::default(),
+      )
+      .unwrap();
+
+    assert!(report.vaults.is_empty());
+    assert_eq!(report.duplicates.len(), 1);
+    assert_eq!(keychain.vault_count(), 1);
+  }
+
+  #[test]
+  fn it_imports_labels_and_usage() {
+    let (mut keychain, address) = keychain_with_mnemonic();
+    let (mut other, _) = keychain_with_mnemonic();
+    other.set_account_label(&address, Some("savings")).unwrap();
+    other
+      .use_signer(address.clone(), b"payload", &SignatureOptions::default())
+      .unwrap();
+    keychain
+      .use_signer(address.clone(), b"payload", &SignatureOptions::default())
+      .unwrap();
+
+    let report = keychain
+      .merge(
+        other.backup(PASSWORD).unwrap(),
+        PASSWORD,
+        LabelConflict::default(),
+      )
+      .unwrap();
+
+    assert!(report.label_conflicts.is_empty());
+    assert_eq!(keychain.get_state().labels[&address], "savings");
+    assert_eq!(keychain.get_state().account_usage(&address).signatures, 2);
+  }
+
+  #[test]
+  fn it_resolves_label_conflicts_with_the_strategy() {
+    let resolve = |strategy: LabelConflict| {
+      let (mut keychain, address) = keychain_with_mnemonic();
+      let (mut other, _) = keychain_with_mnemonic();
+      keychain
+        .set_account_label(&address, Some("laptop"))
+        .unwrap();
+      other.set_account_label(&address, Some("phone")).unwrap();
+
+      let report = keychain
+        .merge(other.backup(PASSWORD).unwrap(), PASSWORD, strategy)
+        .unwrap();
+      assert_eq!(report.label_conflicts, vec![address.clone()]);
+
+      keychain.get_state().labels[&address].clone()
+    };
+
+    assert_eq!(resolve(LabelConflict::KeepExisting), "laptop");
+    assert_eq!(resolve(LabelConflict::Replace), "phone");
+    assert_eq!(resolve(LabelConflict::Combine), "laptop / phone");
+  }
+
+  #[test]
+  fn it_fails_with_a_wrong_password() {
+    let (mut keychain, _) = keychain_with_mnemonic();
+    let mut other: Keychain = Keychain::new();
+    other.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let result = keychain.merge(
+      other.backup(PASSWORD).unwrap(),
+      "wrong password",
+      LabelConflict::default(),
+    );
+
+    assert!(matches!(result, Err(KeychainError::VaultsUnchanged(_))));
+    assert_eq!(keychain.vault_count(), 1);
+  }
+}
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use hdkey::{hdkey_factory, HDKey};
+use identity::{signer::SignatureOptions, IdentityFactoryRegistry, MultiKeyPairDyn};
+use simple::SimpleKey;
+use utils::Controller;
+use walleth_keychain::{
+  DynKeychain, Keychain, KeychainError, MetamaskError, MetamaskKeyring, MetamaskVault,
+};
+
+const MNEMONIC: &str =
+	"grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+const PASSWORD: &str = "metamask-password";
+
+/// A vault without key metadata, holding a mnemonic with two accounts,
+/// an imported private key and a hardware wallet keyring
+const VAULT: &str = include_str!("fixtures/metamask_vault.json");
+
+/// The browser storage of the extension, holding a vault with
+/// key metadata and a mnemonic encoded as bytes
+const STORAGE: &str = include_str!("fixtures/metamask_storage.json");
+
+/// A vault holding a 12-word mnemonic with two accounts, the
+/// length of the phrases generated by the extension
+const VAULT_12_WORDS: &str = include_str!("fixtures/metamask_vault_12_words.json");
+
+/// The first two accounts of the 12-word mnemonic of `VAULT_12_WORDS`
+const ADDRESSES_12_WORDS: [&str; 2] = [
+  "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+  "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+];
+
+/// The address of the private key imported in `VAULT`
+const IMPORTED_ADDRESS: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+
+fn registry() -> IdentityFactoryRegistry<Box<dyn MultiKeyPairDyn>> {
+  let mut registry = IdentityFactoryRegistry::<Box<dyn MultiKeyPairDyn>>::new();
+  registry
+    .register("HDKey", |bytes| Ok(Box::new(HDKey::from(bytes))))
+    .register("SimpleKey", |bytes| {
+      Ok(Box::new(SimpleKey::try_from(bytes)?))
+    });
+
+  registry
+}
+
+fn mnemonic_addresses(count: usize) -> Vec<String> {
+  let mut keychain: Keychain = Keychain::new();
+  keychain
+    .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+    .unwrap();
+
+  (0..count)
+    .map(|_| keychain.add_account(0).unwrap().address)
+    .collect()
+}
+
+mod decrypt {
+  use super::*;
+
+  #[test]
+  fn it_decrypts_the_keyrings_of_a_vault() {
+    let vault = MetamaskVault::from_str(VAULT).unwrap();
+
+    let keyrings = vault.decrypt(PASSWORD).unwrap();
+
+    assert_eq!(vault.iterations(), 10_000);
+    assert_eq!(
+      keyrings[0],
+      MetamaskKeyring::Hd {
+        mnemonic: MNEMONIC.to_string(),
+        number_of_accounts: 2,
+        hd_path: "m/44'/60'/0'/0".to_string(),
+      }
+    );
+    assert!(matches!(
+      &keyrings[1],
+      MetamaskKeyring::Simple { private_keys } if private_keys.len() == 1
+    ));
+    assert_eq!(keyrings[2].keyring_type(), "Ledger Hardware");
+  }
+
+  #[test]
+  fn it_finds_the_vault_in_the_browser_storage() {
+    let vault = MetamaskVault::from_str(STORAGE).unwrap();
+
+    let keyrings = vault.decrypt(PASSWORD).unwrap();
+
+    assert_eq!(vault.iterations(), 1_000);
+    assert!(matches!(
+      &keyrings[0],
+      MetamaskKeyring::Hd { mnemonic, .. } if mnemonic == MNEMONIC
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_wrong_password() {
+    let vault = MetamaskVault::from_str(VAULT).unwrap();
+
+    assert_eq!(
+      vault.decrypt("wrong").unwrap_err(),
+      MetamaskError::DecryptionFailed
+    );
+  }
+
+  #[test]
+  fn it_fails_without_a_vault() {
+    assert!(matches!(
+      MetamaskVault::from_str("{\"data\": {}}"),
+      Err(MetamaskError::InvalidVault(_))
+    ));
+  }
+}
+
+mod import_metamask {
+  use super::*;
+
+  #[test]
+  fn it_creates_vaults_for_mnemonics_and_imported_keys() {
+    let mut keychain = DynKeychain::with_registry(registry());
+
+    let import = keychain.import_metamask(VAULT, PASSWORD).unwrap();
+
+    assert_eq!(import.vaults, vec![0, 1]);
+    assert_eq!(import.skipped, vec!["Ledger Hardware"]);
+    let state = keychain.get_state();
+    let addresses = |index: usize| -> Vec<String> {
+      state.vaults[index]
+        .accounts
+        .iter()
+        .map(|account| account.address.clone())
+        .collect()
+    };
+    assert_eq!(addresses(0), mnemonic_addresses(2));
+    assert_eq!(addresses(1), vec![IMPORTED_ADDRESS]);
+  }
+
+  #[test]
+  fn it_imports_12_word_mnemonics() {
+    let mut keychain = DynKeychain::with_registry(registry());
+
+    let import = keychain.import_metamask(VAULT_12_WORDS, PASSWORD).unwrap();
+
+    assert_eq!(import.vaults, vec![0]);
+    let addresses = keychain.get_state().vaults[0]
+      .accounts
+      .iter()
+      .map(|account| account.address.to_lowercase())
+      .collect::<Vec<String>>();
+    assert_eq!(addresses, ADDRESSES_12_WORDS);
+  }
+
+  #[test]
+  fn it_signs_with_imported_keys_after_unlock() {
+    let mut keychain = DynKeychain::with_registry(registry());
+    keychain.import_metamask(VAULT, PASSWORD).unwrap();
+
+    keychain.lock("password").unwrap();
+    keychain.unlock("password").unwrap();
+
+    assert!(keychain
+      .use_signer(
+        IMPORTED_ADDRESS.to_string(),
+        b"message",
+        &SignatureOptions::default()
+      )
+      .is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_wrong_password() {
+    let mut keychain = DynKeychain::with_registry(registry());
+
+    let result = keychain.import_metamask(VAULT, "wrong");
+
+    assert!(matches!(
+      result,
+      Err(KeychainError::MetamaskError(
+        MetamaskError::DecryptionFailed
+      ))
+    ));
+    assert_eq!(keychain.vault_count(), 0);
+  }
+}
@@ -0,0 +1,214 @@
+use hdkey::{hdkey_factory, HDKey};
+use identity::IdentityFactoryRegistry;
+use utils::Controller;
+use walleth_keychain::{
+  migrations::{read_envelope, Migration, BACKUP_MAGIC, BACKUP_VERSION, LEGACY_VERSION},
+  Keychain, KeychainError, MigrationError, Migrator,
+};
+
+fn backup() -> Vec<u8> {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.add_account(0).unwrap();
+
+  keychain.backup("password").unwrap()
+}
+
+/// Rewrite the vaults of a backup with the u8 lengths
+/// used before they were widened, dropping other sections
+fn legacy_backup(backup: &[u8]) -> Vec<u8> {
+  let mut legacy = vec![];
+  let mut rest = &backup[7..];
+  while !rest.is_empty() {
+    let length = u32::from_le_bytes(rest[..4].try_into().unwrap()) as usize;
+    let section = &rest[5..length + 5];
+    if rest[4] == 0 {
+      let metadata_length = u32::from_le_bytes(section[..4].try_into().unwrap());
+      legacy.push(u8::try_from(length - 3).unwrap());
+      legacy.push(0);
+      legacy.push(u8::try_from(metadata_length).unwrap());
+      legacy.extend(&section[4..]);
+    }
+    rest = &rest[length + 5..];
+  }
+
+  legacy
+}
+
+fn registry() -> IdentityFactoryRegistry<HDKey> {
+  IdentityFactoryRegistry::with_initializable()
+}
+
+fn migration_error(result: Result<Keychain, KeychainError>) -> MigrationError {
+  match result {
+    Err(KeychainError::MigrationError(error)) => error,
+    other => panic!("Expected a migration error, got {:?}", other.map(|_| ())),
+  }
+}
+
+mod backup {
+  use super::*;
+
+  #[test]
+  fn it_wraps_the_backup_in_a_versioned_envelope() {
+    let backup = backup();
+
+    assert_eq!(backup[..4], BACKUP_MAGIC);
+    assert_eq!(read_envelope(&backup).0, BACKUP_VERSION);
+  }
+
+  #[test]
+  fn it_backs_up_vaults_larger_than_u8_lengths() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.derive_range(0, 0, 10).unwrap();
+    let backup = keychain.backup("password").unwrap();
+    assert!(backup.len() > usize::from(u8::MAX));
+
+    let restored = Keychain::<HDKey>::restore(backup, "password").unwrap();
+
+    assert_eq!(restored.get_state().accounts().len(), 10);
+  }
+}
+
+mod restore {
+  use super::*;
+
+  #[test]
+  fn it_reports_no_migration_for_current_backups() {
+    let keychain = Keychain::<HDKey>::restore(backup(), "password").unwrap();
+    let report = keychain.migration_report().unwrap();
+
+    assert_eq!(report.from_version, BACKUP_VERSION);
+    assert!(!report.is_migrated());
+  }
+
+  #[test]
+  fn it_migrates_unversioned_backups() {
+    let legacy = backup()[7..].to_vec();
+
+    let keychain = Keychain::<HDKey>::restore(legacy, "password").unwrap();
+    let report = keychain.migration_report().unwrap();
+
+    assert_eq!(report.from_version, 1);
+    assert_eq!(report.to_version, BACKUP_VERSION);
+    assert_eq!(report.applied.len(), 2);
+  }
+
+  #[test]
+  fn it_migrates_backups_without_envelope_flags() {
+    let backup = backup();
+    let mut previous = BACKUP_MAGIC.to_vec();
+    previous.extend(2u16.to_le_bytes());
+    previous.extend(&backup[7..]);
+
+    let keychain = Keychain::<HDKey>::restore(previous, "password").unwrap();
+
+    assert_eq!(
+      keychain.migration_report().unwrap().applied,
+      vec!["Add flags to the envelope"]
+    );
+  }
+
+  #[test]
+  fn it_refuses_unknown_envelope_flags() {
+    let mut flagged = backup();
+    flagged[6] = 0x80;
+
+    assert_eq!(
+      migration_error(Keychain::<HDKey>::restore(flagged, "password")),
+      MigrationError::UnsupportedFlags(0x80)
+    );
+  }
+
+  #[test]
+  fn it_migrates_backups_with_u8_lengths() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let legacy = legacy_backup(&keychain.backup("password").unwrap());
+    assert_eq!(read_envelope(&legacy).0, LEGACY_VERSION);
+
+    let restored = Keychain::<HDKey>::restore(legacy, "password").unwrap();
+    let report = restored.migration_report().unwrap();
+
+    assert_eq!(report.from_version, LEGACY_VERSION);
+    assert_eq!(report.applied.len(), 3);
+    assert_eq!(restored, keychain);
+  }
+
+  #[test]
+  fn it_refuses_to_downgrade_newer_backups() {
+    let mut newer = backup();
+    newer[4..6].copy_from_slice(&(BACKUP_VERSION + 1).to_le_bytes());
+
+    assert_eq!(
+      migration_error(Keychain::<HDKey>::restore(newer, "password")),
+      MigrationError::UnsupportedVersion(BACKUP_VERSION + 1)
+    );
+  }
+
+  #[test]
+  fn it_has_no_report_when_not_restored() {
+    let keychain: Keychain = Keychain::new();
+
+    assert!(keychain.migration_report().is_none());
+  }
+}
+
+mod restore_with_migrator {
+  use super::*;
+
+  #[test]
+  fn it_fails_when_a_step_is_missing() {
+    let legacy = backup()[7..].to_vec();
+
+    assert_eq!(
+      migration_error(Keychain::restore_with_migrator(
+        legacy,
+        "password",
+        registry(),
+        &Migrator::empty()
+      )),
+      MigrationError::MissingMigration(1)
+    );
+  }
+
+  #[test]
+  fn it_reports_a_failing_step() {
+    let legacy = backup()[7..].to_vec();
+    let migrator = Migrator::empty().with(Migration {
+      from: 1,
+      description: "Always fail",
+      migrate: |_| Err("unreadable".to_string()),
+    });
+
+    assert_eq!(
+      migration_error(Keychain::restore_with_migrator(
+        legacy,
+        "password",
+        registry(),
+        &migrator
+      )),
+      MigrationError::StepFailed(1, "unreadable".to_string())
+    );
+  }
+
+  #[test]
+  fn it_applies_custom_steps() {
+    let legacy = backup()[7..].to_vec();
+    let migrator = Migrator::default().with(Migration {
+      from: 1,
+      description: "Custom step",
+      migrate: Ok,
+    });
+
+    let keychain =
+      Keychain::restore_with_migrator(legacy, "password", registry(), &migrator).unwrap();
+
+    assert_eq!(
+      keychain.migration_report().unwrap().applied,
+      vec!["Custom step", "Add flags to the envelope"]
+    );
+  }
+}
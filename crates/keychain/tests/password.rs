@@ -0,0 +1,156 @@
+use hdkey::hdkey_factory;
+use walleth_keychain::{estimate_entropy, Keychain, KeychainError, PasswordPolicy};
+
+const STRONG_PASSWORD: &str = "Correct-Horse-Battery-Staple-42";
+
+fn keychain_with_policy(policy: PasswordPolicy) -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.set_password_policy(policy);
+
+  keychain
+}
+
+mod estimate_entropy {
+  use super::*;
+
+  #[test]
+  fn it_estimates_more_entropy_for_mixed_character_classes() {
+    assert!(estimate_entropy("Ab3$efgh") > estimate_entropy("qwzxmnbv"));
+  }
+
+  #[test]
+  fn it_discounts_repeated_characters_and_sequences() {
+    assert!(estimate_entropy("aaaaaaaa") < estimate_entropy("qwzxmnbv"));
+    assert!(estimate_entropy("abcdefgh") < estimate_entropy("qwzxmnbv"));
+  }
+
+  #[test]
+  fn it_returns_zero_for_empty_passwords() {
+    assert_eq!(estimate_entropy(""), 0.0);
+  }
+}
+
+mod check {
+  use super::*;
+
+  #[test]
+  fn it_accepts_any_password_by_default() {
+    assert!(PasswordPolicy::new().check("a").is_ok());
+  }
+
+  #[test]
+  fn it_suggests_a_longer_password() {
+    let weakness = PasswordPolicy::new()
+      .with_min_length(10)
+      .check("short")
+      .unwrap_err();
+
+    assert_eq!(weakness.suggestions, vec!["use at least 10 characters"]);
+  }
+
+  #[test]
+  fn it_rejects_denylisted_words() {
+    let weakness = PasswordPolicy::new()
+      .with_denylist(&["walleth"])
+      .check("MyWalleth!2024")
+      .unwrap_err();
+
+    assert_eq!(weakness.suggestions.len(), 1);
+    assert!(weakness.suggestions[0].contains("walleth"));
+  }
+
+  #[test]
+  fn it_reports_the_estimated_entropy() {
+    let weakness = PasswordPolicy::new()
+      .with_min_entropy_bits(200.0)
+      .check(STRONG_PASSWORD)
+      .unwrap_err();
+
+    assert_eq!(weakness.entropy_bits, estimate_entropy(STRONG_PASSWORD));
+  }
+
+  #[test]
+  fn it_rejects_common_passwords_with_the_recommended_policy() {
+    assert!(PasswordPolicy::recommended().check("password123").is_err());
+    assert!(PasswordPolicy::recommended().check(STRONG_PASSWORD).is_ok());
+  }
+}
+
+mod lock {
+  use super::*;
+
+  #[test]
+  fn it_fails_with_a_weak_password() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+
+    let result = keychain.lock("password123");
+
+    assert!(matches!(result, Err(KeychainError::WeakPassword(_))));
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_locks_with_a_strong_password() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+
+    keychain.lock(STRONG_PASSWORD).unwrap();
+
+    assert!(keychain.is_locked());
+  }
+}
+
+mod change_password {
+  use super::*;
+
+  const NEW_PASSWORD: &str = "Tr0ub4dor&3-Purple-Quokka";
+
+  #[test]
+  fn it_changes_the_password_of_a_locked_keychain() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+    keychain.lock(STRONG_PASSWORD).unwrap();
+
+    keychain
+      .change_password(STRONG_PASSWORD, NEW_PASSWORD)
+      .unwrap();
+
+    assert!(keychain.is_locked());
+    assert!(keychain.unlock(STRONG_PASSWORD).is_err());
+    assert!(keychain.unlock(NEW_PASSWORD).is_ok());
+  }
+
+  #[test]
+  fn it_keeps_an_unlocked_keychain_unlocked() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+    keychain.lock(STRONG_PASSWORD).unwrap();
+    keychain.unlock(STRONG_PASSWORD).unwrap();
+
+    keychain
+      .change_password(STRONG_PASSWORD, NEW_PASSWORD)
+      .unwrap();
+
+    assert!(!keychain.is_locked());
+    keychain.lock(NEW_PASSWORD).unwrap();
+    assert!(keychain.unlock(NEW_PASSWORD).is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_a_wrong_old_password() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+    keychain.lock(STRONG_PASSWORD).unwrap();
+
+    assert!(keychain.change_password("wrong", NEW_PASSWORD).is_err());
+    assert!(keychain.unlock(STRONG_PASSWORD).is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_a_weak_new_password() {
+    let mut keychain = keychain_with_policy(PasswordPolicy::recommended());
+    keychain.lock(STRONG_PASSWORD).unwrap();
+
+    let result = keychain.change_password(STRONG_PASSWORD, "password123");
+
+    assert!(matches!(result, Err(KeychainError::WeakPassword(_))));
+    assert!(keychain.unlock(STRONG_PASSWORD).is_ok());
+  }
+}
@@ -0,0 +1,283 @@
+use std::sync::{Arc, Mutex};
+
+use identity::signer::SignatureOptions;
+use utils::{crypto::sha3::keccak256, hex::encode, Controller};
+use walleth_keychain::{
+  decoder::AbiFunction, Keychain, KeychainError, PolicyEvent, PolicyViolation, QuotaUsage,
+  SigningContext, SigningPolicy, SpendingLimit,
+};
+
+mod common;
+use common::keychain_with_account;
+
+const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+fn keychain_with_policy(policy: SigningPolicy) -> (Keychain, String) {
+  let (mut keychain, address) = keychain_with_account();
+  keychain.set_policy(policy);
+
+  (keychain, address)
+}
+
+fn keychain_with_limit(limit: SpendingLimit) -> (Keychain, String) {
+  let (mut keychain, address) = keychain_with_account();
+  keychain.set_policy(SigningPolicy::new().with_spending_limit(&address, limit));
+
+  (keychain, address)
+}
+
+fn send(keychain: &mut Keychain, address: &str, value: u128) -> Result<(), KeychainError> {
+  let context = SigningContext::transaction(Some(TOKEN), value, &[]);
+
+  keychain
+    .use_signer_with_context(
+      address.to_string(),
+      &value.to_le_bytes(),
+      &SignatureOptions::default(),
+      &context,
+    )
+    .map(|_| ())
+}
+
+/// Get the EIP-55 checksummed form of a lowercase `address`
+fn checksum(address: &str) -> String {
+  let hex = address.trim_start_matches("0x");
+  let hash = encode(&keccak256(hex.as_bytes()));
+
+  hex
+    .chars()
+    .zip(hash.chars())
+    .fold("0x".to_string(), |mut checksummed, (char, nibble)| {
+      match nibble.to_digit(16).unwrap() >= 8 {
+        true => checksummed.extend(char.to_uppercase()),
+        false => checksummed.push(char),
+      }
+      checksummed
+    })
+}
+
+fn transfer_calldata() -> Vec<u8> {
+  let mut calldata = AbiFunction::new("transfer(address,uint256)", &[])
+    .unwrap()
+    .selector
+    .to_vec();
+  calldata.extend([0u8; 64]);
+  calldata
+}
+
+fn record_events(keychain: &mut Keychain) -> Arc<Mutex<Vec<PolicyEvent>>> {
+  let events = Arc::new(Mutex::new(vec![]));
+  let recorded = events.clone();
+  keychain
+    .policy_mut()
+    .subscribe(move |event| recorded.lock().unwrap().push(event.clone()));
+
+  events
+}
+
+mod no_blind_signing {
+  use super::*;
+
+  #[test]
+  fn it_signs_decodable_calldata() {
+    let (mut keychain, address) =
+      keychain_with_policy(SigningPolicy::new().with_no_blind_signing());
+    let context = SigningContext::transaction(Some(TOKEN), 0, &transfer_calldata());
+
+    let signature =
+      keychain.use_signer_with_context(address, b"tx", &SignatureOptions::default(), &context);
+
+    assert!(signature.is_ok());
+  }
+
+  #[test]
+  fn it_signs_plain_transfers() {
+    let (mut keychain, address) =
+      keychain_with_policy(SigningPolicy::new().with_no_blind_signing());
+    let context = SigningContext::transaction(Some(TOKEN), 1, &[]);
+
+    let signature =
+      keychain.use_signer_with_context(address, b"tx", &SignatureOptions::default(), &context);
+
+    assert!(signature.is_ok());
+  }
+
+  #[test]
+  fn it_rejects_calldata_that_cannot_be_decoded() {
+    let (mut keychain, address) =
+      keychain_with_policy(SigningPolicy::new().with_no_blind_signing());
+    let events = record_events(&mut keychain);
+    let context = SigningContext::transaction(Some(TOKEN), 0, &[1, 2, 3, 4]);
+
+    let signature = keychain.use_signer_with_context(
+      address.clone(),
+      b"tx",
+      &SignatureOptions::default(),
+      &context,
+    );
+
+    assert!(matches!(
+      signature,
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::BlindSigning(_)
+      ))
+    ));
+    assert!(matches!(
+      &events.lock().unwrap()[..],
+      [PolicyEvent::Rejected { account, .. }] if *account == address
+    ));
+  }
+
+  #[test]
+  fn it_signs_undecodable_calldata_with_an_explicit_override() {
+    let (mut keychain, address) =
+      keychain_with_policy(SigningPolicy::new().with_no_blind_signing());
+    let events = record_events(&mut keychain);
+    let context =
+      SigningContext::transaction(Some(TOKEN), 0, &[1, 2, 3, 4]).allowing_blind_signing();
+
+    let signature =
+      keychain.use_signer_with_context(address, b"tx", &SignatureOptions::default(), &context);
+
+    assert!(signature.is_ok());
+    assert!(matches!(
+      &events.lock().unwrap()[..],
+      [PolicyEvent::Overridden { .. }]
+    ));
+  }
+
+  #[test]
+  fn it_allows_blind_signing_by_default() {
+    let (mut keychain, address) = keychain_with_account();
+    let context = SigningContext::transaction(Some(TOKEN), 0, &[1, 2, 3, 4]);
+
+    let signature =
+      keychain.use_signer_with_context(address, b"tx", &SignatureOptions::default(), &context);
+
+    assert!(signature.is_ok());
+  }
+}
+
+mod spending_limit {
+  use super::*;
+
+  #[test]
+  fn it_signs_within_the_daily_value() {
+    let (mut keychain, address) = keychain_with_limit(SpendingLimit::new().with_daily_value(100));
+
+    send(&mut keychain, &address, 60).unwrap();
+    send(&mut keychain, &address, 40).unwrap();
+  }
+
+  #[test]
+  fn it_rejects_transactions_above_the_daily_value() {
+    let (mut keychain, address) = keychain_with_limit(SpendingLimit::new().with_daily_value(100));
+    let events = record_events(&mut keychain);
+    send(&mut keychain, &address, 60).unwrap();
+
+    assert!(matches!(
+      send(&mut keychain, &address, 41),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DailyValueExceeded {
+          limit: 100,
+          spent: 60
+        }
+      ))
+    ));
+    assert!(matches!(
+      &events.lock().unwrap()[..],
+      [PolicyEvent::Rejected { .. }]
+    ));
+  }
+
+  #[test]
+  fn it_rejects_transactions_above_the_daily_count() {
+    let (mut keychain, address) =
+      keychain_with_limit(SpendingLimit::new().with_daily_transactions(2));
+    send(&mut keychain, &address, 0).unwrap();
+    send(&mut keychain, &address, 0).unwrap();
+
+    assert!(matches!(
+      send(&mut keychain, &address, 0),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DailyTransactionsExceeded { limit: 2 }
+      ))
+    ));
+  }
+
+  #[test]
+  fn it_applies_limits_set_with_a_checksummed_address() {
+    let (mut keychain, address) = keychain_with_account();
+    let checksummed = checksum(&address);
+    assert_ne!(checksummed, address);
+    keychain.set_policy(SigningPolicy::new().with_spending_limit(
+      &checksummed,
+      SpendingLimit::new().with_daily_transactions(1),
+    ));
+
+    send(&mut keychain, &address, 0).unwrap();
+
+    assert!(keychain.policy().spending_limit(&checksummed).is_some());
+    assert!(matches!(
+      send(&mut keychain, &address, 0),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DailyTransactionsExceeded { limit: 1 }
+      ))
+    ));
+  }
+
+  #[test]
+  fn it_does_not_count_rejected_transactions() {
+    let (mut keychain, address) = keychain_with_limit(SpendingLimit::new().with_daily_value(100));
+
+    assert!(send(&mut keychain, &address, 101).is_err());
+    send(&mut keychain, &address, 100).unwrap();
+  }
+
+  #[test]
+  fn it_does_not_count_messages() {
+    let (mut keychain, address) =
+      keychain_with_limit(SpendingLimit::new().with_daily_transactions(1));
+
+    keychain
+      .use_signer(address.clone(), b"hello", &SignatureOptions::default())
+      .unwrap();
+
+    send(&mut keychain, &address, 0).unwrap();
+  }
+
+  #[test]
+  fn it_resets_the_usage_on_a_new_day() {
+    let usage = QuotaUsage {
+      day: 10,
+      value: 100,
+      transactions: 3,
+    };
+
+    assert_eq!(usage.on(10), usage);
+    assert_eq!(
+      usage.on(11),
+      QuotaUsage {
+        day: 11,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn it_keeps_the_usage_across_restores() {
+    let limit = SpendingLimit::new().with_daily_value(100);
+    let (mut keychain, address) = keychain_with_limit(limit);
+    send(&mut keychain, &address, 60).unwrap();
+
+    let backup = keychain.backup("password").unwrap();
+    let mut restored: Keychain = Keychain::restore(backup, "password").unwrap();
+    restored.set_policy(SigningPolicy::new().with_spending_limit(&address, limit));
+
+    assert_eq!(
+      restored.get_state().quota_usage.get(&address),
+      keychain.get_state().quota_usage.get(&address)
+    );
+    assert!(send(&mut restored, &address, 41).is_err());
+  }
+}
@@ -0,0 +1,291 @@
+use std::{thread, time::Duration};
+
+use identity::{signer::SignatureOptions, verify_address};
+use walleth_keychain::{
+  AuditEvent, DuplicateAction, KeychainError, PayloadLedger, PreviewOutcome, SigningPolicy,
+};
+
+mod common;
+use common::keychain_with_accounts;
+
+fn recoverable() -> SignatureOptions {
+  SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  }
+}
+
+mod start_signing_pool {
+  use super::*;
+
+  #[test]
+  fn it_signs_for_the_configured_accounts() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    let signature = pool
+      .sign(&addresses[1], b"payload", &recoverable())
+      .unwrap();
+
+    assert!(verify_address(&addresses[1], b"payload", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_produces_the_same_signatures_as_the_keychain() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    let expected = keychain
+      .use_signer(addresses[0].clone(), b"payload", &recoverable())
+      .unwrap();
+
+    assert_eq!(
+      pool
+        .sign(&addresses[0], b"payload", &recoverable())
+        .unwrap(),
+      expected
+    );
+  }
+
+  #[test]
+  fn it_signs_from_multiple_threads() {
+    let (mut keychain, addresses) = keychain_with_accounts(3);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    let handles: Vec<_> = addresses
+      .iter()
+      .cloned()
+      .map(|address| {
+        let pool = pool.clone();
+        thread::spawn(move || {
+          let pending: Vec<_> = (0..50)
+            .map(|nonce: u32| {
+              pool
+                .submit(&address, &nonce.to_le_bytes(), &recoverable())
+                .unwrap()
+            })
+            .collect();
+
+          pending.into_iter().enumerate().all(|(nonce, pending)| {
+            let signature = pending.recv().unwrap();
+            verify_address(&address, &(nonce as u32).to_le_bytes(), &signature).is_ok()
+          })
+        })
+      })
+      .collect();
+
+    assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+  }
+
+  #[test]
+  fn it_fails_for_accounts_outside_the_pool() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    let pool = keychain.start_signing_pool(&addresses[..1]).unwrap();
+
+    assert!(matches!(
+      pool.sign(&addresses[1], b"payload", &recoverable()),
+      Err(KeychainError::KeyNotFoundForAddress(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_while_locked() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain.lock("password").unwrap();
+
+    assert!(keychain.start_signing_pool(&addresses).is_err());
+  }
+
+  #[test]
+  fn it_fails_with_a_signing_policy() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+
+    assert!(matches!(
+      keychain.start_signing_pool(&addresses),
+      Err(KeychainError::SigningPoolRestricted)
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_a_payload_ledger() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain.set_payload_ledger(Some(PayloadLedger::new(
+      Duration::from_secs(60),
+      DuplicateAction::Refuse,
+    )));
+
+    assert!(matches!(
+      keychain.start_signing_pool(&addresses),
+      Err(KeychainError::SigningPoolRestricted)
+    ));
+  }
+
+  #[test]
+  fn it_records_the_start_in_the_audit_log() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain.start_signing_pool(&addresses).unwrap();
+
+    let entry = keychain.audit_log().entries().last().unwrap();
+    assert_eq!(entry.event, AuditEvent::SigningPool);
+    assert_eq!(entry.account.as_deref(), Some(addresses[0].as_str()));
+  }
+}
+
+mod queue_depth {
+  use super::*;
+
+  #[test]
+  fn it_is_empty_once_requests_are_signed() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    let pending: Vec<_> = (0..10u32)
+      .map(|nonce| {
+        pool
+          .submit(&addresses[0], &nonce.to_le_bytes(), &recoverable())
+          .unwrap()
+      })
+      .collect();
+    pending.into_iter().for_each(|pending| {
+      pending.recv().unwrap();
+    });
+
+    assert_eq!(pool.queue_depth(), 0);
+  }
+}
+
+mod lock {
+  use super::*;
+
+  #[test]
+  fn it_drains_the_pool() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+    let pending: Vec<_> = (0..20u8)
+      .map(|nonce| {
+        pool
+          .submit(&addresses[0], &[nonce], &recoverable())
+          .unwrap()
+      })
+      .collect();
+
+    keychain.lock("password").unwrap();
+
+    assert!(pending.into_iter().all(|pending| pending.recv().is_ok()));
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+}
+
+mod set_policy {
+  use super::*;
+
+  #[test]
+  fn it_stops_the_pool() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+}
+
+mod preview {
+  use super::*;
+
+  #[test]
+  fn it_stops_the_pool_when_a_draft_locking_the_keychain_is_committed() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    keychain
+      .preview(|draft| {
+        draft.lock("password")?;
+        Ok(PreviewOutcome::Commit)
+      })
+      .unwrap();
+
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+
+  #[test]
+  fn it_stops_the_pool_when_a_draft_setting_a_policy_is_committed() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    keychain
+      .preview(|draft| {
+        draft.set_policy(SigningPolicy::new().with_no_blind_signing());
+        Ok(PreviewOutcome::Commit)
+      })
+      .unwrap();
+
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+
+  #[test]
+  fn it_stops_the_pool_when_a_draft_setting_a_ledger_is_committed() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    keychain
+      .preview(|draft| {
+        draft.set_payload_ledger(Some(PayloadLedger::new(
+          Duration::from_secs(60),
+          DuplicateAction::Refuse,
+        )));
+        Ok(PreviewOutcome::Commit)
+      })
+      .unwrap();
+
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+
+  #[test]
+  fn it_keeps_the_pool_when_the_draft_is_discarded() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    keychain
+      .preview(|draft| {
+        draft.lock("password")?;
+        Ok(PreviewOutcome::Discard)
+      })
+      .unwrap();
+
+    assert!(pool.sign(&addresses[0], b"payload", &recoverable()).is_ok());
+  }
+}
+
+mod drop {
+  use super::*;
+
+  #[test]
+  fn it_stops_the_pool_when_the_keychain_is_dropped() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    let pool = keychain.start_signing_pool(&addresses).unwrap();
+
+    std::mem::drop(keychain);
+
+    assert!(matches!(
+      pool.sign(&addresses[0], b"payload", &recoverable()),
+      Err(KeychainError::SigningPoolClosed)
+    ));
+  }
+}
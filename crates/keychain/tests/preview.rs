@@ -0,0 +1,174 @@
+use std::{
+  panic::{catch_unwind, AssertUnwindSafe},
+  sync::{Arc, Mutex},
+};
+
+use hdkey::hdkey_factory;
+use identity::signer::SignatureOptions;
+use utils::Controller;
+use walleth_keychain::{Keychain, KeychainError, PreviewOutcome, SigningContext, SigningPolicy};
+
+const MNEMONIC: &str =
+  "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+fn keychain_with_mnemonic() -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain
+    .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+    .unwrap();
+
+  keychain
+}
+
+mod preview {
+  use super::*;
+
+  #[test]
+  fn it_commits_the_draft_with_one_event() {
+    let mut keychain = keychain_with_mnemonic();
+    let events = Arc::new(Mutex::new(0));
+    let received = events.clone();
+    keychain.subscribe(move |_| *received.lock().unwrap() += 1);
+
+    let outcome = keychain
+      .preview(|draft| {
+        let account = draft.add_account(0)?;
+        draft.add_account(0)?;
+        draft.create_profile("work")?;
+        draft.set_account_label(&account.address, Some("main"))?;
+        Ok(PreviewOutcome::Commit)
+      })
+      .unwrap();
+
+    assert_eq!(outcome, PreviewOutcome::Commit);
+    assert_eq!(*events.lock().unwrap(), 1);
+    let state = keychain.get_state();
+    assert_eq!(state.accounts().len(), 2);
+    assert!(state.profile("work").is_some());
+    assert_eq!(state.labels.len(), 1);
+    assert_eq!(keychain.add_account(0).unwrap().path.index, 2);
+  }
+
+  #[test]
+  fn it_discards_the_draft() {
+    let mut keychain = keychain_with_mnemonic();
+    let revision = keychain.revision();
+
+    let outcome = keychain
+      .preview(|draft| {
+        draft.add_account(0)?;
+        draft.create_profile("work")?;
+        Ok(PreviewOutcome::Discard)
+      })
+      .unwrap();
+
+    assert_eq!(outcome, PreviewOutcome::Discard);
+    assert!(keychain.get_state().accounts().is_empty());
+    assert!(keychain.get_state().profile("work").is_none());
+    assert_eq!(keychain.revision(), revision);
+    assert_eq!(keychain.add_account(0).unwrap().path.index, 0);
+  }
+
+  #[test]
+  fn it_discards_the_draft_on_errors() {
+    let mut keychain = keychain_with_mnemonic();
+
+    let result = keychain.preview(|draft| {
+      draft.add_account(0)?;
+      draft.add_account(4)?;
+      Ok(PreviewOutcome::Commit)
+    });
+
+    assert!(matches!(result, Err(KeychainError::KeyNotFoundForIndex(4))));
+    assert!(keychain.get_state().accounts().is_empty());
+  }
+
+  #[test]
+  fn it_refuses_to_sign_or_decrypt_in_the_draft() {
+    let mut keychain = keychain_with_mnemonic();
+    let address = keychain.add_account(0).unwrap().address;
+    let entries = keychain.audit_log().entries().len();
+
+    let result = keychain.preview(|draft| {
+      draft.use_signer(address.clone(), b"message", &SignatureOptions::default())?;
+      Ok(PreviewOutcome::Discard)
+    });
+
+    assert!(matches!(result, Err(KeychainError::DraftRestricted)));
+    assert!(matches!(
+      keychain.preview(|draft| {
+        draft.encryption_public_key(&address)?;
+        Ok(PreviewOutcome::Discard)
+      }),
+      Err(KeychainError::DraftRestricted)
+    ));
+    assert_eq!(keychain.audit_log().entries().len(), entries);
+    assert!(keychain
+      .use_signer(address, b"message", &SignatureOptions::default())
+      .is_ok());
+  }
+
+  #[test]
+  fn it_keeps_the_policy_when_the_draft_is_discarded() {
+    let mut keychain = keychain_with_mnemonic();
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+
+    keychain
+      .preview(|draft| {
+        draft.set_policy(SigningPolicy::new());
+        Ok(PreviewOutcome::Discard)
+      })
+      .unwrap();
+    let result = keychain.preview(|draft| {
+      draft.policy_mut().no_blind_signing = false;
+      draft.add_account(4)?;
+      Ok(PreviewOutcome::Commit)
+    });
+
+    assert!(result.is_err());
+    assert!(keychain.policy().no_blind_signing);
+  }
+
+  #[test]
+  fn it_installs_the_policy_of_a_committed_draft() {
+    let mut keychain = keychain_with_mnemonic();
+    let events = Arc::new(Mutex::new(0));
+    let received = events.clone();
+    keychain
+      .policy_mut()
+      .subscribe(move |_| *received.lock().unwrap() += 1);
+
+    keychain
+      .preview(|draft| {
+        draft.policy_mut().no_blind_signing = true;
+        Ok(PreviewOutcome::Commit)
+      })
+      .unwrap();
+    let account = keychain.add_account(0).unwrap();
+    let context = SigningContext::transaction(Some(&account.address), 0, &[0xde, 0xad]);
+
+    assert!(keychain.policy().no_blind_signing);
+    assert!(keychain
+      .use_signer_with_context(
+        account.address,
+        b"payload",
+        &SignatureOptions::default(),
+        &context
+      )
+      .is_err());
+    assert_eq!(*events.lock().unwrap(), 1);
+  }
+
+  #[test]
+  fn it_restores_the_policy_when_the_draft_panics() {
+    let mut keychain = keychain_with_mnemonic();
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+      keychain.preview(|_| panic!("draft failed")).unwrap();
+    }));
+
+    assert!(result.is_err());
+    assert!(keychain.policy().no_blind_signing);
+  }
+}
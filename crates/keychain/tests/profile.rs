@@ -0,0 +1,194 @@
+use hdkey::hdkey_factory;
+use identity::signer::SignatureOptions;
+use utils::Controller;
+use walleth_keychain::{Keychain, KeychainError, VaultStatus, DEFAULT_PROFILE};
+
+const PASSWORD: &str = "password";
+
+fn keychain_with_profiles() -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.create_profile("work").unwrap();
+  keychain.switch_profile("work").unwrap();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+
+  keychain
+}
+
+mod create_profile {
+  use super::*;
+
+  #[test]
+  fn it_starts_with_the_default_profile() {
+    let keychain: Keychain = Keychain::new();
+
+    assert_eq!(keychain.active_profile(), DEFAULT_PROFILE);
+    assert_eq!(keychain.get_state().profiles.len(), 1);
+  }
+
+  #[test]
+  fn it_creates_an_empty_profile() {
+    let mut keychain: Keychain = Keychain::new();
+
+    keychain.create_profile("testnet").unwrap();
+
+    let profile = keychain.get_state().profile("testnet").unwrap();
+    assert!(profile.vaults.is_empty());
+  }
+
+  #[test]
+  fn it_fails_when_the_profile_exists() {
+    let mut keychain: Keychain = Keychain::new();
+
+    let result = keychain.create_profile(DEFAULT_PROFILE);
+
+    assert!(matches!(
+      result,
+      Err(KeychainError::ProfileAlreadyExists(_))
+    ));
+  }
+}
+
+mod switch_profile {
+  use super::*;
+
+  #[test]
+  fn it_adds_new_vaults_to_the_active_profile() {
+    let keychain = keychain_with_profiles();
+
+    let state = keychain.get_state();
+    assert_eq!(state.profile(DEFAULT_PROFILE).unwrap().vaults, vec![0]);
+    assert_eq!(state.profile("work").unwrap().vaults, vec![1, 2]);
+    assert_eq!(state.active_profile().unwrap().name, "work");
+  }
+
+  #[test]
+  fn it_fails_with_unknown_profile() {
+    let mut keychain: Keychain = Keychain::new();
+
+    let result = keychain.switch_profile("work");
+
+    assert!(matches!(result, Err(KeychainError::ProfileNotFound(_))));
+  }
+}
+
+mod move_vault {
+  use super::*;
+
+  #[test]
+  fn it_moves_a_vault_between_profiles() {
+    let mut keychain = keychain_with_profiles();
+
+    keychain.move_vault(1, DEFAULT_PROFILE).unwrap();
+
+    let state = keychain.get_state();
+    assert_eq!(state.profile(DEFAULT_PROFILE).unwrap().vaults, vec![0, 1]);
+    assert_eq!(state.profile("work").unwrap().vaults, vec![2]);
+  }
+
+  #[test]
+  fn it_fails_with_unknown_vault_index() {
+    let mut keychain = keychain_with_profiles();
+
+    let result = keychain.move_vault(3, DEFAULT_PROFILE);
+
+    assert!(matches!(result, Err(KeychainError::KeyNotFoundForIndex(3))));
+  }
+}
+
+mod lock_profile {
+  use super::*;
+
+  #[test]
+  fn it_locks_only_the_vaults_of_the_profile() {
+    let mut keychain = keychain_with_profiles();
+
+    keychain.lock_profile("work", PASSWORD).unwrap();
+
+    let state = keychain.get_state();
+    assert_eq!(state.profile_status("work"), Some(VaultStatus::Locked));
+    assert_eq!(
+      state.profile_status(DEFAULT_PROFILE),
+      Some(VaultStatus::Unlocked)
+    );
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_refuses_to_sign_with_accounts_of_locked_profiles() {
+    let mut keychain = keychain_with_profiles();
+    let personal = keychain.add_account(0).unwrap();
+    let work = keychain.add_account(1).unwrap();
+
+    keychain.lock_profile("work", PASSWORD).unwrap();
+
+    assert!(keychain
+      .use_signer(personal.address, b"message", &SignatureOptions::default())
+      .is_ok());
+    assert!(matches!(
+      keychain.use_signer(work.address, b"message", &SignatureOptions::default()),
+      Err(KeychainError::Locked(_))
+    ));
+  }
+}
+
+mod unlock_profile {
+  use super::*;
+
+  #[test]
+  fn it_unlocks_the_vaults_of_the_profile() {
+    let mut keychain = keychain_with_profiles();
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.unlock_profile("work", PASSWORD).unwrap();
+
+    let state = keychain.get_state();
+    assert_eq!(state.profile_status("work"), Some(VaultStatus::Unlocked));
+    assert_eq!(
+      state.profile_status(DEFAULT_PROFILE),
+      Some(VaultStatus::Locked)
+    );
+  }
+
+  #[test]
+  fn it_fails_with_wrong_password() {
+    let mut keychain = keychain_with_profiles();
+    keychain.lock_profile("work", PASSWORD).unwrap();
+
+    assert!(keychain.unlock_profile("work", "wrong").is_err());
+  }
+}
+
+mod backup_profile {
+  use super::*;
+
+  #[test]
+  fn it_restores_the_profiles_of_a_backup() {
+    let mut keychain = keychain_with_profiles();
+    keychain.create_profile("testnet").unwrap();
+
+    let backup = keychain.backup(PASSWORD).unwrap();
+    let restored: Keychain = Keychain::restore(backup, PASSWORD).unwrap();
+
+    let state = restored.get_state();
+    assert_eq!(state.profile(DEFAULT_PROFILE).unwrap().vaults, vec![0]);
+    assert_eq!(state.profile("work").unwrap().vaults, vec![1, 2]);
+    assert!(state.profile("testnet").unwrap().vaults.is_empty());
+    assert_eq!(restored.active_profile(), DEFAULT_PROFILE);
+  }
+
+  #[test]
+  fn it_backs_up_only_the_vaults_of_the_profile() {
+    let mut keychain = keychain_with_profiles();
+    let fingerprint = keychain.get_state().vaults[2].fingerprint;
+
+    let backup = keychain.backup_profile("work", PASSWORD).unwrap();
+    let restored: Keychain = Keychain::restore(backup, PASSWORD).unwrap();
+
+    let state = restored.get_state();
+    assert_eq!(state.vault_count(), 2);
+    assert_eq!(state.profile("work").unwrap().vaults, vec![0, 1]);
+    assert_eq!(state.vaults[1].fingerprint, fingerprint);
+  }
+}
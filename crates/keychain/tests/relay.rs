@@ -0,0 +1,122 @@
+use hdkey::hdkey_factory;
+use identity::signer::verify_address;
+use walleth_keychain::{ForwardRequest, Forwarder, Keychain, KeychainError, SignedForwardRequest};
+
+const COUNTER: &str = "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC";
+
+fn forwarder() -> Forwarder {
+  Forwarder {
+    name: "Forwarder".to_string(),
+    version: "1".to_string(),
+    chain_id: 137,
+    address: "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB".to_string(),
+  }
+}
+
+fn keychain_with_request() -> (Keychain, ForwardRequest) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let request = ForwardRequest {
+    from: keychain.add_account(0).unwrap().address,
+    to: COUNTER.to_string(),
+    gas: 100_000,
+    nonce: 2,
+    deadline: 1_700_000_000,
+    data: vec![0xd0, 0x9d, 0xe0, 0x8a],
+    ..Default::default()
+  };
+
+  (keychain, request)
+}
+
+mod sign_forward_request {
+  use super::*;
+
+  #[test]
+  fn it_signs_the_forwarder_typed_data() {
+    let (mut keychain, request) = keychain_with_request();
+
+    let signed = keychain
+      .sign_forward_request(&forwarder(), request.clone())
+      .unwrap();
+
+    let typed_data = request.to_typed_data(&forwarder()).unwrap();
+    assert_eq!(
+      typed_data.encode_type("ForwardRequest").unwrap(),
+      "ForwardRequest(address from,address to,uint256 value,uint256 gas,\
+       uint256 nonce,uint48 deadline,bytes data)"
+    );
+    assert!(verify_address(
+      &request.from,
+      &typed_data.signing_bytes().unwrap(),
+      &signed.signature
+    )
+    .is_ok());
+  }
+
+  #[test]
+  fn it_serializes_the_relayer_body() {
+    let (mut keychain, request) = keychain_with_request();
+
+    let json = keychain
+      .sign_forward_request(&forwarder(), request)
+      .unwrap()
+      .to_json();
+
+    assert_eq!(json["chainId"], 137);
+    assert_eq!(json["request"]["gas"], "100000");
+    assert_eq!(json["request"]["data"], "0xd09de08a");
+    assert_eq!(
+      json["request"]["signature"].as_str().unwrap().len(),
+      2 + 65 * 2
+    );
+  }
+}
+
+mod relay_call {
+  use super::*;
+
+  #[test]
+  fn it_submits_the_signed_request() {
+    let (mut keychain, request) = keychain_with_request();
+    let mut relayed = vec![];
+    let mut relayer = |signed: &SignedForwardRequest| {
+      relayed.push(signed.clone());
+      Ok("0x1234".to_string())
+    };
+
+    let id = keychain
+      .relay_call(&mut relayer, &forwarder(), request.clone())
+      .unwrap();
+
+    assert_eq!(id, "0x1234");
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].request, request);
+  }
+
+  #[test]
+  fn it_returns_relayer_errors() {
+    let (mut keychain, request) = keychain_with_request();
+    let mut relayer = |_: &SignedForwardRequest| -> Result<String, KeychainError> {
+      Err(KeychainError::RelayError("insufficient funds".to_string()))
+    };
+
+    assert!(matches!(
+      keychain.relay_call(&mut relayer, &forwarder(), request),
+      Err(KeychainError::RelayError(_))
+    ));
+  }
+
+  #[test]
+  fn it_does_not_relay_for_unknown_senders() {
+    let (mut keychain, mut request) = keychain_with_request();
+    request.from = COUNTER.to_string();
+    let mut relayer = |_: &SignedForwardRequest| -> Result<String, KeychainError> {
+      panic!("The request should not be relayed")
+    };
+
+    assert!(keychain
+      .relay_call(&mut relayer, &forwarder(), request)
+      .is_err());
+  }
+}
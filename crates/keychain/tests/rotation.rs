@@ -0,0 +1,136 @@
+use hdkey::hdkey_factory;
+use utils::Controller;
+use walleth_keychain::{Keychain, KeychainError};
+
+const MNEMONIC: &str =
+  "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game";
+
+const PASSWORD: &str = "password";
+
+fn keychain_with_accounts(indexes: &[usize]) -> Keychain {
+  let mut keychain = Keychain::new();
+  keychain
+    .add_multi_keypair(hdkey_factory, Some(MNEMONIC.to_string()))
+    .unwrap();
+  for index in indexes {
+    keychain.add_account_at(0, *index).unwrap();
+  }
+
+  keychain
+}
+
+mod rotate_vault {
+  use super::*;
+
+  #[test]
+  fn it_derives_the_same_paths_in_a_new_vault() {
+    let mut keychain = keychain_with_accounts(&[0, 3]);
+
+    let plan = keychain.rotate_vault(0, hdkey_factory, None).unwrap();
+
+    assert_eq!(plan.from_vault, 0);
+    assert_eq!(plan.to_vault, 1);
+    assert_eq!(
+      plan
+        .pairs
+        .iter()
+        .map(|pair| pair.path.index)
+        .collect::<Vec<_>>(),
+      vec![0, 3]
+    );
+    let state = keychain.get_state();
+    assert_eq!(state.vaults[1].accounts.len(), 2);
+    assert_ne!(state.vaults[0].fingerprint, state.vaults[1].fingerprint);
+    for pair in &plan.pairs {
+      assert_ne!(pair.from, pair.to);
+    }
+  }
+
+  #[test]
+  fn it_pairs_balances_and_copies_labels() {
+    let mut keychain = keychain_with_accounts(&[0, 1]);
+    let accounts = keychain.get_state().vaults[0].accounts.clone();
+    keychain
+      .set_account_snapshot(&accounts[0].address, Some(100), Some(1))
+      .unwrap();
+    keychain
+      .set_account_label(&accounts[1].address, Some("savings"))
+      .unwrap();
+
+    let plan = keychain.rotate_vault(0, hdkey_factory, None).unwrap();
+
+    assert_eq!(plan.pairs[0].balance, Some(100));
+    assert_eq!(plan.pairs[1].balance, None);
+    assert_eq!(plan.pending_sweeps(), vec![&plan.pairs[0]]);
+    assert_eq!(
+      keychain.get_state().labels.get(&plan.pairs[1].to),
+      Some(&"savings".to_string())
+    );
+  }
+
+  #[test]
+  fn it_moves_the_domain_bindings_to_the_new_accounts() {
+    let mut keychain = keychain_with_accounts(&[0]);
+    let account = keychain
+      .domain_account(0, "https://app.example.com")
+      .unwrap();
+
+    let plan = keychain.rotate_vault(0, hdkey_factory, None).unwrap();
+
+    let pair = plan
+      .pairs
+      .iter()
+      .find(|pair| pair.from == account.address)
+      .unwrap();
+    assert_eq!(
+      keychain.get_state().domains.values().collect::<Vec<_>>(),
+      vec![&pair.to]
+    );
+  }
+
+  #[test]
+  fn it_keeps_the_new_vault_in_the_same_profile() {
+    let mut keychain = keychain_with_accounts(&[0]);
+    keychain.create_profile("work").unwrap();
+    keychain.move_vault(0, "work").unwrap();
+
+    let plan = keychain.rotate_vault(0, hdkey_factory, None).unwrap();
+
+    let state = keychain.get_state();
+    assert_eq!(state.profile_of(plan.to_vault).unwrap().name, "work");
+  }
+
+  #[test]
+  fn it_rotates_locked_vaults() {
+    let mut keychain = keychain_with_accounts(&[0]);
+    keychain.lock(PASSWORD).unwrap();
+
+    let plan = keychain.rotate_vault(0, hdkey_factory, None).unwrap();
+
+    assert_eq!(plan.pairs.len(), 1);
+  }
+
+  #[test]
+  fn it_fails_on_missing_vaults() {
+    let mut keychain = keychain_with_accounts(&[]);
+
+    assert!(matches!(
+      keychain.rotate_vault(4, hdkey_factory, None),
+      Err(KeychainError::KeyNotFoundForIndex(4))
+    ));
+    assert_eq!(keychain.vault_count(), 1);
+  }
+
+  #[test]
+  fn it_leaves_the_keychain_untouched_on_failure() {
+    let mut keychain = keychain_with_accounts(&[0, 3]);
+    let revision = keychain.revision();
+
+    assert!(keychain
+      .rotate_vault(0, hdkey_factory, Some("not a mnemonic".to_string()))
+      .is_err());
+    assert_eq!(keychain.vault_count(), 1);
+    assert_eq!(keychain.get_state().vaults.len(), 1);
+    assert_eq!(keychain.revision(), revision);
+  }
+}
@@ -0,0 +1,686 @@
+#![cfg(feature = "rpc")]
+
+use std::{
+  io::{Read, Write},
+  net::{SocketAddr, TcpStream},
+  sync::{Arc, Mutex},
+  thread,
+  time::{Duration, Instant},
+};
+
+use hdkey::hdkey_factory;
+use identity::signer::{verify_address, Signature, SignatureOptions};
+use serde_json::{json, Value};
+use utils::TxHash;
+use walleth_keychain::{
+  Keychain, KeychainError, RpcError, RpcHandler, RpcServer, RpcTransaction, SharedKeychain,
+  SigningPolicy,
+};
+
+const PASSWORD: &str = "password";
+
+fn handler() -> (RpcHandler, SharedKeychain, String) {
+  let mut keychain = Keychain::new();
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let account = keychain.add_account(0).unwrap();
+  let keychain = SharedKeychain::new(keychain);
+
+  (RpcHandler::new(keychain.clone()), keychain, account.address)
+}
+
+fn call(handler: &RpcHandler, method: &str, params: Value) -> Value {
+  handler.handle(&json!({
+    "jsonrpc": "2.0",
+    "id": 1,
+    "method": method,
+    "params": params,
+  }))
+}
+
+fn personal_message(message: &[u8]) -> Vec<u8> {
+  let mut bytes = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+  bytes.extend(message);
+  bytes
+}
+
+fn signature(response: &Value) -> Signature {
+  Signature::from_rsv_hex(response["result"].as_str().unwrap()).unwrap()
+}
+
+mod eth_accounts {
+  use super::*;
+
+  #[test]
+  fn it_returns_the_accounts_of_unlocked_vaults() {
+    let (handler, _, address) = handler();
+
+    let response = call(&handler, "eth_accounts", json!([]));
+
+    assert_eq!(response["result"], json!([address]));
+    assert_eq!(response["id"], json!(1));
+  }
+
+  #[test]
+  fn it_hides_the_accounts_of_locked_vaults() {
+    let (handler, keychain, _) = handler();
+    keychain.write().unwrap().lock(PASSWORD).unwrap();
+
+    let response = call(&handler, "eth_accounts", json!([]));
+
+    assert_eq!(response["result"], json!([]));
+  }
+}
+
+mod personal_sign {
+  use super::*;
+
+  #[test]
+  fn it_signs_a_personal_message() {
+    let (handler, _, address) = handler();
+
+    let response = call(&handler, "personal_sign", json!(["0x68656c6c6f", address]));
+
+    assert!(verify_address(&address, &personal_message(b"hello"), &signature(&response)).is_ok());
+  }
+
+  #[test]
+  fn it_signs_plain_text() {
+    let (handler, _, address) = handler();
+
+    let response = call(&handler, "personal_sign", json!(["hello", address]));
+
+    assert!(verify_address(&address, &personal_message(b"hello"), &signature(&response)).is_ok());
+  }
+
+  #[test]
+  fn it_refuses_accounts_of_locked_vaults() {
+    let (handler, keychain, address) = handler();
+    keychain.write().unwrap().lock(PASSWORD).unwrap();
+
+    let response = call(&handler, "personal_sign", json!(["hello", address]));
+
+    assert_eq!(response["error"]["code"], json!(4100));
+  }
+}
+
+mod eth_sign {
+  use super::*;
+
+  #[test]
+  fn it_signs_with_the_address_first() {
+    let (handler, _, address) = handler();
+
+    let response = call(&handler, "eth_sign", json!([address, "0x68656c6c6f"]));
+
+    assert!(verify_address(&address, &personal_message(b"hello"), &signature(&response)).is_ok());
+  }
+}
+
+mod eth_sign_typed_data_v4 {
+  use super::*;
+
+  fn typed_data() -> Value {
+    json!({
+      "types": {
+        "EIP712Domain": [{ "name": "name", "type": "string" }],
+        "Greeting": [{ "name": "text", "type": "string" }]
+      },
+      "primaryType": "Greeting",
+      "domain": { "name": "walleth" },
+      "message": { "text": "gm" }
+    })
+  }
+
+  #[test]
+  fn it_signs_typed_data_as_a_string() {
+    let (handler, _, address) = handler();
+    let typed_data = typed_data();
+
+    let response = call(
+      &handler,
+      "eth_signTypedData_v4",
+      json!([address, typed_data.to_string()]),
+    );
+
+    let signing_bytes = walleth_keychain::TypedData::try_from(&typed_data)
+      .unwrap()
+      .signing_bytes()
+      .unwrap();
+    assert!(verify_address(&address, &signing_bytes, &signature(&response)).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_invalid_typed_data() {
+    let (handler, _, address) = handler();
+
+    let response = call(&handler, "eth_signTypedData_v4", json!([address, {}]));
+
+    assert_eq!(response["error"]["code"], json!(-32602));
+  }
+}
+
+mod eth_send_transaction {
+  use super::*;
+
+  #[test]
+  fn it_is_unsupported_without_a_sender() {
+    let (handler, _, address) = handler();
+
+    let response = call(
+      &handler,
+      "eth_sendTransaction",
+      json!([{ "from": address }]),
+    );
+
+    assert_eq!(response["error"]["code"], json!(4200));
+  }
+
+  fn transaction(address: &str) -> Value {
+    json!({
+      "from": address,
+      "to": address,
+      "value": "0x1",
+      "chainId": "0x1",
+      "nonce": "0x0",
+      "gas": "0x5208",
+      "maxFeePerGas": "0x3b9aca00",
+      "maxPriorityFeePerGas": "0x3b9aca00",
+    })
+  }
+
+  #[test]
+  fn it_signs_the_transaction_and_delegates_the_broadcast() {
+    let (handler, keychain, address) = handler();
+    let broadcast = Arc::new(Mutex::new(vec![]));
+    let handler = handler.with_transaction_sender({
+      let broadcast = Arc::clone(&broadcast);
+      move |raw_transaction: &[u8]| {
+        *broadcast.lock().unwrap() = raw_transaction.to_vec();
+        Ok::<TxHash, RpcError>(TxHash([0x12; 32]))
+      }
+    });
+
+    let response = call(
+      &handler,
+      "eth_sendTransaction",
+      json!([transaction(&address)]),
+    );
+
+    let transaction = RpcTransaction::try_from(&transaction(&address)).unwrap();
+    let signature = keychain
+      .sign_with_context(
+        &address,
+        &transaction.signing_bytes(),
+        &SignatureOptions {
+          recoverable: true,
+          ..Default::default()
+        },
+        &transaction.context(),
+      )
+      .unwrap();
+    assert_eq!(response["result"], json!(TxHash([0x12; 32]).to_string()));
+    assert_eq!(
+      *broadcast.lock().unwrap(),
+      transaction.raw_bytes(&signature).unwrap()
+    );
+    assert!(verify_address(&address, &transaction.signing_bytes(), &signature).is_ok());
+  }
+
+  #[test]
+  fn it_checks_the_transaction_against_the_policy() {
+    let (handler, keychain, address) = handler();
+    keychain
+      .write()
+      .unwrap()
+      .set_policy(SigningPolicy::new().with_no_blind_signing());
+    let handler = handler.with_transaction_sender(|_: &[u8]| -> Result<TxHash, RpcError> {
+      panic!("The transaction must not be broadcast")
+    });
+    let mut transaction = transaction(&address);
+    transaction["data"] = json!("0xdeadbeef");
+
+    let response = call(&handler, "eth_sendTransaction", json!([transaction]));
+
+    assert_eq!(response["error"]["code"], json!(4001));
+  }
+
+  #[test]
+  fn it_rejects_incomplete_transactions() {
+    let (handler, _, address) = handler();
+    let handler = handler.with_transaction_sender(|_: &[u8]| Ok(TxHash::default()));
+    let mut transaction = transaction(&address);
+    transaction.as_object_mut().unwrap().remove("nonce");
+
+    let response = call(&handler, "eth_sendTransaction", json!([transaction]));
+
+    assert_eq!(response["error"]["code"], json!(-32602));
+  }
+
+  #[test]
+  fn it_refuses_unknown_senders() {
+    let (handler, _, _) = handler();
+    let handler = handler.with_transaction_sender(|_: &[u8]| Ok(TxHash::default()));
+
+    let response = call(
+      &handler,
+      "eth_sendTransaction",
+      json!([{ "from": "0x0000000000000000000000000000000000000001" }]),
+    );
+
+    assert_eq!(response["error"]["code"], json!(4100));
+  }
+}
+
+mod eth_sign_transaction {
+  use utils::{crypto::sha3::keccak256, hex::encode_prefixed};
+
+  use super::*;
+
+  fn transaction(address: &str) -> Value {
+    json!({
+      "from": address,
+      "to": address,
+      "value": "0x1",
+      "chainId": "0x1",
+      "nonce": "0x0",
+      "gas": "0x5208",
+      "maxFeePerGas": "0x3b9aca00",
+      "maxPriorityFeePerGas": "0x3b9aca00",
+    })
+  }
+
+  #[test]
+  fn it_returns_the_signed_transaction() {
+    let (handler, keychain, address) = handler();
+
+    let response = call(
+      &handler,
+      "eth_signTransaction",
+      json!([transaction(&address)]),
+    );
+
+    let transaction = RpcTransaction::try_from(&transaction(&address)).unwrap();
+    let signature = keychain
+      .sign_with_context(
+        &address,
+        &transaction.signing_bytes(),
+        &SignatureOptions {
+          recoverable: true,
+          ..Default::default()
+        },
+        &transaction.context(),
+      )
+      .unwrap();
+    let raw = transaction.raw_bytes(&signature).unwrap();
+    let signed = &response["result"]["tx"];
+    assert_eq!(response["result"]["raw"], json!(encode_prefixed(&raw)));
+    assert_eq!(signed["hash"], json!(TxHash(keccak256(&raw)).to_string()));
+    assert_eq!(signed["to"], json!(address));
+    assert_eq!(signed["value"], json!("0x1"));
+    assert_eq!(
+      signed["yParity"],
+      json!(format!("{:#x}", signature.recovery_id().unwrap()))
+    );
+    assert_eq!(
+      Signature::from_rsv_hex(&format!(
+        "0x{:0>64}{:0>64}{:02x}",
+        signed["r"].as_str().unwrap().trim_start_matches("0x"),
+        signed["s"].as_str().unwrap().trim_start_matches("0x"),
+        signature.recovery_id().unwrap() + 27,
+      ))
+      .unwrap(),
+      signature
+    );
+  }
+
+  #[test]
+  fn it_does_not_need_a_sender() {
+    let (handler, _, address) = handler();
+
+    let response = call(
+      &handler,
+      "eth_signTransaction",
+      json!([transaction(&address)]),
+    );
+
+    assert!(response["result"]["raw"].is_string());
+  }
+
+  #[test]
+  fn it_checks_the_transaction_against_the_policy() {
+    let (handler, keychain, address) = handler();
+    keychain
+      .write()
+      .unwrap()
+      .set_policy(SigningPolicy::new().with_no_blind_signing());
+    let mut transaction = transaction(&address);
+    transaction["data"] = json!("0xdeadbeef");
+
+    let response = call(&handler, "eth_signTransaction", json!([transaction]));
+
+    assert_eq!(response["error"]["code"], json!(4001));
+  }
+
+  #[test]
+  fn it_refuses_unknown_senders() {
+    let (handler, _, _) = handler();
+
+    let response = call(
+      &handler,
+      "eth_signTransaction",
+      json!([{ "from": "0x0000000000000000000000000000000000000001" }]),
+    );
+
+    assert_eq!(response["error"]["code"], json!(4100));
+  }
+}
+
+mod from_raw_hex {
+  use utils::hex::encode_prefixed;
+
+  use super::*;
+
+  const FROM: &str = "0x1111111111111111111111111111111111111111";
+
+  fn transaction() -> RpcTransaction {
+    RpcTransaction::try_from(&json!({
+      "from": FROM,
+      "to": "0x2222222222222222222222222222222222222222",
+      "value": "0xde0b6b3a7640000",
+      "data": "0xa9059cbb",
+      "chainId": "0x89",
+      "nonce": "0x7",
+      "gas": "0x5208",
+      "maxFeePerGas": "0x3b9aca00",
+      "maxPriorityFeePerGas": "0x1",
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn it_decodes_an_unsigned_transaction() {
+    let transaction = transaction();
+
+    assert_eq!(
+      RpcTransaction::from_raw_hex(&encode_prefixed(&transaction.signing_bytes()), FROM).unwrap(),
+      transaction
+    );
+  }
+
+  #[test]
+  fn it_decodes_contract_deployments() {
+    let transaction = RpcTransaction {
+      to: None,
+      ..transaction()
+    };
+
+    assert_eq!(
+      RpcTransaction::from_raw_hex(&encode_prefixed(&transaction.signing_bytes()), FROM).unwrap(),
+      transaction
+    );
+  }
+
+  #[test]
+  fn it_displays_the_decoded_fields() {
+    let transaction = transaction();
+
+    let fields = transaction.to_json();
+
+    assert_eq!(fields["value"], json!("0xde0b6b3a7640000"));
+    assert_eq!(fields["input"], json!("0xa9059cbb"));
+    assert_eq!(RpcTransaction::try_from(&fields).unwrap(), transaction);
+  }
+
+  #[test]
+  fn it_rejects_unsupported_transactions() {
+    let transaction = transaction();
+    let signed = transaction
+      .raw_bytes(&Signature::from_rsv_hex(&format!("0x{}1b", "11".repeat(64))).unwrap())
+      .unwrap();
+    // An access list holding an empty entry, in a list short enough
+    // for its length to be encoded in its first byte
+    let mut with_access_list = transaction.signing_bytes();
+    *with_access_list.last_mut().unwrap() = 0xc1;
+    with_access_list.push(0xc0);
+    with_access_list[1] += 1;
+
+    for (raw, message) in [
+      ("0xzz".to_string(), "Invalid hex"),
+      ("0xc0".to_string(), "Unsupported transaction type"),
+      (encode_prefixed(&signed), "Invalid transaction fields"),
+      (
+        encode_prefixed(&with_access_list),
+        "Access lists are not supported",
+      ),
+    ] {
+      assert_eq!(
+        RpcTransaction::from_raw_hex(&raw, FROM),
+        Err(RpcError::invalid_params(message))
+      );
+    }
+  }
+}
+
+mod sign_transaction {
+  use utils::crypto::sha3::keccak256;
+
+  use super::*;
+
+  #[test]
+  fn it_returns_the_raw_signed_transaction() {
+    let (_, keychain, address) = handler();
+    let transaction = RpcTransaction::try_from(&json!({
+      "from": address,
+      "chainId": "0x1",
+      "nonce": "0x0",
+      "gas": "0x5208",
+      "maxFeePerGas": "0x1",
+      "maxPriorityFeePerGas": "0x1",
+    }))
+    .unwrap();
+
+    let signed = keychain
+      .write()
+      .unwrap()
+      .sign_transaction(&transaction)
+      .unwrap();
+
+    let signature = keychain
+      .sign_with_context(
+        &address,
+        &transaction.signing_bytes(),
+        &SignatureOptions {
+          recoverable: true,
+          ..Default::default()
+        },
+        &transaction.context(),
+      )
+      .unwrap();
+    assert_eq!(signed.raw, transaction.raw_bytes(&signature).unwrap());
+    assert_eq!(signed.hash, TxHash(keccak256(&signed.raw)));
+    assert_eq!(signed.transaction, transaction);
+  }
+
+  #[test]
+  fn it_checks_the_transaction_against_the_policy() {
+    let (_, keychain, address) = handler();
+    let mut keychain = keychain.write().unwrap();
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+    let transaction = RpcTransaction::try_from(&json!({
+      "from": address,
+      "data": "0xdeadbeef",
+      "chainId": "0x1",
+      "nonce": "0x0",
+      "gas": "0x5208",
+      "maxFeePerGas": "0x1",
+      "maxPriorityFeePerGas": "0x1",
+    }))
+    .unwrap();
+
+    assert!(matches!(
+      keychain.sign_transaction(&transaction),
+      Err(KeychainError::PolicyViolation(_))
+    ));
+  }
+}
+
+mod handle {
+  use super::*;
+
+  #[test]
+  fn it_answers_batches() {
+    let (handler, _, _) = handler();
+
+    let response = handler.handle(&json!([
+      { "jsonrpc": "2.0", "id": 1, "method": "eth_accounts" },
+      { "jsonrpc": "2.0", "id": 2, "method": "eth_chainId" }
+    ]));
+
+    assert_eq!(response[0]["id"], json!(1));
+    assert_eq!(response[1]["error"]["code"], json!(-32601));
+  }
+
+  #[test]
+  fn it_reports_parse_errors() {
+    let (handler, _, _) = handler();
+
+    let response = serde_json::from_str::<Value>(&handler.handle_str("{")).unwrap();
+
+    assert_eq!(response["error"]["code"], json!(-32700));
+  }
+}
+
+mod rpc_server {
+  use super::*;
+
+  fn serve(server: RpcServer<hdkey::HDKey>) -> SocketAddr {
+    let address = server.local_addr().unwrap();
+    thread::spawn(move || server.serve());
+
+    address
+  }
+
+  fn send(address: SocketAddr, request: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    response
+  }
+
+  fn post(server: RpcServer<hdkey::HDKey>, headers: &str, body: &str) -> String {
+    send(
+      serve(server),
+      &format!(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\n\r\n{}",
+        headers,
+        body.len(),
+        body
+      ),
+    )
+  }
+
+  #[test]
+  fn it_answers_over_http() {
+    let (handler, _, address) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler).unwrap();
+
+    let response = post(
+      server,
+      "",
+      r#"{"jsonrpc":"2.0","id":7,"method":"eth_accounts"}"#,
+    );
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains(&address));
+  }
+
+  #[test]
+  fn it_refuses_requests_from_web_pages() {
+    let (handler, _, _) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler).unwrap();
+
+    let response = post(
+      server,
+      "Origin: https://example.com\r\n",
+      r#"{"jsonrpc":"2.0","id":7,"method":"eth_accounts"}"#,
+    );
+
+    assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+  }
+
+  #[test]
+  fn it_accepts_allowed_origins() {
+    let (handler, _, _) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler)
+      .unwrap()
+      .allow_origin("https://example.com");
+
+    let response = post(
+      server,
+      "Origin: https://example.com\r\n",
+      r#"{"jsonrpc":"2.0","id":7,"method":"eth_accounts"}"#,
+    );
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+  }
+
+  #[test]
+  fn it_refuses_overflowing_content_lengths() {
+    let (handler, _, _) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler).unwrap();
+
+    let response = send(
+      serve(server),
+      "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 18446744073709551615\r\n\r\n",
+    );
+
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large"));
+  }
+
+  #[test]
+  fn it_keeps_serving_after_a_dropped_connection() {
+    let (handler, _, _) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler).unwrap();
+    let address = serve(server);
+
+    drop(TcpStream::connect(address).unwrap());
+    let response = send(
+      address,
+      "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n[]",
+    );
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+  }
+
+  #[test]
+  fn it_drops_requests_sent_too_slowly() {
+    let (handler, _, _) = handler();
+    let server = RpcServer::bind("127.0.0.1:0", handler)
+      .unwrap()
+      .with_request_timeout(Duration::from_millis(200));
+    let address = serve(server);
+
+    // A client sending a byte at a time, never completing its request
+    let mut slow = TcpStream::connect(address).unwrap();
+    thread::spawn(move || {
+      for _ in 0..100 {
+        if slow.write_all(b"P").is_err() {
+          break;
+        }
+        thread::sleep(Duration::from_millis(50));
+      }
+    });
+    thread::sleep(Duration::from_millis(50));
+
+    let started = Instant::now();
+    let response = send(
+      address,
+      "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n[]",
+    );
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+  }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use hdkey::hdkey_factory;
+use vault::KdfParams;
+use walleth_keychain::{Keychain, KeychainError, SessionToken};
+
+const PASSWORD: &str = "password";
+
+fn locked_keychain() -> (Keychain, SessionToken) {
+  let mut keychain = Keychain::new();
+  keychain.set_kdf_params(KdfParams::new(10));
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  keychain.lock(PASSWORD).unwrap();
+  let token = keychain.unlock_with_session(PASSWORD).unwrap();
+  keychain.lock(PASSWORD).unwrap();
+
+  (keychain, token)
+}
+
+mod unlock_with_token {
+  use super::*;
+
+  #[test]
+  fn it_unlocks_without_the_password() {
+    let (mut keychain, token) = locked_keychain();
+
+    keychain.unlock_with_token(&token).unwrap();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_unlocks_every_vault() {
+    let mut keychain = Keychain::new();
+    keychain.set_kdf_params(KdfParams::new(10));
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+    let token = keychain.unlock_with_session(PASSWORD).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    keychain.unlock_with_token(&token).unwrap();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_still_unlocks_with_the_password() {
+    let (mut keychain, _) = locked_keychain();
+
+    keychain.unlock(PASSWORD).unwrap();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_unlocks_with_a_stored_token() {
+    let (mut keychain, token) = locked_keychain();
+    let stored = token.as_bytes().to_vec();
+
+    keychain
+      .unlock_with_token(&SessionToken::from_bytes(&stored).unwrap())
+      .unwrap();
+
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_fails_with_another_token() {
+    let (mut keychain, _) = locked_keychain();
+    let token = SessionToken::from_bytes(&[7u8; 32]).unwrap();
+
+    assert!(matches!(
+      keychain.unlock_with_token(&token),
+      Err(KeychainError::InvalidSessionToken)
+    ));
+    assert!(keychain.is_locked());
+  }
+
+  #[test]
+  fn it_fails_once_expired() {
+    let mut keychain = Keychain::new();
+    keychain.set_kdf_params(KdfParams::new(10));
+    keychain.set_session_ttl(Duration::ZERO);
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+    let token = keychain.unlock_with_session(PASSWORD).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+
+    assert!(matches!(
+      keychain.unlock_with_token(&token),
+      Err(KeychainError::SessionExpired)
+    ));
+  }
+
+  #[test]
+  fn it_fails_after_the_session_ends() {
+    let (mut keychain, token) = locked_keychain();
+
+    keychain.end_session();
+
+    assert!(matches!(
+      keychain.unlock_with_token(&token),
+      Err(KeychainError::InvalidSessionToken)
+    ));
+  }
+
+  #[test]
+  fn it_revokes_tokens_when_changing_the_password() {
+    let (mut keychain, token) = locked_keychain();
+
+    keychain.change_password(PASSWORD, "new password").unwrap();
+
+    assert!(keychain.unlock_with_token(&token).is_err());
+  }
+
+  #[test]
+  fn it_rejects_tokens_of_the_wrong_length() {
+    assert!(matches!(
+      SessionToken::from_bytes(&[0u8; 16]),
+      Err(KeychainError::InvalidSessionToken)
+    ));
+  }
+
+  #[test]
+  fn it_does_not_print_the_token() {
+    let (_, token) = locked_keychain();
+
+    assert_eq!(format!("{:?}", token), "SessionToken(****)");
+  }
+}
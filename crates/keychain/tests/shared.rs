@@ -0,0 +1,368 @@
+use std::{
+  sync::{Arc, Condvar, Mutex},
+  thread,
+  time::Duration,
+};
+
+use identity::{signer::SignatureOptions, verify_address};
+use walleth_keychain::{
+  DuplicateAction, KeychainError, PayloadLedger, PolicyViolation, SharedKeychain, SigningContext,
+  SigningPolicy, SpendingLimit,
+};
+
+mod common;
+use common::keychain_with_accounts;
+
+fn shared_keychain(accounts: usize) -> (SharedKeychain, Vec<String>) {
+  let (keychain, addresses) = keychain_with_accounts(accounts);
+
+  (SharedKeychain::new(keychain), addresses)
+}
+
+fn recoverable() -> SignatureOptions {
+  SignatureOptions {
+    recoverable: true,
+    ..Default::default()
+  }
+}
+
+mod read_accounts {
+  use super::*;
+
+  #[test]
+  fn it_reads_the_accounts() {
+    let (shared, addresses) = shared_keychain(3);
+
+    let accounts = shared.read_accounts().unwrap();
+
+    assert_eq!(
+      accounts
+        .into_iter()
+        .map(|account| account.address)
+        .collect::<Vec<String>>(),
+      addresses
+    );
+  }
+}
+
+mod with_signer {
+  use super::*;
+
+  #[test]
+  fn it_signs_with_the_bound_account() {
+    let (shared, addresses) = shared_keychain(1);
+
+    let signature = shared
+      .with_signer(&addresses[0], |signer| {
+        signer.sign(b"Hello world!", &recoverable())
+      })
+      .unwrap()
+      .unwrap();
+
+    assert!(verify_address(&addresses[0], b"Hello world!", &signature).is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_an_unknown_account() {
+    let (shared, _) = shared_keychain(1);
+
+    assert!(matches!(
+      shared.with_signer("0x0000000000000000000000000000000000000000", |_| ()),
+      Err(KeychainError::KeyNotFoundForAddress(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_while_locked() {
+    let (shared, addresses) = shared_keychain(1);
+    shared.write().unwrap().lock("password").unwrap();
+
+    let result = shared
+      .with_signer(&addresses[0], |signer| {
+        signer.sign(b"Hello world!", &recoverable())
+      })
+      .unwrap();
+
+    assert!(result.is_err());
+  }
+}
+
+mod sign {
+  use super::*;
+
+  #[test]
+  fn it_signs_concurrently_from_multiple_threads() {
+    let (shared, addresses) = shared_keychain(4);
+
+    let handles: Vec<_> = addresses
+      .iter()
+      .cloned()
+      .map(|address| {
+        let shared = shared.clone();
+        thread::spawn(move || {
+          (0..5).all(|nonce| {
+            let message = format!("message {}", nonce);
+            let signature = shared
+              .sign(&address, message.as_bytes(), &recoverable())
+              .unwrap();
+            verify_address(&address, message.as_bytes(), &signature).is_ok()
+          })
+        })
+      })
+      .collect();
+
+    assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+    assert_eq!(shared.read().unwrap().audit_log().entries().len(), 20);
+    assert!(shared.read().unwrap().audit_log().verify().is_ok());
+  }
+
+  #[test]
+  fn it_does_not_exceed_the_daily_quota_from_multiple_threads() {
+    let (shared, addresses) = shared_keychain(1);
+    let address = addresses[0].clone();
+    shared.write().unwrap().set_policy(
+      SigningPolicy::new()
+        .with_spending_limit(&address, SpendingLimit::new().with_daily_transactions(3)),
+    );
+
+    let handles: Vec<_> = (0..16u8)
+      .map(|nonce| {
+        let (shared, address) = (shared.clone(), address.clone());
+        thread::spawn(move || {
+          let context = SigningContext::transaction(None, 1, &[]);
+          match shared.sign_with_context(&address, &[nonce], &recoverable(), &context) {
+            Ok(_) => Ok(()),
+            Err(KeychainError::PolicyViolation(violation)) => Err(Some(violation)),
+            Err(_) => Err(None),
+          }
+        })
+      })
+      .collect();
+    let results: Vec<_> = handles
+      .into_iter()
+      .map(|handle| handle.join().unwrap())
+      .collect();
+
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 3);
+    assert!(results.iter().all(|result| matches!(
+      result,
+      Ok(()) | Err(Some(PolicyViolation::DailyTransactionsExceeded { .. }))
+    )));
+  }
+
+  #[test]
+  fn it_signs_a_payload_once_from_multiple_threads() {
+    let (shared, addresses) = shared_keychain(1);
+    let address = addresses[0].clone();
+    shared
+      .write()
+      .unwrap()
+      .set_payload_ledger(Some(PayloadLedger::new(
+        Duration::from_secs(60),
+        DuplicateAction::Refuse,
+      )));
+
+    let handles: Vec<_> = (0..16)
+      .map(|_| {
+        let (shared, address) = (shared.clone(), address.clone());
+        thread::spawn(move || shared.sign(&address, b"payload", &recoverable()).is_ok())
+      })
+      .collect();
+
+    assert_eq!(
+      handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|signed| *signed)
+        .count(),
+      1
+    );
+  }
+}
+
+mod sign_in_parallel {
+  use hdkey::{HDKey, HDKeyError};
+  use identity::{
+    signer::{IntoSignable, Signature},
+    Account, DerivationPath, GenericIdentity, IdentityError, IdentityFactoryRegistry,
+    Initializable, MultiKeyPair,
+  };
+  use utils::{Controller, PublicKeyBytes};
+  use walleth_keychain::{current_day, Keychain};
+
+  use super::*;
+
+  /// A meeting point of the signers of a `RendezvousKey`
+  #[derive(Debug)]
+  struct Rendezvous {
+    arrived: Mutex<usize>,
+    condvar: Condvar,
+    expected: usize,
+    timeout: Duration,
+  }
+
+  impl Rendezvous {
+    fn new(expected: usize, timeout: Duration) -> Arc<Self> {
+      Arc::new(Self {
+        arrived: Mutex::new(0),
+        condvar: Condvar::new(),
+        expected,
+        timeout,
+      })
+    }
+
+    /// Wait for the expected signers, returning whether they all arrived
+    fn arrive(&self) -> bool {
+      let mut arrived = self.arrived.lock().unwrap();
+      *arrived += 1;
+      self.condvar.notify_all();
+      let (arrived, _) = self
+        .condvar
+        .wait_timeout_while(arrived, self.timeout, |arrived| *arrived < self.expected)
+        .unwrap();
+
+      *arrived >= self.expected
+    }
+  }
+
+  /// An HD key failing to sign unless its signers meet at a rendezvous
+  #[derive(Debug)]
+  struct RendezvousKey {
+    key: HDKey,
+    rendezvous: Arc<Rendezvous>,
+  }
+
+  impl GenericIdentity for RendezvousKey {
+    fn identity_type(&self) -> String {
+      "RendezvousKey".to_string()
+    }
+
+    fn fingerprint(&self) -> [u8; 4] {
+      self.key.fingerprint()
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+      self.key.serialize()
+    }
+
+    fn deserialize(&mut self, bytes: &[u8]) -> Result<(), Box<dyn IdentityError>> {
+      self.key.deserialize(bytes)
+    }
+  }
+
+  impl MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath> for RendezvousKey {
+    fn private_key_at(&self, path: DerivationPath) -> Result<[u8; 32], Box<dyn IdentityError>> {
+      self.key.private_key_at(path)
+    }
+
+    fn public_key_at(
+      &self,
+      path: DerivationPath,
+    ) -> Result<PublicKeyBytes, Box<dyn IdentityError>> {
+      self.key.public_key_at(path)
+    }
+
+    fn sign(
+      &self,
+      from: &Account,
+      message: &dyn IntoSignable,
+      options: &SignatureOptions,
+    ) -> Result<Signature, Box<dyn IdentityError>> {
+      match self.rendezvous.arrive() {
+        true => self.key.sign(from, message, options),
+        false => Err(HDKeyError::InvalidSignature.into()),
+      }
+    }
+
+    fn verify(
+      &self,
+      from: &Account,
+      message: &dyn IntoSignable,
+      signature: &Signature,
+    ) -> Result<(), Box<dyn IdentityError>> {
+      self.key.verify(from, message, signature)
+    }
+  }
+
+  fn shared_rendezvous_keychain(
+    rendezvous: Arc<Rendezvous>,
+  ) -> (SharedKeychain<RendezvousKey>, Vec<String>) {
+    let mut keychain = Keychain::with_registry(IdentityFactoryRegistry::new());
+    keychain
+      .add_multi_keypair(
+        |rendezvous| {
+          Ok(RendezvousKey {
+            key: HDKey::new(),
+            rendezvous,
+          })
+        },
+        rendezvous,
+      )
+      .unwrap();
+    let addresses = keychain
+      .derive_range(0, 0, 2)
+      .unwrap()
+      .into_iter()
+      .map(|account| account.address)
+      .collect();
+
+    (SharedKeychain::new(keychain), addresses)
+  }
+
+  #[test]
+  fn it_signs_with_two_accounts_at_the_same_time() {
+    let (shared, addresses) =
+      shared_rendezvous_keychain(Rendezvous::new(2, Duration::from_secs(10)));
+    // Both requests wait for the write lock, so that each is
+    // reserved before the other is signed
+    let guard = shared.write().unwrap();
+
+    let handles: Vec<_> = addresses
+      .iter()
+      .cloned()
+      .map(|address| {
+        let shared = shared.clone();
+        thread::spawn(move || shared.sign(&address, b"payload", &recoverable()).is_ok())
+      })
+      .collect();
+    thread::sleep(Duration::from_millis(100));
+    drop(guard);
+
+    assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+    assert_eq!(shared.read().unwrap().audit_log().entries().len(), 2);
+  }
+
+  #[test]
+  fn it_releases_the_reservation_when_signing_fails() {
+    let (shared, addresses) =
+      shared_rendezvous_keychain(Rendezvous::new(2, Duration::from_millis(10)));
+    let address = addresses[0].clone();
+    {
+      let mut keychain = shared.write().unwrap();
+      keychain.set_policy(
+        SigningPolicy::new()
+          .with_spending_limit(&address, SpendingLimit::new().with_daily_transactions(1)),
+      );
+      keychain.set_payload_ledger(Some(PayloadLedger::new(
+        Duration::from_secs(60),
+        DuplicateAction::Refuse,
+      )));
+    }
+    let context = SigningContext::transaction(None, 1, &[]);
+
+    assert!(shared
+      .sign_with_context(&address, b"payload", &recoverable(), &context)
+      .is_err());
+
+    let mut keychain = shared.write().unwrap();
+    assert_eq!(
+      keychain
+        .get_state()
+        .quota_usage(&address, current_day())
+        .transactions,
+      0
+    );
+    assert!(keychain.payload_ledger_mut().unwrap().is_empty());
+    assert!(keychain.audit_log().entries().is_empty());
+  }
+}
@@ -0,0 +1,253 @@
+use std::{
+  str::FromStr,
+  time::{Duration, UNIX_EPOCH},
+};
+
+use hdkey::hdkey_factory;
+use identity::signer::Signature;
+use walleth_keychain::{Keychain, SiweError, SiweMessage, SiweVerification};
+
+const MESSAGE: &str = "service.org wants you to sign in with your Ethereum account:
+0xe5A12547fe4E872D192E3eCecb76F2Ce1aeA4946
+
+I accept the ServiceOrg Terms of Service: https://service.org/tos
+
+URI: https://service.org/login
+Version: 1
+Chain ID: 1
+Nonce: 32891757
+Issued At: 2021-09-30T16:25:24.000Z
+Expiration Time: 2021-10-30T16:25:24+02:00
+Resources:
+- ipfs://Qme7ss3ARVgxv6rXqVPiikMJ8u2NLgmgszg13pYrDKEoiu
+- https://example.com/my-web2-claim.json";
+
+fn signed_message(keychain: &mut Keychain) -> (SiweMessage, Signature) {
+  keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+  let account = keychain.add_account(0).unwrap();
+  let message = SiweMessage::new(
+    "example.com",
+    &account.address,
+    "https://example.com/login",
+    1,
+    "abcdef1234",
+  )
+  .with_statement("Sign in to Example");
+  let signature = keychain.sign_siwe(&message).unwrap();
+
+  (message, signature)
+}
+
+mod from_str {
+  use super::*;
+
+  #[test]
+  fn it_parses_a_message() {
+    let message = SiweMessage::from_str(MESSAGE).unwrap();
+
+    assert_eq!(message.domain, "service.org");
+    assert_eq!(
+      message.address,
+      "0xe5A12547fe4E872D192E3eCecb76F2Ce1aeA4946"
+    );
+    assert_eq!(
+      message.statement.as_deref(),
+      Some("I accept the ServiceOrg Terms of Service: https://service.org/tos")
+    );
+    assert_eq!(message.chain_id, 1);
+    assert_eq!(message.nonce, "32891757");
+    assert_eq!(message.resources.len(), 2);
+  }
+
+  #[test]
+  fn it_reencodes_byte_for_byte() {
+    let message = SiweMessage::from_str(MESSAGE).unwrap();
+
+    assert_eq!(message.to_string(), MESSAGE);
+  }
+
+  #[test]
+  fn it_parses_messages_without_statement() {
+    let message = SiweMessage::new(
+      "example.com",
+      "0xe5a12547fe4e872d192e3ececb76f2ce1aea4946",
+      "https://example.com",
+      5,
+      "abcdef1234",
+    );
+
+    assert!(message.to_string().contains("4946\n\n\nURI: "));
+    assert_eq!(
+      SiweMessage::from_str(&message.to_string()).unwrap(),
+      message
+    );
+  }
+
+  #[test]
+  fn it_fails_with_a_malformed_message() {
+    assert!(matches!(
+      SiweMessage::from_str(&MESSAGE.replace("Version: 1", "Version: 2")),
+      Err(SiweError::InvalidMessage(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_an_invalid_timestamp() {
+    assert!(matches!(
+      SiweMessage::from_str(&MESSAGE.replace("2021-09-30T16:25:24.000Z", "yesterday")),
+      Err(SiweError::InvalidTimestamp(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_a_multibyte_offset() {
+    assert!(matches!(
+      SiweMessage::from_str(
+        &MESSAGE.replace("2021-09-30T16:25:24.000Z", "2021-01-01T00:00:00+0\u{20ac}0")
+      ),
+      Err(SiweError::InvalidTimestamp(_))
+    ));
+  }
+
+  #[test]
+  fn it_fails_with_a_signed_offset_component() {
+    assert!(matches!(
+      SiweMessage::from_str(
+        &MESSAGE.replace("2021-09-30T16:25:24.000Z", "2021-01-01T00:00:00+-1:-1")
+      ),
+      Err(SiweError::InvalidTimestamp(_))
+    ));
+  }
+}
+
+mod new {
+  use super::*;
+
+  #[test]
+  fn it_checksums_the_address() {
+    let message = SiweMessage::new(
+      "example.com",
+      "0xe5a12547fe4e872d192e3ececb76f2ce1aea4946",
+      "https://example.com",
+      1,
+      "abcdef1234",
+    );
+
+    assert_eq!(
+      message.address,
+      "0xe5A12547fe4E872D192E3eCecb76F2Ce1aeA4946"
+    );
+  }
+
+  #[test]
+  fn it_formats_timestamps_as_rfc3339() {
+    let message = SiweMessage::new("a.com", "0x00", "https://a.com", 1, "abcdef1234")
+      .with_issued_at(UNIX_EPOCH + Duration::from_secs(1633019124))
+      .with_expiration_time(UNIX_EPOCH + Duration::from_secs(951782400));
+
+    assert_eq!(message.issued_at, "2021-09-30T16:25:24Z");
+    assert_eq!(
+      message.expiration_time.as_deref(),
+      Some("2000-02-29T00:00:00Z")
+    );
+  }
+}
+
+mod verify {
+  use super::*;
+
+  #[test]
+  fn it_verifies_a_message_signed_by_the_keychain() {
+    let mut keychain = Keychain::new();
+    let (message, signature) = signed_message(&mut keychain);
+
+    let verification = SiweVerification {
+      domain: Some("example.com".to_string()),
+      nonce: Some("abcdef1234".to_string()),
+      ..Default::default()
+    };
+
+    assert!(message.verify(&signature, &verification).is_ok());
+  }
+
+  #[test]
+  fn it_verifies_a_parsed_message() {
+    let mut keychain = Keychain::new();
+    let (message, signature) = signed_message(&mut keychain);
+    let parsed = SiweMessage::from_str(&message.to_string()).unwrap();
+
+    assert!(parsed
+      .verify(&signature, &SiweVerification::default())
+      .is_ok());
+  }
+
+  #[test]
+  fn it_fails_with_another_domain() {
+    let mut keychain = Keychain::new();
+    let (message, signature) = signed_message(&mut keychain);
+
+    let verification = SiweVerification {
+      domain: Some("evil.com".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      message.verify(&signature, &verification),
+      Err(SiweError::DomainMismatch)
+    );
+  }
+
+  #[test]
+  fn it_fails_with_another_nonce() {
+    let mut keychain = Keychain::new();
+    let (message, signature) = signed_message(&mut keychain);
+
+    let verification = SiweVerification {
+      nonce: Some("1234abcdef".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      message.verify(&signature, &verification),
+      Err(SiweError::NonceMismatch)
+    );
+  }
+
+  #[test]
+  fn it_checks_the_validity_period() {
+    let message = SiweMessage::from_str(MESSAGE)
+      .unwrap()
+      .with_not_before(UNIX_EPOCH + Duration::from_secs(1633000000));
+    let signature = Signature::from_compact(&[1u8; 64]).unwrap();
+    let at = |secs| SiweVerification {
+      time: Some(UNIX_EPOCH + Duration::from_secs(secs)),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      message.verify(&signature, &at(1632999999)),
+      Err(SiweError::NotYetValid)
+    );
+    // 2021-10-30T14:25:24Z, the expiration time in UTC
+    assert_eq!(
+      message.verify(&signature, &at(1635603924)),
+      Err(SiweError::Expired)
+    );
+    assert_eq!(
+      message.verify(&signature, &at(1635603923)),
+      Err(SiweError::InvalidSignature)
+    );
+  }
+
+  #[test]
+  fn it_fails_with_a_tampered_message() {
+    let mut keychain = Keychain::new();
+    let (message, signature) = signed_message(&mut keychain);
+    let tampered = message.with_resource("https://example.com/admin");
+
+    assert_eq!(
+      tampered.verify(&signature, &SiweVerification::default()),
+      Err(SiweError::InvalidSignature)
+    );
+  }
+}
@@ -0,0 +1,213 @@
+use hdkey::hdkey_factory;
+use identity::signer::SignatureOptions;
+use utils::Controller;
+use walleth_keychain::{
+  Keychain, KeychainError, PairingCode, SyncChannel, SyncError, SyncHandshake, DEFAULT_PROFILE,
+};
+
+const PASSWORD: &str = "password";
+
+fn paired_channels() -> (SyncChannel, SyncChannel) {
+  let code = PairingCode::generate();
+  let (handshake, message) = SyncHandshake::initiate(&code).unwrap();
+  let (responder, response) = SyncHandshake::respond(&code, &message).unwrap();
+
+  (handshake.finish(&response).unwrap(), responder)
+}
+
+mod pairing_code {
+  use super::*;
+
+  #[test]
+  fn it_generates_a_grouped_code() {
+    let code = PairingCode::generate().to_string();
+
+    assert_eq!(code.len(), 19);
+    assert_eq!(code.split('-').count(), 4);
+  }
+
+  #[test]
+  fn it_parses_a_displayed_code() {
+    let code = PairingCode::generate();
+
+    assert_eq!(code.to_string().parse::<PairingCode>().unwrap(), code);
+  }
+
+  #[test]
+  fn it_ignores_whitespace() {
+    let code = "4821 0937 5512 6604".parse::<PairingCode>().unwrap();
+
+    assert_eq!(code.to_string(), "4821-0937-5512-6604");
+  }
+
+  #[test]
+  fn it_rejects_invalid_codes() {
+    assert!(matches!(
+      "4821-0937".parse::<PairingCode>(),
+      Err(SyncError::InvalidPairingCode(_))
+    ));
+    assert!(matches!(
+      "4821-0937-5512-660a".parse::<PairingCode>(),
+      Err(SyncError::InvalidPairingCode(_))
+    ));
+  }
+}
+
+mod sync_channel {
+  use super::*;
+
+  #[test]
+  fn it_exchanges_messages_both_ways() {
+    let (mut initiator, mut responder) = paired_channels();
+
+    let sealed = initiator.seal(b"hello laptop").unwrap();
+    assert_eq!(responder.open(&sealed).unwrap(), b"hello laptop");
+
+    let sealed = responder.seal(b"hello desktop").unwrap();
+    assert_eq!(initiator.open(&sealed).unwrap(), b"hello desktop");
+  }
+
+  #[test]
+  fn it_splits_large_payloads_in_frames() {
+    let (mut initiator, mut responder) = paired_channels();
+    let payload = vec![7u8; 200_000];
+
+    let sealed = initiator.seal(&payload).unwrap();
+
+    assert_eq!(responder.open(&sealed).unwrap(), payload);
+  }
+
+  #[test]
+  fn it_fails_the_handshake_with_a_different_code() {
+    let (_, message) = SyncHandshake::initiate(&PairingCode::generate()).unwrap();
+
+    assert!(matches!(
+      SyncHandshake::respond(&PairingCode::generate(), &message),
+      Err(SyncError::Handshake(_))
+    ));
+  }
+
+  #[test]
+  fn it_rejects_tampered_messages() {
+    let (mut initiator, mut responder) = paired_channels();
+    let mut sealed = initiator.seal(b"hello laptop").unwrap();
+    let last = sealed.len() - 1;
+    sealed[last] ^= 1;
+
+    assert_eq!(responder.open(&sealed), Err(SyncError::Decryption));
+  }
+
+  #[test]
+  fn it_rejects_truncated_messages() {
+    let (mut initiator, mut responder) = paired_channels();
+    let sealed = initiator.seal(b"hello laptop").unwrap();
+
+    assert!(matches!(
+      responder.open(&sealed[..5]),
+      Err(SyncError::UnexpectedMessage(_))
+    ));
+  }
+}
+
+mod sync_export {
+  use super::*;
+
+  #[test]
+  fn it_transfers_selected_vaults() {
+    let (mut desktop_channel, mut laptop_channel) = paired_channels();
+    let mut desktop = Keychain::new();
+    desktop.add_multi_keypair(hdkey_factory, None).unwrap();
+    desktop.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = desktop.add_account(1).unwrap();
+    let mut laptop: Keychain = Keychain::new();
+    laptop.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let sealed = desktop
+      .sync_export(&mut desktop_channel, &[1], PASSWORD)
+      .unwrap();
+    let indexes = laptop
+      .sync_import(&mut laptop_channel, &sealed, PASSWORD)
+      .unwrap();
+
+    assert_eq!(indexes, vec![1]);
+    assert_eq!(laptop.vault_count(), 2);
+    assert!(laptop
+      .get_state()
+      .accounts()
+      .iter()
+      .any(|imported| imported.address == account.address));
+    laptop
+      .use_signer(account.address, b"hello", &SignatureOptions::default())
+      .unwrap();
+  }
+
+  #[test]
+  fn it_does_not_send_plaintext_secrets() {
+    let (mut desktop_channel, mut laptop_channel) = paired_channels();
+    let mut desktop = Keychain::new();
+    desktop.add_multi_keypair(hdkey_factory, None).unwrap();
+
+    let sealed = desktop
+      .sync_export(&mut desktop_channel, &[0], PASSWORD)
+      .unwrap();
+    let mut laptop: Keychain = Keychain::new();
+
+    assert!(matches!(
+      laptop.sync_import(&mut laptop_channel, &sealed, "wrong password"),
+      Err(KeychainError::VaultsUnchanged(_))
+    ));
+  }
+
+  #[test]
+  fn it_keeps_the_profile_of_the_vaults() {
+    let (mut desktop_channel, mut laptop_channel) = paired_channels();
+    let mut desktop = Keychain::new();
+    desktop.create_profile("work").unwrap();
+    desktop.switch_profile("work").unwrap();
+    desktop.add_multi_keypair(hdkey_factory, None).unwrap();
+    let mut laptop: Keychain = Keychain::new();
+
+    let sealed = desktop
+      .sync_export(&mut desktop_channel, &[0], PASSWORD)
+      .unwrap();
+    laptop
+      .sync_import(&mut laptop_channel, &sealed, PASSWORD)
+      .unwrap();
+
+    assert_eq!(laptop.get_state().profile("work").unwrap().vaults, vec![0]);
+    assert_eq!(laptop.active_profile(), DEFAULT_PROFILE);
+  }
+
+  #[test]
+  fn it_rejects_sections_other_than_vaults_and_profiles() {
+    let (mut desktop_channel, mut laptop_channel) = paired_channels();
+    let mut desktop = Keychain::new();
+    desktop.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = desktop.add_account(0).unwrap();
+    desktop
+      .set_account_label(&account.address, Some("savings"))
+      .unwrap();
+    let mut laptop: Keychain = Keychain::new();
+
+    let backup = desktop.backup(PASSWORD).unwrap();
+    let sealed = desktop_channel.seal(&backup).unwrap();
+
+    assert!(matches!(
+      laptop.sync_import(&mut laptop_channel, &sealed, PASSWORD),
+      Err(KeychainError::ByteDeserializationError(_))
+    ));
+    assert!(laptop.get_state().vaults.is_empty());
+    assert!(laptop.get_state().labels.is_empty());
+  }
+
+  #[test]
+  fn it_fails_with_an_unknown_vault() {
+    let (mut desktop_channel, _) = paired_channels();
+    let mut desktop: Keychain = Keychain::new();
+
+    assert!(matches!(
+      desktop.sync_export(&mut desktop_channel, &[0], PASSWORD),
+      Err(KeychainError::KeyNotFoundForIndex(0))
+    ));
+  }
+}
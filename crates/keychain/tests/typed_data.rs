@@ -0,0 +1,349 @@
+use hdkey::hdkey_factory;
+use identity::signer::verify_address;
+use walleth_keychain::{Keychain, TypedData, TypedDataError};
+
+/// The example of EIP-712
+const MAIL: &str = r#"{
+  "types": {
+    "EIP712Domain": [
+      { "name": "name", "type": "string" },
+      { "name": "version", "type": "string" },
+      { "name": "chainId", "type": "uint256" },
+      { "name": "verifyingContract", "type": "address" }
+    ],
+    "Person": [
+      { "name": "name", "type": "string" },
+      { "name": "wallet", "type": "address" }
+    ],
+    "Mail": [
+      { "name": "from", "type": "Person" },
+      { "name": "to", "type": "Person" },
+      { "name": "contents", "type": "string" }
+    ]
+  },
+  "primaryType": "Mail",
+  "domain": {
+    "name": "Ether Mail",
+    "version": "1",
+    "chainId": 1,
+    "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+  },
+  "message": {
+    "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+    "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+    "contents": "Hello, Bob!"
+  }
+}"#;
+
+mod typed_data {
+  use super::*;
+
+  #[test]
+  fn it_encodes_types_with_their_dependencies() {
+    let typed_data = MAIL.parse::<TypedData>().unwrap();
+
+    assert_eq!(
+      typed_data.encode_type("Mail").unwrap(),
+      "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+    );
+  }
+
+  #[test]
+  fn it_hashes_the_eip_712_example() {
+    let typed_data = MAIL.parse::<TypedData>().unwrap();
+
+    assert_eq!(
+      typed_data.domain_separator().unwrap().to_string(),
+      "0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    assert_eq!(
+      typed_data.message_hash().unwrap().to_string(),
+      "0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+    );
+    assert_eq!(
+      typed_data.digest().unwrap().to_string(),
+      "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+  }
+
+  #[test]
+  fn it_encodes_arrays_and_integers() {
+    let typed_data = r#"{
+      "types": {
+        "EIP712Domain": [{ "name": "chainId", "type": "uint256" }],
+        "Batch": [
+          { "name": "amounts", "type": "int256[]" },
+          { "name": "deadline", "type": "uint256" }
+        ]
+      },
+      "primaryType": "Batch",
+      "domain": { "chainId": "0x1" },
+      "message": { "amounts": [-1, "2", "0x03"], "deadline": "115792089237316195423570985008687907853269984665640564039457584007913129639935" }
+    }"#
+      .parse::<TypedData>()
+      .unwrap();
+
+    assert!(typed_data.digest().is_ok());
+  }
+
+  #[test]
+  fn it_rejects_unknown_types() {
+    let typed_data = MAIL
+      .replace(r#""type": "Person" }"#, r#""type": "Persona" }"#)
+      .parse::<TypedData>()
+      .unwrap();
+
+    assert_eq!(
+      typed_data.digest(),
+      Err(TypedDataError::UnknownType("Persona".to_string()))
+    );
+  }
+
+  #[test]
+  fn it_rejects_invalid_values() {
+    let typed_data = MAIL
+      .replace("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB", "0xbBbB")
+      .parse::<TypedData>()
+      .unwrap();
+
+    assert_eq!(
+      typed_data.digest(),
+      Err(TypedDataError::InvalidValue("wallet".to_string()))
+    );
+  }
+
+  #[test]
+  fn it_rejects_negative_unsigned_integers() {
+    let typed_data = MAIL
+      .replace(r#""chainId": 1"#, r#""chainId": -1"#)
+      .parse::<TypedData>()
+      .unwrap();
+
+    assert_eq!(
+      typed_data.digest(),
+      Err(TypedDataError::InvalidValue("chainId".to_string()))
+    );
+  }
+
+  fn signed(r#type: &str, value: &str) -> TypedData {
+    format!(
+      r#"{{
+        "types": {{
+          "EIP712Domain": [],
+          "Signed": [{{ "name": "value", "type": "{}" }}]
+        }},
+        "primaryType": "Signed",
+        "domain": {{}},
+        "message": {{ "value": {} }}
+      }}"#,
+      r#type, value
+    )
+    .parse::<TypedData>()
+    .unwrap()
+  }
+
+  #[test]
+  fn it_encodes_signed_integers_in_the_range_of_their_type() {
+    for (r#type, value) in [
+      ("int8", "127"),
+      ("int8", "-128"),
+      ("int8", r#""-0x80""#),
+      ("int16", "-1"),
+      ("int", "-1"),
+      (
+        "int256",
+        r#""57896044618658097711785492504343953926634992332820282019728792003956564819967""#,
+      ),
+      (
+        "int256",
+        r#""-57896044618658097711785492504343953926634992332820282019728792003956564819968""#,
+      ),
+    ] {
+      assert!(
+        signed(r#type, value).digest().is_ok(),
+        "{} {}",
+        r#type,
+        value
+      );
+    }
+  }
+
+  #[test]
+  fn it_rejects_signed_integers_out_of_the_range_of_their_type() {
+    for (r#type, value) in [
+      ("int8", "128"),
+      ("int8", "-129"),
+      ("int8", r#""0xff""#),
+      ("int16", "32768"),
+      (
+        "int256",
+        r#""57896044618658097711785492504343953926634992332820282019728792003956564819968""#,
+      ),
+      (
+        "int256",
+        r#""-57896044618658097711785492504343953926634992332820282019728792003956564819969""#,
+      ),
+    ] {
+      assert_eq!(
+        signed(r#type, value).digest(),
+        Err(TypedDataError::InvalidValue("value".to_string())),
+        "{} {}",
+        r#type,
+        value
+      );
+    }
+  }
+
+  #[test]
+  fn it_rejects_invalid_signed_integer_widths() {
+    for r#type in ["int0", "int7", "int264", "intx"] {
+      assert_eq!(
+        signed(r#type, "1").digest(),
+        Err(TypedDataError::UnknownType(r#type.to_string()))
+      );
+    }
+  }
+}
+
+mod sign_typed_data {
+  use super::*;
+
+  #[test]
+  fn it_signs_the_typed_data_digest() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let account = keychain.add_account(0).unwrap();
+    let typed_data = MAIL.parse::<TypedData>().unwrap();
+
+    let signature = keychain
+      .sign_typed_data(&account.address, &typed_data)
+      .unwrap();
+
+    assert!(verify_address(
+      &account.address,
+      &typed_data.signing_bytes().unwrap(),
+      &signature
+    )
+    .is_ok());
+  }
+}
+
+mod sign_permit {
+  use std::time::Duration;
+
+  use walleth_keychain::{Permit, TokenDomain};
+
+  use super::*;
+
+  const SPENDER: &str = "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB";
+
+  fn token() -> TokenDomain {
+    TokenDomain {
+      name: "USD Coin".to_string(),
+      version: "2".to_string(),
+      chain_id: 1,
+      address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+    }
+  }
+
+  #[test]
+  fn it_signs_with_the_owner() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let owner = keychain.add_account(0).unwrap().address;
+    let permit = Permit::new(
+      token(),
+      &owner,
+      SPENDER,
+      Permit::UNLIMITED,
+      0,
+      Duration::from_secs(60),
+    );
+
+    let signature = keychain.sign_permit(&permit).unwrap();
+
+    let typed_data = permit.to_typed_data().unwrap();
+    assert!(verify_address(&owner, &typed_data.signing_bytes().unwrap(), &signature).is_ok());
+    assert_eq!(typed_data.primary_type, "Permit");
+    assert_eq!(typed_data.chain_id(), Some(1));
+    assert_eq!(
+      typed_data.message["value"],
+      format!("0x{}", "ff".repeat(32))
+    );
+  }
+
+  #[test]
+  fn it_rejects_expired_permits() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let owner = keychain.add_account(0).unwrap().address;
+    let mut permit = Permit::new(token(), &owner, SPENDER, 1.into(), 0, Duration::ZERO);
+    permit.deadline -= 1;
+
+    assert!(permit.is_expired());
+    assert!(matches!(
+      keychain.sign_permit(&permit),
+      Err(walleth_keychain::KeychainError::TypedDataError(
+        TypedDataError::Expired(_)
+      ))
+    ));
+  }
+}
+
+mod sign_permit2 {
+  use std::time::Duration;
+
+  use walleth_keychain::{Permit2, PERMIT2_ADDRESS};
+
+  use super::*;
+
+  #[test]
+  fn it_signs_a_permit_single() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let owner = keychain.add_account(0).unwrap().address;
+    let permit = Permit2::new(
+      10,
+      "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+      "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+      Permit2::UNLIMITED,
+      3,
+      Duration::from_secs(3600),
+      Duration::from_secs(60),
+    );
+
+    keychain.sign_permit2(&owner, &permit).unwrap();
+
+    let typed_data = permit.to_typed_data().unwrap();
+    assert_eq!(
+      typed_data.encode_type("PermitSingle").unwrap(),
+      "PermitSingle(PermitDetails details,address spender,uint256 sigDeadline)\
+       PermitDetails(address token,uint160 amount,uint48 expiration,uint48 nonce)"
+    );
+    assert_eq!(typed_data.domain["verifyingContract"], PERMIT2_ADDRESS);
+    assert_eq!(typed_data.chain_id(), Some(10));
+  }
+
+  #[test]
+  fn it_rejects_amounts_wider_than_160_bits() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let owner = keychain.add_account(0).unwrap().address;
+    let permit = Permit2::new(
+      10,
+      "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+      "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+      walleth_keychain::Permit::UNLIMITED,
+      3,
+      Duration::from_secs(3600),
+      Duration::from_secs(60),
+    );
+
+    assert!(matches!(
+      keychain.sign_permit2(&owner, &permit),
+      Err(walleth_keychain::KeychainError::TypedDataError(
+        TypedDataError::InvalidValue(_)
+      ))
+    ));
+  }
+}
@@ -0,0 +1,526 @@
+use std::str::FromStr;
+
+use identity::{signer::Signature, verify_address, Account, DerivationPath};
+use walleth_keychain::{
+  ur::{self, Cbor},
+  EthDataType, EthSignRequest, EthSignature, Keychain, KeychainError, PolicyViolation,
+  SigningPolicy, SpendingLimit, TypedData, Ur, UrDecoder, UrError,
+};
+
+mod common;
+
+/// Typed data signed on chain 1
+const MAIL: &str = r#"{
+  "types": {
+    "EIP712Domain": [
+      { "name": "name", "type": "string" },
+      { "name": "chainId", "type": "uint256" }
+    ],
+    "Mail": [{ "name": "contents", "type": "string" }]
+  },
+  "primaryType": "Mail",
+  "domain": { "name": "Ether Mail", "chainId": 1 },
+  "message": { "contents": "Hello, Bob!" }
+}"#;
+
+/// The recipient of the test transactions
+const TO: [u8; 20] = [0x11; 20];
+
+/// Encode the RLP fields of a transaction to `TO` transferring
+/// `value` wei with `data`, following the chain id, nonce and fee
+/// fields in `header`, and followed by the encoded `trailer` fields
+fn transaction(header: &[u8], value: u8, data: &[u8], trailer: &[u8]) -> Vec<u8> {
+  let mut payload = header.to_vec();
+  payload.push(0x94);
+  payload.extend(TO);
+  payload.push(value);
+  payload.push(0x80 + data.len() as u8);
+  payload.extend(data);
+  payload.extend(trailer);
+
+  let mut bytes = vec![0xc0 + payload.len() as u8];
+  bytes.extend(payload);
+  bytes
+}
+
+/// Encode a legacy transaction signed with EIP-155 on chain 1
+fn legacy_transaction(value: u8, data: &[u8]) -> Vec<u8> {
+  // nonce 0, gas price 1, gas 21000
+  transaction(
+    &[0x80, 0x01, 0x82, 0x52, 0x08],
+    value,
+    data,
+    &[0x01, 0x80, 0x80],
+  )
+}
+
+/// Encode an EIP-1559 transaction on chain 1
+fn eip1559_transaction(value: u8, data: &[u8]) -> Vec<u8> {
+  // chain id 1, nonce 0, fees of 1 wei, gas 21000 and an empty access list
+  let mut bytes = vec![0x02];
+  bytes.extend(transaction(
+    &[0x01, 0x80, 0x01, 0x01, 0x82, 0x52, 0x08],
+    value,
+    data,
+    &[0xc0],
+  ));
+  bytes
+}
+
+fn keychain_with_account() -> (Keychain, Account) {
+  let (keychain, address) = common::keychain_with_account();
+  let id = keychain.account_id(&address).unwrap();
+  let account = keychain.account(id).unwrap().clone();
+
+  (keychain, account)
+}
+
+mod bytewords {
+  use super::*;
+
+  #[test]
+  fn it_encodes_minimal_bytewords_with_checksum() {
+    assert_eq!(
+      ur::bytewords::encode(&[0, 1, 2, 128, 255]),
+      "aeadaolazmjendeoti"
+    );
+  }
+
+  #[test]
+  fn it_decodes_uppercase_bytewords() {
+    assert_eq!(
+      ur::bytewords::decode("AEADAOLAZMJENDEOTI").unwrap(),
+      vec![0, 1, 2, 128, 255]
+    );
+  }
+
+  #[test]
+  fn it_rejects_a_wrong_checksum() {
+    assert_eq!(
+      ur::bytewords::decode("aeadaolazmjendeoto"),
+      Err(UrError::ChecksumMismatch)
+    );
+  }
+}
+
+mod cbor {
+  use super::*;
+
+  #[test]
+  fn it_encodes_integers_in_the_shortest_form() {
+    assert_eq!(Cbor::Unsigned(23).to_bytes(), vec![0x17]);
+    assert_eq!(Cbor::Unsigned(500).to_bytes(), vec![0x19, 0x01, 0xf4]);
+  }
+
+  #[test]
+  fn it_round_trips_nested_values() {
+    let value = Cbor::Map(vec![
+      (
+        Cbor::Unsigned(1),
+        Cbor::Tag(37, Box::new(Cbor::Bytes(vec![1u8; 16]))),
+      ),
+      (
+        Cbor::Unsigned(2),
+        Cbor::Array(vec![Cbor::Text("walleth".to_string()), Cbor::Bool(true)]),
+      ),
+    ]);
+
+    assert_eq!(Cbor::from_bytes(&value.to_bytes()).unwrap(), value);
+  }
+
+  #[test]
+  fn it_rejects_truncated_input() {
+    assert!(Cbor::from_bytes(&[0x45, 0x01, 0x02]).is_err());
+  }
+
+  #[test]
+  fn it_rejects_trailing_bytes() {
+    assert!(Cbor::from_bytes(&[0x01, 0x02]).is_err());
+  }
+}
+
+mod to_parts {
+  use super::*;
+
+  #[test]
+  fn it_returns_a_single_part_for_short_payloads() {
+    let ur = Ur::new("bytes", vec![1, 2, 3]).unwrap();
+
+    let parts = ur.to_parts(100);
+
+    assert_eq!(parts, vec![ur.to_string()]);
+    assert_eq!(Ur::from_str(&parts[0]).unwrap(), ur);
+  }
+
+  #[test]
+  fn it_splits_long_payloads_in_sequenced_parts() {
+    let ur = Ur::new("bytes", (0..=255).collect()).unwrap();
+
+    let parts = ur.to_parts(50);
+
+    assert_eq!(parts.len(), 6);
+    assert!(parts[0].starts_with("ur:bytes/1-6/"));
+    assert!(parts[5].starts_with("ur:bytes/6-6/"));
+  }
+}
+
+mod ur_decoder {
+  use super::*;
+
+  #[test]
+  fn it_reassembles_parts_in_any_order() {
+    let ur = Ur::new("bytes", (0..=255).collect()).unwrap();
+    let mut decoder = UrDecoder::new();
+
+    for part in ur.to_parts(50).iter().rev().chain(ur.to_parts(50).iter()) {
+      decoder.receive(part).unwrap();
+    }
+
+    assert!(decoder.is_complete());
+    assert_eq!(decoder.result(), Some(&ur));
+  }
+
+  #[test]
+  fn it_reports_progress() {
+    let ur = Ur::new("bytes", (0..=255).collect()).unwrap();
+    let parts = ur.to_parts(50);
+    let mut decoder = UrDecoder::new();
+
+    decoder.receive(&parts[0]).unwrap();
+    decoder.receive(&parts[1]).unwrap();
+
+    assert!(!decoder.is_complete());
+    assert!((decoder.progress() - 2.0 / 6.0).abs() < f64::EPSILON);
+  }
+
+  #[test]
+  fn it_accepts_a_single_part_ur() {
+    let ur = Ur::new("bytes", vec![1, 2, 3]).unwrap();
+    let mut decoder = UrDecoder::new();
+
+    decoder.receive(&ur.to_string().to_uppercase()).unwrap();
+
+    assert_eq!(decoder.result(), Some(&ur));
+  }
+
+  #[test]
+  fn it_rejects_parts_of_another_message() {
+    let first = Ur::new("bytes", (0..=255).collect()).unwrap();
+    let second = Ur::new("bytes", (0..=255).rev().collect()).unwrap();
+    let mut decoder = UrDecoder::new();
+
+    decoder.receive(&first.to_parts(50)[0]).unwrap();
+
+    assert_eq!(
+      decoder.receive(&second.to_parts(50)[1]),
+      Err(UrError::InconsistentPart)
+    );
+  }
+
+  #[test]
+  fn it_rejects_a_part_numbered_zero() {
+    let mut decoder = UrDecoder::new();
+
+    assert_eq!(
+      decoder.receive(&part(0, 2, 20, vec![0u8; 10])),
+      Err(UrError::InconsistentPart)
+    );
+    assert_eq!(decoder.progress(), 0.0);
+  }
+
+  #[test]
+  fn it_rejects_oversized_lengths() {
+    let mut decoder = UrDecoder::new();
+
+    assert_eq!(
+      decoder.receive(&part(1, 1 << 60, 20, vec![0u8; 10])),
+      Err(UrError::InconsistentPart)
+    );
+    assert_eq!(
+      decoder.receive(&part(1, 2, 1 << 60, vec![0u8; 10])),
+      Err(UrError::InconsistentPart)
+    );
+  }
+
+  fn part(number: u64, length: u64, message_length: u64, fragment: Vec<u8>) -> String {
+    let part = Cbor::Array(vec![
+      Cbor::Unsigned(number),
+      Cbor::Unsigned(length),
+      Cbor::Unsigned(message_length),
+      Cbor::Unsigned(0),
+      Cbor::Bytes(fragment),
+    ]);
+
+    format!(
+      "ur:bytes/{}-{}/{}",
+      number,
+      length,
+      ur::bytewords::encode(&part.to_bytes())
+    )
+  }
+}
+
+mod eth_sign_request {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_through_a_ur_string() {
+    let request = EthSignRequest {
+      request_id: Some([7u8; 16]),
+      chain_id: Some(1),
+      source_fingerprint: Some(0x12345678),
+      address: Some([9u8; 20]),
+      origin: Some("hot wallet".to_string()),
+      ..EthSignRequest::new(
+        b"Hello world!".to_vec(),
+        EthDataType::PersonalMessage,
+        DerivationPath::new(1, 0, 4),
+      )
+    };
+
+    let ur = Ur::from_str(&request.to_ur().to_string()).unwrap();
+
+    assert_eq!(ur.ur_type, "eth-sign-request");
+    assert_eq!(EthSignRequest::try_from(&ur).unwrap(), request);
+  }
+
+  #[test]
+  fn it_rejects_another_ur_type() {
+    let ur = Ur::new("bytes", vec![]).unwrap();
+
+    assert_eq!(
+      EthSignRequest::try_from(&ur),
+      Err(UrError::UnexpectedType("bytes".to_string()))
+    );
+  }
+}
+
+mod sign_ur_request {
+  use super::*;
+
+  #[test]
+  fn it_signs_a_personal_message_for_the_request_address() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest {
+      request_id: Some([7u8; 16]),
+      address: Some(account.address_bytes().unwrap()),
+      ..EthSignRequest::new(
+        b"Hello world!".to_vec(),
+        EthDataType::PersonalMessage,
+        account.path,
+      )
+    };
+
+    let response = keychain.sign_ur_request(&request).unwrap();
+    let signature = Signature::from_compact(&response.signature).unwrap();
+
+    assert_eq!(response.request_id, Some([7u8; 16]));
+    assert!([27, 28].contains(&response.signature[64]));
+    verify_address(
+      &account.address,
+      &request.signable_bytes().unwrap(),
+      &signature,
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn it_finds_the_account_by_derivation_path() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest::new(
+      b"Hello world!".to_vec(),
+      EthDataType::PersonalMessage,
+      account.path,
+    );
+
+    let response = keychain.sign_ur_request(&request).unwrap();
+
+    verify_address(
+      &account.address,
+      &request.signable_bytes().unwrap(),
+      &Signature::from_compact(&response.signature).unwrap(),
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn it_applies_eip155_to_legacy_transactions() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest {
+      chain_id: Some(1),
+      ..EthSignRequest::new(
+        legacy_transaction(1, &[]),
+        EthDataType::Transaction,
+        account.path,
+      )
+    };
+
+    let response = keychain.sign_ur_request(&request).unwrap();
+
+    assert!([37, 38].contains(&response.signature[64]));
+  }
+
+  #[test]
+  fn it_round_trips_the_signature_through_a_ur() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest::new(
+      eip1559_transaction(1, &[]),
+      EthDataType::TypedTransaction,
+      account.path,
+    );
+
+    let response = keychain.sign_ur_request(&request).unwrap();
+    let ur = Ur::from_str(&response.to_ur().to_string()).unwrap();
+
+    assert!([0, 1].contains(&response.signature[64]));
+    assert_eq!(EthSignature::try_from(&ur).unwrap(), response);
+  }
+
+  #[test]
+  fn it_decodes_transactions_for_the_policy() {
+    let request = EthSignRequest::new(
+      eip1559_transaction(5, &[0xde, 0xad]),
+      EthDataType::TypedTransaction,
+      DerivationPath::from(0),
+    );
+
+    let context = request.context().unwrap();
+    let transaction = context.transaction.unwrap();
+
+    assert_eq!(context.chain_id, Some(1));
+    assert_eq!(transaction.to, Some(format!("0x{}", "11".repeat(20))));
+    assert_eq!(transaction.value, 5);
+    assert_eq!(transaction.data, vec![0xde, 0xad]);
+  }
+
+  #[test]
+  fn it_applies_the_spending_limits_to_transactions() {
+    let (mut keychain, account) = keychain_with_account();
+    keychain.set_policy(
+      SigningPolicy::new().with_spending_limit(
+        &account.address,
+        SpendingLimit::new()
+          .with_daily_transactions(2)
+          .with_daily_value(2),
+      ),
+    );
+    let request = |sign_data, data_type| EthSignRequest::new(sign_data, data_type, account.path);
+
+    keychain
+      .sign_ur_request(&request(
+        legacy_transaction(1, &[]),
+        EthDataType::Transaction,
+      ))
+      .unwrap();
+
+    assert!(matches!(
+      keychain.sign_ur_request(&request(
+        eip1559_transaction(2, &[]),
+        EthDataType::TypedTransaction
+      )),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DailyValueExceeded { limit: 2, spent: 1 }
+      ))
+    ));
+    keychain
+      .sign_ur_request(&request(
+        eip1559_transaction(1, &[]),
+        EthDataType::TypedTransaction,
+      ))
+      .unwrap();
+    assert!(matches!(
+      keychain.sign_ur_request(&request(
+        legacy_transaction(0, &[]),
+        EthDataType::Transaction
+      )),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::DailyTransactionsExceeded { limit: 2 }
+      ))
+    ));
+  }
+
+  #[test]
+  fn it_refuses_blind_signing_of_transactions() {
+    let (mut keychain, account) = keychain_with_account();
+    keychain.set_policy(SigningPolicy::new().with_no_blind_signing());
+    let request = EthSignRequest::new(
+      legacy_transaction(0, &[0xde, 0xad, 0xbe, 0xef]),
+      EthDataType::Transaction,
+      account.path,
+    );
+
+    assert!(matches!(
+      keychain.sign_ur_request(&request),
+      Err(KeychainError::PolicyViolation(
+        PolicyViolation::BlindSigning(_)
+      ))
+    ));
+  }
+
+  #[test]
+  fn it_rejects_transactions_that_cannot_be_decoded() {
+    let (mut keychain, account) = keychain_with_account();
+
+    for (sign_data, data_type) in [
+      (vec![0xc0], EthDataType::Transaction),
+      (vec![0x02, 0xc1], EthDataType::TypedTransaction),
+      (vec![0x05, 0xc0], EthDataType::TypedTransaction),
+    ] {
+      assert!(matches!(
+        keychain.sign_ur_request(&EthSignRequest::new(sign_data, data_type, account.path)),
+        Err(KeychainError::UrError(UrError::InvalidTransaction(_)))
+      ));
+    }
+  }
+
+  #[test]
+  fn it_signs_typed_data() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest::new(
+      MAIL.as_bytes().to_vec(),
+      EthDataType::TypedData,
+      account.path,
+    );
+
+    let response = keychain.sign_ur_request(&request).unwrap();
+    let expected = keychain
+      .sign_typed_data(&account.address, &MAIL.parse::<TypedData>().unwrap())
+      .unwrap();
+
+    assert_eq!(request.context().unwrap().chain_id, Some(1));
+    assert_eq!(&response.signature[..64], &expected.to_compact()[..]);
+    assert!([27, 28].contains(&response.signature[64]));
+  }
+
+  #[test]
+  fn it_rejects_typed_data_that_cannot_be_parsed() {
+    let (mut keychain, account) = keychain_with_account();
+    let request = EthSignRequest::new(b"{}".to_vec(), EthDataType::TypedData, account.path);
+
+    assert!(matches!(
+      keychain.sign_ur_request(&request),
+      Err(KeychainError::UrError(UrError::InvalidTypedData(_)))
+    ));
+  }
+
+  #[test]
+  fn it_rejects_chain_ids_overflowing_v() {
+    let (mut keychain, account) = keychain_with_account();
+    let entries = keychain.audit_log().entries().len();
+    let request = EthSignRequest {
+      chain_id: Some(u64::MAX / 2),
+      ..EthSignRequest::new(
+        legacy_transaction(1, &[]),
+        EthDataType::Transaction,
+        account.path,
+      )
+    };
+
+    assert_eq!(request.v(0), Err(UrError::ChainIdOutOfRange(u64::MAX / 2)));
+    assert!(matches!(
+      keychain.sign_ur_request(&request),
+      Err(KeychainError::UrError(UrError::ChainIdOutOfRange(_)))
+    ));
+    assert_eq!(keychain.audit_log().entries().len(), entries);
+  }
+}
@@ -0,0 +1,159 @@
+use identity::signer::SignatureOptions;
+use serde_json::json;
+use utils::Controller;
+use walleth_keychain::{AccountUsage, Keychain, SigningContext};
+
+mod common;
+use common::keychain_with_accounts;
+
+mod record_signature {
+  use super::*;
+
+  #[test]
+  fn it_counts_signatures_and_chains() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+
+    keychain
+      .use_signer(addresses[0].clone(), b"first", &SignatureOptions::default())
+      .unwrap();
+    keychain
+      .use_signer_with_context(
+        addresses[0].clone(),
+        b"second",
+        &SignatureOptions::default(),
+        &SigningContext::default().on_chain(10),
+      )
+      .unwrap();
+
+    let usage = keychain.get_state().account_usage(&addresses[0]);
+    assert_eq!(usage.signatures, 2);
+    assert!(usage.last_used.is_some());
+    assert_eq!(usage.chains.into_iter().collect::<Vec<u64>>(), vec![10]);
+  }
+
+  #[test]
+  fn it_does_not_count_rejected_signatures() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain.lock("password").unwrap();
+
+    assert!(keychain
+      .use_signer(
+        addresses[0].clone(),
+        b"payload",
+        &SignatureOptions::default()
+      )
+      .is_err());
+
+    assert_eq!(
+      keychain.get_state().account_usage(&addresses[0]),
+      AccountUsage::default()
+    );
+  }
+}
+
+mod accounts_by_activity {
+  use super::*;
+
+  #[test]
+  fn it_sorts_used_accounts_first() {
+    let (mut keychain, addresses) = keychain_with_accounts(3);
+    keychain
+      .use_signer(
+        addresses[2].clone(),
+        b"payload",
+        &SignatureOptions::default(),
+      )
+      .unwrap();
+
+    let sorted = keychain
+      .get_state()
+      .accounts_by_activity()
+      .into_iter()
+      .map(|account| account.address.clone())
+      .collect::<Vec<String>>();
+
+    assert_eq!(
+      sorted,
+      vec![
+        addresses[2].clone(),
+        addresses[0].clone(),
+        addresses[1].clone()
+      ]
+    );
+  }
+}
+
+mod dormant_accounts {
+  use super::*;
+
+  #[test]
+  fn it_flags_accounts_not_used_since() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .use_signer(
+        addresses[1].clone(),
+        b"payload",
+        &SignatureOptions::default(),
+      )
+      .unwrap();
+    let last_used = keychain
+      .get_state()
+      .account_usage(&addresses[1])
+      .last_used
+      .unwrap();
+
+    let dormant = |since: u64| {
+      keychain
+        .get_state()
+        .dormant_accounts(since)
+        .into_iter()
+        .map(|account| account.address.clone())
+        .collect::<Vec<String>>()
+    };
+
+    assert_eq!(dormant(last_used), vec![addresses[0].clone()]);
+    assert_eq!(dormant(last_used + 1), addresses);
+  }
+}
+
+mod backup {
+  use super::*;
+
+  #[test]
+  fn it_persists_the_usage_of_the_accounts() {
+    let (mut keychain, addresses) = keychain_with_accounts(1);
+    keychain
+      .use_signer_with_context(
+        addresses[0].clone(),
+        b"payload",
+        &SignatureOptions::default(),
+        &SigningContext::default().on_chain(1),
+      )
+      .unwrap();
+
+    let backup = keychain.backup("password").unwrap();
+    let restored: Keychain = Keychain::restore(backup, "password").unwrap();
+
+    assert_eq!(
+      restored.get_state().account_usage(&addresses[0]),
+      keychain.get_state().account_usage(&addresses[0])
+    );
+  }
+}
+
+mod account_usage {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_through_json() {
+    let usage = AccountUsage::default()
+      .with_signature(100, Some(1))
+      .with_signature(50, Some(137));
+
+    assert_eq!(
+      usage.to_json(),
+      json!({ "signatures": 2, "lastUsed": 100, "chains": [1, 137] })
+    );
+    assert_eq!(AccountUsage::try_from(&usage.to_json()).unwrap(), usage);
+  }
+}
@@ -0,0 +1,80 @@
+use utils::Controller;
+
+mod common;
+use common::keychain_with_accounts;
+
+const PASSWORD: &str = "password";
+
+mod view {
+  use super::*;
+
+  #[test]
+  fn it_exposes_accounts_labels_and_balances() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .set_account_label(&addresses[0], Some("savings"))
+      .unwrap();
+    keychain
+      .set_account_snapshot(&addresses[1], Some(42), None)
+      .unwrap();
+
+    let view = keychain.get_state().view();
+
+    assert_eq!(view.accounts().len(), 2);
+    assert_eq!(view.vault_count(), 1);
+    assert_eq!(
+      view.account(&addresses[0]).unwrap().label,
+      Some("savings".to_string())
+    );
+    assert_eq!(view.account(&addresses[1]).unwrap().balance, Some(42));
+  }
+
+  #[test]
+  fn it_reports_lock_status() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+
+    assert!(!keychain.get_state().view().is_locked());
+
+    keychain.lock(PASSWORD).unwrap();
+    let view = keychain.get_state().view();
+
+    assert!(view.is_locked());
+    assert_eq!(view.is_vault_locked(0), Some(true));
+    assert!(view.account(&addresses[0]).unwrap().locked);
+  }
+
+  #[test]
+  fn it_does_not_change_with_the_keychain() {
+    let (mut keychain, _) = keychain_with_accounts(2);
+    let view = keychain.get_state().view();
+
+    keychain.add_account(0).unwrap();
+
+    assert_eq!(view.accounts().len(), 2);
+    assert_ne!(view, keychain.get_state().view());
+  }
+
+  #[test]
+  fn it_is_the_same_as_its_clones() {
+    let (keychain, _) = keychain_with_accounts(2);
+    let view = keychain.get_state().view();
+
+    assert!(view.same(&view.clone()));
+    assert!(view.same(&keychain.get_state().view()));
+  }
+
+  #[test]
+  fn it_serializes_without_secrets() {
+    let (mut keychain, addresses) = keychain_with_accounts(2);
+    keychain
+      .set_account_snapshot(&addresses[0], Some(u128::MAX), None)
+      .unwrap();
+
+    let json = serde_json::to_value(keychain.get_state().view()).unwrap();
+
+    assert_eq!(json["accounts"][0]["address"], addresses[0]);
+    assert_eq!(json["accounts"][0]["balance"], u128::MAX.to_string());
+    assert_eq!(json["vaults"][0], false);
+    assert_eq!(json["active_profile"], "default");
+  }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use keychain::{decoder::format_units, AccountView, KeychainView};
+
+/// The number of decimals of an ether amount in wei
+const ETHER_DECIMALS: u8 = 18;
+
+/// A single row of an account list
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountRow {
+  /// The address of the account
+  pub address: String,
+  /// The label of the account, or its shortened address
+  pub title: String,
+  /// The last tracked balance of the account in ether, if any
+  pub balance: Option<String>,
+  /// Whether the vault holding the account is locked
+  pub locked: bool,
+}
+
+impl From<&AccountView> for AccountRow {
+  fn from(account: &AccountView) -> Self {
+    Self {
+      address: account.address.clone(),
+      title: account
+        .label
+        .clone()
+        .unwrap_or_else(|| short_address(&account.address)),
+      balance: account.balance.map(format_ether),
+      locked: account.locked,
+    }
+  }
+}
+
+/// The view-model of a list of accounts, with an optional selection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountListModel {
+  rows: Arc<[AccountRow]>,
+  selected: Option<String>,
+}
+
+impl AccountListModel {
+  /// Create a list of the accounts of `view`, with no selection
+  pub fn new(view: &KeychainView) -> Self {
+    Self {
+      rows: view.accounts().iter().map(AccountRow::from).collect(),
+      selected: None,
+    }
+  }
+
+  /// Get the rows of the list
+  pub fn rows(&self) -> &[AccountRow] {
+    &self.rows
+  }
+
+  /// Select the account at `address`.
+  /// Returns false, leaving the selection unchanged, if it is not listed
+  pub fn select(&mut self, address: &str) -> bool {
+    match self.rows.iter().any(|row| row.address == address) {
+      true => {
+        self.selected = Some(address.to_string());
+        true
+      }
+      false => false,
+    }
+  }
+
+  /// Clear the selection
+  pub fn clear_selection(&mut self) {
+    self.selected = None;
+  }
+
+  /// Get the selected row, if any
+  pub fn selected(&self) -> Option<&AccountRow> {
+    let selected = self.selected.as_ref()?;
+    self.rows.iter().find(|row| &row.address == selected)
+  }
+
+  /// Replace the rows with the accounts of `view`, keeping the
+  /// selection if the selected account is still listed
+  pub fn refresh(&mut self, view: &KeychainView) {
+    let selected = self.selected.take();
+    self.rows = Self::new(view).rows;
+
+    if let Some(address) = selected {
+      self.select(&address);
+    }
+  }
+}
+
+/// Format an amount of wei as ether, trimming trailing zeros
+pub fn format_ether(wei: u128) -> String {
+  let mut value = [0u8; 32];
+  value[16..].copy_from_slice(&wei.to_be_bytes());
+
+  format_units(&value, ETHER_DECIMALS)
+}
+
+/// Shorten `address` to its first and last four hex digits
+pub fn short_address(address: &str) -> String {
+  match address.len() > 12 {
+    true => format!("{}…{}", &address[..6], &address[address.len() - 4..]),
+    false => address.to_string(),
+  }
+}
@@ -0,0 +1,78 @@
+use keychain::{KeychainView, SigningContext};
+
+use crate::{format_ether, short_address};
+
+/// The decision taken on a signature approval prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApprovalDecision {
+  /// The user has not decided yet
+  Pending,
+  /// The user approved the signature
+  Approved,
+  /// The user rejected the signature
+  Rejected,
+}
+
+/// The view-model of a prompt asking to approve a signature
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApprovalPrompt {
+  /// The address of the signing account
+  pub address: String,
+  /// The label of the signing account, or its shortened address
+  pub account: String,
+  /// The recipient of the transaction, if any
+  pub recipient: Option<String>,
+  /// The value transferred in ether, if the payload is a transaction
+  pub value: Option<String>,
+  /// The id of the chain the payload is signed for, if known
+  pub chain_id: Option<u64>,
+  /// Whether the payload carries calldata
+  pub has_calldata: bool,
+  context: SigningContext,
+  decision: ApprovalDecision,
+}
+
+impl ApprovalPrompt {
+  /// Create a prompt to sign with the account at `address`, labelled
+  /// as in `view`, what `context` describes
+  pub fn new(view: &KeychainView, address: &str, context: SigningContext) -> Self {
+    let transaction = context.transaction.as_ref();
+
+    Self {
+      address: address.to_string(),
+      account: view
+        .account(address)
+        .and_then(|account| account.label.clone())
+        .unwrap_or_else(|| short_address(address)),
+      recipient: transaction.and_then(|transaction| transaction.to.clone()),
+      value: transaction.map(|transaction| format_ether(transaction.value)),
+      chain_id: context.chain_id,
+      has_calldata: transaction.is_some_and(|transaction| !transaction.data.is_empty()),
+      context,
+      decision: ApprovalDecision::Pending,
+    }
+  }
+
+  /// Approve the signature
+  pub fn approve(&mut self) {
+    self.decision = ApprovalDecision::Approved;
+  }
+
+  /// Reject the signature
+  pub fn reject(&mut self) {
+    self.decision = ApprovalDecision::Rejected;
+  }
+
+  /// Get the decision taken on the prompt
+  pub fn decision(&self) -> ApprovalDecision {
+    self.decision
+  }
+
+  /// Get the signing context to sign with, once approved
+  pub fn approved_context(&self) -> Option<&SigningContext> {
+    match self.decision {
+      ApprovalDecision::Approved => Some(&self.context),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,66 @@
+use std::{
+  error::Error,
+  sync::{Arc, Mutex},
+};
+
+use keychain::{KeychainState, KeychainView};
+use utils::Controller;
+
+/// A keychain view kept up to date by a subscription to the keychain
+/// state, to be polled by GUI layers from their own event loop
+#[derive(Debug)]
+pub struct ViewBinding {
+  subscription: usize,
+  latest: Arc<Mutex<(u64, KeychainView)>>,
+}
+
+impl ViewBinding {
+  /// Subscribe to the state of `keychain`, starting from its current view
+  pub fn attach<C, E>(keychain: &mut C) -> Self
+  where
+    C: Controller<KeychainState, E>,
+    E: Error,
+  {
+    let latest = Arc::new(Mutex::new((0, keychain.get_state().view())));
+    let shared = latest.clone();
+
+    let subscription = keychain.subscribe(move |state: &KeychainState| {
+      let mut latest = shared
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+      *latest = (latest.0 + 1, state.view());
+    });
+
+    Self {
+      subscription,
+      latest,
+    }
+  }
+
+  /// Get the latest view of the keychain
+  pub fn view(&self) -> KeychainView {
+    self.lock().1.clone()
+  }
+
+  /// Get the number of state changes received since attached,
+  /// to tell whether the view changed since it was last polled
+  pub fn revision(&self) -> u64 {
+    self.lock().0
+  }
+
+  /// Unsubscribe from the state of `keychain`
+  pub fn detach<C, E>(self, keychain: &mut C)
+  where
+    C: Controller<KeychainState, E>,
+    E: Error,
+  {
+    keychain.unsubscribe(self.subscription);
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, (u64, KeychainView)> {
+    self
+      .latest
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
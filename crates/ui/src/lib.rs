@@ -0,0 +1,11 @@
+pub mod account_list;
+pub use account_list::*;
+
+pub mod approval;
+pub use approval::*;
+
+pub mod binding;
+pub use binding::*;
+
+pub mod unlock;
+pub use unlock::*;
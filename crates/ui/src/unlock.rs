@@ -0,0 +1,68 @@
+use std::fmt::Display;
+
+/// The view-model of an unlock dialog, holding the typed password
+/// until it is submitted
+#[derive(Debug, Default)]
+pub struct UnlockDialog {
+  password: String,
+  error: Option<String>,
+  failed_attempts: u32,
+  unlocked: bool,
+}
+
+impl UnlockDialog {
+  /// Create an empty unlock dialog
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the typed password, clearing the previous error
+  pub fn set_password(&mut self, password: &str) {
+    self.password = password.to_string();
+    self.error = None;
+  }
+
+  /// Check if a password has been typed
+  pub fn can_submit(&self) -> bool {
+    !self.password.is_empty() && !self.unlocked
+  }
+
+  /// Submit the typed password to `unlock`, like `Keychain::unlock`.
+  /// The password is cleared from the dialog whatever the outcome,
+  /// and the error is kept to be displayed on failure
+  pub fn submit<F, E>(&mut self, unlock: F) -> bool
+  where
+    F: FnOnce(&str) -> Result<(), E>,
+    E: Display,
+  {
+    let password = std::mem::take(&mut self.password);
+
+    match unlock(&password) {
+      Ok(()) => {
+        self.error = None;
+        self.unlocked = true;
+      }
+      Err(error) => {
+        self.error = Some(error.to_string());
+        self.failed_attempts += 1;
+      }
+    }
+
+    self.unlocked
+  }
+
+  /// Get the error of the last submission, if it failed
+  pub fn error(&self) -> Option<&str> {
+    self.error.as_deref()
+  }
+
+  /// Get the number of failed submissions
+  pub fn failed_attempts(&self) -> u32 {
+    self.failed_attempts
+  }
+
+  /// Check if the last submission unlocked the keychain
+  pub fn is_unlocked(&self) -> bool {
+    self.unlocked
+  }
+}
@@ -0,0 +1,156 @@
+use hdkey::hdkey_factory;
+use keychain::{Keychain, SigningContext};
+use utils::Controller;
+use walleth_ui::*;
+
+const PASSWORD: &str = "password";
+
+mod account_list_model {
+  use super::*;
+
+  #[test]
+  fn it_lists_labels_and_balances() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let addresses: Vec<String> = (0..2)
+      .map(|_| keychain.add_account(0).unwrap().address)
+      .collect();
+    keychain
+      .set_account_label(&addresses[0], Some("savings"))
+      .unwrap();
+    keychain
+      .set_account_snapshot(&addresses[1], Some(1_500_000_000_000_000_000), None)
+      .unwrap();
+
+    let list = AccountListModel::new(&keychain.get_state().view());
+
+    assert_eq!(list.rows()[0].title, "savings");
+    assert_eq!(list.rows()[1].title, short_address(&addresses[1]));
+    assert_eq!(list.rows()[1].balance, Some("1.5".to_string()));
+  }
+
+  #[test]
+  fn it_keeps_the_selection_on_refresh() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let addresses: Vec<String> = (0..2)
+      .map(|_| keychain.add_account(0).unwrap().address)
+      .collect();
+    let mut list = AccountListModel::new(&keychain.get_state().view());
+
+    assert!(list.select(&addresses[1]));
+    assert!(!list.select("0x0"));
+
+    keychain.add_account(0).unwrap();
+    list.refresh(&keychain.get_state().view());
+
+    assert_eq!(list.rows().len(), 3);
+    assert_eq!(list.selected().unwrap().address, addresses[1]);
+  }
+}
+
+mod unlock_dialog {
+  use super::*;
+
+  #[test]
+  fn it_unlocks_the_keychain() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+    let mut dialog = UnlockDialog::new();
+
+    dialog.set_password(PASSWORD);
+
+    assert!(dialog.can_submit());
+    assert!(dialog.submit(|password| keychain.unlock(password)));
+    assert!(!keychain.is_locked());
+  }
+
+  #[test]
+  fn it_reports_failed_attempts() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    keychain.lock(PASSWORD).unwrap();
+    let mut dialog = UnlockDialog::new();
+
+    dialog.set_password("wrong");
+
+    assert!(!dialog.submit(|password| keychain.unlock(password)));
+    assert!(dialog.error().is_some());
+    assert_eq!(dialog.failed_attempts(), 1);
+    assert!(!dialog.can_submit());
+  }
+}
+
+mod approval_prompt {
+  use super::*;
+
+  #[test]
+  fn it_describes_the_transaction() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let addresses: Vec<String> = (0..2)
+      .map(|_| keychain.add_account(0).unwrap().address)
+      .collect();
+    let context =
+      SigningContext::transaction(Some(&addresses[1]), 2_000_000_000_000_000_000, &[]).on_chain(1);
+
+    let prompt = ApprovalPrompt::new(&keychain.get_state().view(), &addresses[0], context);
+
+    assert_eq!(prompt.recipient, Some(addresses[1].clone()));
+    assert_eq!(prompt.value, Some("2".to_string()));
+    assert_eq!(prompt.chain_id, Some(1));
+    assert!(!prompt.has_calldata);
+    assert_eq!(prompt.decision(), ApprovalDecision::Pending);
+  }
+
+  #[test]
+  fn it_releases_the_context_once_approved() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let addresses: Vec<String> = (0..2)
+      .map(|_| keychain.add_account(0).unwrap().address)
+      .collect();
+    let view = keychain.get_state().view();
+    let mut prompt = ApprovalPrompt::new(&view, &addresses[0], SigningContext::default());
+
+    assert!(prompt.approved_context().is_none());
+    prompt.reject();
+    assert!(prompt.approved_context().is_none());
+    prompt.approve();
+    assert!(prompt.approved_context().is_some());
+  }
+}
+
+mod view_binding {
+  use super::*;
+
+  #[test]
+  fn it_follows_the_keychain_state() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let binding = ViewBinding::attach(&mut keychain);
+
+    keychain.add_account(0).unwrap();
+
+    assert!(binding.revision() > 0);
+    assert_eq!(binding.view(), keychain.get_state().view());
+  }
+
+  #[test]
+  fn it_stops_following_once_detached() {
+    let mut keychain = Keychain::new();
+    keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+    let binding = ViewBinding::attach(&mut keychain);
+    let latest = binding.view();
+    let observer = ViewBinding::attach(&mut keychain);
+
+    binding.detach(&mut keychain);
+    keychain.add_account(0).unwrap();
+
+    assert_eq!(
+      observer.view().accounts().len(),
+      latest.accounts().len() + 1
+    );
+  }
+}
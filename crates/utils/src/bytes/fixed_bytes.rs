@@ -0,0 +1,143 @@
+use std::{
+  fmt::{Debug, Display, Formatter},
+  hash::{Hash, Hasher},
+  str::FromStr,
+};
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use subtle::ConstantTimeEq;
+
+use crate::hex::{decode_to_array, Hex, HexError};
+
+/// Define a newtype around a fixed-size byte array, displayed and
+/// serialized as a 0x-prefixed hex string, and compared in constant time
+macro_rules! fixed_bytes {
+  ($(#[$attr:meta])* $name:ident, $length:expr) => {
+    $(#[$attr])*
+    #[derive(Clone, Copy)]
+    pub struct $name(pub [u8; $length]);
+
+    impl $name {
+      /// The number of bytes
+      pub const LENGTH: usize = $length;
+
+      /// Get the bytes
+      pub fn as_bytes(&self) -> &[u8; $length] {
+        &self.0
+      }
+
+      /// Get the bytes, consuming the newtype
+      pub fn into_bytes(self) -> [u8; $length] {
+        self.0
+      }
+    }
+
+    impl Default for $name {
+      fn default() -> Self {
+        Self([0u8; $length])
+      }
+    }
+
+    impl PartialEq for $name {
+      fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+      }
+    }
+
+    impl Eq for $name {}
+
+    impl Hash for $name {
+      fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+      }
+    }
+
+    impl From<[u8; $length]> for $name {
+      fn from(bytes: [u8; $length]) -> Self {
+        Self(bytes)
+      }
+    }
+
+    impl From<$name> for [u8; $length] {
+      fn from(value: $name) -> Self {
+        value.0
+      }
+    }
+
+    impl TryFrom<&[u8]> for $name {
+      type Error = HexError;
+
+      fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(bytes.try_into().or(Err(HexError::InvalidHexLength))?))
+      }
+    }
+
+    impl AsRef<[u8]> for $name {
+      fn as_ref(&self) -> &[u8] {
+        &self.0
+      }
+    }
+
+    impl Display for $name {
+      fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&Hex(&self.0), f)
+      }
+    }
+
+    impl Debug for $name {
+      fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", stringify!($name), Hex(&self.0))
+      }
+    }
+
+    impl FromStr for $name {
+      type Err = HexError;
+
+      /// Parse a hex string, with or without the 0x prefix
+      fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(decode_to_array(value)?))
+      }
+    }
+
+    impl Serialize for $name {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+      }
+    }
+
+    impl<'de> Deserialize<'de> for $name {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+          .parse()
+          .map_err(DeError::custom)
+      }
+    }
+  };
+}
+
+fixed_bytes!(
+  /// A 32-byte value, like a keccak256 digest
+  B256,
+  32
+);
+
+impl From<u128> for B256 {
+  /// Get the big-endian 256 bits word of an integer
+  fn from(value: u128) -> Self {
+    let mut bytes = [0u8; 32];
+    bytes[16..].copy_from_slice(&value.to_be_bytes());
+    Self(bytes)
+  }
+}
+
+fixed_bytes!(
+  /// The 32-byte hash of a transaction
+  TxHash,
+  32
+);
+
+fixed_bytes!(
+  /// A compressed secp256k1 public key
+  PublicKeyBytes,
+  33
+);
@@ -0,0 +1,2 @@
+pub mod fixed_bytes;
+pub use fixed_bytes::*;
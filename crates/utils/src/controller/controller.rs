@@ -1,4 +1,4 @@
-use std::error::Error;
+use std::{error::Error, future::Future};
 
 /// A controller is a struct that holds a state and allows for updates to that state.
 pub trait Controller<State, ControllerError>
@@ -14,10 +14,34 @@ where
   where
     F: Fn(&mut State);
 
+  /// Update the current state like `update`, restoring the previous
+  /// state if a subscriber fails to be notified of the new one
+  fn try_update<F>(&mut self, updater: F) -> Result<(), ControllerError>
+  where
+    F: Fn(&mut State);
+
+  /// Update the current state with an updater needing IO to compute the
+  /// new one, like fetching balances. The updater receives a copy of the
+  /// current state, and the state it resolves to replaces the current one
+  fn update_async<F, Fut>(
+    &mut self,
+    updater: F,
+  ) -> impl Future<Output = Result<(), ControllerError>>
+  where
+    State: Clone,
+    F: FnOnce(State) -> Fut,
+    Fut: Future<Output = Result<State, ControllerError>>,
+  {
+    async move {
+      let state = updater(self.get_state().clone()).await?;
+      self.try_update(move |current| *current = state.clone())
+    }
+  }
+
   /// Subscribe to state changes
   fn subscribe<F>(&mut self, subscriber: F) -> usize
   where
-    F: 'static + FnMut(&State);
+    F: 'static + FnMut(&State) + Send;
 
   /// Unsubscribe from state changes
   fn unsubscribe(&mut self, id: usize);
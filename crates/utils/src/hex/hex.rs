@@ -1,32 +1,65 @@
+use std::fmt::{Debug, Display, Formatter};
+
 use hex;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HexError {
   InvalidHex,
   InvalidHexLength,
   InvalidHexAddress,
 }
 
+impl Display for HexError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::InvalidHex => write!(f, "Invalid hex"),
+      Self::InvalidHexLength => write!(f, "Invalid hex length"),
+      Self::InvalidHexAddress => write!(f, "Invalid hex address"),
+    }
+  }
+}
+
+impl std::error::Error for HexError {}
+
 /// Encode a byte array into a hex string
 pub fn encode(data: &[u8]) -> String {
   hex::encode(data)
 }
 
+/// Encode a byte array into a 0x-prefixed hex string,
+/// allocating the string only once
+pub fn encode_prefixed(data: &[u8]) -> String {
+  let mut encoded = vec![0u8; 2 + data.len() * 2];
+  encoded[..2].copy_from_slice(b"0x");
+  // The buffer is exactly twice the length of the data after the prefix
+  hex::encode_to_slice(data, &mut encoded[2..]).unwrap();
+
+  // Hex digits are always valid UTF-8
+  String::from_utf8(encoded).unwrap()
+}
+
 /// Decode a hex `&str` into a byte array
 pub fn decode(data: &str) -> Result<Vec<u8>, HexError> {
   hex::decode(data).or(Err(HexError::InvalidHex))
 }
 
-/// Assert that a `&String` is a valid hex address
-pub fn assert_is_valid_hex_address(value: &String) -> Result<(), HexError> {
-  let unprefixed = remove0x(value);
-
-  assert_is_hex(&unprefixed)?;
-
-  if unprefixed.len() != 40 {
+/// Decode a hex `&str`, with or without the 0x prefix,
+/// into an array of exactly `N` bytes, without allocating
+pub fn decode_to_array<const N: usize>(data: &str) -> Result<[u8; N], HexError> {
+  let unprefixed = remove0x(data);
+  if unprefixed.len() != N * 2 {
     return Err(HexError::InvalidHexLength);
   }
 
-  Ok(())
+  let mut decoded = [0u8; N];
+  hex::decode_to_slice(unprefixed, &mut decoded).or(Err(HexError::InvalidHex))?;
+
+  Ok(decoded)
+}
+
+/// Assert that a `&str` is a valid hex address
+pub fn assert_is_valid_hex_address(value: &str) -> Result<(), HexError> {
+  decode_to_array::<20>(value).map(|_| ())
 }
 
 /// Assert that a `&str` is a valid hex
@@ -38,17 +71,51 @@ pub fn assert_is_hex(value: &str) -> Result<(), HexError> {
 }
 
 /// Remove the 0x prefix from a string
-pub fn remove0x(value: &String) -> String {
-  match value.starts_with("0x") {
-    true => String::from(&value[2..]),
-    _ => value.to_string(),
-  }
+pub fn remove0x(value: &str) -> &str {
+  value.strip_prefix("0x").unwrap_or(value)
 }
 
 /// Add the 0x prefix to a string
-pub fn add0x(value: &String) -> String {
+pub fn add0x(value: &str) -> String {
   match value.starts_with("0x") {
     true => value.to_string(),
     _ => format!("0x{}", value),
   }
 }
+
+/// Convert the hex digits of a string to lowercase, in place
+pub fn to_lowercase_in_place(value: &mut str) {
+  value.make_ascii_lowercase();
+}
+
+/// Convert the hex digits of a string to uppercase, in place.
+/// The 0x prefix, if any, is kept lowercase
+pub fn to_uppercase_in_place(value: &mut str) {
+  let start = value.len() - remove0x(value).len();
+  value[start..].make_ascii_uppercase();
+}
+
+/// A wrapper displaying bytes as a 0x-prefixed hex string,
+/// without encoding them into an intermediate `String`.
+///
+/// Byte newtypes, like addresses or transaction hashes,
+/// can implement `Display` by delegating to it
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Hex<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> Display for Hex<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "0x")?;
+    self
+      .0
+      .as_ref()
+      .iter()
+      .try_for_each(|byte| write!(f, "{:02x}", byte))
+  }
+}
+
+impl<T: AsRef<[u8]>> Debug for Hex<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(self, f)
+  }
+}
@@ -1,7 +1,13 @@
+#![allow(clippy::module_inception)]
+
+pub mod bytes;
 pub mod controller;
 pub mod crypto;
 pub mod hex;
 pub mod observable;
+pub mod secure;
 
+pub use bytes::{PublicKeyBytes, TxHash, B256};
 pub use controller::Controller;
-pub use observable::{Observable, Observer};
+pub use observable::{Middleware, Observable, Observer};
+pub use secure::{ExposeAcknowledgement, RevealedSecret, RevealedSecretError, SecureBytes};
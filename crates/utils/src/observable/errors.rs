@@ -6,12 +6,14 @@ use std::{
 #[derive(Debug)]
 pub enum ObservableError {
   UnableToLockObserver,
+  UnableToLockMiddleware,
 }
 
 impl Display for ObservableError {
   fn fmt(&self, f: &mut Formatter) -> Result {
     match self {
       ObservableError::UnableToLockObserver => write!(f, "Unable to lock observer"),
+      ObservableError::UnableToLockMiddleware => write!(f, "Unable to lock middleware"),
     }
   }
 }
@@ -0,0 +1,28 @@
+use std::fmt::{Debug, Formatter, Result};
+
+/// A cross-cutting concern attached to an `Observable`, like logging
+/// state transitions, persisting snapshots or skipping rapid updates.
+///
+/// Middlewares run in the order they were attached, after each state
+/// change and before the subscribers are notified
+pub trait Middleware<S>: Send {
+  /// Handle the transition from `previous` to `next`.
+  /// Returns `false` to keep the subscribers from being notified
+  /// of this change, skipping the following middlewares too
+  fn handle(&mut self, previous: &S, next: &S) -> bool;
+}
+
+impl<S, F> Middleware<S> for F
+where
+  F: FnMut(&S, &S) -> bool + Send,
+{
+  fn handle(&mut self, previous: &S, next: &S) -> bool {
+    self(previous, next)
+  }
+}
+
+impl<S> Debug for dyn Middleware<S> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(f, "Middleware")
+  }
+}
@@ -6,3 +6,6 @@ pub use errors::ObservableError;
 
 pub mod observer;
 pub use observer::Observer;
+
+pub mod middleware;
+pub use middleware::Middleware;
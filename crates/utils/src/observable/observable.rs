@@ -1,12 +1,20 @@
-use std::sync::{Arc, Mutex};
+use std::{
+  sync::{
+    mpsc::{channel, RecvTimeoutError},
+    Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
 
-use super::{ObservableError, Observer};
+use super::{Middleware, ObservableError, Observer};
 
 /// A store for state that can be subscribed to
 #[derive(Debug, Clone)]
 pub struct Observable<S> {
   state: S,
   observers: Vec<Observer<S>>,
+  middlewares: Vec<Arc<Mutex<dyn Middleware<S>>>>,
 }
 
 impl<S> Observable<S>
@@ -17,9 +25,20 @@ where
     Observable {
       state: initial_state,
       observers: vec![],
+      middlewares: vec![],
     }
   }
 
+  /// Attach a middleware handling each state change
+  /// before the subscribers are notified
+  pub fn with_middleware<M>(mut self, middleware: M) -> Self
+  where
+    M: Middleware<S> + 'static,
+  {
+    self.middlewares.push(Arc::new(Mutex::new(middleware)));
+    self
+  }
+
   /// Get the current state
   pub fn get_state(&self) -> &S {
     &self.state
@@ -28,8 +47,9 @@ where
   /// Set the current state
   /// This will call all event listeners with the new state
   pub fn set_state(&mut self, new_state: S) -> Result<(), ObservableError> {
+    let previous = self.snapshot();
     self.state = new_state;
-    self.emit()
+    self.notify(previous.as_ref())
   }
 
   /// Update the current state
@@ -40,15 +60,35 @@ where
   where
     F: Fn(&mut S),
   {
+    let previous = self.snapshot();
     updater(&mut self.state);
-    self.emit()
+    self.notify(previous.as_ref())
+  }
+
+  /// Update the current state like `update`, restoring the previous
+  /// state if a subscriber cannot be called. Subscribers called before
+  /// the failing one have already received the new state
+  pub fn try_update<F>(&mut self, updater: F) -> Result<(), ObservableError>
+  where
+    F: Fn(&mut S),
+  {
+    let previous = self.state.clone();
+    updater(&mut self.state);
+
+    match self.notify(Some(&previous)) {
+      Ok(()) => Ok(()),
+      Err(error) => {
+        self.state = previous;
+        Err(error)
+      }
+    }
   }
 
   /// Subscribe to state changes
   /// Returns the id of the subscriber
   pub fn subscribe<F>(&mut self, subscriber: F) -> usize
   where
-    F: 'static + FnMut(&S),
+    F: 'static + FnMut(&S) + Send,
   {
     self.observers.push(Observer::new(
       self.observers.len(),
@@ -62,6 +102,87 @@ where
     self.observers.retain(|observer| observer.id != id);
   }
 
+  /// Subscribe only to the state changes accepted by `predicate`,
+  /// called with the previously emitted state and the new one.
+  /// Returns the id of the subscriber
+  pub fn subscribe_filtered<P, F>(&mut self, mut predicate: P, mut subscriber: F) -> usize
+  where
+    S: Send + 'static,
+    P: 'static + FnMut(&S, &S) -> bool + Send,
+    F: 'static + FnMut(&S) + Send,
+  {
+    let mut previous = self.state.clone();
+
+    self.subscribe(move |state: &S| {
+      if predicate(&previous, state) {
+        subscriber(state);
+      }
+      previous = state.clone();
+    })
+  }
+
+  /// Subscribe to state changes, receiving only the latest state once
+  /// no other change happened for `duration`.
+  /// The subscriber is called from a background thread, which stops
+  /// when the subscriber is unsubscribed or the observable is dropped
+  /// Returns the id of the subscriber
+  pub fn subscribe_debounced<F>(&mut self, duration: Duration, mut subscriber: F) -> usize
+  where
+    S: Send + 'static,
+    F: 'static + FnMut(&S) + Send,
+  {
+    let (sender, receiver) = channel::<S>();
+
+    thread::spawn(move || {
+      while let Ok(mut latest) = receiver.recv() {
+        loop {
+          match receiver.recv_timeout(duration) {
+            Ok(state) => latest = state,
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => {
+              subscriber(&latest);
+              return;
+            }
+          }
+        }
+        subscriber(&latest);
+      }
+    });
+
+    self.subscribe(move |state: &S| {
+      // The receiver is only gone if the subscriber panicked
+      let _ = sender.send(state.clone());
+    })
+  }
+
+  /// Clone the current state, only if a middleware needs to see it
+  fn snapshot(&self) -> Option<S> {
+    match self.middlewares.is_empty() {
+      true => None,
+      false => Some(self.state.clone()),
+    }
+  }
+
+  /// Pass the transition from `previous` to the current state through
+  /// the middlewares, then emit the current state unless one of them
+  /// chose to skip the subscribers
+  fn notify(&mut self, previous: Option<&S>) -> Result<(), ObservableError> {
+    if let Some(previous) = previous {
+      for middleware in &self.middlewares {
+        let mut guard = match middleware.lock() {
+          Ok(guard) => guard,
+          Err(_) => return Err(ObservableError::UnableToLockMiddleware),
+        };
+
+        if !guard.handle(previous, &self.state) {
+          return Ok(());
+        }
+      }
+    }
+
+    self.emit()
+  }
+
   /// Emit the current state to all subscribers
   fn emit(&mut self) -> Result<(), ObservableError> {
     for observer in &mut self.observers {
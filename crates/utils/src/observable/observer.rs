@@ -3,7 +3,7 @@ use std::{
   sync::{Arc, Mutex},
 };
 
-type Listener<T> = dyn FnMut(&T);
+type Listener<T> = dyn FnMut(&T) + Send;
 
 #[derive(Clone)]
 pub struct Observer<S> {
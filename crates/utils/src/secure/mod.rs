@@ -0,0 +1,8 @@
+#[cfg(feature = "secure-mem")]
+mod page_lock;
+
+pub mod secure_bytes;
+pub use secure_bytes::SecureBytes;
+
+pub mod revealed_secret;
+pub use revealed_secret::{ExposeAcknowledgement, RevealedSecret, RevealedSecretError};
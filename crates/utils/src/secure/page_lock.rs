@@ -0,0 +1,66 @@
+use std::{
+  collections::BTreeMap,
+  sync::{Mutex, OnceLock},
+};
+
+/// The number of live buffers using each locked page, indexed by page
+/// address. Locks apply to whole pages and are not counted by the
+/// operating system, so a page is only unlocked once no buffer uses it
+static LOCKED_PAGES: OnceLock<Mutex<BTreeMap<usize, usize>>> = OnceLock::new();
+
+/// Get the addresses of the pages spanned by `len` bytes at `address`
+fn pages(address: *const u8, len: usize) -> impl Iterator<Item = usize> {
+  let size = region::page::size();
+  let first = address as usize / size * size;
+  let last = (address as usize + len - 1) / size * size;
+
+  (first..=last).step_by(size)
+}
+
+/// Lock the pages spanned by `len` bytes at `address` in RAM.
+/// Returns false, locking nothing, if a page cannot be locked
+pub(crate) fn lock(address: *const u8, len: usize) -> bool {
+  let mut locked = LOCKED_PAGES
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap_or_else(|poisoned| poisoned.into_inner());
+  let size = region::page::size();
+
+  let new_pages = pages(address, len)
+    .filter(|page| !locked.contains_key(page))
+    .collect::<Vec<usize>>();
+  for (count, page) in new_pages.iter().enumerate() {
+    if region::lock(*page as *const u8, size)
+      .map(std::mem::forget)
+      .is_err()
+    {
+      new_pages[..count].iter().for_each(|page| {
+        let _ = region::unlock(*page as *const u8, size);
+      });
+      return false;
+    }
+  }
+
+  pages(address, len).for_each(|page| *locked.entry(page).or_default() += 1);
+  true
+}
+
+/// Release the pages spanned by `len` bytes at `address`,
+/// unlocking the ones no other buffer uses
+pub(crate) fn unlock(address: *const u8, len: usize) {
+  let mut locked = LOCKED_PAGES
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap_or_else(|poisoned| poisoned.into_inner());
+  let size = region::page::size();
+
+  for page in pages(address, len) {
+    if let Some(count) = locked.get_mut(&page) {
+      *count -= 1;
+      if *count == 0 {
+        locked.remove(&page);
+        let _ = region::unlock(page as *const u8, size);
+      }
+    }
+  }
+}
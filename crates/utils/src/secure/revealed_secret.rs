@@ -0,0 +1,87 @@
+use std::{
+  error::Error,
+  fmt::{Debug, Display, Formatter},
+  time::{Duration, Instant},
+};
+
+use super::SecureBytes;
+
+/// An acknowledgement that an exposed secret will not be logged,
+/// persisted or kept around longer than needed, required by
+/// `RevealedSecret::expose` so that exposing a secret is always
+/// an explicit and searchable decision
+#[derive(Debug)]
+pub struct ExposeAcknowledgement(());
+
+impl ExposeAcknowledgement {
+  /// Acknowledge that the exposed secret is handled with care
+  pub const fn i_will_not_log_or_retain_this_secret() -> Self {
+    Self(())
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevealedSecretError {
+  Expired,
+}
+
+impl Display for RevealedSecretError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Expired => write!(f, "The revealed secret has expired"),
+    }
+  }
+}
+
+impl Error for RevealedSecretError {}
+
+/// A secret revealed for a limited time, like an exported private key.
+///
+/// The secret is zeroed when dropped or once its time to live has passed
+/// and an exposure is attempted. It is never printed, cannot be cloned,
+/// and can only be read through `expose`
+pub struct RevealedSecret {
+  secret: SecureBytes,
+  expires_at: Instant,
+}
+
+impl RevealedSecret {
+  /// Reveal `secret` for `ttl`
+  pub fn new(secret: SecureBytes, ttl: Duration) -> Self {
+    Self {
+      secret,
+      expires_at: Instant::now() + ttl,
+    }
+  }
+
+  /// Check if the time to live of the secret has passed
+  pub fn is_expired(&self) -> bool {
+    Instant::now() >= self.expires_at
+  }
+
+  /// Get the time left before the secret expires
+  pub fn remaining(&self) -> Duration {
+    self.expires_at.saturating_duration_since(Instant::now())
+  }
+
+  /// Expose the secret bytes, if it has not expired yet.
+  /// An expired secret is zeroed right away
+  pub fn expose(
+    &mut self,
+    _acknowledgement: ExposeAcknowledgement,
+  ) -> Result<&[u8], RevealedSecretError> {
+    if self.is_expired() {
+      self.secret = SecureBytes::new(&[]);
+      return Err(RevealedSecretError::Expired);
+    }
+
+    Ok(self.secret.as_slice())
+  }
+}
+
+impl Debug for RevealedSecret {
+  /// The secret is never printed
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "RevealedSecret([REDACTED])")
+  }
+}
@@ -0,0 +1,105 @@
+use std::{
+  fmt::{Debug, Formatter},
+  ops::Deref,
+};
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+#[cfg(feature = "secure-mem")]
+use super::page_lock;
+
+/// A fixed-size buffer for secrets, like seeds and private keys.
+///
+/// The buffer is zeroed when dropped. With the `secure-mem` feature,
+/// its memory pages are also locked in RAM, so that the secret is never
+/// swapped to disk. Pages shared with other buffers stay locked until
+/// all of them are dropped. Locking is best-effort: when the operating
+/// system refuses it, the buffer is still usable without the lock.
+pub struct SecureBytes {
+  /// The secret bytes, never reallocated so that the lock stays valid
+  bytes: Box<[u8]>,
+  /// Whether the memory pages of `bytes` are locked, released on drop
+  locked: bool,
+}
+
+impl SecureBytes {
+  /// Create a new `SecureBytes` holding a copy of `bytes`
+  pub fn new(bytes: &[u8]) -> Self {
+    let bytes: Box<[u8]> = bytes.into();
+
+    Self {
+      #[cfg(feature = "secure-mem")]
+      locked: !bytes.is_empty() && page_lock::lock(bytes.as_ptr(), bytes.len()),
+      #[cfg(not(feature = "secure-mem"))]
+      locked: false,
+      bytes,
+    }
+  }
+
+  /// Check if the memory of the buffer is locked in RAM
+  pub fn is_locked(&self) -> bool {
+    self.locked
+  }
+
+  /// Get the secret as a slice of bytes
+  pub fn as_slice(&self) -> &[u8] {
+    &self.bytes
+  }
+}
+
+impl Deref for SecureBytes {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    &self.bytes
+  }
+}
+
+impl AsRef<[u8]> for SecureBytes {
+  fn as_ref(&self) -> &[u8] {
+    &self.bytes
+  }
+}
+
+impl From<Vec<u8>> for SecureBytes {
+  /// Move a secret into a `SecureBytes`, zeroing the original vector
+  fn from(mut bytes: Vec<u8>) -> Self {
+    let secure_bytes = Self::new(&bytes);
+    bytes.zeroize();
+
+    secure_bytes
+  }
+}
+
+impl Clone for SecureBytes {
+  fn clone(&self) -> Self {
+    Self::new(&self.bytes)
+  }
+}
+
+impl PartialEq for SecureBytes {
+  /// Secrets are compared in constant time
+  fn eq(&self, other: &Self) -> bool {
+    self.bytes.ct_eq(&other.bytes).into()
+  }
+}
+
+impl Debug for SecureBytes {
+  /// The secret is never printed
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "SecureBytes([REDACTED; {}])", self.bytes.len())
+  }
+}
+
+impl Drop for SecureBytes {
+  /// The bytes are zeroed, then their pages released, before they are freed
+  fn drop(&mut self) {
+    self.bytes.zeroize();
+
+    #[cfg(feature = "secure-mem")]
+    if self.locked {
+      page_lock::unlock(self.bytes.as_ptr(), self.bytes.len());
+    }
+  }
+}
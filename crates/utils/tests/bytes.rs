@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use walleth_utils::{hex::HexError, PublicKeyBytes, TxHash, B256};
+
+#[test]
+fn it_displays_as_prefixed_hex() {
+  assert_eq!(
+    B256([0xab; 32]).to_string(),
+    format!("0x{}", "ab".repeat(32))
+  );
+  assert_eq!(
+    format!("{:?}", TxHash([1; 32])),
+    format!("TxHash(0x{})", "01".repeat(32))
+  );
+}
+
+#[test]
+fn it_parses_hex_with_or_without_prefix() {
+  let hex = "02".repeat(33);
+
+  assert_eq!(
+    PublicKeyBytes::from_str(&hex).unwrap(),
+    PublicKeyBytes([2; 33])
+  );
+  assert_eq!(
+    format!("0x{}", hex).parse::<PublicKeyBytes>().unwrap(),
+    PublicKeyBytes([2; 33])
+  );
+}
+
+#[test]
+fn it_rejects_hex_of_another_length() {
+  assert_eq!("0x1234".parse::<B256>(), Err(HexError::InvalidHexLength));
+  assert_eq!(
+    B256::try_from(&[0u8; 31][..]),
+    Err(HexError::InvalidHexLength)
+  );
+}
+
+#[test]
+fn it_compares_by_content() {
+  assert_eq!(B256([1; 32]), B256::from([1; 32]));
+  assert_ne!(B256([1; 32]), B256::default());
+}
+
+#[test]
+fn it_round_trips_through_serde() {
+  let hash = TxHash([0xcd; 32]);
+
+  let json = serde_json::to_value(hash).unwrap();
+
+  assert_eq!(json, serde_json::json!(hash.to_string()));
+  assert_eq!(serde_json::from_value::<TxHash>(json).unwrap(), hash);
+  assert!(serde_json::from_value::<TxHash>(serde_json::json!("0x12")).is_err());
+}
+
+#[test]
+fn it_converts_integers_to_big_endian_words() {
+  assert_eq!(
+    B256::from(0x0102u128).to_string(),
+    format!("0x{}0102", "00".repeat(30))
+  );
+  assert_eq!(
+    B256::from(u128::MAX).to_string(),
+    format!("0x{}{}", "00".repeat(16), "ff".repeat(16))
+  );
+}
@@ -0,0 +1,105 @@
+use std::{
+  future::Future,
+  panic::{catch_unwind, AssertUnwindSafe},
+  pin::pin,
+  task::{Context, Poll, Waker},
+};
+
+use walleth_utils::{observable::ObservableError, Controller, Observable};
+
+struct Counter {
+  store: Observable<u32>,
+}
+
+impl Controller<u32, ObservableError> for Counter {
+  fn get_state(&self) -> &u32 {
+    self.store.get_state()
+  }
+
+  fn update<F>(&mut self, updater: F) -> Result<(), ObservableError>
+  where
+    F: Fn(&mut u32),
+  {
+    self.store.update(updater)
+  }
+
+  fn try_update<F>(&mut self, updater: F) -> Result<(), ObservableError>
+  where
+    F: Fn(&mut u32),
+  {
+    self.store.try_update(updater)
+  }
+
+  fn subscribe<F>(&mut self, subscriber: F) -> usize
+  where
+    F: 'static + FnMut(&u32) + Send,
+  {
+    self.store.subscribe(subscriber)
+  }
+
+  fn unsubscribe(&mut self, id: usize) {
+    self.store.unsubscribe(id)
+  }
+}
+
+/// Poll a future that never waits to completion
+fn ready<T>(future: impl Future<Output = T>) -> T {
+  match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+    Poll::Ready(output) => output,
+    Poll::Pending => panic!("The future is not ready"),
+  }
+}
+
+#[test]
+fn it_updates_the_state() {
+  let mut counter = Counter {
+    store: Observable::new(0),
+  };
+
+  counter.try_update(|state| *state += 1).unwrap();
+
+  assert_eq!(*counter.get_state(), 1);
+}
+
+#[test]
+fn it_rolls_back_when_a_subscriber_fails() {
+  let mut counter = Counter {
+    store: Observable::new(0),
+  };
+  counter.subscribe(|state| {
+    if *state == 1 {
+      panic!("Subscriber failure");
+    }
+  });
+  // The panicking subscriber cannot be called anymore
+  assert!(catch_unwind(AssertUnwindSafe(|| counter.update(|state| *state = 1))).is_err());
+
+  let result = counter.try_update(|state| *state = 2);
+
+  assert!(matches!(result, Err(ObservableError::UnableToLockObserver)));
+  assert_eq!(*counter.get_state(), 1);
+}
+
+#[test]
+fn it_updates_the_state_asynchronously() {
+  let mut counter = Counter {
+    store: Observable::new(1),
+  };
+
+  ready(counter.update_async(|state| async move { Ok(state * 10) })).unwrap();
+
+  assert_eq!(*counter.get_state(), 10);
+}
+
+#[test]
+fn it_keeps_the_state_when_the_async_updater_fails() {
+  let mut counter = Counter {
+    store: Observable::new(1),
+  };
+
+  let result =
+    ready(counter.update_async(|_| async { Err(ObservableError::UnableToLockObserver) }));
+
+  assert!(result.is_err());
+  assert_eq!(*counter.get_state(), 1);
+}
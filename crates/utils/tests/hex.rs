@@ -0,0 +1,66 @@
+use walleth_utils::hex::{
+  add0x, assert_is_valid_hex_address, decode_to_array, encode_prefixed, remove0x,
+  to_lowercase_in_place, to_uppercase_in_place, Hex, HexError,
+};
+
+#[test]
+fn it_encodes_with_the_prefix() {
+  assert_eq!(encode_prefixed(&[0xde, 0xad, 0xbe, 0xef]), "0xdeadbeef");
+  assert_eq!(encode_prefixed(&[]), "0x");
+}
+
+#[test]
+fn it_decodes_to_an_array() {
+  assert_eq!(decode_to_array::<2>("0xbeef"), Ok([0xbe, 0xef]));
+  assert_eq!(decode_to_array::<2>("BEEF"), Ok([0xbe, 0xef]));
+}
+
+#[test]
+fn it_fails_to_decode_to_an_array_of_another_length() {
+  assert_eq!(
+    decode_to_array::<4>("0xbeef"),
+    Err(HexError::InvalidHexLength)
+  );
+  assert_eq!(decode_to_array::<2>("0xbeeg"), Err(HexError::InvalidHex));
+}
+
+#[test]
+fn it_adds_and_removes_the_prefix() {
+  assert_eq!(remove0x("0xbeef"), "beef");
+  assert_eq!(remove0x("beef"), "beef");
+  assert_eq!(add0x("beef"), "0xbeef");
+  assert_eq!(add0x("0xbeef"), "0xbeef");
+}
+
+#[test]
+fn it_validates_hex_addresses() {
+  assert!(assert_is_valid_hex_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+  assert!(assert_is_valid_hex_address("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+  assert!(assert_is_valid_hex_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea").is_err());
+  assert!(assert_is_valid_hex_address("0xzaaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_err());
+}
+
+#[test]
+fn it_converts_case_in_place() {
+  let mut value = String::from("0xDeadBeef");
+
+  to_lowercase_in_place(&mut value);
+  assert_eq!(value, "0xdeadbeef");
+
+  to_uppercase_in_place(&mut value);
+  assert_eq!(value, "0xDEADBEEF");
+}
+
+#[test]
+fn it_displays_bytes_as_hex() {
+  struct TxHash([u8; 4]);
+
+  impl std::fmt::Display for TxHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      Hex(&self.0).fmt(f)
+    }
+  }
+
+  assert_eq!(TxHash([0xde, 0xad, 0xbe, 0xef]).to_string(), "0xdeadbeef");
+  assert_eq!(format!("{:?}", Hex(vec![0x01])), "0x01");
+}
@@ -1,4 +1,7 @@
-use std::sync::{Arc, Mutex};
+use std::{
+  sync::{mpsc::channel, Arc, Mutex},
+  time::Duration,
+};
 
 use walleth_utils::Observable;
 
@@ -64,3 +67,102 @@ fn it_stops_calling_callback_after_unsubscribe() {
 
   assert_eq!(history.lock().unwrap().len(), 1);
 }
+
+#[test]
+fn it_passes_transitions_through_middlewares() {
+  let transitions = Arc::new(Mutex::<Vec<(i32, i32)>>::new(vec![]));
+  let r_transitions = transitions.clone();
+  let mut store = Observable::new(0).with_middleware(move |previous: &i32, next: &i32| {
+    r_transitions.lock().unwrap().push((*previous, *next));
+    true
+  });
+
+  store.set_state(1).unwrap();
+  store.update(|state| *state += 1).unwrap();
+
+  assert_eq!(*transitions.lock().unwrap(), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn it_skips_subscribers_when_a_middleware_returns_false() {
+  let history = Arc::new(Mutex::<Vec<i32>>::new(vec![]));
+  let r_history = history.clone();
+  let mut store = Observable::new(0).with_middleware(|previous: &i32, next: &i32| previous != next);
+  store.subscribe(move |state| {
+    r_history.lock().unwrap().push(*state);
+  });
+
+  store.set_state(1).unwrap();
+  store.set_state(1).unwrap();
+  store.set_state(2).unwrap();
+
+  assert_eq!(*history.lock().unwrap(), vec![1, 2]);
+  assert_eq!(store.get_state(), &2);
+}
+
+#[test]
+fn it_runs_middlewares_in_order() {
+  let calls = Arc::new(Mutex::<Vec<&str>>::new(vec![]));
+  let (first, second) = (calls.clone(), calls.clone());
+  let mut store = Observable::new(0)
+    .with_middleware(move |_: &i32, _: &i32| {
+      first.lock().unwrap().push("first");
+      false
+    })
+    .with_middleware(move |_: &i32, _: &i32| {
+      second.lock().unwrap().push("second");
+      true
+    });
+
+  store.set_state(1).unwrap();
+
+  assert_eq!(*calls.lock().unwrap(), vec!["first"]);
+}
+
+#[test]
+fn it_calls_filtered_subscribers_only_on_accepted_changes() {
+  let history = Arc::new(Mutex::<Vec<(i32, i32)>>::new(vec![]));
+  let r_history = history.clone();
+  let mut store = Observable::new((0, 0));
+  store.subscribe_filtered(
+    |previous: &(i32, i32), next: &(i32, i32)| previous.0 != next.0,
+    move |state| r_history.lock().unwrap().push(*state),
+  );
+
+  store.set_state((0, 1)).unwrap();
+  store.set_state((1, 1)).unwrap();
+  store.set_state((1, 2)).unwrap();
+  store.set_state((2, 2)).unwrap();
+
+  assert_eq!(*history.lock().unwrap(), vec![(1, 1), (2, 2)]);
+}
+
+#[test]
+fn it_calls_debounced_subscribers_with_the_latest_state() {
+  let (sender, receiver) = channel();
+  let mut store = Observable::new(0);
+  store.subscribe_debounced(Duration::from_millis(50), move |state: &i32| {
+    sender.send(*state).unwrap();
+  });
+
+  store.set_state(1).unwrap();
+  store.set_state(2).unwrap();
+  store.set_state(3).unwrap();
+
+  assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(3));
+  assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn it_flushes_debounced_subscribers_when_unsubscribed() {
+  let (sender, receiver) = channel();
+  let mut store = Observable::new(0);
+  let id = store.subscribe_debounced(Duration::from_secs(60), move |state: &i32| {
+    sender.send(*state).unwrap();
+  });
+
+  store.set_state(1).unwrap();
+  store.unsubscribe(id);
+
+  assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(1));
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use walleth_utils::{ExposeAcknowledgement, RevealedSecret, RevealedSecretError, SecureBytes};
+
+fn acknowledgement() -> ExposeAcknowledgement {
+  ExposeAcknowledgement::i_will_not_log_or_retain_this_secret()
+}
+
+#[test]
+fn it_exposes_the_secret_before_it_expires() {
+  let mut secret = RevealedSecret::new(SecureBytes::new(&[1, 2, 3]), Duration::from_secs(60));
+
+  assert!(!secret.is_expired());
+  assert!(secret.remaining() > Duration::ZERO);
+  assert_eq!(secret.expose(acknowledgement()).unwrap(), &[1, 2, 3]);
+}
+
+#[test]
+fn it_refuses_to_expose_expired_secrets() {
+  let mut secret = RevealedSecret::new(SecureBytes::new(&[1, 2, 3]), Duration::ZERO);
+
+  assert!(secret.is_expired());
+  assert_eq!(secret.remaining(), Duration::ZERO);
+  assert_eq!(
+    secret.expose(acknowledgement()),
+    Err(RevealedSecretError::Expired)
+  );
+}
+
+#[test]
+fn it_does_not_print_the_secret() {
+  let secret = RevealedSecret::new(SecureBytes::new(&[0xab; 32]), Duration::from_secs(60));
+
+  assert_eq!(format!("{:?}", secret), "RevealedSecret([REDACTED])");
+}
@@ -0,0 +1,61 @@
+use walleth_utils::SecureBytes;
+
+#[test]
+fn it_holds_a_copy_of_the_bytes() {
+  let secure_bytes = SecureBytes::new(&[1, 2, 3]);
+  assert_eq!(secure_bytes.as_slice(), &[1, 2, 3]);
+}
+
+#[test]
+fn it_compares_by_content() {
+  assert_eq!(
+    SecureBytes::new(&[1, 2, 3]),
+    SecureBytes::from(vec![1, 2, 3])
+  );
+  assert_ne!(SecureBytes::new(&[1, 2, 3]), SecureBytes::new(&[3, 2, 1]));
+}
+
+#[test]
+fn it_compares_secrets_of_different_lengths() {
+  assert_ne!(SecureBytes::new(&[1, 2, 3]), SecureBytes::new(&[1, 2]));
+  assert_ne!(SecureBytes::new(&[]), SecureBytes::new(&[1]));
+}
+
+#[test]
+fn it_clones_the_bytes() {
+  let secure_bytes = SecureBytes::new(&[1, 2, 3]);
+  assert_eq!(secure_bytes.clone(), secure_bytes);
+}
+
+#[test]
+fn it_does_not_print_the_secret() {
+  let secure_bytes = SecureBytes::new(&[42, 42, 42]);
+  assert_eq!(format!("{:?}", secure_bytes), "SecureBytes([REDACTED; 3])");
+}
+
+#[cfg(feature = "secure-mem")]
+#[test]
+fn it_locks_the_memory() {
+  let secure_bytes = SecureBytes::new(&[1, 2, 3]);
+  assert!(secure_bytes.is_locked());
+}
+
+#[cfg(feature = "secure-mem")]
+#[test]
+fn it_keeps_shared_pages_locked_when_a_secret_is_dropped() {
+  let first = SecureBytes::new(&[1, 2, 3]);
+  let second = SecureBytes::new(&[4, 5, 6]);
+  drop(first);
+
+  let third = SecureBytes::new(&[7, 8, 9]);
+  assert!(second.is_locked());
+  assert!(third.is_locked());
+  assert_eq!(second.as_slice(), &[4, 5, 6]);
+}
+
+#[cfg(not(feature = "secure-mem"))]
+#[test]
+fn it_does_not_lock_the_memory_without_feature() {
+  let secure_bytes = SecureBytes::new(&[1, 2, 3]);
+  assert!(!secure_bytes.is_locked());
+}
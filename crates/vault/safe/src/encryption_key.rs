@@ -1,7 +1,11 @@
+use std::time::Instant;
+
 use hmac::Hmac;
 use pbkdf2::pbkdf2;
-use rand_core::{OsRng, RngCore};
+use rand_core::OsRng;
 use sha3::Keccak256;
+use tracing::debug;
+use walleth_core::EntropySource;
 
 /// A Public Key & Salt pair that can be used for simmetric encryption,
 /// compatible with ChaCha20Poly1305
@@ -13,27 +17,33 @@ pub struct EncryptionKey {
 impl EncryptionKey {
   /// Create a new EncryptionKey from a password and a number of rounds
   pub fn new(password: &[u8], rounds: u32) -> Self {
+    Self::new_with_entropy(password, rounds, &mut OsRng)
+  }
+
+  /// Create a new EncryptionKey from a password and a number of rounds,
+  /// drawing the salt from the passed entropy source
+  pub fn new_with_entropy<E: EntropySource>(password: &[u8], rounds: u32, entropy: &mut E) -> Self {
     // Salt generation
     let mut salt = [0; 16];
-    OsRng.fill_bytes(&mut salt);
-
-    // Key derivation
-    let mut pubk = [0; 32];
-    if pbkdf2::<Hmac<Keccak256>>(password, &salt, rounds, &mut pubk).is_err() {
-      panic!("Key derivation failed")
-    }
+    entropy.fill_bytes(&mut salt);
 
-    Self { pubk, salt }
+    Self::with_salt(password, salt, rounds)
   }
 
   /// Create a new EncryptionKey from a password and a salt, and
   /// passing a number of rounds
   pub fn with_salt(password: &[u8], salt: [u8; 16], rounds: u32) -> Self {
     // Key derivation
+    let started = Instant::now();
     let mut pubk = [0; 32];
     if pbkdf2::<Hmac<Keccak256>>(password, &salt, rounds, &mut pubk).is_err() {
       panic!("Key derivation failed")
     }
+    debug!(
+      rounds,
+      elapsed_ms = started.elapsed().as_millis() as u64,
+      "derived encryption key"
+    );
 
     Self { pubk, salt }
   }
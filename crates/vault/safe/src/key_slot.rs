@@ -0,0 +1,114 @@
+use walleth_core::EntropySource;
+
+use crate::{ChaCha20Poly1305Cipher, CipherKey, CipherNonce, EncryptionKey};
+
+/// The PBKDF2 rounds of passkey slots. The PRF output of a passkey is
+/// a uniformly random secret, so it is not stretched like a password
+pub const PASSKEY_KEY_SLOT_ROUNDS: u32 = 1;
+
+/// A copy of the data key of a safe, encrypted with a key derived from
+/// one unlocking credential, like a password or a recovery key.
+///
+/// A safe encrypted with a data key can hold several key slots, LUKS-style,
+/// so that it can be opened with any of their credentials, and each
+/// credential can be revoked by removing its slot
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeySlot {
+  /// The name of the credential, to tell slots apart
+  pub name: String,
+  /// The salt used to derive the key encrypting the data key
+  pub salt: [u8; 16],
+  /// The PBKDF2 rounds used to derive the key encrypting the data key
+  pub rounds: u32,
+  /// The nonce used to encrypt the data key
+  pub nonce: CipherNonce,
+  /// The encrypted data key
+  pub wrapped_key: Vec<u8>,
+  /// The WebAuthn credential id of a passkey slot, whose credential is
+  /// the output of the PRF extension of the passkey evaluated with `salt`.
+  /// Missing for slots of typed credentials, like passwords
+  pub credential_id: Option<Vec<u8>>,
+}
+
+impl KeySlot {
+  /// Create a slot encrypting `data_key` with a key derived
+  /// from `credential` with `rounds` iterations of PBKDF2
+  pub fn new(
+    name: &str,
+    data_key: &CipherKey,
+    credential: &[u8],
+    rounds: u32,
+  ) -> Result<Self, String> {
+    let encryption_key = EncryptionKey::new(credential, rounds);
+    let (wrapped_key, nonce) = ChaCha20Poly1305Cipher::encrypt(&encryption_key.pubk, data_key)?;
+
+    Ok(Self {
+      name: name.to_string(),
+      salt: encryption_key.salt,
+      rounds,
+      nonce,
+      wrapped_key,
+      credential_id: None,
+    })
+  }
+
+  /// Create a slot like `KeySlot::new`, drawing the salt
+  /// and the nonce from the passed entropy source
+  pub fn new_with_entropy<E: EntropySource>(
+    name: &str,
+    data_key: &CipherKey,
+    credential: &[u8],
+    rounds: u32,
+    entropy: &mut E,
+  ) -> Result<Self, String> {
+    let encryption_key = EncryptionKey::new_with_entropy(credential, rounds, entropy);
+    let (wrapped_key, nonce) =
+      ChaCha20Poly1305Cipher::encrypt_with_entropy(entropy, &encryption_key.pubk, data_key)?;
+
+    Ok(Self {
+      name: name.to_string(),
+      salt: encryption_key.salt,
+      rounds,
+      nonce,
+      wrapped_key,
+      credential_id: None,
+    })
+  }
+
+  /// Create a slot encrypting `data_key` with the output of the PRF
+  /// extension of the passkey `credential_id`, evaluated with `salt`
+  pub fn new_passkey(
+    name: &str,
+    data_key: &CipherKey,
+    credential_id: &[u8],
+    salt: [u8; 16],
+    prf_output: &[u8],
+  ) -> Result<Self, String> {
+    let encryption_key = EncryptionKey::with_salt(prf_output, salt, PASSKEY_KEY_SLOT_ROUNDS);
+    let (wrapped_key, nonce) = ChaCha20Poly1305Cipher::encrypt(&encryption_key.pubk, data_key)?;
+
+    Ok(Self {
+      name: name.to_string(),
+      salt,
+      rounds: PASSKEY_KEY_SLOT_ROUNDS,
+      nonce,
+      wrapped_key,
+      credential_id: Some(credential_id.to_vec()),
+    })
+  }
+
+  /// Decrypt the data key with `credential`, or with
+  /// the PRF output of the passkey of the slot
+  pub fn open(&self, credential: &[u8]) -> Result<CipherKey, String> {
+    let encryption_key = EncryptionKey::with_salt(credential, self.salt, self.rounds);
+    let mut data_key =
+      ChaCha20Poly1305Cipher::decrypt(&encryption_key.pubk, &self.nonce, &self.wrapped_key)?;
+    let key = data_key
+      .as_slice()
+      .try_into()
+      .or(Err("unexpected data key length".to_string()));
+    data_key.fill(0);
+
+    key
+  }
+}
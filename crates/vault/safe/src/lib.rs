@@ -1,9 +1,12 @@
 pub mod cipher;
 pub mod encryption_key;
 pub mod errors;
+pub mod key_slot;
 pub mod safe;
 
 pub use cipher::{ChaCha20Poly1305Cipher, CipherKey, CipherNonce};
 pub use encryption_key::EncryptionKey;
 pub use errors::SafeError;
+pub use key_slot::{KeySlot, PASSKEY_KEY_SLOT_ROUNDS};
 pub use safe::Safe;
+pub use walleth_core::EntropySource;
@@ -1,3 +1,5 @@
+use walleth_core::EntropySource;
+
 use crate::{ChaCha20Poly1305Cipher, CipherKey, SafeError};
 
 /// A safe is a container for encrypted data.
@@ -32,58 +34,97 @@ impl<T> Safe<T> {
     })
   }
 
+  /// Create a new safe from unencrypted data, drawing
+  /// the nonce from the passed entropy source
+  pub fn from_plain_bytes_with_entropy<E: EntropySource>(
+    metadata: T,
+    key: &CipherKey,
+    plain_bytes: Vec<u8>,
+    entropy: &mut E,
+  ) -> Result<Self, String> {
+    let (encrypted_bytes, nonce) =
+      ChaCha20Poly1305Cipher::encrypt_with_entropy(entropy, key, &plain_bytes)?;
+
+    Ok(Safe {
+      metadata,
+      encrypted_bytes: encrypted_bytes.into_boxed_slice(),
+      nonce,
+    })
+  }
+
   /// Decrypt the safe with a key. Returns the decrypted bytes.
   pub fn decrypt(&self, key: &CipherKey) -> Result<Vec<u8>, String> {
     ChaCha20Poly1305Cipher::decrypt(key, &self.nonce, &self.encrypted_bytes)
   }
 }
 
-impl<T> From<Safe<T>> for Vec<u8>
+impl<T> TryFrom<Safe<T>> for Vec<u8>
 where
-  T: TryFrom<Vec<u8>> + Into<Vec<u8>>,
+  T: TryInto<Vec<u8>>,
 {
+  type Error = SafeError;
+
   /// Serialize `Safe` to bytes
-  fn from(safe: Safe<T>) -> Vec<u8> {
+  fn try_from(safe: Safe<T>) -> Result<Vec<u8>, SafeError> {
     let mut bytes: Vec<u8> = vec![];
-    let metadata_bytes = safe.metadata.into();
+    let mut metadata_bytes: Vec<u8> = safe.metadata.try_into().or(Err(
+      SafeError::Serialization("error serializing metadata".to_string()),
+    ))?;
+    let metadata_len = u32::try_from(metadata_bytes.len()).or(Err(SafeError::Serialization(
+      "metadata too large".to_string(),
+    )))?;
 
-    bytes.append(&mut vec![u8::try_from(metadata_bytes.len()).unwrap()]);
-    bytes.append(&mut metadata_bytes.into());
+    // The metadata length is encoded as a little endian u32
+    bytes.append(&mut metadata_len.to_le_bytes().to_vec());
+    bytes.append(&mut metadata_bytes);
     bytes.append(&mut safe.encrypted_bytes.into());
     bytes.append(&mut safe.nonce.to_vec());
 
-    bytes
+    Ok(bytes)
   }
 }
 
 impl<T> TryFrom<Vec<u8>> for Safe<T>
 where
-  T: TryFrom<Vec<u8>> + Into<Vec<u8>>,
+  T: TryFrom<Vec<u8>>,
 {
   type Error = SafeError;
 
   /// Deserialize `Safe` from bytes
   fn try_from(bytes: Vec<u8>) -> Result<Self, SafeError> {
-    let metadata_len = bytes[0];
-    let metadata = T::try_from(bytes[1..metadata_len as usize + 1].to_vec()).or(Err(
+    let length_error = || SafeError::Deserialization("unexpected bytes length".to_string());
+
+    let metadata_len = u32::from_le_bytes(
+      bytes
+        .get(..4)
+        .and_then(|length| length.try_into().ok())
+        .ok_or_else(length_error)?,
+    ) as usize;
+    let metadata_end = metadata_len.checked_add(4).ok_or_else(length_error)?;
+    // The nonce takes the last 24 bytes
+    let nonce_start = bytes
+      .len()
+      .checked_sub(24)
+      .filter(|nonce_start| *nonce_start >= metadata_end)
+      .ok_or_else(length_error)?;
+
+    let metadata = T::try_from(bytes[4..metadata_end].to_vec()).or(Err(
       SafeError::Deserialization("error deserializing metadata".to_string()),
     ))?;
-    let encrypted_bytes = bytes[metadata_len as usize + 1..bytes.len() - 24].to_vec();
-    let nonce = bytes[bytes.len() - 24..bytes.len()].to_vec();
+    let encrypted_bytes = bytes[metadata_end..nonce_start].to_vec();
+    let nonce = bytes[nonce_start..].to_vec();
 
     Ok(Safe {
       metadata,
       encrypted_bytes: encrypted_bytes.into_boxed_slice(),
-      nonce: nonce.try_into().or(Err(SafeError::Deserialization(
-        "unexpected bytes length".to_string(),
-      )))?,
+      nonce: nonce.try_into().or(Err(length_error()))?,
     })
   }
 }
 
 impl<T> PartialEq for Safe<T>
 where
-  T: PartialEq + TryFrom<Vec<u8>> + Into<Vec<u8>>,
+  T: PartialEq,
 {
   fn eq(&self, other: &Self) -> bool {
     self.metadata == other.metadata
@@ -0,0 +1,35 @@
+use proptest::prelude::*;
+use walleth_vault_safe::{ChaCha20Poly1305Cipher, EncryptionKey, Safe};
+
+proptest! {
+  #[test]
+  fn serialized_safes_decrypt_to_the_same_bytes(
+    metadata in prop::collection::vec(any::<u8>(), 0..64),
+    plain_bytes in prop::collection::vec(any::<u8>(), 0..256),
+  ) {
+    let key = ChaCha20Poly1305Cipher::new_key();
+    let safe = Safe::from_plain_bytes(metadata.clone(), &key, plain_bytes.clone()).unwrap();
+
+    let bytes: Vec<u8> = safe.clone().try_into().unwrap();
+    let restored = Safe::<Vec<u8>>::try_from(bytes).unwrap();
+
+    prop_assert!(restored == safe);
+    prop_assert_eq!(&restored.metadata, &metadata);
+    prop_assert_eq!(restored.decrypt(&key).unwrap(), plain_bytes);
+  }
+
+  #[test]
+  fn safes_reject_other_passwords(
+    password in prop::collection::vec(any::<u8>(), 1..32),
+    wrong_password in prop::collection::vec(any::<u8>(), 1..32),
+    plain_bytes in prop::collection::vec(any::<u8>(), 1..64),
+  ) {
+    prop_assume!(password != wrong_password);
+    let key = EncryptionKey::new(&password, 10);
+    let safe = Safe::<Vec<u8>>::from_plain_bytes(vec![], &key.pubk, plain_bytes).unwrap();
+
+    let wrong_key = EncryptionKey::with_salt(&wrong_password, key.salt, 10);
+
+    prop_assert!(safe.decrypt(&wrong_key.pubk).is_err());
+  }
+}
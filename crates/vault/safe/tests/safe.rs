@@ -1,4 +1,32 @@
-use walleth_vault_safe::{ChaCha20Poly1305Cipher, Safe};
+use rand_core::{CryptoRng, RngCore};
+use walleth_vault_safe::{ChaCha20Poly1305Cipher, EncryptionKey, KeySlot, Safe};
+
+/// A deterministic entropy source filling bytes with an incrementing counter
+struct CounterRng(u8);
+
+impl RngCore for CounterRng {
+  fn next_u32(&mut self) -> u32 {
+    rand_core::impls::next_u32_via_fill(self)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    rand_core::impls::next_u64_via_fill(self)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for byte in dest {
+      self.0 = self.0.wrapping_add(1);
+      *byte = self.0;
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl CryptoRng for CounterRng {}
 
 mod from_plain_bytes {
   use super::*;
@@ -41,3 +69,113 @@ mod decrypt {
     assert!(decrypted_bytes.is_err());
   }
 }
+
+mod try_from {
+  use super::*;
+
+  #[test]
+  fn it_round_trips_through_bytes() {
+    let key = ChaCha20Poly1305Cipher::new_key();
+    let safe = Safe::from_plain_bytes(vec![1u8, 2u8], &key, vec![3u8]).unwrap();
+
+    let bytes: Vec<u8> = safe.clone().try_into().unwrap();
+
+    assert!(Safe::<Vec<u8>>::try_from(bytes).unwrap() == safe);
+  }
+
+  #[test]
+  fn it_fails_on_truncated_bytes() {
+    let key = ChaCha20Poly1305Cipher::new_key();
+    let safe = Safe::from_plain_bytes(vec![1u8, 2u8], &key, vec![3u8]).unwrap();
+    let bytes: Vec<u8> = safe.try_into().unwrap();
+
+    for length in [0, 3, 6, bytes.len() - 24] {
+      assert!(Safe::<Vec<u8>>::try_from(bytes[..length].to_vec()).is_err());
+    }
+  }
+
+  #[test]
+  fn it_fails_when_the_metadata_length_overflows() {
+    let bytes = [u32::MAX.to_le_bytes().to_vec(), vec![0u8; 32]].concat();
+
+    assert!(Safe::<Vec<u8>>::try_from(bytes).is_err());
+  }
+}
+
+mod from_plain_bytes_with_entropy {
+  use super::*;
+
+  #[test]
+  fn it_is_deterministic_with_the_same_entropy() {
+    let key = [7u8; 32];
+    let bytes = [0u8, 1u8, 2u8, 3u8, 4u8].to_vec();
+
+    let first: Vec<u8> =
+      Safe::from_plain_bytes_with_entropy(vec![1u8], &key, bytes.clone(), &mut CounterRng(0))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let second: Vec<u8> =
+      Safe::from_plain_bytes_with_entropy(vec![1u8], &key, bytes, &mut CounterRng(0))
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn it_can_be_decrypted() {
+    let key = [7u8; 32];
+    let bytes = [0u8, 1u8, 2u8, 3u8, 4u8].to_vec();
+
+    let safe =
+      Safe::from_plain_bytes_with_entropy("metadata", &key, bytes.clone(), &mut CounterRng(0))
+        .unwrap();
+
+    assert_eq!(safe.decrypt(&key).unwrap(), bytes);
+  }
+}
+
+mod new_with_entropy {
+  use super::*;
+
+  #[test]
+  fn it_draws_the_salt_from_the_entropy_source() {
+    let key = EncryptionKey::new_with_entropy(b"password", 10, &mut CounterRng(0));
+
+    assert_eq!(key.salt, core::array::from_fn(|i| i as u8 + 1));
+    assert_eq!(
+      key.pubk,
+      EncryptionKey::with_salt(b"password", key.salt, 10).pubk
+    );
+  }
+}
+
+mod key_slot {
+  use super::*;
+
+  #[test]
+  fn it_opens_the_data_key_with_its_credential() {
+    let data_key = ChaCha20Poly1305Cipher::new_key();
+
+    let key_slot = KeySlot::new("recovery", &data_key, b"credential", 10).unwrap();
+
+    assert_eq!(key_slot.open(b"credential").unwrap(), data_key);
+    assert!(key_slot.open(b"another credential").is_err());
+  }
+
+  #[test]
+  fn it_is_deterministic_with_the_same_entropy() {
+    let data_key = [7u8; 32];
+
+    let first =
+      KeySlot::new_with_entropy("recovery", &data_key, b"credential", 10, &mut CounterRng(0))
+        .unwrap();
+    let second =
+      KeySlot::new_with_entropy("recovery", &data_key, b"credential", 10, &mut CounterRng(0))
+        .unwrap();
+
+    assert_eq!(first, second);
+  }
+}
@@ -19,6 +19,10 @@ pub enum VaultError {
   SafeDecrypt,
   SafeExport(String),
   SafeRestore(String),
+  InvalidSecret(String),
+  KeySlot(String),
+  IntegrityMismatch(String),
+  IndexRangeOutOfBounds(usize, usize),
 }
 
 impl Display for VaultError {
@@ -39,6 +43,16 @@ impl Display for VaultError {
       Self::SafeDecrypt => write!(f, "Safe decryption error"),
       Self::SafeExport(message) => write!(f, "Safe export error > {}", message),
       Self::SafeRestore(message) => write!(f, "Safe restore error > {}", message),
+      Self::InvalidSecret(message) => write!(f, "Invalid secret: {}", message),
+      Self::KeySlot(message) => write!(f, "Key slot error: {}", message),
+      Self::IntegrityMismatch(message) => write!(f, "Integrity mismatch: {}", message),
+      Self::IndexRangeOutOfBounds(start, count) => {
+        write!(
+          f,
+          "Index range out of bounds: {} keys from {}",
+          count, start
+        )
+      }
       Self::IdentityError(error) => write!(f, "{}", error),
     }
   }
@@ -0,0 +1,26 @@
+/// Parameters of the key derivation function turning
+/// passwords into vault encryption keys (PBKDF2-HMAC-Keccak256).
+///
+/// More rounds make brute forcing passwords slower,
+/// at the cost of slower locking and unlocking.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KdfParams {
+  /// The number of PBKDF2 iterations
+  pub rounds: u32,
+}
+
+impl KdfParams {
+  /// The rounds used by vaults locked without explicit parameters
+  pub const DEFAULT_ROUNDS: u32 = 1000;
+
+  /// Create parameters with `rounds` iterations
+  pub fn new(rounds: u32) -> Self {
+    Self { rounds }
+  }
+}
+
+impl Default for KdfParams {
+  fn default() -> Self {
+    Self::new(Self::DEFAULT_ROUNDS)
+  }
+}
@@ -1,5 +1,13 @@
 pub mod errors;
+pub mod kdf;
+pub mod metadata;
+pub mod secrets;
+pub mod staged;
 pub mod vault;
 
 pub use errors::VaultError;
-pub use vault::Vault;
+pub use kdf::KdfParams;
+pub use metadata::VaultMetadata;
+pub use secrets::VaultSecrets;
+pub use staged::StagedVault;
+pub use vault::{Vault, HARDENED_INDEX_OFFSET, PASSWORD_KEY_SLOT, SEQUENTIAL_INDEX_LIMIT};
@@ -0,0 +1,261 @@
+use identity::{Account, DerivationPath};
+use safe::KeySlot;
+
+use crate::VaultError;
+
+/// Plaintext metadata stored alongside the encrypted
+/// identity of a locked vault
+#[derive(Clone, Debug, PartialEq)]
+pub struct VaultMetadata {
+  /// The salt used to derive the encryption key from the password
+  pub salt: [u8; 16],
+  /// The fingerprint of the identity inside the vault
+  pub fingerprint: [u8; 4],
+  /// The type of the identity inside the vault, used to
+  /// pick the right deserializer when unlocking
+  pub identity_type: String,
+  /// The public accounts derived from the identity, sorted by index.
+  /// They are reported while the vault is locked, and their indexes
+  /// are used to recreate the same accounts after unlocking
+  pub accounts: Vec<Account>,
+  /// The length of the serialized identity at the start of the
+  /// encrypted payload, followed by the vault secrets.
+  /// Missing for vaults locked before secrets were supported,
+  /// whose payload only holds the identity
+  pub identity_length: Option<usize>,
+  /// The PBKDF2 rounds used to derive the encryption key.
+  /// Missing for vaults locked before rounds were configurable,
+  /// which used `KdfParams::DEFAULT_ROUNDS`
+  pub kdf_rounds: Option<u32>,
+  /// The slots holding the data key encrypting the payload, one for each
+  /// unlocking credential. Empty for vaults encrypted with a key derived
+  /// from the password directly, with `salt` and `kdf_rounds`
+  pub key_slots: Vec<KeySlot>,
+}
+
+impl TryFrom<VaultMetadata> for Vec<u8> {
+  type Error = VaultError;
+
+  /// Serialize the metadata to bytes, failing if a field
+  /// does not fit its length prefix
+  fn try_from(metadata: VaultMetadata) -> Result<Vec<u8>, VaultError> {
+    let mut bytes = metadata.salt.to_vec();
+    bytes.extend(metadata.fingerprint);
+    write_bytes(&mut bytes, metadata.identity_type.as_bytes())?;
+    bytes.extend(to_u32(metadata.accounts.len())?.to_le_bytes());
+    metadata
+      .accounts
+      .iter()
+      .try_for_each(|account| -> Result<(), VaultError> {
+        bytes.extend(to_u32(account.path.index)?.to_le_bytes());
+        write_bytes(&mut bytes, &account.public_key)?;
+        write_bytes(&mut bytes, account.address.as_bytes())
+      })?;
+    if let Some(identity_length) = metadata.identity_length {
+      bytes.extend(to_u32(identity_length)?.to_le_bytes());
+      if let Some(kdf_rounds) = metadata.kdf_rounds {
+        bytes.extend(kdf_rounds.to_le_bytes());
+        if !metadata.key_slots.is_empty() {
+          write_key_slots(&mut bytes, &metadata.key_slots)?;
+        }
+      }
+    }
+
+    Ok(bytes)
+  }
+}
+
+/// Append `key_slots` to `bytes`, prefixed by their u8 count
+fn write_key_slots(bytes: &mut Vec<u8>, key_slots: &[KeySlot]) -> Result<(), VaultError> {
+  let count = u8::try_from(key_slots.len()).or(Err(VaultError::SafeExport(
+    "too many key slots".to_string(),
+  )))?;
+  bytes.push(count);
+  key_slots
+    .iter()
+    .try_for_each(|key_slot| -> Result<(), VaultError> {
+      write_bytes(bytes, key_slot.name.as_bytes())?;
+      bytes.extend(key_slot.salt);
+      bytes.extend(key_slot.rounds.to_le_bytes());
+      bytes.extend(key_slot.nonce);
+      write_bytes(bytes, &key_slot.wrapped_key)
+    })?;
+  // The credential ids of passkey slots follow all the slots, for
+  // metadata written before passkeys were supported to stay readable
+  if key_slots
+    .iter()
+    .any(|key_slot| key_slot.credential_id.is_some())
+  {
+    key_slots.iter().try_for_each(|key_slot| {
+      write_bytes(bytes, key_slot.credential_id.as_deref().unwrap_or_default())
+    })?;
+  }
+
+  Ok(())
+}
+
+/// Convert a length or an index to the u32 it is serialized as
+fn to_u32(value: usize) -> Result<u32, VaultError> {
+  u32::try_from(value).or(Err(VaultError::SafeExport(
+    "metadata value too large".to_string(),
+  )))
+}
+
+/// Append `value` to `bytes`, prefixed by its u8 length
+fn write_bytes(bytes: &mut Vec<u8>, value: &[u8]) -> Result<(), VaultError> {
+  let length = u8::try_from(value.len()).or(Err(VaultError::SafeExport(
+    "metadata field too long".to_string(),
+  )))?;
+  bytes.push(length);
+  bytes.extend(value);
+
+  Ok(())
+}
+
+impl TryFrom<Vec<u8>> for VaultMetadata {
+  type Error = VaultError;
+
+  /// Deserialize the metadata from bytes
+  fn try_from(bytes: Vec<u8>) -> Result<Self, VaultError> {
+    let error = |message: &str| VaultError::VaultRestoreFromBytes(message.to_string());
+
+    if bytes.len() < 20 {
+      return Err(error("unexpected metadata length"));
+    }
+
+    // Unwraps are safe because the length has been checked
+    let salt = bytes[..16].try_into().unwrap();
+    let fingerprint = bytes[16..20].try_into().unwrap();
+
+    // Metadata created before identity types and accounts
+    // were stored only holds the salt and the fingerprint
+    if bytes.len() == 20 {
+      return Ok(Self {
+        salt,
+        fingerprint,
+        identity_type: String::new(),
+        accounts: vec![],
+        identity_length: None,
+        kdf_rounds: None,
+        key_slots: vec![],
+      });
+    }
+
+    let mut reader = MetadataReader {
+      bytes: &bytes,
+      offset: 20,
+    };
+    let identity_type = reader.read_string()?;
+    let accounts_count = reader.read_u32()?;
+    let accounts = (0..accounts_count)
+      .map(|_| {
+        let index = reader.read_u32()?;
+        let public_key = reader.read_bytes()?.to_vec();
+        let address = reader.read_string()?;
+
+        Ok(Account {
+          address,
+          public_key,
+          path: DerivationPath::from(index),
+        })
+      })
+      .collect::<Result<Vec<Account>, VaultError>>()?;
+    let identity_length = match reader.is_empty() {
+      true => None,
+      false => Some(reader.read_u32()?),
+    };
+    let kdf_rounds = match reader.is_empty() {
+      true => None,
+      false => Some(reader.read_u32()? as u32),
+    };
+    let key_slots = match reader.is_empty() {
+      true => vec![],
+      false => reader.read_key_slots()?,
+    };
+
+    Ok(Self {
+      salt,
+      fingerprint,
+      identity_type,
+      accounts,
+      identity_length,
+      kdf_rounds,
+      key_slots,
+    })
+  }
+}
+
+/// A cursor over serialized metadata bytes
+struct MetadataReader<'a> {
+  bytes: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> MetadataReader<'a> {
+  /// Read the next `length` bytes
+  fn take(&mut self, length: usize) -> Result<&'a [u8], VaultError> {
+    let bytes = self.bytes.get(self.offset..self.offset + length).ok_or(
+      VaultError::VaultRestoreFromBytes("unexpected metadata length".to_string()),
+    )?;
+    self.offset += length;
+
+    Ok(bytes)
+  }
+
+  /// Check if all bytes have been read
+  fn is_empty(&self) -> bool {
+    self.offset >= self.bytes.len()
+  }
+
+  /// Read a little endian u32
+  fn read_u32(&mut self) -> Result<usize, VaultError> {
+    // Unwrap is safe because exactly 4 bytes are taken
+    Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize)
+  }
+
+  /// Read an array of `N` bytes
+  fn read_array<const N: usize>(&mut self) -> Result<[u8; N], VaultError> {
+    // Unwrap is safe because exactly `N` bytes are taken
+    Ok(self.take(N)?.try_into().unwrap())
+  }
+
+  /// Read key slots prefixed by their u8 count
+  fn read_key_slots(&mut self) -> Result<Vec<KeySlot>, VaultError> {
+    let count = self.take(1)?[0];
+    let mut key_slots = (0..count)
+      .map(|_| {
+        Ok(KeySlot {
+          name: self.read_string()?,
+          salt: self.read_array()?,
+          rounds: self.read_u32()? as u32,
+          nonce: self.read_array()?,
+          wrapped_key: self.read_bytes()?.to_vec(),
+          credential_id: None,
+        })
+      })
+      .collect::<Result<Vec<KeySlot>, VaultError>>()?;
+    if !self.is_empty() {
+      for key_slot in key_slots.iter_mut() {
+        let credential_id = self.read_bytes()?;
+        if !credential_id.is_empty() {
+          key_slot.credential_id = Some(credential_id.to_vec());
+        }
+      }
+    }
+
+    Ok(key_slots)
+  }
+
+  /// Read bytes prefixed by their u8 length
+  fn read_bytes(&mut self) -> Result<&'a [u8], VaultError> {
+    let length = usize::from(self.take(1)?[0]);
+    self.take(length)
+  }
+
+  /// Read a UTF-8 string prefixed by its u8 length
+  fn read_string(&mut self) -> Result<String, VaultError> {
+    String::from_utf8(self.read_bytes()?.to_vec()).or(Err(VaultError::VaultRestoreFromBytes(
+      "invalid string".to_string(),
+    )))
+  }
+}
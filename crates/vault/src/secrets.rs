@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use utils::SecureBytes;
+
+use crate::VaultError;
+
+/// Small named payloads stored encrypted inside a vault,
+/// alongside its identity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VaultSecrets {
+  secrets: BTreeMap<String, SecureBytes>,
+}
+
+impl VaultSecrets {
+  /// The maximum length of a secret key, in bytes
+  pub const MAX_KEY_LENGTH: usize = u8::MAX as usize;
+
+  /// The maximum length of a secret value, in bytes
+  pub const MAX_VALUE_LENGTH: usize = u16::MAX as usize;
+
+  /// Create an empty set of secrets
+  pub fn new() -> Self {
+    Self {
+      secrets: BTreeMap::new(),
+    }
+  }
+
+  /// Store `value` under `key`, replacing any previous value
+  pub fn put(&mut self, key: &str, value: &[u8]) -> Result<(), VaultError> {
+    if key.is_empty() || key.len() > Self::MAX_KEY_LENGTH {
+      return Err(VaultError::InvalidSecret("invalid key length".to_string()));
+    }
+    if value.len() > Self::MAX_VALUE_LENGTH {
+      return Err(VaultError::InvalidSecret("value too large".to_string()));
+    }
+    self
+      .secrets
+      .insert(key.to_string(), SecureBytes::new(value));
+
+    Ok(())
+  }
+
+  /// Get the value stored under `key`
+  pub fn get(&self, key: &str) -> Option<&SecureBytes> {
+    self.secrets.get(key)
+  }
+
+  /// Remove the value stored under `key`, returning it
+  pub fn remove(&mut self, key: &str) -> Option<SecureBytes> {
+    self.secrets.remove(key)
+  }
+
+  /// Get the keys of all stored secrets, sorted
+  pub fn keys(&self) -> Vec<&str> {
+    self.secrets.keys().map(String::as_str).collect()
+  }
+
+  /// Check if no secrets are stored
+  pub fn is_empty(&self) -> bool {
+    self.secrets.is_empty()
+  }
+
+  /// Serialize the secrets to bytes, as a u32 LE count followed by
+  /// each key prefixed by its u8 length and each value by its u16 LE length
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = (self.secrets.len() as u32).to_le_bytes().to_vec();
+    self.secrets.iter().for_each(|(key, value)| {
+      bytes.push(key.len() as u8);
+      bytes.extend(key.as_bytes());
+      bytes.extend((value.len() as u16).to_le_bytes());
+      bytes.extend(value.as_slice());
+    });
+
+    bytes
+  }
+}
+
+impl TryFrom<&[u8]> for VaultSecrets {
+  type Error = VaultError;
+
+  /// Deserialize secrets from bytes
+  fn try_from(bytes: &[u8]) -> Result<Self, VaultError> {
+    let error = || VaultError::VaultRestoreFromBytes("invalid secrets".to_string());
+    let mut offset = 0;
+    let mut take = |length: usize| -> Result<&[u8], VaultError> {
+      let slice = bytes.get(offset..offset + length).ok_or_else(error)?;
+      offset += length;
+      Ok(slice)
+    };
+
+    // Unwraps are safe because exactly 4 and 2 bytes are taken
+    let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+    let mut secrets = Self::new();
+    for _ in 0..count {
+      let key_length = usize::from(take(1)?[0]);
+      let key = String::from_utf8(take(key_length)?.to_vec()).or(Err(error()))?;
+      let value_length = usize::from(u16::from_le_bytes(take(2)?.try_into().unwrap()));
+      secrets.put(&key, take(value_length)?).or(Err(error()))?;
+    }
+
+    Ok(secrets)
+  }
+}
@@ -0,0 +1,15 @@
+use safe::Safe;
+use utils::SecureBytes;
+
+use crate::{VaultMetadata, VaultSecrets};
+
+/// The outcome of locking or unlocking a `Vault`, computed without
+/// changing it, so that several vaults can be locked or unlocked
+/// all together or not at all. Applied with `Vault::apply`
+pub enum StagedVault<T> {
+  /// The safe holding the encrypted identity and secrets
+  Locked(Safe<VaultMetadata>),
+  /// The decrypted identity and secrets, and the data key
+  /// encrypting them if the vault has key slots
+  Unlocked(T, VaultSecrets, Option<SecureBytes>),
+}
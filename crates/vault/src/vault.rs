@@ -1,23 +1,66 @@
-use std::fmt::{Debug, Formatter};
+use std::{
+  collections::BTreeSet,
+  fmt::{Debug, Display, Formatter},
+  time::Instant,
+};
 
-use identity::{Account, GenericIdentity, IdentityError, Initializable, MultiKeyPair};
-use safe::{EncryptionKey, Safe};
+use identity::{
+  signer::{IntoSignable, Signature, SignatureOptions},
+  Account, DerivationPath, GenericIdentity, IdentityError, IdentityFactoryRegistry, Initializable,
+  MultiKeyPair,
+};
+use safe::{ChaCha20Poly1305Cipher, CipherKey, EncryptionKey, KeySlot, Safe};
+use tracing::{debug, instrument};
+use utils::{hex::Hex, PublicKeyBytes, SecureBytes};
 
-use crate::VaultError;
+use crate::{KdfParams, StagedVault, VaultError, VaultMetadata, VaultSecrets};
+
+/// The name of the key slot of the password locking the vault
+pub const PASSWORD_KEY_SLOT: &str = "password";
+
+/// The index below which `Vault::add_key` derives keys, leaving the
+/// indexes above it to keys derived at a chosen index, like per-domain keys
+pub const SEQUENTIAL_INDEX_LIMIT: usize = 1 << 30;
+
+/// The index from which BIP-32 child keys are hardened, so the
+/// first one past the indexes of the keys derived by the vault
+pub const HARDENED_INDEX_OFFSET: usize = 1 << 31;
 
 /// A `Vault` is a safe wrapper around a Hierarchical Deterministic (HD) wallet
 /// backed by a mnemonic phrase. It can generate new keys and sign transactions.
 ///
 /// When locked, the mnemonic phrase is encrypted safely and the keys are removed from memory.
 /// When unlocked, the mnemonic phrase is decrypted and the keys are recreated in memory.
+#[derive(Clone)]
 pub struct Vault<T> {
   /// The identity inside the vault.
   /// Available in-memory only when the vault is unlocked.
   identity: Option<T>,
   /// An encrypted wrapper around the vault.
   /// Available in-memory only when the vault is locked.
-  /// The safe holds the encryption salt as plaintext metadata
-  safe: Option<Safe<[u8; 16]>>,
+  /// The safe holds the encryption salt and the identity
+  /// fingerprint as plaintext metadata
+  safe: Option<Safe<VaultMetadata>>,
+  /// The fingerprint of the identity, available also while locked
+  fingerprint: [u8; 4],
+  /// The type of the identity, available also while locked
+  identity_type: String,
+  /// The indexes of the keys derived from the identity, used
+  /// to recreate the same accounts after unlocking
+  indexes: BTreeSet<usize>,
+  /// The secrets attached to the vault.
+  /// Available in-memory only when the vault is unlocked,
+  /// encrypted in the safe together with the identity otherwise
+  secrets: VaultSecrets,
+  /// The parameters used to derive the encryption key when locking
+  kdf: KdfParams,
+  /// The slots of the credentials unlocking the vault, available also
+  /// while locked. Empty unless credentials other than the password
+  /// were added, in which case the payload is encrypted with a data key
+  key_slots: Vec<KeySlot>,
+  /// The data key encrypting the payload when the vault has key slots.
+  /// Available in-memory only when the vault is unlocked
+  data_key: Option<SecureBytes>,
 }
 
 impl<T> Vault<T> {
@@ -25,6 +68,7 @@ impl<T> Vault<T> {
   pub fn new<F, A>(factory: F, args: A) -> Result<Self, VaultError>
   where
     F: FnOnce(A) -> Result<T, Box<dyn IdentityError>>,
+    T: GenericIdentity,
   {
     let identity = match factory(args) {
       Ok(identity) => identity,
@@ -32,16 +76,44 @@ impl<T> Vault<T> {
     };
 
     Ok(Vault {
+      fingerprint: identity.fingerprint(),
+      identity_type: identity.identity_type(),
       identity: Some(identity),
       safe: None,
+      indexes: BTreeSet::new(),
+      secrets: VaultSecrets::new(),
+      kdf: KdfParams::default(),
+      key_slots: vec![],
+      data_key: None,
     })
   }
 
+  /// Get the parameters used to derive the encryption key when locking
+  pub fn kdf_params(&self) -> KdfParams {
+    self.kdf
+  }
+
+  /// Set the parameters used to derive the encryption key
+  /// the next time the vault is locked
+  pub fn set_kdf_params(&mut self, kdf: KdfParams) {
+    self.kdf = kdf;
+  }
+
   /// Check if the vault is locked
   pub fn is_unlocked(&self) -> bool {
     self.safe.is_none()
   }
 
+  /// Get the fingerprint of the identity inside the vault
+  pub fn fingerprint(&self) -> [u8; 4] {
+    self.fingerprint
+  }
+
+  /// Get the type of the identity inside the vault
+  pub fn identity_type(&self) -> &str {
+    &self.identity_type
+  }
+
   pub fn get_identity(&self) -> Result<&T, VaultError> {
     match &self.identity {
       Some(identity) => Ok(identity),
@@ -56,98 +128,526 @@ impl<T> Vault<T> {
     }
   }
 
+  /// Attach a small secret payload to the vault under `key`,
+  /// replacing any previous value. The secret is encrypted
+  /// together with the identity when the vault is locked
+  pub fn put_secret(&mut self, key: &str, value: &[u8]) -> Result<(), VaultError> {
+    self.get_identity()?;
+    self.secrets.put(key, value)
+  }
+
+  /// Get the secret stored under `key`
+  pub fn get_secret(&self, key: &str) -> Result<Option<&[u8]>, VaultError> {
+    self.get_identity()?;
+    Ok(self.secrets.get(key).map(|value| value.as_slice()))
+  }
+
+  /// Remove the secret stored under `key`
+  /// Returns whether a secret was removed
+  pub fn remove_secret(&mut self, key: &str) -> Result<bool, VaultError> {
+    self.get_identity()?;
+    Ok(self.secrets.remove(key).is_some())
+  }
+
+  /// Get the keys of the secrets attached to the vault
+  pub fn secret_keys(&self) -> Result<Vec<&str>, VaultError> {
+    self.get_identity()?;
+    Ok(self.secrets.keys())
+  }
+
+  /// Add a slot unlocking the vault with `credential`, like a recovery
+  /// key, besides its password. The slot is stored when the vault is
+  /// locked next. The vault must be unlocked
+  pub fn add_key_slot(&mut self, name: &str, credential: &[u8]) -> Result<(), VaultError> {
+    let rounds = self.kdf.rounds;
+    self.push_key_slot(name, |data_key| {
+      KeySlot::new(name, data_key, credential, rounds)
+    })
+  }
+
+  /// Add a slot unlocking the vault with the output of the PRF extension
+  /// of the passkey `credential_id`, evaluated with `salt`, so that
+  /// a hardware authenticator unlocks it without a typed password
+  pub fn add_passkey_slot(
+    &mut self,
+    name: &str,
+    credential_id: &[u8],
+    salt: [u8; 16],
+    prf_output: &[u8],
+  ) -> Result<(), VaultError> {
+    self.push_key_slot(name, |data_key| {
+      KeySlot::new_passkey(name, data_key, credential_id, salt, prf_output)
+    })
+  }
+
+  /// Add the slot created by `create` with the data key of the vault,
+  /// generating the data key if the vault has no slots yet
+  fn push_key_slot<F>(&mut self, name: &str, create: F) -> Result<(), VaultError>
+  where
+    F: FnOnce(&CipherKey) -> Result<KeySlot, String>,
+  {
+    self.get_identity()?;
+    if name == PASSWORD_KEY_SLOT || self.key_slots.iter().any(|key_slot| key_slot.name == name) {
+      return Err(VaultError::KeySlot(format!("{} already exists", name)));
+    }
+
+    let data_key = to_cipher_key(&self.data_key()?)?;
+    let key_slot = create(&data_key).or(Err(VaultError::SafeCreation))?;
+    self.key_slots.push(key_slot);
+
+    Ok(())
+  }
+
+  /// Get the data key encrypting the payload of the vault, generating it
+  /// if the vault has no slots yet. The payload is encrypted with the data
+  /// key from the next lock on. The vault must be unlocked
+  pub fn data_key(&mut self) -> Result<SecureBytes, VaultError> {
+    self.get_identity()?;
+
+    Ok(
+      self
+        .data_key
+        .get_or_insert_with(|| SecureBytes::new(&ChaCha20Poly1305Cipher::new_key()))
+        .clone(),
+    )
+  }
+
+  /// Remove the slot named `name`, revoking its credential.
+  /// Works while locked too. The slot of the password cannot be removed.
+  /// Returns whether a slot was removed
+  pub fn remove_key_slot(&mut self, name: &str) -> Result<bool, VaultError> {
+    if name == PASSWORD_KEY_SLOT {
+      return Err(VaultError::KeySlot(
+        "the password slot cannot be removed".to_string(),
+      ));
+    }
+
+    let count = self.key_slots.len();
+    self.key_slots.retain(|key_slot| key_slot.name != name);
+    if let Some(safe) = &mut self.safe {
+      safe
+        .metadata
+        .key_slots
+        .retain(|key_slot| key_slot.name != name);
+    }
+
+    Ok(self.key_slots.len() < count)
+  }
+
+  /// Get the key slots of the vault, holding no secret
+  pub fn key_slots(&self) -> &[KeySlot] {
+    &self.key_slots
+  }
+
+  /// Get the names of the key slots of the vault
+  pub fn key_slot_names(&self) -> Vec<&str> {
+    self
+      .key_slots
+      .iter()
+      .map(|key_slot| key_slot.name.as_str())
+      .collect()
+  }
+
   /// Serializes the vault to bytes if it is locked
   /// this operation fails when the vault is unlocked
   /// as no safe has been created, and the exported bytes would
   /// be unencrypted.
   pub fn to_bytes(&self) -> Result<Vec<u8>, VaultError> {
     match &self.safe {
-      Some(safe) => Ok(safe.clone().into()),
+      Some(safe) => Ok(safe.clone().try_into()?),
       None => Err(VaultError::ForbiddenWhileUnlocked),
     }
   }
+
+  /// Lock or unlock the vault with the outcome of
+  /// `Vault::stage_lock` or `Vault::stage_unlock`
+  pub fn apply(&mut self, staged: StagedVault<T>) {
+    match staged {
+      StagedVault::Locked(safe) => {
+        // The `identity`, the secrets and the data key are removed from memory
+        self.key_slots = safe.metadata.key_slots.clone();
+        self.safe = Some(safe);
+        self.identity = None;
+        self.secrets = VaultSecrets::new();
+        self.data_key = None;
+      }
+      StagedVault::Unlocked(identity, secrets, data_key) => {
+        // The safe is removed from memory, while the
+        // HD wallet and the secrets are stored in memory
+        self.safe = None;
+        self.identity = Some(identity);
+        self.secrets = secrets;
+        self.data_key = data_key;
+      }
+    }
+  }
+}
+
+impl<T: GenericIdentity> Vault<T> {
+  /// Unlock the vault, recreating the identity with the
+  /// deserializer registered for its identity type
+  #[instrument(level = "debug", skip_all, err, fields(fingerprint = ?self.fingerprint))]
+  pub fn unlock_with_registry(
+    &mut self,
+    password: &[u8],
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<(), VaultError> {
+    let staged = self.stage_unlock(password, registry)?;
+    self.apply(staged);
+
+    Ok(())
+  }
+
+  /// Decrypt the identity and the secrets of the vault, without
+  /// unlocking it. The vault is unlocked by applying the result
+  pub fn stage_unlock(
+    &self,
+    password: &[u8],
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<StagedVault<T>, VaultError> {
+    match &self.safe {
+      Some(safe) => self.open_safe(safe, password, registry),
+      None => Err(VaultError::AlreadyUnlocked),
+    }
+  }
+
+  /// Decrypt the identity and the secrets of the vault with its data key,
+  /// as returned by `Vault::data_key`, instead of a credential. The vault
+  /// is unlocked by applying the result
+  pub fn stage_unlock_with_data_key(
+    &self,
+    data_key: &SecureBytes,
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<StagedVault<T>, VaultError> {
+    match &self.safe {
+      // Safes without slots are encrypted with the password only
+      Some(safe) if safe.metadata.key_slots.is_empty() => Err(VaultError::SafeDecrypt),
+      Some(safe) => self.decrypt_safe(
+        safe,
+        &to_cipher_key(data_key)?,
+        Some(data_key.clone()),
+        registry,
+      ),
+      None => Err(VaultError::AlreadyUnlocked),
+    }
+  }
+
+  /// Decrypt the identity and the secrets held by `safe`
+  fn open_safe(
+    &self,
+    safe: &Safe<VaultMetadata>,
+    password: &[u8],
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<StagedVault<T>, VaultError> {
+    let (key, data_key) = match safe.metadata.key_slots.is_empty() {
+      // The encryption key is recreated from the password and the salt
+      true => {
+        let rounds = safe
+          .metadata
+          .kdf_rounds
+          .unwrap_or(KdfParams::DEFAULT_ROUNDS);
+        let encryption_key = EncryptionKey::with_salt(password, safe.metadata.salt, rounds);
+        (encryption_key.pubk, None)
+      }
+      // The data key is decrypted from the first slot opened by the credential
+      false => {
+        let data_key = safe
+          .metadata
+          .key_slots
+          .iter()
+          .find_map(|key_slot| key_slot.open(password).ok())
+          .ok_or(VaultError::SafeDecrypt)?;
+        (data_key, Some(SecureBytes::new(&data_key)))
+      }
+    };
+
+    self.decrypt_safe(safe, &key, data_key, registry)
+  }
+
+  /// Decrypt the identity and the secrets held by `safe` with `key`,
+  /// keeping `data_key` to lock the vault again
+  fn decrypt_safe(
+    &self,
+    safe: &Safe<VaultMetadata>,
+    key: &CipherKey,
+    data_key: Option<SecureBytes>,
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<StagedVault<T>, VaultError> {
+    // The seed is decrypted from the safe, in memory zeroed on drop
+    let recovered_seed = SecureBytes::from(safe.decrypt(key).or(Err(VaultError::SafeDecrypt))?);
+    // The payload holds the identity, followed by the secrets if any
+    let (identity_bytes, secrets) = match safe.metadata.identity_length {
+      Some(length) if length <= recovered_seed.len() => (
+        &recovered_seed[..length],
+        VaultSecrets::try_from(&recovered_seed[length..])?,
+      ),
+      Some(_) => return Err(VaultError::SafeDecrypt),
+      None => (recovered_seed.as_slice(), VaultSecrets::new()),
+    };
+    // The identity is recreated from bytes
+    let identity = registry.deserialize(&self.identity_type, identity_bytes)?;
+
+    Ok(StagedVault::Unlocked(identity, secrets, data_key))
+  }
+}
+
+impl<T: GenericIdentity + Initializable> Vault<T> {
+  /// Unlock the vault
+  pub fn unlock(&mut self, password: &[u8]) -> Result<(), VaultError> {
+    self.unlock_with_registry(password, &IdentityFactoryRegistry::with_initializable())
+  }
 }
 
-impl<T: Initializable> Vault<T> {
+impl<T: GenericIdentity + MultiKeyPair<[u8; 32], PublicKeyBytes, DerivationPath>> Vault<T> {
   /// Lock the vault
   ///
   /// Remove all private keys and the seed from memory
-  /// and encrypt the HD wallet, storing an unencrypted count
-  /// of the number of keys in the vault, to be able to recreate
-  /// the same accounts when unlocking.
+  /// and encrypt the HD wallet, storing the unencrypted public
+  /// accounts of the vault, to be able to report them while locked
+  /// and recreate the same accounts when unlocking.
+  #[instrument(level = "debug", skip_all, err, fields(fingerprint = ?self.fingerprint))]
   pub fn lock(&mut self, password: &[u8]) -> Result<(), VaultError> {
+    if let Some(staged) = self.stage_lock(password)? {
+      self.apply(staged);
+    }
+
+    Ok(())
+  }
+
+  /// Encrypt the identity and the secrets of the vault, without locking
+  /// it. The vault is locked by applying the result. Locked vaults have
+  /// nothing to encrypt
+  pub fn stage_lock(&self, password: &[u8]) -> Result<Option<StagedVault<T>>, VaultError> {
     match &self.identity {
       Some(identity) => {
-        // Create an encryption key from the password
-        let encryption_key = EncryptionKey::new(password, 1000);
-        // A safe is created with the encryption salt as metadata, and
-        // the identity as encrypted data bytes
-        self.safe = Some(
-          Safe::from_plain_bytes(
-            encryption_key.salt,
-            &encryption_key.pubk,
-            identity.serialize(),
-          )
-          .or(Err(VaultError::SafeCreation))?,
-        );
-        // The `identity` is removed from memory
-        self.identity = None;
+        let (key, salt, key_slots) = match &self.data_key {
+          // Create an encryption key from the password
+          None => {
+            let encryption_key = EncryptionKey::new(password, self.kdf.rounds);
+            (encryption_key.pubk, encryption_key.salt, vec![])
+          }
+          // Encrypt with the data key, replacing the slot of the password
+          Some(data_key) => {
+            let data_key = to_cipher_key(data_key)?;
+            let password_slot =
+              KeySlot::new(PASSWORD_KEY_SLOT, &data_key, password, self.kdf.rounds)
+                .or(Err(VaultError::SafeCreation))?;
+            let salt = password_slot.salt;
+            let mut key_slots = vec![password_slot];
+            key_slots.extend(
+              self
+                .key_slots
+                .iter()
+                .filter(|key_slot| key_slot.name != PASSWORD_KEY_SLOT)
+                .cloned(),
+            );
+            (data_key, salt, key_slots)
+          }
+        };
+        // The identity and the secrets are encrypted together
+        let mut payload = identity.serialize();
+        let identity_length = payload.len();
+        payload.extend(self.secrets.to_bytes());
+        // A safe is created with the encryption salt and the fingerprint
+        // as metadata, and the identity as encrypted data bytes
+        let safe = Safe::from_plain_bytes(
+          VaultMetadata {
+            salt,
+            fingerprint: self.fingerprint,
+            identity_type: self.identity_type.clone(),
+            accounts: self.accounts()?,
+            identity_length: Some(identity_length),
+            kdf_rounds: Some(self.kdf.rounds),
+            key_slots,
+          },
+          &key,
+          payload,
+        )
+        .or(Err(VaultError::SafeCreation))?;
 
-        Ok(())
+        Ok(Some(StagedVault::Locked(safe)))
       }
-      None => Ok(()),
+      None => Ok(None),
     }
   }
 
-  /// Unlock the vault
-  pub fn unlock(&mut self, password: &[u8]) -> Result<(), VaultError> {
-    match &self.safe {
-      Some(safe) => {
-        // The encryption key is recreated from the password and the salt
-        let encryption_key = EncryptionKey::with_salt(password, safe.metadata, 1000);
-        // The seed is decrypted from the safe
-        let recovered_seed = safe
-          .decrypt(&encryption_key.pubk)
-          .or(Err(VaultError::SafeDecrypt))?;
-        // The identity is recreated from bytes
-        let mut identity = T::new();
-        identity.deserialize(recovered_seed.as_slice())?;
-        // The safe is removed from memory
-        self.safe = None;
-        // The HD wallet is stored in memory
-        self.identity = Some(identity);
+  /// Check that the vault decrypts with `password`, and that the identity
+  /// inside it has the stored fingerprint and derives the first stored
+  /// account again, without unlocking the vault. Unlocked vaults are
+  /// encrypted first, like when they are backed up
+  pub fn verify_integrity(
+    &self,
+    password: &[u8],
+    registry: &IdentityFactoryRegistry<T>,
+  ) -> Result<(), VaultError> {
+    let safe = match &self.safe {
+      Some(safe) => safe.clone(),
+      None => match self.stage_lock(password)? {
+        Some(StagedVault::Locked(safe)) => safe,
+        _ => return Err(VaultError::SafeCreation),
+      },
+    };
+    let StagedVault::Unlocked(identity, ..) = self.open_safe(&safe, password, registry)? else {
+      return Err(VaultError::SafeDecrypt);
+    };
 
-        Ok(())
+    if identity.fingerprint() != safe.metadata.fingerprint {
+      return Err(VaultError::IntegrityMismatch(format!(
+        "fingerprint {}",
+        Hex(safe.metadata.fingerprint)
+      )));
+    }
+    if let Some(account) = safe.metadata.accounts.first() {
+      let private_key = identity
+        .private_key_at(account.path)
+        .or(Err(VaultError::KeyDerivation))?;
+      if Account::from_private_key(private_key, account.path)?.address != account.address {
+        return Err(VaultError::IntegrityMismatch(format!(
+          "account {}",
+          account.address
+        )));
       }
-      None => Err(VaultError::AlreadyUnlocked),
     }
+
+    Ok(())
   }
-}
 
-impl<T: GenericIdentity + MultiKeyPair<[u8; 32], [u8; 32], usize>> Vault<T> {
-  /// Add a new key to the vault
+  /// Add a new key to the vault, derived at the index following
+  /// the highest one derived so far below `SEQUENTIAL_INDEX_LIMIT`
   /// Returns the key
-  pub fn add_key(&mut self, path: usize) -> Result<Account<usize>, VaultError> {
+  pub fn add_key(&mut self) -> Result<Account, VaultError> {
+    let index = match self.indexes.range(..SEQUENTIAL_INDEX_LIMIT).next_back() {
+      Some(last) => last + 1,
+      None => 0,
+    };
+
+    self.add_key_at(index)
+  }
+
+  /// Add a key to the vault, derived at `index`.
+  /// Adding a key already derived has no effect.
+  /// Returns the key
+  pub fn add_key_at(&mut self, index: usize) -> Result<Account, VaultError> {
+    let account = self.account_at(DerivationPath::from(index))?;
+    self.indexes.insert(index);
+
+    Ok(account)
+  }
+
+  /// Add `count` keys to the vault, derived at consecutive
+  /// indexes starting from `start`. Fails if the range reaches
+  /// past the non-hardened indexes
+  /// Returns the keys
+  pub fn add_keys_range(&mut self, start: usize, count: usize) -> Result<Vec<Account>, VaultError> {
+    let end = start
+      .checked_add(count)
+      .filter(|end| *end <= HARDENED_INDEX_OFFSET)
+      .ok_or(VaultError::IndexRangeOutOfBounds(start, count))?;
+    let accounts = self.accounts_at((start..end).map(DerivationPath::from).collect())?;
+    self.indexes.extend(start..end);
+
+    Ok(accounts)
+  }
+
+  /// Get all the accounts derived from the vault, sorted by index.
+  /// While locked, the public accounts stored in the safe metadata are returned
+  pub fn accounts(&self) -> Result<Vec<Account>, VaultError> {
+    if let Some(safe) = &self.safe {
+      return Ok(safe.metadata.accounts.clone());
+    }
+
+    self.accounts_at(
+      self
+        .indexes
+        .iter()
+        .map(|index| DerivationPath::from(*index))
+        .collect(),
+    )
+  }
+
+  /// Get the account at a derivation path
+  fn account_at(&self, path: DerivationPath) -> Result<Account, VaultError> {
     let identity = self.get_identity()?;
+    let started = Instant::now();
     let private_key = identity
       .private_key_at(path)
       .or(Err(VaultError::KeyDerivation))?;
+    debug!(
+      %path,
+      elapsed_ms = started.elapsed().as_millis() as u64,
+      "derived key"
+    );
 
     Ok(Account::from_private_key(private_key, path)?)
   }
 
+  /// Get the accounts at multiple derivation paths at once
+  fn accounts_at(&self, paths: Vec<DerivationPath>) -> Result<Vec<Account>, VaultError> {
+    let started = Instant::now();
+    let private_keys = self
+      .get_identity()?
+      .private_keys_at(&paths)
+      .or(Err(VaultError::KeyDerivation))?;
+    debug!(
+      count = paths.len(),
+      elapsed_ms = started.elapsed().as_millis() as u64,
+      "derived keys"
+    );
+
+    private_keys
+      .into_iter()
+      .zip(paths)
+      .map(|(private_key, path)| Ok(Account::from_private_key(private_key, path)?))
+      .collect()
+  }
+
+  /// Check if an account has been derived from the vault
+  pub fn has_account(&self, account: &Account) -> Result<bool, VaultError> {
+    if account.path.account != 0
+      || account.path.change != 0
+      || !self.indexes.contains(&account.path.index)
+    {
+      return Ok(false);
+    }
+
+    Ok(self.account_at(account.path)?.address == account.address)
+  }
+
   /// Signs a message with one of the vault accounts.
   /// The message can be a byte slice, it will be digested internally
-  /// by the function.
-  pub fn sign(&self, account: &Account<usize>, message: &[u8]) -> Result<Vec<u8>, VaultError> {
+  /// by the function, or any type implementing `IntoSignable`.
+  pub fn sign<S>(
+    &self,
+    account: &Account,
+    message: &S,
+    options: &SignatureOptions,
+  ) -> Result<Signature, VaultError>
+  where
+    S: IntoSignable + ?Sized,
+  {
     let identity = self
       .get_identity()
       .or(Err(VaultError::ForbiddenWhileLocked))?;
+    let started = Instant::now();
+    let signature = identity.sign(account, &message.to_signable(), options)?;
+    debug!(
+      address = %account.address,
+      elapsed_ms = started.elapsed().as_millis() as u64,
+      "signed message"
+    );
 
-    Ok(identity.sign(account, message)?)
+    Ok(signature)
   }
 }
 
+/// Get the cipher key held by `data_key`
+fn to_cipher_key(data_key: &SecureBytes) -> Result<CipherKey, VaultError> {
+  data_key
+    .as_slice()
+    .try_into()
+    .or(Err(VaultError::KeySlot("invalid data key".to_string())))
+}
+
 impl<T: GenericIdentity + PartialEq> PartialEq for Vault<T> {
   fn eq(&self, other: &Self) -> bool {
     self.identity == other.identity && self.safe == other.safe
@@ -158,15 +658,48 @@ impl<T> TryFrom<Vec<u8>> for Vault<T> {
   type Error = VaultError;
 
   fn try_from(bytes: Vec<u8>) -> Result<Self, VaultError> {
+    let safe = Safe::<VaultMetadata>::try_from(bytes)?;
+
     Ok(Self {
       identity: None,
-      safe: Some(Safe::try_from(bytes)?),
+      fingerprint: safe.metadata.fingerprint,
+      identity_type: safe.metadata.identity_type.clone(),
+      indexes: safe
+        .metadata
+        .accounts
+        .iter()
+        .map(|account| account.path.index)
+        .collect(),
+      kdf: KdfParams::new(
+        safe
+          .metadata
+          .kdf_rounds
+          .unwrap_or(KdfParams::DEFAULT_ROUNDS),
+      ),
+      key_slots: safe.metadata.key_slots.clone(),
+      safe: Some(safe),
+      secrets: VaultSecrets::new(),
+      data_key: None,
     })
   }
 }
 
 impl<T> Debug for Vault<T> {
+  /// Only non-sensitive information is printed, never
+  /// the identity nor the encrypted bytes of the safe
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Vault")
+      .field("fingerprint", &Hex(self.fingerprint))
+      .field("identity_type", &self.identity_type)
+      .field("locked", &self.safe.is_some())
+      .field("accounts", &self.indexes.len())
+      .finish()
+  }
+}
+
+impl<T> Display for Vault<T> {
+  /// Display the vault by its fingerprint, like `Vault 0x1a2b3c4d`
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("Vault").field("safe", &self.safe).finish()
+    write!(f, "Vault {}", Hex(self.fingerprint))
   }
 }
@@ -1,75 +1,94 @@
+//! # walleth
+//!
+//! A (WIP) Rust library for easily create, manage, use and protect Ethereum accounts. Interacting with keys and signing transactions securely (will be) made as easy as breathing.
+//!
+//! ## Features
+//! - [x] Multiple BIP39 HD wallets management
+//! - [x] Built-in encryption for all keys managed
+//! - [x] Built-in bytes serialization / deserialization for the entire keychain
+//! - [x] Customizable wallet classes (HD, single, etc..)
+//! - [ ] Built-in network scraper
+//! - [ ] Built-in transaction manager
+//! - [ ] Built-in JSON-RPC Provider engine
+//!
+//! ## Usage
+//!
+//! ### Create a new keychain
+//! ```
+//! use walleth::keychain::Keychain;
+//! use walleth::hdkey::HDKey;
+//!
+//! let mut keychain = Keychain::<HDKey>::new();
+//! ```
+//!
+//! ### Add a new HD Wallet to the keychain
+//! ```
+//! use walleth::keychain::Keychain;
+//! use walleth::hdkey::{HDKey, hdkey_factory};
+//!
+//! let mut keychain = Keychain::<HDKey>::new();
+//! let hdwallet = keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+//! ```
+//!
+//! ### Add a new HD Wallet to the keychain with a specific mnemonic
+//! ```
+//! use walleth::keychain::Keychain;
+//! use walleth::hdkey::{HDKey, hdkey_factory};
+//!
+//! let mnemonic = "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game".to_string();
+//! let mut keychain = Keychain::<HDKey>::new();
+//! let hdwallet = keychain.add_multi_keypair(hdkey_factory, Some(mnemonic)).unwrap();
+//! ```
+//!
+//! ### Derive keys and sign
+//!
+//! ```
+//! use walleth::{
+//!   keychain::Keychain,
+//!   hdkey::{HDKey, hdkey_factory},
+//!   identity::{
+//!     MultiKeyPair,
+//!     AccountDeriver,
+//!     DerivationPath,
+//!     signer::{Signer, Signable, SignatureOptions}
+//!   },
+//! };
+//!
+//! let mut keychain = Keychain::<HDKey>::new();
+//! let hdwallet = keychain.add_multi_keypair(hdkey_factory, None).unwrap();
+//!
+//! // Derive the account at m/44'/60'/0'/0/0
+//! let account = hdwallet.account_at(DerivationPath::from(0)).unwrap();
+//!
+//! // Sign a message
+//! let signature = hdwallet.sign(&account, b"Hello", &SignatureOptions::default()).unwrap();
+//!
+//! // Verify signature
+//! hdwallet.verify(&account, b"Hello", &signature).unwrap();
+//! ```
+//!
+//! ## Cargo features
+//!
+//! Signing with `identity` and `walleth_core` is always available. The
+//! following default features can be disabled to slim the dependencies:
+//! - `hd`: BIP-32 HD wallets (`hdkey`)
+//! - `safe`: encrypted vaults and the keychain managing them
+//!   (`safe`, `vault`, `keychain`, `simple`), requires `hd`
+//! - `hardware`: hardware wallets (`hwi`)
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "hd")]
 pub use hdkey;
-/// # walleth
-///
-/// A (WIP) Rust library for easily create, manage, use and protect Ethereum accounts. Interacting with keys and signing transactions securely (will be) made as easy as breathing.
-///
-/// ## Features
-/// - [x] Multiple BIP39 HD wallets management
-/// - [x] Built-in encryption for all keys managed
-/// - [x] Built-in bytes serialization / deserialization for the entire keychain
-/// - [x] Customizable wallet classes (HD, single, etc..)
-/// - [ ] Built-in network scraper
-/// - [ ] Built-in transaction manager
-/// - [ ] Built-in JSON-RPC Provider engine
-///
-/// ## Usage
-///
-/// ### Create a new keychain
-/// ```
-/// use walleth::keychain::Keychain;
-/// use walleth::hdkey::HDKey;
-///
-/// let mut keychain = Keychain::<HDKey>::new();
-/// ```
-///
-/// ### Add a new HD Wallet to the keychain
-/// ```
-/// use walleth::keychain::Keychain;
-/// use walleth::hdkey::{HDKey, hdkey_factory};
-///
-/// let mut keychain = Keychain::<HDKey>::new();
-/// let hdwallet = keychain.add_multi_keypair(hdkey_factory, None).unwrap();
-/// ```
-///
-/// ### Add a new HD Wallet to the keychain with a specific mnemonic
-/// ```
-/// use walleth::keychain::Keychain;
-/// use walleth::hdkey::{HDKey, hdkey_factory};
-///
-/// let mnemonic = "grocery belt target explain clay essay focus spatial skull brain measure matrix toward visual protect owner stone scale slim ghost panda exact combine game".to_string();
-/// let mut keychain = Keychain::<HDKey>::new();
-/// let hdwallet = keychain.add_multi_keypair(hdkey_factory, Some(mnemonic)).unwrap();
-/// ```
-///
-/// ### Derive keys and sign
-///
-/// ```
-/// use walleth::{
-///   keychain::Keychain,
-///   hdkey::{HDKey, hdkey_factory},
-///   identity::{
-///     MultiKeyPair,
-///     AccountDeriver,
-///     signer::{Signer, Signable}
-///   },
-/// };
-///
-/// let mut keychain = Keychain::<HDKey>::new();
-/// let hdwallet = keychain.add_multi_keypair(hdkey_factory, None).unwrap();
-///
-/// // Derive an account at path
-/// let account = hdwallet.account_at(0).unwrap();
-///
-/// // Sign a message
-/// let signature = hdwallet.sign(&account, "Hello".as_bytes()).unwrap();
-///
-/// // Verify signature
-/// hdwallet.verify(&account, "Hello".as_bytes(), &signature);
-/// ```
+#[cfg(feature = "hardware")]
+pub use hwi;
 pub use identity;
+#[cfg(feature = "safe")]
 pub use keychain;
+#[cfg(feature = "safe")]
 pub use safe;
+#[cfg(feature = "safe")]
+pub use simple;
 pub use utils;
+#[cfg(feature = "safe")]
 pub use vault;
+pub use walleth_core;